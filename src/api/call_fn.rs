@@ -230,6 +230,9 @@ impl Engine {
 
         let orig_source = mem::replace(&mut global.source, ast.source_raw().cloned());
 
+        #[cfg(feature = "coverage")]
+        self.cov_ast(ast);
+
         let orig_lib_len = global.lib.len();
         global.lib.push(ast.shared_lib().clone());
 
@@ -260,7 +263,15 @@ impl Engine {
                 let orig_scope_len = scope.len();
             }
 
-            self.eval_global_statements(global, caches, scope, statements, true)
+            #[cfg(feature = "profiling")]
+            let entry = self.prof_enter_script(global);
+
+            let r = self.eval_global_statements(global, caches, scope, statements, true);
+
+            #[cfg(feature = "profiling")]
+            self.prof_exit(global, entry);
+
+            r
         } else {
             Ok(Dynamic::UNIT)
         };
@@ -1,7 +1,7 @@
 //! Module that defines the public compilation API of [`Engine`].
 
 use crate::parser::{ParseResult, ParseState};
-use crate::{Engine, Scope, AST};
+use crate::{Engine, ParseError, Scope, AST};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -238,6 +238,75 @@ impl Engine {
         }
         Ok(_ast)
     }
+    /// Compile a string into an [`AST`], recovering from parse errors instead of stopping at the
+    /// first one.
+    ///
+    /// When a statement fails to parse, the error is recorded and parsing resumes at the next
+    /// statement (or at the end of the enclosing block). All errors are returned, in the order
+    /// they are encountered, together with a partial [`AST`] containing all the statements and
+    /// functions that are parsed successfully.
+    ///
+    /// The script is valid if and only if no error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, ParseErrorType, INT};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let (ast, errors) = engine.compile_with_recovery("
+    ///     let x = 40 +;
+    ///     let z = (1;
+    ///     let y = 2;
+    ///     y
+    /// ");
+    ///
+    /// assert_eq!(errors.len(), 2);
+    /// assert!(matches!(errors[0].err_type(), ParseErrorType::BadInput(..)));
+    /// assert!(matches!(errors[1].err_type(), ParseErrorType::MissingToken(..)));
+    ///
+    /// // Statements that parse are kept
+    /// assert_eq!(engine.eval_ast::<INT>(&ast)?, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compile_with_recovery(&self, script: impl AsRef<str>) -> (AST, Vec<ParseError>) {
+        let scripts = [script];
+        let (stream, tc) = self.lex(&scripts);
+
+        tc.borrow_mut().errors = Some(Vec::new());
+
+        let input = &mut stream.peekable();
+        let lib = &mut <_>::default();
+        let state = ParseState::new(None, input, tc.clone(), lib);
+        let result = self.parse(
+            state,
+            #[cfg(not(feature = "no_optimize"))]
+            self.optimization_level,
+        );
+
+        let mut errors = tc.borrow_mut().errors.take().unwrap_or_default();
+
+        let ast = match result {
+            #[allow(unused_mut)]
+            Ok(mut ast) => {
+                #[cfg(feature = "metadata")]
+                {
+                    let global_comments = &tc.borrow().global_comments;
+                    ast.doc = global_comments.into();
+                }
+                ast
+            }
+            Err(err) => {
+                errors.push(err);
+                AST::empty()
+            }
+        };
+
+        (ast, errors)
+    }
     /// Compile a string containing an expression into an [`AST`],
     /// which can be used later for evaluation.
     ///
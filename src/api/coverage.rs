@@ -0,0 +1,77 @@
+//! Module that defines the coverage collection API of [`Engine`].
+#![cfg(feature = "coverage")]
+
+use crate::eval::Coverage;
+use crate::func::{locked_read, locked_write, Locked};
+use crate::Engine;
+use std::mem;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+impl Engine {
+    /// Enable/disable collecting statement and branch [coverage][Coverage] of the scripts run by
+    /// this [`Engine`], including script functions and imported modules.
+    ///
+    /// Coverage accumulates over all runs until it is [taken][Engine::take_coverage].
+    /// Disabling coverage discards whatever has been collected.
+    ///
+    /// Scripts run by the `grain` bytecode VM are not covered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_coverage_enabled(true);
+    ///
+    /// engine.run("let x = 42;\nif x > 0 { print(x); } else { print(-x); }")?;
+    ///
+    /// let coverage = engine.take_coverage().unwrap();
+    /// let script = coverage.get("").unwrap();
+    ///
+    /// // `print(-x)` is never run
+    /// assert_eq!(script.statements.values().filter(|&&n| n == 0).count(), 1);
+    /// assert!(coverage.to_lcov().contains("DA:2,1\n"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_coverage_enabled(&mut self, enable: bool) -> &mut Self {
+        if !enable {
+            self.coverage = None;
+        } else if self.coverage.is_none() {
+            self.coverage = Some(Locked::new(Coverage::new()));
+        }
+        self
+    }
+    /// Is statement and branch [coverage][Coverage] being collected?
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_coverage_enabled(&self) -> bool {
+        self.coverage.is_some()
+    }
+    /// Get a copy of the [coverage][Coverage] collected so far.
+    ///
+    /// Returns [`None`] if coverage is not being collected.
+    #[inline]
+    #[must_use]
+    pub fn coverage(&self) -> Option<Coverage> {
+        self.coverage
+            .as_ref()
+            .and_then(|coverage| locked_read(coverage).map(|c| c.clone()))
+    }
+    /// Take the [coverage][Coverage] collected so far, and start collecting afresh.
+    ///
+    /// Taking the coverage after each run gets the coverage of each run separately.
+    ///
+    /// Returns [`None`] if coverage is not being collected.
+    #[inline]
+    pub fn take_coverage(&self) -> Option<Coverage> {
+        self.coverage
+            .as_ref()
+            .and_then(|coverage| locked_write(coverage).map(|mut c| mem::take(&mut *c)))
+    }
+}
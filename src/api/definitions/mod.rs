@@ -413,6 +413,34 @@ impl Module {
             writer.write_str("}")?;
         }
 
+        #[cfg(not(feature = "no_object"))]
+        {
+            let mut structs = self
+                .iter_custom_types()
+                .filter_map(|(_, t)| t.fields.as_ref().map(|fields| (t, fields)))
+                .collect::<Vec<_>>();
+            structs.sort_by(|(a, _), (b, _)| a.display_name.cmp(&b.display_name));
+
+            for (info, fields) in structs {
+                if !first {
+                    writer.write_str("\n\n")?;
+                }
+                first = false;
+
+                #[cfg(feature = "metadata")]
+                for comment in &*info.comments {
+                    writeln!(writer, "{comment}")?;
+                }
+
+                write!(
+                    writer,
+                    "struct {} {{ {} }}",
+                    info.display_name,
+                    fields.join(", ")
+                )?;
+            }
+        }
+
         let mut vars = self.iter_var().collect::<Vec<_>>();
         vars.sort_by(|(a, _), (b, _)| a.cmp(b));
 
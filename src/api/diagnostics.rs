@@ -0,0 +1,678 @@
+//! Module that defines the diagnostics (lint) API of [`Engine`].
+
+use crate::ast::{ASTFlags, Expr, Stmt};
+use crate::engine::{
+    KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL, KEYWORD_FN_PTR_CURRY,
+    KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_TYPE_OF,
+};
+use crate::parser::{ParseResult, ParseState};
+use crate::tokenizer::is_id_first_alphabetic;
+use crate::types::{Diagnostic, DiagnosticType};
+use crate::{Engine, Identifier, ImmutableString, Position, AST};
+use std::collections::BTreeSet;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Names of functions that are handled directly by the [`Engine`].
+const KEYWORD_FUNCTIONS: &[&str] = &[
+    KEYWORD_PRINT,
+    KEYWORD_DEBUG,
+    KEYWORD_TYPE_OF,
+    KEYWORD_EVAL,
+    KEYWORD_FN_PTR,
+    KEYWORD_FN_PTR_CALL,
+    KEYWORD_FN_PTR_CURRY,
+    #[cfg(not(feature = "no_closure"))]
+    crate::engine::KEYWORD_IS_SHARED,
+    KEYWORD_IS_DEF_VAR,
+    #[cfg(not(feature = "no_function"))]
+    crate::engine::KEYWORD_IS_DEF_FN,
+];
+
+/// Array functions taking a function name as a string, which is deprecated.
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_index"))]
+const DEPRECATED_FN_NAME_FUNCTIONS: &[&str] = &[
+    "map",
+    "filter",
+    "index_of",
+    "some",
+    "all",
+    "dedup",
+    "reduce",
+    "reduce_rev",
+    "sort",
+    "drain",
+    "retain",
+];
+
+/// Kind of a variable tracked during analysis.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum VarKind {
+    /// A variable defined in the script.
+    Variable,
+    /// A function parameter.
+    #[cfg_attr(feature = "no_function", allow(dead_code))]
+    Parameter,
+    /// A variable that is never reported as unused (e.g. exported or captured).
+    Hidden,
+}
+
+/// A variable tracked during analysis.
+#[derive(Debug)]
+struct Var {
+    /// Name of the variable.
+    name: ImmutableString,
+    /// Position of the definition.
+    pos: Position,
+    /// Kind of the variable.
+    kind: VarKind,
+    /// Is the variable ever read?
+    used: bool,
+}
+
+/// Analyzer that walks an [`AST`] to collect diagnostics.
+struct Analyzer<'a> {
+    /// The [`Engine`].
+    engine: &'a Engine,
+    /// Functions library of the script.
+    #[cfg(not(feature = "no_function"))]
+    lib: &'a crate::Module,
+    /// Functions library currently in effect (e.g. inside a `module` block).
+    #[cfg(not(feature = "no_function"))]
+    current_lib: &'a crate::Module,
+    /// Stack of variables in scope.
+    vars: Vec<Var>,
+    /// Index into `vars` where the current function starts.
+    frame: usize,
+    /// Names and number of parameters of all functions registered with the [`Engine`].
+    functions: Option<BTreeSet<(Identifier, usize)>>,
+    /// Diagnostics collected.
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Analyzer<'a> {
+    /// Collect all diagnostics of an [`AST`].
+    fn analyze(engine: &'a Engine, ast: &'a AST) -> Vec<Diagnostic> {
+        let mut analyzer = Self {
+            engine,
+            #[cfg(not(feature = "no_function"))]
+            lib: ast.shared_lib(),
+            #[cfg(not(feature = "no_function"))]
+            current_lib: ast.shared_lib(),
+            vars: Vec::new(),
+            frame: 0,
+            functions: None,
+            diagnostics: Vec::new(),
+        };
+
+        analyzer.walk_statements(ast.statements());
+        // Functions are walked while global variables are still in scope for `global::` access
+        #[cfg(not(feature = "no_function"))]
+        analyzer.walk_functions(ast.shared_lib());
+        analyzer.pop_vars(0);
+
+        analyzer.diagnostics
+    }
+
+    /// Add a diagnostic.
+    #[inline(always)]
+    fn report(&mut self, typ: DiagnosticType, pos: Position) {
+        self.diagnostics.push(Diagnostic(typ, pos));
+    }
+
+    /// Define a variable in the current scope.
+    fn define(&mut self, name: &ImmutableString, pos: Position, kind: VarKind) {
+        if self.vars[self.frame..].iter().any(|v| v.name == *name) {
+            self.report(DiagnosticType::ShadowedVariable(name.to_string()), pos);
+        }
+
+        self.vars.push(Var {
+            name: name.clone(),
+            pos,
+            kind,
+            used: false,
+        });
+    }
+    /// Remove all variables from the given index onwards, reporting those that are unused.
+    fn pop_vars(&mut self, len: usize) {
+        for var in self.vars.drain(len..).collect::<Vec<_>>() {
+            if var.used {
+                continue;
+            }
+
+            let name = var.name.to_string();
+
+            match var.kind {
+                VarKind::Variable => self.report(DiagnosticType::UnusedVariable(name), var.pos),
+                VarKind::Parameter => self.report(DiagnosticType::UnusedParameter(name), var.pos),
+                VarKind::Hidden => (),
+            }
+        }
+    }
+    /// Mark a variable in the current function as used.
+    ///
+    /// Returns `false` if there is no such variable.
+    fn use_var(&mut self, name: &str) -> bool {
+        match self.vars[self.frame..]
+            .iter_mut()
+            .rev()
+            .find(|v| v.name == name)
+        {
+            Some(var) => {
+                var.used = true;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Mark all variables in the current function as used (e.g. when `eval` is called).
+    fn use_all_vars(&mut self) {
+        self.vars[self.frame..]
+            .iter_mut()
+            .for_each(|v| v.used = true);
+    }
+
+    /// Walk a block of statements in a new scope.
+    fn walk_block(&mut self, statements: &'a [Stmt]) {
+        let len = self.vars.len();
+        self.walk_statements(statements);
+        self.pop_vars(len);
+    }
+    /// Walk statements in the current scope.
+    fn walk_statements(&mut self, statements: &'a [Stmt]) {
+        let mut diverged = false;
+        let mut reported = false;
+
+        for stmt in statements {
+            if diverged && !reported && !matches!(stmt, Stmt::Noop(..)) {
+                self.report(DiagnosticType::UnreachableCode, stmt.position());
+                reported = true;
+            }
+
+            self.walk_stmt(stmt);
+
+            if matches!(stmt, Stmt::Return(..) | Stmt::BreakLoop(..)) {
+                diverged = true;
+            }
+        }
+    }
+    /// Check whether a condition is a constant.
+    fn check_condition(&mut self, expr: &Expr) {
+        if let Expr::BoolConstant(value, pos) = expr {
+            self.report(DiagnosticType::ConstantCondition(*value), *pos);
+        }
+    }
+
+    /// Walk a statement.
+    fn walk_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Var(x, options, ..) => {
+                self.walk_expr(&x.1);
+
+                let kind = if options.intersects(ASTFlags::EXPORTED) {
+                    VarKind::Hidden
+                } else {
+                    VarKind::Variable
+                };
+                self.define(&x.0.name, x.0.pos, kind);
+            }
+            Stmt::If(x, ..) => {
+                self.walk_expr(&x.expr);
+                self.check_condition(&x.expr);
+                self.walk_block(x.body.statements());
+                self.walk_block(x.branch.statements());
+            }
+            Stmt::Switch(x, ..) => {
+                let (expr, sw) = &**x;
+
+                self.walk_expr(expr);
+
+                for (index, block) in sw.expressions.iter().enumerate() {
+                    let len = self.vars.len();
+
+                    sw.patterns
+                        .iter()
+                        .filter(|&&(.., i)| i == index)
+                        .for_each(|(pattern, ..)| self.define_pattern(pattern));
+
+                    self.walk_expr(&block.lhs);
+                    self.walk_expr(&block.rhs);
+                    self.pop_vars(len);
+                }
+            }
+            Stmt::While(x, ..) => {
+                self.walk_expr(&x.expr);
+                self.check_condition(&x.expr);
+                self.walk_block(x.body.statements());
+            }
+            Stmt::Do(x, ..) => {
+                let len = self.vars.len();
+                self.walk_statements(x.body.statements());
+                self.walk_expr(&x.expr);
+                self.check_condition(&x.expr);
+                self.pop_vars(len);
+            }
+            Stmt::For(x, ..) => {
+                let (var, counter, flow) = &**x;
+
+                self.walk_expr(&flow.expr);
+
+                let len = self.vars.len();
+                self.define(&var.name, var.pos, VarKind::Variable);
+                if let Some(counter) = counter {
+                    self.define(&counter.name, counter.pos, VarKind::Variable);
+                }
+                self.walk_statements(flow.body.statements());
+                self.pop_vars(len);
+            }
+            Stmt::Assignment(x, ..) => {
+                let (op, expr) = &**x;
+
+                match expr.lhs {
+                    // Plain assignment to a variable is not a use
+                    Expr::Variable(..) if !op.is_op_assignment() => (),
+                    _ => self.walk_expr(&expr.lhs),
+                }
+                self.walk_expr(&expr.rhs);
+            }
+            Stmt::FnCall(x, pos) => self.walk_fn_call(x, *pos),
+            Stmt::Block(x, ..) => self.walk_block(x.statements()),
+            Stmt::TryCatch(x, ..) => {
+                self.walk_block(x.body.statements());
+
+                for clause in &x.catches {
+                    let len = self.vars.len();
+                    if let Expr::Variable(v, _, pos) = &clause.var {
+                        self.define(&v.1, *pos, VarKind::Variable);
+                    }
+                    self.walk_expr(&clause.guard);
+                    self.walk_statements(clause.body.statements());
+                    self.pop_vars(len);
+                }
+
+                self.walk_block(x.finally.statements());
+            }
+            Stmt::Expr(e) | Stmt::BreakLoop(Some(e), ..) | Stmt::Return(Some(e), ..) => {
+                self.walk_expr(e);
+            }
+            #[cfg(not(feature = "no_function"))]
+            Stmt::Yield(e, ..) => self.walk_expr(e),
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, ..) => self.walk_expr(&x.0),
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(x, ..) => {
+                self.use_var(&x.0.name);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Module(x, ..) => {
+                let frame = std::mem::replace(&mut self.frame, self.vars.len());
+                #[cfg(not(feature = "no_function"))]
+                let lib = std::mem::replace(&mut self.current_lib, x.body.shared_lib());
+
+                self.walk_statements(x.body.statements());
+                #[cfg(not(feature = "no_function"))]
+                self.walk_functions(x.body.shared_lib());
+                self.pop_vars(self.frame);
+
+                #[cfg(not(feature = "no_function"))]
+                {
+                    self.current_lib = lib;
+                }
+                self.frame = frame;
+            }
+            _ => (),
+        }
+    }
+
+    /// Define all variables bound by a `switch` case pattern.
+    fn define_pattern(&mut self, pattern: &'a crate::ast::SwitchPattern) {
+        use crate::ast::SwitchPattern;
+
+        match pattern {
+            SwitchPattern::Bind(var) => self.define(&var.name, var.pos, VarKind::Variable),
+            SwitchPattern::Tuple(x) => x.iter().for_each(|p| self.define_pattern(p)),
+            SwitchPattern::Enum(x) => x.2.iter().for_each(|p| self.define_pattern(p)),
+            SwitchPattern::Wildcard | SwitchPattern::Value(..) => (),
+        }
+    }
+
+    /// Walk an expression.
+    fn walk_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Variable(x, ..) => {
+                #[cfg(not(feature = "no_module"))]
+                if !x.2.is_empty() {
+                    // Global constants are accessed via `global::`
+                    #[cfg(not(feature = "no_function"))]
+                    if x.2.root() == crate::engine::KEYWORD_GLOBAL {
+                        if let Some(var) = self.vars.iter_mut().rev().find(|v| v.name == x.1) {
+                            var.used = true;
+                        }
+                    }
+                    return;
+                }
+                self.use_var(&x.1);
+            }
+            #[cfg(not(feature = "no_function"))]
+            Expr::DynamicConstant(value, ..) => self.walk_closure(value, 0),
+            Expr::InterpolatedString(x, ..) | Expr::Array(x, ..) | Expr::Tuple(x, ..) => {
+                x.iter().for_each(|e| self.walk_expr(e));
+            }
+            Expr::Enum(x, ..) => x.2.iter().for_each(|e| self.walk_expr(e)),
+            Expr::Map(x, ..) => x.0.iter().for_each(|(_, e)| self.walk_expr(e)),
+            #[cfg(not(feature = "no_object"))]
+            Expr::Struct(x, ..) => x.0.iter().for_each(|(_, e)| self.walk_expr(e)),
+            Expr::Spread(e, ..) => self.walk_expr(e),
+            Expr::And(x, ..) | Expr::Or(x, ..) | Expr::Coalesce(x, ..) => {
+                x.iter().for_each(|e| self.walk_expr(e));
+            }
+            Expr::Comprehension(x, ..) => {
+                self.walk_expr(&x.iterable);
+
+                let len = self.vars.len();
+                self.define(&x.var.name, x.var.pos, VarKind::Variable);
+                if let Some(ref counter) = x.counter {
+                    self.define(&counter.name, counter.pos, VarKind::Variable);
+                }
+                if let Some(ref condition) = x.condition {
+                    self.walk_expr(condition);
+                }
+                if let Some(ref key) = x.key {
+                    self.walk_expr(key);
+                }
+                self.walk_expr(&x.value);
+                self.pop_vars(len);
+            }
+            Expr::Stmt(x) => self.walk_block(x.statements()),
+            Expr::FnCall(x, pos) => self.walk_fn_call(x, *pos),
+            Expr::MethodCall(x, ..) => self.walk_method_call(x),
+            Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+                self.walk_expr(&x.lhs);
+                self.walk_chain(&x.rhs);
+            }
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, ..) => x.inputs.iter().for_each(|e| self.walk_expr(e)),
+            _ => (),
+        }
+    }
+    /// Walk the right-hand side of a dotted or indexed chain.
+    fn walk_chain(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Property(..) => (),
+            Expr::MethodCall(x, ..) => self.walk_method_call(x),
+            Expr::Dot(x, ..) => {
+                self.walk_chain(&x.lhs);
+                self.walk_chain(&x.rhs);
+            }
+            Expr::Index(x, ..) => {
+                self.walk_chain(&x.lhs);
+                self.walk_expr(&x.rhs);
+            }
+            _ => self.walk_expr(expr),
+        }
+    }
+    /// Walk a method call.
+    ///
+    /// Methods are not checked for existence because they may be function pointers stored in
+    /// object map properties.
+    fn walk_method_call(&mut self, x: &'a crate::ast::FnCallExpr) {
+        self.check_fn_name_strings(x, true);
+
+        x.args.iter().for_each(|e| self.walk_expr(e));
+    }
+    /// Walk a function call.
+    fn walk_fn_call(&mut self, x: &'a crate::ast::FnCallExpr, pos: Position) {
+        #[cfg(not(feature = "no_module"))]
+        let namespaced = !x.namespace.is_empty();
+        #[cfg(feature = "no_module")]
+        let namespaced = false;
+
+        if x.capture_parent_scope
+            || (!namespaced && (x.name == KEYWORD_EVAL || x.name == KEYWORD_IS_DEF_VAR))
+        {
+            // The scope may be accessed dynamically
+            self.use_all_vars();
+        }
+
+        if !namespaced {
+            self.check_fn_name_strings(x, false);
+        }
+
+        #[allow(unused_mut)]
+        let mut args = x.args.as_slice();
+
+        // Closures with captured variables
+        #[cfg(not(feature = "no_function"))]
+        if x.name == KEYWORD_FN_PTR_CURRY && !namespaced {
+            if let Some(Expr::DynamicConstant(value, ..)) = args.first() {
+                self.walk_closure(value, args.len() - 1);
+                args = &args[1..];
+            }
+        }
+
+        args.iter().for_each(|e| self.walk_expr(e));
+
+        // Calling a function pointer held in a variable
+        if !namespaced && self.use_var(&x.name) {
+            return;
+        }
+
+        if !namespaced && x.op_token.is_none() && !self.is_known_fn(&x.name, x.args.len()) {
+            self.report(
+                DiagnosticType::UnknownFunction(x.name.to_string(), x.args.len()),
+                pos,
+            );
+        }
+    }
+    /// Is a function (called without a namespace) known?
+    fn is_known_fn(&mut self, name: &str, num_params: usize) -> bool {
+        if !name.starts_with(is_id_first_alphabetic) || KEYWORD_FUNCTIONS.contains(&name) {
+            return true;
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        if self.lib.get_script_fn(name, num_params).is_some()
+            || self.current_lib.get_script_fn(name, num_params).is_some()
+        {
+            return true;
+        }
+
+        let engine = self.engine;
+
+        self.functions
+            .get_or_insert_with(|| {
+                let mut functions = BTreeSet::new();
+
+                for m in &engine.global_modules {
+                    functions.extend(m.iter_fn().map(|(_, f)| (f.name.clone(), f.num_params)));
+                }
+
+                #[cfg(not(feature = "no_module"))]
+                for m in engine.global_sub_modules.values() {
+                    collect_global_functions(m, &mut functions);
+                }
+
+                functions
+            })
+            .contains(&(name.into(), num_params))
+    }
+    /// Check for function names passed as strings where function pointers are expected.
+    ///
+    /// `call` and `curry` always fail on strings, while array functions (e.g. `map`) still accept
+    /// the names of script-defined functions, which is deprecated.
+    fn check_fn_name_strings(&mut self, x: &crate::ast::FnCallExpr, is_method: bool) {
+        let is_fn_ptr_call =
+            !is_method && (x.name == KEYWORD_FN_PTR_CALL || x.name == KEYWORD_FN_PTR_CURRY);
+        #[cfg(not(feature = "no_function"))]
+        #[cfg(not(feature = "no_index"))]
+        let is_array_fn = DEPRECATED_FN_NAME_FUNCTIONS.contains(&x.name.as_str());
+        #[cfg(any(feature = "no_function", feature = "no_index"))]
+        let is_array_fn = false;
+
+        if !is_fn_ptr_call && !is_array_fn {
+            return;
+        }
+
+        for (i, arg) in x.args.iter().enumerate() {
+            match arg {
+                Expr::StringConstant(s, pos)
+                    if (is_fn_ptr_call && i == 0) || (is_array_fn && self.is_script_fn(s)) =>
+                {
+                    self.report(DiagnosticType::FnNameString(s.to_string()), *pos);
+                }
+                _ => (),
+            }
+        }
+    }
+    /// Is there a script-defined function (of any arity) with a particular name?
+    fn is_script_fn(&self, _name: &str) -> bool {
+        #[cfg(not(feature = "no_function"))]
+        return self.current_lib.iter_script_fn().any(|f| f.2 == _name)
+            || self.lib.iter_script_fn().any(|f| f.2 == _name);
+        #[cfg(feature = "no_function")]
+        return false;
+    }
+
+    /// Walk all script-defined functions in a functions library, except closures.
+    #[cfg(not(feature = "no_function"))]
+    fn walk_functions(&mut self, lib: &'a crate::Module) {
+        lib.iter_script_fn()
+            .filter(|(.., fn_def)| !crate::func::is_anonymous_fn(&fn_def.name))
+            .for_each(|(.., fn_def)| self.walk_fn_def(fn_def, 0));
+    }
+    /// Walk the body of a closure, given its function pointer.
+    ///
+    /// The first `num_externals` parameters are captured variables.
+    #[cfg(not(feature = "no_function"))]
+    fn walk_closure(&mut self, value: &crate::Dynamic, num_externals: usize) {
+        let fn_def = match value.read_lock::<crate::FnPtr>() {
+            Some(fn_ptr) if fn_ptr.is_anonymous() => self
+                .current_lib
+                .iter_script_fn()
+                .find(|f| f.2 == fn_ptr.fn_name())
+                .map(|(.., fn_def)| fn_def),
+            _ => None,
+        };
+
+        if let Some(fn_def) = fn_def {
+            self.walk_fn_def(fn_def, num_externals);
+        }
+    }
+    /// Walk the body of a script-defined function in a new frame.
+    #[cfg(not(feature = "no_function"))]
+    fn walk_fn_def(&mut self, fn_def: &'a crate::ast::ScriptFuncDef, num_externals: usize) {
+        let frame = std::mem::replace(&mut self.frame, self.vars.len());
+        // Parameter positions are not kept, so use the position of the function body
+        let pos = fn_def.body.position();
+
+        for (i, param) in fn_def.params.iter().enumerate() {
+            let kind = if i < num_externals {
+                VarKind::Hidden
+            } else {
+                VarKind::Parameter
+            };
+            self.define(param, pos, kind);
+        }
+
+        self.walk_statements(fn_def.body.statements());
+        self.pop_vars(self.frame);
+        self.frame = frame;
+    }
+}
+
+/// Collect the names and number of parameters of all global functions in a module and its
+/// sub-modules.
+#[cfg(not(feature = "no_module"))]
+fn collect_global_functions(module: &crate::Module, functions: &mut BTreeSet<(Identifier, usize)>) {
+    functions.extend(
+        module
+            .iter_fn()
+            .filter(|(_, f)| f.namespace == crate::FnNamespace::Global)
+            .map(|(_, f)| (f.name.clone(), f.num_params)),
+    );
+
+    for (_, m) in module.iter_sub_modules() {
+        collect_global_functions(m, functions);
+    }
+}
+
+impl Engine {
+    /// Compile a string into an [`AST`], together with [diagnostics][Diagnostic] (i.e. warnings)
+    /// about suspicious code in the script.
+    ///
+    /// The following are reported:
+    ///
+    /// * variables and function parameters that are never used,
+    /// * variables that shadow other variables in the same function, even when shadowing is
+    ///   allowed,
+    /// * statements that can never be reached because they follow a `return`, `throw`, `break`
+    ///   or `continue`,
+    /// * `if`, `while` and `do` statements with constant conditions,
+    /// * calls to functions that are neither defined in the script nor registered with this
+    ///   [`Engine`] (method calls are not checked),
+    /// * function names passed as strings to `call`, `curry` or array functions (e.g. `map`),
+    ///   which is deprecated for the latter,
+    /// * keywords and operators marked deprecated via [`deprecate_symbol`][Engine::deprecate_symbol].
+    ///
+    /// Diagnostics are sorted by position. They are reported before the [`AST`] is optimized, so
+    /// code later removed by the optimizer is still analyzed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, DiagnosticType, INT};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let (ast, diagnostics) = engine.compile_with_diagnostics("let x = 40; let y = 2; 42")?;
+    ///
+    /// assert_eq!(diagnostics.len(), 2);
+    /// assert_eq!(diagnostics[0].code(), "unused_variable");
+    /// assert_eq!(diagnostics[0].diagnostic_type(), &DiagnosticType::UnusedVariable("x".into()));
+    /// assert_eq!(diagnostics[1].diagnostic_type(), &DiagnosticType::UnusedVariable("y".into()));
+    ///
+    /// assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compile_with_diagnostics(
+        &self,
+        script: impl AsRef<str>,
+    ) -> ParseResult<(AST, Vec<Diagnostic>)> {
+        let scripts = [script];
+        let (stream, tc) = self.lex(&scripts);
+
+        tc.borrow_mut().deprecated = Some(Vec::new());
+
+        let input = &mut stream.peekable();
+        let lib = &mut <_>::default();
+        let state = ParseState::new(None, input, tc.clone(), lib);
+        #[allow(unused_mut)]
+        let mut ast = self.parse(
+            state,
+            #[cfg(not(feature = "no_optimize"))]
+            crate::OptimizationLevel::None,
+        )?;
+        #[cfg(feature = "metadata")]
+        {
+            let global_comments = &tc.borrow().global_comments;
+            ast.doc = global_comments.into();
+        }
+
+        let mut diagnostics = Analyzer::analyze(self, &ast);
+
+        let deprecated = tc.borrow_mut().deprecated.take().unwrap_or_default();
+        diagnostics.extend(deprecated.into_iter().map(|(symbol, pos)| {
+            let note = self.deprecated_symbols[&symbol].clone();
+            Diagnostic(DiagnosticType::Deprecated(symbol.to_string(), note), pos)
+        }));
+        diagnostics.sort_by_key(|d| d.1);
+
+        #[cfg(not(feature = "no_optimize"))]
+        let ast = self.optimize_ast(&crate::Scope::new(), ast, self.optimization_level);
+
+        Ok((ast, diagnostics))
+    }
+}
@@ -228,6 +228,9 @@ impl Engine {
     ) -> RhaiResult {
         let orig_source = mem::replace(&mut global.source, ast.source_raw().cloned());
 
+        #[cfg(feature = "coverage")]
+        self.cov_ast(ast);
+
         #[cfg(not(feature = "no_function"))]
         let orig_lib_len = global.lib.len();
 
@@ -250,7 +253,15 @@ impl Engine {
             g.source = orig_source;
         }}
 
-        let r = self.eval_global_statements(global, caches, scope, ast.statements(), true)?;
+        #[cfg(feature = "profiling")]
+        let entry = self.prof_enter_script(global);
+
+        let r = self.eval_global_statements(global, caches, scope, ast.statements(), true);
+
+        #[cfg(feature = "profiling")]
+        self.prof_exit(global, entry);
+
+        let r = r?;
 
         #[cfg(feature = "debugging")]
         if self.is_debugger_registered() {
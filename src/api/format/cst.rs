@@ -0,0 +1,322 @@
+//! Module defining a lossless concrete syntax view of a script.
+
+use crate::parser::{ParseResult, ParseState};
+use crate::tokenizer::is_id_first_alphabetic;
+use crate::{Engine, Position};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// A comment in a script.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Comment {
+    /// Text of the comment, including the comment markers.
+    pub text: String,
+    /// Is the comment preceded by a blank line?
+    pub blank_before: bool,
+}
+
+impl Comment {
+    /// Is this a line comment (i.e. `//`)?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_line(&self) -> bool {
+        self.text.starts_with("//")
+    }
+}
+
+/// Kind of a token.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Class {
+    /// An identifier, keyword, custom keyword or label.
+    Word,
+    /// A literal constant, or an interpolated string kept verbatim.
+    Literal,
+    /// An operator or punctuation symbol.
+    Symbol,
+    /// Raw text not recognized as tokens (e.g. consumed by custom syntax), kept verbatim.
+    Raw,
+}
+
+/// A token in a script, together with the comments around it.
+#[derive(Debug, Clone)]
+pub struct CstToken {
+    /// Original text of the token.
+    pub text: String,
+    /// Kind of the token.
+    pub class: Class,
+    /// Own-line comments before the token.
+    pub leading: Vec<Comment>,
+    /// Comments after the token on the same line.
+    pub trailing: Vec<Comment>,
+    /// Is the token preceded by a blank line?
+    pub blank_before: bool,
+}
+
+/// A lossless concrete syntax view of a script: all its tokens in order, together with comments
+/// and blank lines.
+#[derive(Debug, Clone, Default)]
+pub struct Cst {
+    /// Tokens of the script.
+    pub tokens: Vec<CstToken>,
+    /// Own-line comments after the last token.
+    pub comments: Vec<Comment>,
+}
+
+/// Classify the text of a token.
+fn classify(text: &str) -> Class {
+    let mut chars = text.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some('0'..='9' | '"' | '`'), ..) | (Some('#'), Some('"' | '#')) => Class::Literal,
+        (Some('\''), ..) if text.len() > 2 && text.ends_with('\'') => Class::Literal,
+        (Some('\''), ..) => Class::Word,
+        (Some(ch), ..) if ch == '_' || is_id_first_alphabetic(ch) => Class::Word,
+        _ => Class::Symbol,
+    }
+}
+
+impl Cst {
+    /// Parse a script into a [`Cst`].
+    ///
+    /// The script is parsed in full, so only valid scripts are accepted.
+    pub fn parse(engine: &Engine, script: &str) -> ParseResult<Self> {
+        let scripts = [script];
+        let (stream, tc) = engine.lex(&scripts);
+
+        tc.borrow_mut().spans = Some(Vec::new());
+
+        let input = &mut stream.peekable();
+        let lib = &mut <_>::default();
+        let state = ParseState::new(None, input, tc.clone(), lib);
+
+        engine.parse(
+            state,
+            #[cfg(not(feature = "no_optimize"))]
+            crate::OptimizationLevel::None,
+        )?;
+
+        let spans = tc.borrow_mut().spans.take().unwrap_or_default();
+
+        let chars: Vec<_> = script.chars().collect();
+        let mut line_starts = vec![0];
+        line_starts.extend(
+            chars
+                .iter()
+                .enumerate()
+                .filter(|&(_, &ch)| ch == '\n')
+                .map(|(i, _)| i + 1),
+        );
+        let offset = |pos: Position| {
+            let line = line_starts[pos.line().unwrap() - 1];
+            (line + pos.position().unwrap_or(1) - 1).min(chars.len())
+        };
+
+        // Map spans into character ranges
+        let mut ranges = Vec::with_capacity(spans.len());
+
+        for span in spans {
+            let end = offset(span.end()) + 1;
+            let start = if span.start().is_none() {
+                ranges.last().map_or(0, |&(_, end)| end)
+            } else {
+                offset(span.start())
+            };
+            let text: String = chars[start..end].iter().collect();
+
+            // Skip doc-comments
+            if text.starts_with("//") || text.starts_with("/*") {
+                continue;
+            }
+            ranges.push((start, end));
+        }
+
+        // Merge interpolated strings into single tokens
+        let text_at = |ranges: &[(usize, usize)], i: usize| -> String {
+            ranges.get(i).map_or_else(String::new, |&(start, end)| {
+                chars[start..end].iter().collect()
+            })
+        };
+        let mut tokens = Vec::with_capacity(ranges.len());
+        let mut i = 0;
+
+        while i < ranges.len() {
+            let (start, mut end) = ranges[i];
+            let text = text_at(&ranges, i);
+
+            if (text.starts_with('`') || text.starts_with("\"\"\""))
+                && text.ends_with('$')
+                && text_at(&ranges, i + 1) == "{"
+            {
+                loop {
+                    let mut level = 0;
+                    i += 1;
+
+                    while i < ranges.len() {
+                        match text_at(&ranges, i).as_str() {
+                            "{" | "#{" => level += 1,
+                            "}" => level -= 1,
+                            _ => (),
+                        }
+                        if level == 0 {
+                            break;
+                        }
+                        i += 1;
+                    }
+
+                    i += 1;
+
+                    if i >= ranges.len() {
+                        break;
+                    }
+                    end = ranges[i].1;
+
+                    if !(text_at(&ranges, i).ends_with('$') && text_at(&ranges, i + 1) == "{") {
+                        break;
+                    }
+                }
+                tokens.push((start, end, Class::Literal));
+            } else {
+                let class = classify(&text);
+                // Some symbols include trailing whitespace
+                if class == Class::Symbol {
+                    end -= text.chars().rev().take_while(|c| c.is_whitespace()).count();
+                }
+                tokens.push((start, end, class));
+            }
+            i += 1;
+        }
+
+        // Collect comments and blank lines in gaps between tokens
+        let mut cst = Self::default();
+        let mut prev_end = 0;
+
+        for (start, end, class) in
+            tokens
+                .into_iter()
+                .chain([(chars.len(), chars.len(), Class::Raw)])
+        {
+            let gap = &chars[prev_end..start.max(prev_end)];
+            let at_end = start == chars.len() && end == start;
+            let mut token = CstToken {
+                text: chars[start..end].iter().collect(),
+                class,
+                leading: Vec::new(),
+                trailing: Vec::new(),
+                blank_before: false,
+            };
+
+            match scan_gap(gap) {
+                Some(items) => {
+                    let mut new_line = cst.tokens.is_empty();
+                    let mut new_lines = 0;
+
+                    for item in items {
+                        match item {
+                            Trivia::NewLine => {
+                                new_line = true;
+                                new_lines += 1;
+                            }
+                            Trivia::Comment(text) if !new_line => {
+                                if let Some(prev) = cst.tokens.last_mut() {
+                                    prev.trailing.push(Comment {
+                                        text,
+                                        blank_before: false,
+                                    });
+                                }
+                            }
+                            Trivia::Comment(text) => {
+                                token.leading.push(Comment {
+                                    text,
+                                    blank_before: new_lines >= 2,
+                                });
+                                new_lines = 0;
+                            }
+                        }
+                    }
+                    token.blank_before = new_lines >= 2;
+                }
+                None => {
+                    // Unrecognized text is kept verbatim together with the following token
+                    let text: String = gap.iter().collect();
+                    token.text = text.trim_start().to_string() + token.text.as_str();
+                    token.class = Class::Raw;
+                }
+            }
+
+            if at_end {
+                cst.comments = token.leading;
+                if !token.text.is_empty() {
+                    cst.tokens.push(CstToken {
+                        leading: Vec::new(),
+                        ..token
+                    });
+                }
+            } else {
+                cst.tokens.push(token);
+            }
+
+            prev_end = end;
+        }
+
+        Ok(cst)
+    }
+}
+
+/// An item in the gap between two tokens.
+enum Trivia {
+    /// A new-line.
+    NewLine,
+    /// A comment.
+    Comment(String),
+}
+
+/// Break up the gap between two tokens into new-lines and comments.
+///
+/// Returns `None` if the gap contains anything other than whitespace and comments.
+fn scan_gap(gap: &[char]) -> Option<Vec<Trivia>> {
+    let mut items = Vec::new();
+    let mut i = 0;
+
+    while i < gap.len() {
+        match (gap[i], gap.get(i + 1)) {
+            ('\n', ..) => {
+                items.push(Trivia::NewLine);
+                i += 1;
+            }
+            (ch, ..) if ch.is_whitespace() => i += 1,
+            ('/', Some('/')) => {
+                let start = i;
+                while i < gap.len() && gap[i] != '\n' {
+                    i += 1;
+                }
+                let text: String = gap[start..i].iter().collect();
+                items.push(Trivia::Comment(text.trim_end().to_string()));
+            }
+            ('/', Some('*')) => {
+                let start = i;
+                let mut level = 0;
+
+                while i < gap.len() {
+                    match (gap[i], gap.get(i + 1)) {
+                        ('/', Some('*')) => {
+                            level += 1;
+                            i += 2;
+                        }
+                        ('*', Some('/')) => {
+                            level -= 1;
+                            i += 2;
+                            if level == 0 {
+                                break;
+                            }
+                        }
+                        _ => i += 1,
+                    }
+                }
+                items.push(Trivia::Comment(gap[start..i].iter().collect()));
+            }
+            _ => return None,
+        }
+    }
+
+    Some(items)
+}
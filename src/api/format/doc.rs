@@ -0,0 +1,204 @@
+//! Module defining a document layout and its pretty-printer.
+
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// A document to be laid out.
+#[derive(Debug, Clone)]
+pub enum Doc {
+    /// Text, printed as-is.
+    Text(String),
+    /// A space, or a new-line if the enclosing group is broken.
+    Line,
+    /// Nothing, or a new-line if the enclosing group is broken.
+    SoftLine,
+    /// Always a new-line.
+    HardLine,
+    /// An empty line.
+    BlankLine,
+    /// A sequence of documents.
+    Concat(Vec<Doc>),
+    /// A sequence of documents, indented one level when broken into new lines.
+    Nest(Vec<Doc>),
+    /// A sequence of documents that is printed on a single line if it fits.
+    Group(Vec<Doc>),
+    /// A comment on its own line.
+    Comment(String),
+    /// A comment at the end of the current line.
+    LineComment(String),
+}
+
+impl Doc {
+    /// Create a [`Doc::Text`].
+    #[inline(always)]
+    #[must_use]
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+}
+
+/// Printing mode of a document.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Mode {
+    /// Lines are spaces.
+    Flat,
+    /// Lines are new-lines.
+    Break,
+}
+
+/// Pretty-printer of documents.
+struct Printer {
+    /// Maximum line width.
+    line_width: usize,
+    /// Number of spaces for one level of indentation.
+    indent_width: usize,
+    /// Output text.
+    output: String,
+    /// Current column.
+    column: usize,
+    /// Indentation of the current line, if nothing has been printed on it yet.
+    pending_indent: Option<usize>,
+    /// Comments to print at the end of the current line.
+    suffixes: Vec<String>,
+}
+
+impl Printer {
+    /// Print text.
+    fn text(&mut self, text: &str) {
+        if let Some(indent) = self.pending_indent.take() {
+            self.output.extend(std::iter::repeat(' ').take(indent));
+            self.column = indent;
+        }
+        self.output.push_str(text);
+
+        match text.rfind('\n') {
+            Some(n) => self.column = text[n + 1..].chars().count(),
+            None => self.column += text.chars().count(),
+        }
+    }
+    /// Print comments pending at the end of the current line.
+    fn flush_suffixes(&mut self) {
+        for suffix in std::mem::take(&mut self.suffixes) {
+            self.trim_end();
+            self.output.push(' ');
+            self.output.push_str(&suffix);
+        }
+    }
+    /// Remove trailing spaces.
+    fn trim_end(&mut self) {
+        let len = self.output.trim_end_matches(' ').len();
+        self.output.truncate(len);
+    }
+    /// Start a new line, unless nothing has been printed on the current line.
+    fn new_line(&mut self, indent: usize) {
+        self.flush_suffixes();
+
+        if self.pending_indent.is_none() {
+            self.trim_end();
+            self.output.push('\n');
+        }
+        self.pending_indent = Some(indent);
+        self.column = indent;
+    }
+    /// Start a new line after an empty line.
+    fn blank_line(&mut self, indent: usize) {
+        self.new_line(indent);
+
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+            self.output.push('\n');
+        }
+    }
+
+    /// Does a flat document, followed by the rest of the commands, fit on the current line?
+    fn fits(&self, docs: &[Doc], rest: &[(usize, Mode, &Doc)]) -> bool {
+        let mut remaining = self.line_width as isize - self.column as isize;
+        let mut stack: Vec<_> = docs
+            .iter()
+            .rev()
+            .map(|doc| (Mode::Flat, false, doc))
+            .collect();
+        let mut rest = rest.iter().rev().map(|&(_, mode, doc)| (mode, true, doc));
+
+        loop {
+            let (mode, is_rest, doc) = match stack.pop() {
+                Some(item) => item,
+                None => match rest.next() {
+                    Some(item) => item,
+                    None => return true,
+                },
+            };
+
+            match doc {
+                Doc::Text(text) => match text.find('\n') {
+                    Some(n) => return remaining >= text[..n].chars().count() as isize,
+                    None => remaining -= text.chars().count() as isize,
+                },
+                Doc::Line | Doc::SoftLine if mode == Mode::Break => return true,
+                Doc::Line => remaining -= 1,
+                Doc::SoftLine => (),
+                Doc::HardLine | Doc::BlankLine | Doc::Comment(..) => return is_rest,
+                Doc::LineComment(..) if is_rest => (),
+                Doc::LineComment(..) => return false,
+                Doc::Concat(docs) | Doc::Nest(docs) | Doc::Group(docs) => {
+                    stack.extend(docs.iter().rev().map(|doc| (mode, is_rest, doc)));
+                }
+            }
+
+            if remaining < 0 {
+                return false;
+            }
+        }
+    }
+
+    /// Print a document.
+    fn print(&mut self, doc: &Doc) {
+        let mut commands = vec![(0, Mode::Break, doc)];
+
+        while let Some((indent, mode, doc)) = commands.pop() {
+            match doc {
+                Doc::Text(text) => self.text(text),
+                Doc::Line if mode == Mode::Flat => self.text(" "),
+                Doc::SoftLine if mode == Mode::Flat => (),
+                Doc::Line | Doc::SoftLine | Doc::HardLine => self.new_line(indent),
+                Doc::BlankLine => self.blank_line(indent),
+                Doc::Concat(docs) => commands.extend(docs.iter().rev().map(|d| (indent, mode, d))),
+                Doc::Nest(docs) => {
+                    let indent = indent + self.indent_width;
+                    commands.extend(docs.iter().rev().map(|d| (indent, mode, d)));
+                }
+                Doc::Group(docs) => {
+                    let mode = if mode == Mode::Flat || self.fits(docs, &commands) {
+                        Mode::Flat
+                    } else {
+                        Mode::Break
+                    };
+                    commands.extend(docs.iter().rev().map(|d| (indent, mode, d)));
+                }
+                Doc::Comment(text) => {
+                    self.new_line(indent);
+                    self.text(text);
+                    self.new_line(indent);
+                }
+                Doc::LineComment(text) => self.suffixes.push(text.clone()),
+            }
+        }
+
+        self.new_line(0);
+    }
+}
+
+/// Pretty-print a document.
+#[must_use]
+pub fn print(doc: &Doc, indent_width: usize, line_width: usize) -> String {
+    let mut printer = Printer {
+        line_width,
+        indent_width,
+        output: String::new(),
+        column: 0,
+        pending_indent: Some(0),
+        suffixes: Vec::new(),
+    };
+
+    printer.print(doc);
+    printer.output
+}
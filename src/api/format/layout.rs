@@ -0,0 +1,534 @@
+//! Module that lays out the tokens of a script into a document.
+
+use super::cst::{Class, Comment, Cst, CstToken};
+use super::doc::Doc;
+use crate::tokenizer::{
+    is_id_continue, is_id_first_alphabetic, is_reserved_keyword_or_symbol, Token,
+};
+use crate::Engine;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Keywords that are followed by an expression or a name, i.e. they do not end an operand.
+const KEYWORDS: &[&str] = &[
+    "if", "else", "while", "until", "loop", "do", "for", "in", "switch", "return", "throw", "try",
+    "catch", "finally", "let", "const", "fn", "private", "import", "export", "as", "break",
+    "continue", "yield", "defer", "enum", "struct",
+];
+
+/// Keywords starting statements whose blocks are always broken into multiple lines.
+const STMT_KEYWORDS: &[&str] = &["if", "while", "loop", "for", "do", "fn", "private", "try"];
+
+/// Keywords that continue a statement after a block.
+const CONTINUE_KEYWORDS: &[&str] = &["else", "catch", "finally"];
+
+/// Role of a token within an expression.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Role {
+    /// No special role.
+    None,
+    /// A unary operator.
+    Unary,
+    /// The opening `|` of closure parameters.
+    ClosureOpen,
+    /// The closing `|` of closure parameters.
+    ClosureClose,
+}
+
+/// Kind of a bracketed group of tokens.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Group {
+    /// A block of statements.
+    Block,
+    /// A comma-separated list, with spaces inside braces if `true`.
+    List(bool),
+    /// The body of a `switch` expression.
+    Switch,
+}
+
+/// Lays out the tokens of a script into a [`Doc`].
+pub struct Layout<'a> {
+    /// The [`Engine`].
+    #[cfg_attr(feature = "no_custom_syntax", allow(dead_code))]
+    engine: &'a Engine,
+    /// Tokens of the script.
+    tokens: Vec<CstToken>,
+    /// Index of the matching closing bracket, for opening brackets.
+    matching: Vec<Option<usize>>,
+    /// Roles of the tokens.
+    roles: Vec<Role>,
+}
+
+impl<'a> Layout<'a> {
+    /// Create a new [`Layout`].
+    #[must_use]
+    pub fn new(engine: &'a Engine, tokens: Vec<CstToken>) -> Self {
+        let mut matching = vec![None; tokens.len()];
+        let mut stack = Vec::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            if token.class != Class::Symbol {
+                continue;
+            }
+            match token.text.as_str() {
+                "(" | "[" | "?[" | "{" | "#{" => stack.push(i),
+                ")" | "]" | "}" => {
+                    if let Some(open) = stack.pop() {
+                        matching[open] = Some(i);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut layout = Self {
+            engine,
+            tokens,
+            matching,
+            roles: Vec::new(),
+        };
+
+        layout.roles = vec![Role::None; layout.tokens.len()];
+
+        for i in 0..layout.tokens.len() {
+            if layout.tokens[i].class != Class::Symbol
+                || (i > 0 && layout.is_operand_end(i - 1))
+                || layout.roles[i] != Role::None
+            {
+                continue;
+            }
+            match layout.text(i) {
+                "-" | "+" | "!" => layout.roles[i] = Role::Unary,
+                "|" => {
+                    layout.roles[i] = Role::ClosureOpen;
+
+                    let mut j = i + 1;
+
+                    while j < layout.tokens.len() {
+                        if let Some(close) = layout.matching[j] {
+                            j = close + 1;
+                            continue;
+                        }
+                        match layout.text(j) {
+                            "|" => {
+                                layout.roles[j] = Role::ClosureClose;
+                                break;
+                            }
+                            ")" | "]" | "}" => break,
+                            _ => j += 1,
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        layout
+    }
+
+    /// Get the text of a token.
+    #[inline(always)]
+    fn text(&self, index: usize) -> &str {
+        self.tokens.get(index).map_or("", |t| t.text.as_str())
+    }
+    /// Is the token a symbol with the specified text?
+    #[inline]
+    fn is_symbol(&self, index: usize, text: &str) -> bool {
+        self.tokens
+            .get(index)
+            .map_or(false, |t| t.class == Class::Symbol && t.text == text)
+    }
+    /// Is the text a custom keyword or operator?
+    #[inline]
+    #[allow(unused_variables)]
+    fn is_custom_keyword(&self, text: &str) -> bool {
+        #[cfg(not(feature = "no_custom_syntax"))]
+        return self.engine.custom_keywords.contains_key(text);
+        #[cfg(feature = "no_custom_syntax")]
+        return false;
+    }
+    /// Is the text the first keyword of a custom syntax?
+    #[inline]
+    #[allow(unused_variables)]
+    fn is_custom_syntax(&self, text: &str) -> bool {
+        #[cfg(not(feature = "no_custom_syntax"))]
+        return self.engine.custom_syntax.contains_key(text);
+        #[cfg(feature = "no_custom_syntax")]
+        return false;
+    }
+    /// Is the token a keyword (including custom keywords and custom syntax)?
+    fn is_keyword(&self, index: usize) -> bool {
+        let text = self.text(index);
+        KEYWORDS.contains(&text) || self.is_custom_keyword(text) || self.is_custom_syntax(text)
+    }
+    /// Can the token end an operand?
+    fn is_operand_end(&self, index: usize) -> bool {
+        match self.tokens[index].class {
+            Class::Literal | Class::Raw => true,
+            Class::Word => !self.is_keyword(index),
+            Class::Symbol => matches!(self.text(index), ")" | "]" | "}" | "()"),
+        }
+    }
+    /// Is the token immediately followed by call arguments?
+    fn is_callee(&self, index: usize) -> bool {
+        match self.tokens[index].class {
+            Class::Word => !self.is_keyword(index),
+            Class::Symbol => matches!(self.text(index), ")" | "]" | "()"),
+            Class::Literal | Class::Raw => false,
+        }
+    }
+
+    /// Is a space needed between two consecutive tokens?
+    fn needs_space(&self, prev: usize, next: usize) -> bool {
+        let (a, b) = (self.text(prev), self.text(next));
+        let prev_symbol = self.tokens[prev].class == Class::Symbol;
+        let next_symbol = self.tokens[next].class == Class::Symbol;
+
+        let no_space = (next_symbol
+            && matches!(b, "," | ";" | ")" | "]" | "." | "?." | "::" | "?[" | ":"))
+            || (next_symbol && matches!(b, "(" | "()") && self.is_callee(prev))
+            || (next_symbol && b == "[" && self.is_operand_end(prev))
+            || (prev_symbol && matches!(a, "(" | "[" | "?[" | "." | "?." | "::" | "..."))
+            || (prev_symbol && matches!(a, ".." | "..="))
+            || (next_symbol && matches!(b, ".." | "..="))
+            || matches!(self.roles[prev], Role::Unary | Role::ClosureOpen)
+            || self.roles[next] == Role::ClosureClose;
+
+        if !no_space {
+            return true;
+        }
+
+        // Make sure that the two tokens do not merge into one
+        match (a.chars().last(), b.chars().next()) {
+            (Some(x), Some(y)) => {
+                let is_id = |c: char| c == '_' || is_id_first_alphabetic(c) || is_id_continue(c);
+
+                if is_id(x) && is_id(y) {
+                    return true;
+                }
+                let pair: String = [x, y].into_iter().collect();
+
+                Token::lookup_symbol_from_syntax(&pair).is_some()
+                    || is_reserved_keyword_or_symbol(&pair).0
+                    || self.is_custom_keyword(&pair)
+            }
+            _ => false,
+        }
+    }
+
+    /// Lay out a token together with its trailing comments.
+    fn token(&self, index: usize) -> Doc {
+        let token = &self.tokens[index];
+        let mut docs = vec![Doc::text(token.text.as_str())];
+        docs.extend(Self::trailing(&token.trailing));
+        Doc::Concat(docs)
+    }
+    /// Lay out trailing comments.
+    fn trailing(comments: &[Comment]) -> Vec<Doc> {
+        comments
+            .iter()
+            .flat_map(|c| {
+                if c.is_line() {
+                    vec![Doc::LineComment(c.text.clone())]
+                } else {
+                    vec![Doc::text(" "), Doc::text(c.text.as_str())]
+                }
+            })
+            .collect()
+    }
+    /// Lay out the own-line comments before a token, taking them out of the token.
+    ///
+    /// Blank lines are kept only if `blank` is `true` (except between comments).
+    fn leading(&mut self, index: usize, blank: bool) -> Vec<Doc> {
+        let comments = std::mem::take(&mut self.tokens[index].leading);
+        let mut docs = Vec::new();
+
+        for (n, comment) in comments.iter().enumerate() {
+            if comment.blank_before && (blank || n > 0) {
+                docs.push(Doc::BlankLine);
+            }
+            docs.push(Doc::Comment(comment.text.clone()));
+        }
+        if self.tokens[index].blank_before && (blank || !comments.is_empty()) {
+            docs.push(Doc::BlankLine);
+        }
+
+        docs
+    }
+
+    /// Does the token continue a statement after a block?
+    fn is_continuation(&self, start: usize, index: usize) -> bool {
+        let text = self.text(index);
+
+        match self.tokens[index].class {
+            Class::Word if CONTINUE_KEYWORDS.contains(&text) => true,
+            Class::Word if matches!(text, "while" | "until") => {
+                let mut first = start;
+                if self.tokens[first].text.starts_with('\'') && self.is_symbol(first + 1, ":") {
+                    first += 2;
+                }
+                self.text(first) == "do" || self.is_custom_syntax(self.text(first))
+            }
+            Class::Word => self.is_custom_keyword(text) && !self.is_custom_syntax(text),
+            Class::Symbol => !matches!(text, "(" | "[" | "{" | "#{" | "!" | "-" | "+" | "|" | "||"),
+            Class::Literal | Class::Raw => false,
+        }
+    }
+    /// Split a range of tokens into statements.
+    fn statements(&self, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let mut statements = Vec::new();
+        let mut first = start;
+        let mut i = start;
+
+        while i < end {
+            if let Some(close) = self.matching[i] {
+                let is_brace = self.text(i) == "{";
+                i = close + 1;
+
+                if is_brace && i < end && !self.is_continuation(first, i) {
+                    statements.push((first, i));
+                    first = i;
+                }
+                continue;
+            }
+            if self.is_symbol(i, ";") {
+                statements.push((first, i + 1));
+                first = i + 1;
+            }
+            i += 1;
+        }
+        if first < end {
+            statements.push((first, end));
+        }
+
+        statements
+    }
+    /// Lay out the own-line comments before a closing bracket.
+    fn closing(&mut self, index: usize, blank: bool) -> Vec<Doc> {
+        if index < self.tokens.len() {
+            self.tokens[index].blank_before = false;
+            self.leading(index, blank)
+        } else {
+            Vec::new()
+        }
+    }
+    /// Lay out a sequence of statements, followed by the comments before the closing bracket.
+    fn block_body(&mut self, start: usize, end: usize) -> Vec<Doc> {
+        let statements = self.statements(start, end);
+        let mut docs = Vec::new();
+
+        for (n, &(first, last)) in statements.iter().enumerate() {
+            if n > 0 {
+                docs.push(Doc::HardLine);
+            }
+            docs.extend(self.leading(first, n > 0));
+
+            let mut keyword = first;
+            if self.tokens[keyword].text.starts_with('\'') && self.is_symbol(keyword + 1, ":") {
+                keyword += 2;
+            }
+            let force = keyword < last
+                && self.tokens[keyword].class == Class::Word
+                && STMT_KEYWORDS.contains(&self.text(keyword));
+
+            docs.push(Doc::Concat(self.sequence(first, last, force)));
+        }
+        docs.extend(self.closing(end, !statements.is_empty()));
+
+        docs
+    }
+    /// Lay out a sequence of tokens within the same brackets.
+    ///
+    /// Blocks are always broken into multiple lines if `force` is `true`.
+    fn sequence(&mut self, start: usize, end: usize, force: bool) -> Vec<Doc> {
+        let mut docs = Vec::new();
+        let mut prev = None;
+        let mut pending = None;
+        let mut ternary = false;
+        let mut i = start;
+
+        while i < end {
+            if let Some(p) = prev {
+                // Custom syntax may use `?` and `:` as a ternary operator
+                if self.needs_space(p, i) || (ternary && self.is_symbol(i, ":")) {
+                    docs.push(Doc::text(" "));
+                }
+                docs.extend(self.leading(i, false));
+            }
+
+            if self.tokens[i].class == Class::Word
+                && matches!(self.text(i), "switch" | "struct" | "enum")
+            {
+                pending = Some(i);
+            }
+            ternary |= self.is_symbol(i, "?");
+
+            match self.matching[i] {
+                Some(close) => {
+                    let kind = match self.text(i) {
+                        "(" | "[" | "?[" => Group::List(false),
+                        "#{" => Group::List(true),
+                        _ => match pending.take().map(|p| self.text(p)) {
+                            Some("switch") => Group::Switch,
+                            Some(..) => Group::List(true),
+                            None if i > start
+                                && self.tokens[i - 1].class == Class::Word
+                                && !self.is_keyword(i - 1)
+                                && self.tokens[i + 1].class == Class::Word
+                                && self.is_symbol(i + 2, ":") =>
+                            {
+                                Group::List(true)
+                            }
+                            None => Group::Block,
+                        },
+                    };
+                    docs.push(self.group(kind, i, close, force));
+                    prev = Some(close);
+                    i = close + 1;
+                }
+                None => {
+                    docs.push(self.token(i));
+                    prev = Some(i);
+                    i += 1;
+                }
+            }
+        }
+
+        docs
+    }
+    /// Lay out a bracketed group of tokens.
+    fn group(&mut self, kind: Group, open: usize, close: usize, force: bool) -> Doc {
+        let close_text = Doc::text(self.text(close));
+        let close_trailing = Self::trailing(&self.tokens[close].trailing);
+
+        if open + 1 == close
+            && self.tokens[open].trailing.is_empty()
+            && self.tokens[close].leading.is_empty()
+        {
+            let mut docs = vec![self.token(open), close_text];
+            docs.extend(close_trailing);
+            return Doc::Concat(docs);
+        }
+
+        let (line, inner) = match kind {
+            Group::Block => {
+                let statements = self.statements(open + 1, close);
+                let flat =
+                    !force && statements.len() == 1 && !self.is_symbol(statements[0].1 - 1, ";");
+                let line = if flat { Doc::Line } else { Doc::HardLine };
+                (line, self.block_body(open + 1, close))
+            }
+            Group::Switch => (Doc::HardLine, self.switch_arms(open + 1, close)),
+            Group::List(spaced) => {
+                let line = if spaced { Doc::Line } else { Doc::SoftLine };
+                (line, self.list_items(open + 1, close))
+            }
+        };
+
+        let mut nest = vec![line.clone()];
+        nest.extend(inner);
+
+        let mut docs = vec![self.token(open), Doc::Nest(nest), line, close_text];
+        docs.extend(close_trailing);
+        Doc::Group(docs)
+    }
+    /// Lay out comma-separated list items.
+    fn list_items(&mut self, start: usize, end: usize) -> Vec<Doc> {
+        let mut docs = Vec::new();
+        let mut first = start;
+        let mut i = start;
+        let mut n = 0;
+
+        while i <= end {
+            if i < end {
+                if let Some(close) = self.matching[i] {
+                    i = close + 1;
+                    continue;
+                }
+                if !self.is_symbol(i, ",") {
+                    i += 1;
+                    continue;
+                }
+            } else if first >= end {
+                break;
+            }
+
+            if n > 0 {
+                docs.push(Doc::Line);
+            }
+            docs.extend(self.leading(first, n > 0));
+            docs.extend(self.sequence(first, i, false));
+            if i < end {
+                docs.push(self.token(i));
+            }
+
+            n += 1;
+            i += 1;
+            first = i;
+        }
+        docs.extend(self.closing(end, n > 0));
+
+        docs
+    }
+    /// Lay out the arms of a `switch` expression, one per line.
+    fn switch_arms(&mut self, start: usize, end: usize) -> Vec<Doc> {
+        let mut docs = Vec::new();
+        let mut first = start;
+        let mut n = 0;
+
+        while first < end {
+            let mut i = first;
+            let mut last = end;
+
+            while i < end {
+                if let Some(close) = self.matching[i] {
+                    // A block arm needs no comma
+                    if self.text(i) == "{"
+                        && i > first
+                        && self.is_symbol(i - 1, "=>")
+                        && !self.is_symbol(close + 1, ",")
+                    {
+                        last = close + 1;
+                        break;
+                    }
+                    i = close + 1;
+                    continue;
+                }
+                if self.is_symbol(i, ",") {
+                    last = i + 1;
+                    break;
+                }
+                i += 1;
+            }
+
+            if n > 0 {
+                docs.push(Doc::HardLine);
+            }
+            docs.extend(self.leading(first, n > 0));
+            docs.extend(self.sequence(first, last, false));
+
+            n += 1;
+            first = last;
+        }
+        docs.extend(self.closing(end, n > 0));
+
+        docs
+    }
+
+    /// Lay out the entire script.
+    #[must_use]
+    pub fn layout(cst: Cst, engine: &Engine) -> Doc {
+        let len = cst.tokens.len();
+        let mut layout = Layout::new(engine, cst.tokens);
+        let mut docs = layout.block_body(0, len);
+
+        for (n, comment) in cst.comments.iter().enumerate() {
+            if comment.blank_before && (len > 0 || n > 0) {
+                docs.push(Doc::BlankLine);
+            }
+            docs.push(Doc::Comment(comment.text.clone()));
+        }
+
+        Doc::Concat(docs)
+    }
+}
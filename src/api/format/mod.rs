@@ -0,0 +1,95 @@
+//! Module that provides a source formatter for scripts.
+#![cfg(not(feature = "no_position"))]
+
+mod cst;
+mod doc;
+mod layout;
+
+use crate::parser::ParseResult;
+use crate::Engine;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Options for formatting a script via [`Engine::format_script`].
+///
+/// Not available under `no_position`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct FormatOptions {
+    /// Number of spaces for each level of indentation. Default 4.
+    pub indent_width: usize,
+    /// Maximum width of a line, which may be exceeded only if a line cannot be broken. Default 100.
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatOptions {
+    /// Create a default [`FormatOptions`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            indent_width: 4,
+            line_width: 100,
+        }
+    }
+    /// Set the number of spaces for each level of indentation.
+    #[inline(always)]
+    #[must_use]
+    pub const fn indent_width(mut self, value: usize) -> Self {
+        self.indent_width = value;
+        self
+    }
+    /// Set the maximum width of a line.
+    #[inline(always)]
+    #[must_use]
+    pub const fn line_width(mut self, value: usize) -> Self {
+        self.line_width = value;
+        self
+    }
+}
+
+impl Engine {
+    /// Format a script into a canonical layout.
+    ///
+    /// Not available under `no_position`.
+    ///
+    /// The script must be valid. All comments and blank lines between statements are kept.
+    /// Custom operators and custom syntax registered with this [`Engine`] are recognized.
+    ///
+    /// Formatting is idempotent: formatting an already-formatted script returns it unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, FormatOptions};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let script = engine.format_script(
+    ///     "let x=[1,2,3];   // an array\nif x.len()>2{print(x)}",
+    ///     FormatOptions::new().indent_width(2),
+    /// )?;
+    ///
+    /// assert_eq!(script, "let x = [1, 2, 3]; // an array\nif x.len() > 2 {\n  print(x)\n}\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_script(
+        &self,
+        script: impl AsRef<str>,
+        options: FormatOptions,
+    ) -> ParseResult<String> {
+        let cst = cst::Cst::parse(self, script.as_ref())?;
+        let doc = layout::Layout::layout(cst, self);
+
+        Ok(doc::print(&doc, options.indent_width, options.line_width))
+    }
+}
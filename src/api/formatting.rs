@@ -2,7 +2,7 @@
 use crate::packages::iter_basic::{BitRange, CharsStream, StepRange};
 use crate::parser::{ParseResult, ParseState};
 use crate::{
-    Engine, ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, Position, RhaiError,
+    Dynamic, Engine, ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, Position, RhaiError,
     SmartString, ERR,
 };
 use std::any::type_name;
@@ -39,6 +39,10 @@ pub fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     if name == type_name::<rust_decimal::Decimal>() {
         return if shorthands { "decimal" } else { "Decimal" };
     }
+    #[cfg(feature = "bigint")]
+    if name == type_name::<num_bigint::BigInt>() {
+        return if shorthands { "bigint" } else { "BigInt" };
+    }
     if name == type_name::<FnPtr>() || name == "FnPtr" {
         return if shorthands { "Fn" } else { "FnPtr" };
     }
@@ -54,6 +58,24 @@ pub fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     if name == type_name::<crate::Map>() || name == "Map" {
         return if shorthands { "map" } else { "Map" };
     }
+    if name == type_name::<crate::Tuple>() || name == "Tuple" {
+        return if shorthands { "tuple" } else { "Tuple" };
+    }
+    if name == type_name::<crate::EnumValue>() || name == "EnumValue" {
+        return if shorthands { "enum" } else { "EnumValue" };
+    }
+    #[cfg(not(feature = "no_object"))]
+    if name == type_name::<crate::StructValue>() || name == "StructValue" {
+        return if shorthands { "struct" } else { "StructValue" };
+    }
+    #[cfg(not(feature = "no_function"))]
+    if name == type_name::<crate::Generator>() || name == "Generator" {
+        return if shorthands { "generator" } else { "Generator" };
+    }
+    #[cfg(feature = "regex")]
+    if name == type_name::<crate::Regex>() || name == "Regex" {
+        return if shorthands { "regex" } else { "Regex" };
+    }
     #[cfg(not(feature = "no_time"))]
     if name == type_name::<crate::Instant>() || name == "Instant" {
         return if shorthands { "timestamp" } else { "Instant" };
@@ -219,6 +241,24 @@ impl Engine {
             .unwrap_or_else(|| map_std_type_name(name, true))
     }
 
+    /// Pretty-print the type name of a value, as returned by `type_of`.
+    ///
+    /// This is [`map_type_name`][Engine::map_type_name] on the value's type, except that a value
+    /// of a script-defined `struct` or `enum` type is named by its type.
+    #[inline]
+    #[must_use]
+    pub(crate) fn map_value_type_name<'a>(&'a self, value: &Dynamic) -> std::borrow::Cow<'a, str> {
+        #[cfg(not(feature = "no_object"))]
+        if let Ok(v) = value.as_struct_ref() {
+            return v.struct_name().to_string().into();
+        }
+        if let Ok(v) = value.as_enum_ref() {
+            return v.enum_name().to_string().into();
+        }
+
+        self.map_type_name(value.type_name()).into()
+    }
+
     /// Format a Rust parameter type.
     ///
     /// If a type is registered via [`register_type_with_name`][Engine::register_type_with_name],
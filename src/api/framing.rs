@@ -0,0 +1,61 @@
+//! Module implementing the `Content-Length` framing of messages shared by the
+//! [Language Server](https://microsoft.github.io/language-server-protocol/) and
+//! [Debug Adapter](https://microsoft.github.io/debug-adapter-protocol/) protocols.
+#![cfg(any(feature = "lsp", feature = "dap"))]
+
+use std::io::{self, BufRead, Write};
+
+/// Read a message, framed by a `Content-Length` header, from a stream.
+///
+/// Returns [`None`] at the end of the stream.
+///
+/// # Errors
+///
+/// Returns an error if the stream cannot be read, or if the message is not framed properly or is
+/// not valid UTF-8.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                let n = value.trim().parse::<usize>().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid Content-Length header")
+                })?;
+                length = Some(n);
+            }
+        }
+    }
+
+    let mut buf = vec![0; length.unwrap()];
+    input.read_exact(&mut buf)?;
+
+    String::from_utf8(buf)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Write a message, framed by a `Content-Length` header, into a stream.
+///
+/// # Errors
+///
+/// Returns an error if the stream cannot be written to.
+pub fn write_message(output: &mut impl Write, message: &str) -> io::Result<()> {
+    write!(output, "Content-Length: {}\r\n\r\n{message}", message.len())
+}
@@ -184,6 +184,17 @@ fn format_dynamic_as_json(result: &mut String, value: &Dynamic) {
         }
         Union::FnPtr(ref f, _, _) => write!(result, "{:?}", f.fn_name()).unwrap(),
         Union::Map(ref m, ..) => *result += &format_map_as_json(m),
+        Union::Struct(ref st, _, _) => {
+            *result += "{";
+            for (i, (name, value)) in st.iter().enumerate() {
+                if i > 0 {
+                    *result += ",";
+                }
+                write!(result, "{name:?}:").unwrap();
+                format_dynamic_as_json(result, value);
+            }
+            *result += "}";
+        }
         #[cfg(not(feature = "no_index"))]
         Union::Array(ref a, _, _) => {
             *result += "[";
@@ -206,6 +217,20 @@ fn format_dynamic_as_json(result: &mut String, value: &Dynamic) {
             }
             *result += "]";
         }
+        #[cfg(feature = "bigint")]
+        Union::BigInt(ref n, _, _) => write!(result, "{n}").unwrap(),
+        Union::Tuple(ref t, _, _) => format_items_as_json(result, t),
+        Union::Enum(ref e, _, _) if e.fields().is_empty() => {
+            write!(result, "{:?}", e.variant()).unwrap();
+        }
+        Union::Enum(ref e, _, _) => {
+            write!(result, "{{{:?}:", e.variant()).unwrap();
+            match e.fields().as_ref() {
+                [value] => format_dynamic_as_json(result, value),
+                fields => format_items_as_json(result, fields),
+            }
+            *result += "}";
+        }
         #[cfg(not(feature = "no_closure"))]
         Union::Shared(ref v, _, _) => {
             let value = &*crate::func::locked_read(v).unwrap();
@@ -214,3 +239,15 @@ fn format_dynamic_as_json(result: &mut String, value: &Dynamic) {
         _ => write!(result, "{value:?}").unwrap(),
     }
 }
+
+/// Format a list of [`Dynamic`] values as a JSON array.
+fn format_items_as_json(result: &mut String, items: &[Dynamic]) {
+    *result += "[";
+    for (i, x) in items.iter().enumerate() {
+        if i > 0 {
+            *result += ",";
+        }
+        format_dynamic_as_json(result, x);
+    }
+    *result += "]";
+}
@@ -6,6 +6,8 @@ pub mod run;
 
 pub mod compile;
 
+pub mod diagnostics;
+
 pub mod json;
 
 pub mod files;
@@ -20,10 +22,20 @@ pub mod optimize;
 
 pub mod limits;
 
+pub mod coverage;
+
+pub mod profiler;
+
+pub mod overflow;
+
 pub mod events;
 
 pub mod formatting;
 
+pub mod format;
+
+pub mod framing;
+
 pub mod custom_syntax;
 
 pub mod build_type;
@@ -163,6 +175,61 @@ impl Engine {
         self.disabled_symbols.contains(symbol)
     }
 
+    /// Mark a particular keyword or operator as deprecated.
+    ///
+    /// The symbol can still be used, but each use is reported by
+    /// [`compile_with_diagnostics`][Engine::compile_with_diagnostics] together with the `note`,
+    /// which usually describes the replacement.
+    ///
+    /// Symbols are matched by token, so a deprecated keyword is also reported when it is used as
+    /// a variable, function or property name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), rhai::ParseError> {
+    /// use rhai::{Engine, DiagnosticType};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.deprecate_symbol("while", "use 'loop' with 'break' instead");
+    ///
+    /// let (_, diagnostics) = engine.compile_with_diagnostics("while false {}")?;
+    ///
+    /// assert!(diagnostics.iter().any(|d| d.diagnostic_type()
+    ///     == &DiagnosticType::Deprecated("while".into(), "use 'loop' with 'break' instead".into())));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn deprecate_symbol(
+        &mut self,
+        symbol: impl Into<Identifier>,
+        note: impl Into<String>,
+    ) -> &mut Self {
+        self.deprecated_symbols.insert(symbol.into(), note.into());
+        self
+    }
+
+    /// Is a particular keyword or operator deprecated?
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.deprecate_symbol("while", "");
+    ///
+    /// assert!(engine.is_symbol_deprecated("while"));
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_symbol_deprecated(&self, symbol: &str) -> bool {
+        self.deprecated_symbols.contains_key(symbol)
+    }
+
     /// Register a custom operator with a precedence into the language.
     ///
     /// Not available under `no_custom_syntax`.
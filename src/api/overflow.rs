@@ -0,0 +1,51 @@
+//! Settings for [`Engine`]'s integer overflow behavior.
+#![cfg(not(feature = "unchecked"))]
+
+use crate::Engine;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// How integer arithmetic behaves when the result does not fit in the integer type.
+///
+/// This applies to the `+`, `-`, `*`, `/`, `%` and `**` operators (and their assignment forms),
+/// as well as unary `-` and `abs`, for all integer types.
+///
+/// Division by zero, and raising an integer to a negative power, are always errors.
+///
+/// Not available under `unchecked`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum Overflow {
+    /// Raise an arithmetic error on overflow.
+    #[default]
+    Checked,
+    /// Wrap around at the boundary of the integer type (two's complement).
+    Wrapping,
+    /// Clamp the result at the numeric bounds of the integer type.
+    Saturating,
+}
+
+impl Engine {
+    /// Set how integer arithmetic handles overflow.
+    /// Default is [`Overflow::Checked`].
+    ///
+    /// Constants folded by the optimizer are computed with the mode in effect when the script is
+    /// compiled, so set this before compiling.
+    ///
+    /// Not available under `unchecked`.
+    #[inline(always)]
+    pub fn set_integer_overflow(&mut self, overflow: Overflow) -> &mut Self {
+        self.integer_overflow = overflow;
+        self
+    }
+    /// How integer arithmetic handles overflow.
+    /// Default is [`Overflow::Checked`].
+    ///
+    /// Not available under `unchecked`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn integer_overflow(&self) -> Overflow {
+        self.integer_overflow
+    }
+}
@@ -0,0 +1,75 @@
+//! Module that defines the profiling API of [`Engine`].
+#![cfg(feature = "profiling")]
+
+use crate::eval::Profile;
+use crate::func::{locked_read, locked_write, Locked};
+use crate::Engine;
+use std::mem;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+impl Engine {
+    /// Enable/disable collecting a call-level [profile][Profile] of the scripts run by this
+    /// [`Engine`], including calls via [`Engine::call_fn`], function pointers and imported modules.
+    ///
+    /// The profile accumulates over all runs until it is [taken][Engine::take_profile].
+    /// Disabling profiling discards whatever has been collected.
+    ///
+    /// Scripts run by the `grain` bytecode VM are not profiled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_profiling_enabled(true);
+    ///
+    /// engine.run("fn foo(x) { x.len() } foo([1, 2, 3]);")?;
+    ///
+    /// let profile = engine.take_profile().unwrap();
+    ///
+    /// assert_eq!(profile.get(&["<script>", "foo", "len"]).unwrap().calls, 1);
+    ///
+    /// // Write `profile.to_folded()` to a file to make a flamegraph
+    /// println!("{}", profile.to_table());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_profiling_enabled(&mut self, enable: bool) -> &mut Self {
+        if !enable {
+            self.profiler = None;
+        } else if self.profiler.is_none() {
+            self.profiler = Some(Locked::new(Profile::new()));
+        }
+        self
+    }
+    /// Is a call-level [profile][Profile] being collected?
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_profiling_enabled(&self) -> bool {
+        self.profiler.is_some()
+    }
+    /// Get a copy of the [profile][Profile] collected so far.
+    ///
+    /// Returns [`None`] if profiling is not enabled.
+    #[inline]
+    #[must_use]
+    pub fn profile(&self) -> Option<Profile> {
+        self.profiler
+            .as_ref()
+            .and_then(|profile| locked_read(profile).map(|p| p.clone()))
+    }
+    /// Take the [profile][Profile] collected so far, and start collecting afresh.
+    ///
+    /// Returns [`None`] if profiling is not enabled.
+    #[inline]
+    pub fn take_profile(&self) -> Option<Profile> {
+        self.profiler
+            .as_ref()
+            .and_then(|profile| locked_write(profile).map(|mut p| mem::take(&mut *p)))
+    }
+}
@@ -124,13 +124,24 @@ impl Engine {
         let global = &mut self.new_global_runtime_state();
         global.source = ast.source_raw().cloned();
 
+        #[cfg(feature = "coverage")]
+        self.cov_ast(ast);
+
         #[cfg(not(feature = "no_function"))]
         global.lib.push(ast.shared_lib().clone());
 
         #[cfg(not(feature = "no_module"))]
         global.embedded_module_resolver.clone_from(&ast.resolver);
 
-        let _ = self.eval_global_statements(global, caches, scope, ast.statements(), true)?;
+        #[cfg(feature = "profiling")]
+        let entry = self.prof_enter_script(global);
+
+        let r = self.eval_global_statements(global, caches, scope, ast.statements(), true);
+
+        #[cfg(feature = "profiling")]
+        self.prof_exit(global, entry);
+
+        let _ = r?;
 
         #[cfg(feature = "debugging")]
         if self.is_debugger_registered() {
@@ -17,7 +17,6 @@ use std::{
 /// # Thread Safety
 ///
 /// Currently, [`AST`] is neither `Send` nor `Sync`. Turn on the `sync` feature to make it `Send + Sync`.
-#[derive(Clone)]
 pub struct AST {
     /// Source of the [`AST`].
     source: Option<ImmutableString>,
@@ -34,6 +33,30 @@ pub struct AST {
     pub(crate) doc: crate::SmartString,
 }
 
+impl Clone for AST {
+    fn clone(&self) -> Self {
+        #[cfg(not(feature = "no_function"))]
+        let mut lib = self.lib.clone();
+
+        // Each clone keeps its own `static` variables, starting from the current values
+        #[cfg(not(feature = "no_function"))]
+        if lib.iter_script_fn().any(|(.., f)| f.statics.is_some()) {
+            crate::func::shared_make_mut(&mut lib).separate_static_variables();
+        }
+
+        Self {
+            source: self.source.clone(),
+            body: self.body.clone(),
+            #[cfg(not(feature = "no_function"))]
+            lib,
+            #[cfg(not(feature = "no_module"))]
+            resolver: self.resolver.clone(),
+            #[cfg(feature = "metadata")]
+            doc: self.doc.clone(),
+        }
+    }
+}
+
 impl Default for AST {
     #[inline(always)]
     fn default() -> Self {
@@ -212,6 +235,23 @@ impl AST {
     const fn shared_lib(&self) -> &crate::SharedModule {
         &self.lib
     }
+    /// Record script-defined `struct` types in the functions library of the [`AST`].
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    #[inline]
+    pub(crate) fn add_struct_types(
+        &mut self,
+        types: impl IntoIterator<Item = crate::types::CustomTypeInfo>,
+    ) {
+        let mut types = types.into_iter().peekable();
+
+        if types.peek().is_some() {
+            let lib = crate::func::shared_make_mut(&mut self.lib);
+            types.for_each(|info| {
+                lib.set_custom_type_info(info);
+            });
+        }
+    }
     /// _(internals)_ Get the embedded [module resolver][crate::ModuleResolver].
     /// Exported under the `internals` feature only.
     ///
@@ -675,6 +715,84 @@ impl AST {
             .iter_script_fn()
             .map(|(.., fn_def)| fn_def.as_ref().into())
     }
+    /// Iterate through the current values of all `static` variables in script-defined functions,
+    /// as `(function name, variable name, value)`.
+    ///
+    /// A `static` variable only appears here after the statement defining it has run at least once.
+    ///
+    /// Each clone of an [`AST`] has its own `static` variables.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// use rhai::{Engine, Scope, INT};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let mut ast = engine.compile(r#"
+    ///     fn hit() {
+    ///         static count = 0;
+    ///         count += 1;
+    ///         count
+    ///     }
+    /// "#)?;
+    ///
+    /// let mut scope = Scope::new();
+    ///
+    /// engine.call_fn::<INT>(&mut scope, &ast, "hit", ())?;
+    /// assert_eq!(engine.call_fn::<INT>(&mut scope, &ast, "hit", ())?, 2);
+    ///
+    /// for (fn_name, name, value) in ast.iter_static_variables() {
+    ///     assert_eq!(fn_name, "hit");
+    ///     assert_eq!(name, "count");
+    ///     assert_eq!(value.as_int().unwrap(), 2);
+    /// }
+    ///
+    /// // Start counting afresh
+    /// ast.reset_static_variables();
+    ///
+    /// assert_eq!(engine.call_fn::<INT>(&mut scope, &ast, "hit", ())?, 1);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_function"))]
+    pub fn iter_static_variables(
+        &self,
+    ) -> impl Iterator<Item = (&str, ImmutableString, Dynamic)> + '_ {
+        self.lib
+            .iter_script_fn()
+            .filter_map(|(.., fn_def)| fn_def.statics.as_ref().map(|s| (fn_def, s)))
+            .flat_map(|(fn_def, statics)| {
+                crate::func::locked_read(statics)
+                    .unwrap()
+                    .iter()
+                    .map(|(name, value)| {
+                        (fn_def.name.as_str(), name.clone(), value.flatten_clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+    }
+    /// Clear all `static` variables in script-defined functions, so that each is initialized
+    /// afresh the next time the statement defining it runs.
+    ///
+    /// Clones of this [`AST`] are not affected.
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    pub fn reset_static_variables(&mut self) -> &mut Self {
+        self.lib
+            .iter_script_fn()
+            .filter_map(|(.., fn_def)| fn_def.statics.as_ref())
+            .for_each(|statics| crate::func::locked_write(statics).unwrap().clear());
+        self
+    }
     /// Clear all function definitions in the [`AST`].
     ///
     /// Not available under `no_function`.
@@ -793,6 +911,34 @@ impl AST {
             }
         }
 
+        true
+    }
+    /// _(internals)_ Recursively walk the [`AST`], including function bodies (if any), with a
+    /// [`VisitorMut`] that may rewrite nodes in place.
+    /// Return `false` from the visitor to terminate the walk.
+    /// Exported under the `internals` feature only.
+    ///
+    /// This can be used either before or after [`Engine::optimize_ast`][crate::Engine::optimize_ast].
+    #[cfg(feature = "internals")]
+    pub fn walk_mut(&mut self, visitor: &mut (impl VisitorMut + ?Sized)) -> bool {
+        for stmt in &mut self.body {
+            if !stmt.walk_mut(visitor) {
+                return false;
+            }
+        }
+        #[cfg(not(feature = "no_function"))]
+        if self.has_functions() {
+            let lib = crate::func::shared_make_mut(&mut self.lib);
+
+            for fn_def in lib.iter_script_fn_def_mut() {
+                for stmt in fn_def.body.statements_mut() {
+                    if !stmt.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+        }
+
         true
     }
 }
@@ -921,6 +1067,69 @@ impl ASTNode<'_> {
     }
 }
 
+/// _(internals)_ A visitor that rewrites the nodes of an [`AST`] in place.
+/// Exported under the `internals` feature only.
+///
+/// Each node is visited _after_ all its child nodes, so a node can be replaced (e.g. wrapped inside
+/// a new function call) without the replacement being visited again.
+///
+/// A function call statement ([`Stmt::FnCall`]) is first visited as an [`Expr::FnCall`], so
+/// function calls need only be handled in [`visit_expr`][VisitorMut::visit_expr].
+///
+/// Pre-calculated hashes are _not_ updated automatically. Use [`FnCallExpr::new`][super::FnCallExpr::new]
+/// to create new function calls, or [`FnCallExpr::recalc_hashes`][super::FnCallExpr::recalc_hashes]
+/// after changing the name or number of arguments of an existing one.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, Expr, FnCallExpr, VisitorMut, INT};
+///
+/// // Wrap every call to `get` into a call to `audit`
+/// struct Audit;
+///
+/// impl VisitorMut for Audit {
+///     fn visit_expr(&mut self, expr: &mut Expr) -> bool {
+///         if matches!(expr, Expr::FnCall(x, ..) if x.name == "get") {
+///             let pos = expr.position();
+///             *expr = FnCallExpr::new("audit", [expr.take()]).into_fn_call_expr(pos);
+///         }
+///         true
+///     }
+/// }
+///
+/// let mut engine = Engine::new();
+///
+/// engine.register_fn("get", |x: INT| x)
+///       .register_fn("audit", |x: INT| x + 1);
+///
+/// let mut ast = engine.compile("get(41)")?;
+///
+/// ast.walk_mut(&mut Audit);
+///
+/// assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "internals")]
+pub trait VisitorMut {
+    /// Visit a [statement][Stmt], after all its child nodes have been visited.
+    /// Return `false` to terminate the walk.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn visit_stmt(&mut self, stmt: &mut Stmt) -> bool {
+        true
+    }
+    /// Visit an [expression][Expr], after all its child nodes have been visited.
+    /// Return `false` to terminate the walk.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn visit_expr(&mut self, expr: &mut Expr) -> bool {
+        true
+    }
+}
+
 /// _(internals)_ Encapsulated AST environment.
 /// Exported under the `internals` feature only.
 ///
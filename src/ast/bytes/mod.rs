@@ -0,0 +1,399 @@
+//! Module defining the binary form of a compiled [`AST`].
+//!
+//! # Shape
+//!
+//! ```text
+//! "RAST"          magic
+//! u16             format version
+//! u8              INT width in bytes
+//! u8              FLOAT width in bytes, 0 under `no_float`
+//! varint          feature bitmask, see `FEATURES`
+//! u8              optimization level
+//! ast             source, doc-comments, statements, functions library
+//! ```
+//!
+//! Integers are LEB128, signed values zigzagged. Hashes are fixed-width because they are
+//! uniformly spread and would not get any shorter.
+//!
+//! The hasher is seeded per process by default, so function call, variable and property hashes
+//! are not written at all but recalculated from their names on load.
+//!
+//! The only exception are the case values of a `switch`: the parser keeps nothing but their
+//! hashes, so these are written as computed, prefixed by a hasher probe that makes sure they
+//! still mean the same thing to the engine loading them.
+
+use super::AST;
+use crate::{Engine, Position};
+#[cfg(feature = "no_std")]
+use core_error::Error;
+#[cfg(not(feature = "no_std"))]
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+mod read;
+mod write;
+
+/// Identifies the format.
+const MAGIC: [u8; 4] = *b"RAST";
+
+/// Bumped whenever an encoding changes in a way that an older reader would misread.
+const VERSION: u16 = 2;
+
+/// How deeply statements and expressions may nest.
+///
+/// Reading recurses, so a corrupted input must not be able to overflow the stack.
+/// This is well above what the parser's default limits allow.
+const MAX_DEPTH: usize = 512;
+
+/// Features that change the shape of the [`AST`] or the values it holds.
+///
+/// Order is the wire order -- append only.
+const FEATURES: &[(&str, bool)] = &[
+    ("no_index", cfg!(feature = "no_index")),
+    ("no_object", cfg!(feature = "no_object")),
+    ("no_time", cfg!(feature = "no_time")),
+    ("no_float", cfg!(feature = "no_float")),
+    ("no_function", cfg!(feature = "no_function")),
+    ("no_closure", cfg!(feature = "no_closure")),
+    ("no_module", cfg!(feature = "no_module")),
+    ("no_custom_syntax", cfg!(feature = "no_custom_syntax")),
+    ("no_position", cfg!(feature = "no_position")),
+    ("no_optimize", cfg!(feature = "no_optimize")),
+    ("decimal", cfg!(feature = "decimal")),
+    ("bigint", cfg!(feature = "bigint")),
+];
+
+/// Statement tags. Append only.
+#[allow(dead_code)]
+mod stmt_tag {
+    pub const NOOP: u8 = 0x00;
+    pub const IF: u8 = 0x01;
+    pub const SWITCH: u8 = 0x02;
+    pub const WHILE: u8 = 0x03;
+    pub const DO: u8 = 0x04;
+    pub const FOR: u8 = 0x05;
+    pub const VAR: u8 = 0x06;
+    pub const ASSIGNMENT: u8 = 0x07;
+    pub const FN_CALL: u8 = 0x08;
+    pub const BLOCK: u8 = 0x09;
+    pub const TRY_CATCH: u8 = 0x0a;
+    pub const EXPR: u8 = 0x0b;
+    pub const BREAK_LOOP: u8 = 0x0c;
+    pub const RETURN: u8 = 0x0d;
+    pub const YIELD: u8 = 0x0e;
+    pub const IMPORT: u8 = 0x0f;
+    pub const EXPORT: u8 = 0x10;
+    pub const MODULE: u8 = 0x11;
+    pub const SHARE: u8 = 0x12;
+}
+
+/// Expression tags. Append only.
+#[allow(dead_code)]
+mod expr_tag {
+    pub const DYNAMIC_CONSTANT: u8 = 0x00;
+    pub const BOOL: u8 = 0x01;
+    pub const INT: u8 = 0x02;
+    pub const FLOAT: u8 = 0x03;
+    pub const CHAR: u8 = 0x04;
+    pub const STRING: u8 = 0x05;
+    pub const INTERPOLATED_STRING: u8 = 0x06;
+    pub const ARRAY: u8 = 0x07;
+    pub const MAP: u8 = 0x08;
+    pub const STRUCT: u8 = 0x09;
+    pub const TUPLE: u8 = 0x0a;
+    pub const ENUM: u8 = 0x0b;
+    pub const COMPREHENSION: u8 = 0x0c;
+    pub const SPREAD: u8 = 0x0d;
+    pub const UNIT: u8 = 0x0e;
+    pub const VARIABLE: u8 = 0x0f;
+    pub const THIS_PTR: u8 = 0x10;
+    pub const PROPERTY: u8 = 0x11;
+    pub const METHOD_CALL: u8 = 0x12;
+    pub const STMT: u8 = 0x13;
+    pub const FN_CALL: u8 = 0x14;
+    pub const DOT: u8 = 0x15;
+    pub const INDEX: u8 = 0x16;
+    pub const AND: u8 = 0x17;
+    pub const OR: u8 = 0x18;
+    pub const COALESCE: u8 = 0x19;
+    pub const CUSTOM: u8 = 0x1a;
+}
+
+/// Constant value tags. Append only.
+///
+/// Defined on every build, even where a feature means that a tag can never be written,
+/// so that the numbering does not shift with features.
+#[allow(dead_code)]
+mod value_tag {
+    pub const UNIT: u8 = 0x00;
+    pub const FALSE: u8 = 0x01;
+    pub const TRUE: u8 = 0x02;
+    pub const INT: u8 = 0x03;
+    pub const FLOAT: u8 = 0x04;
+    pub const DECIMAL: u8 = 0x05;
+    pub const BIGINT: u8 = 0x06;
+    pub const CHAR: u8 = 0x07;
+    pub const STRING: u8 = 0x08;
+    pub const ARRAY: u8 = 0x09;
+    pub const BLOB: u8 = 0x0a;
+    pub const MAP: u8 = 0x0b;
+    pub const FN_PTR: u8 = 0x0c;
+    pub const TUPLE: u8 = 0x0d;
+    pub const ENUM: u8 = 0x0e;
+    pub const STRUCT: u8 = 0x0f;
+    pub const RANGE: u8 = 0x10;
+    pub const RANGE_INCLUSIVE: u8 = 0x11;
+}
+
+/// `switch` pattern tags. Append only.
+mod pattern_tag {
+    pub const WILDCARD: u8 = 0x00;
+    pub const BIND: u8 = 0x01;
+    pub const VALUE: u8 = 0x02;
+    pub const TUPLE: u8 = 0x03;
+    pub const ENUM: u8 = 0x04;
+}
+
+/// Operator token tags.
+///
+/// Unary plus/minus share their syntax with the binary operators and so need tags of their own.
+mod token_tag {
+    pub const NONE: u8 = 0x00;
+    pub const SYNTAX: u8 = 0x01;
+    pub const UNARY_PLUS: u8 = 0x02;
+    pub const UNARY_MINUS: u8 = 0x03;
+}
+
+/// Error when writing an [`AST`] into, or reading it back from, its binary form.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[non_exhaustive]
+#[must_use]
+pub enum ASTFormatError {
+    /// The input is not a serialized [`AST`].
+    BadMagic,
+    /// The input was written in a format version that this build does not read.
+    /// Wrapped value is the version found.
+    UnsupportedVersion(u16),
+    /// The input was written by an [`Engine`] with a different configuration.
+    /// Wrapped value describes the difference.
+    ConfigMismatch(String),
+    /// The input is truncated or malformed.
+    /// Wrapped value is the byte offset where the problem was found.
+    Corrupted(usize),
+    /// Statements or expressions nest too deeply.
+    TooDeep,
+    /// A constant value has no binary form (e.g. a custom type or a timestamp).
+    /// Wrapped values are the type name and the position of the constant.
+    UnsupportedValue(String, Position),
+    /// The [`AST`] holds something that has no binary form.
+    /// Wrapped value describes it.
+    Unsupported(String),
+    /// The [`AST`] uses a custom syntax that is not registered with the [`Engine`].
+    /// Wrapped value is the custom syntax key.
+    UnknownCustomSyntax(String),
+}
+
+impl Error for ASTFormatError {}
+
+impl fmt::Display for ASTFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => f.write_str("Not a serialized AST"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "Serialized AST is format version {v}, but version {VERSION} is expected"
+            ),
+            Self::ConfigMismatch(s) => write!(f, "Serialized AST was written {s}"),
+            Self::Corrupted(offset) => write!(f, "Serialized AST is corrupted at byte {offset}"),
+            Self::TooDeep => write!(f, "AST nests deeper than {MAX_DEPTH} levels"),
+            Self::UnsupportedValue(typ, pos) if pos.is_none() => {
+                write!(f, "Constant of type '{typ}' cannot be serialized")
+            }
+            Self::UnsupportedValue(typ, pos) => {
+                write!(f, "Constant of type '{typ}' cannot be serialized ({pos})")
+            }
+            Self::Unsupported(s) => write!(f, "AST cannot be serialized: {s}"),
+            Self::UnknownCustomSyntax(s) => write!(f, "Custom syntax '{s}' is not registered"),
+        }
+    }
+}
+
+/// The feature bitmask of this build.
+fn features() -> u64 {
+    FEATURES
+        .iter()
+        .enumerate()
+        .filter(|(.., (.., on))| *on)
+        .fold(0, |mask, (bit, ..)| mask | (1 << bit))
+}
+
+/// Width of `FLOAT`, or zero under `no_float`.
+const fn float_width() -> u8 {
+    #[cfg(not(feature = "no_float"))]
+    return std::mem::size_of::<crate::FLOAT>() as u8;
+    #[cfg(feature = "no_float")]
+    return 0;
+}
+
+/// A hash that depends only on the hasher's seed.
+///
+/// Written in front of the case hashes of a `switch` statement.
+fn hasher_probe() -> u64 {
+    crate::calc_fn_hash(None, "rhai", 0)
+}
+
+/// The optimization level of an [`Engine`], as written.
+#[allow(unused_variables)]
+fn optimization_level(engine: &Engine) -> u8 {
+    #[cfg(not(feature = "no_optimize"))]
+    return match engine.optimization_level() {
+        crate::OptimizationLevel::None => 0,
+        crate::OptimizationLevel::Simple => 1,
+        crate::OptimizationLevel::Full => 2,
+    };
+    #[cfg(feature = "no_optimize")]
+    return 0;
+}
+
+fn put_uvarint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn put_ivarint(out: &mut Vec<u8>, value: i64) {
+    put_uvarint(out, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+fn put_str(out: &mut Vec<u8>, value: &str) {
+    put_uvarint(out, value.len() as u64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+impl AST {
+    /// Serialize this [`AST`] into a binary form that can be loaded back with
+    /// [`AST::from_bytes`], skipping parsing and optimization altogether.
+    ///
+    /// Statements, the functions library, inline modules, doc-comments and positions are
+    /// all kept.
+    ///
+    /// The [`Engine`] should be the one that compiled the [`AST`]; its optimization level is
+    /// recorded together with the format version and the features of this build, and an
+    /// [`Engine`] that differs in any of them refuses to load the result.
+    ///
+    /// The result can be loaded in another process, except when the [`AST`] contains a `switch`
+    /// statement with value cases: only the hashes of those values are kept, so both processes
+    /// must use the same hashing seed, either via the `RHAI_HASHING_SEED` environment variable
+    /// at build time or [`set_hashing_seed`][crate::config::hashing::set_hashing_seed].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the [`AST`] holds anything that only has meaning in this process, such as a
+    /// constant of a custom type, a native function or an embedded module resolver.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use rhai::{Engine, AST, INT};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("let x = 40; x + 2")?;
+    ///
+    /// let bytes = ast.to_bytes(&engine)?;
+    ///
+    /// let ast = AST::from_bytes(&engine, &bytes)?;
+    ///
+    /// assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bytes(&self, engine: &Engine) -> Result<Vec<u8>, ASTFormatError> {
+        let mut out = Vec::new();
+
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.push(std::mem::size_of::<crate::INT>() as u8);
+        out.push(float_width());
+        put_uvarint(&mut out, features());
+        out.push(optimization_level(engine));
+
+        let mut writer = write::Writer::new(out);
+        writer.put_ast(self)?;
+        Ok(writer.finish())
+    }
+
+    /// Load an [`AST`] from the binary form written by [`AST::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input is not a serialized [`AST`], is corrupted, or was written by a
+    /// different format version, a build with different features, or an [`Engine`] with a
+    /// different optimization level.
+    ///
+    /// Also fails if the [`AST`] has `switch` value cases hashed with a different hashing seed,
+    /// or uses custom syntax that is not registered with the [`Engine`].
+    pub fn from_bytes(engine: &Engine, bytes: &[u8]) -> Result<Self, ASTFormatError> {
+        let mut reader = read::Reader::new(engine, bytes);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(ASTFormatError::BadMagic);
+        }
+
+        let version = u16::from_le_bytes([reader.byte()?, reader.byte()?]);
+        if version != VERSION {
+            return Err(ASTFormatError::UnsupportedVersion(version));
+        }
+
+        let int_width = reader.byte()?;
+        if usize::from(int_width) != std::mem::size_of::<crate::INT>() {
+            return Err(ASTFormatError::ConfigMismatch(format!(
+                "with {}-bit integers",
+                u32::from(int_width) * 8
+            )));
+        }
+        let width = reader.byte()?;
+        if width != float_width() && width != 0 && float_width() != 0 {
+            return Err(ASTFormatError::ConfigMismatch(format!(
+                "with {}-bit floating-point numbers",
+                u32::from(width) * 8
+            )));
+        }
+
+        let mask = reader.uvarint()?;
+        let differing = mask ^ features();
+        if differing != 0 {
+            let bit = differing.trailing_zeros() as usize;
+            let on = mask & (1 << bit) != 0;
+            return Err(ASTFormatError::ConfigMismatch(match FEATURES.get(bit) {
+                Some((name, ..)) if on => format!("with feature '{name}'"),
+                Some((name, ..)) => format!("without feature '{name}'"),
+                None => "with an unknown feature".to_string(),
+            }));
+        }
+
+        let level = reader.byte()?;
+        if level != optimization_level(engine) {
+            return Err(ASTFormatError::ConfigMismatch(match level {
+                0 => "with optimization level None".to_string(),
+                1 => "with optimization level Simple".to_string(),
+                2 => "with optimization level Full".to_string(),
+                _ => return Err(ASTFormatError::Corrupted(reader.offset() - 1)),
+            }));
+        }
+
+        let ast = reader.get_ast()?;
+        reader.finish()?;
+        Ok(ast)
+    }
+}
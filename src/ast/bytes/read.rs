@@ -0,0 +1,894 @@
+//! Reading an [`AST`] back from its binary form.
+
+use super::{expr_tag, pattern_tag, stmt_tag, token_tag, value_tag, ASTFormatError, MAX_DEPTH};
+use crate::ast::{
+    ASTFlags, BinaryExpr, CatchClause, Expr, FlowControl, FnCallExpr, FnCallHashes, Ident,
+    OpAssignment, RangeCase, Stmt, StmtBlock, SwitchCasesCollection, SwitchPattern, TryCatchBlock,
+    AST,
+};
+use crate::tokenizer::Token;
+use crate::types::dynamic::AccessMode;
+use crate::types::{Span, StringsInterner};
+use crate::{
+    calc_fn_hash, Dynamic, Engine, EnumValue, FnArgsVec, FnPtr, ImmutableString, Position,
+    StaticVec, ThinVec, Tuple, INT,
+};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    convert::TryFrom,
+    num::{NonZeroU8, NonZeroUsize},
+};
+
+type ReadResult<T> = Result<T, ASTFormatError>;
+
+/// Decodes an [`AST`] from a byte slice.
+pub(super) struct Reader<'a> {
+    /// Used to check for custom syntax.
+    #[cfg_attr(feature = "no_custom_syntax", allow(dead_code))]
+    engine: &'a Engine,
+    bytes: &'a [u8],
+    pos: usize,
+    depth: usize,
+    interner: StringsInterner,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(engine: &'a Engine, bytes: &'a [u8]) -> Self {
+        Self {
+            engine,
+            bytes,
+            pos: 0,
+            depth: 0,
+            interner: StringsInterner::new(256),
+        }
+    }
+
+    /// Byte offset of the next read.
+    pub const fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Error at the current offset.
+    const fn corrupted(&self) -> ASTFormatError {
+        ASTFormatError::Corrupted(self.pos)
+    }
+
+    /// Make sure that the input has been consumed completely.
+    pub fn finish(&self) -> ReadResult<()> {
+        if self.pos == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(self.corrupted())
+        }
+    }
+
+    fn enter(&mut self) -> ReadResult<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(ASTFormatError::TooDeep);
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    pub fn take(&mut self, n: usize) -> ReadResult<&'a [u8]> {
+        let bytes = self
+            .pos
+            .checked_add(n)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or_else(|| self.corrupted())?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    pub fn byte(&mut self) -> ReadResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> ReadResult<bool> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ASTFormatError::Corrupted(self.pos - 1)),
+        }
+    }
+
+    pub fn u64(&mut self) -> ReadResult<u64> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(<[u8; 8]>::try_from(bytes).unwrap()))
+    }
+
+    /// LEB128, refusing anything that does not fit into 64 bits.
+    pub fn uvarint(&mut self) -> ReadResult<u64> {
+        let start = self.pos;
+        let mut value = 0_u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            let payload = u64::from(byte & 0x7f);
+            if shift == 63 && payload > 1 {
+                break;
+            }
+            value |= payload << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(ASTFormatError::Corrupted(start))
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn ivarint(&mut self) -> ReadResult<i64> {
+        let raw = self.uvarint()?;
+        Ok(((raw >> 1) as i64) ^ -((raw & 1) as i64))
+    }
+
+    fn usize(&mut self) -> ReadResult<usize> {
+        let start = self.pos;
+        usize::try_from(self.uvarint()?).map_err(|_| ASTFormatError::Corrupted(start))
+    }
+
+    /// A count of items, each taking up at least one byte.
+    ///
+    /// A count larger than the remaining input cannot be right, and refusing it up-front keeps
+    /// a corrupted count from reserving a huge amount of memory.
+    fn len(&mut self) -> ReadResult<usize> {
+        let start = self.pos;
+        let len = self.usize()?;
+        if len > self.bytes.len() - self.pos {
+            return Err(ASTFormatError::Corrupted(start));
+        }
+        Ok(len)
+    }
+
+    fn int(&mut self) -> ReadResult<INT> {
+        let start = self.pos;
+        INT::try_from(self.ivarint()?).map_err(|_| ASTFormatError::Corrupted(start))
+    }
+
+    fn char(&mut self) -> ReadResult<char> {
+        let start = self.pos;
+        u32::try_from(self.uvarint()?)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(ASTFormatError::Corrupted(start))
+    }
+
+    fn str(&mut self) -> ReadResult<&'a str> {
+        let start = self.pos;
+        let len = self.len()?;
+        std::str::from_utf8(self.take(len)?).map_err(|_| ASTFormatError::Corrupted(start))
+    }
+
+    fn string(&mut self) -> ReadResult<ImmutableString> {
+        let s = self.str()?;
+        Ok(self.interner.get(s))
+    }
+
+    fn pos(&mut self) -> ReadResult<Position> {
+        let start = self.pos;
+        let line = self.uvarint()?;
+        if line == 0 {
+            return Ok(Position::NONE);
+        }
+        let col = self.uvarint()?;
+        match (u16::try_from(line), u16::try_from(col)) {
+            (Ok(line), Ok(col)) => Ok(Position::new(line, col)),
+            _ => Err(ASTFormatError::Corrupted(start)),
+        }
+    }
+
+    fn index(&mut self) -> ReadResult<Option<NonZeroUsize>> {
+        Ok(NonZeroUsize::new(self.usize()?))
+    }
+
+    fn flags(&mut self) -> ReadResult<ASTFlags> {
+        let bits = self.byte()?;
+        ASTFlags::from_bits(bits).ok_or(ASTFormatError::Corrupted(self.pos - 1))
+    }
+
+    fn ident(&mut self) -> ReadResult<Ident> {
+        Ok(Ident {
+            name: self.string()?,
+            pos: self.pos()?,
+        })
+    }
+
+    fn opt_ident(&mut self) -> ReadResult<Option<Ident>> {
+        Ok(if self.bool()? {
+            Some(self.ident()?)
+        } else {
+            None
+        })
+    }
+
+    fn token(&mut self) -> ReadResult<Option<Token>> {
+        let start = self.pos;
+        match self.byte()? {
+            token_tag::NONE => Ok(None),
+            token_tag::UNARY_PLUS => Ok(Some(Token::UnaryPlus)),
+            token_tag::UNARY_MINUS => Ok(Some(Token::UnaryMinus)),
+            token_tag::SYNTAX => Token::lookup_symbol_from_syntax(self.str()?)
+                .map(Some)
+                .ok_or(ASTFormatError::Corrupted(start)),
+            _ => Err(ASTFormatError::Corrupted(start)),
+        }
+    }
+
+    /// Source, doc-comments, statements and the functions library.
+    pub fn get_ast(&mut self) -> ReadResult<AST> {
+        let source = self.string()?;
+        let _doc = self.str()?;
+
+        let len = self.len()?;
+        let mut statements = Vec::with_capacity(len);
+        for _ in 0..len {
+            statements.push(self.stmt()?);
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        let mut ast = AST::new(statements, self.lib()?);
+        #[cfg(feature = "no_function")]
+        let mut ast = AST::new(statements);
+
+        #[cfg(feature = "metadata")]
+        {
+            ast.doc = _doc.into();
+        }
+        ast.set_source(source);
+
+        Ok(ast)
+    }
+
+    /// Script-defined functions, then custom types.
+    #[cfg(not(feature = "no_function"))]
+    fn lib(&mut self) -> ReadResult<crate::Module> {
+        use crate::ast::ScriptFuncDef;
+        use crate::types::CustomTypeInfo;
+        use crate::FnAccess;
+
+        let mut lib = crate::Module::new();
+
+        for _ in 0..self.len()? {
+            let name = self.string()?;
+            let access = if self.bool()? {
+                FnAccess::Private
+            } else {
+                FnAccess::Public
+            };
+            #[cfg(not(feature = "no_object"))]
+            let this_type = if self.bool()? {
+                Some(self.string()?)
+            } else {
+                None
+            };
+            let params = (0..self.len()?)
+                .map(|_| self.string())
+                .collect::<ReadResult<_>>()?;
+            let is_generator = self.bool()?;
+            let statics = if self.bool()? {
+                Some(crate::Shared::new(crate::Locked::new(<_>::default())))
+            } else {
+                None
+            };
+            let _comments = self.comments()?;
+            let mut body = self.block()?;
+            if is_generator {
+                body.cache_yield();
+            }
+
+            lib.set_script_fn(ScriptFuncDef {
+                body,
+                name,
+                access,
+                #[cfg(not(feature = "no_object"))]
+                this_type,
+                params,
+                is_generator,
+                statics,
+                #[cfg(feature = "metadata")]
+                comments: _comments,
+            });
+        }
+
+        for _ in 0..self.len()? {
+            let type_name = self.str()?.into();
+            let display_name = self.str()?.into();
+            let _comments = self.comments()?;
+            #[cfg(not(feature = "no_object"))]
+            let fields = if self.bool()? {
+                Some(
+                    (0..self.len()?)
+                        .map(|_| self.str().map(Into::into))
+                        .collect::<ReadResult<_>>()?,
+                )
+            } else {
+                None
+            };
+
+            lib.set_custom_type_info(CustomTypeInfo {
+                type_name,
+                display_name,
+                #[cfg(feature = "metadata")]
+                comments: _comments,
+                #[cfg(not(feature = "no_object"))]
+                fields,
+            });
+        }
+
+        Ok(lib)
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    fn comments(&mut self) -> ReadResult<StaticVec<crate::SmartString>> {
+        (0..self.len()?)
+            .map(|_| self.str().map(Into::into))
+            .collect()
+    }
+
+    fn block(&mut self) -> ReadResult<StmtBlock> {
+        let span = Span::new(self.pos()?, self.pos()?);
+        let len = self.len()?;
+        let mut statements = Vec::with_capacity(len);
+        for _ in 0..len {
+            statements.push(self.stmt()?);
+        }
+        Ok(StmtBlock::new_with_span(statements, span))
+    }
+
+    fn flow(&mut self) -> ReadResult<FlowControl> {
+        Ok(FlowControl {
+            expr: self.expr()?,
+            body: self.block()?,
+            branch: self.block()?,
+        })
+    }
+
+    fn binary(&mut self) -> ReadResult<BinaryExpr> {
+        Ok(BinaryExpr {
+            lhs: self.expr()?,
+            rhs: self.expr()?,
+        })
+    }
+
+    fn opt_expr(&mut self) -> ReadResult<Option<Expr>> {
+        Ok(if self.bool()? {
+            Some(self.expr()?)
+        } else {
+            None
+        })
+    }
+
+    fn exprs<T: FromIterator<Expr>>(&mut self) -> ReadResult<T> {
+        (0..self.len()?).map(|_| self.expr()).collect()
+    }
+
+    fn stmt(&mut self) -> ReadResult<Stmt> {
+        self.enter()?;
+
+        let start = self.pos;
+
+        let stmt = match self.byte()? {
+            stmt_tag::NOOP => Stmt::Noop(self.pos()?),
+            stmt_tag::IF => Stmt::If(self.flow()?.into(), self.pos()?),
+            stmt_tag::SWITCH => {
+                let expr = self.expr()?;
+                let cases = self.switch_cases()?;
+                Stmt::Switch((expr, cases).into(), self.pos()?)
+            }
+            stmt_tag::WHILE => Stmt::While(self.flow()?.into(), self.pos()?),
+            stmt_tag::DO => Stmt::Do(self.flow()?.into(), self.flags()?, self.pos()?),
+            stmt_tag::FOR => {
+                let var = self.ident()?;
+                let counter = self.opt_ident()?;
+                let flow = self.flow()?;
+                Stmt::For((var, counter, flow).into(), self.pos()?)
+            }
+            stmt_tag::VAR => {
+                let var = self.ident()?;
+                let expr = self.expr()?;
+                let index = self.index()?;
+                Stmt::Var((var, expr, index).into(), self.flags()?, self.pos()?)
+            }
+            stmt_tag::ASSIGNMENT => {
+                let op = if self.bool()? {
+                    let start = self.pos;
+                    Some(
+                        Token::lookup_symbol_from_syntax(self.str()?)
+                            .filter(|op| op.get_base_op_from_assignment().is_some())
+                            .ok_or(ASTFormatError::Corrupted(start))?,
+                    )
+                } else {
+                    None
+                };
+                let pos = self.pos()?;
+                let op = match op {
+                    Some(op) => OpAssignment::new_op_assignment_from_token(op, pos),
+                    None => OpAssignment::new_assignment(pos),
+                };
+                Stmt::Assignment((op, self.binary()?).into())
+            }
+            stmt_tag::FN_CALL => Stmt::FnCall(self.fn_call(false)?.into(), self.pos()?),
+            stmt_tag::BLOCK => Stmt::Block(self.block()?.into()),
+            stmt_tag::TRY_CATCH => {
+                let body = self.block()?;
+                let catches = (0..self.len()?)
+                    .map(|_| {
+                        Ok(CatchClause {
+                            var: self.expr()?,
+                            guard: self.expr()?,
+                            body: self.block()?,
+                        })
+                    })
+                    .collect::<ReadResult<_>>()?;
+                let finally = self.block()?;
+                Stmt::TryCatch(
+                    TryCatchBlock {
+                        body,
+                        catches,
+                        finally,
+                    }
+                    .into(),
+                    self.pos()?,
+                )
+            }
+            stmt_tag::EXPR => Stmt::Expr(self.expr()?.into()),
+            stmt_tag::BREAK_LOOP => {
+                let expr = self.opt_expr()?.map(Box::new);
+                let flags = self.flags()?;
+                Stmt::BreakLoop(expr, flags, self.byte()?, self.pos()?)
+            }
+            stmt_tag::RETURN => {
+                let expr = self.opt_expr()?.map(Box::new);
+                Stmt::Return(expr, self.flags()?, self.pos()?)
+            }
+            #[cfg(not(feature = "no_function"))]
+            stmt_tag::YIELD => Stmt::Yield(self.expr()?.into(), self.pos()?),
+            #[cfg(not(feature = "no_module"))]
+            stmt_tag::IMPORT => {
+                let expr = self.expr()?;
+                let alias = self.ident()?;
+                Stmt::Import((expr, alias).into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            stmt_tag::EXPORT => {
+                let name = self.ident()?;
+                let alias = self.ident()?;
+                Stmt::Export((name, alias).into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            stmt_tag::MODULE => {
+                let name = self.ident()?;
+                let body = self.get_ast()?;
+                Stmt::Module(crate::ast::ModuleBlock { name, body }.into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_closure"))]
+            stmt_tag::SHARE => Stmt::Share(
+                (0..self.len()?)
+                    .map(|_| Ok((self.ident()?, self.index()?)))
+                    .collect::<ReadResult<FnArgsVec<_>>>()?
+                    .into(),
+            ),
+            _ => return Err(ASTFormatError::Corrupted(start)),
+        };
+
+        self.leave();
+        Ok(stmt)
+    }
+
+    /// Read an index into a list of `len` items.
+    fn index_into(&mut self, len: usize) -> ReadResult<usize> {
+        let start = self.pos;
+        let index = self.usize()?;
+        if index >= len {
+            return Err(ASTFormatError::Corrupted(start));
+        }
+        Ok(index)
+    }
+
+    fn switch_cases(&mut self) -> ReadResult<SwitchCasesCollection> {
+        let expressions: FnArgsVec<_> = (0..self.len()?)
+            .map(|_| self.binary())
+            .collect::<ReadResult<_>>()?;
+        let n = expressions.len();
+
+        let mut cases = crate::func::StraightHashMap::default();
+        let num_cases = self.len()?;
+        if num_cases > 0 && self.u64()? != super::hasher_probe() {
+            return Err(ASTFormatError::ConfigMismatch(
+                "with a different hashing seed, which 'switch' cases depend on".to_string(),
+            ));
+        }
+        for _ in 0..num_cases {
+            let hash = self.u64()?;
+            let blocks = (0..self.len()?)
+                .map(|_| self.index_into(n))
+                .collect::<ReadResult<_>>()?;
+            cases.insert(hash, blocks);
+        }
+
+        let ranges = (0..self.len()?)
+            .map(|_| {
+                let start = self.pos;
+                let tag = self.byte()?;
+                let (from, to) = (self.int()?, self.int()?);
+                let index = self.index_into(n)?;
+                match tag {
+                    value_tag::RANGE => Ok(RangeCase::ExclusiveInt(from..to, index)),
+                    value_tag::RANGE_INCLUSIVE => Ok(RangeCase::InclusiveInt(from..=to, index)),
+                    _ => Err(ASTFormatError::Corrupted(start)),
+                }
+            })
+            .collect::<ReadResult<_>>()?;
+
+        let def_case = match self.index_into(n + 1)? {
+            0 => None,
+            index => Some(index - 1),
+        };
+
+        let patterns = (0..self.len()?)
+            .map(|_| Ok((self.pattern()?, self.index_into(n)?)))
+            .collect::<ReadResult<_>>()?;
+
+        Ok(SwitchCasesCollection {
+            expressions,
+            cases,
+            ranges,
+            def_case,
+            patterns,
+        })
+    }
+
+    fn pattern(&mut self) -> ReadResult<SwitchPattern> {
+        self.enter()?;
+
+        let start = self.pos;
+
+        let pattern = match self.byte()? {
+            pattern_tag::WILDCARD => SwitchPattern::Wildcard,
+            pattern_tag::BIND => SwitchPattern::Bind(self.ident()?),
+            pattern_tag::VALUE => SwitchPattern::Value(self.value()?.into()),
+            pattern_tag::TUPLE => SwitchPattern::Tuple(
+                (0..self.len()?)
+                    .map(|_| self.pattern())
+                    .collect::<ReadResult<_>>()?,
+            ),
+            pattern_tag::ENUM => {
+                let name = self.string()?;
+                let variant = self.string()?;
+                let fields = (0..self.len()?)
+                    .map(|_| self.pattern())
+                    .collect::<ReadResult<_>>()?;
+                SwitchPattern::Enum((name, variant, fields).into())
+            }
+            _ => return Err(ASTFormatError::Corrupted(start)),
+        };
+
+        self.leave();
+        Ok(pattern)
+    }
+
+    /// The hashes are recalculated the same way as the parser does: a method call hashes the
+    /// object as one more argument, and its script hash leaves it out.
+    fn fn_call(&mut self, is_method: bool) -> ReadResult<FnCallExpr> {
+        #[cfg(not(feature = "no_module"))]
+        let namespace = self.namespace()?;
+        let name = self.string()?;
+
+        let start = self.pos;
+        let has_script_hash = self.bool()?;
+        let args: FnArgsVec<_> = self.exprs()?;
+
+        let num_args = args.len() + usize::from(is_method);
+        #[cfg(not(feature = "no_module"))]
+        let native = calc_fn_hash(namespace.path.iter().map(Ident::as_str), &name, num_args);
+        #[cfg(feature = "no_module")]
+        let native = calc_fn_hash(None, &name, num_args);
+
+        let hashes = match has_script_hash {
+            false => FnCallHashes::from_native_only(native),
+            #[cfg(not(feature = "no_function"))]
+            true if is_method => FnCallHashes::from_script_and_native(
+                calc_fn_hash(None, &name, num_args - 1),
+                native,
+            ),
+            #[cfg(not(feature = "no_function"))]
+            true => FnCallHashes::from_hash(native),
+            #[cfg(feature = "no_function")]
+            true => return Err(ASTFormatError::Corrupted(start)),
+        };
+        let _ = start;
+
+        Ok(FnCallExpr {
+            #[cfg(not(feature = "no_module"))]
+            namespace,
+            name,
+            hashes,
+            args,
+            capture_parent_scope: self.bool()?,
+            op_token: self.token()?,
+        })
+    }
+
+    #[cfg(not(feature = "no_module"))]
+    fn namespace(&mut self) -> ReadResult<crate::ast::Namespace> {
+        let path = (0..self.len()?)
+            .map(|_| self.ident())
+            .collect::<ReadResult<_>>()?;
+        Ok(crate::ast::Namespace {
+            path,
+            index: self.index()?,
+        })
+    }
+
+    /// `(name, expr)` pairs of a map or `struct` literal.
+    fn fields(&mut self) -> ReadResult<StaticVec<(Ident, Expr)>> {
+        (0..self.len()?)
+            .map(|_| Ok((self.ident()?, self.expr()?)))
+            .collect()
+    }
+
+    fn expr(&mut self) -> ReadResult<Expr> {
+        self.enter()?;
+
+        let start = self.pos;
+
+        let expr = match self.byte()? {
+            expr_tag::DYNAMIC_CONSTANT => Expr::DynamicConstant(self.value()?.into(), self.pos()?),
+            expr_tag::BOOL => Expr::BoolConstant(self.bool()?, self.pos()?),
+            expr_tag::INT => Expr::IntegerConstant(self.int()?, self.pos()?),
+            #[cfg(not(feature = "no_float"))]
+            expr_tag::FLOAT => Expr::FloatConstant(self.float()?.into(), self.pos()?),
+            expr_tag::CHAR => Expr::CharConstant(self.char()?, self.pos()?),
+            expr_tag::STRING => Expr::StringConstant(self.string()?, self.pos()?),
+            expr_tag::INTERPOLATED_STRING => Expr::InterpolatedString(self.exprs()?, self.pos()?),
+            expr_tag::ARRAY => Expr::Array(self.exprs()?, self.pos()?),
+            expr_tag::MAP => {
+                let fields = self.fields()?;
+                let template = (0..self.len()?)
+                    .map(|_| Ok((self.str()?.into(), self.value()?)))
+                    .collect::<ReadResult<_>>()?;
+                Expr::Map((fields, template).into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_object"))]
+            expr_tag::STRUCT => {
+                let fields = self.fields()?;
+                let template = self.struct_value()?;
+                Expr::Struct((fields, template).into(), self.pos()?)
+            }
+            expr_tag::TUPLE => Expr::Tuple(self.exprs()?, self.pos()?),
+            expr_tag::ENUM => {
+                let name = self.string()?;
+                let variant = self.string()?;
+                let fields = self.exprs()?;
+                Expr::Enum((name, variant, fields).into(), self.pos()?)
+            }
+            expr_tag::COMPREHENSION => {
+                let key = self.opt_expr()?;
+                let value = self.expr()?;
+                let var = self.ident()?;
+                let counter = self.opt_ident()?;
+                let iterable = self.expr()?;
+                let condition = self.opt_expr()?;
+                Expr::Comprehension(
+                    crate::ast::ComprehensionExpr {
+                        key,
+                        value,
+                        var,
+                        counter,
+                        iterable,
+                        condition,
+                    }
+                    .into(),
+                    self.pos()?,
+                )
+            }
+            expr_tag::SPREAD => Expr::Spread(self.expr()?.into(), self.pos()?),
+            expr_tag::UNIT => Expr::Unit(self.pos()?),
+            expr_tag::VARIABLE => {
+                let index = self.index()?;
+                let name = self.string()?;
+                #[cfg(not(feature = "no_module"))]
+                let x = {
+                    let namespace = self.namespace()?;
+                    let hash = if namespace.is_empty() {
+                        0
+                    } else {
+                        crate::calc_var_hash(namespace.path.iter().map(Ident::as_str), &name)
+                    };
+                    (index, name, namespace, hash)
+                };
+                #[cfg(feature = "no_module")]
+                let x = (index, name);
+                let short_index = u8::try_from(self.usize()?).map_err(|_| self.corrupted())?;
+                Expr::Variable(x.into(), NonZeroU8::new(short_index), self.pos()?)
+            }
+            expr_tag::THIS_PTR => Expr::ThisPtr(self.pos()?),
+            expr_tag::PROPERTY => {
+                let getter = self.string()?;
+                let getter = (getter.clone(), calc_fn_hash(None, &getter, 1));
+                let setter = self.string()?;
+                let setter = (setter.clone(), calc_fn_hash(None, &setter, 2));
+                let prop = self.string()?;
+                Expr::Property((getter, setter, prop).into(), self.pos()?)
+            }
+            expr_tag::METHOD_CALL => Expr::MethodCall(self.fn_call(true)?.into(), self.pos()?),
+            expr_tag::STMT => Expr::Stmt(self.block()?.into()),
+            expr_tag::FN_CALL => Expr::FnCall(self.fn_call(false)?.into(), self.pos()?),
+            expr_tag::DOT => Expr::Dot(self.binary()?.into(), self.flags()?, self.pos()?),
+            expr_tag::INDEX => Expr::Index(self.binary()?.into(), self.flags()?, self.pos()?),
+            expr_tag::AND => Expr::And(Box::new(self.exprs()?), self.pos()?),
+            expr_tag::OR => Expr::Or(Box::new(self.exprs()?), self.pos()?),
+            expr_tag::COALESCE => Expr::Coalesce(Box::new(self.exprs()?), self.pos()?),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            expr_tag::CUSTOM => {
+                let inputs = self.exprs()?;
+                let tokens: FnArgsVec<ImmutableString> = (0..self.len()?)
+                    .map(|_| self.string())
+                    .collect::<ReadResult<_>>()?;
+                // The first token is the key of the custom syntax.
+                match tokens.first() {
+                    Some(key) if self.engine.custom_syntax.contains_key(key.as_str()) => (),
+                    Some(key) => return Err(ASTFormatError::UnknownCustomSyntax(key.to_string())),
+                    None => return Err(ASTFormatError::Corrupted(start)),
+                }
+                let state = self.value()?;
+                let scope_may_be_changed = self.bool()?;
+                let self_terminated = self.bool()?;
+                Expr::Custom(
+                    crate::ast::CustomExpr {
+                        inputs,
+                        tokens,
+                        state,
+                        scope_may_be_changed,
+                        self_terminated,
+                    }
+                    .into(),
+                    self.pos()?,
+                )
+            }
+            _ => return Err(ASTFormatError::Corrupted(start)),
+        };
+
+        self.leave();
+        Ok(expr)
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    fn float(&mut self) -> ReadResult<crate::FLOAT> {
+        const WIDTH: usize = std::mem::size_of::<crate::FLOAT>();
+        let bytes = self.take(WIDTH)?;
+        Ok(crate::FLOAT::from_le_bytes(
+            <[u8; WIDTH]>::try_from(bytes).unwrap(),
+        ))
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    fn struct_value(&mut self) -> ReadResult<crate::StructValue> {
+        let name = self.string()?;
+        let fields = (0..self.len()?)
+            .map(|_| Ok((self.string()?, self.value()?)))
+            .collect::<ReadResult<Vec<_>>>()?;
+        Ok(crate::StructValue::new(name, fields))
+    }
+
+    fn values<T: FromIterator<Dynamic>>(&mut self) -> ReadResult<T> {
+        (0..self.len()?).map(|_| self.value()).collect()
+    }
+
+    fn value(&mut self) -> ReadResult<Dynamic> {
+        self.enter()?;
+
+        let start = self.pos;
+
+        let mut value = match self.byte()? {
+            value_tag::UNIT => Dynamic::UNIT,
+            value_tag::FALSE => Dynamic::FALSE,
+            value_tag::TRUE => Dynamic::TRUE,
+            value_tag::INT => Dynamic::from_int(self.int()?),
+            #[cfg(not(feature = "no_float"))]
+            value_tag::FLOAT => Dynamic::from_float(self.float()?),
+            #[cfg(feature = "decimal")]
+            value_tag::DECIMAL => {
+                let mantissa = i128::from_le_bytes(<[u8; 16]>::try_from(self.take(16)?).unwrap());
+                let scale = u32::try_from(self.uvarint()?).map_err(|_| self.corrupted())?;
+                rust_decimal::Decimal::try_from_i128_with_scale(mantissa, scale)
+                    .map(Dynamic::from_decimal)
+                    .map_err(|_| ASTFormatError::Corrupted(start))?
+            }
+            #[cfg(feature = "bigint")]
+            value_tag::BIGINT => {
+                let len = self.len()?;
+                Dynamic::from_bigint(num_bigint::BigInt::from_signed_bytes_le(self.take(len)?))
+            }
+            value_tag::CHAR => Dynamic::from_char(self.char()?),
+            value_tag::STRING => self.string()?.into(),
+            #[cfg(not(feature = "no_index"))]
+            value_tag::ARRAY => Dynamic::from_array(self.values()?),
+            #[cfg(not(feature = "no_index"))]
+            value_tag::BLOB => {
+                let len = self.len()?;
+                Dynamic::from_blob(self.take(len)?.to_vec())
+            }
+            #[cfg(not(feature = "no_object"))]
+            value_tag::MAP => Dynamic::from_map(
+                (0..self.len()?)
+                    .map(|_| Ok((self.str()?.into(), self.value()?)))
+                    .collect::<ReadResult<_>>()?,
+            ),
+            value_tag::FN_PTR => self.fn_ptr()?.into(),
+            value_tag::TUPLE => Dynamic::from_tuple(self.values::<Vec<_>>()?),
+            value_tag::ENUM => {
+                let name = self.string()?;
+                let variant = self.string()?;
+                let fields = Tuple::new(self.values::<Vec<_>>()?);
+                EnumValue::new(name, variant, fields).into()
+            }
+            #[cfg(not(feature = "no_object"))]
+            value_tag::STRUCT => self.struct_value()?.into(),
+            value_tag::RANGE => {
+                let from = self.int()?;
+                (from..self.int()?).into()
+            }
+            value_tag::RANGE_INCLUSIVE => {
+                let from = self.int()?;
+                (from..=self.int()?).into()
+            }
+            _ => return Err(ASTFormatError::Corrupted(start)),
+        };
+
+        let start = self.pos;
+        let meta = self.byte()?;
+        if meta & !0b11 != 0 {
+            return Err(ASTFormatError::Corrupted(start));
+        }
+        if meta & 0b01 != 0 {
+            value.set_access_mode(AccessMode::ReadOnly);
+        }
+        if meta & 0b10 != 0 {
+            let start = self.pos;
+            let tag = self.ivarint()?;
+            value.set_tag(
+                tag.try_into()
+                    .map_err(|_| ASTFormatError::Corrupted(start))?,
+            );
+        }
+
+        self.leave();
+        Ok(value)
+    }
+
+    fn fn_ptr(&mut self) -> ReadResult<FnPtr> {
+        use crate::types::fn_ptr::FnPtrType;
+
+        let name = self.string()?;
+        let curry: ThinVec<_> = self.values()?;
+
+        let start = self.pos;
+        let typ = match self.byte()? {
+            0 => FnPtrType::Normal,
+            #[cfg(not(feature = "no_function"))]
+            1 => {
+                let num_params = self.usize()?;
+                FnPtrType::Script {
+                    num_params,
+                    hash: calc_fn_hash(None, &name, num_params),
+                }
+            }
+            _ => return Err(ASTFormatError::Corrupted(start)),
+        };
+
+        Ok(FnPtr {
+            name,
+            curry,
+            #[cfg(not(feature = "no_function"))]
+            env: None,
+            typ,
+        })
+    }
+}
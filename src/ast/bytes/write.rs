@@ -0,0 +1,845 @@
+//! Writing an [`AST`] into its binary form.
+
+use super::{
+    expr_tag, pattern_tag, put_ivarint, put_str, put_uvarint, stmt_tag, token_tag, value_tag,
+    ASTFormatError, MAX_DEPTH,
+};
+use crate::ast::{
+    BinaryExpr, Expr, FlowControl, FnCallExpr, Ident, RangeCase, Stmt, StmtBlock,
+    SwitchCasesCollection, SwitchPattern, AST,
+};
+use crate::tokenizer::Token;
+use crate::types::dynamic::{AccessMode, Union};
+use crate::{Dynamic, FnPtr, Position, INT};
+use std::num::NonZeroUsize;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+type WriteResult = Result<(), ASTFormatError>;
+
+/// Encodes an [`AST`] into a buffer.
+pub(super) struct Writer {
+    out: Vec<u8>,
+    depth: usize,
+}
+
+impl Writer {
+    pub fn new(out: Vec<u8>) -> Self {
+        Self { out, depth: 0 }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.out
+    }
+
+    fn enter(&mut self) -> WriteResult {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(ASTFormatError::TooDeep);
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn put_u8(&mut self, value: u8) {
+        self.out.push(value);
+    }
+
+    fn put_bool(&mut self, value: bool) {
+        self.out.push(u8::from(value));
+    }
+
+    fn put_uvarint(&mut self, value: u64) {
+        put_uvarint(&mut self.out, value);
+    }
+
+    fn put_len(&mut self, len: usize) {
+        put_uvarint(&mut self.out, len as u64);
+    }
+
+    #[allow(clippy::useless_conversion)]
+    fn put_int(&mut self, value: INT) {
+        put_ivarint(&mut self.out, value.into());
+    }
+
+    fn put_hash(&mut self, hash: u64) {
+        self.out.extend_from_slice(&hash.to_le_bytes());
+    }
+
+    fn put_str(&mut self, value: &str) {
+        put_str(&mut self.out, value);
+    }
+
+    /// A position is its line number followed by its character position, or a single zero
+    /// for [`Position::NONE`].
+    fn put_pos(&mut self, pos: Position) {
+        match pos.line() {
+            Some(line) => {
+                self.put_len(line);
+                self.put_len(pos.position().unwrap_or(0));
+            }
+            None => self.put_u8(0),
+        }
+    }
+
+    /// An optional index is written as zero for [`None`].
+    fn put_index(&mut self, index: Option<NonZeroUsize>) {
+        self.put_len(index.map_or(0, NonZeroUsize::get));
+    }
+
+    fn put_ident(&mut self, ident: &Ident) {
+        self.put_str(&ident.name);
+        self.put_pos(ident.pos);
+    }
+
+    fn put_opt_ident(&mut self, ident: Option<&Ident>) {
+        self.put_bool(ident.is_some());
+        if let Some(ident) = ident {
+            self.put_ident(ident);
+        }
+    }
+
+    fn put_token(&mut self, token: Option<&Token>) -> WriteResult {
+        match token {
+            None => self.put_u8(token_tag::NONE),
+            Some(Token::UnaryPlus) => self.put_u8(token_tag::UNARY_PLUS),
+            Some(Token::UnaryMinus) => self.put_u8(token_tag::UNARY_MINUS),
+            Some(token) => {
+                let syntax = token.literal_syntax();
+                if Token::lookup_symbol_from_syntax(syntax).as_ref() != Some(token) {
+                    return Err(ASTFormatError::Unsupported(format!(
+                        "operator token {token:?}"
+                    )));
+                }
+                self.put_u8(token_tag::SYNTAX);
+                self.put_str(syntax);
+            }
+        }
+        Ok(())
+    }
+
+    /// Source, doc-comments, statements and the functions library.
+    pub fn put_ast(&mut self, ast: &AST) -> WriteResult {
+        #[cfg(not(feature = "no_module"))]
+        if ast.resolver.is_some() {
+            return Err(ASTFormatError::Unsupported(
+                "embedded module resolver".to_string(),
+            ));
+        }
+
+        self.put_str(ast.source().unwrap_or(""));
+        #[cfg(feature = "metadata")]
+        self.put_str(ast.doc());
+        #[cfg(not(feature = "metadata"))]
+        self.put_str("");
+
+        self.put_len(ast.statements().len());
+        for stmt in ast.statements() {
+            self.put_stmt(stmt)?;
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        self.put_lib(ast.shared_lib())?;
+
+        Ok(())
+    }
+
+    /// Script-defined functions, then custom types.
+    #[cfg(not(feature = "no_function"))]
+    fn put_lib(&mut self, lib: &crate::Module) -> WriteResult {
+        if let Some((_, m)) = lib.iter_fn().find(|(f, ..)| !f.is_script()) {
+            return Err(ASTFormatError::Unsupported(format!(
+                "native function '{}' in functions library",
+                m.name
+            )));
+        }
+        if let Some((name, ..)) = lib.iter_var().next() {
+            return Err(ASTFormatError::Unsupported(format!(
+                "variable '{name}' in functions library"
+            )));
+        }
+        if let Some((name, ..)) = lib.iter_sub_modules().next() {
+            return Err(ASTFormatError::Unsupported(format!(
+                "sub-module '{name}' in functions library"
+            )));
+        }
+
+        self.put_len(lib.iter_script_fn().count());
+
+        for (.., fn_def) in lib.iter_script_fn() {
+            self.put_str(&fn_def.name);
+            self.put_bool(fn_def.access.is_private());
+            #[cfg(not(feature = "no_object"))]
+            self.put_bool(fn_def.this_type.is_some());
+            #[cfg(not(feature = "no_object"))]
+            if let Some(ref this_type) = fn_def.this_type {
+                self.put_str(this_type);
+            }
+            self.put_len(fn_def.params.len());
+            for param in &fn_def.params {
+                self.put_str(param);
+            }
+            self.put_bool(fn_def.is_generator);
+            self.put_bool(fn_def.statics.is_some());
+            #[cfg(feature = "metadata")]
+            self.put_comments(fn_def.comments.iter().map(<_>::as_ref));
+            #[cfg(not(feature = "metadata"))]
+            self.put_comments(std::iter::empty());
+            self.put_block(&fn_def.body)?;
+        }
+
+        self.put_len(lib.iter_custom_types().count());
+
+        for (.., info) in lib.iter_custom_types() {
+            self.put_str(&info.type_name);
+            self.put_str(&info.display_name);
+            #[cfg(feature = "metadata")]
+            self.put_comments(info.comments.iter().map(<_>::as_ref));
+            #[cfg(not(feature = "metadata"))]
+            self.put_comments(std::iter::empty());
+            #[cfg(not(feature = "no_object"))]
+            {
+                self.put_bool(info.fields.is_some());
+                if let Some(ref fields) = info.fields {
+                    self.put_len(fields.len());
+                    for field in fields {
+                        self.put_str(field);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    fn put_comments<'a>(&mut self, comments: impl ExactSizeIterator<Item = &'a str>) {
+        self.put_len(comments.len());
+        for comment in comments {
+            self.put_str(comment);
+        }
+    }
+
+    fn put_block(&mut self, block: &StmtBlock) -> WriteResult {
+        let span = block.span();
+        self.put_pos(span.start());
+        self.put_pos(span.end());
+        self.put_len(block.statements().len());
+        for stmt in block.statements() {
+            self.put_stmt(stmt)?;
+        }
+        Ok(())
+    }
+
+    fn put_flow(&mut self, flow: &FlowControl) -> WriteResult {
+        self.put_expr(&flow.expr)?;
+        self.put_block(&flow.body)?;
+        self.put_block(&flow.branch)
+    }
+
+    fn put_binary(&mut self, x: &BinaryExpr) -> WriteResult {
+        self.put_expr(&x.lhs)?;
+        self.put_expr(&x.rhs)
+    }
+
+    fn put_opt_expr(&mut self, expr: Option<&Expr>) -> WriteResult {
+        self.put_bool(expr.is_some());
+        expr.map_or(Ok(()), |expr| self.put_expr(expr))
+    }
+
+    fn put_exprs<'a>(&mut self, exprs: impl ExactSizeIterator<Item = &'a Expr>) -> WriteResult {
+        self.put_len(exprs.len());
+        for expr in exprs {
+            self.put_expr(expr)?;
+        }
+        Ok(())
+    }
+
+    fn put_stmt(&mut self, stmt: &Stmt) -> WriteResult {
+        self.enter()?;
+
+        match stmt {
+            Stmt::Noop(pos) => {
+                self.put_u8(stmt_tag::NOOP);
+                self.put_pos(*pos);
+            }
+            Stmt::If(x, pos) => {
+                self.put_u8(stmt_tag::IF);
+                self.put_flow(x)?;
+                self.put_pos(*pos);
+            }
+            Stmt::Switch(x, pos) => {
+                self.put_u8(stmt_tag::SWITCH);
+                self.put_expr(&x.0)?;
+                self.put_switch_cases(&x.1)?;
+                self.put_pos(*pos);
+            }
+            Stmt::While(x, pos) => {
+                self.put_u8(stmt_tag::WHILE);
+                self.put_flow(x)?;
+                self.put_pos(*pos);
+            }
+            Stmt::Do(x, flags, pos) => {
+                self.put_u8(stmt_tag::DO);
+                self.put_flow(x)?;
+                self.put_u8(flags.bits());
+                self.put_pos(*pos);
+            }
+            Stmt::For(x, pos) => {
+                self.put_u8(stmt_tag::FOR);
+                self.put_ident(&x.0);
+                self.put_opt_ident(x.1.as_ref());
+                self.put_flow(&x.2)?;
+                self.put_pos(*pos);
+            }
+            Stmt::Var(x, flags, pos) => {
+                self.put_u8(stmt_tag::VAR);
+                self.put_ident(&x.0);
+                self.put_expr(&x.1)?;
+                self.put_index(x.2);
+                self.put_u8(flags.bits());
+                self.put_pos(*pos);
+            }
+            Stmt::Assignment(x) => {
+                self.put_u8(stmt_tag::ASSIGNMENT);
+                match x.0.get_op_assignment_info() {
+                    Some((.., op_assign, _, _, _)) => {
+                        self.put_bool(true);
+                        self.put_str(op_assign.literal_syntax());
+                    }
+                    None => self.put_bool(false),
+                }
+                self.put_pos(x.0.position());
+                self.put_binary(&x.1)?;
+            }
+            Stmt::FnCall(x, pos) => {
+                self.put_u8(stmt_tag::FN_CALL);
+                self.put_fn_call(x)?;
+                self.put_pos(*pos);
+            }
+            Stmt::Block(x) => {
+                self.put_u8(stmt_tag::BLOCK);
+                self.put_block(x)?;
+            }
+            Stmt::TryCatch(x, pos) => {
+                self.put_u8(stmt_tag::TRY_CATCH);
+                self.put_block(&x.body)?;
+                self.put_len(x.catches.len());
+                for clause in &x.catches {
+                    self.put_expr(&clause.var)?;
+                    self.put_expr(&clause.guard)?;
+                    self.put_block(&clause.body)?;
+                }
+                self.put_block(&x.finally)?;
+                self.put_pos(*pos);
+            }
+            Stmt::Expr(x) => {
+                self.put_u8(stmt_tag::EXPR);
+                self.put_expr(x)?;
+            }
+            Stmt::BreakLoop(expr, flags, label, pos) => {
+                self.put_u8(stmt_tag::BREAK_LOOP);
+                self.put_opt_expr(expr.as_deref())?;
+                self.put_u8(flags.bits());
+                self.put_u8(*label);
+                self.put_pos(*pos);
+            }
+            Stmt::Return(expr, flags, pos) => {
+                self.put_u8(stmt_tag::RETURN);
+                self.put_opt_expr(expr.as_deref())?;
+                self.put_u8(flags.bits());
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_function"))]
+            Stmt::Yield(x, pos) => {
+                self.put_u8(stmt_tag::YIELD);
+                self.put_expr(x)?;
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, pos) => {
+                self.put_u8(stmt_tag::IMPORT);
+                self.put_expr(&x.0)?;
+                self.put_ident(&x.1);
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(x, pos) => {
+                self.put_u8(stmt_tag::EXPORT);
+                self.put_ident(&x.0);
+                self.put_ident(&x.1);
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Module(x, pos) => {
+                self.put_u8(stmt_tag::MODULE);
+                self.put_ident(&x.name);
+                self.put_ast(&x.body)?;
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(x) => {
+                self.put_u8(stmt_tag::SHARE);
+                self.put_len(x.len());
+                for (ident, index) in x.iter() {
+                    self.put_ident(ident);
+                    self.put_index(*index);
+                }
+            }
+        }
+
+        self.leave();
+        Ok(())
+    }
+
+    fn put_switch_cases(&mut self, x: &SwitchCasesCollection) -> WriteResult {
+        self.put_len(x.expressions.len());
+        for expr in &x.expressions {
+            self.put_binary(expr)?;
+        }
+
+        // Sorted so that the same AST always serializes to the same bytes.
+        let mut cases: Vec<_> = x.cases.iter().collect();
+        cases.sort_unstable_by_key(|(hash, ..)| **hash);
+        self.put_len(cases.len());
+        if !cases.is_empty() {
+            self.put_hash(super::hasher_probe());
+        }
+        for (hash, blocks) in cases {
+            self.put_hash(*hash);
+            self.put_len(blocks.len());
+            for &index in blocks {
+                self.put_len(index);
+            }
+        }
+
+        self.put_len(x.ranges.len());
+        for range in &x.ranges {
+            match range {
+                RangeCase::ExclusiveInt(r, index) => {
+                    self.put_u8(value_tag::RANGE);
+                    self.put_int(r.start);
+                    self.put_int(r.end);
+                    self.put_len(*index);
+                }
+                RangeCase::InclusiveInt(r, index) => {
+                    self.put_u8(value_tag::RANGE_INCLUSIVE);
+                    self.put_int(*r.start());
+                    self.put_int(*r.end());
+                    self.put_len(*index);
+                }
+            }
+        }
+
+        self.put_len(x.def_case.map_or(0, |index| index + 1));
+
+        self.put_len(x.patterns.len());
+        for (pattern, index) in &x.patterns {
+            self.put_pattern(pattern)?;
+            self.put_len(*index);
+        }
+
+        Ok(())
+    }
+
+    fn put_pattern(&mut self, pattern: &SwitchPattern) -> WriteResult {
+        self.enter()?;
+
+        match pattern {
+            SwitchPattern::Wildcard => self.put_u8(pattern_tag::WILDCARD),
+            SwitchPattern::Bind(ident) => {
+                self.put_u8(pattern_tag::BIND);
+                self.put_ident(ident);
+            }
+            SwitchPattern::Value(value) => {
+                self.put_u8(pattern_tag::VALUE);
+                self.put_value(value, Position::NONE)?;
+            }
+            SwitchPattern::Tuple(x) => {
+                self.put_u8(pattern_tag::TUPLE);
+                self.put_len(x.len());
+                for p in x {
+                    self.put_pattern(p)?;
+                }
+            }
+            SwitchPattern::Enum(x) => {
+                self.put_u8(pattern_tag::ENUM);
+                self.put_str(&x.0);
+                self.put_str(&x.1);
+                self.put_len(x.2.len());
+                for p in &x.2 {
+                    self.put_pattern(p)?;
+                }
+            }
+        }
+
+        self.leave();
+        Ok(())
+    }
+
+    /// The hashes are not written, only whether there is a script hash; see `Reader::fn_call`.
+    fn put_fn_call(&mut self, x: &FnCallExpr) -> WriteResult {
+        #[cfg(not(feature = "no_module"))]
+        self.put_namespace(&x.namespace);
+        self.put_str(&x.name);
+        self.put_bool(!x.hashes.is_native_only());
+
+        self.put_exprs(x.args.iter())?;
+        self.put_bool(x.capture_parent_scope);
+        self.put_token(x.op_token.as_ref())
+    }
+
+    #[cfg(not(feature = "no_module"))]
+    fn put_namespace(&mut self, namespace: &crate::ast::Namespace) {
+        self.put_len(namespace.path.len());
+        for ident in &namespace.path {
+            self.put_ident(ident);
+        }
+        self.put_index(namespace.index);
+    }
+
+    fn put_expr(&mut self, expr: &Expr) -> WriteResult {
+        self.enter()?;
+
+        match expr {
+            Expr::DynamicConstant(value, pos) => {
+                self.put_u8(expr_tag::DYNAMIC_CONSTANT);
+                self.put_value(value, *pos)?;
+                self.put_pos(*pos);
+            }
+            Expr::BoolConstant(value, pos) => {
+                self.put_u8(expr_tag::BOOL);
+                self.put_bool(*value);
+                self.put_pos(*pos);
+            }
+            Expr::IntegerConstant(value, pos) => {
+                self.put_u8(expr_tag::INT);
+                self.put_int(*value);
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(value, pos) => {
+                self.put_u8(expr_tag::FLOAT);
+                self.out.extend_from_slice(&value.to_le_bytes());
+                self.put_pos(*pos);
+            }
+            Expr::CharConstant(value, pos) => {
+                self.put_u8(expr_tag::CHAR);
+                self.put_uvarint(u64::from(u32::from(*value)));
+                self.put_pos(*pos);
+            }
+            Expr::StringConstant(value, pos) => {
+                self.put_u8(expr_tag::STRING);
+                self.put_str(value);
+                self.put_pos(*pos);
+            }
+            Expr::InterpolatedString(x, pos) => {
+                self.put_u8(expr_tag::INTERPOLATED_STRING);
+                self.put_exprs(x.iter())?;
+                self.put_pos(*pos);
+            }
+            Expr::Array(x, pos) => {
+                self.put_u8(expr_tag::ARRAY);
+                self.put_exprs(x.iter())?;
+                self.put_pos(*pos);
+            }
+            Expr::Map(x, pos) => {
+                self.put_u8(expr_tag::MAP);
+                self.put_len(x.0.len());
+                for (ident, expr) in &x.0 {
+                    self.put_ident(ident);
+                    self.put_expr(expr)?;
+                }
+                self.put_len(x.1.len());
+                for (key, value) in &x.1 {
+                    self.put_str(key);
+                    self.put_value(value, *pos)?;
+                }
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_object"))]
+            Expr::Struct(x, pos) => {
+                self.put_u8(expr_tag::STRUCT);
+                self.put_len(x.0.len());
+                for (ident, expr) in &x.0 {
+                    self.put_ident(ident);
+                    self.put_expr(expr)?;
+                }
+                self.put_struct_value(&x.1, *pos)?;
+                self.put_pos(*pos);
+            }
+            Expr::Tuple(x, pos) => {
+                self.put_u8(expr_tag::TUPLE);
+                self.put_exprs(x.iter())?;
+                self.put_pos(*pos);
+            }
+            Expr::Enum(x, pos) => {
+                self.put_u8(expr_tag::ENUM);
+                self.put_str(&x.0);
+                self.put_str(&x.1);
+                self.put_exprs(x.2.iter())?;
+                self.put_pos(*pos);
+            }
+            Expr::Comprehension(x, pos) => {
+                self.put_u8(expr_tag::COMPREHENSION);
+                self.put_opt_expr(x.key.as_ref())?;
+                self.put_expr(&x.value)?;
+                self.put_ident(&x.var);
+                self.put_opt_ident(x.counter.as_ref());
+                self.put_expr(&x.iterable)?;
+                self.put_opt_expr(x.condition.as_ref())?;
+                self.put_pos(*pos);
+            }
+            Expr::Spread(x, pos) => {
+                self.put_u8(expr_tag::SPREAD);
+                self.put_expr(x)?;
+                self.put_pos(*pos);
+            }
+            Expr::Unit(pos) => {
+                self.put_u8(expr_tag::UNIT);
+                self.put_pos(*pos);
+            }
+            Expr::Variable(x, short_index, pos) => {
+                self.put_u8(expr_tag::VARIABLE);
+                self.put_index(x.0);
+                self.put_str(&x.1);
+                #[cfg(not(feature = "no_module"))]
+                self.put_namespace(&x.2);
+                self.put_len(short_index.map_or(0, |n| n.get().into()));
+                self.put_pos(*pos);
+            }
+            Expr::ThisPtr(pos) => {
+                self.put_u8(expr_tag::THIS_PTR);
+                self.put_pos(*pos);
+            }
+            Expr::Property(x, pos) => {
+                self.put_u8(expr_tag::PROPERTY);
+                self.put_str(&(x.0).0);
+                self.put_str(&(x.1).0);
+                self.put_str(&x.2);
+                self.put_pos(*pos);
+            }
+            Expr::MethodCall(x, pos) => {
+                self.put_u8(expr_tag::METHOD_CALL);
+                self.put_fn_call(x)?;
+                self.put_pos(*pos);
+            }
+            Expr::Stmt(x) => {
+                self.put_u8(expr_tag::STMT);
+                self.put_block(x)?;
+            }
+            Expr::FnCall(x, pos) => {
+                self.put_u8(expr_tag::FN_CALL);
+                self.put_fn_call(x)?;
+                self.put_pos(*pos);
+            }
+            Expr::Dot(x, flags, pos) | Expr::Index(x, flags, pos) => {
+                self.put_u8(if matches!(expr, Expr::Dot(..)) {
+                    expr_tag::DOT
+                } else {
+                    expr_tag::INDEX
+                });
+                self.put_binary(x)?;
+                self.put_u8(flags.bits());
+                self.put_pos(*pos);
+            }
+            Expr::And(x, pos) | Expr::Or(x, pos) | Expr::Coalesce(x, pos) => {
+                self.put_u8(match expr {
+                    Expr::And(..) => expr_tag::AND,
+                    Expr::Or(..) => expr_tag::OR,
+                    _ => expr_tag::COALESCE,
+                });
+                self.put_exprs(x.iter())?;
+                self.put_pos(*pos);
+            }
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, pos) => {
+                self.put_u8(expr_tag::CUSTOM);
+                self.put_exprs(x.inputs.iter())?;
+                self.put_len(x.tokens.len());
+                for token in &x.tokens {
+                    self.put_str(token);
+                }
+                self.put_value(&x.state, *pos)?;
+                self.put_bool(x.scope_may_be_changed);
+                self.put_bool(x.self_terminated);
+                self.put_pos(*pos);
+            }
+        }
+
+        self.leave();
+        Ok(())
+    }
+
+    /// A constant value is its tag and contents, followed by a byte holding whether it is
+    /// read-only and whether it carries a tag, and then the tag itself if it does.
+    ///
+    /// `pos` is only used to report an unsupported value.
+    fn put_value(&mut self, value: &Dynamic, pos: Position) -> WriteResult {
+        self.enter()?;
+
+        let unsupported = || ASTFormatError::UnsupportedValue(value.type_name().into(), pos);
+
+        match value.0 {
+            Union::Unit(..) => self.put_u8(value_tag::UNIT),
+            Union::Bool(false, ..) => self.put_u8(value_tag::FALSE),
+            Union::Bool(true, ..) => self.put_u8(value_tag::TRUE),
+            Union::Int(n, ..) => {
+                self.put_u8(value_tag::INT);
+                self.put_int(n);
+            }
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(f, ..) => {
+                self.put_u8(value_tag::FLOAT);
+                self.out.extend_from_slice(&f.to_le_bytes());
+            }
+            #[cfg(feature = "decimal")]
+            Union::Decimal(ref d, ..) => {
+                self.put_u8(value_tag::DECIMAL);
+                self.out.extend_from_slice(&d.mantissa().to_le_bytes());
+                self.put_uvarint(d.scale().into());
+            }
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref n, ..) => {
+                self.put_u8(value_tag::BIGINT);
+                let bytes = n.to_signed_bytes_le();
+                self.put_len(bytes.len());
+                self.out.extend_from_slice(&bytes);
+            }
+            Union::Char(c, ..) => {
+                self.put_u8(value_tag::CHAR);
+                self.put_uvarint(u64::from(u32::from(c)));
+            }
+            Union::Str(ref s, ..) => {
+                self.put_u8(value_tag::STRING);
+                self.put_str(s);
+            }
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(ref a, ..) => {
+                self.put_u8(value_tag::ARRAY);
+                self.put_len(a.len());
+                for item in a.iter() {
+                    self.put_value(item, pos)?;
+                }
+            }
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref b, ..) => {
+                self.put_u8(value_tag::BLOB);
+                self.put_len(b.len());
+                self.out.extend_from_slice(b);
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(ref m, ..) => {
+                self.put_u8(value_tag::MAP);
+                self.put_len(m.len());
+                for (key, item) in m.iter() {
+                    self.put_str(key);
+                    self.put_value(item, pos)?;
+                }
+            }
+            Union::FnPtr(ref f, ..) => {
+                self.put_u8(value_tag::FN_PTR);
+                self.put_fn_ptr(f, pos)?;
+            }
+            Union::Tuple(ref t, ..) => {
+                self.put_u8(value_tag::TUPLE);
+                self.put_len(t.len());
+                for item in t.iter() {
+                    self.put_value(item, pos)?;
+                }
+            }
+            Union::Enum(ref e, ..) => {
+                self.put_u8(value_tag::ENUM);
+                self.put_str(e.enum_name());
+                self.put_str(e.variant());
+                self.put_len(e.fields().len());
+                for item in e.fields().iter() {
+                    self.put_value(item, pos)?;
+                }
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref s, ..) => {
+                self.put_u8(value_tag::STRUCT);
+                self.put_struct_value(s, pos)?;
+            }
+            Union::Variant(..) => {
+                if let Some(r) = value.downcast_ref::<crate::ExclusiveRange>() {
+                    self.put_u8(value_tag::RANGE);
+                    self.put_int(r.start);
+                    self.put_int(r.end);
+                } else if let Some(r) = value.downcast_ref::<crate::InclusiveRange>() {
+                    self.put_u8(value_tag::RANGE_INCLUSIVE);
+                    self.put_int(*r.start());
+                    self.put_int(*r.end());
+                } else {
+                    return Err(unsupported());
+                }
+            }
+            #[cfg(not(feature = "no_time"))]
+            Union::TimeStamp(..) => return Err(unsupported()),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(..) => return Err(unsupported()),
+        }
+
+        let tag = value.tag();
+        let read_only = matches!(value.access_mode(), AccessMode::ReadOnly);
+        self.put_u8(u8::from(read_only) | (u8::from(tag != 0) << 1));
+        if tag != 0 {
+            put_ivarint(&mut self.out, tag.into());
+        }
+
+        self.leave();
+        Ok(())
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    fn put_struct_value(&mut self, value: &crate::StructValue, pos: Position) -> WriteResult {
+        self.put_str(value.struct_name());
+        self.put_len(value.len());
+        for (name, item) in value.iter() {
+            self.put_str(name);
+            self.put_value(item, pos)?;
+        }
+        Ok(())
+    }
+
+    /// Only function pointers that can be recreated from a name are written: neither native
+    /// functions nor encapsulated environments have meaning outside this process.
+    fn put_fn_ptr(&mut self, f: &FnPtr, pos: Position) -> WriteResult {
+        use crate::types::fn_ptr::FnPtrType;
+
+        #[cfg(not(feature = "no_function"))]
+        if f.env.is_some() {
+            return Err(ASTFormatError::UnsupportedValue(
+                format!("function pointer '{}' with environment", f.fn_name()),
+                pos,
+            ));
+        }
+
+        self.put_str(f.fn_name());
+        self.put_len(f.curry().len());
+        for item in f.iter_curry() {
+            self.put_value(item, pos)?;
+        }
+
+        match f.typ {
+            FnPtrType::Normal => self.put_u8(0),
+            #[cfg(not(feature = "no_function"))]
+            FnPtrType::Script { num_params, .. } => {
+                self.put_u8(1);
+                self.put_len(num_params);
+            }
+            FnPtrType::Native(..) => {
+                return Err(ASTFormatError::UnsupportedValue(
+                    format!("native function pointer '{}'", f.fn_name()),
+                    pos,
+                ))
+            }
+        }
+
+        Ok(())
+    }
+}
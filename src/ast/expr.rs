@@ -3,7 +3,7 @@
 use super::{ASTFlags, ASTNode, Ident, Stmt, StmtBlock};
 use crate::engine::KEYWORD_FN_PTR;
 use crate::eval::GlobalRuntimeState;
-use crate::tokenizer::Token;
+use crate::tokenizer::{is_valid_identifier, Token};
 use crate::types::dynamic::Union;
 use crate::{
     calc_fn_hash, Dynamic, FnArgsVec, FnPtr, Identifier, ImmutableString, Position, SmartString,
@@ -31,6 +31,24 @@ pub struct BinaryExpr {
     pub rhs: Expr,
 }
 
+/// _(internals)_ An array or object map comprehension.
+/// Exported under the `internals` feature only.
+#[derive(Debug, Clone, Hash)]
+pub struct ComprehensionExpr {
+    /// Key expression, for an object map comprehension only.
+    pub key: Option<Expr>,
+    /// Item expression (the value expression for an object map comprehension).
+    pub value: Expr,
+    /// Loop variable.
+    pub var: Ident,
+    /// Counter variable, if any.
+    pub counter: Option<Ident>,
+    /// Expression to iterate over.
+    pub iterable: Expr,
+    /// Filter condition, if any.
+    pub condition: Option<Expr>,
+}
+
 /// _(internals)_ A custom syntax expression.
 /// Exported under the `internals` feature only.
 ///
@@ -222,6 +240,25 @@ impl fmt::Debug for FnCallExpr {
 }
 
 impl FnCallExpr {
+    /// _(internals)_ Create a new [`FnCallExpr`] calling an unqualified function, with the
+    /// hashes pre-calculated.
+    /// Exported under the `internals` feature only.
+    #[cfg(feature = "internals")]
+    #[inline]
+    #[must_use]
+    pub fn new(name: impl Into<ImmutableString>, args: impl IntoIterator<Item = Expr>) -> Self {
+        let mut x = Self {
+            #[cfg(not(feature = "no_module"))]
+            namespace: super::Namespace::NONE,
+            name: name.into(),
+            hashes: FnCallHashes::from_native_only(0),
+            args: args.into_iter().collect(),
+            capture_parent_scope: false,
+            op_token: None,
+        };
+        x.recalc_hashes();
+        x
+    }
     /// Does this function call contain a qualified namespace?
     ///
     /// Not available under `no_module`
@@ -243,12 +280,45 @@ impl FnCallExpr {
     pub fn into_fn_call_expr(self, pos: Position) -> Expr {
         Expr::FnCall(self.into(), pos)
     }
+    /// _(internals)_ Convert this into a [`Stmt::FnCall`].
+    /// Exported under the `internals` feature only.
+    #[cfg(feature = "internals")]
+    #[inline(always)]
+    #[must_use]
+    pub fn into_fn_call_stmt(self, pos: Position) -> Stmt {
+        Stmt::FnCall(self.into(), pos)
+    }
     /// Are all arguments constant?
     #[inline]
     #[must_use]
     pub fn constant_args(&self) -> bool {
         self.args.is_empty() || self.args.iter().all(Expr::is_constant)
     }
+    /// Are any arguments spread (i.e. `...args`)?
+    #[inline]
+    #[must_use]
+    pub fn has_spread_args(&self) -> bool {
+        self.args.iter().any(|arg| matches!(arg, Expr::Spread(..)))
+    }
+    /// Recalculate the pre-calculated hashes after the name, namespace or number of arguments
+    /// has changed.
+    #[inline]
+    pub fn recalc_hashes(&mut self) {
+        #[cfg(not(feature = "no_module"))]
+        let hash = calc_fn_hash(
+            self.namespace.path.iter().map(Ident::as_str),
+            &self.name,
+            self.args.len(),
+        );
+        #[cfg(feature = "no_module")]
+        let hash = calc_fn_hash(None, &self.name, self.args.len());
+
+        self.hashes = if is_valid_identifier(&self.name) {
+            FnCallHashes::from_hash(hash)
+        } else {
+            FnCallHashes::from_native_only(hash)
+        };
+    }
 }
 
 /// _(internals)_ An expression sub-tree.
@@ -284,6 +354,25 @@ pub enum Expr {
         Box<(StaticVec<(Ident, Self)>, BTreeMap<Identifier, Dynamic>)>,
         Position,
     ),
+    /// Struct `{` name`:` expr`,` ... `}` - (fields in source order, template value)
+    ///
+    /// Not available under `no_object`.
+    #[cfg(not(feature = "no_object"))]
+    Struct(
+        Box<(StaticVec<(Ident, Self)>, crate::StructValue)>,
+        Position,
+    ),
+    /// ( expr, ... )
+    Tuple(ThinVec<Self>, Position),
+    /// Enum `::` Variant `(` expr `,` ... `)` - (enum name, variant name, fields)
+    Enum(
+        Box<(ImmutableString, ImmutableString, ThinVec<Self>)>,
+        Position,
+    ),
+    /// `[` expr `for` name `in` expr `if` expr `]` | `#{` key`:` expr `for` name `in` expr `if` expr `}`
+    Comprehension(Box<ComprehensionExpr>, Position),
+    /// `...` expr - only valid inside an array literal, a map literal or a function call's arguments
+    Spread(Box<Self>, Position),
     /// ()
     Unit(Position),
     /// Variable access - (optional long index, variable name, namespace, namespace hash), optional short index, position
@@ -371,12 +460,29 @@ impl fmt::Debug for Expr {
                 f.write_str("Array")?;
                 f.debug_list().entries(x.iter()).finish()
             }
+            Self::Tuple(x, ..) => {
+                f.write_str("Tuple")?;
+                f.debug_list().entries(x.iter()).finish()
+            }
+            Self::Enum(x, ..) => {
+                write!(f, "Enum({}::{})", x.0, x.1)?;
+                f.debug_list().entries(x.2.iter()).finish()
+            }
+            Self::Spread(x, ..) => f.debug_tuple("Spread").field(x).finish(),
+            Self::Comprehension(x, ..) => fmt::Debug::fmt(x, f),
             Self::Map(x, ..) => {
                 f.write_str("Map")?;
                 f.debug_map()
                     .entries(x.0.iter().map(|(k, v)| (k, v)))
                     .finish()
             }
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(x, ..) => {
+                write!(f, "Struct({})", x.1.struct_name())?;
+                f.debug_map()
+                    .entries(x.0.iter().map(|(k, v)| (k, v)))
+                    .finish()
+            }
             Self::ThisPtr(..) => f.debug_struct("ThisPtr").finish(),
             Self::Variable(x, i, ..) => {
                 f.write_str("Variable(")?;
@@ -499,6 +605,20 @@ impl Expr {
                 Dynamic::from_array(arr)
             }
 
+            Self::Tuple(x, ..) if self.is_constant() => Dynamic::from_tuple(
+                x.iter()
+                    .map(|v| v.get_literal_value(global).unwrap())
+                    .collect::<crate::Tuple>(),
+            ),
+
+            Self::Enum(x, ..) if self.is_constant() => Dynamic::from_enum(crate::EnumValue::new(
+                x.0.clone(),
+                x.1.clone(),
+                x.2.iter()
+                    .map(|v| v.get_literal_value(global).unwrap())
+                    .collect::<crate::Tuple>(),
+            )),
+
             #[cfg(not(feature = "no_object"))]
             Self::Map(x, ..) if self.is_constant() => {
                 let mut map = x.1.clone();
@@ -510,6 +630,17 @@ impl Expr {
                 Dynamic::from_map(map)
             }
 
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(x, ..) if self.is_constant() => {
+                let mut value = x.1.clone();
+
+                for (k, v) in &x.0 {
+                    *value.get_mut(k.as_str()).unwrap() = v.get_literal_value(global).unwrap();
+                }
+
+                Dynamic::from_struct(value)
+            }
+
             // Interpolated string
             Self::InterpolatedString(x, ..) if self.is_constant() => {
                 let mut s = SmartString::new_const();
@@ -635,6 +766,8 @@ impl Expr {
 
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(..) => ASTFlags::empty(),
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(..) => ASTFlags::empty(),
 
             Self::DynamicConstant(..)
             | Self::BoolConstant(..)
@@ -643,6 +776,10 @@ impl Expr {
             | Self::Unit(..)
             | Self::StringConstant(..)
             | Self::Array(..)
+            | Self::Tuple(..)
+            | Self::Enum(..)
+            | Self::Spread(..)
+            | Self::Comprehension(..)
             | Self::Map(..)
             | Self::Variable(..)
             | Self::ThisPtr(..)
@@ -666,6 +803,8 @@ impl Expr {
         match self {
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(.., pos) => *pos,
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(.., pos) => *pos,
 
             Self::DynamicConstant(.., pos)
             | Self::BoolConstant(.., pos)
@@ -674,6 +813,10 @@ impl Expr {
             | Self::Unit(pos)
             | Self::StringConstant(.., pos)
             | Self::Array(.., pos)
+            | Self::Tuple(.., pos)
+            | Self::Enum(.., pos)
+            | Self::Spread(.., pos)
+            | Self::Comprehension(.., pos)
             | Self::Map(.., pos)
             | Self::Variable(.., pos)
             | Self::ThisPtr(pos)
@@ -723,6 +866,8 @@ impl Expr {
         match self {
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(.., pos) => *pos = new_pos,
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(.., pos) => *pos = new_pos,
 
             Self::DynamicConstant(.., pos)
             | Self::BoolConstant(.., pos)
@@ -731,6 +876,10 @@ impl Expr {
             | Self::Unit(pos)
             | Self::StringConstant(.., pos)
             | Self::Array(.., pos)
+            | Self::Tuple(.., pos)
+            | Self::Enum(.., pos)
+            | Self::Spread(.., pos)
+            | Self::Comprehension(.., pos)
             | Self::Map(.., pos)
             | Self::And(.., pos)
             | Self::Or(.., pos)
@@ -759,9 +908,14 @@ impl Expr {
     #[must_use]
     pub fn is_pure(&self) -> bool {
         match self {
-            Self::InterpolatedString(x, ..) | Self::Array(x, ..) => x.iter().all(Self::is_pure),
+            Self::InterpolatedString(x, ..) | Self::Array(x, ..) | Self::Tuple(x, ..) => {
+                x.iter().all(Self::is_pure)
+            }
+            Self::Enum(x, ..) => x.2.iter().all(Self::is_pure),
 
             Self::Map(x, ..) => x.0.iter().map(|(.., v)| v).all(Self::is_pure),
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(x, ..) => x.0.iter().map(|(.., v)| v).all(Self::is_pure),
 
             Self::And(x, ..) | Self::Or(x, ..) | Self::Coalesce(x, ..) => {
                 x.iter().all(Self::is_pure)
@@ -796,9 +950,14 @@ impl Expr {
             | Self::StringConstant(..)
             | Self::Unit(..) => true,
 
-            Self::InterpolatedString(x, ..) | Self::Array(x, ..) => x.iter().all(Self::is_constant),
+            Self::InterpolatedString(x, ..) | Self::Array(x, ..) | Self::Tuple(x, ..) => {
+                x.iter().all(Self::is_constant)
+            }
+            Self::Enum(x, ..) => x.2.iter().all(Self::is_constant),
 
             Self::Map(x, ..) => x.0.iter().map(|(.., expr)| expr).all(Self::is_constant),
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(x, ..) => x.0.iter().map(|(.., expr)| expr).all(Self::is_constant),
 
             _ => false,
         }
@@ -818,6 +977,8 @@ impl Expr {
         match self {
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(..) => false,
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(..) => false,
 
             Self::DynamicConstant(..)
             | Self::BoolConstant(..)
@@ -837,6 +998,10 @@ impl Expr {
             | Self::Dot(..)
             | Self::Index(..)
             | Self::Array(..)
+            | Self::Tuple(..)
+            | Self::Enum(..)
+            | Self::Spread(..)
+            | Self::Comprehension(..)
             | Self::Map(..) => false,
 
             #[cfg(not(feature = "no_custom_syntax"))]
@@ -878,13 +1043,39 @@ impl Expr {
                     }
                 }
             }
-            Self::InterpolatedString(x, ..) | Self::Array(x, ..) => {
+            Self::InterpolatedString(x, ..) | Self::Array(x, ..) | Self::Tuple(x, ..) => {
                 for e in &**x {
                     if !e.walk(path, on_node) {
                         return false;
                     }
                 }
             }
+            Self::Enum(x, ..) => {
+                for e in &x.2 {
+                    if !e.walk(path, on_node) {
+                        return false;
+                    }
+                }
+            }
+            Self::Spread(x, ..) if !x.walk(path, on_node) => return false,
+            Self::Comprehension(x, ..) => {
+                let ComprehensionExpr {
+                    key,
+                    value,
+                    iterable,
+                    condition,
+                    ..
+                } = &**x;
+
+                if !iterable.walk(path, on_node) {
+                    return false;
+                }
+                for e in condition.iter().chain(key).chain(once(value)) {
+                    if !e.walk(path, on_node) {
+                        return false;
+                    }
+                }
+            }
             Self::Map(x, ..) => {
                 for (.., e) in &x.0 {
                     if !e.walk(path, on_node) {
@@ -892,6 +1083,14 @@ impl Expr {
                     }
                 }
             }
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(x, ..) => {
+                for (.., e) in &x.0 {
+                    if !e.walk(path, on_node) {
+                        return false;
+                    }
+                }
+            }
             Self::Index(x, ..) | Self::Dot(x, ..) => {
                 if !x.lhs.walk(path, on_node) {
                     return false;
@@ -929,4 +1128,106 @@ impl Expr {
 
         true
     }
+    /// _(internals)_ Recursively walk this expression with a [`VisitorMut`][super::VisitorMut]
+    /// that may rewrite nodes in place.
+    /// Return `false` from the visitor to terminate the walk.
+    /// Exported under the `internals` feature only.
+    #[cfg(feature = "internals")]
+    #[allow(clippy::collapsible_match)]
+    pub fn walk_mut(&mut self, visitor: &mut (impl super::VisitorMut + ?Sized)) -> bool {
+        match self {
+            Self::Stmt(x) => {
+                for s in x.statements_mut() {
+                    if !s.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::InterpolatedString(x, ..) | Self::Array(x, ..) | Self::Tuple(x, ..) => {
+                for e in x {
+                    if !e.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::Enum(x, ..) => {
+                for e in &mut x.2 {
+                    if !e.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::Spread(x, ..) => {
+                if !x.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            Self::Comprehension(x, ..) => {
+                let ComprehensionExpr {
+                    key,
+                    value,
+                    iterable,
+                    condition,
+                    ..
+                } = &mut **x;
+
+                if !iterable.walk_mut(visitor) {
+                    return false;
+                }
+                for e in condition.iter_mut().chain(key).chain(once(value)) {
+                    if !e.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::Map(x, ..) => {
+                for (.., e) in &mut x.0 {
+                    if !e.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(x, ..) => {
+                for (.., e) in &mut x.0 {
+                    if !e.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::Index(x, ..) | Self::Dot(x, ..) => {
+                if !x.lhs.walk_mut(visitor) {
+                    return false;
+                }
+                if !x.rhs.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            Self::And(x, ..) | Self::Or(x, ..) | Self::Coalesce(x, ..) => {
+                for expr in &mut ***x {
+                    if !expr.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::FnCall(x, ..) | Self::MethodCall(x, ..) => {
+                for e in &mut x.args {
+                    if !e.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Self::Custom(x, ..) => {
+                for e in &mut x.inputs {
+                    if !e.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        visitor.visit_expr(self)
+    }
 }
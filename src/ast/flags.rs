@@ -53,6 +53,8 @@ bitflags! {
         const NEGATED = 0b_0000_0100;
         /// The [`AST`][crate::AST] node breaks out of normal control flow.
         const BREAK = 0b_0000_1000;
+        /// The [`AST`][crate::AST] node is a `static` variable that keeps its value between calls.
+        const STATIC = 0b_0001_0000;
     }
 }
 
@@ -2,6 +2,7 @@
 
 #[allow(clippy::module_inception)]
 pub mod ast;
+pub mod bytes;
 pub mod expr;
 pub mod flags;
 pub mod ident;
@@ -9,7 +10,12 @@ pub mod namespace;
 pub mod script_fn;
 pub mod stmt;
 
+#[cfg(feature = "internals")]
+pub use ast::VisitorMut;
 pub use ast::{ASTNode, EncapsulatedEnviron, AST};
+pub use bytes::ASTFormatError;
+#[allow(unused_imports)]
+pub use expr::ComprehensionExpr;
 #[cfg(not(feature = "no_custom_syntax"))]
 pub use expr::CustomExpr;
 pub use expr::{BinaryExpr, Expr, FnCallExpr, FnCallHashes};
@@ -18,10 +24,12 @@ pub use ident::Ident;
 #[cfg(not(feature = "no_module"))]
 pub use namespace::Namespace;
 #[cfg(not(feature = "no_function"))]
-pub use script_fn::{ScriptFnMetadata, ScriptFuncDef};
+pub use script_fn::{ScriptFnMetadata, ScriptFuncDef, StaticVariables};
+#[cfg(not(feature = "no_module"))]
+pub use stmt::ModuleBlock;
 pub use stmt::{
-    CaseBlocksList, FlowControl, OpAssignment, RangeCase, Stmt, StmtBlock, StmtBlockContainer,
-    SwitchCasesCollection,
+    CaseBlocksList, CatchClause, FlowControl, OpAssignment, RangeCase, Stmt, StmtBlock,
+    StmtBlockContainer, SwitchCasesCollection, SwitchPattern, TryCatchBlock,
 };
 
 /// _(internals)_ Empty placeholder for a script-defined function.
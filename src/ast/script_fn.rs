@@ -7,6 +7,17 @@ use crate::{FnArgsVec, ImmutableString};
 use std::prelude::v1::*;
 use std::{fmt, hash::Hash};
 
+/// Storage for the `static` variables of a script-defined function, keyed by variable name.
+///
+/// Each value is pushed into the scope whenever the `static` statement runs, so changes made
+/// during one call are seen by the next.
+///
+/// The values are shared values, except under `no_closure` where a copy is put into the scope and
+/// synchronized with the storage around each statement. A change made to the copy is then lost if
+/// the same statement also calls the function (e.g. recursively) which changes the variable.
+pub type StaticVariables =
+    crate::Shared<crate::Locked<std::collections::BTreeMap<ImmutableString, crate::Dynamic>>>;
+
 /// _(internals)_ A type containing information on a script-defined function.
 /// Exported under the `internals` feature only.
 #[derive(Debug, Clone)]
@@ -23,6 +34,15 @@ pub struct ScriptFuncDef {
     pub this_type: Option<ImmutableString>,
     /// Names of function parameters.
     pub params: FnArgsVec<ImmutableString>,
+    /// Is this function a generator (i.e. does its body contain `yield` statements)?
+    ///
+    /// Calling a generator returns a [`Generator`][crate::Generator] which runs the body lazily.
+    pub is_generator: bool,
+    /// Storage for the `static` variables declared in the function body, if any.
+    ///
+    /// Clones of this function definition (and therefore of the [`Module`][crate::Module] holding
+    /// it) share the same storage, but each clone of an [`AST`][crate::AST] has its own.
+    pub statics: Option<StaticVariables>,
     /// _(metadata)_ Function doc-comments (if any). Exported under the `metadata` feature only.
     ///
     /// Doc-comments are comment lines beginning with `///` or comment blocks beginning with `/**`,
@@ -53,6 +73,8 @@ impl ScriptFuncDef {
             #[cfg(not(feature = "no_object"))]
             this_type: self.this_type.clone(),
             params: self.params.clone(),
+            is_generator: self.is_generator,
+            statics: None,
             #[cfg(feature = "metadata")]
             comments: <_>::default(),
         }
@@ -2,11 +2,14 @@
 
 use super::{ASTFlags, ASTNode, BinaryExpr, Expr, FnCallExpr, Ident};
 use crate::engine::{KEYWORD_EVAL, OP_EQUALS};
-use crate::func::StraightHashMap;
+use crate::func::{hashing::get_hasher, StraightHashMap};
 use crate::tokenizer::Token;
 use crate::types::dynamic::Union;
 use crate::types::Span;
-use crate::{calc_fn_hash, Dynamic, FnArgsVec, Position, StaticVec, INT};
+use crate::{
+    calc_fn_hash, Dynamic, EnumValue, ExclusiveRange, FnArgsVec, ImmutableString, InclusiveRange,
+    Position, StaticVec, ThinVec, Tuple, INT,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
@@ -258,6 +261,8 @@ impl RangeCase {
             Dynamic(Union::Float(v, ..)) => self.contains_float(**v),
             #[cfg(feature = "decimal")]
             Dynamic(Union::Decimal(v, ..)) => self.contains_decimal(**v),
+            #[cfg(feature = "bigint")]
+            Dynamic(Union::BigInt(v, ..)) => self.contains_bigint(v),
             _ => false,
         }
     }
@@ -298,6 +303,20 @@ impl RangeCase {
             }
         }
     }
+    /// Is the specified big integer within this range?
+    #[cfg(feature = "bigint")]
+    #[inline]
+    #[must_use]
+    pub fn contains_bigint(&self, n: &num_bigint::BigInt) -> bool {
+        use num_bigint::BigInt;
+
+        match self {
+            Self::ExclusiveInt(r, ..) => (BigInt::from(r.start)..BigInt::from(r.end)).contains(n),
+            Self::InclusiveInt(r, ..) => {
+                (BigInt::from(*r.start())..=BigInt::from(*r.end())).contains(n)
+            }
+        }
+    }
     /// Is the specified range inclusive?
     #[inline(always)]
     #[must_use]
@@ -326,6 +345,131 @@ impl RangeCase {
 
 pub type CaseBlocksList = smallvec::SmallVec<[usize; 2]>;
 
+/// _(internals)_ A destructuring pattern for a `switch` case.
+/// Exported under the `internals` feature only.
+///
+/// Patterns match tuples and `enum` values, binding parts of them to variables.
+#[derive(Clone, Hash)]
+#[non_exhaustive]
+pub enum SwitchPattern {
+    /// `_` - matches any value.
+    Wildcard,
+    /// Variable name - matches any value, binding it to the variable.
+    Bind(Ident),
+    /// A literal value, which may be a range.
+    Value(Box<Dynamic>),
+    /// `(` pattern `,` ... `)` - matches a tuple with the same number of items.
+    Tuple(ThinVec<Self>),
+    /// Enum `::` Variant `(` pattern `,` ... `)` - (enum name, variant name, field patterns)
+    Enum(Box<(ImmutableString, ImmutableString, ThinVec<Self>)>),
+}
+
+impl fmt::Debug for SwitchPattern {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wildcard => f.write_str("_"),
+            Self::Bind(x) => f.write_str(&x.name),
+            Self::Value(x) => write!(f, "{x:?}"),
+            Self::Tuple(x) => {
+                f.write_str("Tuple")?;
+                f.debug_list().entries(x.iter()).finish()
+            }
+            Self::Enum(x) => {
+                write!(f, "{}::{}", x.0, x.1)?;
+                f.debug_list().entries(x.2.iter()).finish()
+            }
+        }
+    }
+}
+
+impl SwitchPattern {
+    /// Convert this pattern into a literal value if it does not bind any variable nor contain
+    /// any wildcard or nested range.
+    #[must_use]
+    pub fn to_literal(&self) -> Option<Dynamic> {
+        fn to_item(pattern: &SwitchPattern) -> Option<Dynamic> {
+            match pattern {
+                SwitchPattern::Value(x) if x.is::<ExclusiveRange>() => None,
+                SwitchPattern::Value(x) if x.is::<InclusiveRange>() => None,
+                _ => pattern.to_literal(),
+            }
+        }
+
+        match self {
+            Self::Wildcard | Self::Bind(..) => None,
+            Self::Value(x) => Some((**x).clone()),
+            Self::Tuple(x) => x
+                .iter()
+                .map(to_item)
+                .collect::<Option<Tuple>>()
+                .map(Dynamic::from_tuple),
+            Self::Enum(x) => {
+                x.2.iter()
+                    .map(to_item)
+                    .collect::<Option<Tuple>>()
+                    .map(|fields| EnumValue::new(x.0.clone(), x.1.clone(), fields).into())
+            }
+        }
+    }
+    /// Iterate all the variables bound by this pattern, in order.
+    pub fn for_each_binding<'a>(&'a self, f: &mut impl FnMut(&'a Ident)) {
+        match self {
+            Self::Wildcard | Self::Value(..) => (),
+            Self::Bind(x) => f(x),
+            Self::Tuple(x) => x.iter().for_each(|p| p.for_each_binding(f)),
+            Self::Enum(x) => x.2.iter().for_each(|p| p.for_each_binding(f)),
+        }
+    }
+    /// Does a value match this pattern?
+    ///
+    /// Values bound to variables are pushed into `bindings` in the same order as
+    /// [`for_each_binding`][Self::for_each_binding].
+    #[must_use]
+    pub fn matches(&self, value: &Dynamic, bindings: &mut StaticVec<Dynamic>) -> bool {
+        match self {
+            Self::Wildcard => true,
+            Self::Bind(..) => {
+                bindings.push(value.flatten_clone());
+                true
+            }
+            Self::Value(x) => {
+                let value = &*value.read_lock::<Dynamic>().unwrap();
+
+                if let Some(range) = x.read_lock::<ExclusiveRange>() {
+                    return RangeCase::from(range.clone()).contains(value);
+                }
+                if let Some(range) = x.read_lock::<InclusiveRange>() {
+                    return RangeCase::from(range.clone()).contains(value);
+                }
+                if !value.is_hashable() {
+                    return false;
+                }
+
+                let hasher1 = &mut get_hasher();
+                x.hash(hasher1);
+                let hasher2 = &mut get_hasher();
+                value.hash(hasher2);
+                hasher1.finish() == hasher2.finish()
+            }
+            Self::Tuple(x) => value.read_lock::<Tuple>().map_or(false, |t| {
+                t.len() == x.len() && x.iter().zip(t.iter()).all(|(p, v)| p.matches(v, bindings))
+            }),
+            Self::Enum(x) => value.read_lock::<EnumValue>().map_or(false, |e| {
+                let (name, variant, fields) = &**x;
+
+                e.is(name, variant)
+                    && e.fields().len() == fields.len()
+                    && fields
+                        .iter()
+                        .zip(e.fields().iter())
+                        .all(|(p, v)| p.matches(v, bindings))
+            }),
+        }
+    }
+}
+
 /// _(internals)_ A type containing all cases for a `switch` statement.
 /// Exported under the `internals` feature only.
 #[derive(Debug, Clone)]
@@ -338,6 +482,8 @@ pub struct SwitchCasesCollection {
     pub ranges: StaticVec<RangeCase>,
     /// Statements block for the default case (there can be no condition for the default case).
     pub def_case: Option<usize>,
+    /// List of destructuring pattern cases, checked in order.
+    pub patterns: StaticVec<(SwitchPattern, usize)>,
 }
 
 impl Hash for SwitchCasesCollection {
@@ -350,6 +496,7 @@ impl Hash for SwitchCasesCollection {
 
         self.ranges.hash(state);
         self.def_case.hash(state);
+        self.patterns.hash(state);
     }
 }
 
@@ -379,6 +526,11 @@ pub struct StmtBlock {
     block: StmtBlockContainer,
     /// [Position] of the statements block.
     span: Span,
+    /// Does this statements block contain `yield` statements?
+    ///
+    /// Cached by [`cache_yield`][StmtBlock::cache_yield].
+    #[cfg(not(feature = "no_function"))]
+    has_yield: bool,
 }
 
 impl StmtBlock {
@@ -403,6 +555,8 @@ impl StmtBlock {
         Self {
             block: statements,
             span,
+            #[cfg(not(feature = "no_function"))]
+            has_yield: false,
         }
     }
     /// Create an empty [`StmtBlock`].
@@ -412,6 +566,8 @@ impl StmtBlock {
         Self {
             block: StmtBlockContainer::new_const(),
             span: Span::new(pos, pos),
+            #[cfg(not(feature = "no_function"))]
+            has_yield: false,
         }
     }
     /// Returns `true` if this statements block contains no statements.
@@ -476,6 +632,49 @@ impl StmtBlock {
     pub fn set_position(&mut self, start_pos: Position, end_pos: Position) {
         self.span = Span::new(start_pos, end_pos);
     }
+    /// Does this statements block contain `yield` statements?
+    ///
+    /// Always `false` unless cached by [`cache_yield`][StmtBlock::cache_yield].
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn has_yield(&self) -> bool {
+        self.has_yield
+    }
+    /// Cache whether this statements block, and each statements block nested within its
+    /// statements, contain `yield` statements.  Returns `true` if this statements block does.
+    ///
+    /// This must be called again after the statements are modified.
+    ///
+    /// `yield` statements can only be nested within statements blocks, `if` statements and loops,
+    /// so expressions are not searched.
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    pub fn cache_yield(&mut self) -> bool {
+        let mut has_yield = false;
+
+        for stmt in &mut self.block {
+            has_yield |= match stmt {
+                Stmt::Yield(..) => true,
+                Stmt::Block(block) => block.cache_yield(),
+                Stmt::If(x, ..) | Stmt::While(x, ..) | Stmt::Do(x, ..) => {
+                    let body = x.body.cache_yield();
+                    x.branch.cache_yield() || body
+                }
+                Stmt::For(x, ..) => {
+                    let body = x.2.body.cache_yield();
+                    x.2.branch.cache_yield() || body
+                }
+                _ => false,
+            };
+        }
+
+        self.has_yield = has_yield;
+        has_yield
+    }
 }
 
 impl Borrow<[Stmt]> for StmtBlock {
@@ -517,15 +716,14 @@ impl From<Stmt> for StmtBlock {
     fn from(stmt: Stmt) -> Self {
         match stmt {
             Stmt::Block(block) => *block,
-            Stmt::Noop(pos) => Self {
-                block: StmtBlockContainer::new_const(),
-                span: Span::new(pos, pos),
-            },
+            Stmt::Noop(pos) => Self::empty(pos),
             _ => {
                 let pos = stmt.position();
                 Self {
                     block: vec![stmt].into(),
                     span: Span::new(pos, Position::NONE),
+                    #[cfg(not(feature = "no_function"))]
+                    has_yield: false,
                 }
             }
         }
@@ -578,6 +776,64 @@ pub struct FlowControl {
     pub branch: StmtBlock,
 }
 
+/// _(internals)_ A `catch` clause of a `try` statement.
+/// Exported under the `internals` feature only.
+#[derive(Debug, Clone, Hash)]
+pub struct CatchClause {
+    /// Error variable: [`Expr::Variable`], or [`Expr::Unit`] if none.
+    pub var: Expr,
+    /// Condition for the clause to catch the error, `true` if none.
+    ///
+    /// A filter on the type of the error value (e.g. `catch (e: string)`) is also a condition.
+    pub guard: Expr,
+    /// Body of the clause.
+    pub body: StmtBlock,
+}
+
+/// _(internals)_ A `try` statement containing:
+/// * a statements body,
+/// * `catch` clauses, tried in order, and
+/// * a `finally` block
+///
+/// Exported under the `internals` feature only.
+#[derive(Debug, Clone, Hash)]
+pub struct TryCatchBlock {
+    /// Main body.
+    pub body: StmtBlock,
+    /// List of `catch` clauses. An error not caught by any clause propagates.
+    pub catches: StaticVec<CatchClause>,
+    /// `finally` block, run however the statement exits (except for an uncatchable error).
+    pub finally: StmtBlock,
+}
+
+/// _(internals)_ An inline `module` block.
+/// Exported under the `internals` feature only.
+///
+/// Not available under `no_module`.
+#[cfg(not(feature = "no_module"))]
+#[derive(Debug, Clone)]
+pub struct ModuleBlock {
+    /// Name of the module.
+    pub name: Ident,
+    /// Body of the module, together with the functions defined in it.
+    pub body: crate::AST,
+}
+
+#[cfg(not(feature = "no_module"))]
+impl Hash for ModuleBlock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.body.statements().hash(state);
+
+        #[cfg(not(feature = "no_function"))]
+        self.body.iter_fn_def().for_each(|f| {
+            f.name.hash(state);
+            f.params.hash(state);
+            f.body.hash(state);
+        });
+    }
+}
+
 /// _(internals)_ A statement.
 /// Exported under the `internals` feature only.
 #[derive(Debug, Clone, Hash)]
@@ -609,12 +865,13 @@ pub enum Stmt {
     Do(Box<FlowControl>, ASTFlags, Position),
     /// `for` `(` id `,` counter `)` `in` expr `{` stmt `}`
     For(Box<(Ident, Option<Ident>, FlowControl)>, Position),
-    /// \[`export`\] `let`|`const` id `=` expr
+    /// \[`export`\] `let`|`const`|`static` id `=` expr
     ///
     /// ### Flags
     ///
     /// * [`EXPORTED`][ASTFlags::EXPORTED] = `export`  
-    /// * [`CONSTANT`][ASTFlags::CONSTANT] = `const`
+    /// * [`CONSTANT`][ASTFlags::CONSTANT] = `const`  
+    /// * [`STATIC`][ASTFlags::STATIC] = `static`
     Var(Box<(Ident, Expr, Option<NonZeroUsize>)>, ASTFlags, Position),
     /// expr op`=` expr
     Assignment(Box<(OpAssignment, BinaryExpr)>),
@@ -625,17 +882,20 @@ pub enum Stmt {
     FnCall(Box<FnCallExpr>, Position),
     /// `{` stmt`;` ... `}`
     Block(Box<StmtBlock>),
-    /// `try` `{` stmt; ... `}` `catch` `(` var `)` `{` stmt; ... `}`
-    TryCatch(Box<FlowControl>, Position),
+    /// `try` `{` stmt; ... `}` `catch` `(` var `)` `if` expr `{` stmt; ... `}` ... `finally` `{` stmt; ... `}`
+    TryCatch(Box<TryCatchBlock>, Position),
     /// [expression][Expr]
     Expr(Box<Expr>),
-    /// `continue`/`break` expr
+    /// `continue`/`break` `'`label expr
+    ///
+    /// The [`u8`] is the number of enclosing loops to leave before the one targeted, resolved from
+    /// the label at parse time (zero for the innermost loop).
     ///
     /// ### Flags
     ///
     /// * [`NONE`][ASTFlags::NONE] = `continue`
     /// * [`BREAK`][ASTFlags::BREAK] = `break`
-    BreakLoop(Option<Box<Expr>>, ASTFlags, Position),
+    BreakLoop(Option<Box<Expr>>, ASTFlags, u8, Position),
     /// `return`/`throw` expr
     ///
     /// ### Flags
@@ -643,6 +903,11 @@ pub enum Stmt {
     /// * [`NONE`][ASTFlags::NONE] = `return`
     /// * [`BREAK`][ASTFlags::BREAK] = `throw`
     Return(Option<Box<Expr>>, ASTFlags, Position),
+    /// `yield` expr
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    Yield(Box<Expr>, Position),
     /// `import` expr `as` alias
     ///
     /// Not available under `no_module`.
@@ -653,6 +918,11 @@ pub enum Stmt {
     /// Not available under `no_module`.
     #[cfg(not(feature = "no_module"))]
     Export(Box<(Ident, Ident)>, Position),
+    /// `module` name `{` stmt`;` ... `}`
+    ///
+    /// Not available under `no_module`.
+    #[cfg(not(feature = "no_module"))]
+    Module(Box<ModuleBlock>, Position),
     /// Convert a list of variables to shared.
     ///
     /// Not available under `no_closure`.
@@ -686,7 +956,7 @@ impl Stmt {
         match self {
             Self::Do(_, options, _)
             | Self::Var(_, options, _)
-            | Self::BreakLoop(_, options, _)
+            | Self::BreakLoop(_, options, ..)
             | Self::Return(_, options, _) => *options,
 
             Self::Noop(..)
@@ -700,8 +970,11 @@ impl Stmt {
             | Self::TryCatch(..)
             | Self::Assignment(..) => ASTFlags::empty(),
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(..) => ASTFlags::empty(),
+
             #[cfg(not(feature = "no_module"))]
-            Self::Import(..) | Self::Export(..) => ASTFlags::empty(),
+            Self::Import(..) | Self::Export(..) | Self::Module(..) => ASTFlags::empty(),
 
             #[cfg(not(feature = "no_closure"))]
             Self::Share(..) => ASTFlags::empty(),
@@ -729,10 +1002,15 @@ impl Stmt {
 
             Self::Expr(x) => x.start_position(),
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(.., pos) => *pos,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(.., pos) => *pos,
             #[cfg(not(feature = "no_module"))]
             Self::Export(.., pos) => *pos,
+            #[cfg(not(feature = "no_module"))]
+            Self::Module(.., pos) => *pos,
 
             #[cfg(not(feature = "no_closure"))]
             Self::Share(x) => x[0].0.pos,
@@ -761,10 +1039,15 @@ impl Stmt {
                 x.set_position(new_pos);
             }
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(.., pos) => *pos = new_pos,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(.., pos) => *pos = new_pos,
             #[cfg(not(feature = "no_module"))]
             Self::Export(.., pos) => *pos = new_pos,
+            #[cfg(not(feature = "no_module"))]
+            Self::Module(.., pos) => *pos = new_pos,
 
             #[cfg(not(feature = "no_closure"))]
             Self::Share(x) => x.iter_mut().for_each(|(x, _)| x.pos = new_pos),
@@ -780,18 +1063,18 @@ impl Stmt {
             | Self::Switch(..)
             | Self::Block(..)
             | Self::Expr(..)
-            | Self::FnCall(..) => true,
+            | Self::FnCall(..)
+            | Self::TryCatch(..) => true,
 
-            Self::Noop(..)
-            | Self::While(..)
-            | Self::Do(..)
-            | Self::For(..)
-            | Self::TryCatch(..) => false,
+            Self::Noop(..) | Self::While(..) | Self::Do(..) | Self::For(..) => false,
 
             Self::Var(..) | Self::Assignment(..) | Self::BreakLoop(..) | Self::Return(..) => false,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(..) => false,
+
             #[cfg(not(feature = "no_module"))]
-            Self::Import(..) | Self::Export(..) => false,
+            Self::Import(..) | Self::Export(..) | Self::Module(..) => false,
 
             #[cfg(not(feature = "no_closure"))]
             Self::Share(..) => false,
@@ -808,6 +1091,9 @@ impl Stmt {
             | Self::Block(..)
             | Self::TryCatch(..) => true,
 
+            #[cfg(not(feature = "no_module"))]
+            Self::Module(..) => true,
+
             // A No-op requires a semicolon in order to know it is an empty statement!
             Self::Noop(..) => false,
 
@@ -824,6 +1110,9 @@ impl Stmt {
             | Self::BreakLoop(..)
             | Self::Return(..) => false,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(..) => false,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(..) | Self::Export(..) => false,
 
@@ -855,6 +1144,10 @@ impl Stmt {
                         let block = &sw.expressions[r.index()];
                         block.lhs.is_pure() && block.rhs.is_pure()
                     })
+                    && sw.patterns.iter().all(|&(.., index)| {
+                        let block = &sw.expressions[index];
+                        block.lhs.is_pure() && block.rhs.is_pure()
+                    })
                     && sw.def_case.is_some()
                     && sw.expressions[sw.def_case.unwrap()].rhs.is_pure()
             }
@@ -878,16 +1171,20 @@ impl Stmt {
             Self::Var(..) | Self::Assignment(..) | Self::FnCall(..) => false,
             Self::Block(block, ..) => block.iter().all(Self::is_pure),
             Self::BreakLoop(..) | Self::Return(..) => false,
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(..) => false,
             Self::TryCatch(x, ..) => {
-                x.expr.is_pure()
-                    && x.body.iter().all(Self::is_pure)
-                    && x.branch.iter().all(Self::is_pure)
+                x.body.iter().all(Self::is_pure)
+                    && x.catches
+                        .iter()
+                        .all(|c| c.guard.is_pure() && c.body.iter().all(Self::is_pure))
+                    && x.finally.iter().all(Self::is_pure)
             }
 
             #[cfg(not(feature = "no_module"))]
             Self::Import(..) => false,
             #[cfg(not(feature = "no_module"))]
-            Self::Export(..) => false,
+            Self::Export(..) | Self::Module(..) => false,
 
             #[cfg(not(feature = "no_closure"))]
             Self::Share(..) => false,
@@ -920,7 +1217,7 @@ impl Stmt {
             Self::FnCall(x, ..) => x.name == KEYWORD_EVAL,
 
             #[cfg(not(feature = "no_module"))]
-            Self::Import(..) | Self::Export(..) => true,
+            Self::Import(..) | Self::Export(..) | Self::Module(..) => true,
 
             _ => false,
         }
@@ -960,6 +1257,44 @@ impl Stmt {
     pub const fn is_control_flow_break(&self) -> bool {
         matches!(self, Self::Return(..) | Self::BreakLoop(..))
     }
+    /// Get the [position][Position] of the first `yield` statement nested within this statement
+    /// (including itself), if any.
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    pub fn yield_position(&self) -> Option<Position> {
+        let mut pos = None;
+
+        self.walk(&mut Vec::new(), &mut |path| match path.last() {
+            Some(ASTNode::Stmt(Self::Yield(.., p))) => {
+                pos = Some(*p);
+                false
+            }
+            _ => true,
+        });
+
+        pos
+    }
+    /// Does this statement contain `yield` statements (including itself)?
+    ///
+    /// Unlike [`yield_position`][Stmt::yield_position], this does not walk the statement but
+    /// relies on the cache in its nested statements blocks (see [`StmtBlock::cache_yield`]).
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    pub const fn has_yield(&self) -> bool {
+        match self {
+            Self::Yield(..) => true,
+            Self::Block(block) => block.has_yield(),
+            Self::If(x, ..) | Self::While(x, ..) | Self::Do(x, ..) => {
+                x.body.has_yield() || x.branch.has_yield()
+            }
+            Self::For(x, ..) => x.2.body.has_yield() || x.2.branch.has_yield(),
+            _ => false,
+        }
+    }
     /// Return this [`Stmt`], replacing it with [`Stmt::Noop`].
     #[inline(always)]
     #[must_use]
@@ -1029,6 +1364,16 @@ impl Stmt {
                         return false;
                     }
                 }
+                for &(.., index) in &sw.patterns {
+                    let block = &sw.expressions[index];
+
+                    if !block.lhs.walk(path, on_node) {
+                        return false;
+                    }
+                    if !block.rhs.walk(path, on_node) {
+                        return false;
+                    }
+                }
                 if let Some(index) = sw.def_case {
                     let block = &sw.expressions[index];
                     if !block.lhs.walk(path, on_node) {
@@ -1087,7 +1432,17 @@ impl Stmt {
                         return false;
                     }
                 }
-                for s in &x.branch {
+                for c in &x.catches {
+                    if !c.guard.walk(path, on_node) {
+                        return false;
+                    }
+                    for s in &c.body {
+                        if !s.walk(path, on_node) {
+                            return false;
+                        }
+                    }
+                }
+                for s in &x.finally {
                     if !s.walk(path, on_node) {
                         return false;
                     }
@@ -1103,12 +1458,28 @@ impl Stmt {
                     return false;
                 }
             }
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(e, ..) if !e.walk(path, on_node) => return false,
             #[cfg(not(feature = "no_module"))]
             Self::Import(x, ..) => {
                 if !x.0.walk(path, on_node) {
                     return false;
                 }
             }
+            #[cfg(not(feature = "no_module"))]
+            Self::Module(x, ..) => {
+                for s in x.body.statements() {
+                    if !s.walk(path, on_node) {
+                        return false;
+                    }
+                }
+                #[cfg(not(feature = "no_function"))]
+                for s in x.body.iter_fn_def().flat_map(|f| f.body.iter()) {
+                    if !s.walk(path, on_node) {
+                        return false;
+                    }
+                }
+            }
             _ => (),
         }
 
@@ -1116,4 +1487,141 @@ impl Stmt {
 
         true
     }
+    /// _(internals)_ Recursively walk this statement with a [`VisitorMut`][super::VisitorMut]
+    /// that may rewrite nodes in place.
+    /// Return `false` from the visitor to terminate the walk.
+    /// Exported under the `internals` feature only.
+    ///
+    /// A function call statement is first visited as an [`Expr::FnCall`], which is turned back
+    /// into a statement afterwards.
+    #[cfg(feature = "internals")]
+    #[allow(clippy::collapsible_match)]
+    pub fn walk_mut(&mut self, visitor: &mut (impl super::VisitorMut + ?Sized)) -> bool {
+        match self {
+            Self::Var(x, ..) => {
+                if !x.1.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            Self::If(x, ..) | Self::While(x, ..) | Self::Do(x, ..) => {
+                if !x.expr.walk_mut(visitor) {
+                    return false;
+                }
+                for s in x
+                    .body
+                    .statements_mut()
+                    .iter_mut()
+                    .chain(x.branch.statements_mut())
+                {
+                    if !s.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::Switch(x, ..) => {
+                let (expr, sw) = &mut **x;
+
+                if !expr.walk_mut(visitor) {
+                    return false;
+                }
+                for block in &mut sw.expressions {
+                    if !block.lhs.walk_mut(visitor) {
+                        return false;
+                    }
+                    if !block.rhs.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::For(x, ..) => {
+                if !x.2.expr.walk_mut(visitor) {
+                    return false;
+                }
+                for s in x.2.body.statements_mut() {
+                    if !s.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::Assignment(x, ..) => {
+                if !x.1.lhs.walk_mut(visitor) {
+                    return false;
+                }
+                if !x.1.rhs.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            Self::FnCall(..) => {
+                let mut expr = match self.take() {
+                    Self::FnCall(x, pos) => Expr::FnCall(x, pos),
+                    _ => unreachable!("`Stmt::FnCall`"),
+                };
+                let completed = expr.walk_mut(visitor);
+
+                *self = match expr {
+                    Expr::FnCall(x, pos) => Self::FnCall(x, pos),
+                    expr => Self::Expr(expr.into()),
+                };
+
+                if !completed {
+                    return false;
+                }
+            }
+            Self::Block(x, ..) => {
+                for s in x.statements_mut() {
+                    if !s.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::TryCatch(x, ..) => {
+                for s in x.body.statements_mut() {
+                    if !s.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+                for c in &mut x.catches {
+                    if !c.guard.walk_mut(visitor) {
+                        return false;
+                    }
+                    for s in c.body.statements_mut() {
+                        if !s.walk_mut(visitor) {
+                            return false;
+                        }
+                    }
+                }
+                for s in x.finally.statements_mut() {
+                    if !s.walk_mut(visitor) {
+                        return false;
+                    }
+                }
+            }
+            Self::Expr(e) | Self::BreakLoop(Some(e), ..) | Self::Return(Some(e), ..) => {
+                if !e.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            #[cfg(not(feature = "no_function"))]
+            Self::Yield(e, ..) => {
+                if !e.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            #[cfg(not(feature = "no_module"))]
+            Self::Import(x, ..) => {
+                if !x.0.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            #[cfg(not(feature = "no_module"))]
+            Self::Module(x, ..) => {
+                if !x.body.walk_mut(visitor) {
+                    return false;
+                }
+            }
+            _ => (),
+        }
+
+        visitor.visit_stmt(self)
+    }
 }
@@ -1,4 +1,6 @@
-use rhai::debugger::{BreakPoint, DebuggerCommand, DebuggerEvent};
+use rhai::debugger::{
+    BreakPoint, BreakPointExpr, DebuggerCommand, DebuggerEvent, HitCondition, WatchedValue,
+};
 use rhai::{Dynamic, Engine, EvalAltResult, Position, Scope, INT};
 
 use std::{
@@ -148,6 +150,10 @@ fn print_debug_help() {
     println!(
         "break/b <func> <#args> => set a new break-point for a function call with #args arguments"
     );
+    println!("break/b ... if <expr>  => break only when an expression is true");
+    println!("break/b ... hit <#>    => break only on a hit count (e.g. 3, >= 3 or % 3)");
+    println!("break/b ... log <text> => print a message (with ${{...}}) instead of breaking");
+    println!("watch/w <variable>     => break when the value of a variable changes");
     println!("throw                  => throw a runtime exception");
     println!("throw <message...>     => throw an exception with string data");
     println!("throw <#>              => throw an exception with numeric data");
@@ -226,6 +232,87 @@ fn load_script(engine: &Engine) -> (rhai::AST, String) {
     }
 }
 
+// Split the conditions (` if <expr>`, ` hit <#>` and ` log <text>`) off a break command.
+fn split_conditions(input: &str) -> (&str, &str) {
+    let input = input.trim();
+
+    if !matches!(input.split_whitespace().next(), Some("break" | "b")) {
+        return (input, "");
+    }
+
+    let start = [" if ", " hit ", " log "]
+        .iter()
+        .filter_map(|clause| input.find(clause))
+        .min()
+        .unwrap_or(input.len());
+
+    input.split_at(start)
+}
+
+// Add a new break-point, with the conditions (if any) of the break command.
+fn add_break_point(context: &mut rhai::EvalContext, bp: BreakPoint, conditions: &str) {
+    let (conditions, log_message) = match conditions.split_once(" log ") {
+        Some((c, message)) => {
+            match BreakPointExpr::new_log_message(context.engine(), message.trim()) {
+                Ok(message) => (c, Some(message)),
+                Err(err) => {
+                    eprintln!("\x1b[31mInvalid log message: {err}\x1b[39m");
+                    return;
+                }
+            }
+        }
+        None => (conditions, None),
+    };
+    let (condition, hit_condition) = match conditions.split_once(" hit ") {
+        Some((c, hits)) => match hits.parse::<HitCondition>() {
+            Ok(hits) => (c.trim(), Some(hits)),
+            Err(()) => {
+                eprintln!("\x1b[31mInvalid hit condition: '{}'\x1b[39m", hits.trim());
+                return;
+            }
+        },
+        None => (conditions.trim(), None),
+    };
+    let condition = match condition.strip_prefix("if ") {
+        Some(expr) => match BreakPointExpr::new(context.engine(), expr.trim()) {
+            Ok(expr) => Some(expr),
+            Err(err) => {
+                eprintln!("\x1b[31mInvalid condition: {err}\x1b[39m");
+                return;
+            }
+        },
+        None if condition.is_empty() => None,
+        None => {
+            eprintln!("\x1b[31mInvalid condition: '{condition}'\x1b[39m");
+            return;
+        }
+    };
+
+    let bp = if condition.is_none() && hit_condition.is_none() && log_message.is_none() {
+        bp
+    } else {
+        BreakPoint::Conditional {
+            break_point: bp.into(),
+            condition,
+            hit_condition,
+            log_message,
+            hits: 0,
+        }
+    };
+
+    match bp.location() {
+        #[cfg(not(feature = "no_position"))]
+        BreakPoint::AtPosition { .. } => println!("Break-point added {bp}"),
+        _ => println!("Break-point added for {bp}"),
+    }
+
+    context
+        .global_runtime_state_mut()
+        .debugger_mut()
+        .break_points_mut()
+        .push(bp);
+}
+
 // Main callback for debugging.
 fn debug_callback(
     mut context: rhai::EvalContext,
@@ -247,7 +334,7 @@ fn debug_callback(
         DebuggerEvent::End => println!("\x1b[31m! Script end\x1b[39m"),
         DebuggerEvent::Step => (),
         DebuggerEvent::BreakPoint(n) => {
-            match context.global_runtime_state().debugger().break_points()[n] {
+            match *context.global_runtime_state().debugger().break_points()[n].location() {
                 #[cfg(not(feature = "no_position"))]
                 BreakPoint::AtPosition { .. } => (),
                 BreakPoint::AtFunctionName { ref name, .. }
@@ -258,6 +345,9 @@ fn debug_callback(
                 BreakPoint::AtProperty { ref name, .. } => {
                     println!("! Property {name} accessed.")
                 }
+                BreakPoint::AtVariableChange { ref name, .. } => {
+                    println!("! Variable {name} changed.")
+                }
                 _ => unreachable!(),
             }
         }
@@ -303,9 +393,12 @@ fn debug_callback(
 
         input.clear();
 
-        match stdin().read_line(&mut input) {
+        let read = stdin().read_line(&mut input);
+        let (command, conditions) = split_conditions(&input);
+
+        match read {
             Ok(0) => break Ok(DebuggerCommand::Continue),
-            Ok(_) => match input.split_whitespace().collect::<Vec<_>>().as_slice() {
+            Ok(_) => match command.split_whitespace().collect::<Vec<_>>().as_slice() {
                 ["help" | "h"] => print_debug_help(),
                 ["exit" | "quit" | "q" | "kill", ..] => {
                     println!("Script terminated. Bye!");
@@ -486,12 +579,7 @@ fn debug_callback(
                             args,
                             enabled: true,
                         };
-                        println!("Break-point added for {bp}");
-                        context
-                            .global_runtime_state_mut()
-                            .debugger_mut()
-                            .break_points_mut()
-                            .push(bp);
+                        add_break_point(&mut context, bp, conditions);
                     } else {
                         eprintln!("\x1b[31mInvalid number of arguments: '{args}'\x1b[39m");
                     }
@@ -503,12 +591,7 @@ fn debug_callback(
                         name: param[1..].into(),
                         enabled: true,
                     };
-                    println!("Break-point added for {bp}");
-                    context
-                        .global_runtime_state_mut()
-                        .debugger_mut()
-                        .break_points_mut()
-                        .push(bp);
+                    add_break_point(&mut context, bp, conditions);
                 }
                 // Numeric parameter
                 #[cfg(not(feature = "no_position"))]
@@ -526,12 +609,7 @@ fn debug_callback(
                             pos: Position::new(n as u16, 0),
                             enabled: true,
                         };
-                        println!("Break-point added {bp}");
-                        context
-                            .global_runtime_state_mut()
-                            .debugger_mut()
-                            .break_points_mut()
-                            .push(bp);
+                        add_break_point(&mut context, bp, conditions);
                     } else {
                         eprintln!("\x1b[31mInvalid line number: '{n}'\x1b[39m");
                     }
@@ -542,12 +620,7 @@ fn debug_callback(
                         name: param.trim().into(),
                         enabled: true,
                     };
-                    println!("Break-point added for {bp}");
-                    context
-                        .global_runtime_state_mut()
-                        .debugger_mut()
-                        .break_points_mut()
-                        .push(bp);
+                    add_break_point(&mut context, bp, conditions);
                 }
                 #[cfg(not(feature = "no_position"))]
                 ["break" | "b"] => {
@@ -556,7 +629,18 @@ fn debug_callback(
                         pos,
                         enabled: true,
                     };
-                    println!("Break-point added {bp}");
+                    add_break_point(&mut context, bp, conditions);
+                }
+                ["watch" | "w", var_name] => {
+                    let bp = BreakPoint::AtVariableChange {
+                        name: (*var_name).into(),
+                        value: context
+                            .scope()
+                            .get(var_name)
+                            .map(|v| WatchedValue(v.flatten_clone())),
+                        enabled: true,
+                    };
+                    println!("Break-point added for {bp}");
                     context
                         .global_runtime_state_mut()
                         .debugger_mut()
@@ -591,7 +675,46 @@ fn debug_callback(
     }
 }
 
+// Run as a debug adapter over the standard input and output.
+#[cfg(feature = "dap")]
+fn run_debug_adapter() -> ! {
+    use std::io::BufReader;
+
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "no_optimize"))]
+    engine.set_optimization_level(rhai::OptimizationLevel::None);
+
+    // Set a file module resolver without caching
+    #[cfg(not(feature = "no_module"))]
+    {
+        let mut resolver = rhai::module_resolvers::FileModuleResolver::new();
+        resolver.enable_cache(false);
+        engine.set_module_resolver(resolver);
+    }
+
+    let adapter = rhai::dap::DebugAdapter::new(BufReader::new(stdin()), stdout());
+
+    if let Err(err) = adapter.run(&mut engine) {
+        eprintln!("Error: {err}");
+        exit(1);
+    }
+
+    exit(0);
+}
+
 fn main() {
+    if env::args().nth(1).as_deref() == Some("--dap") {
+        #[cfg(feature = "dap")]
+        run_debug_adapter();
+
+        #[cfg(not(feature = "dap"))]
+        {
+            eprintln!("\x1b[31mThe debug adapter requires the `dap` feature.\x1b[39m");
+            exit(1);
+        }
+    }
+
     let title = format!("Rhai Debugger (version {})", env!("CARGO_PKG_VERSION"));
     println!("{title}");
     println!("{0:=<1$}", "", title.len());
@@ -0,0 +1,136 @@
+#[cfg(not(feature = "no_position"))]
+use rhai::{Engine, FormatOptions, ParseError};
+
+use std::process::exit;
+#[cfg(not(feature = "no_position"))]
+use std::{env, fs, io::Read};
+
+#[cfg(not(feature = "no_position"))]
+fn print_help() {
+    println!("Usage: rhai-fmt [--check] [--indent <width>] [--width <width>] [FILE...]");
+    println!();
+    println!("Formats each Rhai script file in place.");
+    println!("If no file is given, formats the standard input into the standard output.");
+    println!();
+    println!("Options:");
+    println!("  --check           Do not write any file; exit with an error if any file is not formatted");
+    println!("  --indent <width>  Number of spaces for each level of indentation (default 4)");
+    println!("  --width <width>   Maximum width of a line (default 100)");
+}
+
+/// Read a numeric option value.
+#[cfg(not(feature = "no_position"))]
+fn parse_width(option: &str, value: Option<String>) -> usize {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(width)) => width,
+        _ => {
+            eprintln!("Option {option} requires a number.");
+            exit(1);
+        }
+    }
+}
+
+/// Format a script, keeping the shebang line (if any).
+#[cfg(not(feature = "no_position"))]
+fn format(engine: &Engine, contents: &str, options: FormatOptions) -> Result<String, ParseError> {
+    if contents.starts_with("#!") {
+        let n = contents.find('\n').map_or(contents.len(), |n| n + 1);
+        let shebang = contents[..n].trim_end();
+        let script = engine.format_script(&contents[n..], options)?;
+        Ok(format!("{shebang}\n{script}"))
+    } else {
+        engine.format_script(contents, options)
+    }
+}
+
+#[cfg(not(feature = "no_position"))]
+fn main() {
+    let mut check = false;
+    let mut options = FormatOptions::new();
+    let mut filenames = Vec::new();
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--indent" => options = options.indent_width(parse_width(&arg, args.next())),
+            "--width" => options = options.line_width(parse_width(&arg, args.next())),
+            "-h" | "--help" => {
+                print_help();
+                exit(0);
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("Unknown option: {arg}");
+                print_help();
+                exit(1);
+            }
+            _ => filenames.push(arg),
+        }
+    }
+
+    let engine = Engine::new();
+
+    if filenames.is_empty() {
+        let mut contents = String::new();
+
+        if let Err(err) = std::io::stdin().read_to_string(&mut contents) {
+            eprintln!("Error reading standard input\n{err}");
+            exit(1);
+        }
+
+        match format(&engine, &contents, options) {
+            Ok(output) if check && output != contents => exit(1),
+            Ok(..) if check => (),
+            Ok(output) => print!("{output}"),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut failed = false;
+
+    for filename in filenames {
+        let contents = match fs::read_to_string(&filename) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("Error reading script file: {filename}\n{err}");
+                exit(1);
+            }
+        };
+
+        let output = match format(&engine, &contents, options) {
+            Ok(output) => output,
+            Err(err) => {
+                eprintln!("{filename}: {err}");
+                failed = true;
+                continue;
+            }
+        };
+
+        if output == contents {
+            continue;
+        }
+
+        if check {
+            println!("{filename} is not formatted");
+            failed = true;
+        } else if let Err(err) = fs::write(&filename, output) {
+            eprintln!("Error writing script file: {filename}\n{err}");
+            exit(1);
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
+
+#[cfg(feature = "no_position")]
+fn main() {
+    eprintln!("rhai-fmt is not available under `no_position`.");
+    exit(1);
+}
@@ -0,0 +1,30 @@
+use rhai::lsp::Server;
+use rhai::Engine;
+
+use std::{env, io, process::exit};
+
+fn print_help() {
+    println!("Usage: rhai-lsp");
+    println!();
+    println!("Runs a Rhai language server, speaking the Language Server Protocol over the standard input and output.");
+}
+
+fn main() {
+    if let Some(arg) = env::args().nth(1) {
+        if arg != "-h" && arg != "--help" {
+            eprintln!("Unknown option: {arg}");
+        }
+        print_help();
+        exit(0);
+    }
+
+    let mut server = Server::new(Engine::new());
+
+    if let Err(err) = server.run(io::stdin().lock(), io::stdout().lock()) {
+        eprintln!("Error: {err}");
+        exit(1);
+    }
+
+    // Exit with an error if the client did not shut down the server properly
+    exit(if server.is_shut_down() { 0 } else { 1 });
+}
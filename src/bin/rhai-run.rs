@@ -1,25 +1,25 @@
-use rhai::{Engine, EvalAltResult, Position};
+use rhai::{Diagnostic, Engine, EvalAltResult, Position};
 
 use std::{env, fs::File, io::Read, path::Path, process::exit};
 
-fn eprint_error(input: &str, mut err: EvalAltResult) {
-    fn eprint_line(lines: &[&str], pos: Position, err_msg: &str) {
-        let line = pos.line().unwrap();
-        let line_no = format!("{line}: ");
-
-        eprintln!("{line_no}{}", lines[line - 1]);
-
-        for (i, err_line) in err_msg.to_string().lines().enumerate() {
-            // Display position marker
-            println!(
-                "{0:>1$}{err_line}",
-                if i > 0 { "| " } else { "^ " },
-                line_no.len() + pos.position().unwrap() + 1,
-            );
-        }
-        eprintln!();
+fn eprint_line(lines: &[&str], pos: Position, err_msg: &str) {
+    let line = pos.line().unwrap();
+    let line_no = format!("{line}: ");
+
+    eprintln!("{line_no}{}", lines[line - 1]);
+
+    for (i, err_line) in err_msg.to_string().lines().enumerate() {
+        // Display position marker
+        println!(
+            "{0:>1$}{err_line}",
+            if i > 0 { "| " } else { "^ " },
+            line_no.len() + pos.position().unwrap() + 1,
+        );
     }
+    eprintln!();
+}
 
+fn eprint_error(input: &str, mut err: EvalAltResult) {
     // Do not use `line` because it "eats" the last empty line if the script ends with a newline.
     let lines: Vec<_> = input.split('\n').collect();
 
@@ -35,10 +35,49 @@ fn eprint_error(input: &str, mut err: EvalAltResult) {
     }
 }
 
+fn eprint_diagnostic(input: &str, diagnostic: &Diagnostic) {
+    let lines: Vec<_> = input.split('\n').collect();
+    let pos = diagnostic.position();
+    let msg = format!(
+        "{}: {} [{}]",
+        diagnostic.severity(),
+        diagnostic.diagnostic_type(),
+        diagnostic.code()
+    );
+
+    if pos.is_none() {
+        // No position
+        eprintln!("{msg}");
+    } else {
+        // Specific position
+        eprint_line(&lines, pos, &msg)
+    }
+}
+
+fn eprint_header(filename: &str) {
+    eprintln!("{:=<1$}", "", filename.len());
+    eprintln!("{filename}");
+    eprintln!("{:=<1$}", "", filename.len());
+    eprintln!();
+}
+
 fn main() {
     let mut contents = String::new();
+    let mut check = false;
+    let mut failed = false;
+
+    let filenames: Vec<_> = env::args()
+        .skip(1)
+        .filter(|arg| match arg.as_str() {
+            "--check" => {
+                check = true;
+                false
+            }
+            _ => true,
+        })
+        .collect();
 
-    for filename in env::args().skip(1) {
+    for filename in filenames {
         let filename = match Path::new(&filename).canonicalize() {
             Err(err) => {
                 eprintln!("Error script file path: {filename}\n{err}");
@@ -88,6 +127,26 @@ fn main() {
             &contents[..]
         };
 
+        if check {
+            // Only compile the script and print diagnostics
+            match engine.compile_with_diagnostics(contents) {
+                Ok((_, diagnostics)) if diagnostics.is_empty() => (),
+                Ok((_, diagnostics)) => {
+                    eprint_header(&filename.to_string_lossy());
+
+                    for diagnostic in &diagnostics {
+                        eprint_diagnostic(contents, diagnostic);
+                    }
+                }
+                Err(err) => {
+                    eprint_header(&filename.to_string_lossy());
+                    eprint_error(contents, err.into());
+                    failed = true;
+                }
+            }
+            continue;
+        }
+
         if let Err(err) = engine
             .compile(contents)
             .map_err(|err| err.into())
@@ -96,14 +155,12 @@ fn main() {
                 engine.run_ast(&ast)
             })
         {
-            let filename = filename.to_string_lossy();
-
-            eprintln!("{:=<1$}", "", filename.len());
-            eprintln!("{filename}");
-            eprintln!("{:=<1$}", "", filename.len());
-            eprintln!();
-
+            eprint_header(&filename.to_string_lossy());
             eprint_error(contents, *err);
         }
     }
+
+    if failed {
+        exit(1);
+    }
 }
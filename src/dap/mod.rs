@@ -0,0 +1,951 @@
+//! _(dap)_ A [Debug Adapter Protocol](https://microsoft.github.io/debug-adapter-protocol/)
+//! server for Rhai scripts.
+//! Exported under the `dap` feature only.
+//!
+//! The adapter drives the [debugging interface][crate::debugger] of an [`Engine`], so that any
+//! editor speaking the protocol can debug Rhai scripts. It supports:
+//!
+//! * launching a script file, optionally stopping on entry,
+//! * line break-points (mapped to [`BreakPoint::AtPosition`]) and function break-points (mapped
+//!   to [`BreakPoint::AtFunctionName`]),
+//! * conditions, hit conditions (`n`, `== n`, `>= n` or `% n`) and log messages (with `{...}`
+//!   interpolation) on both kinds of break-points (mapped to [`BreakPoint::Conditional`]),
+//! * continuing, stepping over (`next`), stepping in and stepping out,
+//! * stack traces built from the function call stack,
+//! * local variables (including `this`) and function arguments, with arrays and object maps
+//!   expandable,
+//! * evaluation of expressions in the scope of the current stop,
+//! * output of `print` and `debug`.
+//!
+//! A script runs on a single thread, with ID 1, which is always stopped as a whole.
+//!
+//! Line break-points stop once each time the script reaches their line, and steps go by line
+//! (unless a finer `granularity` is requested), even though the [`Debugger`] itself stops at
+//! every statement and expression.
+//!
+//! Line break-points inside modules imported by the launched script via a
+//! [`FileModuleResolver`][crate::module_resolvers::FileModuleResolver] are matched by the import
+//! path, which is the source name such modules are given. The path is taken relative to the
+//! directory of the launched script and without extension (e.g. a break-point in `lib/utils.rhai`
+//! matches the module from `import "lib/utils"`).
+//!
+//! [`DebugAdapter::run`] registers the adapter as the debugger of an [`Engine`] and talks to the
+//! client over a pair of streams (e.g. standard input and output, as in `rhai-dbg --dap`).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rhai::dap::DebugAdapter;
+//! use rhai::Engine;
+//! use std::io::{stdin, stdout, BufReader};
+//!
+//! let mut engine = Engine::new();
+//!
+//! DebugAdapter::new(BufReader::new(stdin()), stdout()).run(&mut engine)?;
+//! # Ok::<_, std::io::Error>(())
+//! ```
+
+pub use crate::api::framing::{read_message, write_message};
+
+use crate::ast::ASTNode;
+use crate::debugger::{BreakPoint, BreakPointExpr, Debugger, DebuggerCommand, DebuggerEvent};
+use crate::func::{locked_write, Locked, SendSync, Shared};
+use crate::{Dynamic, Engine, EvalContext, Position, RhaiResultOf, Scope, AST, ERR};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// ID of the only thread running a script.
+const THREAD_ID: i64 = 1;
+
+/// Canonicalize a path, keeping it unchanged if it cannot be resolved.
+fn canonical(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The items of an array or the properties of an object map.
+fn children(_value: &Dynamic) -> Vec<(String, Dynamic)> {
+    #[cfg(not(feature = "no_index"))]
+    if let Some(array) = _value.read_lock::<crate::Array>() {
+        return array
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (format!("[{i}]"), item.clone()))
+            .collect();
+    }
+    #[cfg(not(feature = "no_object"))]
+    if let Some(map) = _value.read_lock::<crate::Map>() {
+        return map
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+    }
+
+    Vec::new()
+}
+
+/// Wrap a break-point with the `condition`, `hitCondition` and `logMessage` of a source or
+/// function break-point, if any.
+fn conditional(
+    engine: &Engine,
+    args: &Value,
+    break_point: BreakPoint,
+) -> Result<BreakPoint, String> {
+    let text = |key: &str| args[key].as_str().map(str::trim).filter(|s| !s.is_empty());
+
+    let hit_condition = match text("hitCondition") {
+        Some(hits) => match hits.parse() {
+            Ok(hits) => Some(hits),
+            Err(()) => return Err(format!("invalid hit condition: {hits}")),
+        },
+        None => None,
+    };
+
+    // Log messages interpolate expressions in `{...}`
+    let log_message = text("logMessage").map(|message| {
+        let mut depth = 0_usize;
+        let mut text = String::with_capacity(message.len());
+
+        for ch in message.chars() {
+            match ch {
+                '{' if depth == 0 => {
+                    text.push('$');
+                    depth += 1;
+                }
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                '`' if depth == 0 => text.push('`'),
+                _ => (),
+            }
+            text.push(ch);
+        }
+
+        text
+    });
+    let log_message = match log_message {
+        Some(message) => match BreakPointExpr::new_log_message(engine, message) {
+            Ok(message) => Some(message),
+            Err(err) => return Err(format!("invalid log message: {err}")),
+        },
+        None => None,
+    };
+
+    let condition = match text("condition") {
+        Some(condition) => match BreakPointExpr::new(engine, condition) {
+            Ok(condition) => Some(condition),
+            Err(err) => return Err(format!("invalid condition: {err}")),
+        },
+        None => None,
+    };
+
+    if condition.is_none() && hit_condition.is_none() && log_message.is_none() {
+        return Ok(break_point);
+    }
+
+    Ok(BreakPoint::Conditional {
+        break_point: break_point.into(),
+        condition,
+        hit_condition,
+        log_message,
+        hits: 0,
+    })
+}
+
+/// The underlying break-point of a conditional break-point.
+#[cfg(not(feature = "no_position"))]
+fn location_mut(break_point: &mut BreakPoint) -> &mut BreakPoint {
+    match break_point {
+        BreakPoint::Conditional { break_point, .. } => location_mut(break_point),
+        break_point => break_point,
+    }
+}
+
+/// Stream of messages to the client.
+struct Output<W> {
+    /// The underlying stream.
+    writer: W,
+    /// Sequence number of the last message sent.
+    seq: i64,
+    /// The first error writing to the stream, after which nothing more is sent.
+    error: Option<io::Error>,
+}
+
+/// Shared handle to the stream of messages to the client.
+///
+/// The stream is locked only while sending a single message, so that `print` and `debug` can
+/// send output in the middle of a stop.
+struct Client<W>(Shared<Locked<Output<W>>>);
+
+impl<W> Clone for Client<W> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<W: Write> Client<W> {
+    /// Send a message, numbering it.
+    fn send(&self, mut message: Value) {
+        let Some(mut output) = locked_write(&self.0) else {
+            return;
+        };
+        let output = &mut *output;
+
+        if output.error.is_some() {
+            return;
+        }
+
+        output.seq += 1;
+        message["seq"] = output.seq.into();
+
+        if let Err(err) = write_message(&mut output.writer, &message.to_string())
+            .and_then(|()| output.writer.flush())
+        {
+            output.error = Some(err);
+        }
+    }
+    /// Send the response to a request, with either a body (if any) or an error message.
+    fn respond(&self, request: &Value, body: Result<Value, String>) {
+        let mut message = json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": body.is_ok(),
+        });
+
+        match body {
+            Ok(Value::Null) => (),
+            Ok(body) => message["body"] = body,
+            Err(err) => message["message"] = err.into(),
+        }
+
+        self.send(message);
+    }
+    /// Send an event.
+    fn event(&self, event: &str, body: Value) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+}
+
+/// A break-point set by the client.
+struct Marker {
+    /// ID of the break-point.
+    id: i64,
+    /// File of a line break-point.
+    file: Option<PathBuf>,
+    /// The break-point, whose source is filled in when synchronized into the [`Debugger`].
+    break_point: BreakPoint,
+}
+
+/// State of a debugging session.
+struct Session<R> {
+    /// Stream of messages from the client.
+    input: R,
+    /// Canonical path of the launched script.
+    program: Option<PathBuf>,
+    /// Stop at the first statement of the script?
+    stop_on_entry: bool,
+    /// Does the client number lines from 1?
+    lines_start_at1: bool,
+    /// Does the client number columns from 1?
+    columns_start_at1: bool,
+    /// Has the client finished configuring the session?
+    configured: bool,
+    /// Has the client disconnected?
+    disconnected: bool,
+    /// Break-points set by the client, in the order they are given to the [`Debugger`].
+    markers: Vec<Marker>,
+    /// ID of the last break-point set.
+    last_id: i64,
+    /// Children of the variables that can be expanded, keyed by variable reference minus one.
+    ///
+    /// Cleared whenever the script resumes.
+    variables: Vec<Vec<(String, Dynamic)>>,
+    /// Source, level of function calls and position of the last stop.
+    last_stop: Option<(Option<String>, usize, Position)>,
+    /// Command the script resumed with, while it is still on the line of the last stop.
+    ///
+    /// Break-points match every statement and expression on a line, and steps stop at each of
+    /// them, so events on the same line are skipped until the script moves to another line.
+    resume: Option<DebuggerCommand>,
+}
+
+impl<R: BufRead> Session<R> {
+    /// Read the next request from the client.
+    ///
+    /// Returns [`None`] (and marks the session as disconnected) at the end of the stream.
+    fn next_request(&mut self) -> Option<Value> {
+        loop {
+            let Ok(Some(message)) = read_message(&mut self.input) else {
+                self.disconnected = true;
+                return None;
+            };
+
+            match serde_json::from_str::<Value>(&message) {
+                Ok(message) if message["type"] == "request" => return Some(message),
+                _ => (),
+            }
+        }
+    }
+    /// Source name of a script file, as given by the [`Engine`].
+    #[cfg_attr(feature = "no_position", allow(dead_code))]
+    fn source_of(&self, file: &Path) -> String {
+        if self.program.as_deref() == Some(file) {
+            return file.to_string_lossy().into();
+        }
+
+        let base = self.program.as_deref().and_then(Path::parent);
+        let path = base.and_then(|base| file.strip_prefix(base).ok());
+
+        path.unwrap_or(file)
+            .with_extension("")
+            .to_string_lossy()
+            .into()
+    }
+    /// Script file of a source name given by the [`Engine`].
+    fn file_of(&self, source: &str) -> PathBuf {
+        let path = Path::new(source);
+
+        if self.program.as_deref() == Some(path) {
+            return path.to_path_buf();
+        }
+
+        let mut file = match self.program.as_deref().and_then(Path::parent) {
+            Some(base) => base.join(path),
+            None => path.to_path_buf(),
+        };
+        if file.extension().is_none() {
+            file.set_extension("rhai");
+        }
+        file
+    }
+    /// Is the script still on the line of the last stop, at the same level of function calls?
+    ///
+    /// Coming back to the exact position of the last stop (e.g. in a loop) starts a new pass over
+    /// the line.
+    fn is_on_line(&self, source: Option<&str>, level: usize, pos: Position) -> bool {
+        match self.last_stop {
+            Some((ref last_source, last_level, last_pos)) => {
+                last_source.as_deref() == source
+                    && last_level == level
+                    && last_pos.line().is_some()
+                    && last_pos.line() == pos.line()
+                    && last_pos != pos
+            }
+            None => false,
+        }
+    }
+    /// Line and column numbers of a [`Position`], as expected by the client.
+    fn client_position(&self, pos: Position) -> (i64, i64) {
+        let line = pos.line().unwrap_or(0) as i64;
+        let column = pos.position().unwrap_or(0) as i64;
+
+        (
+            if self.lines_start_at1 || line == 0 {
+                line
+            } else {
+                line - 1
+            },
+            if self.columns_start_at1 || column == 0 {
+                column
+            } else {
+                column - 1
+            },
+        )
+    }
+    /// Copy the break-points set by the client into a [`Debugger`].
+    fn sync_break_points(&self, debugger: &mut Debugger) {
+        *debugger.break_points_mut() = self
+            .markers
+            .iter()
+            .map(|marker| {
+                #[allow(unused_mut)]
+                let mut break_point = marker.break_point.clone();
+
+                #[cfg(not(feature = "no_position"))]
+                if let (Some(file), BreakPoint::AtPosition { source, .. }) =
+                    (&marker.file, location_mut(&mut break_point))
+                {
+                    *source = Some(self.source_of(file).into());
+                }
+
+                break_point
+            })
+            .collect();
+    }
+    /// Compile the script to launch.
+    fn launch(&mut self, engine: &Engine, args: &Value) -> Result<AST, String> {
+        let Some(program) = args["program"].as_str() else {
+            return Err("missing program to launch".into());
+        };
+
+        let path = canonical(program);
+        let ast = engine
+            .compile_file(path.clone())
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+
+        self.stop_on_entry = args["stopOnEntry"].as_bool().unwrap_or(false);
+        self.program = Some(path);
+
+        Ok(ast)
+    }
+    /// Set the line break-points of a file.
+    fn set_break_points(&mut self, engine: &Engine, args: &Value) -> Value {
+        let file = canonical(args["source"]["path"].as_str().unwrap_or_default());
+
+        self.markers.retain(|m| m.file.as_ref() != Some(&file));
+
+        let lines = args["breakpoints"].as_array().into_iter().flatten();
+
+        let break_points = lines
+            .map(|bp| {
+                let line = bp["line"].as_i64().unwrap_or(0) + i64::from(!self.lines_start_at1);
+
+                #[cfg(not(feature = "no_position"))]
+                if let Ok(line @ 1..) = u16::try_from(line) {
+                    let column = match bp["column"].as_i64() {
+                        Some(col) => col + i64::from(!self.columns_start_at1),
+                        None => 0,
+                    };
+
+                    let break_point = BreakPoint::AtPosition {
+                        source: None,
+                        pos: Position::new(line, u16::try_from(column).unwrap_or(0)),
+                        enabled: true,
+                    };
+                    let break_point = match conditional(engine, bp, break_point) {
+                        Ok(break_point) => break_point,
+                        Err(message) => return json!({ "verified": false, "message": message }),
+                    };
+
+                    self.last_id += 1;
+                    self.markers.push(Marker {
+                        id: self.last_id,
+                        file: Some(file.clone()),
+                        break_point,
+                    });
+
+                    return json!({
+                        "id": self.last_id,
+                        "verified": true,
+                        "source": args["source"],
+                        "line": bp["line"],
+                    });
+                }
+
+                let _ = line;
+
+                json!({ "verified": false, "message": "invalid line" })
+            })
+            .collect::<Vec<_>>();
+
+        json!({ "breakpoints": break_points })
+    }
+    /// Set the function break-points.
+    fn set_function_break_points(&mut self, engine: &Engine, args: &Value) -> Value {
+        self.markers.retain(|m| m.file.is_some());
+
+        let names = args["breakpoints"].as_array().into_iter().flatten();
+
+        let break_points = names
+            .map(|bp| match bp["name"].as_str().map(str::trim) {
+                Some(name) if !name.is_empty() => {
+                    let break_point = BreakPoint::AtFunctionName {
+                        name: name.into(),
+                        enabled: true,
+                    };
+                    let break_point = match conditional(engine, bp, break_point) {
+                        Ok(break_point) => break_point,
+                        Err(message) => return json!({ "verified": false, "message": message }),
+                    };
+
+                    self.last_id += 1;
+                    self.markers.push(Marker {
+                        id: self.last_id,
+                        file: None,
+                        break_point,
+                    });
+                    json!({ "id": self.last_id, "verified": true })
+                }
+                _ => json!({ "verified": false, "message": "missing function name" }),
+            })
+            .collect::<Vec<_>>();
+
+        json!({ "breakpoints": break_points })
+    }
+    /// Handle a request that is valid whether the script is stopped or not.
+    fn handle<W: Write>(&mut self, engine: &Engine, client: &Client<W>, request: &Value) {
+        let args = &request["arguments"];
+
+        let body = match request["command"].as_str().unwrap_or_default() {
+            "initialize" => {
+                self.lines_start_at1 = args["linesStartAt1"].as_bool().unwrap_or(true);
+                self.columns_start_at1 = args["columnsStartAt1"].as_bool().unwrap_or(true);
+
+                client.respond(
+                    request,
+                    Ok(json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsFunctionBreakpoints": true,
+                        "supportsConditionalBreakpoints": true,
+                        "supportsHitConditionalBreakpoints": true,
+                        "supportsLogPoints": true,
+                        "supportsEvaluateForHovers": true,
+                        "supportsTerminateRequest": true,
+                    })),
+                );
+                client.event("initialized", json!({}));
+                return;
+            }
+            "setBreakpoints" => Ok(self.set_break_points(engine, args)),
+            "setFunctionBreakpoints" => Ok(self.set_function_break_points(engine, args)),
+            "setExceptionBreakpoints" => Ok(json!({ "breakpoints": [] })),
+            "configurationDone" => {
+                self.configured = true;
+                Ok(Value::Null)
+            }
+            "threads" => Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })),
+            "disconnect" | "terminate" => {
+                self.disconnected = true;
+                Ok(Value::Null)
+            }
+            "launch" => Err("a script is already launched".into()),
+            "stackTrace" | "scopes" | "variables" | "evaluate" | "continue" | "next" | "stepIn"
+            | "stepOut" => Err("the script is not stopped".into()),
+            command => Err(format!("unsupported request: {command}")),
+        };
+
+        client.respond(request, body);
+    }
+    /// Record a variable, making its children (if any) available to the client.
+    fn variable(&mut self, engine: &Engine, name: &str, value: &Dynamic) -> Value {
+        let children = children(value);
+
+        let reference = if children.is_empty() {
+            0
+        } else {
+            self.variables.push(children);
+            self.variables.len()
+        };
+
+        json!({
+            "name": name,
+            "value": format!("{value:?}"),
+            "type": engine.map_type_name(value.type_name()),
+            "variablesReference": reference,
+        })
+    }
+    /// A stack frame.
+    fn frame(&self, id: usize, name: &str, source: Option<&str>, pos: Position) -> Value {
+        let (line, column) = self.client_position(pos);
+
+        let mut frame = json!({ "id": id, "name": name, "line": line, "column": column });
+
+        if let Some(source) = source {
+            let file = self.file_of(source);
+            let name = file
+                .file_name()
+                .map_or(source.into(), |f| f.to_string_lossy());
+
+            frame["source"] = json!({ "name": name, "path": file.to_string_lossy() });
+        }
+
+        frame
+    }
+    /// The stack trace of a stop, with the current function first.
+    fn stack_trace(&self, context: &EvalContext, source: Option<&str>, pos: Position) -> Value {
+        let call_stack = context.global_runtime_state().debugger().call_stack();
+        let program = self.program.as_ref().map(|p| p.to_string_lossy());
+
+        // Name and source of the function running at a particular level of function calls
+        let function = |level: usize| match call_stack[..level].last() {
+            Some(f) => (f.fn_name.as_str(), f.source.as_deref()),
+            None => ("main", program.as_deref()),
+        };
+
+        let mut frames = vec![self.frame(0, function(call_stack.len()).0, source, pos)];
+
+        for (level, call) in call_stack.iter().enumerate().rev() {
+            let (name, source) = function(level);
+            frames.push(self.frame(frames.len(), name, source, call.pos));
+        }
+
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+    /// The scopes of a stack frame.
+    ///
+    /// The current function has all its local variables, while callers have only their arguments.
+    fn scopes(&mut self, context: &EvalContext, args: &Value) -> Value {
+        let frame = args["frameId"].as_u64().unwrap_or(0) as usize;
+
+        let (name, hint, variables) = if frame == 0 {
+            let mut seen = BTreeSet::new();
+
+            let mut locals = context
+                .scope()
+                .iter_raw()
+                .filter(|(name, ..)| seen.insert(*name))
+                .map(|(name, .., value)| (name.to_string(), value.flatten_clone()))
+                .collect::<Vec<_>>();
+            locals.reverse();
+
+            if let Some(this) = context.this_ptr() {
+                locals.insert(0, ("this".into(), this.clone()));
+            }
+
+            ("Locals", "locals", locals)
+        } else {
+            let call_stack = context.global_runtime_state().debugger().call_stack();
+
+            let arguments = call_stack
+                .len()
+                .checked_sub(frame + 1)
+                .map(|level| &call_stack[level].args)
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .map(|(i, arg)| (format!("[{i}]"), arg.clone()))
+                .collect();
+
+            ("Arguments", "arguments", arguments)
+        };
+
+        self.variables.push(variables);
+
+        json!({
+            "scopes": [{
+                "name": name,
+                "presentationHint": hint,
+                "variablesReference": self.variables.len(),
+                "expensive": false,
+            }]
+        })
+    }
+    /// The children of an expandable variable (or of a scope).
+    fn variables(&mut self, engine: &Engine, args: &Value) -> Result<Value, String> {
+        let reference = args["variablesReference"].as_u64().unwrap_or(0) as usize;
+
+        let Some(children) = reference
+            .checked_sub(1)
+            .and_then(|i| self.variables.get(i))
+            .cloned()
+        else {
+            return Err(format!("invalid variables reference: {reference}"));
+        };
+
+        let variables = children
+            .iter()
+            .map(|(name, value)| self.variable(engine, name, value))
+            .collect::<Vec<_>>();
+
+        Ok(json!({ "variables": variables }))
+    }
+    /// Evaluate an expression in the scope of the current function.
+    fn evaluate(&mut self, context: &mut EvalContext, args: &Value) -> Result<Value, String> {
+        if args["frameId"].as_u64().unwrap_or(0) != 0 {
+            return Err("expressions can only be evaluated in the current stack frame".into());
+        }
+
+        let expression = args["expression"].as_str().unwrap_or_default();
+
+        let value = context
+            .eval_expression_suspended(expression)
+            .map_err(|err| err.to_string())?;
+
+        let variable = self.variable(context.engine(), "", &value);
+
+        Ok(json!({
+            "result": variable["value"],
+            "type": variable["type"],
+            "variablesReference": variable["variablesReference"],
+        }))
+    }
+}
+
+/// Stop the script and serve the client until it resumes execution.
+fn on_debugger<R: BufRead, W: Write>(
+    session: &Locked<Session<R>>,
+    client: &Client<W>,
+    mut context: EvalContext,
+    event: DebuggerEvent,
+    node: ASTNode,
+    source: Option<&str>,
+    pos: Position,
+) -> RhaiResultOf<DebuggerCommand> {
+    let Some(mut session) = locked_write(session) else {
+        return Ok(DebuggerCommand::Continue);
+    };
+    let session = &mut *session;
+
+    let level = context.global_runtime_state().debugger().call_stack().len();
+
+    let event = match (event, session.resume) {
+        (DebuggerEvent::Step | DebuggerEvent::BreakPoint(..), Some(command))
+            if session.is_on_line(source, level, pos) =>
+        {
+            // Watch for the script to move to another line
+            return Ok(match command {
+                DebuggerCommand::Continue => DebuggerCommand::StepOver,
+                _ => command,
+            });
+        }
+        (
+            DebuggerEvent::FunctionExitWithValue(..) | DebuggerEvent::FunctionExitWithError(..),
+            Some(DebuggerCommand::Continue | DebuggerCommand::StepInto),
+        ) => {
+            // Only stepping out stops at the end of a function
+            return Ok(DebuggerCommand::StepInto);
+        }
+        (DebuggerEvent::Step, Some(DebuggerCommand::Continue)) => {
+            // Moved to another line, which may have a break-point
+            session.resume = None;
+
+            match context.break_point(node)? {
+                Some(n) => DebuggerEvent::BreakPoint(n),
+                None => return Ok(DebuggerCommand::Continue),
+            }
+        }
+        _ => {
+            session.resume = None;
+            event
+        }
+    };
+
+    let (reason, hit) = match event {
+        DebuggerEvent::Start if session.stop_on_entry => ("entry", None),
+        DebuggerEvent::Start | DebuggerEvent::End => return Ok(DebuggerCommand::Continue),
+        DebuggerEvent::Step
+        | DebuggerEvent::FunctionExitWithValue(..)
+        | DebuggerEvent::FunctionExitWithError(..) => ("step", None),
+        DebuggerEvent::BreakPoint(n) => ("breakpoint", session.markers.get(n).map(|m| m.id)),
+    };
+
+    let mut body = json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true });
+    if let Some(id) = hit {
+        body["hitBreakpointIds"] = json!([id]);
+    }
+    client.event("stopped", body);
+
+    session.last_stop = Some((source.map(Into::into), level, pos));
+
+    loop {
+        let Some(request) = session.next_request() else {
+            return Err(ERR::ErrorTerminated(Dynamic::UNIT, pos).into());
+        };
+        let args = &request["arguments"];
+
+        let command = match request["command"].as_str().unwrap_or_default() {
+            "continue" => DebuggerCommand::Continue,
+            "next" => DebuggerCommand::Next,
+            "stepIn" => DebuggerCommand::StepInto,
+            "stepOut" => DebuggerCommand::FunctionExit,
+            "stackTrace" => {
+                let body = session.stack_trace(&context, source, pos);
+                client.respond(&request, Ok(body));
+                continue;
+            }
+            "scopes" => {
+                let body = session.scopes(&context, args);
+                client.respond(&request, Ok(body));
+                continue;
+            }
+            "variables" => {
+                let body = session.variables(context.engine(), args);
+                client.respond(&request, body);
+                continue;
+            }
+            "evaluate" => {
+                let body = session.evaluate(&mut context, args);
+                client.respond(&request, body);
+                continue;
+            }
+            command => {
+                session.handle(context.engine(), client, &request);
+
+                if session.disconnected {
+                    return Err(ERR::ErrorTerminated(Dynamic::UNIT, pos).into());
+                }
+
+                // Keep the hit counts unless break-points are changed
+                if matches!(command, "setBreakpoints" | "setFunctionBreakpoints") {
+                    let debugger = context.global_runtime_state_mut().debugger_mut();
+                    session.sync_break_points(debugger);
+                }
+                continue;
+            }
+        };
+
+        let body = match command {
+            DebuggerCommand::Continue => json!({ "allThreadsContinued": true }),
+            _ => Value::Null,
+        };
+        client.respond(&request, Ok(body));
+
+        session.variables.clear();
+
+        // Unless asked for finer steps, skip the rest of the line
+        session.resume = match command {
+            DebuggerCommand::Next | DebuggerCommand::StepInto
+                if !matches!(args["granularity"].as_str(), None | Some("line")) =>
+            {
+                None
+            }
+            DebuggerCommand::FunctionExit => None,
+            _ => Some(command),
+        };
+
+        return Ok(command);
+    }
+}
+
+/// A debug adapter for Rhai scripts.
+///
+/// Not available under `no_std`.
+pub struct DebugAdapter<R, W> {
+    /// State of the debugging session.
+    session: Shared<Locked<Session<R>>>,
+    /// Stream of messages to the client.
+    client: Client<W>,
+}
+
+impl<R: BufRead + SendSync + 'static, W: Write + SendSync + 'static> DebugAdapter<R, W> {
+    /// Create a new [`DebugAdapter`] reading requests from `input` and writing responses and
+    /// events into `output`.
+    #[must_use]
+    pub fn new(input: R, output: W) -> Self {
+        let session = Session {
+            input,
+            program: None,
+            stop_on_entry: false,
+            lines_start_at1: true,
+            columns_start_at1: true,
+            configured: false,
+            disconnected: false,
+            markers: Vec::new(),
+            last_id: 0,
+            variables: Vec::new(),
+            last_stop: None,
+            resume: None,
+        };
+        let output = Output {
+            writer: output,
+            seq: 0,
+            error: None,
+        };
+
+        Self {
+            session: Locked::new(session).into(),
+            client: Client(Locked::new(output).into()),
+        }
+    }
+    /// Run a debugging session until the client disconnects.
+    ///
+    /// The script to debug is given by the `program` argument of the `launch` request.
+    /// It is run after the client sends `configurationDone`.
+    ///
+    /// The adapter is registered into the [`Engine`] as its debugger, and as its `print` and
+    /// `debug` handlers, and is left there afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error writing to the output stream.
+    pub fn run(&self, engine: &mut Engine) -> io::Result<()> {
+        if let Some(ast) = self.configure(engine) {
+            self.debug(engine, &ast);
+        }
+
+        match locked_write(&self.client.0).and_then(|mut output| output.error.take()) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+    /// Serve the client until a script is launched and the configuration is done.
+    ///
+    /// Returns [`None`] if the client disconnects before that.
+    fn configure(&self, engine: &Engine) -> Option<AST> {
+        let mut session = locked_write(&self.session).unwrap();
+        let mut ast = None;
+
+        loop {
+            let request = session.next_request()?;
+
+            if request["command"] == "launch" && ast.is_none() {
+                let result = session.launch(engine, &request["arguments"]);
+                ast = result.as_ref().ok().cloned();
+                self.client.respond(&request, result.map(|_| Value::Null));
+            } else {
+                session.handle(engine, &self.client, &request);
+            }
+
+            if session.disconnected {
+                return None;
+            }
+            if session.configured && ast.is_some() {
+                return ast;
+            }
+        }
+    }
+    /// Run the launched script under the debugger, then serve the client until it disconnects.
+    fn debug(&self, engine: &mut Engine, ast: &AST) {
+        let client = self.client.clone();
+        engine.on_print(move |text| {
+            client.event(
+                "output",
+                json!({ "category": "stdout", "output": format!("{text}\n") }),
+            );
+        });
+
+        let client = self.client.clone();
+        engine.on_debug(move |text, _, _| {
+            client.event(
+                "output",
+                json!({ "category": "console", "output": format!("{text}\n") }),
+            );
+        });
+
+        let session = self.session.clone();
+        let init_session = self.session.clone();
+        let client = self.client.clone();
+
+        #[allow(deprecated)]
+        engine.register_debugger(
+            move |_, mut debugger| {
+                if let Some(session) = locked_write(&init_session) {
+                    session.sync_break_points(&mut debugger);
+                }
+                debugger
+            },
+            move |context, event, node, source, pos| {
+                on_debugger(&session, &client, context, event, node, source, pos)
+            },
+        );
+
+        let result = engine.run_ast_with_scope(&mut Scope::new(), ast);
+
+        let mut session = locked_write(&self.session).unwrap();
+
+        if session.disconnected {
+            return;
+        }
+
+        if let Err(ref err) = result {
+            self.client.event(
+                "output",
+                json!({ "category": "stderr", "output": format!("{err}\n") }),
+            );
+        }
+
+        self.client
+            .event("exited", json!({ "exitCode": i64::from(result.is_err()) }));
+        self.client.event("terminated", json!({}));
+
+        while let Some(request) = session.next_request() {
+            session.handle(engine, &self.client, &request);
+
+            if session.disconnected {
+                break;
+            }
+        }
+    }
+}
@@ -58,6 +58,12 @@ pub const OP_CONTAINS: &str = "contains";
 /// Standard not operator.
 pub const OP_NOT: &str = Token::Bang.literal_syntax();
 
+/// Standard string formatting function.
+///
+/// A format specification in an interpolated string (e.g. `${x:.2}`) is implemented as a call to
+/// this function.
+pub const FN_FORMAT: &str = "format";
+
 /// Separator for namespaces.
 #[cfg(not(feature = "no_module"))]
 pub const NAMESPACE_SEPARATOR: &str = Token::DoubleColon.literal_syntax();
@@ -101,6 +107,8 @@ pub struct Engine {
 
     /// A set of symbols to disable.
     pub(crate) disabled_symbols: BTreeSet<Identifier>,
+    /// A map containing deprecated symbols and notes on their replacements.
+    pub(crate) deprecated_symbols: std::collections::BTreeMap<Identifier, String>,
     /// A map containing custom keywords and precedence to recognize.
     #[cfg(not(feature = "no_custom_syntax"))]
     pub(crate) custom_keywords: std::collections::BTreeMap<Identifier, Option<Precedence>>,
@@ -150,6 +158,17 @@ pub struct Engine {
     #[cfg(not(feature = "unchecked"))]
     pub(crate) limits: crate::api::limits::Limits,
 
+    /// Integer overflow behavior.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) integer_overflow: crate::Overflow,
+
+    /// Statement and branch coverage being collected.
+    #[cfg(feature = "coverage")]
+    pub(crate) coverage: Option<Locked<crate::eval::Coverage>>,
+    /// Call-level profile being collected.
+    #[cfg(feature = "profiling")]
+    pub(crate) profiler: Option<Locked<crate::eval::Profile>>,
+
     /// Callback closure for debugging.
     #[cfg(feature = "debugging")]
     pub(crate) debugger_interface: Option<(
@@ -170,6 +189,7 @@ impl fmt::Debug for Engine {
         f.field("global_sub_modules", &self.global_sub_modules);
 
         f.field("disabled_symbols", &self.disabled_symbols);
+        f.field("deprecated_symbols", &self.deprecated_symbols);
 
         #[cfg(not(feature = "no_custom_syntax"))]
         f.field("custom_keywords", &self.custom_keywords).field(
@@ -195,7 +215,13 @@ impl fmt::Debug for Engine {
         f.field("optimization_level", &self.optimization_level);
 
         #[cfg(not(feature = "unchecked"))]
-        f.field("limits", &self.limits);
+        f.field("limits", &self.limits)
+            .field("integer_overflow", &self.integer_overflow);
+
+        #[cfg(feature = "coverage")]
+        f.field("coverage", &self.coverage.is_some());
+        #[cfg(feature = "profiling")]
+        f.field("profiler", &self.profiler.is_some());
 
         #[cfg(feature = "debugging")]
         f.field("debugger_interface", &self.debugger_interface.is_some());
@@ -246,6 +272,7 @@ impl Engine {
 
         interned_strings: None,
         disabled_symbols: BTreeSet::new(),
+        deprecated_symbols: std::collections::BTreeMap::new(),
         #[cfg(not(feature = "no_custom_syntax"))]
         custom_keywords: std::collections::BTreeMap::new(),
         #[cfg(not(feature = "no_custom_syntax"))]
@@ -280,6 +307,14 @@ impl Engine {
         #[cfg(not(feature = "unchecked"))]
         limits: crate::api::limits::Limits::new(),
 
+        #[cfg(not(feature = "unchecked"))]
+        integer_overflow: crate::Overflow::Checked,
+
+        #[cfg(feature = "coverage")]
+        coverage: None,
+        #[cfg(feature = "profiling")]
+        profiler: None,
+
         #[cfg(feature = "debugging")]
         debugger_interface: None,
     };
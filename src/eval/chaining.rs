@@ -172,6 +172,20 @@ impl Engine {
                 })
             }
 
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Tuple(tuple, ..)) => {
+                // val_tuple[idx]
+                let index = idx
+                    .as_int()
+                    .map_err(|typ| self.make_type_mismatch_err::<crate::INT>(typ, idx_pos))?;
+                let len = tuple.len();
+                let tuple_idx = super::calc_index(len, index, true, || {
+                    ERR::ErrorArrayBounds(len, index, idx_pos).into()
+                })?;
+
+                tuple.get_mut(tuple_idx).unwrap().try_into()
+            }
+
             #[cfg(not(feature = "no_object"))]
             Dynamic(Union::Map(map, ..)) => {
                 // val_map[idx]
@@ -203,6 +217,20 @@ impl Engine {
                 }
             }
 
+            #[cfg(not(feature = "no_object"))]
+            Dynamic(Union::Struct(value, ..)) => {
+                // val_struct[field]
+                let index = idx.read_lock::<crate::ImmutableString>().ok_or_else(|| {
+                    self.make_type_mismatch_err::<crate::ImmutableString>(idx.type_name(), idx_pos)
+                })?;
+
+                // Fields of a `struct` are fixed, so an unknown field is always an error
+                value.get_mut(index.as_str()).map_or_else(
+                    || Err(ERR::ErrorPropertyNotFound(index.to_string(), idx_pos).into()),
+                    |value| value.try_into(),
+                )
+            }
+
             #[cfg(not(feature = "no_index"))]
             Dynamic(Union::Int(value, ..))
                 if idx.is::<crate::ExclusiveRange>() || idx.is::<crate::InclusiveRange>() =>
@@ -688,6 +716,12 @@ impl Engine {
         match ChainType::from(parent) {
             #[cfg(not(feature = "no_index"))]
             ChainType::Indexing => {
+                #[cfg(feature = "coverage")]
+                if parent.options().intersects(ASTFlags::NEGATED) {
+                    let taken = usize::from(target.as_ref().is_unit());
+                    self.cov_branch(global, op_pos, crate::eval::BranchKind::Elvis, 2, taken);
+                }
+
                 // Check for existence with the null conditional operator
                 if parent.options().intersects(ASTFlags::NEGATED) && target.as_ref().is_unit() {
                     return Ok((Dynamic::UNIT, false));
@@ -858,12 +892,20 @@ impl Engine {
 
             #[cfg(not(feature = "no_object"))]
             ChainType::Dotting => {
+                #[cfg(feature = "coverage")]
+                if parent.options().intersects(ASTFlags::NEGATED) {
+                    let taken = usize::from(target.as_ref().is_unit());
+                    self.cov_branch(global, op_pos, crate::eval::BranchKind::Elvis, 2, taken);
+                }
+
                 // Check for existence with the Elvis operator
                 if parent.options().intersects(ASTFlags::NEGATED) && target.as_ref().is_unit() {
                     return Ok((Dynamic::UNIT, false));
                 }
 
-                match (rhs, new_val, target.as_ref().is_map()) {
+                let is_record = target.as_ref().is_map() || target.as_ref().is_struct();
+
+                match (rhs, new_val, is_record) {
                     // xxx.fn_name(...) = ???
                     (Expr::MethodCall(..), Some(..), ..) => {
                         unreachable!("method call cannot be assigned to")
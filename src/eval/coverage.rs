@@ -0,0 +1,397 @@
+//! Module defining the collection of statement and branch coverage.
+#![cfg(feature = "coverage")]
+
+use super::GlobalRuntimeState;
+use crate::ast::{ASTFlags, ASTNode, Expr, Stmt};
+use crate::func::locked_write;
+use crate::{Engine, ImmutableString, Position, AST};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// The kind of a branching construct.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum BranchKind {
+    /// An `if` statement or expression.
+    ///
+    /// The outcomes are `true` then `false`.
+    If,
+    /// A `switch` statement or expression.
+    ///
+    /// The outcomes are the cases in order, including the default case (`_`), followed by no
+    /// match if there is no default case.
+    Switch,
+    /// A `??` operator (with any number of operands).
+    ///
+    /// The outcomes are the operands that provide the result.
+    Coalesce,
+    /// A `?.` or `?[` operator.
+    ///
+    /// The outcomes are access then `()` (i.e. short-circuited).
+    Elvis,
+}
+
+/// Coverage of a branching construct.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BranchCoverage {
+    /// Kind of branching construct.
+    pub kind: BranchKind,
+    /// Number of times each outcome is taken.
+    pub hits: Vec<usize>,
+}
+
+impl BranchCoverage {
+    /// Create a new [`BranchCoverage`] with no outcome taken.
+    #[inline(always)]
+    #[must_use]
+    pub fn new(kind: BranchKind, outcomes: usize) -> Self {
+        Self {
+            kind,
+            hits: vec![0; outcomes],
+        }
+    }
+    /// Is the branching construct ever reached?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_reached(&self) -> bool {
+        self.hits.iter().any(|&n| n > 0)
+    }
+}
+
+/// Coverage of the statements and branches of a single script source.
+///
+/// Statements and branching constructs are keyed by their [positions][Position].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FileCoverage {
+    /// Number of times each statement is run.
+    pub statements: BTreeMap<Position, usize>,
+    /// Outcomes of each branching construct.
+    pub branches: BTreeMap<Position, BranchCoverage>,
+}
+
+impl FileCoverage {
+    /// Number of times each line is run, i.e. the most hits of any statement starting on the line.
+    ///
+    /// Always empty under `no_position`.
+    #[must_use]
+    pub fn lines(&self) -> BTreeMap<usize, usize> {
+        let mut lines = BTreeMap::new();
+
+        for (pos, &hits) in &self.statements {
+            if let Some(line) = pos.line() {
+                let n = lines.entry(line).or_insert(0);
+                *n = hits.max(*n);
+            }
+        }
+
+        lines
+    }
+    /// Summary of the coverage, as `(total, covered)` pairs of statements, lines and branch
+    /// outcomes.
+    #[must_use]
+    fn summary(&self) -> [(usize, usize); 3] {
+        let lines = self.lines();
+
+        [
+            (
+                self.statements.len(),
+                self.statements.values().filter(|&&n| n > 0).count(),
+            ),
+            (lines.len(), lines.values().filter(|&&n| n > 0).count()),
+            (
+                self.branches.values().map(|b| b.hits.len()).sum(),
+                self.branches
+                    .values()
+                    .flat_map(|b| &b.hits)
+                    .filter(|&&n| n > 0)
+                    .count(),
+            ),
+        ]
+    }
+}
+
+/// Statement and branch coverage of scripts, collected by an [`Engine`] via
+/// [`set_coverage_enabled`][Engine::set_coverage_enabled].
+///
+/// Coverage is kept separately for each script source (e.g. the path of a module).
+/// Scripts without a source are kept under an empty source name.
+///
+/// All statements and branching constructs in an [`AST`] (including those in functions) are
+/// included, whether they are run or not, as are those in all modules imported by it.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Coverage {
+    /// Coverage by script source.
+    files: BTreeMap<ImmutableString, FileCoverage>,
+}
+
+impl Coverage {
+    /// Create a new, empty, [`Coverage`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            files: BTreeMap::new(),
+        }
+    }
+    /// Is this [`Coverage`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+    /// Get the coverage of a script source (empty if the script has no source).
+    #[inline]
+    #[must_use]
+    pub fn get(&self, source: &str) -> Option<&FileCoverage> {
+        self.files.get(source)
+    }
+    /// Get an iterator over the coverage of all script sources, in order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FileCoverage)> {
+        self.files
+            .iter()
+            .map(|(source, file)| (source.as_str(), file))
+    }
+    /// Add the hits in another [`Coverage`] to this one.
+    pub fn merge(&mut self, other: &Self) {
+        for (source, other) in &other.files {
+            let file = self.files.entry(source.clone()).or_default();
+
+            for (&pos, &hits) in &other.statements {
+                *file.statements.entry(pos).or_insert(0) += hits;
+            }
+            for (&pos, branch) in &other.branches {
+                let entry = file
+                    .branches
+                    .entry(pos)
+                    .or_insert_with(|| BranchCoverage::new(branch.kind, 0));
+
+                if entry.hits.len() < branch.hits.len() {
+                    entry.hits.resize(branch.hits.len(), 0);
+                }
+                entry
+                    .hits
+                    .iter_mut()
+                    .zip(&branch.hits)
+                    .for_each(|(n, &hits)| *n += hits);
+            }
+        }
+    }
+    /// Get the coverage of a script source, adding it if it does not exist.
+    fn file_mut(&mut self, source: Option<&ImmutableString>) -> &mut FileCoverage {
+        let source = source.map_or("", ImmutableString::as_str);
+
+        if !self.files.contains_key(source) {
+            self.files.insert(source.into(), FileCoverage::default());
+        }
+
+        self.files.get_mut(source).unwrap()
+    }
+    /// Add all the statements and branching constructs in an [`AST`], including those in functions.
+    pub(crate) fn add_ast(&mut self, ast: &AST) {
+        let file = self.file_mut(ast.source_raw());
+
+        ast._walk(&mut |path| {
+            let (pos, kind, outcomes) = match path.last().unwrap() {
+                ASTNode::Stmt(Stmt::Noop(..)) => return true,
+                ASTNode::Stmt(stmt @ Stmt::If(..)) => (stmt.position(), BranchKind::If, 2),
+                ASTNode::Stmt(stmt @ Stmt::Switch(x, ..)) => {
+                    let cases = &x.1;
+                    let outcomes = cases.expressions.len() + usize::from(cases.def_case.is_none());
+                    (stmt.position(), BranchKind::Switch, outcomes)
+                }
+                ASTNode::Stmt(stmt) => {
+                    file.statements.entry(stmt.position()).or_insert(0);
+                    return true;
+                }
+                ASTNode::Expr(Expr::Coalesce(x, pos)) => (*pos, BranchKind::Coalesce, x.len()),
+                ASTNode::Expr(expr @ (Expr::Dot(..) | Expr::Index(..)))
+                    if expr.options().intersects(ASTFlags::NEGATED) =>
+                {
+                    (expr.position(), BranchKind::Elvis, 2)
+                }
+                ASTNode::Expr(..) => return true,
+            };
+
+            if let ASTNode::Stmt(..) = path.last().unwrap() {
+                file.statements.entry(pos).or_insert(0);
+            }
+            file.branches
+                .entry(pos)
+                .or_insert_with(|| BranchCoverage::new(kind, outcomes));
+
+            true
+        });
+    }
+    /// Record a hit of a statement.
+    pub(crate) fn hit_statement(&mut self, source: Option<&ImmutableString>, pos: Position) {
+        *self.file_mut(source).statements.entry(pos).or_insert(0) += 1;
+    }
+    /// Record an outcome of a branching construct.
+    pub(crate) fn hit_branch(
+        &mut self,
+        source: Option<&ImmutableString>,
+        pos: Position,
+        kind: BranchKind,
+        outcomes: usize,
+        taken: usize,
+    ) {
+        let branch = self
+            .file_mut(source)
+            .branches
+            .entry(pos)
+            .or_insert_with(|| BranchCoverage::new(kind, outcomes));
+
+        if branch.hits.len() <= taken {
+            branch.hits.resize(taken + 1, 0);
+        }
+        branch.hits[taken] += 1;
+    }
+    /// Export this [`Coverage`] in the [`lcov`](https://github.com/linux-test-project/lcov)
+    /// tracefile format.
+    ///
+    /// Each script source is a source file (`SF`) record.  The hits of a line (`DA`) are the most
+    /// hits of any statement starting on that line.  Each branching construct is a block (`BRDA`)
+    /// numbered in order within its file.
+    #[must_use]
+    pub fn to_lcov(&self) -> String {
+        let mut output = String::new();
+
+        for (source, file) in &self.files {
+            let [.., (lines_found, lines_hit), (branches_found, branches_hit)] = file.summary();
+
+            let _ = writeln!(output, "TN:\nSF:{source}");
+
+            for (block, (pos, branch)) in file.branches.iter().enumerate() {
+                let line = pos.line().unwrap_or(0);
+                let is_reached = branch.is_reached();
+
+                for (i, &hits) in branch.hits.iter().enumerate() {
+                    if is_reached {
+                        let _ = writeln!(output, "BRDA:{line},{block},{i},{hits}");
+                    } else {
+                        let _ = writeln!(output, "BRDA:{line},{block},{i},-");
+                    }
+                }
+            }
+            let _ = writeln!(output, "BRF:{branches_found}\nBRH:{branches_hit}");
+
+            for (line, hits) in file.lines() {
+                let _ = writeln!(output, "DA:{line},{hits}");
+            }
+            let _ = writeln!(output, "LF:{lines_found}\nLH:{lines_hit}\nend_of_record");
+        }
+
+        output
+    }
+    /// Export a summary of this [`Coverage`] in JSON format.
+    ///
+    /// The summary holds, for each script source and in total, the number of statements, lines
+    /// and branch outcomes (`total`), how many are run or taken (`covered`) and the percentage
+    /// covered.  Lines not run are also listed for each script source.
+    ///
+    /// ```json
+    /// {
+    ///   "files": [
+    ///     {
+    ///       "source": "script.rhai",
+    ///       "statements": { "total": 10, "covered": 8, "percent": 80.0 },
+    ///       "lines": { "total": 8, "covered": 7, "percent": 87.5 },
+    ///       "branches": { "total": 4, "covered": 3, "percent": 75.0 },
+    ///       "uncovered_lines": [12]
+    ///     }
+    ///   ],
+    ///   "total": {
+    ///     "statements": { "total": 10, "covered": 8, "percent": 80.0 },
+    ///     "lines": { "total": 8, "covered": 7, "percent": 87.5 },
+    ///     "branches": { "total": 4, "covered": 3, "percent": 75.0 }
+    ///   }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        #[allow(clippy::cast_precision_loss)]
+        fn summary(counts: [(usize, usize); 3]) -> Value {
+            let [statements, lines, branches] = counts.map(|(total, covered)| {
+                let percent = if total == 0 {
+                    100.0
+                } else {
+                    covered as f64 * 100.0 / total as f64
+                };
+                json!({ "total": total, "covered": covered, "percent": percent })
+            });
+            json!({ "statements": statements, "lines": lines, "branches": branches })
+        }
+
+        let mut total = [(0, 0); 3];
+
+        let files = self
+            .files
+            .iter()
+            .map(|(source, file)| {
+                let counts = file.summary();
+
+                for (total, (n, covered)) in total.iter_mut().zip(counts) {
+                    total.0 += n;
+                    total.1 += covered;
+                }
+
+                let mut value = summary(counts);
+                value["source"] = source.as_str().into();
+                value["uncovered_lines"] = file
+                    .lines()
+                    .into_iter()
+                    .filter(|&(.., hits)| hits == 0)
+                    .map(|(line, ..)| line)
+                    .collect();
+                value
+            })
+            .collect::<Vec<_>>();
+
+        json!({ "files": files, "total": summary(total) }).to_string()
+    }
+}
+
+impl Engine {
+    /// Add all the statements and branching constructs in an [`AST`] to the coverage, if it is
+    /// being collected.
+    #[inline(always)]
+    pub(crate) fn cov_ast(&self, ast: &AST) {
+        if let Some(ref coverage) = self.coverage {
+            if let Some(mut coverage) = locked_write(coverage) {
+                coverage.add_ast(ast);
+            }
+        }
+    }
+    /// Record a hit of a statement, if coverage is being collected.
+    #[inline(always)]
+    pub(crate) fn cov_stmt(&self, global: &GlobalRuntimeState, stmt: &Stmt) {
+        if let Some(ref coverage) = self.coverage {
+            if matches!(stmt, Stmt::Noop(..)) {
+                return;
+            }
+            if let Some(mut coverage) = locked_write(coverage) {
+                coverage.hit_statement(global.source_raw(), stmt.position());
+            }
+        }
+    }
+    /// Record an outcome of a branching construct, if coverage is being collected.
+    #[inline(always)]
+    pub(crate) fn cov_branch(
+        &self,
+        global: &GlobalRuntimeState,
+        pos: Position,
+        kind: BranchKind,
+        outcomes: usize,
+        taken: usize,
+    ) {
+        if let Some(ref coverage) = self.coverage {
+            if let Some(mut coverage) = locked_write(coverage) {
+                coverage.hit_branch(global.source_raw(), pos, kind, outcomes, taken);
+            }
+        }
+    }
+}
@@ -38,6 +38,25 @@ pub fn calc_array_sizes(array: &crate::Array) -> (usize, usize, usize) {
                 mx += m;
                 sx += s;
             }
+            Union::Tuple(ref t, ..) => {
+                let (a, m, s) = calc_tuple_sizes(t);
+                ax += a;
+                mx += m;
+                sx += s;
+            }
+            Union::Enum(ref e, ..) => {
+                let (a, m, s) = calc_tuple_sizes(e.fields());
+                ax += a;
+                mx += m;
+                sx += s;
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref st, ..) => {
+                let (a, m, s) = calc_struct_sizes(st);
+                ax += a;
+                mx += m;
+                sx += s;
+            }
             Union::Str(ref s, ..) => sx += s.len(),
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(..) => {
@@ -80,6 +99,24 @@ pub fn calc_map_sizes(map: &crate::Map) -> (usize, usize, usize) {
                 mx += m;
                 sx += s;
             }
+            Union::Tuple(ref t, ..) => {
+                let (a, m, s) = calc_tuple_sizes(t);
+                ax += a;
+                mx += m;
+                sx += s;
+            }
+            Union::Enum(ref e, ..) => {
+                let (a, m, s) = calc_tuple_sizes(e.fields());
+                ax += a;
+                mx += m;
+                sx += s;
+            }
+            Union::Struct(ref st, ..) => {
+                let (a, m, s) = calc_struct_sizes(st);
+                ax += a;
+                mx += m;
+                sx += s;
+            }
             Union::Str(ref s, ..) => sx += s.len(),
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(..) => {
@@ -92,6 +129,41 @@ pub fn calc_map_sizes(map: &crate::Map) -> (usize, usize, usize) {
     (ax, mx, sx)
 }
 
+/// Recursively calculate the sizes of the items of a tuple or the fields of an enum variant.
+///
+/// Each item counts towards the size of [`Array`][crate::Array].
+///
+/// Sizes returned are `(` [`Array`][crate::Array], [`Map`][crate::Map] and [`String`] `)`.
+///
+/// # Panics
+///
+/// Panics if any interior data is shared (should never happen).
+#[inline]
+pub fn calc_tuple_sizes(items: &[Dynamic]) -> (usize, usize, usize) {
+    items.iter().fold((0, 0, 0), |(ax, mx, sx), value| {
+        let (a, m, s) = calc_data_sizes(value, false);
+        (ax + 1 + a, mx + m, sx + s)
+    })
+}
+
+/// Recursively calculate the sizes of the fields of a `struct` value.
+///
+/// Each field counts towards the size of [`Map`][crate::Map].
+///
+/// Sizes returned are `(` [`Array`][crate::Array], [`Map`][crate::Map] and [`String`] `)`.
+///
+/// # Panics
+///
+/// Panics if any interior data is shared (should never happen).
+#[cfg(not(feature = "no_object"))]
+#[inline]
+pub fn calc_struct_sizes(value: &crate::StructValue) -> (usize, usize, usize) {
+    value.iter().fold((0, 0, 0), |(ax, mx, sx), (.., value)| {
+        let (a, m, s) = calc_data_sizes(value, false);
+        (ax + a, mx + 1 + m, sx + s)
+    })
+}
+
 /// Recursively calculate the sizes of a value.
 ///
 /// Sizes returned are `(` [`Array`][crate::Array], [`Map`][crate::Map] and [`String`] `)`.
@@ -108,6 +180,10 @@ pub fn calc_data_sizes(value: &Dynamic, _top: bool) -> (usize, usize, usize) {
         Union::Blob(ref blob, ..) => (blob.len(), 0, 0),
         #[cfg(not(feature = "no_object"))]
         Union::Map(ref map, ..) => calc_map_sizes(map),
+        Union::Tuple(ref t, ..) => calc_tuple_sizes(t),
+        Union::Enum(ref e, ..) => calc_tuple_sizes(e.fields()),
+        #[cfg(not(feature = "no_object"))]
+        Union::Struct(ref st, ..) => calc_struct_sizes(st),
         Union::Str(ref s, ..) => (0, 0, s.len()),
         #[cfg(not(feature = "no_closure"))]
         Union::Shared(..) if _top => calc_data_sizes(&value.read_lock::<Dynamic>().unwrap(), true),
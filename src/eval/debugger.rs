@@ -3,12 +3,20 @@
 
 use super::{Caches, EvalContext, GlobalRuntimeState};
 use crate::ast::{ASTNode, Expr, Stmt};
+use crate::types::dynamic::Union;
 use crate::{
-    Dynamic, Engine, EvalAltResult, ImmutableString, Position, RhaiResultOf, Scope, ThinVec,
+    Dynamic, Engine, EvalAltResult, ImmutableString, ParseError, Position, RhaiResult,
+    RhaiResultOf, Scope, Shared, ThinVec, AST,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{fmt, iter::repeat, mem};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    iter::repeat,
+    mem,
+    str::FromStr,
+};
 
 /// Callback function to initialize the debugger.
 #[cfg(not(feature = "sync"))]
@@ -130,6 +138,41 @@ pub enum BreakPoint {
         /// Is the break-point enabled?
         enabled: bool,
     },
+    /// Break when the value of a variable changes (a watchpoint).
+    ///
+    /// The value is compared at every statement and expression where the variable is visible, so
+    /// the break happens right after the change.  A variable coming into scope for the first time
+    /// is not a change.
+    AtVariableChange {
+        /// Variable name.
+        name: ImmutableString,
+        /// Value last seen, if any.
+        value: Option<WatchedValue>,
+        /// Is the break-point enabled?
+        enabled: bool,
+    },
+    /// Break at another break-point only when its conditions are met, or print a message there
+    /// and continue (a log-point).
+    ///
+    /// A condition that fails to evaluate, or does not evaluate to a boolean, is considered not
+    /// met, and the error is reported via the `debug` callback.
+    ///
+    /// A conditional line break-point is checked once each time the script reaches its line,
+    /// instead of at every statement and expression on the line.
+    Conditional {
+        /// The underlying break-point.
+        break_point: Box<BreakPoint>,
+        /// Expression, evaluated in the current scope, that must be `true` to break.
+        condition: Option<BreakPointExpr>,
+        /// Condition on the number of hits (counted only when `condition` is met).
+        hit_condition: Option<HitCondition>,
+        /// Message to print (via the `print` callback) instead of breaking.
+        ///
+        /// It is the text of a back-tick string literal, so `${...}` interpolates expressions.
+        log_message: Option<BreakPointExpr>,
+        /// Number of times the break-point has been hit.
+        hits: usize,
+    },
 }
 
 impl fmt::Display for BreakPoint {
@@ -180,6 +223,32 @@ impl fmt::Display for BreakPoint {
                 }
                 Ok(())
             }
+            Self::AtVariableChange { name, enabled, .. } => {
+                write!(f, "watch {name}")?;
+                if !*enabled {
+                    f.write_str(" (disabled)")?;
+                }
+                Ok(())
+            }
+            Self::Conditional {
+                break_point,
+                condition,
+                hit_condition,
+                log_message,
+                ..
+            } => {
+                write!(f, "{break_point}")?;
+                if let Some(ref condition) = condition {
+                    write!(f, " if {condition}")?;
+                }
+                if let Some(ref hit_condition) = hit_condition {
+                    write!(f, " hit {hit_condition}")?;
+                }
+                if let Some(ref log_message) = log_message {
+                    write!(f, " log {log_message}")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -195,6 +264,8 @@ impl BreakPoint {
             Self::AtFunctionName { enabled, .. } | Self::AtFunctionCall { enabled, .. } => *enabled,
             #[cfg(not(feature = "no_object"))]
             Self::AtProperty { enabled, .. } => *enabled,
+            Self::AtVariableChange { enabled, .. } => *enabled,
+            Self::Conditional { break_point, .. } => break_point.is_enabled(),
         }
     }
     /// Enable/disable this [`BreakPoint`].
@@ -208,10 +279,320 @@ impl BreakPoint {
             }
             #[cfg(not(feature = "no_object"))]
             Self::AtProperty { enabled, .. } => *enabled = value,
+            Self::AtVariableChange { enabled, .. } => *enabled = value,
+            Self::Conditional { break_point, .. } => break_point.enable(value),
+        }
+    }
+    /// Get the underlying [`BreakPoint`] of a [conditional][BreakPoint::Conditional] break-point,
+    /// or this [`BreakPoint`] itself.
+    #[inline]
+    #[must_use]
+    pub fn location(&self) -> &Self {
+        match self {
+            Self::Conditional { break_point, .. } => break_point.location(),
+            bp => bp,
+        }
+    }
+    /// Is this [`BreakPoint`] at a particular [`AST` Node][ASTNode]?
+    ///
+    /// Watchpoints are never at a node.
+    #[must_use]
+    fn is_at(&self, src: Option<&str>, node: ASTNode) -> bool {
+        let _src = src;
+
+        match self {
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { pos, .. } if pos.is_none() => false,
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { source, pos, .. } if pos.is_beginning_of_line() => {
+                node.position().line().unwrap_or(0) == pos.line().unwrap()
+                    && _src == source.as_deref()
+            }
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { source, pos, .. } => {
+                node.position() == *pos && _src == source.as_deref()
+            }
+            Self::AtFunctionName { name, .. } => match node {
+                ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
+                    x.name == *name
+                }
+                ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
+                    Expr::FnCall(x, ..) => x.name == *name,
+                    _ => false,
+                },
+                _ => false,
+            },
+            Self::AtFunctionCall { name, args, .. } => match node {
+                ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
+                    x.args.len() == *args && x.name == *name
+                }
+                ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
+                    Expr::FnCall(x, ..) => x.args.len() == *args && x.name == *name,
+                    _ => false,
+                },
+                _ => false,
+            },
+            #[cfg(not(feature = "no_object"))]
+            Self::AtProperty { name, .. } => match node {
+                ASTNode::Expr(Expr::Property(x, ..)) => x.2 == *name,
+                _ => false,
+            },
+            Self::AtVariableChange { .. } => false,
+            Self::Conditional { break_point, .. } => break_point.is_at(_src, node),
+        }
+    }
+    /// Is this [`BreakPoint`] a line break-point?
+    #[must_use]
+    fn is_line(&self) -> bool {
+        match self {
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { pos, .. } => pos.is_beginning_of_line(),
+            Self::Conditional { break_point, .. } => break_point.is_line(),
+            _ => false,
+        }
+    }
+    /// Record the current value of the variable of a watchpoint.
+    ///
+    /// Returns `true` if the value has changed since it was last seen.
+    fn watch(&mut self, scope: &Scope) -> bool {
+        match self {
+            Self::AtVariableChange {
+                name,
+                value,
+                enabled: true,
+            } => {
+                // Keep the last value while the variable is not visible (e.g. in a function call)
+                let Some(current) = scope.get(name) else {
+                    return false;
+                };
+                match value {
+                    Some(WatchedValue(old)) if is_same_value(old, current) => false,
+                    Some(WatchedValue(old)) => {
+                        *old = current.flatten_clone();
+                        true
+                    }
+                    None => {
+                        *value = Some(WatchedValue(current.flatten_clone()));
+                        false
+                    }
+                }
+            }
+            Self::Conditional { break_point, .. } => break_point.watch(scope),
+            _ => false,
+        }
+    }
+}
+
+/// Are two values the same?
+///
+/// Values of types without a structural comparison are compared by their [`Debug`][fmt::Debug]
+/// format.
+fn is_same_value(a: &Dynamic, b: &Dynamic) -> bool {
+    #[cfg(not(feature = "no_closure"))]
+    match (&a.0, &b.0) {
+        (Union::Shared(cell, ..), _) => {
+            return crate::func::locked_read(cell).map_or(false, |a| is_same_value(&a, b))
+        }
+        (_, Union::Shared(cell, ..)) => {
+            return crate::func::locked_read(cell).map_or(false, |b| is_same_value(a, &b))
+        }
+        _ => (),
+    }
+
+    let same_values = |a: &[Dynamic], b: &[Dynamic]| {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| is_same_value(a, b))
+    };
+
+    match (&a.0, &b.0) {
+        (Union::Unit(..), Union::Unit(..)) => true,
+        (Union::Bool(a, ..), Union::Bool(b, ..)) => a == b,
+        (Union::Str(a, ..), Union::Str(b, ..)) => a == b,
+        (Union::Char(a, ..), Union::Char(b, ..)) => a == b,
+        (Union::Int(a, ..), Union::Int(b, ..)) => a == b,
+        #[cfg(not(feature = "no_float"))]
+        (Union::Float(a, ..), Union::Float(b, ..)) => a == b,
+        #[cfg(feature = "decimal")]
+        (Union::Decimal(a, ..), Union::Decimal(b, ..)) => a == b,
+        #[cfg(feature = "bigint")]
+        (Union::BigInt(a, ..), Union::BigInt(b, ..)) => a == b,
+        #[cfg(not(feature = "no_index"))]
+        (Union::Array(a, ..), Union::Array(b, ..)) => same_values(a, b),
+        #[cfg(not(feature = "no_index"))]
+        (Union::Blob(a, ..), Union::Blob(b, ..)) => a == b,
+        #[cfg(not(feature = "no_object"))]
+        (Union::Map(a, ..), Union::Map(b, ..)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, a)| b.get(k).map_or(false, |b| is_same_value(a, b)))
+        }
+        (Union::Tuple(a, ..), Union::Tuple(b, ..)) => same_values(a, b),
+        (Union::Enum(a, ..), Union::Enum(b, ..)) => {
+            a.enum_name() == b.enum_name()
+                && a.variant() == b.variant()
+                && same_values(a.fields(), b.fields())
+        }
+        #[cfg(not(feature = "no_object"))]
+        (Union::Struct(a, ..), Union::Struct(b, ..)) => {
+            a.struct_name() == b.struct_name()
+                && a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|((ka, a), (kb, b))| ka == kb && is_same_value(a, b))
+        }
+        (a, b) if mem::discriminant(a) != mem::discriminant(b) => false,
+        _ => format!("{a:?}") == format!("{b:?}"),
+    }
+}
+
+/// The value of a variable last seen by a [watchpoint][BreakPoint::AtVariableChange].
+///
+/// Two [`WatchedValue`]'s are equal if they hold the same value.
+#[derive(Debug, Clone)]
+pub struct WatchedValue(pub Dynamic);
+
+impl PartialEq for WatchedValue {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        is_same_value(&self.0, &other.0)
+    }
+}
+
+impl Eq for WatchedValue {}
+
+impl Hash for WatchedValue {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Not all values can be hashed, so only the type is
+        self.0.type_name().hash(state);
+    }
+}
+
+/// An expression of a [conditional][BreakPoint::Conditional] break-point, compiled once when the
+/// break-point is set.
+///
+/// Two [`BreakPointExpr`]'s are equal if their texts are equal.
+#[derive(Debug, Clone)]
+pub struct BreakPointExpr {
+    /// Text of the expression.
+    text: ImmutableString,
+    /// The compiled expression.
+    ast: Shared<AST>,
+}
+
+impl BreakPointExpr {
+    /// Compile an expression.
+    ///
+    /// Variables are resolved when the expression is evaluated, so an undefined variable is not
+    /// an error even under Strict Variables mode.
+    pub fn new(engine: &Engine, text: impl Into<ImmutableString>) -> Result<Self, ParseError> {
+        let text = text.into();
+        let ast = engine.compile_break_point_expr(&text)?;
+        Ok(Self {
+            text,
+            ast: ast.into(),
+        })
+    }
+    /// Compile a log message, which is the text of a back-tick string literal, so `${...}`
+    /// interpolates expressions.
+    pub fn new_log_message(
+        engine: &Engine,
+        text: impl Into<ImmutableString>,
+    ) -> Result<Self, ParseError> {
+        let text = text.into();
+        let ast = engine.compile_break_point_expr(&format!("`{text}`"))?;
+        Ok(Self {
+            text,
+            ast: ast.into(),
+        })
+    }
+    /// Get the text of the expression.
+    #[inline(always)]
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for BreakPointExpr {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl PartialEq for BreakPointExpr {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for BreakPointExpr {}
+
+impl Hash for BreakPointExpr {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+/// A condition on the number of times a [conditional][BreakPoint::Conditional] break-point is hit.
+///
+/// Parsed from `n` or `== n`, `>= n` and `% n`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum HitCondition {
+    /// Break on the n-th hit only.
+    Equals(usize),
+    /// Break on the n-th hit and all hits afterwards.
+    AtLeast(usize),
+    /// Break on every n-th hit.
+    Multiple(usize),
+}
+
+impl HitCondition {
+    /// Is this [`HitCondition`] met by a number of hits?
+    #[inline]
+    #[must_use]
+    pub const fn is_met(self, hits: usize) -> bool {
+        match self {
+            Self::Equals(n) => hits == n,
+            Self::AtLeast(n) => hits >= n,
+            Self::Multiple(n) => n > 0 && hits % n == 0,
+        }
+    }
+}
+
+impl fmt::Display for HitCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Equals(n) => write!(f, "== {n}"),
+            Self::AtLeast(n) => write!(f, ">= {n}"),
+            Self::Multiple(n) => write!(f, "% {n}"),
         }
     }
 }
 
+impl FromStr for HitCondition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let (make, n): (fn(usize) -> Self, _) = if let Some(n) = s.strip_prefix("==") {
+            (Self::Equals, n)
+        } else if let Some(n) = s.strip_prefix(">=") {
+            (Self::AtLeast, n)
+        } else if let Some(n) = s.strip_prefix('%') {
+            (Self::Multiple, n)
+        } else {
+            (Self::Equals, s)
+        };
+
+        n.trim().parse().map(make).map_err(|_| ())
+    }
+}
+
 /// A function call.
 #[derive(Debug, Clone, Hash)]
 pub struct CallStackFrame {
@@ -257,6 +638,10 @@ pub struct Debugger {
     call_stack: Vec<CallStackFrame>,
     /// The current state.
     state: Dynamic,
+    /// Source and first [position][Position] of the current line at each function call level.
+    lines: Vec<(Option<ImmutableString>, Position)>,
+    /// Is the current line just entered?
+    line_entered: bool,
 }
 
 impl Debugger {
@@ -269,6 +654,8 @@ impl Debugger {
             break_points: Vec::new(),
             call_stack: Vec::new(),
             state: Dynamic::UNIT,
+            lines: Vec::new(),
+            line_entered: false,
         }
     }
     /// Get the current call stack.
@@ -318,53 +705,35 @@ impl Debugger {
         }
     }
     /// Returns the first break-point triggered by a particular [`AST` Node][ASTNode].
+    ///
+    /// Only the locations of break-points are checked: conditions are not evaluated and
+    /// watchpoints never trigger.
     #[must_use]
     pub fn is_break_point(&self, src: Option<&str>, node: ASTNode) -> Option<usize> {
-        let _src = src;
-
         self.break_points()
             .iter()
-            .enumerate()
-            .filter(|(.., bp)| bp.is_enabled())
-            .find(|(.., bp)| match bp {
-                #[cfg(not(feature = "no_position"))]
-                BreakPoint::AtPosition { pos, .. } if pos.is_none() => false,
-                #[cfg(not(feature = "no_position"))]
-                BreakPoint::AtPosition { source, pos, .. } if pos.is_beginning_of_line() => {
-                    node.position().line().unwrap_or(0) == pos.line().unwrap()
-                        && _src == source.as_deref()
-                }
-                #[cfg(not(feature = "no_position"))]
-                BreakPoint::AtPosition { source, pos, .. } => {
-                    node.position() == *pos && _src == source.as_deref()
-                }
-                BreakPoint::AtFunctionName { name, .. } => match node {
-                    ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
-                        x.name == *name
-                    }
-                    ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
-                        Expr::FnCall(x, ..) => x.name == *name,
-                        _ => false,
-                    },
-                    _ => false,
-                },
-                BreakPoint::AtFunctionCall { name, args, .. } => match node {
-                    ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
-                        x.args.len() == *args && x.name == *name
-                    }
-                    ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
-                        Expr::FnCall(x, ..) => x.args.len() == *args && x.name == *name,
-                        _ => false,
-                    },
-                    _ => false,
-                },
-                #[cfg(not(feature = "no_object"))]
-                BreakPoint::AtProperty { name, .. } => match node {
-                    ASTNode::Expr(Expr::Property(x, ..)) => x.2 == *name,
-                    _ => false,
-                },
-            })
-            .map(|(i, ..)| i)
+            .position(|bp| bp.is_enabled() && bp.is_at(src, node))
+    }
+    /// Keep track of the line of each function call level, at every (non-transitive)
+    /// [`AST` Node][ASTNode].
+    ///
+    /// The line is entered again when the same position is reached again (e.g. in a loop).
+    pub(crate) fn track_line(
+        &mut self,
+        src: Option<&ImmutableString>,
+        level: usize,
+        pos: Position,
+    ) {
+        self.lines.resize(level + 1, (None, Position::NONE));
+
+        let (source, start) = &mut self.lines[level];
+
+        self.line_entered = source.as_ref() != src || start.line() != pos.line() || *start == pos;
+
+        if self.line_entered {
+            *source = src.cloned();
+            *start = pos;
+        }
     }
     /// Get a slice of all [`BreakPoint`]'s.
     #[inline(always)]
@@ -447,7 +816,7 @@ impl Engine {
         global: &mut GlobalRuntimeState,
         caches: &mut Caches,
         scope: &mut Scope,
-        this_ptr: Option<&mut Dynamic>,
+        mut this_ptr: Option<&mut Dynamic>,
         node: impl Into<ASTNode<'a>>,
     ) -> RhaiResultOf<Option<DebuggerStatus>> {
         let node = node.into();
@@ -458,29 +827,188 @@ impl Engine {
             _ => (),
         }
 
-        match global.debugger {
-            Some(ref dbg) => {
-                let event = match dbg.status {
-                    DebuggerStatus::Init => Some(DebuggerEvent::Start),
-                    DebuggerStatus::NEXT if node.is_stmt() => Some(DebuggerEvent::Step),
-                    DebuggerStatus::INTO if node.is_expr() => Some(DebuggerEvent::Step),
-                    DebuggerStatus::STEP => Some(DebuggerEvent::Step),
-                    DebuggerStatus::Terminate => Some(DebuggerEvent::End),
-                    _ => None,
-                };
+        let Some(ref mut dbg) = global.debugger else {
+            return Ok(None);
+        };
+
+        dbg.track_line(global.source.as_ref(), global.level, node.position());
+
+        let event = match dbg.status {
+            DebuggerStatus::Init => Some(DebuggerEvent::Start),
+            DebuggerStatus::NEXT if node.is_stmt() => Some(DebuggerEvent::Step),
+            DebuggerStatus::INTO if node.is_expr() => Some(DebuggerEvent::Step),
+            DebuggerStatus::STEP => Some(DebuggerEvent::Step),
+            DebuggerStatus::Terminate => Some(DebuggerEvent::End),
+            _ => None,
+        };
+
+        let event = match event {
+            Some(e) => {
+                // Keep watchpoints up-to-date
+                dbg.break_points_mut().iter_mut().for_each(|bp| {
+                    bp.watch(scope);
+                });
+                e
+            }
+            None => {
+                match self.dbg_break_point(global, caches, scope, this_ptr.as_deref_mut(), node)? {
+                    Some(bp) => DebuggerEvent::BreakPoint(bp),
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        self.dbg_raw(global, caches, scope, this_ptr, node, event)
+    }
+    /// Find the first break-point triggered by a particular [`AST` Node][ASTNode].
+    ///
+    /// All break-points are checked, so that every watchpoint is kept up-to-date, the conditions
+    /// of all conditional break-points are evaluated and the messages of all log-points printed.
+    pub(crate) fn dbg_break_point(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        node: ASTNode,
+    ) -> RhaiResultOf<Option<usize>> {
+        let src = global.source_raw().cloned();
+        let mut found = None;
+
+        for index in 0..global.debugger().break_points().len() {
+            let debugger = global.debugger_mut();
+            let line_entered = debugger.line_entered;
+            let bp = &mut debugger.break_points_mut()[index];
+
+            if !bp.is_enabled() {
+                continue;
+            }
+
+            let triggered = bp.watch(scope) || bp.is_at(src.as_deref(), node);
+
+            let (condition, hit_condition, log_message) = match &*bp {
+                _ if !triggered => continue,
+                BreakPoint::Conditional { .. } if bp.is_line() && !line_entered => continue,
+                BreakPoint::Conditional {
+                    condition,
+                    hit_condition,
+                    log_message,
+                    ..
+                } => (condition.clone(), *hit_condition, log_message.clone()),
+                _ => {
+                    found = found.or(Some(index));
+                    continue;
+                }
+            };
+
+            if let Some(condition) = condition {
+                let result = self.dbg_eval(
+                    global,
+                    caches,
+                    scope,
+                    this_ptr.as_deref_mut(),
+                    &condition.ast,
+                );
 
-                let event = match event {
-                    Some(e) => e,
-                    None => match dbg.is_break_point(global.source(), node) {
-                        Some(bp) => DebuggerEvent::BreakPoint(bp),
-                        None => return Ok(None),
-                    },
+                let met = result.and_then(|v| {
+                    v.as_bool().map_err(|typ| {
+                        EvalAltResult::ErrorMismatchOutputType(
+                            self.map_type_name(std::any::type_name::<bool>()).into(),
+                            typ.into(),
+                            node.position(),
+                        )
+                        .into()
+                    })
+                });
+
+                match met {
+                    Ok(true) => (),
+                    Ok(false) => continue,
+                    Err(err) => {
+                        if let Some(ref debug) = self.debug {
+                            let bp = &global.debugger().break_points()[index];
+                            let text = format!("Break-point {bp}: {err}");
+                            debug(&text, src.as_deref(), node.position());
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            if let BreakPoint::Conditional { hits, .. } =
+                &mut global.debugger_mut().break_points_mut()[index]
+            {
+                *hits += 1;
+
+                if hit_condition.map_or(false, |c| !c.is_met(*hits)) {
+                    continue;
+                }
+            }
+
+            let Some(message) = log_message else {
+                found = found.or(Some(index));
+                continue;
+            };
+
+            let text =
+                match self.dbg_eval(global, caches, scope, this_ptr.as_deref_mut(), &message.ast) {
+                    Ok(value) => value.to_string(),
+                    Err(err) => err.to_string(),
                 };
 
-                self.dbg_raw(global, caches, scope, this_ptr, node, event)
+            if let Some(ref print) = self.print {
+                print(&text);
             }
-            None => Ok(None),
         }
+
+        Ok(found)
+    }
+    /// Compile the expression of a [conditional][BreakPoint::Conditional] break-point, resolving
+    /// variables only when it is evaluated.
+    fn compile_break_point_expr(&self, script: &str) -> Result<AST, ParseError> {
+        let scripts = [script];
+        let (stream, tc) = self.lex(&scripts);
+
+        let input = &mut stream.peekable();
+        let lib = &mut <_>::default();
+        let state = crate::parser::ParseState::new(None, input, tc, lib);
+
+        self.parse_global_expr(
+            state,
+            |settings| {
+                settings
+                    .options
+                    .remove(crate::api::options::LangOptions::STRICT_VAR)
+            },
+            #[cfg(not(feature = "no_optimize"))]
+            self.optimization_level,
+        )
+    }
+    /// Evaluate an expression with the debugger suspended, so that the evaluation itself does not
+    /// trigger any debugger event.
+    pub(crate) fn dbg_eval(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        ast: &AST,
+    ) -> RhaiResult {
+        let debugger = global.debugger_mut();
+        let status = mem::replace(&mut debugger.status, DebuggerStatus::CONTINUE);
+        let break_points = mem::take(debugger.break_points_mut());
+        let lines = mem::take(&mut debugger.lines);
+        let line_entered = debugger.line_entered;
+
+        let result = self.eval_stmt_block(global, caches, scope, this_ptr, ast.statements(), true);
+
+        let debugger = global.debugger_mut();
+        debugger.status = status;
+        *debugger.break_points_mut() = break_points;
+        debugger.lines = lines;
+        debugger.line_entered = line_entered;
+
+        result
     }
     /// Run the debugger callback unconditionally.
     ///
@@ -181,6 +181,36 @@ impl<'a, 's, 'ps, 'g, 'c, 't> EvalContext<'a, 's, 'ps, 'g, 'c, 't> {
     pub fn debugger_mut(&mut self) -> Option<&mut crate::debugger::Debugger> {
         self.global.debugger.as_deref_mut()
     }
+    /// Evaluate an expression within this [evaluation context][`EvalContext`] with the debugger
+    /// suspended, so that no debugger events are triggered by the evaluation itself.
+    #[cfg(feature = "dap")]
+    #[inline]
+    pub(crate) fn eval_expression_suspended(&mut self, script: &str) -> RhaiResult {
+        let ast = self
+            .engine
+            .compile_expression_with_scope(self.scope, script)?;
+
+        self.engine.dbg_eval(
+            self.global,
+            self.caches,
+            self.scope,
+            self.this_ptr.as_deref_mut(),
+            &ast,
+        )
+    }
+    /// Find the first break-point triggered by an [`AST` Node][crate::ASTNode] within this
+    /// [evaluation context][`EvalContext`], evaluating conditions and printing log-points.
+    #[cfg(feature = "dap")]
+    #[inline(always)]
+    pub(crate) fn break_point(&mut self, node: crate::ASTNode) -> RhaiResultOf<Option<usize>> {
+        self.engine.dbg_break_point(
+            self.global,
+            self.caches,
+            self.scope,
+            self.this_ptr.as_deref_mut(),
+            node,
+        )
+    }
 
     /// Evaluate an [expression tree][crate::Expression] within this [evaluation context][`EvalContext`].
     ///
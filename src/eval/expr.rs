@@ -247,6 +247,84 @@ impl Engine {
             _ => unreachable!("Expr::Variable expected but gets {:?}", expr),
         }
     }
+    /// Evaluate the items of a tuple (or the fields of an enum variant).
+    fn eval_tuple_items(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        exprs: &[Expr],
+    ) -> RhaiResultOf<crate::Tuple> {
+        let mut items = Vec::with_capacity(exprs.len());
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut total_data_sizes = (0, 0, 0);
+
+        for item_expr in exprs {
+            let value = self
+                .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), item_expr)?
+                .flatten();
+
+            #[cfg(not(feature = "unchecked"))]
+            if self.has_data_size_limit() {
+                let val_sizes = crate::eval::calc_data_sizes(&value, true);
+
+                total_data_sizes = (
+                    total_data_sizes.0 + val_sizes.0 + 1,
+                    total_data_sizes.1 + val_sizes.1,
+                    total_data_sizes.2 + val_sizes.2,
+                );
+                self.throw_on_size(total_data_sizes)
+                    .map_err(|err| err.fill_position(item_expr.position()))?;
+            }
+
+            items.push(value);
+        }
+
+        Ok(items.into())
+    }
+    /// Evaluate the operand of a spread `...` expression into its list of items.
+    ///
+    /// Arrays and tuples can be spread. A [`Generator`][crate::Generator] is run to completion.
+    pub(crate) fn eval_spread_items(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        expr: &Expr,
+    ) -> RhaiResultOf<Vec<Dynamic>> {
+        let value = self
+            .eval_expr(global, caches, scope, this_ptr, expr)?
+            .flatten();
+
+        if value.is_tuple() {
+            return Ok(value.into_tuple().unwrap().into_vec());
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        if value.is::<crate::Generator>() {
+            let generator = value.cast::<crate::Generator>();
+            let mut items = Vec::new();
+
+            while let Some(item) =
+                self.resume_generator(global, caches, &generator, expr.position())?
+            {
+                items.push(item);
+            }
+
+            return Ok(items);
+        }
+
+        #[cfg(not(feature = "no_index"))]
+        return value
+            .into_array()
+            .map_err(|typ| self.make_type_mismatch_err::<crate::Array>(typ, expr.position()));
+
+        #[cfg(feature = "no_index")]
+        return Err(self.make_type_mismatch_err::<crate::Tuple>(value.type_name(), expr.position()));
+    }
     /// Evaluate an expression.
     pub(crate) fn eval_expr(
         &self,
@@ -329,24 +407,43 @@ impl Engine {
                 let mut total_data_sizes = (0, 0, 0);
 
                 for item_expr in &**x {
-                    let value = self
-                        .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), item_expr)?
-                        .flatten();
+                    #[cfg(not(feature = "unchecked"))]
+                    let start = array.len();
+
+                    match item_expr {
+                        // [ ...items ]
+                        Expr::Spread(items_expr, ..) => {
+                            let this_ptr = this_ptr.as_deref_mut();
+                            let items = self
+                                .eval_spread_items(global, caches, scope, this_ptr, items_expr)?;
+                            array.extend(items);
+                        }
+                        _ => array.push(
+                            self.eval_expr(
+                                global,
+                                caches,
+                                scope,
+                                this_ptr.as_deref_mut(),
+                                item_expr,
+                            )?
+                            .flatten(),
+                        ),
+                    }
 
                     #[cfg(not(feature = "unchecked"))]
                     if self.has_data_size_limit() {
-                        let val_sizes = crate::eval::calc_data_sizes(&value, true);
-
-                        total_data_sizes = (
-                            total_data_sizes.0 + val_sizes.0 + 1,
-                            total_data_sizes.1 + val_sizes.1,
-                            total_data_sizes.2 + val_sizes.2,
-                        );
+                        for value in &array[start..] {
+                            let val_sizes = crate::eval::calc_data_sizes(value, true);
+
+                            total_data_sizes = (
+                                total_data_sizes.0 + val_sizes.0 + 1,
+                                total_data_sizes.1 + val_sizes.1,
+                                total_data_sizes.2 + val_sizes.2,
+                            );
+                        }
                         self.throw_on_size(total_data_sizes)
                             .map_err(|err| err.fill_position(item_expr.position()))?;
                     }
-
-                    array.push(value);
                 }
 
                 Ok(Dynamic::from_array(array))
@@ -360,6 +457,38 @@ impl Engine {
                 let mut total_data_sizes = (0, 0, 0);
 
                 for (key, value_expr) in &x.0 {
+                    // #{ ...map }
+                    if let Expr::Spread(map_expr, ..) = value_expr {
+                        let pos = map_expr.position();
+                        let source = self
+                            .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), map_expr)?
+                            .flatten()
+                            .try_cast_result::<crate::Map>()
+                            .map_err(|v| {
+                                self.make_type_mismatch_err::<crate::Map>(
+                                    self.map_type_name(v.type_name()),
+                                    pos,
+                                )
+                            })?;
+
+                        for (key, value) in source {
+                            #[cfg(not(feature = "unchecked"))]
+                            if self.has_data_size_limit() {
+                                let delta = crate::eval::calc_data_sizes(&value, true);
+                                total_data_sizes = (
+                                    total_data_sizes.0 + delta.0,
+                                    total_data_sizes.1 + delta.1 + 1,
+                                    total_data_sizes.2 + delta.2,
+                                );
+                                self.throw_on_size(total_data_sizes)
+                                    .map_err(|err| err.fill_position(pos))?;
+                            }
+
+                            map.insert(key, value);
+                        }
+                        continue;
+                    }
+
                     let value = self
                         .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), value_expr)?
                         .flatten();
@@ -382,6 +511,55 @@ impl Engine {
                 Ok(Dynamic::from_map(map))
             }
 
+            #[cfg(not(feature = "no_object"))]
+            Expr::Struct(x, ..) => {
+                let mut value = x.1.clone();
+
+                #[cfg(not(feature = "unchecked"))]
+                let mut total_data_sizes = (0, 0, 0);
+
+                for (field, value_expr) in &x.0 {
+                    let field_value = self
+                        .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), value_expr)?
+                        .flatten();
+
+                    #[cfg(not(feature = "unchecked"))]
+                    if self.has_data_size_limit() {
+                        let delta = crate::eval::calc_data_sizes(&field_value, true);
+                        total_data_sizes = (
+                            total_data_sizes.0 + delta.0,
+                            total_data_sizes.1 + delta.1 + 1,
+                            total_data_sizes.2 + delta.2,
+                        );
+                        self.throw_on_size(total_data_sizes)
+                            .map_err(|err| err.fill_position(value_expr.position()))?;
+                    }
+
+                    *value.get_mut(field.as_str()).unwrap() = field_value;
+                }
+
+                Ok(Dynamic::from_struct(value))
+            }
+
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Expr::Comprehension(x, ..) => {
+                self.eval_comprehension(global, caches, scope, this_ptr, x)
+            }
+
+            Expr::Tuple(x, ..) => self
+                .eval_tuple_items(global, caches, scope, this_ptr, x)
+                .map(Dynamic::from_tuple),
+
+            Expr::Enum(x, ..) => {
+                let (name, variant, fields) = &**x;
+                let fields = self.eval_tuple_items(global, caches, scope, this_ptr, fields)?;
+                Ok(Dynamic::from_enum(crate::EnumValue::new(
+                    name.clone(),
+                    variant.clone(),
+                    fields,
+                )))
+            }
+
             Expr::And(x, ..) => {
                 let mut value = Dynamic::TRUE;
 
@@ -416,16 +594,28 @@ impl Engine {
                 Ok(value)
             }
 
-            Expr::Coalesce(x, ..) => {
+            Expr::Coalesce(x, _pos) => {
                 let mut value = Dynamic::UNIT;
+                let mut _taken = 0;
 
-                for expr in &***x {
+                for (i, expr) in x.iter().enumerate() {
                     value = self.eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
+                    _taken = i;
 
                     if !value.is_unit() {
                         break;
                     }
                 }
+
+                #[cfg(feature = "coverage")]
+                self.cov_branch(
+                    global,
+                    *_pos,
+                    crate::eval::BranchKind::Coalesce,
+                    x.len(),
+                    _taken,
+                );
+
                 Ok(value)
             }
 
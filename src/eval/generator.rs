@@ -0,0 +1,578 @@
+//! Module implementing generators, i.e. script-defined functions that are suspended at `yield`
+//! statements and resumed on demand.
+#![cfg(not(feature = "no_function"))]
+
+use super::{Caches, GlobalRuntimeState};
+use crate::ast::{ASTFlags, EncapsulatedEnviron, Expr, FlowControl, ScriptFuncDef, Stmt};
+use crate::func::{locked_write, FnCallArgs};
+use crate::types::generator::{
+    GeneratorForSource, GeneratorFrame, GeneratorState, GeneratorStatus,
+};
+use crate::{Dynamic, Engine, Generator, Position, RhaiResultOf, Scope, Shared, ERR, INT};
+use std::mem;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+impl Engine {
+    /// Create a [`Generator`] for a call to a generator function.
+    ///
+    /// The function body is not run until the first item is requested.
+    ///
+    /// # WARNING
+    ///
+    /// All function call arguments are _consumed_.
+    pub(crate) fn make_generator(
+        &self,
+        global: &GlobalRuntimeState,
+        _env: Option<&EncapsulatedEnviron>,
+        fn_def: &Shared<ScriptFuncDef>,
+        this_ptr: Option<&mut Dynamic>,
+        args: &mut FnCallArgs,
+    ) -> Generator {
+        let mut scope = Scope::new();
+        scope.extend(
+            fn_def
+                .params
+                .iter()
+                .cloned()
+                .zip(args.iter_mut().map(|v| v.take())),
+        );
+
+        #[allow(unused_mut)]
+        let mut lib = global.lib.clone();
+        #[cfg(not(feature = "no_module"))]
+        let mut imports = global
+            .scan_imports_raw()
+            .map(|(n, m)| (n.clone(), m.clone()))
+            .collect::<crate::ThinVec<_>>();
+        #[cfg(not(feature = "no_module"))]
+        let mut constants = global.constants.clone();
+
+        // Merge in encapsulated environment, if any
+        #[cfg(not(feature = "no_module"))]
+        if let Some(env) = _env {
+            imports.extend(env.imports.iter().cloned());
+            lib.extend(env.lib.iter().cloned());
+            constants.clone_from(&env.constants);
+        }
+
+        Generator::new(GeneratorState {
+            fn_def: fn_def.clone(),
+            lib,
+            #[cfg(not(feature = "no_module"))]
+            imports,
+            #[cfg(not(feature = "no_module"))]
+            constants,
+            source: global.source.clone(),
+            scope,
+            this_ptr: this_ptr.map(|v| v.clone()),
+            frames: Vec::new(),
+            #[cfg(feature = "no_closure")]
+            static_slots: Vec::new(),
+            status: GeneratorStatus::Suspended,
+        })
+    }
+
+    /// Resume a [`Generator`], running it until the next `yield` statement.
+    ///
+    /// Returns the yielded value, or [`None`] if the generator has completed.
+    ///
+    /// Each resumption counts as a function call towards the maximum call levels.
+    pub(crate) fn resume_generator(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        generator: &Generator,
+        pos: Position,
+    ) -> RhaiResultOf<Option<Dynamic>> {
+        if generator.is_done() {
+            return Ok(None);
+        }
+
+        self.track_operation(global, pos)?;
+
+        defer! { let orig_level = global.level; global.level += 1 }
+
+        // Check for stack overflow
+        #[cfg(not(feature = "unchecked"))]
+        if global.level > self.max_call_levels() {
+            return Err(ERR::ErrorStackOverflow(pos).into());
+        }
+
+        // Take the running state out of the generator, so that it is not locked while running
+        let mut state = locked_write(&generator.0)
+            .ok_or_else(|| ERR::ErrorDataRace(generator.fn_name().to_string(), pos))?;
+
+        match state.status {
+            GeneratorStatus::Suspended => state.status = GeneratorStatus::Running,
+            GeneratorStatus::Running => {
+                return Err(ERR::ErrorDataRace(state.fn_def.name.to_string(), pos).into())
+            }
+            GeneratorStatus::Done => return Ok(None),
+        }
+
+        let fn_def = state.fn_def.clone();
+        let mut scope = mem::take(&mut state.scope);
+        let mut this_ptr = state.this_ptr.take();
+        let mut frames = mem::take(&mut state.frames);
+
+        let orig_lib = mem::replace(&mut global.lib, mem::take(&mut state.lib));
+        let orig_source = mem::replace(&mut global.source, state.source.clone());
+        #[cfg(not(feature = "no_module"))]
+        let orig_imports_len = global.num_imports();
+        #[cfg(not(feature = "no_module"))]
+        mem::take(&mut state.imports)
+            .into_iter()
+            .for_each(|(n, m)| global.push_import(n, m));
+        #[cfg(not(feature = "no_module"))]
+        let orig_constants = mem::replace(&mut global.constants, state.constants.take());
+        let orig_statics = mem::replace(&mut global.statics, fn_def.statics.clone());
+        #[cfg(feature = "no_closure")]
+        global
+            .static_slots
+            .extend(
+                state
+                    .static_slots
+                    .drain(..)
+                    .map(|slot| crate::eval::StaticSlot {
+                        level: global.level,
+                        ..slot
+                    }),
+            );
+
+        drop(state);
+
+        let orig_fn_resolution_caches_len = caches.fn_resolution_caches_len();
+        caches.push_fn_resolution_cache();
+
+        let result = self.resume_generator_block(
+            global,
+            caches,
+            &mut scope,
+            this_ptr.as_mut(),
+            &mut frames,
+            0,
+            fn_def.body.statements(),
+        );
+
+        caches.rewind_fn_resolution_caches(orig_fn_resolution_caches_len);
+        #[cfg(feature = "no_closure")]
+        let static_slots = {
+            let level = global.level;
+            let (slots, others) = mem::take(&mut global.static_slots)
+                .into_iter()
+                .partition(|slot| slot.level == level);
+            global.static_slots = others;
+            slots
+        };
+        global.statics = orig_statics;
+
+        let result = result.or_else(|err| match *err {
+            // `return` completes the generator
+            ERR::Return(..) => Ok(None),
+            // Exit value is passed straight-through
+            mut err @ ERR::Exit(..) => {
+                err.set_position(pos);
+                Err(err.into())
+            }
+            // System errors are passed straight-through
+            mut err if err.is_system_exception() => {
+                err.set_position(pos);
+                Err(err.into())
+            }
+            // Other errors are wrapped in `ErrorInFunctionCall`
+            _ => Err(ERR::ErrorInFunctionCall(
+                fn_def.name.to_string(),
+                global.source().unwrap_or("").to_string(),
+                err,
+                pos,
+            )
+            .into()),
+        });
+
+        // Put the running state back into the generator
+        let mut state = locked_write(&generator.0).unwrap();
+
+        state.lib = mem::replace(&mut global.lib, orig_lib);
+        state.source = mem::replace(&mut global.source, orig_source);
+        #[cfg(not(feature = "no_module"))]
+        {
+            state.imports = global
+                .scan_imports_raw()
+                .skip(orig_imports_len)
+                .map(|(n, m)| (n.clone(), m.clone()))
+                .collect();
+            global.truncate_imports(orig_imports_len);
+            state.constants = mem::replace(&mut global.constants, orig_constants);
+        }
+
+        if let Ok(Some(..)) = result {
+            state.scope = scope;
+            state.this_ptr = this_ptr;
+            state.frames = frames;
+            #[cfg(feature = "no_closure")]
+            {
+                state.static_slots = static_slots;
+            }
+            state.status = GeneratorStatus::Suspended;
+        } else {
+            state.status = GeneratorStatus::Done;
+        }
+
+        result
+    }
+
+    /// Run, or resume, a statements block inside a generator until a `yield` statement or the end
+    /// of the block.
+    ///
+    /// `frames[depth]` holds the position within this block, if resuming.
+    ///
+    /// Returns the yielded value, or [`None`] if the block has completed.
+    fn resume_generator_block(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        frames: &mut Vec<GeneratorFrame>,
+        depth: usize,
+        statements: &[Stmt],
+    ) -> RhaiResultOf<Option<Dynamic>> {
+        if frames.len() == depth {
+            frames.push(GeneratorFrame::Block(0, scope.len()));
+        }
+
+        loop {
+            let GeneratorFrame::Block(index, scope_len) = frames[depth] else {
+                unreachable!("GeneratorFrame::Block expected");
+            };
+
+            let Some(stmt) = statements.get(index) else {
+                frames.truncate(depth);
+                #[cfg(feature = "no_closure")]
+                global.drop_static_variables(scope_len);
+                scope.rewind(scope_len);
+                return Ok(None);
+            };
+
+            let this_ptr = this_ptr.as_deref_mut();
+
+            // Other calls to the function may have changed its `static` variables
+            #[cfg(feature = "no_closure")]
+            global.load_static_variables(scope);
+
+            // Statements without `yield` are evaluated normally
+            let result = if frames.len() > depth + 1 || stmt.has_yield() {
+                self.resume_generator_stmt(global, caches, scope, this_ptr, frames, depth + 1, stmt)
+            } else {
+                self.eval_stmt(global, caches, scope, this_ptr, stmt, true)
+                    .map(|_| None)
+            };
+
+            #[cfg(feature = "no_closure")]
+            global.store_static_variables(scope);
+
+            match result {
+                // `yield` directly inside this block - continue from the next statement
+                Ok(Some(value)) if frames.len() == depth + 1 => {
+                    frames[depth] = GeneratorFrame::Block(index + 1, scope_len);
+                    return Ok(Some(value));
+                }
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => frames[depth] = GeneratorFrame::Block(index + 1, scope_len),
+                Err(err) => {
+                    frames.truncate(depth);
+                    #[cfg(feature = "no_closure")]
+                    global.drop_static_variables(scope_len);
+                    scope.rewind(scope_len);
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// Run, or resume, a statement containing `yield` inside a generator.
+    ///
+    /// `frames[depth]` holds the state of this statement, if resuming.
+    ///
+    /// Returns the yielded value, or [`None`] if the statement has completed.
+    fn resume_generator_stmt(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        frames: &mut Vec<GeneratorFrame>,
+        depth: usize,
+        stmt: &Stmt,
+    ) -> RhaiResultOf<Option<Dynamic>> {
+        let is_resuming = frames.len() > depth;
+
+        if !is_resuming {
+            self.track_operation(global, stmt.position())?;
+
+            #[cfg(feature = "coverage")]
+            self.cov_stmt(global, stmt);
+        }
+
+        match stmt {
+            Stmt::Yield(expr, ..) => self
+                .eval_expr(global, caches, scope, this_ptr, expr)
+                .map(|v| Some(v.flatten())),
+
+            Stmt::Block(block) => self.resume_generator_block(
+                global,
+                caches,
+                scope,
+                this_ptr,
+                frames,
+                depth,
+                block.statements(),
+            ),
+
+            Stmt::If(x, ..) => {
+                let FlowControl { expr, body, branch } = &**x;
+
+                if !is_resuming {
+                    let guard_val = self
+                        .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
+                        .as_bool()
+                        .map_err(|typ| self.make_type_mismatch_err::<bool>(typ, expr.position()))?;
+
+                    #[cfg(feature = "coverage")]
+                    self.cov_branch(
+                        global,
+                        stmt.position(),
+                        crate::eval::BranchKind::If,
+                        2,
+                        usize::from(!guard_val),
+                    );
+
+                    frames.push(GeneratorFrame::If(guard_val));
+                }
+
+                let GeneratorFrame::If(guard_val) = frames[depth] else {
+                    unreachable!("GeneratorFrame::If expected");
+                };
+
+                let statements = if guard_val { body } else { branch }.statements();
+
+                let result = self.resume_generator_block(
+                    global,
+                    caches,
+                    scope,
+                    this_ptr,
+                    frames,
+                    depth + 1,
+                    statements,
+                );
+
+                if !matches!(result, Ok(Some(..))) {
+                    frames.truncate(depth);
+                }
+
+                result
+            }
+
+            Stmt::While(x, ..) | Stmt::Do(x, ..) => {
+                let FlowControl { expr, body, .. } = &**x;
+                let is_do = matches!(stmt, Stmt::Do(..));
+                let is_while = !stmt.options().contains(ASTFlags::NEGATED);
+                let is_loop = matches!(expr, Expr::Unit(..) | Expr::BoolConstant(true, ..));
+
+                if !is_resuming {
+                    frames.push(GeneratorFrame::Loop);
+                }
+
+                loop {
+                    // Check the condition of a `while` loop, unless resuming its body
+                    if !is_do && !is_loop && frames.len() == depth + 1 {
+                        let condition = self
+                            .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
+                            .as_bool()
+                            .map_err(|typ| {
+                                self.make_type_mismatch_err::<bool>(typ, expr.position())
+                            })?;
+
+                        if !condition {
+                            frames.truncate(depth);
+                            return Ok(None);
+                        }
+                    }
+
+                    match self.resume_generator_block(
+                        global,
+                        caches,
+                        scope,
+                        this_ptr.as_deref_mut(),
+                        frames,
+                        depth + 1,
+                        body.statements(),
+                    ) {
+                        Ok(Some(value)) => return Ok(Some(value)),
+                        Ok(None) => (),
+                        Err(err) => match *err {
+                            ERR::LoopBreakOuter(is_break, value, levels, pos) => {
+                                frames.truncate(depth);
+                                return Err(
+                                    ERR::loop_break(is_break, value, levels - 1, pos).into()
+                                );
+                            }
+                            ERR::LoopBreak(false, ..) if is_do => continue,
+                            ERR::LoopBreak(false, ..) => (),
+                            ERR::LoopBreak(true, ..) => {
+                                frames.truncate(depth);
+                                return Ok(None);
+                            }
+                            _ => return Err(err),
+                        },
+                    }
+
+                    // Check the condition of a `do` loop
+                    if is_do {
+                        let condition = self
+                            .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
+                            .as_bool()
+                            .map_err(|typ| {
+                                self.make_type_mismatch_err::<bool>(typ, expr.position())
+                            })?;
+
+                        if condition ^ is_while {
+                            frames.truncate(depth);
+                            return Ok(None);
+                        }
+                    }
+                }
+            }
+
+            Stmt::For(x, ..) => {
+                let (var_name, counter, FlowControl { expr, body, .. }) = &**x;
+
+                if !is_resuming {
+                    // Guard against too many variables
+                    #[cfg(not(feature = "unchecked"))]
+                    if scope.len() >= self.max_variables() - usize::from(counter.is_some()) {
+                        return Err(ERR::ErrorTooManyVariables(var_name.pos).into());
+                    }
+
+                    let iter_obj = self
+                        .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
+                        .flatten();
+
+                    let source = if iter_obj.is::<Generator>() {
+                        GeneratorForSource::Generator(iter_obj.cast::<Generator>())
+                    } else {
+                        let iter_func = self
+                            .get_iter_func(global, iter_obj.type_id())
+                            .ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
+
+                        #[cfg(not(feature = "sync"))]
+                        let iter = iter_func(iter_obj);
+                        #[cfg(feature = "sync")]
+                        let iter = iter_func(iter_obj).collect::<Vec<_>>().into_iter();
+
+                        GeneratorForSource::Iter(iter)
+                    };
+
+                    let scope_len = scope.len();
+
+                    // Add the loop variables
+                    if let Some(counter) = counter {
+                        scope.push(counter.name.clone(), 0 as INT);
+                    }
+                    scope.push(var_name.name.clone(), ());
+
+                    frames.push(GeneratorFrame::For(source, -1, scope_len));
+                }
+
+                let GeneratorFrame::For(.., scope_len) = frames[depth] else {
+                    unreachable!("GeneratorFrame::For expected");
+                };
+
+                loop {
+                    // Get the next item, unless resuming the loop body
+                    if frames.len() == depth + 1 {
+                        let GeneratorFrame::For(ref mut source, ref mut index_value, ..) =
+                            frames[depth]
+                        else {
+                            unreachable!("GeneratorFrame::For expected");
+                        };
+
+                        let iter_value = match source {
+                            GeneratorForSource::Iter(iter) => iter.next().transpose(),
+                            GeneratorForSource::Generator(generator) => {
+                                let pos = expr.start_position();
+                                self.resume_generator(global, caches, generator, pos)
+                            }
+                        }
+                        .map_err(|err| err.fill_position(expr.position()))?;
+
+                        let Some(value) = iter_value else {
+                            frames.truncate(depth);
+                            scope.rewind(scope_len);
+                            return Ok(None);
+                        };
+
+                        #[cfg(not(feature = "unchecked"))]
+                        {
+                            *index_value = index_value.checked_add(1).ok_or_else(|| {
+                                ERR::ErrorArithmetic(
+                                    format!("for-loop counter overflow: {index_value}"),
+                                    var_name.pos,
+                                )
+                            })?;
+                        }
+                        #[cfg(feature = "unchecked")]
+                        {
+                            *index_value += 1;
+                        }
+
+                        // Increment counter
+                        if counter.is_some() {
+                            *scope.get_mut_by_index(scope_len).write_lock().unwrap() =
+                                Dynamic::from_int(*index_value);
+                        }
+
+                        // Set loop value
+                        let index = scope_len + usize::from(counter.is_some());
+                        *scope.get_mut_by_index(index).write_lock().unwrap() = value.flatten();
+                    }
+
+                    match self.resume_generator_block(
+                        global,
+                        caches,
+                        scope,
+                        this_ptr.as_deref_mut(),
+                        frames,
+                        depth + 1,
+                        body.statements(),
+                    ) {
+                        Ok(Some(value)) => return Ok(Some(value)),
+                        Ok(None) => (),
+                        Err(err) => match *err {
+                            ERR::LoopBreakOuter(is_break, value, levels, pos) => {
+                                frames.truncate(depth);
+                                scope.rewind(scope_len);
+                                return Err(
+                                    ERR::loop_break(is_break, value, levels - 1, pos).into()
+                                );
+                            }
+                            ERR::LoopBreak(false, ..) => (),
+                            ERR::LoopBreak(true, ..) => {
+                                frames.truncate(depth);
+                                scope.rewind(scope_len);
+                                return Ok(None);
+                            }
+                            _ => return Err(err),
+                        },
+                    }
+                }
+            }
+
+            // Other statements cannot contain `yield`
+            _ => self
+                .eval_stmt(global, caches, scope, this_ptr, stmt, true)
+                .map(|_| None),
+        }
+    }
+}
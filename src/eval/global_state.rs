@@ -11,6 +11,23 @@ use std::prelude::v1::*;
 pub type SharedGlobalConstants =
     crate::Shared<crate::Locked<std::collections::BTreeMap<ImmutableString, Dynamic>>>;
 
+/// A `static` variable held in the scope as a copy of its stored value, under `no_closure`.
+#[cfg(feature = "no_closure")]
+#[cfg(not(feature = "no_function"))]
+#[derive(Debug, Clone)]
+pub(crate) struct StaticSlot {
+    /// Function call level of the scope holding the variable.
+    pub level: usize,
+    /// Index of the variable in the scope.
+    pub index: usize,
+    /// Name of the variable.
+    pub name: ImmutableString,
+    /// Storage of the variable.
+    pub statics: crate::ast::StaticVariables,
+    /// Has another call to the function stored a new value since this copy was loaded?
+    pub stale: bool,
+}
+
 /// _(internals)_ Global runtime states.
 /// Exported under the `internals` feature only.
 //
@@ -68,9 +85,21 @@ pub struct GlobalRuntimeState {
     #[cfg(not(feature = "no_module"))]
     #[cfg(not(feature = "no_function"))]
     pub constants: Option<SharedGlobalConstants>,
+    /// Storage for the `static` variables of the script-defined function being run, if any.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) statics: Option<crate::ast::StaticVariables>,
+    /// `static` variables currently held in scopes as copies, for all running function calls.
+    ///
+    /// Only under `no_closure`, where they cannot be shared values.
+    #[cfg(feature = "no_closure")]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) static_slots: Vec<StaticSlot>,
     /// Where a Grain program failed, innermost frame first.
     #[cfg(feature = "grain")]
     pub(crate) grain_faults: Option<crate::Shared<crate::Locked<Vec<crate::grain::Fault>>>>,
+    /// Call stack being profiled, outermost call first.
+    #[cfg(feature = "profiling")]
+    pub(crate) call_path: Vec<ImmutableString>,
     /// Custom state that can be used by the external host.
     pub tag: Dynamic,
     /// Debugging interface.
@@ -104,10 +133,18 @@ impl Engine {
             #[cfg(not(feature = "no_module"))]
             #[cfg(not(feature = "no_function"))]
             constants: None,
+            #[cfg(not(feature = "no_function"))]
+            statics: None,
+            #[cfg(feature = "no_closure")]
+            #[cfg(not(feature = "no_function"))]
+            static_slots: Vec::new(),
 
             #[cfg(feature = "grain")]
             grain_faults: None,
 
+            #[cfg(feature = "profiling")]
+            call_path: Vec::new(),
+
             tag: self.default_tag().clone(),
 
             #[cfg(feature = "debugging")]
@@ -288,6 +325,89 @@ impl GlobalRuntimeState {
         self.source.as_ref()
     }
 
+    /// Track a `static` variable put into the scope of the current function call as a copy.
+    #[cfg(feature = "no_closure")]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn push_static_variable(
+        &mut self,
+        index: usize,
+        name: ImmutableString,
+        statics: crate::ast::StaticVariables,
+    ) {
+        self.static_slots.push(StaticSlot {
+            level: self.level,
+            index,
+            name,
+            statics,
+            stale: false,
+        });
+    }
+    /// Stop tracking the `static` variables of the current function call at or beyond `scope_len`
+    /// in the scope, which are going out of scope.
+    #[cfg(feature = "no_closure")]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn drop_static_variables(&mut self, scope_len: usize) {
+        let level = self.level;
+        self.static_slots
+            .retain(|slot| slot.level != level || slot.index < scope_len);
+    }
+    /// Copy the `static` variables held in the scope of the current function call back into their
+    /// storage.
+    ///
+    /// A copy is skipped if another call to the function has stored a new value in the meantime,
+    /// and copies held by other calls are marked so in turn.
+    #[cfg(feature = "no_closure")]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn store_static_variables(&mut self, scope: &crate::Scope) {
+        let level = self.level;
+
+        for i in 0..self.static_slots.len() {
+            let slot = &self.static_slots[i];
+
+            if slot.level != level || slot.stale || slot.index >= scope.len() {
+                continue;
+            }
+
+            let (name, value, ..) = scope.get_entry_by_index(slot.index);
+
+            if name != slot.name.as_str() {
+                continue;
+            }
+
+            crate::func::locked_write(&slot.statics)
+                .unwrap()
+                .insert(slot.name.clone(), value.clone());
+
+            let (name, statics) = (slot.name.clone(), slot.statics.clone());
+
+            self.static_slots
+                .iter_mut()
+                .filter(|s| s.level != level && s.name == name)
+                .filter(|s| crate::Shared::ptr_eq(&s.statics, &statics))
+                .for_each(|s| s.stale = true);
+        }
+    }
+    /// Refresh the `static` variables held in the scope of the current function call from their
+    /// storage.
+    #[cfg(feature = "no_closure")]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn load_static_variables(&mut self, scope: &mut crate::Scope) {
+        let level = self.level;
+
+        for slot in self.static_slots.iter_mut().filter(|s| s.level == level) {
+            if slot.index >= scope.len() || scope.get_entry_by_index(slot.index).0 != slot.name {
+                continue;
+            }
+            if let Some(value) = crate::func::locked_read(&slot.statics)
+                .unwrap()
+                .get(&slot.name)
+            {
+                *scope.get_mut_by_index(slot.index) = value.clone();
+            }
+            slot.stale = false;
+        }
+    }
+
     /// Return a reference to the debugging interface.
     ///
     /// # Panics
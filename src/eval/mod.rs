@@ -1,29 +1,33 @@
 mod cache;
 mod chaining;
+mod coverage;
 mod data_check;
 mod debugger;
 mod eval_context;
 mod expr;
+mod generator;
 mod global_state;
+mod profiler;
 mod stmt;
 mod target;
 
 #[allow(unused_imports)]
 pub use cache::FnResolutionCache;
 pub use cache::{Caches, FnResolutionCacheEntry};
+#[cfg(feature = "coverage")]
+pub use coverage::{BranchCoverage, BranchKind, Coverage, FileCoverage};
 #[cfg(not(feature = "unchecked"))]
 #[cfg(not(feature = "no_index"))]
 pub use data_check::calc_array_sizes;
 #[cfg(not(feature = "unchecked"))]
-#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
 pub use data_check::calc_data_sizes;
 #[cfg(feature = "debugging")]
 #[cfg(not(feature = "no_function"))]
 pub use debugger::CallStackFrame;
 #[cfg(feature = "debugging")]
 pub use debugger::{
-    BreakPoint, Debugger, DebuggerCommand, DebuggerEvent, DebuggerStatus, OnDebuggerCallback,
-    OnDebuggingInit,
+    BreakPoint, BreakPointExpr, Debugger, DebuggerCommand, DebuggerEvent, DebuggerStatus,
+    HitCondition, OnDebuggerCallback, OnDebuggingInit, WatchedValue,
 };
 #[cfg(feature = "grain")]
 pub(crate) use eval_context::_call_fn_raw;
@@ -33,6 +37,11 @@ pub use global_state::GlobalRuntimeState;
 #[cfg(not(feature = "no_module"))]
 #[cfg(not(feature = "no_function"))]
 pub use global_state::SharedGlobalConstants;
+#[cfg(feature = "no_closure")]
+#[cfg(not(feature = "no_function"))]
+pub(crate) use global_state::StaticSlot;
+#[cfg(feature = "profiling")]
+pub use profiler::{CallStats, FunctionStats, Profile};
 #[cfg(not(feature = "no_index"))]
 pub use target::calc_offset_len;
 pub use target::{calc_index, Target};
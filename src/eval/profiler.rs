@@ -0,0 +1,277 @@
+//! Module defining the call-level script profiler.
+#![cfg(feature = "profiling")]
+
+use super::GlobalRuntimeState;
+use crate::func::locked_write;
+use crate::{Engine, ImmutableString, Instant};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::time::Duration;
+
+/// Name of the root frame of a script without a source.
+const SCRIPT_FRAME: &str = "<script>";
+
+/// Statistics of the calls made with a particular call stack.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct CallStats {
+    /// Number of calls.
+    pub calls: usize,
+    /// Total wall time spent in the calls, including nested calls.
+    pub time: Duration,
+    /// Total number of operations performed in the calls, including nested calls.
+    ///
+    /// Always zero under `unchecked`.
+    pub operations: u64,
+}
+
+/// Statistics of a single function, summed over all the call stacks it appears in.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct FunctionStats {
+    /// Name of the function.
+    pub name: ImmutableString,
+    /// Number of calls.
+    pub calls: usize,
+    /// Total wall time spent in the function, including nested calls.
+    ///
+    /// Recursive calls are only counted once.
+    pub total_time: Duration,
+    /// Wall time spent in the function itself, excluding nested calls.
+    pub self_time: Duration,
+    /// Number of operations performed in the function, including nested calls.
+    ///
+    /// Recursive calls are only counted once.
+    pub total_operations: u64,
+    /// Number of operations performed in the function itself, excluding nested calls.
+    pub self_operations: u64,
+}
+
+/// Call-level profile of scripts, collected by an [`Engine`] via
+/// [`set_profiling_enabled`][Engine::set_profiling_enabled].
+///
+/// Calls to script-defined functions (including closures), native Rust functions and functions
+/// in imported modules are recorded by their full call stacks.
+///
+/// Each script run is the root of its call stacks, named after the script's source (or
+/// `<script>` if it has no source).  Functions called directly (e.g. via
+/// [`Engine::call_fn`][Engine::call_fn]) are roots themselves.  Functions in modules are named
+/// with the module's ID as namespace, e.g. `hello::foo`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Profile {
+    /// Statistics by call stack, outermost call first.
+    stacks: BTreeMap<Vec<ImmutableString>, CallStats>,
+}
+
+impl Profile {
+    /// Create a new, empty, [`Profile`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            stacks: BTreeMap::new(),
+        }
+    }
+    /// Is this [`Profile`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+    /// Get the statistics of a call stack, outermost call first.
+    #[must_use]
+    pub fn get(&self, stack: &[&str]) -> Option<&CallStats> {
+        let stack: Vec<ImmutableString> = stack.iter().map(|&frame| frame.into()).collect();
+        self.stacks.get(&stack)
+    }
+    /// Get an iterator over the statistics of all call stacks, in order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&[ImmutableString], &CallStats)> {
+        self.stacks.iter().map(|(k, stats)| (k.as_slice(), stats))
+    }
+    /// Add the statistics in another [`Profile`] to this one.
+    pub fn merge(&mut self, other: &Self) {
+        for (stack, other) in &other.stacks {
+            let stats = self.stacks.entry(stack.clone()).or_default();
+            stats.calls += other.calls;
+            stats.time += other.time;
+            stats.operations += other.operations;
+        }
+    }
+    /// Record a call.
+    pub(crate) fn record(&mut self, stack: &[ImmutableString], time: Duration, operations: u64) {
+        let stats = match self.stacks.get_mut(stack) {
+            Some(stats) => stats,
+            None => self.stacks.entry(stack.to_vec()).or_default(),
+        };
+        stats.calls += 1;
+        stats.time += time;
+        stats.operations += operations;
+    }
+    /// Statistics of each call stack, excluding nested calls.
+    #[must_use]
+    fn self_stats(&self) -> BTreeMap<&[ImmutableString], (Duration, u64)> {
+        let mut stats: BTreeMap<_, _> = self
+            .stacks
+            .iter()
+            .map(|(k, s)| (k.as_slice(), (s.time, s.operations)))
+            .collect();
+
+        for (stack, s) in &self.stacks {
+            if let Some((time, operations)) = stats.get_mut(&stack[..stack.len() - 1]) {
+                *time = time.saturating_sub(s.time);
+                *operations = operations.saturating_sub(s.operations);
+            }
+        }
+
+        stats
+    }
+    /// Get the statistics of each function, in descending order of the time spent in the
+    /// function itself.
+    #[must_use]
+    pub fn functions(&self) -> Vec<FunctionStats> {
+        let mut functions = BTreeMap::<&ImmutableString, FunctionStats>::new();
+
+        for (stack, (self_time, self_operations)) in self.self_stats() {
+            let (name, callers) = stack.split_last().unwrap();
+            let s = &self.stacks[stack];
+
+            let f = functions.entry(name).or_insert_with(|| FunctionStats {
+                name: name.clone(),
+                ..FunctionStats::default()
+            });
+            f.calls += s.calls;
+            f.self_time += self_time;
+            f.self_operations += self_operations;
+
+            // Do not count recursive calls twice
+            if !callers.contains(name) {
+                f.total_time += s.time;
+                f.total_operations += s.operations;
+            }
+        }
+
+        let mut functions: Vec<_> = functions.into_values().collect();
+        functions.sort_by(|a, b| b.self_time.cmp(&a.self_time).then(a.name.cmp(&b.name)));
+        functions
+    }
+    /// Export this [`Profile`] as folded stacks (one line per call stack, with frames separated
+    /// by `;`), weighted by the microseconds spent in each call stack excluding nested calls.
+    ///
+    /// This is the input format of flamegraph tools such as
+    /// [`inferno`](https://crates.io/crates/inferno) and
+    /// [`flamegraph.pl`](https://github.com/brendangregg/FlameGraph).
+    #[must_use]
+    pub fn to_folded(&self) -> String {
+        Self::fold(self.self_stats(), |(time, ..)| time.as_micros())
+    }
+    /// Export this [`Profile`] as folded stacks (one line per call stack, with frames separated
+    /// by `;`), weighted by the number of operations performed in each call stack excluding nested
+    /// calls.
+    ///
+    /// Unlike [`to_folded`][Profile::to_folded], the output is the same for each run of the same
+    /// scripts.
+    #[must_use]
+    pub fn to_folded_operations(&self) -> String {
+        Self::fold(self.self_stats(), |(.., operations)| operations.into())
+    }
+    /// Write folded stacks.
+    fn fold(
+        stats: BTreeMap<&[ImmutableString], (Duration, u64)>,
+        weight: impl Fn((Duration, u64)) -> u128,
+    ) -> String {
+        let mut output = String::new();
+
+        for (stack, s) in stats {
+            for (i, frame) in stack.iter().enumerate() {
+                if i > 0 {
+                    output.push(';');
+                }
+                output.push_str(frame);
+            }
+            let _ = writeln!(output, " {}", weight(s));
+        }
+
+        output
+    }
+    /// Export a table of the statistics of each [function][Profile::functions], in descending
+    /// order of the time spent in the function itself.
+    ///
+    /// Times are in milliseconds.
+    #[must_use]
+    pub fn to_table(&self) -> String {
+        let functions = self.functions();
+        let width = functions
+            .iter()
+            .map(|f| f.name.chars().count())
+            .chain([8])
+            .max()
+            .unwrap();
+
+        let mut output = String::new();
+
+        let _ = writeln!(
+            output,
+            "{:width$} {:>8} {:>12} {:>12} {:>12} {:>12}",
+            "function", "calls", "total ms", "self ms", "total ops", "self ops"
+        );
+
+        for f in functions {
+            let _ = writeln!(
+                output,
+                "{:width$} {:>8} {:>12.3} {:>12.3} {:>12} {:>12}",
+                f.name,
+                f.calls,
+                f.total_time.as_secs_f64() * 1000.0,
+                f.self_time.as_secs_f64() * 1000.0,
+                f.total_operations,
+                f.self_operations
+            );
+        }
+
+        output
+    }
+}
+
+impl Engine {
+    /// Enter a call frame, if profiling.
+    ///
+    /// The result must be passed to [`prof_exit`][Engine::prof_exit] when the call returns.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn prof_enter(
+        &self,
+        global: &mut GlobalRuntimeState,
+        name: impl FnOnce() -> ImmutableString,
+    ) -> Option<(Instant, u64)> {
+        self.profiler.as_ref()?;
+        global.call_path.push(name());
+        Some((Instant::now(), global.num_operations))
+    }
+    /// Enter the root call frame of a script run, if profiling.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn prof_enter_script(
+        &self,
+        global: &mut GlobalRuntimeState,
+    ) -> Option<(Instant, u64)> {
+        self.profiler.as_ref()?;
+        let name = global.source.clone().unwrap_or_else(|| SCRIPT_FRAME.into());
+        self.prof_enter(global, || name)
+    }
+    /// Exit a call frame entered by [`prof_enter`][Engine::prof_enter] and record the call.
+    #[inline(always)]
+    pub(crate) fn prof_exit(&self, global: &mut GlobalRuntimeState, entry: Option<(Instant, u64)>) {
+        let Some((start, operations)) = entry else {
+            return;
+        };
+        let time = start.elapsed();
+        let operations = global.num_operations.saturating_sub(operations);
+
+        if let Some(mut profile) = self.profiler.as_ref().and_then(locked_write) {
+            profile.record(&global.call_path, time, operations);
+        }
+
+        global.call_path.pop();
+    }
+}
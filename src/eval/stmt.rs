@@ -1,13 +1,19 @@
 //! Module defining functions for evaluating a statement.
 
 use super::{Caches, EvalContext, GlobalRuntimeState, Target};
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+use crate::ast::ComprehensionExpr;
 use crate::ast::{
-    ASTFlags, BinaryExpr, Expr, FlowControl, OpAssignment, Stmt, SwitchCasesCollection,
+    ASTFlags, BinaryExpr, CatchClause, Expr, FlowControl, FnCallHashes, Ident, OpAssignment, Stmt,
+    SwitchCasesCollection, TryCatchBlock,
 };
-use crate::func::{get_builtin_op_assignment_fn, get_hasher};
+use crate::func::{get_builtin_op_assignment_fn, get_hasher, FnIterator};
 use crate::tokenizer::Token;
 use crate::types::dynamic::{AccessMode, Union};
-use crate::{Dynamic, Engine, RhaiResult, RhaiResultOf, Scope, VarDefInfo, ERR, INT};
+use crate::{
+    Dynamic, Engine, RhaiError, RhaiResult, RhaiResultOf, Scope, StaticVec, VarDefInfo, ERR, INT,
+};
+use std::any::TypeId;
 use std::hash::{Hash, Hasher};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -66,17 +72,27 @@ impl Engine {
         }
 
         // Run the statements
-        statements.iter().try_fold(Dynamic::UNIT, |_, stmt| {
+        let result = statements.iter().try_fold(Dynamic::UNIT, |_, stmt| {
             let this_ptr = this_ptr.as_deref_mut();
 
             #[cfg(not(feature = "no_module"))]
             let orig_imports_len = global.num_imports();
 
-            let result =
-                self.eval_stmt(global, caches, scope, this_ptr, stmt, restore_orig_state)?;
+            // Other calls to the function may have changed its `static` variables
+            #[cfg(feature = "no_closure")]
+            #[cfg(not(feature = "no_function"))]
+            global.load_static_variables(scope);
+
+            let result = self.eval_stmt(global, caches, scope, this_ptr, stmt, restore_orig_state);
+
+            #[cfg(feature = "no_closure")]
+            #[cfg(not(feature = "no_function"))]
+            global.store_static_variables(scope);
+
+            let result = result?;
 
             #[cfg(not(feature = "no_module"))]
-            if matches!(stmt, Stmt::Import(..)) {
+            if matches!(stmt, Stmt::Import(..) | Stmt::Module(..)) {
                 // Get the extra modules - see if any functions are marked global.
                 // Without global functions, the extra modules never affect function resolution.
                 if global
@@ -101,7 +117,16 @@ impl Engine {
             }
 
             Ok(result)
-        })
+        });
+
+        // Stop tracking `static` variables going out of scope
+        #[cfg(feature = "no_closure")]
+        #[cfg(not(feature = "no_function"))]
+        if restore_orig_state {
+            global.drop_static_variables(orig_scope_len);
+        }
+
+        result
     }
 
     /// Evaluate an op-assignment statement.
@@ -146,24 +171,31 @@ impl Engine {
                     (Union::Int(n1, ..), Union::Int(n2, ..)) => {
                         #[cfg(not(feature = "unchecked"))]
                         #[allow(clippy::wildcard_imports)]
-                        use crate::packages::arithmetic::arith_basic::INT::functions::*;
+                        use crate::packages::arithmetic::arith_basic::INT::with_overflow::*;
+
+                        #[cfg(not(feature = "unchecked"))]
+                        let mode = self.integer_overflow();
 
                         #[cfg(not(feature = "unchecked"))]
                         match op_x {
                             PlusAssign => {
-                                *n1 = add(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                *n1 = add(mode, *n1, *n2).map_err(|err| err.fill_position(pos))?
                             }
                             MinusAssign => {
-                                *n1 = subtract(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                *n1 = subtract(mode, *n1, *n2)
+                                    .map_err(|err| err.fill_position(pos))?
                             }
                             MultiplyAssign => {
-                                *n1 = multiply(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                *n1 = multiply(mode, *n1, *n2)
+                                    .map_err(|err| err.fill_position(pos))?
                             }
                             DivideAssign => {
-                                *n1 = divide(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                *n1 =
+                                    divide(mode, *n1, *n2).map_err(|err| err.fill_position(pos))?
                             }
                             ModuloAssign => {
-                                *n1 = modulo(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                *n1 =
+                                    modulo(mode, *n1, *n2).map_err(|err| err.fill_position(pos))?
                             }
                             _ => done = false,
                         }
@@ -200,7 +232,7 @@ impl Engine {
 
                 if !done {
                     if let Some((func, need_context)) =
-                        get_builtin_op_assignment_fn(op_x, &lock_guard, &new_val)
+                        get_builtin_op_assignment_fn(op_x, &lock_guard, &new_val, self)
                     {
                         // We may not need to bump the level because built-in's do not need it.
                         //defer! { let orig_level = global.level; global.level += 1 }
@@ -218,22 +250,35 @@ impl Engine {
                 let opx = Some(op_x);
                 let args = &mut [&mut *lock_guard, &mut new_val];
 
-                match self.exec_native_fn_call(
-                    global, caches, op_x_str, opx, hash_x, args, true, false, pos,
-                ) {
-                    Ok(_) => (),
-                    Err(err) if matches!(*err, ERR::ErrorFunctionNotFound(ref f, ..) if f.starts_with(op_x_str)) =>
-                    {
-                        // Expand to `var = var op rhs`
-                        let op = Some(op);
-
-                        *args[0] = self
-                            .exec_native_fn_call(
-                                global, caches, op_str, op, hash, args, true, false, pos,
-                            )?
-                            .0;
-                    }
-                    Err(err) => return Err(err),
+                // Without Fast Operators mode, a script-defined operator function overrides
+                // the op-assignment for all types
+                #[cfg(not(feature = "no_function"))]
+                let is_script_op =
+                    !self.fast_operators() && self.has_script_fn(global, caches, hash);
+                #[cfg(feature = "no_function")]
+                let is_script_op = false;
+
+                done = !is_script_op
+                    && match self.exec_native_fn_call(
+                        global, caches, op_x_str, opx, hash_x, args, true, false, pos,
+                    ) {
+                        Ok(_) => true,
+                        Err(err) if matches!(*err, ERR::ErrorFunctionNotFound(ref f, ..) if f.starts_with(op_x_str)) => {
+                            false
+                        }
+                        Err(err) => return Err(err),
+                    };
+
+                if !done {
+                    // Expand to `var = var op rhs` (the operator may be script-defined)
+                    let op = Some(op);
+                    let hashes = FnCallHashes::from_hash(hash);
+
+                    *args[0] = self
+                        .exec_fn_call(
+                            global, caches, None, op_str, op, hashes, args, true, false, pos,
+                        )?
+                        .0;
                 }
 
                 self.check_data_size(&*args[0], root.position())?;
@@ -253,6 +298,436 @@ impl Engine {
         target.propagate_changed_value(pos)
     }
 
+    /// Get the iterator function for a type.
+    pub(crate) fn get_iter_func<'a>(
+        &'a self,
+        _global: &'a GlobalRuntimeState,
+        iter_type: TypeId,
+    ) -> Option<&'a FnIterator> {
+        // lib should only contain scripts, so technically they cannot have iterators
+
+        // Search order:
+        // 1) Global namespace - functions registered via Engine::register_XXX
+        // 2) Global modules - packages
+        // 3) Imported modules - functions marked with global namespace
+        // 4) Global sub-modules - functions marked with global namespace
+        let iter_func = self
+            .global_modules
+            .iter()
+            .find_map(|m| m.get_iter(iter_type));
+
+        #[cfg(not(feature = "no_module"))]
+        let iter_func = iter_func
+            .or_else(|| _global.get_iter(iter_type))
+            .or_else(|| {
+                self.global_sub_modules
+                    .values()
+                    .find_map(|m| m.get_qualified_iter(iter_type))
+            });
+
+        iter_func
+    }
+
+    /// Evaluate a `for` loop, getting each item from the `next` callback.
+    fn eval_for_loop(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        x: &(Ident, Option<Ident>, FlowControl),
+        mut next: impl FnMut(
+            &Self,
+            &mut GlobalRuntimeState,
+            &mut Caches,
+        ) -> Option<RhaiResultOf<Dynamic>>,
+    ) -> RhaiResult {
+        let (var_name, counter, FlowControl { expr, body, .. }) = x;
+
+        // Restore scope at end of statement
+        defer! { scope => rewind; let orig_scope_len = scope.len(); }
+
+        // Add the loop variables
+        let counter_index = counter.as_ref().map(|counter| {
+            scope.push(counter.name.clone(), 0 as INT);
+            scope.len() - 1
+        });
+
+        scope.push(var_name.name.clone(), ());
+        let index = scope.len() - 1;
+
+        let mut result = Dynamic::UNIT;
+
+        if body.is_empty() {
+            while let Some(iter_value) = next(self, global, caches) {
+                if let Err(err) = iter_value {
+                    return Err(err.fill_position(expr.position()));
+                }
+                self.track_operation(global, body.position())?;
+            }
+        } else {
+            let mut index_value: INT = -1;
+
+            while let Some(iter_value) = next(self, global, caches) {
+                #[cfg(not(feature = "unchecked"))]
+                {
+                    index_value = index_value.checked_add(1).ok_or_else(|| {
+                        ERR::ErrorArithmetic(
+                            format!("for-loop counter overflow: {index_value}"),
+                            var_name.pos,
+                        )
+                    })?;
+                }
+                #[cfg(feature = "unchecked")]
+                {
+                    index_value += 1;
+                }
+
+                // Increment counter
+                if let Some(counter_index) = counter_index {
+                    *scope.get_mut_by_index(counter_index).write_lock().unwrap() =
+                        Dynamic::from_int(index_value);
+                }
+
+                // Set loop value
+                let value = iter_value
+                    .map_err(|err| err.fill_position(expr.position()))?
+                    .flatten();
+
+                *scope.get_mut_by_index(index).write_lock().unwrap() = value;
+
+                // Run block
+                let this_ptr = this_ptr.as_deref_mut();
+                let statements = body.statements();
+
+                match self.eval_stmt_block(global, caches, scope, this_ptr, statements, true) {
+                    Ok(_) => (),
+                    Err(err) => match *err {
+                        ERR::LoopBreakOuter(is_break, value, levels, pos) => {
+                            return Err(ERR::loop_break(is_break, value, levels - 1, pos).into())
+                        }
+                        ERR::LoopBreak(false, ..) => (),
+                        ERR::LoopBreak(true, value, ..) => {
+                            result = value;
+                            break;
+                        }
+                        _ => return Err(err),
+                    },
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Evaluate an array or object map comprehension.
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    pub(crate) fn eval_comprehension(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        x: &ComprehensionExpr,
+    ) -> RhaiResult {
+        let iterable = &x.iterable;
+
+        // Guard against too many variables
+        #[cfg(not(feature = "unchecked"))]
+        if scope.len() >= self.max_variables() - usize::from(x.counter.is_some()) {
+            return Err(ERR::ErrorTooManyVariables(x.var.pos).into());
+        }
+
+        let iter_obj = self
+            .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), iterable)?
+            .flatten();
+
+        // A generator is resumed for each item
+        #[cfg(not(feature = "no_function"))]
+        if iter_obj.is::<crate::Generator>() {
+            let generator = iter_obj.cast::<crate::Generator>();
+            let pos = iterable.start_position();
+
+            return self.collect_comprehension(global, caches, scope, this_ptr, x, |e, g, c| {
+                e.resume_generator(g, c, &generator, pos).transpose()
+            });
+        }
+
+        let iter_func = self
+            .get_iter_func(global, iter_obj.type_id())
+            .ok_or_else(|| ERR::ErrorFor(iterable.start_position()))?;
+
+        let mut iter = iter_func(iter_obj);
+
+        self.collect_comprehension(global, caches, scope, this_ptr, x, |_, _, _| iter.next())
+    }
+
+    /// Collect the items of an array or object map comprehension, getting each item from the
+    /// `next` callback.
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    fn collect_comprehension(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        x: &ComprehensionExpr,
+        mut next: impl FnMut(
+            &Self,
+            &mut GlobalRuntimeState,
+            &mut Caches,
+        ) -> Option<RhaiResultOf<Dynamic>>,
+    ) -> RhaiResult {
+        let ComprehensionExpr {
+            key,
+            value,
+            var,
+            counter,
+            iterable,
+            condition,
+        } = x;
+
+        // Restore scope at end of comprehension
+        defer! { scope => rewind; let orig_scope_len = scope.len(); }
+
+        // Variables in the comprehension are parsed before the loop variables are declared,
+        // so their indices cannot be trusted
+        defer! { let orig_always_search_scope = global.always_search_scope; global.always_search_scope = true }
+
+        // Add the loop variables
+        let counter_index = counter.as_ref().map(|counter| {
+            scope.push(counter.name.clone(), 0 as INT);
+            scope.len() - 1
+        });
+
+        scope.push(var.name.clone(), ());
+        let index = scope.len() - 1;
+
+        #[cfg(not(feature = "no_index"))]
+        let mut array = crate::Array::new();
+        #[cfg(not(feature = "no_object"))]
+        let mut map = crate::Map::new();
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut total_data_sizes = (0, 0, 0);
+
+        let mut index_value: INT = -1;
+
+        while let Some(iter_value) = next(self, global, caches) {
+            #[cfg(not(feature = "unchecked"))]
+            {
+                index_value = index_value.checked_add(1).ok_or_else(|| {
+                    ERR::ErrorArithmetic(
+                        format!("for-loop counter overflow: {index_value}"),
+                        var.pos,
+                    )
+                })?;
+            }
+            #[cfg(feature = "unchecked")]
+            {
+                index_value += 1;
+            }
+
+            // Increment counter
+            if let Some(counter_index) = counter_index {
+                *scope.get_mut_by_index(counter_index).write_lock().unwrap() =
+                    Dynamic::from_int(index_value);
+            }
+
+            // Set loop value
+            let iter_value = iter_value
+                .map_err(|err| err.fill_position(iterable.position()))?
+                .flatten();
+
+            *scope.get_mut_by_index(index).write_lock().unwrap() = iter_value;
+
+            self.track_operation(global, value.position())?;
+
+            // Filter the item
+            if let Some(condition) = condition {
+                let this_ptr = this_ptr.as_deref_mut();
+
+                if !self
+                    .eval_expr(global, caches, scope, this_ptr, condition)?
+                    .as_bool()
+                    .map_err(|typ| self.make_type_mismatch_err::<bool>(typ, condition.position()))?
+                {
+                    continue;
+                }
+            }
+
+            match key {
+                // #{ key: value for ... }
+                #[cfg(not(feature = "no_object"))]
+                Some(key) => {
+                    let k = self
+                        .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), key)?
+                        .try_cast_result::<crate::ImmutableString>()
+                        .map_err(|v| {
+                            self.make_type_mismatch_err::<crate::ImmutableString>(
+                                v.type_name(),
+                                key.position(),
+                            )
+                        })?;
+
+                    let v = self
+                        .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), value)?
+                        .flatten();
+
+                    #[cfg(not(feature = "unchecked"))]
+                    let delta = if self.has_data_size_limit() {
+                        crate::eval::calc_data_sizes(&v, true)
+                    } else {
+                        (0, 0, 0)
+                    };
+
+                    let _old = map.insert(k.as_str().into(), v);
+
+                    #[cfg(not(feature = "unchecked"))]
+                    if self.has_data_size_limit() {
+                        // A replaced value no longer counts
+                        let (arr, map, s) = _old.map_or((0, 0, 0), |old| {
+                            let (arr, map, s) = crate::eval::calc_data_sizes(&old, true);
+                            (arr, map + 1, s)
+                        });
+                        total_data_sizes = (
+                            total_data_sizes.0 + delta.0 - arr,
+                            total_data_sizes.1 + delta.1 + 1 - map,
+                            total_data_sizes.2 + delta.2 - s,
+                        );
+                        self.throw_on_size(total_data_sizes)
+                            .map_err(|err| err.fill_position(value.position()))?;
+                    }
+                }
+                // [ value for ... ]
+                #[cfg(not(feature = "no_index"))]
+                None => {
+                    let v = self
+                        .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), value)?
+                        .flatten();
+
+                    #[cfg(not(feature = "unchecked"))]
+                    if self.has_data_size_limit() {
+                        let val_sizes = crate::eval::calc_data_sizes(&v, true);
+
+                        total_data_sizes = (
+                            total_data_sizes.0 + val_sizes.0 + 1,
+                            total_data_sizes.1 + val_sizes.1,
+                            total_data_sizes.2 + val_sizes.2,
+                        );
+                        self.throw_on_size(total_data_sizes)
+                            .map_err(|err| err.fill_position(value.position()))?;
+                    }
+
+                    array.push(v);
+                }
+                #[allow(unreachable_patterns)]
+                _ => unreachable!("comprehension with an unsupported collection"),
+            }
+        }
+
+        Ok(match key {
+            #[cfg(not(feature = "no_object"))]
+            Some(..) => Dynamic::from_map(map),
+            #[cfg(not(feature = "no_index"))]
+            None => Dynamic::from_array(array),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("comprehension with an unsupported collection"),
+        })
+    }
+
+    /// Evaluate the `catch` clauses of a try/catch statement, in order, on a caught error.
+    ///
+    /// The error is returned unchanged if no clause catches it.
+    fn eval_catch_clauses(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        catches: &[CatchClause],
+        mut err: RhaiError,
+    ) -> RhaiResult {
+        let err_value = match err.unwrap_inner() {
+            // No error variable
+            _ if catches.iter().all(|c| c.var.is_unit()) => Dynamic::UNIT,
+
+            ERR::ErrorRuntime(x, ..) => x.clone(),
+
+            #[cfg(feature = "no_object")]
+            _ => {
+                let err_pos = err.take_position();
+                let value = err.to_string().into();
+                err.set_position(err_pos);
+                value
+            }
+            #[cfg(not(feature = "no_object"))]
+            _ => {
+                let mut err_map = crate::Map::new();
+                let err_pos = err.take_position();
+
+                err_map.insert("message".into(), err.to_string().into());
+
+                if let Some(ref source) = global.source {
+                    err_map.insert("source".into(), source.into());
+                }
+
+                if !err_pos.is_none() {
+                    err_map.insert("line".into(), (err_pos.line().unwrap() as INT).into());
+                    err_map.insert(
+                        "position".into(),
+                        (err_pos.position().unwrap_or(0) as INT).into(),
+                    );
+                }
+
+                err.dump_fields(&mut err_map);
+                err.set_position(err_pos);
+                err_map.into()
+            }
+        };
+
+        for CatchClause { var, guard, body } in catches {
+            // Restore scope at end of clause
+            defer! { scope if !var.is_unit() => rewind; let orig_scope_len = scope.len(); }
+
+            if let Expr::Variable(x, ..) = var {
+                // Guard against too many variables
+                #[cfg(not(feature = "unchecked"))]
+                if scope.len() >= self.max_variables() {
+                    return Err(ERR::ErrorTooManyVariables(var.position()).into());
+                }
+                scope.push(x.1.clone(), err_value.clone());
+            }
+
+            if !matches!(guard, Expr::BoolConstant(true, ..)) {
+                let caught = self
+                    .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), guard)?
+                    .as_bool()
+                    .map_err(|typ| self.make_type_mismatch_err::<bool>(typ, guard.position()))?;
+
+                if !caught {
+                    continue;
+                }
+            }
+
+            let statements = body.statements();
+
+            return self
+                .eval_stmt_block(global, caches, scope, this_ptr, statements, true)
+                .map(|_| Dynamic::UNIT)
+                .map_err(|result_err| match *result_err {
+                    // Re-throw exception
+                    ERR::ErrorRuntime(v, pos) if v.is_unit() => {
+                        err.set_position(pos);
+                        err
+                    }
+                    _ => result_err,
+                });
+        }
+
+        Err(err)
+    }
+
     /// Evaluate a statement.
     pub(crate) fn eval_stmt(
         &self,
@@ -265,6 +740,9 @@ impl Engine {
     ) -> RhaiResult {
         self.track_operation(global, stmt.position())?;
 
+        #[cfg(feature = "coverage")]
+        self.cov_stmt(global, stmt);
+
         #[cfg(feature = "debugging")]
         let reset = self.dbg_reset(global, caches, scope, this_ptr.as_deref_mut(), stmt)?;
         #[cfg(feature = "debugging")]
@@ -432,11 +910,48 @@ impl Engine {
                     return Err(ERR::ErrorTooManyVariables(*pos).into());
                 }
 
-                // Evaluate initial value
-                let value = self
-                    .eval_expr(global, caches, scope, this_ptr, expr)?
-                    .flatten();
-                let mut value = self.intern_string(value);
+                // A static variable is initialized only once, after which the same shared value
+                // (or, under `no_closure`, a copy of the stored value) is put into the scope
+                #[cfg(not(feature = "no_function"))]
+                let statics = global
+                    .statics
+                    .clone()
+                    .filter(|_| options.contains(ASTFlags::STATIC));
+                #[cfg(not(feature = "no_function"))]
+                let existing = statics.as_ref().and_then(|statics| {
+                    crate::func::locked_read(statics)
+                        .unwrap()
+                        .get(&var_name.name)
+                        .cloned()
+                });
+                #[cfg(feature = "no_function")]
+                let existing = None;
+
+                let mut value = match existing {
+                    Some(value) => value,
+                    None => {
+                        // Evaluate initial value
+                        let value = self
+                            .eval_expr(global, caches, scope, this_ptr, expr)?
+                            .flatten();
+                        let value = self.intern_string(value);
+
+                        #[cfg(not(feature = "no_function"))]
+                        let value = match statics {
+                            Some(ref statics) => {
+                                #[cfg(not(feature = "no_closure"))]
+                                let value = value.into_shared();
+                                crate::func::locked_write(statics)
+                                    .unwrap()
+                                    .insert(var_name.name.clone(), value.clone());
+                                value
+                            }
+                            None => value,
+                        };
+
+                        value
+                    }
+                };
 
                 let _alias = if !rewind_scope {
                     // Put global constants into global module
@@ -472,6 +987,14 @@ impl Engine {
                     }
                 }
 
+                // Track the copy in the scope, to be stored back later
+                #[cfg(feature = "no_closure")]
+                #[cfg(not(feature = "no_function"))]
+                if let Some(statics) = statics {
+                    let index = scope.len() - index.map_or(1, |n| n.get());
+                    global.push_static_variable(index, var_name.name.clone(), statics);
+                }
+
                 #[cfg(not(feature = "no_module"))]
                 if let Some(alias) = _alias {
                     scope.add_alias_by_index(scope.len() - 1, alias.as_str().into());
@@ -489,6 +1012,15 @@ impl Engine {
                     .as_bool()
                     .map_err(|typ| self.make_type_mismatch_err::<bool>(typ, expr.position()))?;
 
+                #[cfg(feature = "coverage")]
+                self.cov_branch(
+                    global,
+                    stmt.position(),
+                    crate::eval::BranchKind::If,
+                    2,
+                    usize::from(!guard_val),
+                );
+
                 if guard_val && !body.is_empty() {
                     self.eval_stmt_block(global, caches, scope, this_ptr, body.statements(), true)
                 } else if !guard_val && !branch.is_empty() {
@@ -507,12 +1039,15 @@ impl Engine {
                         cases,
                         def_case,
                         ranges,
+                        patterns,
                     },
                 ) = &**x;
 
                 let mut result = None;
 
-                let value = self.eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
+                let value = self
+                    .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
+                    .flatten();
 
                 if value.is_hashable() {
                     let hasher = &mut get_hasher();
@@ -537,18 +1072,16 @@ impl Engine {
                             };
 
                             if cond_result {
-                                result = Some(&block.rhs);
+                                result = Some(index);
                                 break;
                             }
                         }
                     }
                     // Then check ranges
                     if !result.is_some() && !ranges.is_empty() {
-                        let value = value.flatten();
-
                         // Then check integer ranges
                         for r in ranges.iter().filter(|r| r.contains(&value)) {
-                            let BinaryExpr { lhs, rhs } = &expressions[r.index()];
+                            let lhs = &expressions[r.index()].lhs;
 
                             let cond_result = match lhs {
                                 Expr::BoolConstant(b, ..) => *b,
@@ -561,18 +1094,84 @@ impl Engine {
                             };
 
                             if cond_result {
-                                result = Some(rhs);
+                                result = Some(r.index());
                                 break;
                             }
                         }
                     }
                 }
 
-                result
-                    .or_else(|| def_case.as_ref().map(|&index| &expressions[index].rhs))
-                    .map_or(Ok(Dynamic::UNIT), |expr| {
-                        self.eval_expr(global, caches, scope, this_ptr, expr)
-                    })
+                // Then check destructuring patterns
+                if result.is_none() && !patterns.is_empty() {
+                    let mut bindings = StaticVec::<Dynamic>::new_const();
+
+                    defer! { scope => rewind; let orig_scope_len = scope.len(); }
+
+                    for (pattern, index) in patterns {
+                        bindings.clear();
+
+                        if !pattern.matches(&value, &mut bindings) {
+                            continue;
+                        }
+
+                        let mut names = StaticVec::<&Ident>::new_const();
+                        pattern.for_each_binding(&mut |x| names.push(x));
+
+                        // Guard against too many variables
+                        #[cfg(not(feature = "unchecked"))]
+                        if let Some(x) = names.first() {
+                            if scope.len() + names.len() > self.max_variables() {
+                                return Err(ERR::ErrorTooManyVariables(x.pos).into());
+                            }
+                        }
+
+                        for (x, v) in names.into_iter().zip(bindings.drain(..)) {
+                            scope.push(x.name.clone(), v);
+                        }
+
+                        let BinaryExpr { lhs, rhs } = &expressions[*index];
+
+                        let cond_result = match lhs {
+                            Expr::BoolConstant(b, ..) => *b,
+                            c => self
+                                .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), c)?
+                                .as_bool()
+                                .map_err(|typ| {
+                                    self.make_type_mismatch_err::<bool>(typ, c.position())
+                                })?,
+                        };
+
+                        if cond_result {
+                            #[cfg(feature = "coverage")]
+                            self.cov_branch(
+                                global,
+                                stmt.position(),
+                                crate::eval::BranchKind::Switch,
+                                expressions.len() + usize::from(def_case.is_none()),
+                                *index,
+                            );
+
+                            return self.eval_expr(global, caches, scope, this_ptr, rhs);
+                        }
+
+                        scope.rewind(orig_scope_len);
+                    }
+                }
+
+                let result = result.or(*def_case);
+
+                #[cfg(feature = "coverage")]
+                self.cov_branch(
+                    global,
+                    stmt.position(),
+                    crate::eval::BranchKind::Switch,
+                    expressions.len() + usize::from(def_case.is_none()),
+                    result.unwrap_or(expressions.len()),
+                );
+
+                result.map_or(Ok(Dynamic::UNIT), |index| {
+                    self.eval_expr(global, caches, scope, this_ptr, &expressions[index].rhs)
+                })
             }
 
             // Loop
@@ -594,6 +1193,9 @@ impl Engine {
                     match self.eval_stmt_block(global, caches, scope, this_ptr, statements, true) {
                         Ok(..) => (),
                         Err(err) => match *err {
+                            ERR::LoopBreakOuter(is_break, value, levels, pos) => {
+                                break Err(ERR::loop_break(is_break, value, levels - 1, pos).into())
+                            }
                             ERR::LoopBreak(false, ..) => (),
                             ERR::LoopBreak(true, value, ..) => break Ok(value),
                             _ => break Err(err),
@@ -626,6 +1228,9 @@ impl Engine {
                     match self.eval_stmt_block(global, caches, scope, this_ptr, statements, true) {
                         Ok(..) => (),
                         Err(err) => match *err {
+                            ERR::LoopBreakOuter(is_break, value, levels, pos) => {
+                                break Err(ERR::loop_break(is_break, value, levels - 1, pos).into())
+                            }
                             ERR::LoopBreak(false, ..) => (),
                             ERR::LoopBreak(true, value, ..) => break Ok(value),
                             _ => break Err(err),
@@ -649,6 +1254,11 @@ impl Engine {
                         {
                             Ok(..) => (),
                             Err(err) => match *err {
+                                ERR::LoopBreakOuter(is_break, value, levels, pos) => {
+                                    break Err(
+                                        ERR::loop_break(is_break, value, levels - 1, pos).into()
+                                    )
+                                }
                                 ERR::LoopBreak(false, ..) => continue,
                                 ERR::LoopBreak(true, value, ..) => break Ok(value),
                                 _ => break Err(err),
@@ -669,117 +1279,40 @@ impl Engine {
 
             // For loop
             Stmt::For(x, ..) => {
-                let (var_name, counter, FlowControl { expr, body, .. }) = &**x;
+                let (_var_name, _counter, FlowControl { expr, .. }) = &**x;
 
                 // Guard against too many variables
                 #[cfg(not(feature = "unchecked"))]
-                if scope.len() >= self.max_variables() - usize::from(counter.is_some()) {
-                    return Err(ERR::ErrorTooManyVariables(var_name.pos).into());
+                if scope.len() >= self.max_variables() - usize::from(_counter.is_some()) {
+                    return Err(ERR::ErrorTooManyVariables(_var_name.pos).into());
                 }
 
                 let iter_obj = self
                     .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
                     .flatten();
 
-                let iter_type = iter_obj.type_id();
-
-                // lib should only contain scripts, so technically they cannot have iterators
-
-                // Search order:
-                // 1) Global namespace - functions registered via Engine::register_XXX
-                // 2) Global modules - packages
-                // 3) Imported modules - functions marked with global namespace
-                // 4) Global sub-modules - functions marked with global namespace
-                let iter_func = self
-                    .global_modules
-                    .iter()
-                    .find_map(|m| m.get_iter(iter_type));
+                // A generator is resumed for each item
+                #[cfg(not(feature = "no_function"))]
+                if iter_obj.is::<crate::Generator>() {
+                    let generator = iter_obj.cast::<crate::Generator>();
+                    let pos = expr.start_position();
 
-                #[cfg(not(feature = "no_module"))]
-                let iter_func = iter_func
-                    .or_else(|| global.get_iter(iter_type))
-                    .or_else(|| {
-                        self.global_sub_modules
-                            .values()
-                            .find_map(|m| m.get_qualified_iter(iter_type))
+                    return self.eval_for_loop(global, caches, scope, this_ptr, x, |e, g, c| {
+                        e.resume_generator(g, c, &generator, pos).transpose()
                     });
+                }
 
-                let iter_func = iter_func.ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
-
-                // Restore scope at end of statement
-                defer! { scope => rewind; let orig_scope_len = scope.len(); }
-
-                // Add the loop variables
-                let counter_index = counter.as_ref().map(|counter| {
-                    scope.push(counter.name.clone(), 0 as INT);
-                    scope.len() - 1
-                });
-
-                scope.push(var_name.name.clone(), ());
-                let index = scope.len() - 1;
-
-                let mut result = Dynamic::UNIT;
-
-                if body.is_empty() {
-                    for _ in iter_func(iter_obj) {
-                        self.track_operation(global, body.position())?;
-                    }
-                } else {
-                    let mut index_value: INT = -1;
-
-                    for iter_value in iter_func(iter_obj) {
-                        #[cfg(not(feature = "unchecked"))]
-                        {
-                            index_value = index_value.checked_add(1).ok_or_else(|| {
-                                ERR::ErrorArithmetic(
-                                    format!("for-loop counter overflow: {index_value}"),
-                                    var_name.pos,
-                                )
-                            })?;
-                        }
-                        #[cfg(feature = "unchecked")]
-                        {
-                            index_value += 1;
-                        }
-
-                        // Increment counter
-                        if let Some(counter_index) = counter_index {
-                            *scope.get_mut_by_index(counter_index).write_lock().unwrap() =
-                                Dynamic::from_int(index_value);
-                        }
-
-                        // Set loop value
-                        let value = iter_value
-                            .map_err(|err| err.fill_position(expr.position()))?
-                            .flatten();
-
-                        *scope.get_mut_by_index(index).write_lock().unwrap() = value;
-
-                        // Run block
-                        let this_ptr = this_ptr.as_deref_mut();
-                        let statements = body.statements();
+                let iter_func = self
+                    .get_iter_func(global, iter_obj.type_id())
+                    .ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
 
-                        match self
-                            .eval_stmt_block(global, caches, scope, this_ptr, statements, true)
-                        {
-                            Ok(_) => (),
-                            Err(err) => match *err {
-                                ERR::LoopBreak(false, ..) => (),
-                                ERR::LoopBreak(true, value, ..) => {
-                                    result = value;
-                                    break;
-                                }
-                                _ => return Err(err),
-                            },
-                        }
-                    }
-                }
+                let mut iter = iter_func(iter_obj);
 
-                Ok(result)
+                self.eval_for_loop(global, caches, scope, this_ptr, x, |_, _, _| iter.next())
             }
 
             // Continue/Break statement
-            Stmt::BreakLoop(expr, options, pos) => {
+            Stmt::BreakLoop(expr, options, levels, pos) => {
                 let is_break = options.contains(ASTFlags::BREAK);
 
                 let value = match expr {
@@ -787,18 +1320,18 @@ impl Engine {
                     None => Dynamic::UNIT,
                 };
 
-                Err(ERR::LoopBreak(is_break, value, *pos).into())
+                Err(ERR::loop_break(is_break, value, *levels, *pos).into())
             }
 
             // Try/Catch statement
             Stmt::TryCatch(x, ..) => {
-                let FlowControl {
+                let TryCatchBlock {
                     body,
-                    expr: catch_var,
-                    branch,
+                    catches,
+                    finally,
                 } = &**x;
 
-                match self.eval_stmt_block(
+                let result = match self.eval_stmt_block(
                     global,
                     caches,
                     scope,
@@ -809,71 +1342,35 @@ impl Engine {
                     r @ Ok(_) => r,
                     Err(err) if err.is_pseudo_error() => Err(err),
                     Err(err) if !err.is_catchable() => Err(err),
-                    Err(mut err) => {
-                        let err_value = match err.unwrap_inner() {
-                            // No error variable
-                            _ if catch_var.is_unit() => Dynamic::UNIT,
-
-                            ERR::ErrorRuntime(x, ..) => x.clone(),
-
-                            #[cfg(feature = "no_object")]
-                            _ => {
-                                let _ = err.take_position();
-                                err.to_string().into()
-                            }
-                            #[cfg(not(feature = "no_object"))]
-                            _ => {
-                                let mut err_map = crate::Map::new();
-                                let err_pos = err.take_position();
-
-                                err_map.insert("message".into(), err.to_string().into());
-
-                                if let Some(ref source) = global.source {
-                                    err_map.insert("source".into(), source.into());
-                                }
-
-                                if !err_pos.is_none() {
-                                    err_map.insert(
-                                        "line".into(),
-                                        (err_pos.line().unwrap() as INT).into(),
-                                    );
-                                    err_map.insert(
-                                        "position".into(),
-                                        (err_pos.position().unwrap_or(0) as INT).into(),
-                                    );
-                                }
-
-                                err.dump_fields(&mut err_map);
-                                err_map.into()
-                            }
-                        };
-
-                        // Restore scope at end of block
-                        defer! { scope if !catch_var.is_unit() => rewind; let orig_scope_len = scope.len(); }
+                    Err(err) if catches.is_empty() => Err(err),
+                    Err(err) => self.eval_catch_clauses(
+                        global,
+                        caches,
+                        scope,
+                        this_ptr.as_deref_mut(),
+                        catches,
+                        err,
+                    ),
+                };
 
-                        if let Expr::Variable(x, ..) = catch_var {
-                            // Guard against too many variables
-                            #[cfg(not(feature = "unchecked"))]
-                            if scope.len() >= self.max_variables() {
-                                return Err(ERR::ErrorTooManyVariables(catch_var.position()).into());
-                            }
-                            scope.push(x.1.clone(), err_value);
-                        }
+                if finally.is_empty() {
+                    return result;
+                }
 
-                        let this_ptr = this_ptr.as_deref_mut();
-                        let statements = branch.statements();
-
-                        self.eval_stmt_block(global, caches, scope, this_ptr, statements, true)
-                            .map(|_| Dynamic::UNIT)
-                            .map_err(|result_err| match *result_err {
-                                // Re-throw exception
-                                ERR::ErrorRuntime(v, pos) if v.is_unit() => {
-                                    err.set_position(pos);
-                                    err
-                                }
-                                _ => result_err,
-                            })
-                    }
+                // Run the finally block, unless the script is exiting or has hit a system error
+                match result {
+                    Err(ref err) if matches!(**err, ERR::Exit(..)) => result,
+                    Err(ref err) if !err.is_pseudo_error() && !err.is_catchable() => result,
+                    _ => self
+                        .eval_stmt_block(
+                            global,
+                            caches,
+                            scope,
+                            this_ptr,
+                            finally.statements(),
+                            true,
+                        )
+                        .and(result),
                 }
             }
 
@@ -895,6 +1392,10 @@ impl Engine {
             // Empty return
             Stmt::Return(None, .., pos) => Err(ERR::Return(Dynamic::UNIT, *pos).into()),
 
+            // Yield statement - only valid inside a generator, which runs it separately
+            #[cfg(not(feature = "no_function"))]
+            Stmt::Yield(..) => unreachable!("Stmt::Yield is only evaluated inside a generator"),
+
             // Import statement
             #[cfg(not(feature = "no_module"))]
             Stmt::Import(x, _pos) => {
@@ -962,6 +1463,38 @@ impl Engine {
                 Ok(Dynamic::UNIT)
             }
 
+            // Inline module definition
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Module(x, _pos) => {
+                // Guard against too many modules
+                #[cfg(not(feature = "unchecked"))]
+                if global.num_modules_loaded >= self.max_modules() {
+                    return Err(ERR::ErrorTooManyModules(*_pos).into());
+                }
+
+                // The module body keeps the source and embedded module resolver of the script
+                // that contains it
+                let resolver = global.embedded_module_resolver.clone();
+
+                let mut module = if resolver.is_none() && global.source.is_none() {
+                    crate::Module::eval_ast_as_new_raw(self, &mut Scope::new(), global, &x.body)?
+                } else {
+                    let mut body = x.body.clone();
+                    body.resolver = resolver;
+                    if let Some(ref source) = global.source {
+                        body.set_source(source.clone());
+                    }
+                    crate::Module::eval_ast_as_new_raw(self, &mut Scope::new(), global, &body)?
+                };
+
+                module.build_index();
+                global.push_import(x.name.name.clone(), module);
+
+                global.num_modules_loaded += 1;
+
+                Ok(Dynamic::UNIT)
+            }
+
             // Export statement
             #[cfg(not(feature = "no_module"))]
             Stmt::Export(x, ..) => {
@@ -1046,7 +1579,7 @@ impl Engine {
             .or_else(|err| match *err {
                 ERR::Return(out, ..) => Ok(out),
                 ERR::Exit(out, ..) if map_exit_to_return_value => Ok(out),
-                ERR::LoopBreak(..) => {
+                ERR::LoopBreak(..) | ERR::LoopBreakOuter(..) => {
                     unreachable!("no outer loop scope to break out of")
                 }
                 _ => Err(err),
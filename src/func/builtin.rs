@@ -8,8 +8,8 @@ use super::native::FnBuiltin;
 use crate::tokenizer::{Token, Token::*};
 use crate::types::dynamic::Union;
 use crate::{
-    Dynamic, ExclusiveRange, ImmutableString, InclusiveRange, NativeCallContext, RhaiResult,
-    SmartString, INT,
+    Dynamic, Engine, ExclusiveRange, ImmutableString, InclusiveRange, NativeCallContext,
+    RhaiResult, SmartString, INT,
 };
 use std::any::TypeId;
 #[cfg(feature = "no_std")]
@@ -25,6 +25,9 @@ use num_traits::Float;
 #[cfg(feature = "decimal")]
 use rust_decimal::Decimal;
 
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+
 /// The `unchecked` feature is not active.
 const CHECKED_BUILD: bool = cfg!(not(feature = "unchecked"));
 
@@ -57,6 +60,11 @@ fn is_numeric(typ: TypeId) -> bool {
         return true;
     }
 
+    #[cfg(feature = "bigint")]
+    if typ == TypeId::of::<BigInt>() {
+        return true;
+    }
+
     #[cfg(not(feature = "only_i32"))]
     #[cfg(not(feature = "only_i64"))]
     if typ == TypeId::of::<u8>()
@@ -84,8 +92,15 @@ fn is_numeric(typ: TypeId) -> bool {
 /// Build in common binary operator implementations to avoid the cost of calling a registered function.
 ///
 /// The return function will be registered as a _method_, so the first parameter cannot be consumed.
+///
+/// Integer arithmetic follows the [`Overflow`][crate::Overflow] mode of the [`Engine`].
 #[must_use]
-pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<FnBuiltin> {
+pub fn get_builtin_binary_op_fn(
+    op: &Token,
+    x: &Dynamic,
+    y: &Dynamic,
+    _engine: &Engine,
+) -> Option<FnBuiltin> {
     macro_rules! impl_op {
         ($xx:ident $op:tt $yy:ident) => { Some((|_, args| {
             let x = &*args[0].read_lock::<$xx>().unwrap();
@@ -126,6 +141,18 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
             let y = args[1].$yy().unwrap() as $base;
             $func(x, y).map(Into::into)
         }, false)) };
+        ($base:ty => overflow $func:ident ( $xx:ident, $yy:ident )) => {
+            match _engine.integer_overflow() {
+                Overflow::Checked => impl_op!($base => overflow Checked $func($xx, $yy)),
+                Overflow::Wrapping => impl_op!($base => overflow Wrapping $func($xx, $yy)),
+                Overflow::Saturating => impl_op!($base => overflow Saturating $func($xx, $yy)),
+            }
+        };
+        ($base:ty => overflow $mode:ident $func:ident ( $xx:ident, $yy:ident )) => { Some((|_, args| {
+            let x = args[0].$xx().unwrap() as $base;
+            let y = args[1].$yy().unwrap() as $base;
+            with_overflow::$func(Overflow::$mode, x, y).map(Into::into)
+        }, false)) };
         (from $base:ty => $xx:ident $op:tt $yy:ident) => { Some((|_, args| {
             let x = <$base>::from(args[0].$xx().unwrap());
             let y = <$base>::from(args[1].$yy().unwrap());
@@ -276,21 +303,66 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
         };
     }
 
+    #[cfg(feature = "bigint")]
+    macro_rules! impl_bigint {
+        () => {
+            {
+                #[allow(clippy::wildcard_imports)]
+                use crate::packages::arithmetic::bigint_builtin::*;
+
+                macro_rules! op {
+                    ($func:ident) => { Some((|ctx, args| {
+                        $func(ctx.as_ref(), &arg(args[0]), &arg(args[1])).map(Into::into)
+                    }, CHECKED_BUILD)) };
+                    (bits $op:tt) => { Some((|_, args| {
+                        Ok((&*arg(args[0]) $op &*arg(args[1])).into())
+                    }, false)) };
+                    ($op:tt) => { Some((|_, args| {
+                        Ok((*arg(args[0]) $op *arg(args[1])).into())
+                    }, false)) };
+                }
+
+                return match op {
+                    Plus                => op!(add),
+                    Minus               => op!(subtract),
+                    Multiply            => op!(multiply),
+                    Divide              => op!(divide),
+                    Modulo              => op!(modulo),
+                    PowerOf             => op!(power),
+                    LeftShift           => op!(shift_left),
+                    RightShift          => op!(shift_right),
+                    Ampersand           => op!(bits &),
+                    Pipe                => op!(bits |),
+                    XOr                 => op!(bits ^),
+                    EqualsTo            => op!(==),
+                    NotEqualsTo         => op!(!=),
+                    GreaterThan         => op!(>),
+                    GreaterThanEqualsTo => op!(>=),
+                    LessThan            => op!(<),
+                    LessThanEqualsTo    => op!(<=),
+                    _                   => None
+                };
+            }
+        };
+    }
+
     // Check for common patterns
     match (&x.0, &y.0, op) {
         (Union::Int(..), Union::Int(..), _) => {
             #[cfg(not(feature = "unchecked"))]
             #[allow(clippy::wildcard_imports)]
             use crate::packages::arithmetic::arith_basic::INT::functions::*;
+            #[cfg(not(feature = "unchecked"))]
+            use crate::{packages::arithmetic::arith_basic::INT::with_overflow, Overflow};
 
             #[cfg(not(feature = "unchecked"))]
             match op {
-                Plus => return impl_op!(INT => add(as_int, as_int)),
-                Minus => return impl_op!(INT => subtract(as_int, as_int)),
-                Multiply => return impl_op!(INT => multiply(as_int, as_int)),
-                Divide => return impl_op!(INT => divide(as_int, as_int)),
-                Modulo => return impl_op!(INT => modulo(as_int, as_int)),
-                PowerOf => return impl_op!(INT => power(as_int, as_int)),
+                Plus => return impl_op!(INT => overflow add(as_int, as_int)),
+                Minus => return impl_op!(INT => overflow subtract(as_int, as_int)),
+                Multiply => return impl_op!(INT => overflow multiply(as_int, as_int)),
+                Divide => return impl_op!(INT => overflow divide(as_int, as_int)),
+                Modulo => return impl_op!(INT => overflow modulo(as_int, as_int)),
+                PowerOf => return impl_op!(INT => overflow power(as_int, as_int)),
                 RightShift => return impl_op!(INT => Ok(shift_right(as_int, as_int))),
                 LeftShift => return impl_op!(INT => Ok(shift_left(as_int, as_int))),
                 Ampersand => return impl_op!(INT => Ok(binary_and(as_int, as_int))),
@@ -490,6 +562,12 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
             impl_decimal!(as_int, as_decimal)
         }
 
+        #[cfg(feature = "bigint")]
+        (Union::BigInt(..), Union::BigInt(..) | Union::Int(..), _)
+        | (Union::Int(..), Union::BigInt(..), _) => {
+            impl_bigint!()
+        }
+
         // Ranges
         (Union::Int(..), Union::Unit(..), ExclusiveRange) => {
             return Some((
@@ -696,8 +774,15 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
 /// Build in common operator assignment implementations to avoid the cost of calling a registered function.
 ///
 /// The return function is registered as a _method_, so the first parameter cannot be consumed.
+///
+/// Integer arithmetic follows the [`Overflow`][crate::Overflow] mode of the [`Engine`].
 #[must_use]
-pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<FnBuiltin> {
+pub fn get_builtin_op_assignment_fn(
+    op: &Token,
+    x: &Dynamic,
+    y: &Dynamic,
+    _engine: &Engine,
+) -> Option<FnBuiltin> {
     macro_rules! impl_op {
         ($x:ty = x $op:tt $yy:ident) => { Some((|_, args| {
             let x = args[0].$yy().unwrap();
@@ -728,6 +813,19 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
             let y = args[1].$yy().unwrap() as $x;
             Ok((*args[0].write_lock().unwrap() = $func(x, y)?).into())
         }, false)) };
+        ($x:ty => overflow $func:ident ( $xx:ident, $yy:ident )) => {
+            match _engine.integer_overflow() {
+                Overflow::Checked => impl_op!($x => overflow Checked $func($xx, $yy)),
+                Overflow::Wrapping => impl_op!($x => overflow Wrapping $func($xx, $yy)),
+                Overflow::Saturating => impl_op!($x => overflow Saturating $func($xx, $yy)),
+            }
+        };
+        ($x:ty => overflow $mode:ident $func:ident ( $xx:ident, $yy:ident )) => { Some((|_, args| {
+            let x = args[0].$xx().unwrap();
+            let y = args[1].$yy().unwrap() as $x;
+            let v = with_overflow::$func(Overflow::$mode, x, y)?;
+            Ok((*args[0].write_lock().unwrap() = v).into())
+        }, false)) };
         (from $x:ident $op:tt $yy:ident) => { Some((|_, args| {
             let y = <$x>::from(args[1].$yy().unwrap());
             Ok((*args[0].write_lock::<$x>().unwrap() $op y).into())
@@ -800,21 +898,59 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
         };
     }
 
+    #[cfg(feature = "bigint")]
+    macro_rules! impl_bigint {
+        () => {
+            {
+                #[allow(clippy::wildcard_imports)]
+                use crate::packages::arithmetic::bigint_builtin::*;
+
+                macro_rules! op {
+                    ($func:ident) => { Some((|ctx, args| {
+                        let v = $func(ctx.as_ref(), &arg(args[0]), &arg(args[1]))?;
+                        Ok((*args[0].write_lock::<Dynamic>().unwrap() = v.into()).into())
+                    }, CHECKED_BUILD)) };
+                    (bits $op:tt) => { Some((|_, args| {
+                        let v = &*arg(args[0]) $op &*arg(args[1]);
+                        Ok((*args[0].write_lock::<Dynamic>().unwrap() = v.into()).into())
+                    }, false)) };
+                }
+
+                return match op {
+                    PlusAssign          => op!(add),
+                    MinusAssign         => op!(subtract),
+                    MultiplyAssign      => op!(multiply),
+                    DivideAssign        => op!(divide),
+                    ModuloAssign        => op!(modulo),
+                    PowerOfAssign       => op!(power),
+                    LeftShiftAssign     => op!(shift_left),
+                    RightShiftAssign    => op!(shift_right),
+                    AndAssign           => op!(bits &),
+                    OrAssign            => op!(bits |),
+                    XOrAssign           => op!(bits ^),
+                    _                   => None
+                };
+            }
+        };
+    }
+
     // Check for common patterns
     match (&x.0, &y.0, op) {
         (Union::Int(..), Union::Int(..), _) => {
             #[cfg(not(feature = "unchecked"))]
             #[allow(clippy::wildcard_imports)]
             use crate::packages::arithmetic::arith_basic::INT::functions::*;
+            #[cfg(not(feature = "unchecked"))]
+            use crate::{packages::arithmetic::arith_basic::INT::with_overflow, Overflow};
 
             #[cfg(not(feature = "unchecked"))]
             match op {
-                PlusAssign => return impl_op!(INT => add(as_int, as_int)),
-                MinusAssign => return impl_op!(INT => subtract(as_int, as_int)),
-                MultiplyAssign => return impl_op!(INT => multiply(as_int, as_int)),
-                DivideAssign => return impl_op!(INT => divide(as_int, as_int)),
-                ModuloAssign => return impl_op!(INT => modulo(as_int, as_int)),
-                PowerOfAssign => return impl_op!(INT => power(as_int, as_int)),
+                PlusAssign => return impl_op!(INT => overflow add(as_int, as_int)),
+                MinusAssign => return impl_op!(INT => overflow subtract(as_int, as_int)),
+                MultiplyAssign => return impl_op!(INT => overflow multiply(as_int, as_int)),
+                DivideAssign => return impl_op!(INT => overflow divide(as_int, as_int)),
+                ModuloAssign => return impl_op!(INT => overflow modulo(as_int, as_int)),
+                PowerOfAssign => return impl_op!(INT => overflow power(as_int, as_int)),
                 RightShiftAssign => return impl_op!(INT => Ok(shift_right(as_int, as_int))),
                 LeftShiftAssign => return impl_op!(INT => Ok(shift_left(as_int, as_int))),
                 AndAssign => return impl_op!(INT => Ok(binary_and(as_int, as_int))),
@@ -1001,6 +1137,11 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
             impl_decimal!(Decimal, as_decimal, as_int)
         }
 
+        #[cfg(feature = "bigint")]
+        (Union::BigInt(..), Union::BigInt(..) | Union::Int(..), _) => {
+            impl_bigint!()
+        }
+
         // string op= char
         (Union::Str(..), Union::Char(..), _) => match op {
             PlusAssign => Some((
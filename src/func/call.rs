@@ -135,6 +135,20 @@ pub fn is_anonymous_fn(name: &str) -> bool {
     name.starts_with(crate::engine::FN_ANONYMOUS)
 }
 
+/// Calculate the hashes of a function call with spread arguments, once the actual number of
+/// arguments is known.
+#[inline]
+#[must_use]
+fn calc_spread_hashes(fn_name: &str, num_args: usize) -> FnCallHashes {
+    let hash = calc_fn_hash(None, fn_name, num_args);
+
+    if is_valid_identifier(fn_name) {
+        FnCallHashes::from_hash(hash)
+    } else {
+        FnCallHashes::from_native_only(hash)
+    }
+}
+
 impl Engine {
     /// Generate the signature for a function call.
     #[inline]
@@ -268,34 +282,33 @@ impl Engine {
                         }
 
                         // Try to find a built-in version
-                        let builtin =
-                            args.and_then(|args| match op_token {
-                                None => None,
-                                Some(token) if token.is_op_assignment() => {
-                                    let (first_arg, rest_args) = args.split_first().unwrap();
-
-                                    get_builtin_op_assignment_fn(token, first_arg, rest_args[0])
-                                        .map(|(f, has_context)| FnResolutionCacheEntry {
-                                            func: RhaiFunc::Method {
-                                                func: Shared::new(f),
-                                                has_context,
-                                                is_pure: false,
-                                                is_volatile: false,
-                                            },
-                                            source: None,
-                                        })
-                                }
-                                Some(token) => get_builtin_binary_op_fn(token, args[0], args[1])
+                        let builtin = args.and_then(|args| match op_token {
+                            None => None,
+                            Some(token) if token.is_op_assignment() => {
+                                let (first_arg, rest_args) = args.split_first().unwrap();
+
+                                get_builtin_op_assignment_fn(token, first_arg, rest_args[0], self)
                                     .map(|(f, has_context)| FnResolutionCacheEntry {
                                         func: RhaiFunc::Method {
                                             func: Shared::new(f),
                                             has_context,
-                                            is_pure: true,
+                                            is_pure: false,
                                             is_volatile: false,
                                         },
                                         source: None,
-                                    }),
-                            });
+                                    })
+                            }
+                            Some(token) => get_builtin_binary_op_fn(token, args[0], args[1], self)
+                                .map(|(f, has_context)| FnResolutionCacheEntry {
+                                    func: RhaiFunc::Method {
+                                        func: Shared::new(f),
+                                        has_context,
+                                        is_pure: true,
+                                        is_volatile: false,
+                                    },
+                                    source: None,
+                                }),
+                        });
 
                         return if cache.bloom_filter.is_absent_and_set(hash) {
                             // Do not cache "one-hit wonders"
@@ -400,6 +413,9 @@ impl Engine {
                 );
             }
 
+            #[cfg(feature = "profiling")]
+            let entry = self.prof_enter(global, || self.get_interned_string(name));
+
             // Run external function
             let context = func
                 .has_context()
@@ -416,6 +432,9 @@ impl Engine {
             .and_then(|r| self.check_data_size(r, pos))
             .map_err(|err| err.fill_position(pos));
 
+            #[cfg(feature = "profiling")]
+            self.prof_exit(global, entry);
+
             if swap {
                 backup.restore_first_arg(args);
             }
@@ -596,7 +615,7 @@ impl Engine {
         match fn_name {
             // Handle type_of()
             KEYWORD_TYPE_OF if args.len() == 1 => {
-                let typ = self.get_interned_string(self.map_type_name(args[0].type_name()));
+                let typ = self.get_interned_string(&*self.map_value_type_name(args[0]));
                 return Ok(Some(typ.into()));
             }
             KEYWORD_TYPE_OF => (),
@@ -702,8 +721,14 @@ impl Engine {
         defer! { let orig_level = global.level; global.level += 1 }
 
         // Script-defined function call?
+        // Under Fast Operators mode, script-defined operator functions only overload operators
+        // for custom types and structs.
         #[cfg(not(feature = "no_function"))]
-        if !hashes.is_native_only() {
+        if !hashes.is_native_only()
+            && (op_token.is_none()
+                || !self.fast_operators()
+                || args.iter().any(|a| a.is_overloadable()))
+        {
             let hash = hashes.script();
             let local_entry = &mut None;
             let mut resolved = None;
@@ -711,7 +736,7 @@ impl Engine {
             #[cfg(not(feature = "no_object"))]
             if _is_method_call && !args.is_empty() {
                 let typed_hash =
-                    crate::calc_typed_method_hash(hash, self.map_type_name(args[0].type_name()));
+                    crate::calc_typed_method_hash(hash, &self.map_value_type_name(args[0]));
                 resolved =
                     self.resolve_fn(global, caches, local_entry, None, typed_hash, None, false);
             }
@@ -725,7 +750,7 @@ impl Engine {
                     unreachable!("Script function expected");
                 };
 
-                let fn_def = &*fn_def;
+                let fn_def = &fn_def;
                 let env = env.as_deref();
 
                 if fn_def.body.is_empty() {
@@ -832,6 +857,27 @@ impl Engine {
         self.eval_expr(global, caches, scope, this_ptr, arg_expr)
             .map(|r| (r, arg_expr.start_position()))
     }
+    /// Evaluate an argument and add its value to a list, expanding a spread argument (i.e.
+    /// `...args`) in place.
+    #[inline]
+    fn push_arg_values(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        arg_expr: &Expr,
+        arg_values: &mut FnArgsVec<Dynamic>,
+    ) -> RhaiResultOf<()> {
+        if let Expr::Spread(items_expr, ..) = arg_expr {
+            let items = self.eval_spread_items(global, caches, scope, this_ptr, items_expr)?;
+            arg_values.extend(items);
+        } else {
+            let (value, ..) = self.get_arg_value(global, caches, scope, this_ptr, arg_expr)?;
+            arg_values.push(value.flatten());
+        }
+        Ok(())
+    }
 
     /// Call a dot method.
     #[cfg(not(feature = "no_object"))]
@@ -1211,10 +1257,43 @@ impl Engine {
         let mut fn_name = fn_name;
         let mut hashes = hashes;
         let redirected; // Handle call() - Redirect function call
+        let has_spread = first_arg
+            .into_iter()
+            .chain(args_expr)
+            .any(|expr| matches!(expr, Expr::Spread(..)));
 
         match fn_name {
             _ if op_token.is_some() => (),
 
+            // Handle call(fn_ptr, ...args)
+            KEYWORD_FN_PTR_CALL if has_spread && !matches!(first_arg, Some(Expr::Spread(..))) => {
+                let arg = first_arg.unwrap();
+                let (first_arg_value, first_arg_pos) =
+                    self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), arg)?;
+
+                let fn_ptr = first_arg_value.try_cast_result::<FnPtr>().map_err(|v| {
+                    self.make_type_mismatch_err::<FnPtr>(
+                        self.map_type_name(v.type_name()),
+                        first_arg_pos,
+                    )
+                })?;
+
+                let mut arg_values = FnArgsVec::with_capacity(args_expr.len());
+                for expr in args_expr {
+                    let this_ptr = this_ptr.as_deref_mut();
+                    self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
+                }
+
+                let context = (self, fn_name, None, &*global, pos).into();
+
+                return fn_ptr
+                    .call_raw(&context, None, arg_values)
+                    .map_err(|err| err.fill_position(pos));
+            }
+
+            // Other special functions do not take spread arguments
+            _ if has_spread => (),
+
             // Handle call(fn_ptr, ...)
             KEYWORD_FN_PTR_CALL if num_args >= 1 => {
                 let arg = first_arg.unwrap();
@@ -1432,13 +1511,16 @@ impl Engine {
         // variable access) to &mut because `scope` is needed.
         if capture_parent_scope && !scope.is_empty() {
             for expr in first_arg.iter().copied().chain(args_expr.iter()) {
-                let (value, ..) =
-                    self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
-                arg_values.push(value.flatten());
+                let this_ptr = this_ptr.as_deref_mut();
+                self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
             }
             args.extend(curry.iter_mut());
             args.extend(arg_values.iter_mut());
 
+            if has_spread {
+                hashes = calc_spread_hashes(fn_name, args.len());
+            }
+
             // Use parent scope
             let scope = Some(scope);
 
@@ -1469,9 +1551,8 @@ impl Engine {
 
                 // func(x, ...) -> x.func(...)
                 for expr in args_expr {
-                    let (value, ..) =
-                        self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
-                    arg_values.push(value.flatten());
+                    let this_ptr = this_ptr.as_deref_mut();
+                    self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
                 }
 
                 is_ref_mut = true;
@@ -1485,9 +1566,8 @@ impl Engine {
 
                 // func(x, ...) -> x.func(...)
                 for expr in args_expr {
-                    let (value, ..) =
-                        self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
-                    arg_values.push(value.flatten());
+                    let this_ptr = this_ptr.as_deref_mut();
+                    self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
                 }
 
                 let mut target = self.search_namespace(global, caches, scope, this_ptr, first)?;
@@ -1508,9 +1588,8 @@ impl Engine {
             _ => {
                 // func(..., ...)
                 for expr in first_arg.into_iter().chain(args_expr.iter()) {
-                    let (value, ..) =
-                        self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
-                    arg_values.push(value.flatten());
+                    let this_ptr = this_ptr.as_deref_mut();
+                    self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
                 }
                 args.extend(curry.iter_mut());
             }
@@ -1518,6 +1597,10 @@ impl Engine {
 
         args.extend(arg_values.iter_mut());
 
+        if has_spread {
+            hashes = calc_spread_hashes(fn_name, args.len());
+        }
+
         self.exec_fn_call(
             global, caches, None, fn_name, op_token, hashes, &mut args, is_ref_mut, false, pos,
         )
@@ -1561,9 +1644,8 @@ impl Engine {
                 arg_values.push(Dynamic::UNIT);
 
                 for expr in args_expr.iter().skip(1) {
-                    let (value, ..) =
-                        self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
-                    arg_values.push(value.flatten());
+                    let this_ptr = this_ptr.as_deref_mut();
+                    self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
                 }
 
                 // func(x, ...) -> x.func(...)
@@ -1582,9 +1664,8 @@ impl Engine {
                 arg_values.push(Dynamic::UNIT);
 
                 for expr in args_expr.iter().skip(1) {
-                    let (value, ..) =
-                        self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
-                    arg_values.push(value.flatten());
+                    let this_ptr = this_ptr.as_deref_mut();
+                    self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
                 }
 
                 let target = self.search_namespace(global, caches, scope, this_ptr, first)?;
@@ -1605,15 +1686,25 @@ impl Engine {
             Some(_) => {
                 // func(..., ...) or func(mod::x, ...)
                 for expr in args_expr {
-                    let (value, ..) =
-                        self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
-                    arg_values.push(value.flatten());
+                    let this_ptr = this_ptr.as_deref_mut();
+                    self.push_arg_values(global, caches, scope, this_ptr, expr, &mut arg_values)?;
                 }
                 args.extend(arg_values.iter_mut());
             }
             None => (),
         }
 
+        // Recalculate hash for spread arguments
+        let hash = if args_expr
+            .iter()
+            .any(|expr| matches!(expr, Expr::Spread(..)))
+        {
+            let path = namespace.path.iter().map(crate::ast::Ident::as_str);
+            calc_fn_hash(path, fn_name, args.len())
+        } else {
+            hash
+        };
+
         // Search for the root namespace
         let module = self
             .search_imports(global, namespace)
@@ -1689,25 +1780,35 @@ impl Engine {
                 Err(ERR::ErrorNonPureMethodCallOnConstant(fn_name.to_string(), pos).into())
             }
 
-            Some(RhaiFunc::Plugin { func }) => {
-                let context = func
+            Some(
+                f @ (RhaiFunc::Plugin { .. } | RhaiFunc::Pure { .. } | RhaiFunc::Method { .. }),
+            ) => {
+                #[cfg(feature = "profiling")]
+                let entry = self.prof_enter(global, || {
+                    let sep = crate::engine::NAMESPACE_SEPARATOR;
+                    match module.id() {
+                        Some(id) => format!("{id}{sep}{fn_name}").into(),
+                        None => format!("{namespace}{sep}{fn_name}").into(),
+                    }
+                });
+
+                let context = f
                     .has_context()
                     .then(|| (self, fn_name, module.id(), &*global, pos).into());
-                func.call(context, args)
-                    .and_then(|r| self.check_data_size(r, pos))
-            }
 
-            Some(
-                RhaiFunc::Pure {
-                    func, has_context, ..
+                let result = match f {
+                    RhaiFunc::Plugin { func } => func.call(context, args),
+                    RhaiFunc::Pure { func, .. } | RhaiFunc::Method { func, .. } => {
+                        func(context, args)
+                    }
+                    _ => unreachable!("native function expected"),
                 }
-                | RhaiFunc::Method {
-                    func, has_context, ..
-                },
-            ) => {
-                let context =
-                    has_context.then(|| (self, fn_name, module.id(), &*global, pos).into());
-                func(context, args).and_then(|r| self.check_data_size(r, pos))
+                .and_then(|r| self.check_data_size(r, pos));
+
+                #[cfg(feature = "profiling")]
+                self.prof_exit(global, entry);
+
+                result
             }
 
             Some(RhaiFunc::Iterator { .. }) => {
@@ -1829,12 +1930,12 @@ impl Engine {
                 .0
                 .flatten();
 
-            if lhs.is_variant() || rhs.is_variant() {
-                // For custom types, give registered functions a chance to run first before considering the built-in fallback
+            if lhs.is_overloadable() || rhs.is_overloadable() {
+                // For custom types and structs, give registered and script-defined functions a chance to run first before considering the built-in fallback
             } else {
                 // For other types, try to get a built-in
                 if let Some((func, need_context)) =
-                    get_builtin_binary_op_fn(op_token.unwrap(), &lhs, &rhs)
+                    get_builtin_binary_op_fn(op_token.unwrap(), &lhs, &rhs, self)
                 {
                     // We may not need to bump the level because built-in's do not need it.
                     //defer! { let orig_level = global.level; global.level += 1 }
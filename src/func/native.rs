@@ -570,6 +570,29 @@ impl<'a> NativeCallContext<'a> {
             )
             .map(|(r, ..)| r)
     }
+    /// Resume a [`Generator`][crate::Generator] inside the call context, passing each item to the
+    /// callback until it returns `false` or the generator completes.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn resume_generator(
+        &self,
+        generator: &crate::Generator,
+        mut on_item: impl FnMut(Dynamic) -> RhaiResultOf<bool>,
+    ) -> RhaiResultOf<()> {
+        let global = &mut self.global.clone();
+        let caches = &mut Caches::new();
+        let pos = self.call_position();
+
+        while let Some(item) = self
+            .engine()
+            .resume_generator(global, caches, generator, pos)?
+        {
+            if !on_item(item)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Return a mutable reference to the wrapped value of a [`Shared`] resource.
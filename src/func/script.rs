@@ -4,7 +4,7 @@
 use super::call::FnCallArgs;
 use crate::ast::{EncapsulatedEnviron, ScriptFuncDef};
 use crate::eval::{Caches, GlobalRuntimeState};
-use crate::{Dynamic, Engine, Position, RhaiResult, Scope, ERR};
+use crate::{Dynamic, Engine, Position, RhaiResult, Scope, Shared, ERR};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -28,7 +28,7 @@ impl Engine {
         scope: &mut Scope,
         mut this_ptr: Option<&mut Dynamic>,
         _env: Option<&EncapsulatedEnviron>,
-        fn_def: &ScriptFuncDef,
+        fn_def: &Shared<ScriptFuncDef>,
         args: &mut FnCallArgs,
         rewind_scope: bool,
         pos: Position,
@@ -52,8 +52,16 @@ impl Engine {
             return Ok(Dynamic::UNIT);
         }
 
+        // Calling a generator function only creates the generator
+        if fn_def.is_generator {
+            let generator = self.make_generator(global, _env, fn_def, this_ptr, args);
+            return Ok(Dynamic::from(generator));
+        }
+
         let orig_scope_len = scope.len();
         let orig_lib_len = global.lib.len();
+        let orig_statics = std::mem::replace(&mut global.statics, fn_def.statics.clone());
+
         #[cfg(not(feature = "no_module"))]
         let orig_imports_len = global.num_imports();
 
@@ -117,6 +125,15 @@ impl Engine {
             self.dbg(global, caches, scope, this_ptr.as_deref_mut(), &node)?;
         }
 
+        #[cfg(feature = "profiling")]
+        let entry = self.prof_enter(global, || {
+            #[cfg(not(feature = "no_module"))]
+            if let Some(id) = _env.and_then(|env| env.lib.last()).and_then(|m| m.id()) {
+                return format!("{id}{}{}", crate::engine::NAMESPACE_SEPARATOR, fn_def.name).into();
+            }
+            fn_def.name.clone()
+        });
+
         // Evaluate the function
         let mut _result: RhaiResult = self
             .eval_stmt_block(
@@ -156,6 +173,9 @@ impl Engine {
                 .into()),
             });
 
+        #[cfg(feature = "profiling")]
+        self.prof_exit(global, entry);
+
         #[cfg(feature = "debugging")]
         if self.is_debugger_registered() {
             let trigger = match global.debugger_mut().status {
@@ -203,6 +223,9 @@ impl Engine {
         }
 
         // Restore state
+        global.statics = orig_statics;
+        #[cfg(feature = "no_closure")]
+        global.drop_static_variables(0);
         caches.rewind_fn_resolution_caches(orig_fn_resolution_caches_len);
 
         _result
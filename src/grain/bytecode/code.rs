@@ -182,6 +182,18 @@ pub mod tag {
     pub const STATEMENT: u8 = 0x47;
     /// [`Op::StoreLocal`](super::Op::StoreLocal) as a constant.
     pub const STORE_CONST: u8 = 0x48;
+    /// [`Op::CollectStart`](super::Op::CollectStart) for an array.
+    pub const COLLECT_ARRAY: u8 = 0x49;
+    /// [`Op::CollectStart`](super::Op::CollectStart) for a map.
+    pub const COLLECT_MAP: u8 = 0x4a;
+    /// [`Op::CollectKey`](super::Op::CollectKey).
+    pub const COLLECT_KEY: u8 = 0x4b;
+    /// [`Op::Collect`](super::Op::Collect) of an array element.
+    pub const COLLECT_ITEM: u8 = 0x4c;
+    /// [`Op::Collect`](super::Op::Collect) of a map entry.
+    pub const COLLECT_ENTRY: u8 = 0x4d;
+    /// [`Op::CollectEnd`](super::Op::CollectEnd).
+    pub const COLLECT_END: u8 = 0x4e;
 }
 
 /// How wide each tag's instruction is, with 0 for the tags that are not one.
@@ -217,6 +229,12 @@ static WIDTHS: [u8; 256] = {
     widths[tag::INTERPOLATE_END as usize] = 1;
     widths[tag::MAKE_FN_PTR as usize] = 1;
     widths[tag::IS_SHARED as usize] = 1;
+    widths[tag::COLLECT_ARRAY as usize] = 1;
+    widths[tag::COLLECT_MAP as usize] = 1;
+    widths[tag::COLLECT_KEY as usize] = 1;
+    widths[tag::COLLECT_ITEM as usize] = 1;
+    widths[tag::COLLECT_ENTRY as usize] = 1;
+    widths[tag::COLLECT_END as usize] = 1;
 
     widths[tag::CURRY as usize] = 2;
     widths[tag::ROTATE as usize] = 2;
@@ -593,6 +611,19 @@ pub fn assemble(ops: &[Op]) -> Result<(Vec<u8>, Vec<u32>), AssembleError> {
                 code.extend_from_slice(&index.to_le_bytes());
             }
 
+            Op::CollectStart { map } => code.push(if *map {
+                tag::COLLECT_MAP
+            } else {
+                tag::COLLECT_ARRAY
+            }),
+            Op::CollectKey => code.push(tag::COLLECT_KEY),
+            Op::Collect { map } => code.push(if *map {
+                tag::COLLECT_ENTRY
+            } else {
+                tag::COLLECT_ITEM
+            }),
+            Op::CollectEnd => code.push(tag::COLLECT_END),
+
             Op::Share(slot) => {
                 code.push(tag::SHARE);
                 code.extend_from_slice(&slot.to_le_bytes());
@@ -773,6 +804,10 @@ fn encoded_width(op: &Op) -> usize {
         | Op::InterpolateEnd
         | Op::MakeFnPtr
         | Op::IsShared
+        | Op::CollectStart { .. }
+        | Op::CollectKey
+        | Op::Collect { .. }
+        | Op::CollectEnd
         | Op::LoadThis
         | Op::LoadThisShared
         | Op::RequireThis
@@ -960,6 +995,12 @@ pub fn decode(code: &[u8], at: usize) -> Option<Op> {
         tag::MAKE_CLOSURE => Op::MakeClosure(u32::from(small(1)?)),
         tag::MAKE_FN_PTR => Op::MakeFnPtr,
         tag::IS_SHARED => Op::IsShared,
+        tag::COLLECT_ARRAY => Op::CollectStart { map: false },
+        tag::COLLECT_MAP => Op::CollectStart { map: true },
+        tag::COLLECT_KEY => Op::CollectKey,
+        tag::COLLECT_ITEM => Op::Collect { map: false },
+        tag::COLLECT_ENTRY => Op::Collect { map: true },
+        tag::COLLECT_END => Op::CollectEnd,
         tag::CURRY => Op::Curry(code[at + 1]),
         tag::CALL_FN_PTR => Op::CallFnPtr {
             argc: code[at + 1],
@@ -1173,6 +1214,12 @@ mod tests {
             Op::AssignThis { op: None },
             Op::AssignThis { op: Some(6) },
             Op::Rotate(3),
+            Op::CollectStart { map: false },
+            Op::CollectStart { map: true },
+            Op::CollectKey,
+            Op::Collect { map: false },
+            Op::Collect { map: true },
+            Op::CollectEnd,
             Op::UnwindTo(6),
             Op::Tick,
             Op::Statement { depth: 2 },
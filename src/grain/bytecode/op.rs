@@ -485,6 +485,39 @@ pub enum Op {
         map: bool,
     },
 
+    /// Push an empty array, or an empty map, for a comprehension to collect
+    /// into, and start a fresh running total for it.
+    ///
+    /// The container sits on the operand stack for the whole loop, beneath
+    /// whatever each item evaluates; [`Op::Collect`] adds to it in place.
+    CollectStart {
+        /// Whether the comprehension builds a map rather than an array.
+        map: bool,
+    },
+
+    /// Check that the key on top of the stack, evaluated for a map
+    /// comprehension's item, is a string.
+    ///
+    /// Separate from [`Op::Collect`] because Rhai blames the *key* for a key
+    /// that is not a string, and does so before the value is evaluated
+    /// (`eval/stmt.rs:collect_comprehension`).
+    CollectKey,
+
+    /// Pop an item — a value, or a key and a value — and add it to the
+    /// comprehension's container beneath, raising `ErrorDataTooLarge` if that
+    /// puts the running total over a configured limit.
+    ///
+    /// A map entry that replaces another takes the replaced value out of the
+    /// total, as Rhai does.
+    Collect {
+        /// Whether the item is a map entry rather than an array element.
+        map: bool,
+    },
+
+    /// Discard the running total of the comprehension just finished, leaving
+    /// its container on the stack.
+    CollectEnd,
+
     /// Walk `a.b[i].c`, indexing the chain pool.
     ///
     /// One instruction for the whole chain rather than one per step, because
@@ -485,6 +485,13 @@ fn effect(op: &Op, pools: Pools) -> (usize, usize, usize) {
         // still on the stack.
         Op::CheckSize { .. } => (1, 1, 1),
 
+        // The container stays on the stack for the whole comprehension, beneath
+        // each item as it is evaluated and then taken into it.
+        Op::CollectStart { .. } => (0, 0, 1),
+        Op::CollectKey | Op::CollectEnd => (1, 1, 1),
+        Op::Collect { map: false } => (2, 1, 0),
+        Op::Collect { map: true } => (3, 2, 0),
+
         // The buffer is an ordinary operand: started, appended to, then
         // replaced by the string it built.
         // A name in, a pointer out.
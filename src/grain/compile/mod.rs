@@ -7,10 +7,13 @@ use core::mem;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+use crate::ast::ComprehensionExpr;
 #[cfg(not(feature = "no_function"))]
 use crate::ast::ScriptFuncDef;
 use crate::ast::{
-    ASTFlags, Expr, FlowControl, FnCallExpr, OpAssignment, Stmt, StmtBlock, SwitchCasesCollection,
+    ASTFlags, ASTNode, CatchClause, Expr, FlowControl, FnCallExpr, OpAssignment, Stmt, StmtBlock,
+    SwitchCasesCollection, TryCatchBlock,
 };
 use crate::tokenizer::Token;
 use crate::types::{dynamic::AccessMode, Span};
@@ -255,6 +258,24 @@ struct Loop {
     breaks: Vec<usize>,
 }
 
+/// A `finally` block a jump out of its `try` has to run on the way.
+///
+/// The handler that runs it on an error never sees a `break`, `continue` or
+/// `return` — those are jumps here, not errors — so each one inlines the
+/// blocks it leaves.
+#[derive(Clone)]
+struct Finally {
+    statements: Vec<Stmt>,
+    /// Slot depth at the `try`. The walker has rewound the body's locals by
+    /// the time the block runs, and the block's names resolve without them.
+    depth: usize,
+    /// How many `try` regions were armed outside this one.
+    handlers: usize,
+    /// How many loops enclose the `try`, so a `break` can tell whether it
+    /// leaves it.
+    loops: usize,
+}
+
 /// Where a `switch` table entry sends control, before the arms have
 /// addresses.
 #[derive(Debug, Clone, Copy)]
@@ -301,6 +322,9 @@ struct Lowering {
     /// The same for `try` regions: a `break` out of one has to disarm it, or
     /// the next unrelated error is caught into a block already left.
     handlers: usize,
+    /// The `finally` blocks of the `try` statements being lowered, innermost
+    /// last.
+    finallies: Vec<Finally>,
     /// Names that are script functions rather than variables.
     script_fns: Vec<ImmutableString>,
     /// How many statements enclose the one being lowered, for the marker
@@ -530,6 +554,126 @@ impl Lowering {
         true
     }
 
+    /// Lower an array or map comprehension: `[value for x in seq if cond]`,
+    /// `#{key: value for (x, i) in seq}`.
+    ///
+    /// The loop is `for`'s — the iterable first, then the counter and the
+    /// variable declared once and written each time round — with the container
+    /// it builds waiting on the operand stack beneath each item. The container
+    /// is started only once the iterable is off the stack, so a jump out of the
+    /// iterable leaves nothing of it behind.
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    fn comprehension(&mut self, expr: &Expr, x: &ComprehensionExpr) {
+        let ComprehensionExpr {
+            key,
+            value,
+            var,
+            counter,
+            iterable,
+            condition,
+        } = x;
+
+        // A `break`, `continue` or `return` leaving an item would skip the end
+        // of the comprehension, and with it the container and its running
+        // total. The walker unwinds it as an error, which a fragment cannot
+        // turn back into a jump, so the whole lowering goes. One that stays
+        // inside a loop in the item is that loop's business.
+        let mut escapes = false;
+        let mut on_node = |path: &[ASTNode]| {
+            if let Some(ASTNode::Stmt(stmt)) = path.last() {
+                let loops = path
+                    .iter()
+                    .filter(|node| {
+                        matches!(
+                            node,
+                            ASTNode::Stmt(Stmt::For(..) | Stmt::While(..) | Stmt::Do(..))
+                        )
+                    })
+                    .count();
+                escapes |= match stmt {
+                    Stmt::BreakLoop(.., levels, _) => usize::from(*levels) >= loops,
+                    _ => is_jump(stmt),
+                };
+            }
+            !escapes
+        };
+        for e in condition.iter().chain(key).chain(std::iter::once(value)) {
+            e.walk(&mut Vec::new(), &mut on_node);
+        }
+        if escapes {
+            self.residual_expr(expr);
+            self.defeated = true;
+            return;
+        }
+        if self.slots.is_full() {
+            self.residual_expr(expr);
+            return;
+        }
+
+        let outside = u16::try_from(self.slots.depth()).expect("slot count is bounded");
+
+        self.expression(iterable);
+        self.emit_at(Op::IterInit, iterable.start_position());
+        self.iters += 1;
+        self.emit(Op::CollectStart { map: key.is_some() });
+
+        // Counter first, as for `for`.
+        let counter_slot = counter.as_ref().map(|ident| {
+            let name = self.push_name(ident.name.clone());
+            self.emit(Op::Unit);
+            self.emit(Op::DeclareLocal {
+                name,
+                is_const: false,
+            });
+            self.slots.declare(ident.name.clone());
+            self.slots.depth() as u16 - 1
+        });
+        let var_name = self.push_name(var.name.clone());
+        self.emit(Op::Unit);
+        self.emit(Op::DeclareLocal {
+            name: var_name,
+            is_const: false,
+        });
+        self.slots.declare(var.name.clone());
+        let var_slot = self.slots.depth() as u16 - 1;
+
+        let top = self.here();
+        let exit = self.code.len();
+        self.emit_at(
+            Op::IterNext {
+                exit: u32::MAX,
+                indexed: counter_slot.is_some(),
+            },
+            iterable.position(),
+        );
+        self.emit(Op::StoreShared(var_slot));
+        if let Some(slot) = counter_slot {
+            self.emit(Op::StoreShared(slot));
+        }
+        self.emit_at(Op::Tick, value.position());
+
+        // A declined item goes straight round again.
+        if let Some(condition) = condition {
+            self.expression(condition);
+            let skip = self.emit_jump_if_false(condition.position());
+            self.patch_to(skip, top);
+        }
+        if let Some(key) = key {
+            self.expression(key);
+            self.emit_at(Op::CollectKey, key.position());
+        }
+        self.expression(value);
+        self.emit_at(Op::Collect { map: key.is_some() }, value.position());
+        self.emit(Op::Jump(top));
+
+        // Exhausted: `IterNext` dropped the iterator on the way here.
+        self.patch_to(exit, self.here());
+        self.iters -= 1;
+        self.emit(Op::UnwindTo(outside));
+        self.slots.unwind_to(outside as usize);
+        self.emit(Op::CollectEnd);
+    }
+
     /// Lower a `switch` into dispatch tables plus the arms they name.
     ///
     /// The layout is: evaluate and keep the subject, [`Op::Switch`] over
@@ -544,7 +688,8 @@ impl Lowering {
     /// Nearly every arm anyone writes has no guard, and those cost no chain at
     /// all.
     fn switch(&mut self, subject: &Expr, sw: &SwitchCasesCollection) -> bool {
-        if self.slots.is_full() {
+        // Destructuring patterns bind variables per arm; leave them to the tree-walker.
+        if self.slots.is_full() || !sw.patterns.is_empty() {
             return false;
         }
         let unwind_depth = self.slots.depth();
@@ -834,10 +979,22 @@ impl Lowering {
     /// (`func/script.rs:73`), so the parameters are exactly slots 0 upwards.
     #[cfg(not(feature = "no_function"))]
     fn function(&mut self, def: &ScriptFuncDef) -> Option<LoweredFn> {
+        // A call to a generator returns a handle that runs the body lazily,
+        // one `yield` at a time. That is the walker's job; the body stays there.
+        if def.is_generator {
+            return None;
+        }
+        // `static` variables live with the function definition and are swapped in for each call
+        // (`func/script.rs`), which the slot model has no place for.
+        if def.statics.is_some() {
+            return None;
+        }
+
         let first_op = self.code.len();
         let first_residual = self.residuals.len();
         let saved_slots = mem::take(&mut self.slots);
         let saved_loops = mem::take(&mut self.loops);
+        let saved_finallies = mem::take(&mut self.finallies);
         // Per-function, like the slots: one body the model cannot handle must
         // not cost the rest of the program its lowering.
         let saved_defeated = mem::replace(&mut self.defeated, false);
@@ -865,6 +1022,7 @@ impl Lowering {
 
         self.slots = saved_slots;
         self.loops = saved_loops;
+        self.finallies = saved_finallies;
         self.defeated = saved_defeated;
 
         if !lowered {
@@ -1120,54 +1278,91 @@ impl Lowering {
             // something was caught (`.map(|_| Dynamic::UNIT)`,
             // `eval/stmt.rs:863`). So `try { throw 7 } catch (e) { e * 2 }` is
             // unit, not 14.
-            Stmt::TryCatch(payload, ..) => {
-                let FlowControl { expr, body, branch } = &**payload;
+            Stmt::TryCatch(payload, pos) => {
+                let TryCatchBlock {
+                    body,
+                    catches,
+                    finally,
+                } = &**payload;
+
+                if finally.is_empty() {
+                    return self.try_catch(body, catches);
+                }
 
-                // An absent catch variable is `Expr::Unit`; a present one is
-                // an `Expr::Variable` whose position is what Rhai reports
-                // `ErrorTooManyVariables` against.
-                let catch_var = match expr {
-                    Expr::Variable(v, ..) => Some(v.1.clone()),
-                    _ => None,
+                // A `return`, `break` or `continue` inside an expression is a
+                // fragment's, and a fragment unwinds it as an error no handler
+                // catches — so nothing here would run the `finally` block on
+                // the way out. The walker does.
+                let in_expression = |node: &[ASTNode]| {
+                    node.iter().any(|n| matches!(n, ASTNode::Expr(..)))
+                        && matches!(node.last(), Some(ASTNode::Stmt(stmt)) if is_jump(stmt))
+                };
+                let mut jumps = false;
+                let mut on_node = |node: &[ASTNode]| {
+                    jumps |= in_expression(node);
+                    !jumps
                 };
+                for clause in catches.iter() {
+                    clause.guard.walk(&mut Vec::new(), &mut on_node);
+                }
+                for stmt in body
+                    .iter()
+                    .chain(catches.iter().flat_map(|c| c.body.iter()))
+                {
+                    stmt.walk(&mut Vec::new(), &mut on_node);
+                }
+                if jumps {
+                    return false;
+                }
 
-                let catch_name = catch_var.clone().map(|name| self.push_name(name));
+                // An outer region around the whole statement, catch clauses
+                // and all: whatever escapes them goes through the `finally`
+                // block and is raised again.
                 let site = self.code.len();
                 self.emit_at(
                     Op::PushHandler {
                         target: u32::MAX,
-                        catch_var: catch_name,
+                        catch_var: None,
                     },
-                    expr.position(),
+                    *pos,
                 );
+                self.finallies.push(Finally {
+                    statements: finally.statements().to_vec(),
+                    depth: self.slots.depth(),
+                    handlers: self.handlers,
+                    loops: self.loops.len(),
+                });
                 self.handlers += 1;
 
-                if !self.block(body.statements()) {
+                let lowered = if catches.is_empty() {
+                    self.block(body.statements())
+                } else {
+                    self.try_catch(body, catches)
+                };
+                self.finallies.pop();
+                if !lowered {
                     return false;
                 }
                 self.emit(Op::PopHandler);
                 self.handlers -= 1;
+                if !self.block_discarding(finally.statements()) {
+                    return false;
+                }
                 let past = self.emit_jump();
 
-                // The catch block, entered with the scope back where the `try`
-                // began and the variable already pushed on top of it. The
-                // handler is still armed here — that is what makes a bare
-                // `throw;` in this block a re-raise — so the depth goes back
-                // up, and the `PopHandler` below is what ends the region.
+                // Entered with the error held by the handler, which is still
+                // armed — so the bare `throw` below re-raises it, from where it
+                // was first raised since the `Throw` has no position of its
+                // own. An error out of the block itself replaces it, as it
+                // does in the walker.
                 self.patch_to(site, self.here());
                 self.handlers += 1;
-                let depth = self.slots.depth();
-                if let Some(name) = catch_var {
-                    self.slots.declare(name);
-                }
-                if !self.block(branch.statements()) {
+                if !self.block_discarding(finally.statements()) {
                     return false;
                 }
-                self.emit(Op::Pop);
-                self.unwind_to(depth);
-                self.emit(Op::PopHandler);
-                self.handlers -= 1;
                 self.emit(Op::Unit);
+                self.emit(Op::Throw);
+                self.handlers -= 1;
 
                 self.patch_here(past);
                 true
@@ -1344,13 +1539,16 @@ impl Lowering {
                 true
             }
 
-            Stmt::BreakLoop(value, flags, ..) => {
-                let Some(active) = self.loops.last() else {
+            Stmt::BreakLoop(value, flags, levels, ..) => {
+                // A label names an outer loop; the parser has already turned
+                // it into how many inner loops the jump leaves on the way.
+                let Some(target) = self.loops.len().checked_sub(usize::from(*levels) + 1) else {
                     // Outside any loop this is a parse error in Rhai, so it
                     // should be unreachable; bail rather than emit a jump to
                     // nowhere.
                     return false;
                 };
+                let active = &self.loops[target];
                 let continue_target = active.continue_target;
                 let loop_iters = active.iters;
                 let loop_handlers = active.handlers;
@@ -1368,18 +1566,24 @@ impl Lowering {
                         Some(expr) => self.expression(expr),
                         None => self.emit(Op::Unit),
                     }
+                    let Some(armed) = self.leave_finallies(target + 1) else {
+                        return false;
+                    };
                     // Out of the loop entirely, so its own iterator goes too —
                     // `loop_iters` counts from inside the loop and therefore
                     // already includes it.
-                    self.pop_handlers(loop_handlers);
+                    self.pop_handlers(armed, loop_handlers);
                     self.drop_iterators(loop_iters - usize::from(owns_iterator));
                     self.emit(Op::UnwindTo(break_depth));
                     let site = self.emit_jump();
-                    self.loops.last_mut().expect("checked").breaks.push(site);
+                    self.loops[target].breaks.push(site);
                 } else {
+                    let Some(armed) = self.leave_finallies(target + 1) else {
+                        return false;
+                    };
                     // Back into the same loop, so its iterator and its loop
                     // variable both have to survive.
-                    self.pop_handlers(loop_handlers);
+                    self.pop_handlers(armed, loop_handlers);
                     self.drop_iterators(loop_iters);
                     self.emit(Op::UnwindTo(continue_depth));
                     self.emit(Op::Jump(continue_target));
@@ -1411,6 +1615,11 @@ impl Lowering {
                     Some(expr) => self.expression(expr),
                     None => self.emit(Op::Unit),
                 }
+                // Leaving the frame disarms every handler, but not before the
+                // `finally` blocks in the way have run.
+                if self.leave_finallies(0).is_none() {
+                    return false;
+                }
                 self.emit(Op::Return);
                 self.emit(Op::Unit);
                 true
@@ -1427,6 +1636,15 @@ impl Lowering {
             #[cfg(not(feature = "no_module"))]
             Stmt::Import(..) => false,
 
+            // An inline `module` lands on the imports stack the same way.
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Module(..) => false,
+
+            // Only found in generator bodies, which `function` already turns
+            // down: a suspended body keeps its frames in the walker's terms.
+            #[cfg(not(feature = "no_function"))]
+            Stmt::Yield(..) => false,
+
             // Not lowered yet, and listed rather than matched with `_` on
             // purpose. A wildcard here silently turned `import` and `eval`
             // into fragments that answered differently from the walker; naming
@@ -1727,11 +1945,21 @@ impl Lowering {
             // position, because that is what `ErrorUnboundThis` carries.
             Expr::ThisPtr(pos) => self.emit_at(Op::LoadThis, *pos),
 
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Expr::Comprehension(x, ..) => self.comprehension(expr, x),
+            #[cfg(all(feature = "no_index", feature = "no_object"))]
+            Expr::Comprehension(..) => self.residual_expr(expr),
+            #[cfg(not(feature = "no_object"))]
+            Expr::Struct(..) => self.residual_expr(expr),
+
             Expr::MethodCall(..)
             | Expr::Property(..)
             | Expr::DynamicConstant(..)
             | Expr::FnCall(..)
             | Expr::Array(..)
+            | Expr::Tuple(..)
+            | Expr::Enum(..)
+            | Expr::Spread(..)
             | Expr::Map(..) => self.residual_expr(expr),
         }
     }
@@ -1926,7 +2154,7 @@ impl Lowering {
     /// exactly is what keeps the two agreeing on the failures as well as the
     /// successes.
     fn fn_ptr_call(&mut self, call: &FnCallExpr, pos: Position) -> bool {
-        if call_has_namespace!(call) || call.capture_parent_scope {
+        if call_has_namespace!(call) || call.capture_parent_scope || call.has_spread_args() {
             return false;
         }
         let argc = call.args.len();
@@ -1991,7 +2219,8 @@ impl Lowering {
     /// so routing those through `call_fn_raw` would change what they mean.
     /// A call that captures the enclosing scope is closure construction,
     /// and a qualified name resolves against imported modules;
-    /// neither is a plain call.
+    /// neither is a plain call. Nor is one with spread arguments, whose arity
+    /// is only known once the spread values are.
     fn is_lowerable_call(&self, call: &FnCallExpr) -> bool {
         // These are handled by `is_syntactic_call` above, but only at the
         // arities Rhai treats syntactically — at any other arity it falls
@@ -2007,6 +2236,7 @@ impl Lowering {
 
         !call_has_namespace!(call)
             && call.args.len() <= u8::MAX as usize
+            && !call.has_spread_args()
             && !SYNTACTIC.contains(&call.name.as_str())
     }
 
@@ -2103,6 +2333,153 @@ impl Lowering {
         true
     }
 
+    /// Lower a `try` body and its `catch` clauses, leaving the statement's
+    /// value: the body's, or unit once something was caught.
+    fn try_catch(&mut self, body: &StmtBlock, catches: &[CatchClause]) -> bool {
+        // One clause catching everything, which is most of them: the handler
+        // binds its variable directly.
+        if let [CatchClause {
+            var,
+            guard: Expr::BoolConstant(true, ..),
+            body: branch,
+        }] = catches
+        {
+            // An absent catch variable is `Expr::Unit`; a present one is
+            // an `Expr::Variable` whose position is what Rhai reports
+            // `ErrorTooManyVariables` against.
+            let catch_var = match var {
+                Expr::Variable(v, ..) => Some(v.1.clone()),
+                _ => None,
+            };
+
+            let catch_name = catch_var.clone().map(|name| self.push_name(name));
+            let site = self.code.len();
+            self.emit_at(
+                Op::PushHandler {
+                    target: u32::MAX,
+                    catch_var: catch_name,
+                },
+                var.position(),
+            );
+            self.handlers += 1;
+
+            if !self.block(body.statements()) {
+                return false;
+            }
+            self.emit(Op::PopHandler);
+            self.handlers -= 1;
+            let past = self.emit_jump();
+
+            // The catch block, entered with the scope back where the `try`
+            // began and the variable already pushed on top of it. The
+            // handler is still armed here — that is what makes a bare
+            // `throw;` in this block a re-raise — so the depth goes back
+            // up, and the `PopHandler` below is what ends the region.
+            self.patch_to(site, self.here());
+            self.handlers += 1;
+            let depth = self.slots.depth();
+            if let Some(name) = catch_var {
+                self.slots.declare(name);
+            }
+            if !self.block(branch.statements()) {
+                return false;
+            }
+            self.emit(Op::Pop);
+            self.unwind_to(depth);
+            self.emit(Op::PopHandler);
+            self.handlers -= 1;
+            self.emit(Op::Unit);
+
+            self.patch_here(past);
+            return true;
+        }
+
+        // Otherwise the handler binds the error under a name no script can
+        // write, and each clause declares its own variable from that — Rhai
+        // pushes a fresh one per clause, and a clause without one must not
+        // see another's.
+        let needs_value = catches.iter().any(|c| !c.var.is_unit());
+        let hidden = ImmutableString::new();
+        let catch_name = needs_value.then(|| self.push_name(hidden.clone()));
+        let site = self.code.len();
+        self.emit(Op::PushHandler {
+            target: u32::MAX,
+            catch_var: catch_name,
+        });
+        self.handlers += 1;
+
+        if !self.block(body.statements()) {
+            return false;
+        }
+        self.emit(Op::PopHandler);
+        self.handlers -= 1;
+        let past = self.emit_jump();
+
+        self.patch_to(site, self.here());
+        self.handlers += 1;
+        let depth = self.slots.depth();
+        let hidden_slot = needs_value.then(|| self.slots.declare(hidden));
+
+        let mut caught = Vec::new();
+        for CatchClause { var, guard, body } in catches {
+            let clause_depth = self.slots.depth();
+
+            if let (Expr::Variable(v, ..), Some(slot)) = (var, hidden_slot) {
+                let name = self.push_name(v.1.clone());
+                self.emit_at(Op::LoadLocal(slot), var.position());
+                self.emit_at(
+                    Op::DeclareLocal {
+                        name,
+                        is_const: false,
+                    },
+                    var.position(),
+                );
+                self.slots.declare(v.1.clone());
+            }
+
+            let next = match guard {
+                Expr::BoolConstant(true, ..) => None,
+                _ => {
+                    self.expression(guard);
+                    Some(self.emit_jump_if_false(guard.position()))
+                }
+            };
+
+            if !self.block_discarding(body.statements()) {
+                return false;
+            }
+            if self.slots.depth() > clause_depth {
+                let clause_depth = u16::try_from(clause_depth).expect("slot count is bounded");
+                self.emit(Op::UnwindTo(clause_depth));
+            }
+            caught.push(self.emit_jump());
+
+            // A guard that fails goes on to the next clause, without the
+            // variable this one declared.
+            if let Some(next) = next {
+                self.patch_here(next);
+            }
+            self.unwind_to(clause_depth);
+        }
+
+        // Nothing caught it. The handler still holds the error, so a bare
+        // `throw` re-raises it, and with no position of its own it keeps the
+        // one the error was raised at.
+        self.emit(Op::Unit);
+        self.emit(Op::Throw);
+
+        for site in caught {
+            self.patch_here(site);
+        }
+        self.unwind_to(depth);
+        self.emit(Op::PopHandler);
+        self.handlers -= 1;
+        self.emit(Op::Unit);
+
+        self.patch_here(past);
+        true
+    }
+
     /// Emit the scope truncation for leaving a block, and unwind the
     /// compile-time slot model with it.
     ///
@@ -2175,18 +2552,59 @@ impl Lowering {
         }
     }
 
-    /// Disarm every `try` region entered above `floor`.
+    /// Disarm every `try` region entered above `floor`, of the `armed` ones.
     ///
     /// A `break` or `continue` jumps over the `PopHandler` the straight-line
     /// path would have run. Left armed, the handler keeps a stale target and a
     /// stale set of depths, and the next error anywhere in the frame is caught
     /// into a `catch` block that has already been left.
-    fn pop_handlers(&mut self, floor: usize) {
-        for _ in floor..self.handlers {
+    fn pop_handlers(&mut self, armed: usize, floor: usize) {
+        for _ in floor..armed {
             self.emit(Op::PopHandler);
         }
     }
 
+    /// Run the `finally` blocks a jump leaves, innermost first, and return how
+    /// many `try` regions are still armed afterwards — or `None` if one of
+    /// the blocks defeated the lowering.
+    ///
+    /// The jump leaves every `try` entered inside `loops` enclosing loops.
+    /// Each block runs with its own region and the ones inside it disarmed, so
+    /// an error out of it goes where the walker sends it, and with the scope
+    /// back at the `try`. The slot model is put back afterwards: what follows
+    /// the jump is unreachable, but it is still lowered against the scope the
+    /// jump was made from.
+    fn leave_finallies(&mut self, loops: usize) -> Option<usize> {
+        let mut armed = self.handlers;
+        let all = self.finallies.clone();
+        let first = all
+            .iter()
+            .position(|f| f.loops >= loops)
+            .unwrap_or(all.len());
+
+        for (index, finally) in all.iter().enumerate().skip(first).rev() {
+            self.pop_handlers(armed, finally.handlers);
+            armed = finally.handlers;
+
+            // A jump inside the block leaves only the blocks outside it.
+            self.finallies.truncate(index);
+            let (slots, handlers) = (self.slots.clone(), self.handlers);
+            self.handlers = armed;
+            self.unwind_to(finally.depth);
+            let lowered = self.block_discarding(&finally.statements);
+            self.slots = slots;
+            self.handlers = handlers;
+
+            if !lowered {
+                self.finallies = all;
+                return None;
+            }
+        }
+
+        self.finallies = all;
+        Some(armed)
+    }
+
     /// Open a loop whose `break` and `continue` unwind to the same place —
     /// `while`, `loop` and `do`, which declare nothing of their own.
     fn begin_loop(&mut self, continue_target: u32) {
@@ -2421,6 +2839,16 @@ fn wrap_statements(statements: Vec<Stmt>) -> Expr {
     Expr::Stmt(Box::new(StmtBlock::new_with_span(statements, span)))
 }
 
+/// Whether a statement leaves by jumping: `return`, `break` or `continue`.
+/// A `throw` shares `return`'s node but raises an error, which a handler sees.
+fn is_jump(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::BreakLoop(..) => true,
+        Stmt::Return(.., flags, _) => !flags.contains(ASTFlags::BREAK),
+        _ => false,
+    }
+}
+
 /// What orders one script function against another when lowering.
 ///
 /// Everything that tells two declarations apart, nothing that varies between
@@ -62,6 +62,12 @@ pub(crate) fn is_poolable(value: &Dynamic) -> bool {
         return value.read_lock::<Decimal>().is_some();
     }
 
+    // Big integers are enabled by `bigint`.
+    #[cfg(feature = "bigint")]
+    if value.is_bigint() {
+        return value.read_lock::<num_bigint::BigInt>().is_some();
+    }
+
     // A range is a host type by representation but not by nature: Rhai builds
     // one for `0..5` and indexes strings and arrays with it, and its `TypeId`
     // is one both sides can name. Without this every slice is a fragment.
@@ -12,7 +12,7 @@ use std::prelude::v1::*;
 /// and resolution scans backwards, so the newer slot wins while the older one
 /// stays addressable by anything compiled before it — which is exactly Rhai's
 /// behaviour.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct Slots {
     names: Vec<ImmutableString>,
 }
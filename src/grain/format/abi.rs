@@ -35,6 +35,7 @@ const FLAGS: &[(&str, bool)] = &[
     ("no_custom_syntax", cfg!(feature = "no_custom_syntax")),
     ("no_time", cfg!(feature = "no_time")),
     ("unchecked", cfg!(feature = "unchecked")),
+    ("bigint", cfg!(feature = "bigint")),
 ];
 
 /// `Engine` is only `Send + Sync` when Rhai is built with `sync`, so claiming
@@ -109,6 +109,7 @@ mod constant {
     pub const RANGE: u8 = 0x0a;
     pub const RANGE_INCLUSIVE: u8 = 0x0b;
     pub const DECIMAL: u8 = 0x0c;
+    pub const BIGINT: u8 = 0x0d;
 }
 
 /// Everything a stripped artifact left behind.
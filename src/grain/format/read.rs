@@ -483,6 +483,12 @@ fn get_constant(
             )
         }
 
+        #[cfg(feature = "bigint")]
+        constant::BIGINT => {
+            let len = usize::try_from(cursor.uvarint()?).map_err(|_| ReadError::Truncated)?;
+            Dynamic::from_bigint(num_bigint::BigInt::from_signed_bytes_le(cursor.take(len)?))
+        }
+
         constant::CHAR => {
             let code = cursor.index()?;
             Dynamic::from(char::from_u32(code).ok_or(ReadError::MalformedVarint)?)
@@ -408,6 +408,14 @@ fn put_constant(out: &mut Vec<u8>, value: &Dynamic) -> Result<(), String> {
         out.extend_from_slice(&buf);
         return Ok(());
     }
+    #[cfg(feature = "bigint")]
+    if let Ok(number) = value.as_bigint() {
+        out.push(constant::BIGINT);
+        let bytes = number.to_signed_bytes_le();
+        put_uvarint(out, bytes.len() as u64);
+        out.extend_from_slice(&bytes);
+        return Ok(());
+    }
     if let Ok(character) = value.as_char() {
         out.push(constant::CHAR);
         put_uvarint(out, u32::from(character).into());
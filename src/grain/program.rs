@@ -202,6 +202,8 @@ fn unsupported_kind(node: &ASTNode) -> Option<&'static str> {
             Stmt::Import(..) => "import",
             #[cfg(not(feature = "no_module"))]
             Stmt::Export(..) => "export",
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Module(..) => "module",
             #[cfg(not(feature = "no_closure"))]
             Stmt::Share(..) => "a closure capture",
             Stmt::Return(_, flags, ..) if flags.contains(ASTFlags::BREAK) => "throw",
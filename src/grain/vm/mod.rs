@@ -72,7 +72,9 @@ fn call_engine(
 ) -> VmResult {
     let native_only = !crate::tokenizer::is_valid_identifier(fn_name);
     #[cfg(not(feature = "no_function"))]
-    let native_only = native_only && !crate::parser::is_anonymous_fn(fn_name);
+    let native_only = native_only
+        && !crate::parser::is_anonymous_fn(fn_name)
+        && !crate::parser::is_script_operator(fn_name);
 
     crate::eval::_call_fn_raw(
         engine,
@@ -1381,11 +1383,10 @@ impl<'e> Vm<'e> {
                 // between: `import` pushes onto `global.modules`, not
                 // `global.lib` (`eval/stmt.rs:947`), and `global.lib` is only
                 // ever pushed where an AST is being run.
-                let type_name = target.type_name();
                 let compiled = {
-                    let typed = self.engine.map_type_name(type_name);
+                    let typed = self.engine.map_value_type_name(target);
                     program
-                        .method(name_index, argc, typed)
+                        .method(name_index, argc, &typed)
                         .map(|f| (f.params.clone(), f.chunk))
                 };
 
@@ -1734,8 +1735,9 @@ impl<'e> Vm<'e> {
             .name(name)
             .ok_or_else(|| malformed(format!("no name {name}")))?;
 
-        // A map is the one property holder that is not a host type, and
-        // `no_object` removes both it and the syntax that would reach one.
+        // A map and a `struct` are the property holders that are not host
+        // types, and `no_object` removes both and the syntax that would reach
+        // them.
         #[cfg(not(feature = "no_object"))]
         if target.is_map() {
             let mut map = target
@@ -1771,6 +1773,31 @@ impl<'e> Vm<'e> {
             };
         }
 
+        // A `struct` has a fixed set of fields, so an unknown one is an error
+        // whether reading or writing (`eval/chaining.rs:221`).
+        #[cfg(not(feature = "no_object"))]
+        if target.is_struct() {
+            let mut record = target
+                .write_lock::<crate::StructValue>()
+                .ok_or_else(|| malformed("a struct that is not a struct".to_string()))?;
+            let entry = record.get_mut(key).ok_or_else(|| {
+                Box::new(EvalAltResult::ErrorPropertyNotFound(
+                    key.to_string(),
+                    step_pos,
+                ))
+            })?;
+
+            if last {
+                if let Some(value) = value {
+                    self.store(program, chain_op(program, chain)?, entry, value, pos)?;
+                    return Ok((Dynamic::UNIT, true));
+                }
+                return Ok((entry.clone(), false));
+            }
+
+            return self.walk_chain(program, chain, rest, entry, operands, value, pos);
+        }
+
         // A host type: getter in, setter out.
         let call = |vm: &mut Self, fn_name: u32, args: &mut [&mut Dynamic]| -> VmResult {
             let fn_name = program
@@ -1849,7 +1876,8 @@ impl<'e> Vm<'e> {
         if !self.engine.fast_operators() {
             return None;
         }
-        let (func, need_context) = get_builtin_op_assignment_fn(&op.op_assign, target, rhs)?;
+        let (func, need_context) =
+            get_builtin_op_assignment_fn(&op.op_assign, target, rhs, self.engine)?;
         let context = need_context.then(|| (self.engine, "", None, &self.global, pos()).into());
         Some(
             func(context, &mut [target, rhs])
@@ -1887,41 +1915,74 @@ impl<'e> Vm<'e> {
             .name(op.op_name)
             .ok_or_else(|| malformed(format!("no operator name {}", op.op_name)))?;
 
+        // Without Fast Operators mode, a script-defined operator function
+        // overrides the op-assignment for all types, as it does for the walker
+        // (`eval/stmt.rs:234`).
+        let is_script_op =
+            !self.engine.fast_operators() && program.function(op.op_name, 2).is_some();
+
         // The real scope may be borrowed by the target, and dispatch does not
         // read it anyway — operators resolve against the engine.
-        let result = call_engine(
-            self.engine,
-            &mut self.global,
-            &mut self.caches,
-            &mut Scope::new(),
-            op_assign_name,
-            &mut [target, &mut rhs],
-            true,
-            false,
-            pos,
-        );
+        let result = (!is_script_op).then(|| {
+            call_engine(
+                self.engine,
+                &mut self.global,
+                &mut self.caches,
+                &mut Scope::new(),
+                op_assign_name,
+                &mut [target, &mut rhs],
+                true,
+                false,
+                pos,
+            )
+        });
         match result {
-            Ok(_) => Ok(()),
-            Err(err)
-                if matches!(&*err,
+            Some(Ok(_)) => Ok(()),
+            Some(Err(err))
+                if !matches!(&*err,
                     EvalAltResult::ErrorFunctionNotFound(name, ..)
                         if name.starts_with(op_assign_name)) =>
             {
-                let value = call_engine(
-                    self.engine,
-                    &mut self.global,
-                    &mut self.caches,
-                    &mut Scope::new(),
-                    op_name,
-                    &mut [target, &mut rhs],
-                    true,
-                    false,
-                    pos,
-                )?;
+                Err(dispatch_failure(err, pos))
+            }
+            _ => {
+                // A script-defined operator function is one of our chunks, as
+                // it is for a plain operator call (see `call_stacked`).
+                let function = (is_script_op || target.is_overloadable() || rhs.is_overloadable())
+                    .then(|| program.function(op.op_name, 2))
+                    .flatten();
+
+                let value = if let Some(function) = function {
+                    let first = self.stack.len();
+                    self.stack.push(target.clone());
+                    self.stack.push(rhs);
+                    let value = self.call_compiled(
+                        program,
+                        op_name,
+                        &function.params,
+                        function.chunk,
+                        first,
+                        &mut Scope::new(),
+                        pos,
+                    );
+                    self.stack.truncate(first);
+                    value?
+                } else {
+                    call_engine(
+                        self.engine,
+                        &mut self.global,
+                        &mut self.caches,
+                        &mut Scope::new(),
+                        op_name,
+                        &mut [target, &mut rhs],
+                        true,
+                        false,
+                        pos,
+                    )?
+                };
                 *target = value;
                 Ok(())
             }
-            Err(err) => Err(dispatch_failure(err, pos)),
         }
     }
 
@@ -2478,8 +2539,15 @@ impl<'e> Vm<'e> {
         scope: &mut Scope,
         pos: Position,
     ) -> VmResult {
-        // Run compiled function if available.
-        if let Some(function) = program.function(name_index, argc) {
+        // Run compiled function if available. Under Fast Operators mode, an
+        // operator function only overloads custom types and structs, as it
+        // does for the walker (`func/call.rs:726`).
+        let function = program.function(name_index, argc).filter(|_| {
+            !crate::parser::is_script_operator(name)
+                || !self.engine.fast_operators()
+                || self.stack[first..].iter().any(Dynamic::is_overloadable)
+        });
+        if let Some(function) = function {
             return self.call_compiled(
                 program,
                 name,
@@ -3227,6 +3295,94 @@ impl<'e> Vm<'e> {
         }
     }
 
+    /// Take the item on top of the stack into the comprehension's container
+    /// beneath it, and refuse it if that puts the running total over a
+    /// configured limit.
+    ///
+    /// Reproduces `eval/stmt.rs` (`collect_comprehension`). An element counts
+    /// as it does in [`Vm::check_size`]; what is new is a map entry replacing
+    /// another, which a literal cannot do and a comprehension does as often as
+    /// its keys repeat. The replaced value stops counting, as in Rhai, or a
+    /// comprehension folding many items into a few keys would be refused for
+    /// a size it never reaches.
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    fn collect(&mut self, map: bool, pos: Position) -> Result<(), Box<EvalAltResult>> {
+        let value = self.pop()?.flatten();
+        let key = if map { Some(self.pop()?) } else { None };
+
+        // Rhai skips the measurement when no limit could reject it, as for a
+        // literal.
+        #[cfg(not(feature = "unchecked"))]
+        let limited = self.engine.max_string_size() != 0
+            || self.engine.max_array_size() != 0
+            || self.engine.max_map_size() != 0;
+        #[cfg(not(feature = "unchecked"))]
+        let added = if limited {
+            calc_data_sizes(&value, true)
+        } else {
+            (0, 0, 0)
+        };
+
+        let container = self
+            .stack
+            .last_mut()
+            .ok_or_else(|| malformed("comprehension item with no container".to_string()))?;
+
+        let replaced = match key {
+            #[cfg(not(feature = "no_object"))]
+            Some(key) => {
+                let key = key
+                    .into_immutable_string()
+                    .map_err(|actual| malformed(format!("map key is a {actual}, not a string")))?;
+                container
+                    .write_lock::<Map>()
+                    .ok_or_else(|| malformed("map comprehension without a map".to_string()))?
+                    .insert(key.as_str().into(), value)
+            }
+            #[cfg(not(feature = "no_index"))]
+            None => {
+                container
+                    .write_lock::<Array>()
+                    .ok_or_else(|| malformed("array comprehension without an array".to_string()))?
+                    .push(value);
+                None
+            }
+            #[allow(unreachable_patterns)]
+            _ => return Err(malformed("comprehension of a missing type".to_string())),
+        };
+
+        #[cfg(feature = "unchecked")]
+        {
+            let _ = (replaced, pos);
+            Ok(())
+        }
+
+        #[cfg(not(feature = "unchecked"))]
+        {
+            if !limited {
+                return Ok(());
+            }
+
+            let removed = replaced.map_or((0, 0, 0), |old| {
+                let (arrays, maps, strings) = calc_data_sizes(&old, true);
+                (arrays, maps + 1, strings)
+            });
+
+            let total = self.sizes.last_mut().ok_or_else(|| {
+                malformed("comprehension item outside a comprehension".to_string())
+            })?;
+            *total = (
+                total.0 + added.0 + usize::from(!map) - removed.0,
+                total.1 + added.1 + usize::from(map) - removed.1,
+                total.2 + added.2 - removed.2,
+            );
+
+            self.engine
+                .throw_on_size(*total)
+                .map_err(|err| dispatch_failure(err, pos))
+        }
+    }
+
     /// Drop what an escaping error skipped the unwind for.
     ///
     /// An error leaves a block by jumping over the [`Op::UnwindTo`] that would
@@ -3283,7 +3439,10 @@ impl<'e> Vm<'e> {
 
             // Leaving a catch block rather than entering one. A bare `throw;`
             // there — an `ErrorRuntime` carrying unit — means "re-raise what
-            // was caught, from here" (`eval/stmt.rs:866`).
+            // was caught, from here" (`eval/stmt.rs:866`). The compiler's own
+            // re-raise, out of a `finally` block or past `catch` clauses that
+            // all turned the error down, has no position, and the error keeps
+            // the one it was raised at.
             let Some(original) = handler.caught.take() else {
                 break handler;
             };
@@ -3293,7 +3452,9 @@ impl<'e> Vm<'e> {
             if rethrown {
                 let pos = err.position();
                 err = original;
-                err.set_position(pos);
+                if !pos.is_none() {
+                    err.set_position(pos);
+                }
             }
         };
 
@@ -3348,12 +3509,14 @@ impl<'e> Vm<'e> {
             return value.clone();
         }
 
-        // Read *and cleared*, as Rhai does, so the message below carries no
-        // trailing position and a re-raise starts from the catch site.
+        // Cleared while the message is made, as Rhai does, so it carries no
+        // trailing position — then put back, for an error no clause catches.
         #[cfg(feature = "no_object")]
         {
-            let _ = err.take_position();
-            err.to_string().into()
+            let pos = err.take_position();
+            let message = err.to_string().into();
+            err.set_position(pos);
+            message
         }
 
         #[cfg(not(feature = "no_object"))]
@@ -3372,6 +3535,7 @@ impl<'e> Vm<'e> {
                 map.insert("position".into(), column.into());
             }
             err.dump_fields(&mut map);
+            err.set_position(pos);
             map.into()
         }
     }
@@ -3823,10 +3987,10 @@ impl<'e> Vm<'e> {
                         let lhs = &mut lhs[first];
                         let rhs = &mut rhs[0];
 
-                        // Custom types go to dispatch first, so a registered
-                        // function still wins for them.
-                        let builtin = (!lhs.is_variant() && !rhs.is_variant())
-                            .then(|| get_builtin_binary_op_fn(token, lhs, rhs))
+                        // Custom types and structs go to dispatch first, so a
+                        // registered or script-defined function still wins for them.
+                        let builtin = (!lhs.is_overloadable() && !rhs.is_overloadable())
+                            .then(|| get_builtin_binary_op_fn(token, lhs, rhs, self.engine))
                             .flatten();
                         if let Some((func, need_context)) = builtin {
                             let context = need_context
@@ -3974,6 +4138,36 @@ impl<'e> Vm<'e> {
                     self.stack.push(Dynamic::from_map(map));
                 }
 
+                // Emitted only for a comprehension, which is not syntax under
+                // the feature that removes the type it builds.
+                #[cfg(not(feature = "no_index"))]
+                code::tag::COLLECT_ARRAY => {
+                    self.sizes.push((0, 0, 0));
+                    self.stack.push(Dynamic::from_array(Array::new()));
+                }
+                #[cfg(not(feature = "no_object"))]
+                code::tag::COLLECT_MAP => {
+                    self.sizes.push((0, 0, 0));
+                    self.stack.push(Dynamic::from_map(Map::new()));
+                }
+                // Rhai checks the key as soon as it has it, before evaluating
+                // the value, and blames the key for it.
+                #[cfg(not(feature = "no_object"))]
+                code::tag::COLLECT_KEY => {
+                    let key = self.pop()?;
+                    let key = key.try_cast_result::<ImmutableString>().map_err(|actual| {
+                        self.mismatch::<ImmutableString>(actual.type_name(), pos())
+                    })?;
+                    self.stack.push(key.into());
+                }
+                #[cfg(not(feature = "no_index"))]
+                code::tag::COLLECT_ITEM => self.collect(false, pos())?,
+                #[cfg(not(feature = "no_object"))]
+                code::tag::COLLECT_ENTRY => self.collect(true, pos())?,
+                code::tag::COLLECT_END => {
+                    self.sizes.pop();
+                }
+
                 code::tag::CHECK_ARRAY_SIZE | code::tag::CHECK_MAP_SIZE => {
                     let index = small(1)?;
                     let map = tag == code::tag::CHECK_MAP_SIZE;
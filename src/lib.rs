@@ -118,11 +118,15 @@ mod defer;
 mod api;
 mod ast;
 pub mod config;
+#[cfg(feature = "dap")]
+pub mod dap;
 mod engine;
 mod eval;
 mod func;
 #[cfg(feature = "grain")]
 pub mod grain;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 mod module;
 mod optimizer;
 pub mod packages;
@@ -228,7 +232,7 @@ pub use api::custom_syntax::Expression;
 #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
 pub use api::files::{eval_file, run_file};
 pub use api::{eval::eval, run::run};
-pub use ast::{FnAccess, AST};
+pub use ast::{ASTFormatError, FnAccess, AST};
 use defer::Deferred;
 pub use engine::{Engine, OP_CONTAINS, OP_EQUALS};
 pub use eval::{EvalContext, EvalContextFrameGuard};
@@ -240,20 +244,43 @@ pub use func::{plugin, FuncArgs, NativeCallContext, RhaiNativeFunc};
 pub use module::{FnNamespace, FuncRegistration, Module};
 pub use packages::string_basic::{FUNC_TO_DEBUG, FUNC_TO_STRING};
 pub use rhai_codegen::*;
+#[cfg(not(feature = "no_function"))]
+pub use types::Generator;
 #[cfg(not(feature = "no_time"))]
 pub use types::Instant;
+#[cfg(feature = "regex")]
+pub use types::Regex;
+#[cfg(not(feature = "no_object"))]
+pub use types::StructValue;
 pub use types::{
-    Dynamic, EvalAltResult, FnPtr, ImmutableString, LexError, ParseError, ParseErrorType, Position,
-    Scope, VarDefInfo,
+    Diagnostic, DiagnosticType, Dynamic, EnumValue, EvalAltResult, FnPtr, ImmutableString,
+    LexError, ParseError, ParseErrorType, Position, Scope, Severity, Tuple, VarDefInfo,
 };
 
+/// _(coverage)_ Module containing types for statement and branch coverage.
+/// Exported under the `coverage` feature only.
+#[cfg(feature = "coverage")]
+pub mod coverage {
+    pub use super::eval::{BranchCoverage, BranchKind, Coverage, FileCoverage};
+}
+
+/// _(profiling)_ Module containing types for call-level profiling.
+/// Exported under the `profiling` feature only.
+#[cfg(feature = "profiling")]
+pub mod profiler {
+    pub use super::eval::{CallStats, FunctionStats, Profile};
+}
+
 /// _(debugging)_ Module containing types for debugging.
 /// Exported under the `debugging` feature only.
 #[cfg(feature = "debugging")]
 pub mod debugger {
     #[cfg(not(feature = "no_function"))]
     pub use super::eval::CallStackFrame;
-    pub use super::eval::{BreakPoint, Debugger, DebuggerCommand, DebuggerEvent};
+    pub use super::eval::{
+        BreakPoint, BreakPointExpr, Debugger, DebuggerCommand, DebuggerEvent, HitCondition,
+        WatchedValue,
+    };
 }
 
 /// _(internals)_ An identifier in Rhai.
@@ -284,6 +311,9 @@ pub use ast::ScriptFnMetadata;
 #[cfg(not(feature = "no_function"))]
 pub use api::call_fn::CallFnOptions;
 
+#[cfg(not(feature = "no_position"))]
+pub use api::format::FormatOptions;
+
 /// Variable-sized array of [`Dynamic`] values.
 ///
 /// Not available under `no_index`.
@@ -318,6 +348,9 @@ pub use module::resolvers as module_resolvers;
 #[cfg(not(feature = "no_optimize"))]
 pub use optimizer::OptimizationLevel;
 
+#[cfg(not(feature = "unchecked"))]
+pub use api::overflow::Overflow;
+
 // Expose internal data structures.
 
 #[cfg(feature = "internals")]
@@ -335,9 +368,10 @@ pub use types::{BloomFilterU64, CustomTypeInfo, Span, StringsInterner};
 
 #[cfg(feature = "internals")]
 pub use tokenizer::{
-    get_next_token, is_valid_function_name, is_valid_identifier, parse_raw_string_literal,
-    parse_string_literal, InputStream, MultiInputsStream, Token, TokenIterator, TokenizeState,
-    TokenizerControl, TokenizerControlBlock,
+    dedent_heredoc, get_next_token, is_valid_function_name, is_valid_identifier,
+    parse_heredoc_literal, parse_raw_string_literal, parse_string_literal, InputStream,
+    MultiInputsStream, Token, TokenIterator, TokenizeState, TokenizerControl,
+    TokenizerControlBlock,
 };
 
 #[cfg(feature = "internals")]
@@ -348,9 +382,9 @@ pub use api::default_limits;
 
 #[cfg(feature = "internals")]
 pub use ast::{
-    ASTFlags, ASTNode, BinaryExpr, EncapsulatedEnviron, Expr, FlowControl, FnCallExpr,
-    FnCallHashes, Ident, OpAssignment, RangeCase, ScriptFuncDef, Stmt, StmtBlock,
-    SwitchCasesCollection,
+    ASTFlags, ASTNode, BinaryExpr, CatchClause, ComprehensionExpr, EncapsulatedEnviron, Expr,
+    FlowControl, FnCallExpr, FnCallHashes, Ident, OpAssignment, RangeCase, ScriptFuncDef, Stmt,
+    StmtBlock, SwitchCasesCollection, SwitchPattern, TryCatchBlock, VisitorMut,
 };
 
 #[cfg(feature = "internals")]
@@ -359,7 +393,7 @@ pub use ast::CustomExpr;
 
 #[cfg(feature = "internals")]
 #[cfg(not(feature = "no_module"))]
-pub use ast::Namespace;
+pub use ast::{ModuleBlock, Namespace};
 
 #[cfg(feature = "internals")]
 pub use eval::{Caches, FnResolutionCache, FnResolutionCacheEntry, GlobalRuntimeState, Target};
@@ -464,10 +498,18 @@ compile_error!("`f32_float` cannot be used with `no_float`");
 #[cfg(feature = "only_i64")]
 compile_error!("`only_i32` and `only_i64` cannot be used together");
 
+#[cfg(feature = "no_std")]
+#[cfg(feature = "regex")]
+compile_error!("`regex` cannot be used with `no-std`");
+
 #[cfg(feature = "no_std")]
 #[cfg(feature = "wasm-bindgen")]
 compile_error!("`wasm-bindgen` cannot be used with `no-std`");
 
+#[cfg(feature = "no_time")]
+#[cfg(feature = "profiling")]
+compile_error!("`profiling` cannot be used with `no_time`");
+
 #[cfg(target_family = "wasm")]
 #[cfg(feature = "no_std")]
 compile_error!("`no_std` cannot be used for WASM target");
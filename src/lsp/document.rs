@@ -0,0 +1,461 @@
+//! Module containing the analysis of a script document opened in the language server.
+
+use crate::tokenizer::{is_id_continue, Token};
+use crate::{Diagnostic, Engine, ParseError, Position, AST};
+use serde_json::{json, Value};
+
+/// A location in a document: zero-based line and zero-based character index within the line.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Loc {
+    /// Line number (zero-based).
+    pub line: usize,
+    /// Character index within the line (zero-based).
+    pub col: usize,
+}
+
+impl Loc {
+    /// End of the document.
+    pub const END: Self = Self {
+        line: usize::MAX,
+        col: usize::MAX,
+    };
+
+    /// Create a [`Loc`] from a [`Position`].
+    #[must_use]
+    pub fn from_position(pos: Position) -> Self {
+        Self {
+            line: pos.line().unwrap_or(1) - 1,
+            col: pos.position().unwrap_or(1) - 1,
+        }
+    }
+}
+
+/// Kind of a [`Symbol`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum SymbolKind {
+    /// A script-defined function.
+    Function,
+    /// A parameter of a script-defined function.
+    Parameter,
+    /// A variable defined via `let` or a `for` loop.
+    Variable,
+    /// A constant defined via `const`.
+    Constant,
+    /// An imported module (named by its alias).
+    Module,
+    /// A variable exported under a name.
+    Export,
+}
+
+/// A symbol declared in a document.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// Name of the symbol.
+    pub name: String,
+    /// Kind of the symbol.
+    pub kind: SymbolKind,
+    /// Location of the name.
+    pub loc: Loc,
+    /// Start of the declaration.
+    pub start: Loc,
+    /// End of the declaration (the closing brace of a function body).
+    pub end: Loc,
+    /// Index of the enclosing function (if any).
+    pub parent: Option<usize>,
+    /// Path of an imported module, and the location of the path string.
+    pub path: Option<(String, Loc)>,
+}
+
+impl Symbol {
+    /// Create a new [`Symbol`] spanning only its name.
+    fn new(name: &str, kind: SymbolKind, loc: Loc, parent: Option<usize>) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            loc,
+            start: loc,
+            end: loc,
+            parent,
+            path: None,
+        }
+    }
+}
+
+/// An identifier in a document, possibly qualified by a module name.
+#[derive(Debug, Clone)]
+pub struct Word {
+    /// Module qualifier (if any).
+    pub qualifier: Option<String>,
+    /// The identifier.
+    pub name: String,
+    /// Start of the identifier.
+    pub start: Loc,
+    /// End of the identifier (exclusive).
+    pub end: Loc,
+}
+
+/// Get the identifier at position `i` in a list of tokens.
+fn ident(tokens: &[(Token, Loc)], i: usize) -> Option<(&str, Loc)> {
+    match tokens.get(i) {
+        Some((Token::Identifier(s), loc)) => Some((s.as_str(), *loc)),
+        _ => None,
+    }
+}
+
+/// Scan a script for declarations of functions, variables, constants and modules.
+///
+/// Only the tokens are used, so declarations are found even if the script does not parse.
+#[must_use]
+pub fn scan(engine: &Engine, text: &str) -> Vec<Symbol> {
+    let scripts = [text];
+    let (stream, tc) = engine.lex(&scripts);
+
+    // Tokenize, keeping track of interpolated strings because the tokenizer relies on the parser
+    // to switch back to text mode after each interpolated expression
+    let mut tokens = Vec::new();
+    let mut level = 0_usize;
+    let mut interpolations = Vec::<(usize, bool)>::new();
+
+    for (token, pos) in stream {
+        match token {
+            Token::EOF => break,
+            Token::Comment(..) => continue,
+            Token::LeftBrace | Token::MapStart => level += 1,
+            Token::RightBrace => {
+                level = level.saturating_sub(1);
+
+                if interpolations.last().map_or(false, |&(n, ..)| n == level) {
+                    let (.., is_heredoc) = interpolations.pop().unwrap();
+                    let control = &mut *tc.borrow_mut();
+                    control.is_within_text = true;
+                    control.is_within_heredoc = is_heredoc;
+                }
+            }
+            Token::InterpolatedString(..) => {
+                let is_heredoc = std::mem::take(&mut tc.borrow_mut().is_within_heredoc);
+                interpolations.push((level, is_heredoc));
+            }
+            _ => (),
+        }
+        tokens.push((token, Loc::from_position(pos)));
+    }
+
+    let mut symbols = Vec::<Symbol>::new();
+    let mut depth = 0_usize;
+    // Functions whose bodies are being scanned, with the depth of each body
+    let mut functions = Vec::<(usize, usize)>::new();
+    #[allow(unused_mut)]
+    let mut pending_body = None;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let (token, loc) = &tokens[i];
+        let parent = functions.last().map(|&(f, ..)| f);
+
+        match token {
+            Token::LeftBrace | Token::MapStart => {
+                if let Some(f) = pending_body.take() {
+                    functions.push((f, depth));
+                }
+                depth += 1;
+            }
+            Token::RightBrace => {
+                depth = depth.saturating_sub(1);
+
+                if functions.last().map_or(false, |&(.., d)| d == depth) {
+                    let (f, ..) = functions.pop().unwrap();
+                    symbols[f].end = *loc;
+                }
+            }
+            #[cfg(not(feature = "no_function"))]
+            Token::Fn => {
+                // `fn name(...)` or `fn Type.name(...)`
+                let mut j = i + 1;
+                if matches!(tokens.get(j + 1), Some((Token::Period, ..))) {
+                    j += 2;
+                }
+
+                if let Some((name, name_loc)) = ident(&tokens, j) {
+                    let f = symbols.len();
+                    let mut symbol = Symbol::new(name, SymbolKind::Function, name_loc, None);
+                    symbol.start = *loc;
+                    symbol.end = Loc::END;
+                    symbols.push(symbol);
+
+                    j += 1;
+
+                    if matches!(tokens.get(j), Some((Token::LeftParen, ..))) {
+                        while let Some((token, loc)) = tokens.get(j) {
+                            match token {
+                                Token::RightParen => break,
+                                Token::Identifier(s) => symbols.push(Symbol::new(
+                                    s,
+                                    SymbolKind::Parameter,
+                                    *loc,
+                                    Some(f),
+                                )),
+                                _ => (),
+                            }
+                            j += 1;
+                        }
+                    }
+
+                    pending_body = Some(f);
+                    i = j;
+                }
+            }
+            Token::Let | Token::Const => {
+                let kind = if *token == Token::Let {
+                    SymbolKind::Variable
+                } else {
+                    SymbolKind::Constant
+                };
+                if let Some((name, loc)) = ident(&tokens, i + 1) {
+                    symbols.push(Symbol::new(name, kind, loc, parent));
+                }
+            }
+            Token::For => {
+                for (token, loc) in &tokens[i + 1..] {
+                    match token {
+                        Token::In | Token::LeftBrace => break,
+                        Token::Identifier(s) => {
+                            symbols.push(Symbol::new(s, SymbolKind::Variable, *loc, parent));
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            #[cfg(not(feature = "no_module"))]
+            Token::Import => {
+                if let (Some((Token::StringConstant(path), path_loc)), Some((Token::As, ..))) =
+                    (tokens.get(i + 1), tokens.get(i + 2))
+                {
+                    if let Some((alias, loc)) = ident(&tokens, i + 3) {
+                        let mut symbol = Symbol::new(alias, SymbolKind::Module, loc, parent);
+                        symbol.path = Some((path.to_string(), *path_loc));
+                        symbols.push(symbol);
+                    }
+                }
+            }
+            #[cfg(not(feature = "no_module"))]
+            Token::Export => {
+                // `export x`, `export x as y`, `export let x` or `export const x`
+                let n = match tokens.get(i + 1) {
+                    Some((Token::Let | Token::Const, ..)) => i + 2,
+                    _ => i + 1,
+                };
+                if let Some((name, loc)) = ident(&tokens, n) {
+                    let (name, loc) = match tokens.get(n + 1) {
+                        Some((Token::As, ..)) => ident(&tokens, n + 2).unwrap_or((name, loc)),
+                        _ => (name, loc),
+                    };
+                    symbols.push(Symbol::new(name, SymbolKind::Export, loc, parent));
+                }
+            }
+            _ => (),
+        }
+
+        i += 1;
+    }
+
+    symbols
+}
+
+/// A script document opened in the language server.
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// Text of the document.
+    pub text: String,
+    /// Partial [`AST`] of the document.
+    #[cfg_attr(feature = "no_function", allow(dead_code))]
+    pub ast: AST,
+    /// Parse errors in the document.
+    pub errors: Vec<ParseError>,
+    /// Diagnostics (warnings) of the document, only available if there are no parse errors.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Symbols declared in the document.
+    pub symbols: Vec<Symbol>,
+}
+
+impl Document {
+    /// Analyze the text of a document.
+    #[must_use]
+    pub fn new(engine: &Engine, text: String) -> Self {
+        let (ast, errors) = engine.compile_with_recovery(&text);
+
+        let diagnostics = if errors.is_empty() {
+            engine
+                .compile_with_diagnostics(&text)
+                .map_or_else(|_| Vec::new(), |(.., d)| d)
+        } else {
+            Vec::new()
+        };
+
+        let symbols = scan(engine, &text);
+
+        Self {
+            text,
+            ast,
+            errors,
+            diagnostics,
+            symbols,
+        }
+    }
+    /// Get the characters of a line in the document.
+    fn line(&self, line: usize) -> Vec<char> {
+        self.text
+            .split('\n')
+            .nth(line)
+            .map_or_else(Vec::new, |s| s.trim_end_matches('\r').chars().collect())
+    }
+    /// Get the end of the document.
+    #[must_use]
+    pub fn end(&self) -> Loc {
+        let line = self.text.split('\n').count() - 1;
+        Loc {
+            line,
+            col: self.line(line).len(),
+        }
+    }
+    /// Convert a [`Loc`] into an LSP position, counting characters in UTF-16 code units.
+    #[must_use]
+    pub fn to_lsp(&self, loc: Loc) -> Value {
+        let character: usize = self
+            .line(loc.line)
+            .iter()
+            .take(loc.col)
+            .map(|ch| ch.len_utf16())
+            .sum();
+
+        json!({ "line": loc.line, "character": character })
+    }
+    /// Convert an LSP range into a JSON value from two [`Loc`]'s.
+    #[must_use]
+    pub fn to_lsp_range(&self, start: Loc, end: Loc) -> Value {
+        json!({ "start": self.to_lsp(start), "end": self.to_lsp(end) })
+    }
+    /// Convert an LSP position, counting characters in UTF-16 code units, into a [`Loc`].
+    #[must_use]
+    pub fn lsp_to_loc(&self, pos: &Value) -> Loc {
+        let line = pos["line"].as_u64().unwrap_or(0) as usize;
+        let character = pos["character"].as_u64().unwrap_or(0) as usize;
+
+        let mut units = 0;
+        let col = self
+            .line(line)
+            .iter()
+            .take_while(|ch| {
+                units += ch.len_utf16();
+                units <= character
+            })
+            .count();
+
+        Loc { line, col }
+    }
+    /// Get the end of the identifier (if any) starting at a [`Loc`], or the next character.
+    #[must_use]
+    pub fn word_end(&self, loc: Loc) -> Loc {
+        let line = self.line(loc.line);
+        let n = line
+            .iter()
+            .skip(loc.col)
+            .take_while(|&&ch| is_id_continue(ch))
+            .count();
+
+        Loc {
+            line: loc.line,
+            col: (loc.col + n.max(1)).min(line.len().max(loc.col)),
+        }
+    }
+    /// Get the (possibly qualified) identifier at a [`Loc`].
+    #[must_use]
+    pub fn word_at(&self, loc: Loc) -> Option<Word> {
+        let line = self.line(loc.line);
+        let col = loc.col.min(line.len());
+
+        let start = col
+            - line[..col]
+                .iter()
+                .rev()
+                .take_while(|&&ch| is_id_continue(ch))
+                .count();
+        let end = col
+            + line[col..]
+                .iter()
+                .take_while(|&&ch| is_id_continue(ch))
+                .count();
+
+        if start == end {
+            return None;
+        }
+
+        Some(Word {
+            qualifier: self.qualifier_before(Loc {
+                line: loc.line,
+                col: start,
+            }),
+            name: line[start..end].iter().collect(),
+            start: Loc {
+                line: loc.line,
+                col: start,
+            },
+            end: Loc {
+                line: loc.line,
+                col: end,
+            },
+        })
+    }
+    /// Get the module qualifier (if any) of the identifier being typed at a [`Loc`].
+    #[must_use]
+    pub fn qualifier_before(&self, loc: Loc) -> Option<String> {
+        let line = self.line(loc.line);
+        let col = loc.col.min(line.len());
+
+        let start = col
+            - line[..col]
+                .iter()
+                .rev()
+                .take_while(|&&ch| is_id_continue(ch))
+                .count();
+
+        if start >= 2 && line[start - 2..start] == [':', ':'] {
+            let n = line[..start - 2]
+                .iter()
+                .rev()
+                .take_while(|&&ch| is_id_continue(ch))
+                .count();
+            Some(line[start - 2 - n..start - 2].iter().collect())
+        } else {
+            None
+        }
+    }
+    /// Get the index of the function whose definition encloses a [`Loc`].
+    #[must_use]
+    pub fn enclosing_fn(&self, loc: Loc) -> Option<usize> {
+        self.symbols
+            .iter()
+            .position(|s| s.kind == SymbolKind::Function && s.start <= loc && loc <= s.end)
+    }
+    /// Get the variables, constants, parameters and modules visible at a [`Loc`], latest first.
+    ///
+    /// Variables outside a function are not visible inside it.
+    pub fn visible_at(&self, loc: Loc) -> impl Iterator<Item = &Symbol> {
+        let parent = self.enclosing_fn(loc);
+
+        self.symbols.iter().rev().filter(move |s| {
+            s.parent == parent
+                && match s.kind {
+                    SymbolKind::Parameter => true,
+                    SymbolKind::Variable | SymbolKind::Constant | SymbolKind::Module => s.loc < loc,
+                    SymbolKind::Function | SymbolKind::Export => false,
+                }
+        })
+    }
+    /// Find a symbol of a particular kind by name.
+    #[must_use]
+    pub fn find(&self, name: &str, kind: SymbolKind) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .find(|s| s.kind == kind && s.name == name)
+    }
+}
@@ -0,0 +1,806 @@
+//! _(lsp)_ A [Language Server Protocol](https://microsoft.github.io/language-server-protocol/)
+//! server for Rhai scripts.
+//! Exported under the `lsp` feature only.
+//!
+//! The server is built on the parser of an [`Engine`] and on the metadata of the functions and
+//! custom types registered into it, so completion and hover know about everything the host
+//! application provides. It supports:
+//!
+//! * diagnostics: all parse errors (via [`Engine::compile_with_recovery`]) or, for a script that
+//!   parses, all warnings (via [`Engine::compile_with_diagnostics`]),
+//! * completion of variables, script-defined functions, registered functions, modules and custom
+//!   types,
+//! * hover, with doc-comments,
+//! * go-to-definition of variables, script-defined functions and imported modules (resolved
+//!   via a [`FileModuleResolver`][crate::module_resolvers::FileModuleResolver]),
+//! * document symbols.
+//!
+//! Documents are always synchronized in full.
+//!
+//! [`Server::run`] talks JSON-RPC over a pair of streams (e.g. standard input and output, as in
+//! the `rhai-lsp` tool). Messages can also be passed one at a time to [`Server::handle`].
+//!
+//! # Example
+//!
+//! ```
+//! use rhai::lsp::Server;
+//! use rhai::Engine;
+//!
+//! let mut server = Server::new(Engine::new());
+//!
+//! let replies = server.handle(r#"{
+//!     "jsonrpc": "2.0", "method": "textDocument/didOpen",
+//!     "params": { "textDocument": { "uri": "file:///test.rhai", "text": "let x = ;" } }
+//! }"#);
+//!
+//! assert_eq!(replies.len(), 1);
+//! assert!(replies[0].contains("textDocument/publishDiagnostics"));
+//! ```
+
+mod document;
+
+pub use crate::api::framing::{read_message, write_message};
+
+use crate::tokenizer::is_id_first_alphabetic;
+use crate::{Engine, Severity};
+use document::{Document, Loc, Symbol, SymbolKind, Word};
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+#[cfg(not(feature = "no_module"))]
+use std::path::{Path, PathBuf};
+
+/// JSON-RPC error code: invalid JSON.
+const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code: not a valid request.
+const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code: the method does not exist.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// LSP completion item kinds.
+mod completion_kind {
+    pub const FUNCTION: u8 = 3;
+    pub const VARIABLE: u8 = 6;
+    pub const CLASS: u8 = 7;
+    pub const MODULE: u8 = 9;
+    pub const CONSTANT: u8 = 21;
+}
+
+/// LSP symbol kinds.
+mod symbol_kind {
+    pub const MODULE: u8 = 2;
+    pub const FUNCTION: u8 = 12;
+    pub const VARIABLE: u8 = 13;
+    pub const CONSTANT: u8 = 14;
+}
+
+/// A function registered into the [`Engine`].
+#[derive(Debug, Clone)]
+struct NativeFn {
+    /// Name of the function, qualified by the name of its sub-module (if any).
+    name: String,
+    /// Signature of the function.
+    signature: String,
+    /// Doc-comments of the function.
+    doc: String,
+}
+
+/// Turn doc-comments into plain text by removing the comment markers.
+fn doc_text(comments: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    let mut text = String::new();
+
+    for comment in comments {
+        let comment = comment.as_ref();
+
+        let lines: Vec<_> = match comment.strip_prefix("/**") {
+            Some(block) => block
+                .strip_suffix("*/")
+                .unwrap_or(block)
+                .lines()
+                .map(|line| {
+                    let line = line.trim_start();
+                    line.strip_prefix("* ")
+                        .or_else(|| line.strip_prefix('*'))
+                        .unwrap_or(line)
+                })
+                .collect(),
+            None => comment
+                .lines()
+                .map(|line| {
+                    let line = line.trim_start();
+                    let line = line.strip_prefix("///").unwrap_or(line);
+                    line.strip_prefix(' ').unwrap_or(line)
+                })
+                .collect(),
+        };
+
+        for line in lines {
+            text += line;
+            text += "\n";
+        }
+    }
+
+    text.trim().into()
+}
+
+/// Format a signature and its documentation as markdown.
+fn markdown(signature: &str, doc: &str) -> Value {
+    let mut value = format!("```rhai\n{signature}\n```");
+
+    if !doc.is_empty() {
+        value += "\n\n";
+        value += doc;
+    }
+
+    json!({ "kind": "markdown", "value": value })
+}
+
+/// Convert a `file:` URI into a file path.
+#[cfg(not(feature = "no_module"))]
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+
+    while i < encoded.len() {
+        match encoded[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(encoded.get(i + 1..i + 3)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b => {
+                bytes.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    let path = String::from_utf8(bytes).ok()?;
+
+    // Windows paths start with a drive letter, e.g. `file:///C:/...`
+    match path.as_bytes() {
+        [b'/', _, b':', ..] => Some(path[1..].into()),
+        _ => Some(path.into()),
+    }
+}
+
+/// Convert a file path into a `file:` URI.
+#[cfg(not(feature = "no_module"))]
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-._~:".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri += &format!("%{b:02X}");
+        }
+    }
+
+    uri
+}
+
+/// A language server for Rhai scripts.
+///
+/// Not available under `no_std`.
+pub struct Server {
+    /// The [`Engine`] used to parse scripts.
+    engine: Engine,
+    /// Resolver of imported modules.
+    #[cfg(not(feature = "no_module"))]
+    resolver: crate::module_resolvers::FileModuleResolver,
+    /// Open documents, by URI.
+    documents: BTreeMap<String, Document>,
+    /// Functions registered into the [`Engine`].
+    functions: Vec<NativeFn>,
+    /// Custom types registered into the [`Engine`]: display names and doc-comments.
+    custom_types: Vec<(String, String)>,
+    /// Has a `shutdown` request been received?
+    shut_down: bool,
+    /// Has an `exit` notification been received?
+    exited: bool,
+}
+
+impl Server {
+    /// Create a new [`Server`] using an [`Engine`] to parse scripts.
+    ///
+    /// Imported modules are resolved by a default
+    /// [`FileModuleResolver`][crate::module_resolvers::FileModuleResolver], relative to the
+    /// directory of the importing script.
+    #[must_use]
+    pub fn new(engine: Engine) -> Self {
+        let functions = engine.collect_fn_metadata_impl(
+            None,
+            |info| {
+                let f = info.metadata;
+
+                if f.access == crate::FnAccess::Private
+                    || !f.name.starts_with(is_id_first_alphabetic)
+                    || f.name.contains('$')
+                {
+                    return None;
+                }
+
+                let name = f.name.to_string();
+                let signature = f.gen_signature(|s| engine.format_param_type(s));
+
+                #[cfg(not(feature = "no_module"))]
+                let (name, signature) = if info.namespace.is_empty() {
+                    (name, signature)
+                } else {
+                    let ns = &info.namespace;
+                    (format!("{ns}::{name}"), format!("{ns}::{signature}"))
+                };
+
+                Some(NativeFn {
+                    name,
+                    signature,
+                    doc: doc_text(&f.comments),
+                })
+            },
+            true,
+        );
+
+        let custom_types = engine
+            .global_modules
+            .iter()
+            .flat_map(|m| m.iter_custom_types())
+            .map(|(.., t)| (t.display_name.to_string(), doc_text(&t.comments)))
+            .collect();
+
+        Self {
+            engine,
+            #[cfg(not(feature = "no_module"))]
+            resolver: crate::module_resolvers::FileModuleResolver::new(),
+            documents: BTreeMap::new(),
+            functions,
+            custom_types,
+            shut_down: false,
+            exited: false,
+        }
+    }
+    /// Get the [`Engine`] used to parse scripts.
+    #[inline(always)]
+    #[must_use]
+    pub const fn engine(&self) -> &Engine {
+        &self.engine
+    }
+    /// Set the [`FileModuleResolver`][crate::module_resolvers::FileModuleResolver] used to resolve
+    /// imported modules for go-to-definition, hover and completion.
+    ///
+    /// Not available under `no_module`.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub fn set_module_resolver(
+        &mut self,
+        resolver: crate::module_resolvers::FileModuleResolver,
+    ) -> &mut Self {
+        self.resolver = resolver;
+        self
+    }
+    /// Has a `shutdown` request been received?
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+    /// Has an `exit` notification been received?
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Run the server, reading JSON-RPC messages from `input` and writing replies into `output`,
+    /// until the end of `input` or an `exit` notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stream cannot be read or written to, or if a message is not framed
+    /// properly.
+    pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        while !self.exited {
+            let Some(message) = read_message(&mut input)? else {
+                break;
+            };
+
+            for reply in self.handle(&message) {
+                write_message(&mut output, &reply)?;
+            }
+            output.flush()?;
+        }
+
+        Ok(())
+    }
+    /// Handle a single JSON-RPC message (without framing), returning the replies to send back: a
+    /// response to a request, and/or notifications (e.g. diagnostics).
+    #[must_use]
+    pub fn handle(&mut self, message: &str) -> Vec<String> {
+        let message: Value = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(err) => return vec![error_response(&Value::Null, PARSE_ERROR, &err.to_string())],
+        };
+
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        match message.get("id") {
+            // Response to a request from the server
+            Some(..) if method.is_empty() => Vec::new(),
+            // Request
+            Some(id) if self.shut_down => {
+                vec![error_response(id, INVALID_REQUEST, "Server is shut down")]
+            }
+            Some(id) => match self.request(method, params) {
+                Some(result) => {
+                    vec![json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string()]
+                }
+                None => vec![error_response(
+                    id,
+                    METHOD_NOT_FOUND,
+                    &format!("Unknown method: {method}"),
+                )],
+            },
+            // Notification
+            None => self.notify(method, params),
+        }
+    }
+
+    /// Handle a request, returning [`None`] if the method is not supported.
+    fn request(&mut self, method: &str, params: &Value) -> Option<Value> {
+        Some(match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "completionProvider": { "triggerCharacters": [":"] },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": { "name": "rhai-lsp", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => {
+                self.shut_down = true;
+                Value::Null
+            }
+            "textDocument/completion" => self.completion(params),
+            "textDocument/hover" => self.hover(params).unwrap_or_default(),
+            "textDocument/definition" => self.definition(params).unwrap_or_default(),
+            "textDocument/documentSymbol" => self.document_symbols(params).unwrap_or_default(),
+            _ => return None,
+        })
+    }
+    /// Handle a notification, returning notifications to send back.
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<String> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                let doc = Document::new(&self.engine, text.into());
+                self.documents.insert(uri.into(), doc);
+                vec![self.publish_diagnostics(uri)]
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+
+                match changes
+                    .and_then(|c| c.last())
+                    .and_then(|c| c["text"].as_str())
+                {
+                    Some(text) => {
+                        let doc = Document::new(&self.engine, text.into());
+                        self.documents.insert(uri.into(), doc);
+                        vec![self.publish_diagnostics(uri)]
+                    }
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                vec![self.publish_diagnostics(uri)]
+            }
+            "exit" => {
+                self.exited = true;
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the URI and an open document referred to by request parameters.
+    fn document(&self, params: &Value) -> Option<(&str, &Document)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        self.documents
+            .get_key_value(uri)
+            .map(|(uri, doc)| (uri.as_str(), doc))
+    }
+    /// Resolve a module imported by a document into its URI and document.
+    ///
+    /// Open documents are used before files.
+    #[cfg(not(feature = "no_module"))]
+    fn resolve(&self, uri: &str, path: &str) -> Option<(String, Cow<'_, Document>)> {
+        let source = uri_to_path(uri);
+        let file = self
+            .resolver
+            .get_file_path(path, source.as_deref().and_then(Path::parent));
+        let target = path_to_uri(&file);
+
+        if let Some(doc) = self.documents.get(&target) {
+            return Some((target, Cow::Borrowed(doc)));
+        }
+
+        let text = std::fs::read_to_string(&file).ok()?;
+        Some((target, Cow::Owned(Document::new(&self.engine, text))))
+    }
+    /// Resolve a module, by its alias, imported by a document.
+    #[allow(unused_variables)]
+    fn resolve_alias(
+        &self,
+        uri: &str,
+        doc: &Document,
+        alias: &str,
+        loc: Loc,
+    ) -> Option<(String, Cow<'_, Document>)> {
+        #[cfg(not(feature = "no_module"))]
+        return doc
+            .visible_at(loc)
+            .find(|s| s.kind == SymbolKind::Module && s.name == alias)
+            .and_then(|s| self.resolve(uri, &s.path.as_ref()?.0));
+
+        #[cfg(feature = "no_module")]
+        return None;
+    }
+
+    /// Create a `textDocument/publishDiagnostics` notification for a document.
+    fn publish_diagnostics(&self, uri: &str) -> String {
+        let mut diagnostics = Vec::new();
+
+        if let Some(doc) = self.documents.get(uri) {
+            for err in &doc.errors {
+                let loc = Loc::from_position(err.position());
+                diagnostics.push(json!({
+                    "range": doc.to_lsp_range(loc, doc.word_end(loc)),
+                    "severity": 1,
+                    "source": "rhai",
+                    "message": err.err_type().to_string(),
+                }));
+            }
+
+            for diagnostic in &doc.diagnostics {
+                let loc = Loc::from_position(diagnostic.position());
+                let mut value = json!({
+                    "range": doc.to_lsp_range(loc, doc.word_end(loc)),
+                    "severity": match diagnostic.severity() {
+                        Severity::Warning => 2,
+                        Severity::Hint => 4,
+                    },
+                    "code": diagnostic.code(),
+                    "source": "rhai",
+                    "message": diagnostic.diagnostic_type().to_string(),
+                });
+
+                // Tag diagnostics so that editors can render them specially
+                match diagnostic.code() {
+                    "unused_variable" | "unused_parameter" | "unreachable_code" => {
+                        value["tags"] = json!([1]);
+                    }
+                    "deprecated" => value["tags"] = json!([2]),
+                    _ => (),
+                }
+
+                diagnostics.push(value);
+            }
+        }
+
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+        .to_string()
+    }
+
+    /// Get the signature and documentation of a script-defined function in a document.
+    fn fn_info(doc: &Document, f: usize) -> (String, String) {
+        let name = &doc.symbols[f].name;
+        let params: Vec<_> = doc
+            .symbols
+            .iter()
+            .filter(|s| s.parent == Some(f) && s.kind == SymbolKind::Parameter)
+            .map(|s| s.name.as_str())
+            .collect();
+
+        let signature = format!("fn {name}({})", params.join(", "));
+
+        #[cfg(not(feature = "no_function"))]
+        let text = doc
+            .ast
+            .iter_functions()
+            .find(|m| m.name == name && m.params.len() == params.len())
+            .map_or_else(String::new, |m| doc_text(&m.comments));
+
+        #[cfg(feature = "no_function")]
+        let text = String::new();
+
+        (signature, text)
+    }
+    /// Get the signature and documentation of a symbol, or a registered function or custom type.
+    fn describe(&self, uri: &str, doc: &Document, word: &Word) -> Option<(String, String)> {
+        let name = &word.name;
+
+        if let Some(ref qualifier) = word.qualifier {
+            // Member of an imported module
+            if let Some((.., module)) = self.resolve_alias(uri, doc, qualifier, word.start) {
+                return match module.symbols.iter().position(|s| &s.name == name) {
+                    Some(f) if module.symbols[f].kind == SymbolKind::Function => {
+                        Some(Self::fn_info(&module, f))
+                    }
+                    Some(..) => Some((format!("{qualifier}::{name}"), String::new())),
+                    None => None,
+                };
+            }
+
+            // Function in a registered sub-module
+            return self.describe_native(&format!("{qualifier}::{name}"));
+        }
+
+        if let Some(s) = doc.visible_at(word.start).find(|s| &s.name == name) {
+            let signature = match s.kind {
+                SymbolKind::Variable => format!("let {name}"),
+                SymbolKind::Constant => format!("const {name}"),
+                SymbolKind::Module => {
+                    let path = s.path.as_ref().map_or("", |(p, ..)| p);
+                    format!("import {path:?} as {name}")
+                }
+                _ => format!("{name} (parameter)"),
+            };
+            return Some((signature, String::new()));
+        }
+
+        if let Some(f) = doc
+            .symbols
+            .iter()
+            .position(|s| s.kind == SymbolKind::Function && &s.name == name)
+        {
+            return Some(Self::fn_info(doc, f));
+        }
+
+        if let Some(info) = self.describe_native(name) {
+            return Some(info);
+        }
+
+        self.custom_types
+            .iter()
+            .find(|(n, ..)| n == name)
+            .map(|(n, text)| (format!("type {n}"), text.clone()))
+    }
+    /// Get the signatures (of all overloads) and documentation of a registered function.
+    fn describe_native(&self, name: &str) -> Option<(String, String)> {
+        let overloads: Vec<_> = self.functions.iter().filter(|f| f.name == name).collect();
+
+        if overloads.is_empty() {
+            return None;
+        }
+
+        let signatures = overloads
+            .iter()
+            .map(|f| format!("fn {}", f.signature))
+            .collect::<BTreeSet<_>>();
+        let text = overloads
+            .iter()
+            .find(|f| !f.doc.is_empty())
+            .map_or_else(String::new, |f| f.doc.clone());
+
+        Some((signatures.into_iter().collect::<Vec<_>>().join("\n"), text))
+    }
+
+    /// Handle a `textDocument/completion` request.
+    fn completion(&self, params: &Value) -> Value {
+        let Some((uri, doc)) = self.document(params) else {
+            return Value::Null;
+        };
+        let loc = doc.lsp_to_loc(&params["position"]);
+
+        let mut names = BTreeSet::new();
+        let mut items = Vec::new();
+        let mut add = |label: &str, kind: u8, detail: &str, text: &str| {
+            if names.insert(label.to_string()) {
+                let mut item = json!({ "label": label, "kind": kind });
+                if !detail.is_empty() {
+                    item["detail"] = json!(detail);
+                }
+                if !text.is_empty() {
+                    item["documentation"] = json!({ "kind": "markdown", "value": text });
+                }
+                items.push(item);
+            }
+        };
+
+        if let Some(qualifier) = doc.qualifier_before(loc) {
+            // Members of an imported module
+            if let Some((.., module)) = self.resolve_alias(uri, doc, &qualifier, loc) {
+                for (f, s) in module.symbols.iter().enumerate() {
+                    match s.kind {
+                        SymbolKind::Function => {
+                            let (signature, text) = Self::fn_info(&module, f);
+                            add(&s.name, completion_kind::FUNCTION, &signature, &text);
+                        }
+                        SymbolKind::Export => add(&s.name, completion_kind::VARIABLE, "", ""),
+                        _ => (),
+                    }
+                }
+            }
+
+            // Functions in a registered sub-module
+            let prefix = format!("{qualifier}::");
+
+            for f in &self.functions {
+                if let Some(name) = f.name.strip_prefix(&prefix) {
+                    if !name.contains("::") {
+                        add(name, completion_kind::FUNCTION, &f.signature, &f.doc);
+                    }
+                }
+            }
+
+            return json!(items);
+        }
+
+        for s in doc.visible_at(loc) {
+            let kind = match s.kind {
+                SymbolKind::Constant => completion_kind::CONSTANT,
+                SymbolKind::Module => completion_kind::MODULE,
+                _ => completion_kind::VARIABLE,
+            };
+            add(&s.name, kind, "", "");
+        }
+
+        for (f, s) in doc.symbols.iter().enumerate() {
+            if s.kind == SymbolKind::Function {
+                let (signature, text) = Self::fn_info(doc, f);
+                add(&s.name, completion_kind::FUNCTION, &signature, &text);
+            }
+        }
+
+        for f in &self.functions {
+            match f.name.split_once("::") {
+                Some((module, ..)) => add(module, completion_kind::MODULE, "", ""),
+                None => add(&f.name, completion_kind::FUNCTION, &f.signature, &f.doc),
+            }
+        }
+
+        for (name, text) in &self.custom_types {
+            add(name, completion_kind::CLASS, "", text);
+        }
+
+        json!(items)
+    }
+    /// Handle a `textDocument/hover` request.
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (uri, doc) = self.document(params)?;
+        let word = doc.word_at(doc.lsp_to_loc(&params["position"]))?;
+        let (signature, text) = self.describe(uri, doc, &word)?;
+
+        Some(json!({
+            "contents": markdown(&signature, &text),
+            "range": doc.to_lsp_range(word.start, word.end),
+        }))
+    }
+    /// Handle a `textDocument/definition` request.
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (uri, doc) = self.document(params)?;
+        let loc = doc.lsp_to_loc(&params["position"]);
+
+        let location = |uri: &str, doc: &Document, s: Option<&Symbol>| {
+            let loc = s.map_or(Loc::default(), |s| s.loc);
+            let end = s.map_or(loc, |_| doc.word_end(loc));
+            json!({ "uri": uri, "range": doc.to_lsp_range(loc, end) })
+        };
+
+        // Path of an imported module
+        #[cfg(not(feature = "no_module"))]
+        for s in &doc.symbols {
+            if let Some((ref path, path_loc)) = s.path {
+                let len = path.chars().count() + 2;
+
+                if loc.line == path_loc.line
+                    && loc.col >= path_loc.col
+                    && loc.col < path_loc.col + len
+                {
+                    let (target, module) = self.resolve(uri, path)?;
+                    return Some(location(&target, &module, None));
+                }
+            }
+        }
+
+        let word = doc.word_at(loc)?;
+        let name = &word.name;
+
+        if let Some(ref qualifier) = word.qualifier {
+            // Member of an imported module
+            let (target, module) = self.resolve_alias(uri, doc, qualifier, word.start)?;
+            let s = module.symbols.iter().find(|s| {
+                &s.name == name && matches!(s.kind, SymbolKind::Function | SymbolKind::Export)
+            });
+            return Some(location(&target, &module, s));
+        }
+
+        if let Some(s) = doc.visible_at(word.start).find(|s| &s.name == name) {
+            if s.kind == SymbolKind::Module {
+                if let Some((target, module)) = self.resolve_alias(uri, doc, name, word.start) {
+                    return Some(location(&target, &module, None));
+                }
+            }
+            return Some(location(uri, doc, Some(s)));
+        }
+
+        doc.find(name, SymbolKind::Function)
+            .map(|s| location(uri, doc, Some(s)))
+    }
+    /// Handle a `textDocument/documentSymbol` request.
+    fn document_symbols(&self, params: &Value) -> Option<Value> {
+        let (.., doc) = self.document(params)?;
+
+        let symbol = |s: &Symbol, children: Vec<Value>| {
+            let kind = match s.kind {
+                SymbolKind::Function => symbol_kind::FUNCTION,
+                SymbolKind::Constant => symbol_kind::CONSTANT,
+                SymbolKind::Module => symbol_kind::MODULE,
+                _ => symbol_kind::VARIABLE,
+            };
+            let end = match s.end {
+                Loc::END => doc.end(),
+                end if end == s.start => doc.word_end(end),
+                end => Loc {
+                    line: end.line,
+                    col: end.col + 1,
+                },
+            };
+            let mut value = json!({
+                "name": s.name,
+                "kind": kind,
+                "range": doc.to_lsp_range(s.start, end),
+                "selectionRange": doc.to_lsp_range(s.loc, doc.word_end(s.loc)),
+            });
+            if !children.is_empty() {
+                value["children"] = json!(children);
+            }
+            value
+        };
+
+        let symbols: Vec<_> = doc
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(.., s)| s.parent.is_none() && s.kind != SymbolKind::Export)
+            .map(|(i, s)| {
+                let children = doc
+                    .symbols
+                    .iter()
+                    .filter(|c| c.parent == Some(i))
+                    .map(|c| symbol(c, Vec::new()))
+                    .collect();
+                symbol(s, children)
+            })
+            .collect();
+
+        Some(json!(symbols))
+    }
+}
+
+/// Create a JSON-RPC error response.
+fn error_response(id: &Value, code: i64, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
@@ -1014,6 +1014,13 @@ impl Module {
             .add_with_comments(type_name, display_name, comments);
         self
     }
+    /// Register the information of a custom type, such as a script-defined `struct` type.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub(crate) fn set_custom_type_info(&mut self, info: CustomTypeInfo) -> &mut Self {
+        self.custom_types.add_raw(info.type_name.clone(), info);
+        self
+    }
     /// Get the display name of a registered custom type.
     ///
     /// # Example
@@ -1105,6 +1112,7 @@ impl Module {
                 .as_ref()
                 .map_or(true, StraightHashMap::is_empty)
             && self.all_type_iterators.is_empty()
+            && self.custom_types.is_empty()
     }
 
     /// Is the [`Module`] indexed?
@@ -2119,6 +2127,10 @@ impl Module {
         self.dynamic_functions_filter += &other.dynamic_functions_filter;
 
         self.type_iterators.extend(other.type_iterators.clone());
+        other
+            .custom_types
+            .iter()
+            .for_each(|(k, v)| self.custom_types.add_raw(k, v.clone()));
         self.all_functions = None;
         self.all_variables = None;
         self.all_type_iterators.clear();
@@ -2248,6 +2260,60 @@ impl Module {
         })
     }
 
+    /// Get a mutable iterator over the definitions of all script-defined functions in the [`Module`].
+    ///
+    /// The index of the [`Module`], if any, is invalidated.
+    #[cfg(feature = "internals")]
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    pub(crate) fn iter_script_fn_def_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut crate::ast::ScriptFuncDef> {
+        self.all_functions = None;
+        self.flags
+            .remove(ModuleFlags::INDEXED | ModuleFlags::INDEXED_GLOBAL_FUNCTIONS);
+
+        self.functions
+            .iter_mut()
+            .flat_map(StraightHashMap::values_mut)
+            .filter_map(|(f, _)| match f {
+                RhaiFunc::Script { fn_def, .. } => Some(crate::func::shared_make_mut(fn_def)),
+                _ => None,
+            })
+    }
+
+    /// Give each script-defined function in the [`Module`] with `static` variables its own storage,
+    /// holding copies of the current values, instead of sharing it with other clones.
+    ///
+    /// The index of the [`Module`], if any, is invalidated.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn separate_static_variables(&mut self) {
+        self.all_functions = None;
+        self.flags
+            .remove(ModuleFlags::INDEXED | ModuleFlags::INDEXED_GLOBAL_FUNCTIONS);
+
+        self.functions
+            .iter_mut()
+            .flat_map(StraightHashMap::values_mut)
+            .for_each(|(f, _)| match f {
+                RhaiFunc::Script { fn_def, .. } if fn_def.statics.is_some() => {
+                    let fn_def = crate::func::shared_make_mut(fn_def);
+                    let values = crate::func::locked_read(fn_def.statics.as_ref().unwrap())
+                        .unwrap()
+                        .iter()
+                        .map(|(name, value)| {
+                            let value = value.flatten_clone();
+                            #[cfg(not(feature = "no_closure"))]
+                            let value = value.into_shared();
+                            (name.clone(), value)
+                        })
+                        .collect();
+                    fn_def.statics = Some(crate::Locked::new(values).into());
+                }
+                _ => (),
+            });
+    }
+
     /// _(internals)_ Get an iterator over all script-defined functions in the [`Module`].
     /// Exported under the `internals` feature only.
     ///
@@ -2447,6 +2513,12 @@ impl Module {
                 }
             });
 
+        // Script-defined `struct` types become module types
+        #[cfg(not(feature = "no_function"))]
+        ast.shared_lib()
+            .iter_custom_types()
+            .for_each(|(k, v)| module.custom_types.add_raw(k, v.clone()));
+
         module.id = ast.source_raw().cloned();
 
         #[cfg(feature = "metadata")]
@@ -2,7 +2,7 @@
 #![cfg(not(feature = "no_optimize"))]
 
 use crate::ast::{
-    ASTFlags, Expr, FlowControl, OpAssignment, Stmt, StmtBlock, StmtBlockContainer,
+    ASTFlags, Expr, FlowControl, FnCallExpr, OpAssignment, Stmt, StmtBlock, StmtBlockContainer,
     SwitchCasesCollection,
 };
 use crate::engine::{
@@ -24,7 +24,9 @@ use std::{
     borrow::Cow,
     convert::TryFrom,
     hash::{Hash, Hasher},
+    iter::FromIterator,
     mem,
+    ops::Deref,
 };
 
 /// Level of optimization performed.
@@ -129,6 +131,26 @@ impl<'a> OptimizerState<'a> {
             .find(|(n, _)| n == name)
             .and_then(|(_, value)| value.as_deref())
     }
+    /// Is there a script-defined function (which can override built-in) for a function call
+    /// with these argument values?
+    ///
+    /// Under Fast Operators mode, script-defined operator functions only overload operators for
+    /// custom types and structs.
+    #[inline]
+    pub fn has_script_fn(&self, _x: &FnCallExpr, _args: &[Dynamic]) -> bool {
+        #[cfg(not(feature = "no_function"))]
+        return !_x.hashes.is_native_only()
+            && (!_x.is_operator_call()
+                || !self.engine.fast_operators()
+                || _args.iter().any(Dynamic::is_overloadable))
+            && self
+                .global
+                .lib
+                .iter()
+                .any(|m| m.get_script_fn(&_x.name, _x.args.len()).is_some());
+        #[cfg(feature = "no_function")]
+        return false;
+    }
     /// Call a registered function
     #[inline]
     pub fn call_fn_with_const_args(
@@ -547,7 +569,7 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
         }
 
         // switch const { ... }
-        Stmt::Switch(x, pos) if is_hashable_constant(&x.0) => {
+        Stmt::Switch(x, pos) if is_hashable_constant(&x.0) && x.1.patterns.is_empty() => {
             let (
                 match_expr,
                 SwitchCasesCollection {
@@ -555,6 +577,7 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                     cases,
                     ranges,
                     def_case,
+                    ..
                 },
             ) = &mut **x;
 
@@ -699,17 +722,26 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                     cases,
                     ranges,
                     def_case,
-                    ..
+                    patterns,
                 },
             ) = &mut **x;
 
             optimize_expr(match_expr, state, false);
 
             // Optimize blocks
-            for b in &mut *expressions {
+            for (index, b) in expressions.iter_mut().enumerate() {
+                let orig_variables_len = state.variables.len();
+
+                // Variables bound by a pattern shadow any constant with the same name
+                if let Some((pattern, ..)) = patterns.iter().find(|&&(.., n)| n == index) {
+                    pattern.for_each_binding(&mut |x| state.push_var(x.name.clone(), None));
+                }
+
                 optimize_expr(&mut b.lhs, state, false);
                 optimize_expr(&mut b.rhs, state, false);
 
+                state.rewind_var(orig_variables_len);
+
                 if matches!(b.lhs, Expr::BoolConstant(false, ..)) && !b.rhs.is_unit() {
                     b.rhs = Expr::Unit(b.rhs.position());
                     state.set_dirty();
@@ -755,6 +787,16 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                 }
             });
 
+            // Remove false patterns
+            patterns.retain(|(.., index)| {
+                if matches!(expressions[*index].lhs, Expr::BoolConstant(false, ..)) {
+                    state.set_dirty();
+                    false
+                } else {
+                    true
+                }
+            });
+
             if let Some(index) = def_case {
                 optimize_expr(&mut expressions[*index].rhs, state, false);
             }
@@ -764,6 +806,7 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                 if *def_case != Some(index)
                     && cases.values().flat_map(|c| c.iter()).all(|&n| n != index)
                     && ranges.iter().all(|r| r.index() != index)
+                    && patterns.iter().all(|&(.., n)| n != index)
                     && !b.rhs.is_unit()
                 {
                     b.rhs = Expr::Unit(b.rhs.position());
@@ -809,6 +852,30 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
         // import expr as var;
         #[cfg(not(feature = "no_module"))]
         Stmt::Import(x, ..) => optimize_expr(&mut x.0, state, false),
+        // module name { body }
+        #[cfg(not(feature = "no_module"))]
+        Stmt::Module(x, ..) => {
+            // The body runs in its own scope, so it is optimized on its own
+            let statements = std::mem::take(x.body.statements_mut())
+                .into_iter()
+                .collect();
+
+            #[cfg(not(feature = "no_function"))]
+            let functions = x
+                .body
+                .shared_lib()
+                .iter_script_fn()
+                .map(|(.., f)| f.clone())
+                .collect::<Vec<_>>();
+
+            x.body = state.engine.optimize_into_ast(
+                None,
+                statements,
+                #[cfg(not(feature = "no_function"))]
+                functions,
+                state.optimization_level,
+            );
+        }
         // { block }
         Stmt::Block(block) => {
             let mut stmts =
@@ -827,23 +894,76 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                 _ => *block.statements_mut() = stmts,
             }
         }
-        // try { pure try_block } catch ( var ) { catch_block } -> try_block
-        Stmt::TryCatch(x, ..) if x.body.iter().all(Stmt::is_pure) => {
+        // try { pure try_block } catch ( var ) { catch_block } finally { pure_block } -> try_block
+        Stmt::TryCatch(x, ..)
+            if x.body.iter().all(Stmt::is_pure) && x.finally.iter().all(Stmt::is_pure) =>
+        {
             // If try block is pure, there will never be any exceptions
             state.set_dirty();
             let statements = x.body.take_statements();
             let block = StmtBlock::new_with_span(
-                optimize_stmt_block(statements, state, false, true, false),
+                optimize_stmt_block(statements, state, preserve_result, true, false),
                 x.body.span(),
             );
             *stmt = Stmt::Block(block.into());
         }
-        // try { try_block } catch ( var ) { catch_block }
+        // try { try_block } catch ( var ) if guard { catch_block } ... finally { finally_block }
         Stmt::TryCatch(x, ..) => {
-            *x.body.statements_mut() =
-                optimize_stmt_block(x.body.take_statements(), state, false, true, false);
-            *x.branch.statements_mut() =
-                optimize_stmt_block(x.branch.take_statements(), state, false, true, false);
+            *x.body.statements_mut() = optimize_stmt_block(
+                x.body.take_statements(),
+                state,
+                preserve_result,
+                true,
+                false,
+            );
+
+            // If try block is pure, there will never be any exceptions to catch
+            if !x.catches.is_empty() && x.body.iter().all(Stmt::is_pure) {
+                state.set_dirty();
+                x.catches.clear();
+            }
+
+            for clause in &mut x.catches {
+                let orig_variables_len = state.variables.len();
+
+                // The error variable shadows any constant with the same name
+                if let Expr::Variable(v, ..) = &clause.var {
+                    state.push_var(v.1.clone(), None);
+                }
+
+                optimize_expr(&mut clause.guard, state, false);
+                *clause.body.statements_mut() =
+                    optimize_stmt_block(clause.body.take_statements(), state, false, true, false);
+
+                state.rewind_var(orig_variables_len);
+            }
+
+            // catch ( var ) if false { catch_block } -> remove the clause
+            let len = x.catches.len();
+            x.catches
+                .retain(|c| !matches!(c.guard, Expr::BoolConstant(false, ..)));
+
+            // catch ( var ) { catch_block } catch ... -> all further clauses are unreachable
+            if let Some(n) = x
+                .catches
+                .iter()
+                .position(|c| matches!(c.guard, Expr::BoolConstant(true, ..)))
+            {
+                x.catches.truncate(n + 1);
+            }
+
+            if x.catches.len() != len {
+                state.set_dirty();
+            }
+
+            *x.finally.statements_mut() =
+                optimize_stmt_block(x.finally.take_statements(), state, false, true, false);
+
+            // try { try_block } -> { try_block }
+            if x.catches.is_empty() && x.finally.is_empty() {
+                state.set_dirty();
+                *stmt = Stmt::Block(mem::take(&mut x.body).into());
+            }
         }
 
         // expr(stmt)
@@ -891,6 +1011,10 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
         // return expr;
         Stmt::Return(Some(ref mut expr), ..) => optimize_expr(expr, state, false),
 
+        // yield expr;
+        #[cfg(not(feature = "no_function"))]
+        Stmt::Yield(ref mut expr, ..) => optimize_expr(expr, state, false),
+
         // Share nothing
         #[cfg(not(feature = "no_closure"))]
         Stmt::Share(x) if x.is_empty() => {
@@ -935,6 +1059,114 @@ fn move_constant_arg(arg_expr: &mut Expr) -> bool {
     }
 }
 
+// Is this a spread of a constant array or tuple?
+fn is_constant_list_spread(expr: &Expr) -> bool {
+    match expr {
+        Expr::Spread(x, ..) => match **x {
+            #[cfg(not(feature = "no_index"))]
+            Expr::DynamicConstant(ref v, ..) if v.is_array() => true,
+            Expr::DynamicConstant(ref v, ..) => v.is_tuple(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Expand spreads of constant arrays or tuples into their items.
+fn expand_constant_spreads<T>(items: &mut T) -> bool
+where
+    T: Default + Deref<Target = [Expr]> + IntoIterator<Item = Expr> + FromIterator<Expr>,
+{
+    if !items.iter().any(is_constant_list_spread) {
+        return false;
+    }
+
+    *items = mem::take(items)
+        .into_iter()
+        .flat_map(|expr| {
+            if !is_constant_list_spread(&expr) {
+                return vec![expr];
+            }
+
+            let pos = expr.position();
+            let value = match expr {
+                Expr::Spread(x, ..) => x.get_literal_value(None).unwrap(),
+                _ => unreachable!("Expr::Spread expected but gets {:?}", expr),
+            };
+
+            let items = match value.try_cast_result::<crate::Tuple>() {
+                Ok(tuple) => tuple.into_vec(),
+                #[cfg(not(feature = "no_index"))]
+                Err(array) => array.cast::<crate::Array>(),
+                #[cfg(feature = "no_index")]
+                Err(v) => unreachable!("tuple expected but gets {}", v.type_name()),
+            };
+
+            items
+                .into_iter()
+                .map(|v| Expr::from_dynamic(v, pos))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    true
+}
+
+// Expand spreads of constant object maps into their properties.
+//
+// A spread is not expanded if any of its properties is also set elsewhere in the map literal
+// because that would change which value overrides the other.
+#[cfg(not(feature = "no_object"))]
+fn expand_constant_map_spreads(
+    entries: &mut StaticVec<(crate::ast::Ident, Expr)>,
+    template: &mut std::collections::BTreeMap<crate::Identifier, Dynamic>,
+) -> bool {
+    let mut changed = false;
+    let mut i = 0;
+
+    while i < entries.len() {
+        let map = match entries[i].1 {
+            Expr::Spread(ref x, ..) => match **x {
+                Expr::DynamicConstant(ref v, ..) if v.is_map() => v.as_map_ref().unwrap().clone(),
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            },
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        if map.keys().any(|k| {
+            entries
+                .iter()
+                .any(|(p, e)| !matches!(e, Expr::Spread(..)) && p.name == k.as_str())
+        }) {
+            i += 1;
+            continue;
+        }
+
+        let pos = entries.remove(i).1.position();
+
+        for (name, value) in map {
+            let expr = Expr::from_dynamic(value, pos);
+            let ident = crate::ast::Ident {
+                name: name.as_str().into(),
+                pos,
+            };
+            entries.insert(i, (ident, expr));
+            template.insert(name, Dynamic::UNIT);
+            i += 1;
+        }
+
+        changed = true;
+    }
+
+    changed
+}
+
 /// Optimize an [expression][Expr].
 fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
     // These keywords are handled specially
@@ -995,7 +1227,7 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
             (lhs, Expr::MethodCall(x, pos)) if lhs.is_constant() && x.name == KEYWORD_TYPE_OF && x.args.is_empty() => {
                 if let Some(value) = lhs.get_literal_value(None) {
                     state.set_dirty();
-                    let typ = state.engine.map_type_name(value.type_name()).into();
+                    let typ = state.engine.map_value_type_name(&value).as_ref().into();
                     *expr = Expr::from_dynamic(typ, *pos);
                 }
             }
@@ -1139,7 +1371,23 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         }
         // [ items .. ]
         #[cfg(not(feature = "no_index"))]
-        Expr::Array(x, ..) => x.iter_mut().for_each(|expr| optimize_expr(expr, state, false)),
+        Expr::Array(x, ..) => {
+            x.iter_mut().for_each(|expr| optimize_expr(expr, state, false));
+
+            // [ .., ...[ constant, .. ], .. ] -> [ .., constant, .. ]
+            if expand_constant_spreads(x) {
+                state.set_dirty();
+            }
+        }
+        // ( constant, .. ) | Enum::Variant( constant, .. )
+        Expr::Tuple(..) | Expr::Enum(..) if expr.is_constant() => {
+            state.set_dirty();
+            *expr = Expr::DynamicConstant(expr.get_literal_value(None).unwrap().into(), expr.position());
+        }
+        // ( items, .. )
+        Expr::Tuple(x, ..) => x.iter_mut().for_each(|expr| optimize_expr(expr, state, false)),
+        // Enum::Variant( items, .. )
+        Expr::Enum(x, ..) => x.2.iter_mut().for_each(|expr| optimize_expr(expr, state, false)),
         // #{ key:constant, .. }
         #[cfg(not(feature = "no_object"))]
         Expr::Map(..) if expr.is_constant() => {
@@ -1148,7 +1396,47 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         }
         // #{ key:value, .. }
         #[cfg(not(feature = "no_object"))]
-        Expr::Map(x, ..) => x.0.iter_mut().for_each(|(.., expr)| optimize_expr(expr, state, false)),
+        Expr::Map(x, ..) => {
+            x.0.iter_mut().for_each(|(.., expr)| optimize_expr(expr, state, false));
+
+            // #{ .., ...#{ key:constant, .. }, .. } -> #{ .., key:constant, .. }
+            let (entries, template) = &mut **x;
+            if expand_constant_map_spreads(entries, template) {
+                state.set_dirty();
+            }
+        }
+        // Struct { field:constant, .. }
+        #[cfg(not(feature = "no_object"))]
+        Expr::Struct(..) if expr.is_constant() => {
+            state.set_dirty();
+            *expr = Expr::DynamicConstant(expr.get_literal_value(None).unwrap().into(), expr.position());
+        }
+        // Struct { field:value, .. }
+        #[cfg(not(feature = "no_object"))]
+        Expr::Struct(x, ..) => x.0.iter_mut().for_each(|(.., expr)| optimize_expr(expr, state, false)),
+        // [ value for name in iterable if condition ] | #{ key: value for name in iterable if condition }
+        Expr::Comprehension(x, ..) => {
+            optimize_expr(&mut x.iterable, state, false);
+
+            // The loop variables shadow any constants with the same names
+            let orig_variables_len = state.variables.len();
+            if let Some(ref counter) = x.counter {
+                state.push_var(counter.name.clone(), None);
+            }
+            state.push_var(x.var.name.clone(), None);
+
+            if let Some(ref mut condition) = x.condition {
+                optimize_expr(condition, state, false);
+            }
+            if let Some(ref mut key) = x.key {
+                optimize_expr(key, state, false);
+            }
+            optimize_expr(&mut x.value, state, false);
+
+            state.rewind_var(orig_variables_len);
+        }
+        // ...items
+        Expr::Spread(x, ..) => optimize_expr(x, state, false),
         // lhs && rhs
         Expr::And(x, ..) => {
             let mut is_false = None;
@@ -1262,6 +1550,16 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
             }
         }
 
+        // id(.., ...[ constant, .. ], ..) -> id(.., constant, ..)
+        Expr::FnCall(x, ..) if x.has_spread_args() => {
+            x.args.iter_mut().for_each(|arg_expr| optimize_expr(arg_expr, state, false));
+
+            if expand_constant_spreads(&mut x.args) {
+                x.recalc_hashes();
+                state.set_dirty();
+            }
+        }
+
         // nnn::id(args ..) -> optimize function call arguments
         #[cfg(not(feature = "no_module"))]
         Expr::FnCall(x, ..) if x.is_qualified() => x.args.iter_mut().for_each(|arg_expr| {
@@ -1316,7 +1614,7 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
             match x.name.as_str() {
                 KEYWORD_TYPE_OF if arg_values.len() == 1 => {
                     state.set_dirty();
-                    let typ = state.engine.map_type_name(arg_values[0].type_name()).into();
+                    let typ = state.engine.map_value_type_name(&arg_values[0]).as_ref().into();
                     *expr = Expr::from_dynamic(typ, *pos);
                     return;
                 }
@@ -1326,9 +1624,9 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
                     *expr = Expr::from_dynamic(Dynamic::FALSE, *pos);
                     return;
                 }
-                // Overloaded operators can override built-in.
-                _ if x.args.len() == 2 && x.is_operator_call() && (state.engine.fast_operators() || !state.engine.has_native_fn_override(x.hashes.native(), &arg_types)) => {
-                    if let Some((f, ctx)) = get_builtin_binary_op_fn(x.op_token.as_ref().unwrap(), &arg_values[0], &arg_values[1]) {
+                // Overloaded operators (native or script-defined) can override built-in.
+                _ if x.args.len() == 2 && x.is_operator_call() && !state.has_script_fn(x, arg_values) && (state.engine.fast_operators() || !state.engine.has_native_fn_override(x.hashes.native(), &arg_types)) => {
+                    if let Some((f, ctx)) = get_builtin_binary_op_fn(x.op_token.as_ref().unwrap(), &arg_values[0], &arg_values[1], state.engine) {
                         let context = ctx.then(|| (state.engine, x.name.as_str(), None, &state.global, *pos).into());
                         let (first, second) = arg_values.split_first_mut().unwrap();
 
@@ -1354,16 +1652,12 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         Expr::FnCall(x, pos) if state.optimization_level == OptimizationLevel::Full // full optimizations
                                 && x.constant_args() // all arguments are constants
         => {
-            // First search for script-defined functions (can override built-in)
-            let _has_script_fn = false;
-            #[cfg(not(feature = "no_function"))]
-            let _has_script_fn = !x.hashes.is_native_only() && state.global.lib.iter().find_map(|m| m.get_script_fn(&x.name, x.args.len())).is_some();
-
-            if !_has_script_fn {
-                let arg_values = &mut x.args.iter().map(|a| a.get_literal_value(None)).collect::<Option<FnArgsVec<_>>>().unwrap();
+            let arg_values = &mut x.args.iter().map(|a| a.get_literal_value(None)).collect::<Option<FnArgsVec<_>>>().unwrap();
 
+            // First search for script-defined functions (can override built-in)
+            if !state.has_script_fn(x, arg_values) {
                 let result = match x.name.as_str() {
-                    KEYWORD_TYPE_OF if arg_values.len() == 1 => Some(state.engine.map_type_name(arg_values[0].type_name()).into()),
+                    KEYWORD_TYPE_OF if arg_values.len() == 1 => Some(state.engine.map_value_type_name(&arg_values[0]).as_ref().into()),
                     #[cfg(not(feature = "no_closure"))]
                     crate::engine::KEYWORD_IS_SHARED if arg_values.len() == 1 => Some(Dynamic::FALSE),
                     _ => state.call_fn_with_const_args(&x.name, x.op_token.as_ref(), arg_values)
@@ -1517,6 +1811,9 @@ impl Engine {
                 let statements = fn_def.body.take_statements();
                 *fn_def.body.statements_mut() =
                     self.optimize_top_level(statements, scope, lib2, optimization_level);
+                if fn_def.is_generator {
+                    fn_def.body.cache_yield();
+                }
                 fn_def.into()
             }))
             .into()
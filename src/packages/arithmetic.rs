@@ -1,5 +1,7 @@
 use crate::plugin::*;
-use crate::{def_package, Position, RhaiError, RhaiResultOf, ERR, INT};
+#[cfg(not(feature = "unchecked"))]
+use crate::Overflow;
+use crate::{def_package, Position, RhaiError, RhaiResultOf, Tuple, ERR, INT};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -20,68 +22,113 @@ macro_rules! gen_arithmetic_functions {
         pub mod $root { $(pub mod $arg_type {
             use super::super::*;
 
+            /// Arithmetic operators under a particular [`Overflow`] mode.
+            #[cfg(not(feature = "unchecked"))]
+            pub mod with_overflow {
+                use super::*;
+                use std::convert::TryFrom;
+
+                pub fn add(mode: Overflow, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    match mode {
+                        Overflow::Checked => x.checked_add(y).ok_or_else(|| make_err(format!("Addition overflow: {x} + {y}"))),
+                        Overflow::Wrapping => Ok(x.wrapping_add(y)),
+                        Overflow::Saturating => Ok(x.saturating_add(y)),
+                    }
+                }
+                pub fn subtract(mode: Overflow, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    match mode {
+                        Overflow::Checked => x.checked_sub(y).ok_or_else(|| make_err(format!("Subtraction overflow: {x} - {y}"))),
+                        Overflow::Wrapping => Ok(x.wrapping_sub(y)),
+                        Overflow::Saturating => Ok(x.saturating_sub(y)),
+                    }
+                }
+                pub fn multiply(mode: Overflow, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    match mode {
+                        Overflow::Checked => x.checked_mul(y).ok_or_else(|| make_err(format!("Multiplication overflow: {x} * {y}"))),
+                        Overflow::Wrapping => Ok(x.wrapping_mul(y)),
+                        Overflow::Saturating => Ok(x.saturating_mul(y)),
+                    }
+                }
+                pub fn divide(mode: Overflow, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    // Detect division by zero
+                    if y == 0 {
+                        return Err(make_err(format!("Division by zero: {x} / {y}")));
+                    }
+                    match mode {
+                        Overflow::Checked => x.checked_div(y).ok_or_else(|| make_err(format!("Division overflow: {x} / {y}"))),
+                        Overflow::Wrapping => Ok(x.wrapping_div(y)),
+                        Overflow::Saturating => Ok(x.saturating_div(y)),
+                    }
+                }
+                pub fn modulo(mode: Overflow, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    match mode {
+                        Overflow::Checked => x.checked_rem(y).ok_or_else(|| make_err(format!("Modulo division by zero or overflow: {x} % {y}"))),
+                        // The only overflowing case is `MIN % -1`, which is zero in both modes
+                        Overflow::Wrapping | Overflow::Saturating if y != 0 => Ok(x.wrapping_rem(y)),
+                        Overflow::Wrapping | Overflow::Saturating => Err(make_err(format!("Modulo division by zero: {x} % {y}"))),
+                    }
+                }
+                pub fn power(mode: Overflow, x: $arg_type, y: INT) -> RhaiResultOf<$arg_type> {
+                    if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
+                        return Err(make_err(format!("Exponential overflow: {x} ** {y}")));
+                    }
+                    if y < 0 {
+                        return Err(make_err(format!("Integer raised to a negative power: {x} ** {y}")));
+                    }
+                    let y = u32::try_from(y).unwrap();
+                    match mode {
+                        Overflow::Checked => x.checked_pow(y).ok_or_else(|| make_err(format!("Exponential overflow: {x} ** {y}"))),
+                        Overflow::Wrapping => Ok(x.wrapping_pow(y)),
+                        Overflow::Saturating => Ok(x.saturating_pow(y)),
+                    }
+                }
+            }
+
             #[export_module]
             pub mod functions {
                 use std::convert::TryFrom;
 
                 #[rhai_fn(name = "+", return_raw)]
-                pub fn add(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_add(y).ok_or_else(|| make_err(format!("Addition overflow: {x} + {y}")))
-                    } else {
-                        Ok(x + y)
-                    }
+                pub fn add(_ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return super::with_overflow::add(_ctx.engine().integer_overflow(), x, y);
+                    #[cfg(feature = "unchecked")]
+                    return Ok(x + y);
                 }
                 #[rhai_fn(name = "-", return_raw)]
-                pub fn subtract(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_sub(y).ok_or_else(|| make_err(format!("Subtraction overflow: {x} - {y}")))
-                    } else {
-                        Ok(x - y)
-                    }
+                pub fn subtract(_ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return super::with_overflow::subtract(_ctx.engine().integer_overflow(), x, y);
+                    #[cfg(feature = "unchecked")]
+                    return Ok(x - y);
                 }
                 #[rhai_fn(name = "*", return_raw)]
-                pub fn multiply(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_mul(y).ok_or_else(|| make_err(format!("Multiplication overflow: {x} * {y}")))
-                    } else {
-                        Ok(x * y)
-                    }
+                pub fn multiply(_ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return super::with_overflow::multiply(_ctx.engine().integer_overflow(), x, y);
+                    #[cfg(feature = "unchecked")]
+                    return Ok(x * y);
                 }
                 #[rhai_fn(name = "/", return_raw)]
-                pub fn divide(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        // Detect division by zero
-                        if y == 0 {
-                            Err(make_err(format!("Division by zero: {x} / {y}")))
-                        } else {
-                            x.checked_div(y).ok_or_else(|| make_err(format!("Division overflow: {x} / {y}")))
-                        }
-                    } else {
-                        Ok(x / y)
-                    }
+                pub fn divide(_ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return super::with_overflow::divide(_ctx.engine().integer_overflow(), x, y);
+                    #[cfg(feature = "unchecked")]
+                    return Ok(x / y);
                 }
                 #[rhai_fn(name = "%", return_raw)]
-                pub fn modulo(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_rem(y).ok_or_else(|| make_err(format!("Modulo division by zero or overflow: {x} % {y}")))
-                    } else {
-                        Ok(x % y)
-                    }
+                pub fn modulo(_ctx: NativeCallContext, x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return super::with_overflow::modulo(_ctx.engine().integer_overflow(), x, y);
+                    #[cfg(feature = "unchecked")]
+                    return Ok(x % y);
                 }
                 #[rhai_fn(name = "**", return_raw)]
-                pub fn power(x: $arg_type, y: INT) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
-                            Err(make_err(format!("Exponential overflow: {x} ** {y}")))
-                        } else if y < 0 {
-                            Err(make_err(format!("Integer raised to a negative power: {x} ** {y}")))
-                        } else {
-                            x.checked_pow(u32::try_from(y).unwrap()).ok_or_else(|| make_err(format!("Exponential overflow: {x} ** {y}")))
-                        }
-                    } else {
-                        Ok(x.pow(u32::try_from(y).unwrap()))
-                    }
+                pub fn power(_ctx: NativeCallContext, x: $arg_type, y: INT) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return super::with_overflow::power(_ctx.engine().integer_overflow(), x, y);
+                    #[cfg(feature = "unchecked")]
+                    return Ok(x.pow(u32::try_from(y).unwrap()));
                 }
 
                 #[rhai_fn(name = "<<")]
@@ -157,12 +204,15 @@ macro_rules! gen_signed_functions {
             #[export_module]
             pub mod functions {
                 #[rhai_fn(name = "-", return_raw)]
-                pub fn neg(x: $arg_type) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_neg().ok_or_else(|| make_err(format!("Negation overflow: -{x}")))
-                    } else {
-                        Ok(-x)
-                    }
+                pub fn neg(_ctx: NativeCallContext, x: $arg_type) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return match _ctx.engine().integer_overflow() {
+                        Overflow::Checked => x.checked_neg().ok_or_else(|| make_err(format!("Negation overflow: -{x}"))),
+                        Overflow::Wrapping => Ok(x.wrapping_neg()),
+                        Overflow::Saturating => Ok(x.saturating_neg()),
+                    };
+                    #[cfg(feature = "unchecked")]
+                    return Ok(-x);
                 }
                 #[rhai_fn(name = "+")]
                 pub const fn plus(x: $arg_type) -> $arg_type {
@@ -170,12 +220,42 @@ macro_rules! gen_signed_functions {
                 }
                 /// Return the absolute value of the number.
                 #[rhai_fn(return_raw)]
-                pub fn abs(x: $arg_type) -> RhaiResultOf<$arg_type> {
-                    if cfg!(not(feature = "unchecked")) {
-                        x.checked_abs().ok_or_else(|| make_err(format!("Negation overflow: -{x}")))
-                    } else {
-                        Ok(x.abs())
-                    }
+                pub fn abs(_ctx: NativeCallContext, x: $arg_type) -> RhaiResultOf<$arg_type> {
+                    #[cfg(not(feature = "unchecked"))]
+                    return match _ctx.engine().integer_overflow() {
+                        Overflow::Checked => x.checked_abs().ok_or_else(|| make_err(format!("Negation overflow: -{x}"))),
+                        Overflow::Wrapping => Ok(x.wrapping_abs()),
+                        Overflow::Saturating => Ok(x.saturating_abs()),
+                    };
+                    #[cfg(feature = "unchecked")]
+                    return Ok(x.abs());
+                }
+                /// Negate the number, wrapping around at the boundary of the type.
+                pub const fn wrapping_neg(x: $arg_type) -> $arg_type {
+                    x.wrapping_neg()
+                }
+                /// Negate the number, saturating at the numeric bounds of the type.
+                pub const fn saturating_neg(x: $arg_type) -> $arg_type {
+                    x.saturating_neg()
+                }
+                /// Negate the number, returning a tuple of the wrapped result and whether it overflowed.
+                pub fn overflowing_neg(x: $arg_type) -> Tuple {
+                    let (value, overflowed) = x.overflowing_neg();
+                    vec![Dynamic::from(value), overflowed.into()].into()
+                }
+                /// Return the absolute value of the number, wrapping around at the boundary of the type.
+                pub const fn wrapping_abs(x: $arg_type) -> $arg_type {
+                    x.wrapping_abs()
+                }
+                /// Return the absolute value of the number, saturating at the numeric bounds of the type.
+                pub const fn saturating_abs(x: $arg_type) -> $arg_type {
+                    x.saturating_abs()
+                }
+                /// Return the absolute value of the number as a tuple of the wrapped result and
+                /// whether it overflowed.
+                pub fn overflowing_abs(x: $arg_type) -> Tuple {
+                    let (value, overflowed) = x.overflowing_abs();
+                    vec![Dynamic::from(value), overflowed.into()].into()
                 }
                 /// Return the sign (as an integer) of the number according to the following:
                 ///
@@ -190,6 +270,169 @@ macro_rules! gen_signed_functions {
     }
 }
 
+macro_rules! gen_overflow_functions {
+    ($root:ident => $($arg_type:ident),+) => {
+        #[allow(non_snake_case)]
+        pub mod $root { $(pub mod $arg_type {
+            use super::super::*;
+
+            #[export_module]
+            pub mod functions {
+                use std::convert::TryFrom;
+
+                /// Convert a shift amount or exponent into `u32`, raising an error if it is out of range.
+                #[rhai_fn(skip)]
+                pub fn to_u32(x: $arg_type, op: &str, y: INT) -> RhaiResultOf<u32> {
+                    u32::try_from(y).map_err(|_| make_err(format!("Invalid operand: {x} {op} {y}")))
+                }
+                #[rhai_fn(skip)]
+                pub fn check_divisor(x: $arg_type, op: &str, y: $arg_type) -> RhaiResultOf<()> {
+                    if y == 0 {
+                        Err(make_err(format!("Division by zero: {x} {op} {y}")))
+                    } else {
+                        Ok(())
+                    }
+                }
+                #[rhai_fn(skip)]
+                pub fn to_tuple((value, overflowed): ($arg_type, bool)) -> Tuple {
+                    vec![Dynamic::from(value), overflowed.into()].into()
+                }
+
+                /// Add two numbers, wrapping around at the boundary of the type.
+                pub const fn wrapping_add(x: $arg_type, y: $arg_type) -> $arg_type {
+                    x.wrapping_add(y)
+                }
+                /// Subtract two numbers, wrapping around at the boundary of the type.
+                pub const fn wrapping_sub(x: $arg_type, y: $arg_type) -> $arg_type {
+                    x.wrapping_sub(y)
+                }
+                /// Multiply two numbers, wrapping around at the boundary of the type.
+                pub const fn wrapping_mul(x: $arg_type, y: $arg_type) -> $arg_type {
+                    x.wrapping_mul(y)
+                }
+                /// Divide two numbers, wrapping around at the boundary of the type.
+                ///
+                /// Division by zero is an error.
+                #[rhai_fn(return_raw)]
+                pub fn wrapping_div(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    check_divisor(x, "/", y)?;
+                    Ok(x.wrapping_div(y))
+                }
+                /// Return the remainder of dividing two numbers, wrapping around at the boundary of the type.
+                ///
+                /// Division by zero is an error.
+                #[rhai_fn(return_raw)]
+                pub fn wrapping_rem(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    check_divisor(x, "%", y)?;
+                    Ok(x.wrapping_rem(y))
+                }
+                /// Raise a number to a power, wrapping around at the boundary of the type.
+                ///
+                /// A negative exponent is an error.
+                #[rhai_fn(return_raw)]
+                pub fn wrapping_pow(x: $arg_type, y: INT) -> RhaiResultOf<$arg_type> {
+                    Ok(x.wrapping_pow(to_u32(x, "**", y)?))
+                }
+                /// Shift a number left by a number of bits, masking the shift amount to the bit width of the type.
+                ///
+                /// A negative shift amount is an error.
+                #[rhai_fn(return_raw)]
+                pub fn wrapping_shl(x: $arg_type, y: INT) -> RhaiResultOf<$arg_type> {
+                    Ok(x.wrapping_shl(to_u32(x, "<<", y)?))
+                }
+                /// Shift a number right by a number of bits, masking the shift amount to the bit width of the type.
+                ///
+                /// A negative shift amount is an error.
+                #[rhai_fn(return_raw)]
+                pub fn wrapping_shr(x: $arg_type, y: INT) -> RhaiResultOf<$arg_type> {
+                    Ok(x.wrapping_shr(to_u32(x, ">>", y)?))
+                }
+
+                /// Add two numbers, saturating at the numeric bounds of the type.
+                pub const fn saturating_add(x: $arg_type, y: $arg_type) -> $arg_type {
+                    x.saturating_add(y)
+                }
+                /// Subtract two numbers, saturating at the numeric bounds of the type.
+                pub const fn saturating_sub(x: $arg_type, y: $arg_type) -> $arg_type {
+                    x.saturating_sub(y)
+                }
+                /// Multiply two numbers, saturating at the numeric bounds of the type.
+                pub const fn saturating_mul(x: $arg_type, y: $arg_type) -> $arg_type {
+                    x.saturating_mul(y)
+                }
+                /// Divide two numbers, saturating at the numeric bounds of the type.
+                ///
+                /// Division by zero is an error.
+                #[rhai_fn(return_raw)]
+                pub fn saturating_div(x: $arg_type, y: $arg_type) -> RhaiResultOf<$arg_type> {
+                    check_divisor(x, "/", y)?;
+                    Ok(x.saturating_div(y))
+                }
+                /// Raise a number to a power, saturating at the numeric bounds of the type.
+                ///
+                /// A negative exponent is an error.
+                #[rhai_fn(return_raw)]
+                pub fn saturating_pow(x: $arg_type, y: INT) -> RhaiResultOf<$arg_type> {
+                    Ok(x.saturating_pow(to_u32(x, "**", y)?))
+                }
+
+                /// Add two numbers, returning a tuple of the wrapped result and whether it overflowed.
+                pub fn overflowing_add(x: $arg_type, y: $arg_type) -> Tuple {
+                    to_tuple(x.overflowing_add(y))
+                }
+                /// Subtract two numbers, returning a tuple of the wrapped result and whether it overflowed.
+                pub fn overflowing_sub(x: $arg_type, y: $arg_type) -> Tuple {
+                    to_tuple(x.overflowing_sub(y))
+                }
+                /// Multiply two numbers, returning a tuple of the wrapped result and whether it overflowed.
+                pub fn overflowing_mul(x: $arg_type, y: $arg_type) -> Tuple {
+                    to_tuple(x.overflowing_mul(y))
+                }
+                /// Divide two numbers, returning a tuple of the wrapped result and whether it overflowed.
+                ///
+                /// Division by zero is an error.
+                #[rhai_fn(return_raw)]
+                pub fn overflowing_div(x: $arg_type, y: $arg_type) -> RhaiResultOf<Tuple> {
+                    check_divisor(x, "/", y)?;
+                    Ok(to_tuple(x.overflowing_div(y)))
+                }
+                /// Return the remainder of dividing two numbers, as a tuple of the wrapped result and
+                /// whether it overflowed.
+                ///
+                /// Division by zero is an error.
+                #[rhai_fn(return_raw)]
+                pub fn overflowing_rem(x: $arg_type, y: $arg_type) -> RhaiResultOf<Tuple> {
+                    check_divisor(x, "%", y)?;
+                    Ok(to_tuple(x.overflowing_rem(y)))
+                }
+                /// Raise a number to a power, returning a tuple of the wrapped result and whether it overflowed.
+                ///
+                /// A negative exponent is an error.
+                #[rhai_fn(return_raw)]
+                pub fn overflowing_pow(x: $arg_type, y: INT) -> RhaiResultOf<Tuple> {
+                    Ok(to_tuple(x.overflowing_pow(to_u32(x, "**", y)?)))
+                }
+                /// Shift a number left by a number of bits, returning a tuple of the result and whether
+                /// the shift amount was at least the bit width of the type (in which case it is masked).
+                ///
+                /// A negative shift amount is an error.
+                #[rhai_fn(return_raw)]
+                pub fn overflowing_shl(x: $arg_type, y: INT) -> RhaiResultOf<Tuple> {
+                    Ok(to_tuple(x.overflowing_shl(to_u32(x, "<<", y)?)))
+                }
+                /// Shift a number right by a number of bits, returning a tuple of the result and whether
+                /// the shift amount was at least the bit width of the type (in which case it is masked).
+                ///
+                /// A negative shift amount is an error.
+                #[rhai_fn(return_raw)]
+                pub fn overflowing_shr(x: $arg_type, y: INT) -> RhaiResultOf<Tuple> {
+                    Ok(to_tuple(x.overflowing_shr(to_u32(x, ">>", y)?)))
+                }
+            }
+        })* }
+    }
+}
+
 macro_rules! reg_functions {
     ($mod_name:ident += $root:ident ; $($arg_type:ident),+ ) => { $(
         combine_with_exported_module!($mod_name, "arithmetic", $root::$arg_type::functions);
@@ -207,6 +450,12 @@ def_package! {
         // Avoid dead code warnings
         #[cfg(not(feature = "unchecked"))]
         {
+            let _ = arith_basic::INT::functions::add;
+            let _ = arith_basic::INT::functions::subtract;
+            let _ = arith_basic::INT::functions::multiply;
+            let _ = arith_basic::INT::functions::divide;
+            let _ = arith_basic::INT::functions::modulo;
+            let _ = arith_basic::INT::functions::power;
             let _ = arith_basic::INT::functions::is_zero;
             let _ = arith_basic::INT::functions::is_odd;
             let _ = arith_basic::INT::functions::is_even;
@@ -214,6 +463,7 @@ def_package! {
 
         // Register other arithmetic functions for integers
         reg_functions!(lib += signed_basic; INT);
+        reg_functions!(lib += overflow_basic; INT);
 
         // Basic arithmetic for other integer types
         #[cfg(not(feature = "only_i32"))]
@@ -223,6 +473,8 @@ def_package! {
             reg_functions!(lib += arith_numbers; i8, u8, i16, u16, i32, u32, u64);
             gen_signed_functions!(signed_numbers => i8, i16, i32);
             reg_functions!(lib += signed_numbers; i8, i16, i32);
+            gen_overflow_functions!(overflow_numbers => i8, u8, i16, u16, i32, u32, u64);
+            reg_functions!(lib += overflow_numbers; i8, u8, i16, u16, i32, u32, u64);
 
             #[cfg(not(target_family = "wasm"))]
             {
@@ -230,6 +482,8 @@ def_package! {
                 reg_functions!(lib += arith_numbers; i128, u128);
                 gen_signed_functions!(signed_numbers => i128);
                 reg_functions!(lib += signed_numbers; i128);
+                gen_overflow_functions!(overflow_numbers => i128, u128);
+                reg_functions!(lib += overflow_numbers; i128, u128);
             }
         }
 
@@ -243,6 +497,10 @@ def_package! {
         // Decimal functions
         #[cfg(feature = "decimal")]
         combine_with_exported_module!(lib, "decimal", decimal_functions);
+
+        // BigInt functions
+        #[cfg(feature = "bigint")]
+        combine_with_exported_module!(lib, "bigint", bigint_functions);
     }
 }
 
@@ -251,6 +509,8 @@ gen_arithmetic_functions!(arith_basic => INT);
 
 gen_signed_functions!(signed_basic => INT);
 
+gen_overflow_functions!(overflow_basic => INT);
+
 /// These integer functions are not part of the built-in set so they must be registered.
 #[export_module]
 mod non_builtin_int_functions {
@@ -573,3 +833,197 @@ pub mod decimal_functions {
         x.is_zero()
     }
 }
+
+#[cfg(feature = "bigint")]
+#[export_module]
+pub mod bigint_functions {
+    use num_bigint::{BigInt, Sign};
+    use num_traits::Signed;
+
+    #[rhai_fn(name = "-")]
+    pub fn neg(x: BigInt) -> BigInt {
+        -x
+    }
+    #[rhai_fn(name = "+")]
+    pub fn plus(x: BigInt) -> BigInt {
+        x
+    }
+    /// Return the absolute value of the big integer.
+    pub fn abs(x: BigInt) -> BigInt {
+        x.abs()
+    }
+    /// Return the sign (as an integer) of the big integer according to the following:
+    ///
+    /// * `0` if the number is zero
+    /// * `1` if the number is positive
+    /// * `-1` if the number is negative
+    pub fn sign(x: BigInt) -> INT {
+        match x.sign() {
+            Sign::NoSign => 0,
+            Sign::Plus => 1,
+            Sign::Minus => -1,
+        }
+    }
+    /// Return true if the big integer is zero.
+    #[rhai_fn(get = "is_zero", name = "is_zero")]
+    pub fn is_zero(x: BigInt) -> bool {
+        x.sign() == Sign::NoSign
+    }
+    /// Return true if the big integer is odd.
+    #[rhai_fn(get = "is_odd", name = "is_odd")]
+    pub fn is_odd(x: BigInt) -> bool {
+        x.bit(0)
+    }
+    /// Return true if the big integer is even.
+    #[rhai_fn(get = "is_even", name = "is_even")]
+    pub fn is_even(x: BigInt) -> bool {
+        !x.bit(0)
+    }
+    /// Return the number of bits needed to represent the magnitude of the big integer.
+    #[rhai_fn(get = "bits", name = "bits")]
+    pub fn bits(x: BigInt) -> INT {
+        x.bits() as INT
+    }
+}
+
+/// Built-in operators for [`BigInt`][num_bigint::BigInt].
+///
+/// Integer operands are widened to [`BigInt`][num_bigint::BigInt].
+#[cfg(feature = "bigint")]
+pub mod bigint_builtin {
+    use super::make_err;
+    use crate::types::dynamic::Union;
+    use crate::{Dynamic, NativeCallContext, RhaiResultOf};
+    use num_bigint::{BigInt, Sign};
+    use num_traits::ToPrimitive;
+    use std::borrow::Cow;
+    #[cfg(feature = "no_std")]
+    use std::prelude::v1::*;
+
+    /// Get an operand as a [`BigInt`], widening an integer.
+    #[inline]
+    #[must_use]
+    pub fn arg(value: &Dynamic) -> Cow<'_, BigInt> {
+        match value.0 {
+            Union::BigInt(ref n, ..) => Cow::Borrowed(n),
+            Union::Int(n, ..) => Cow::Owned(n.into()),
+            _ => Cow::Owned(
+                value
+                    .as_int()
+                    .map_or_else(|_| value.as_bigint().expect("BigInt or INT"), Into::into),
+            ),
+        }
+    }
+
+    /// Raise an error if a big integer with `bits` bits has more decimal digits than the
+    /// maximum string size allows.
+    ///
+    /// Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    fn check_bits(ctx: Option<&NativeCallContext>, bits: u64) -> RhaiResultOf<()> {
+        let max = ctx.map_or(0, |ctx| ctx.engine().max_string_size()) as u64;
+
+        // An n-bit number has floor(n * log10(2)) + 1 decimal digits
+        if max > 0 && bits.saturating_mul(30_103) / 100_000 >= max {
+            return Err(crate::ERR::ErrorDataTooLarge(
+                "Size of big integer".to_string(),
+                crate::Position::NONE,
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+    /// Check the size of a result.
+    #[inline]
+    #[allow(unused_variables)]
+    fn checked(ctx: Option<&NativeCallContext>, n: BigInt) -> RhaiResultOf<BigInt> {
+        #[cfg(not(feature = "unchecked"))]
+        check_bits(ctx, n.bits())?;
+        Ok(n)
+    }
+
+    pub fn add(ctx: Option<&NativeCallContext>, x: &BigInt, y: &BigInt) -> RhaiResultOf<BigInt> {
+        checked(ctx, x + y)
+    }
+    pub fn subtract(
+        ctx: Option<&NativeCallContext>,
+        x: &BigInt,
+        y: &BigInt,
+    ) -> RhaiResultOf<BigInt> {
+        checked(ctx, x - y)
+    }
+    pub fn multiply(
+        ctx: Option<&NativeCallContext>,
+        x: &BigInt,
+        y: &BigInt,
+    ) -> RhaiResultOf<BigInt> {
+        // The product has at least this many bits
+        #[cfg(not(feature = "unchecked"))]
+        if x.sign() != Sign::NoSign && y.sign() != Sign::NoSign {
+            check_bits(ctx, x.bits() + y.bits() - 1)?;
+        }
+        checked(ctx, x * y)
+    }
+    pub fn divide(ctx: Option<&NativeCallContext>, x: &BigInt, y: &BigInt) -> RhaiResultOf<BigInt> {
+        if y.sign() == Sign::NoSign {
+            return Err(make_err(format!("Division by zero: {x} / {y}")));
+        }
+        checked(ctx, x / y)
+    }
+    pub fn modulo(ctx: Option<&NativeCallContext>, x: &BigInt, y: &BigInt) -> RhaiResultOf<BigInt> {
+        if y.sign() == Sign::NoSign {
+            return Err(make_err(format!("Modulo division by zero: {x} % {y}")));
+        }
+        checked(ctx, x % y)
+    }
+    pub fn power(ctx: Option<&NativeCallContext>, x: &BigInt, y: &BigInt) -> RhaiResultOf<BigInt> {
+        if y.sign() == Sign::Minus {
+            return Err(make_err(format!(
+                "Integer raised to a negative power: {x} ** {y}"
+            )));
+        }
+        let Some(y) = y.to_u32() else {
+            return Err(make_err(format!("Exponential overflow: {x} ** {y}")));
+        };
+        // The result has at least this many bits
+        #[cfg(not(feature = "unchecked"))]
+        if x.bits() > 1 {
+            check_bits(ctx, (x.bits() - 1).saturating_mul(y as u64) + 1)?;
+        }
+        checked(ctx, x.pow(y))
+    }
+    pub fn shift_left(
+        ctx: Option<&NativeCallContext>,
+        x: &BigInt,
+        y: &BigInt,
+    ) -> RhaiResultOf<BigInt> {
+        if y.sign() == Sign::Minus {
+            return shift_right(ctx, x, &-y);
+        }
+        if x.sign() == Sign::NoSign {
+            return Ok(BigInt::default());
+        }
+        let Some(y) = y.to_usize() else {
+            return Err(make_err(format!("Left-shift overflow: {x} << {y}")));
+        };
+        #[cfg(not(feature = "unchecked"))]
+        check_bits(ctx, x.bits().saturating_add(y as u64))?;
+        checked(ctx, x << y)
+    }
+    pub fn shift_right(
+        ctx: Option<&NativeCallContext>,
+        x: &BigInt,
+        y: &BigInt,
+    ) -> RhaiResultOf<BigInt> {
+        if y.sign() == Sign::Minus {
+            return shift_left(ctx, x, &-y);
+        }
+        // Shifting right by at least as many bits as the number has leaves only the sign
+        match y.to_u64() {
+            Some(y) if y < x.bits() => Ok(x >> y),
+            _ if x.sign() == Sign::Minus => Ok(BigInt::from(-1)),
+            _ => Ok(BigInt::default()),
+        }
+    }
+}
@@ -1615,6 +1615,7 @@ pub mod array_functions {
     /// * integer numbers
     /// * floating-point numbers
     /// * decimal numbers
+    /// * big integers
     /// * characters
     /// * strings
     /// * booleans
@@ -1687,6 +1688,15 @@ pub mod array_functions {
             });
             return Ok(());
         }
+        #[cfg(feature = "bigint")]
+        if type_id == TypeId::of::<num_bigint::BigInt>() {
+            array.sort_by(|a, b| {
+                let a = &*a.read_lock::<num_bigint::BigInt>().unwrap();
+                let b = &*b.read_lock::<num_bigint::BigInt>().unwrap();
+                a.cmp(b)
+            });
+            return Ok(());
+        }
         if type_id == TypeId::of::<bool>() {
             array.sort_by(|a, b| {
                 let a = a.as_bool().unwrap();
@@ -1714,6 +1724,7 @@ pub mod array_functions {
     /// * integer numbers
     /// * floating-point numbers
     /// * decimal numbers
+    /// * big integers
     /// * characters
     /// * strings
     /// * booleans
@@ -1806,6 +1817,15 @@ pub mod array_functions {
             });
             return Ok(());
         }
+        #[cfg(feature = "bigint")]
+        if type_id == TypeId::of::<num_bigint::BigInt>() {
+            array.sort_by(|a, b| {
+                let a = &*a.read_lock::<num_bigint::BigInt>().unwrap();
+                let b = &*b.read_lock::<num_bigint::BigInt>().unwrap();
+                b.cmp(a)
+            });
+            return Ok(());
+        }
         if type_id == TypeId::of::<bool>() {
             array.sort_by(|a, b| {
                 let a = a.as_bool().unwrap();
@@ -1886,6 +1906,7 @@ pub mod array_functions {
     /// * integer numbers
     /// * floating-point numbers
     /// * decimal numbers
+    /// * big integers
     /// * characters
     /// * strings
     /// * booleans
@@ -1915,6 +1936,7 @@ pub mod array_functions {
     /// * integer numbers
     /// * floating-point numbers
     /// * decimal numbers
+    /// * big integers
     /// * characters
     /// * strings
     /// * booleans
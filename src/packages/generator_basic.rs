@@ -0,0 +1,139 @@
+#![cfg(not(feature = "no_function"))]
+
+use crate::plugin::*;
+use crate::{def_package, Dynamic, Generator, NativeCallContext, RhaiResult};
+#[cfg(not(feature = "no_index"))]
+use crate::{Array, RhaiResultOf, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+def_package! {
+    /// Package of basic generator utilities.
+    pub BasicGeneratorPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "generator", generator_functions);
+    }
+}
+
+/// Check that an array collected from a generator is not over the maximum size limit.
+#[cfg(not(feature = "no_index"))]
+#[inline]
+fn check_array_size(_ctx: &NativeCallContext, _array: &Array) -> RhaiResultOf<()> {
+    #[cfg(not(feature = "unchecked"))]
+    if _ctx.engine().max_array_size() > 0 && _array.len() > _ctx.engine().max_array_size() {
+        return Err(crate::ERR::ErrorDataTooLarge(
+            "Size of array".to_string(),
+            _ctx.call_position(),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+#[export_module]
+mod generator_functions {
+    /// Resume the generator and return the next item.
+    ///
+    /// If the generator has completed, `()` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// fn count() { yield 1; yield 2; }
+    ///
+    /// let g = count();
+    ///
+    /// print(g.next());    // prints 1
+    /// print(g.next());    // prints 2
+    /// print(g.next());    // prints ()
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn next(ctx: NativeCallContext, generator: &mut Generator) -> RhaiResult {
+        let mut value = Dynamic::UNIT;
+
+        ctx.resume_generator(generator, |item| {
+            value = item;
+            Ok(false)
+        })?;
+
+        Ok(value)
+    }
+    /// Return `true` if the generator has completed and produces no more items.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// fn count() { yield 1; }
+    ///
+    /// let g = count();
+    ///
+    /// g.next();
+    /// print(g.is_done);   // prints false
+    ///
+    /// g.next();
+    /// print(g.is_done);   // prints true
+    /// ```
+    #[rhai_fn(name = "is_done", get = "is_done", pure)]
+    pub fn is_done(generator: &mut Generator) -> bool {
+        generator.is_done()
+    }
+    /// Run the generator to completion and return all remaining items as an array.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// fn squares(n) { for x in 1..=n { yield x * x; } }
+    ///
+    /// print(squares(4).to_array());   // prints [1, 4, 9, 16]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn to_array(ctx: NativeCallContext, generator: &mut Generator) -> RhaiResultOf<Array> {
+        let mut array = Array::new();
+
+        ctx.resume_generator(generator, |item| {
+            array.push(item);
+            check_array_size(&ctx, &array)?;
+            Ok(true)
+        })?;
+
+        Ok(array)
+    }
+    /// Resume the generator up to `count` times and return the items as an array.
+    ///
+    /// The array is shorter than `count` if the generator completes first.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// fn naturals() { let x = 0; loop { x += 1; yield x; } }
+    ///
+    /// let g = naturals();
+    ///
+    /// print(g.take(3));   // prints [1, 2, 3]
+    /// print(g.take(2));   // prints [4, 5]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn take(
+        ctx: NativeCallContext,
+        generator: &mut Generator,
+        count: INT,
+    ) -> RhaiResultOf<Array> {
+        let mut array = Array::new();
+
+        if count <= 0 {
+            return Ok(array);
+        }
+
+        ctx.resume_generator(generator, |item| {
+            array.push(item);
+            check_array_size(&ctx, &array)?;
+            Ok((array.len() as INT) < count)
+        })?;
+
+        Ok(array)
+    }
+}
@@ -61,6 +61,9 @@ def_package! {
         #[cfg(feature = "decimal")]
         combine_with_exported_module!(lib, "decimal", decimal_functions);
 
+        #[cfg(feature = "bigint")]
+        combine_with_exported_module!(lib, "bigint", bigint_functions);
+
         combine_with_exported_module!(lib, "logic", logic_functions);
 
         combine_with_exported_module!(lib, "min_max", min_max_functions);
@@ -832,3 +835,63 @@ mod decimal_functions {
         }
     }
 }
+
+#[cfg(feature = "bigint")]
+#[export_module]
+mod bigint_functions {
+    use crate::INT;
+    use num_bigint::BigInt;
+
+    #[rhai_fn(name = "max")]
+    pub fn max_bb(x: BigInt, y: BigInt) -> BigInt {
+        if x >= y {
+            x
+        } else {
+            y
+        }
+    }
+    #[rhai_fn(name = "max")]
+    pub fn max_ib(x: INT, y: BigInt) -> BigInt {
+        let x = x.into();
+        if x >= y {
+            x
+        } else {
+            y
+        }
+    }
+    #[rhai_fn(name = "max")]
+    pub fn max_bi(x: BigInt, y: INT) -> BigInt {
+        let y = y.into();
+        if x >= y {
+            x
+        } else {
+            y
+        }
+    }
+    #[rhai_fn(name = "min")]
+    pub fn min_bb(x: BigInt, y: BigInt) -> BigInt {
+        if x <= y {
+            x
+        } else {
+            y
+        }
+    }
+    #[rhai_fn(name = "min")]
+    pub fn min_ib(x: INT, y: BigInt) -> BigInt {
+        let x = x.into();
+        if x <= y {
+            x
+        } else {
+            y
+        }
+    }
+    #[rhai_fn(name = "min")]
+    pub fn min_bi(x: BigInt, y: INT) -> BigInt {
+        let y = y.into();
+        if x <= y {
+            x
+        } else {
+            y
+        }
+    }
+}
@@ -2,7 +2,10 @@
 
 use crate::engine::OP_EQUALS;
 use crate::plugin::*;
-use crate::{def_package, Dynamic, FnPtr, Map, NativeCallContext, RhaiResultOf, INT};
+use crate::{
+    def_package, Dynamic, FnPtr, ImmutableString, Map, NativeCallContext, RhaiResultOf,
+    StructValue, INT,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{convert::TryFrom, mem};
@@ -16,6 +19,7 @@ def_package! {
         lib.set_standard_lib(true);
 
         combine_with_exported_module!(lib, "map", map_functions);
+        combine_with_exported_module!(lib, "struct", struct_functions);
     }
 }
 
@@ -543,3 +547,72 @@ mod map_functions {
         return crate::format_map_as_json(map);
     }
 }
+
+#[export_module]
+mod struct_functions {
+    /// Return the name of the `struct` type of the value.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// struct Point { x, y }
+    ///
+    /// print(Point { x: 1, y: 2 }.struct_name());     // prints "Point"
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn struct_name(value: &mut StructValue) -> ImmutableString {
+        value.struct_name().into()
+    }
+    /// Return the fields of the value as an object map.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// struct Point { x, y }
+    ///
+    /// print(Point { x: 1, y: 2 }.to_map());          // prints #{"x": 1, "y": 2}
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn to_map(value: &mut StructValue) -> Map {
+        value.to_map()
+    }
+    /// Return `true` if two `struct` values are equal (i.e. same `struct` type and all fields equal).
+    ///
+    /// The operator `==` is used to compare fields and must be defined,
+    /// otherwise `false` is assumed.
+    #[rhai_fn(name = "==", return_raw, pure)]
+    pub fn equals(
+        ctx: NativeCallContext,
+        value1: &mut StructValue,
+        mut value2: StructValue,
+    ) -> RhaiResultOf<bool> {
+        if !value1.is(value2.struct_name()) || value1.len() != value2.len() {
+            return Ok(false);
+        }
+
+        for (v1, v2) in value1.values_mut().zip(value2.values_mut()) {
+            let equals = ctx
+                .call_native_fn_raw(OP_EQUALS, true, &mut [v1, v2])?
+                .as_bool()
+                .unwrap_or(false);
+
+            if !equals {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+    /// Return `true` if two `struct` values are not equal.
+    ///
+    /// The operator `==` is used to compare fields and must be defined,
+    /// otherwise `false` is assumed.
+    #[rhai_fn(name = "!=", return_raw, pure)]
+    pub fn not_equals(
+        ctx: NativeCallContext,
+        value1: &mut StructValue,
+        value2: StructValue,
+    ) -> RhaiResultOf<bool> {
+        equals(ctx, value1, value2).map(|r| !r)
+    }
+}
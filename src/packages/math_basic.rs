@@ -80,6 +80,26 @@ def_package! {
             #[cfg(not(feature = "only_i64"))]
             gen_conv_functions!(lib => to_decimal(i8, u8, i16, u16, i32, u32, i64, u64).into() -> Decimal);
         }
+
+        // BigInt functions
+        #[cfg(feature = "bigint")]
+        {
+            use num_bigint::BigInt;
+
+            combine_with_exported_module!(lib, "bigint", bigint_functions);
+
+            gen_conv_functions!(lib => to_bigint(BigInt) -> BigInt);
+            gen_conv_functions!(lib => to_bigint(INT).into() -> BigInt);
+
+            #[cfg(not(feature = "only_i32"))]
+            #[cfg(not(feature = "only_i64"))]
+            {
+                gen_conv_functions!(lib => to_bigint(i8, u8, i16, u16, i32, u32, i64, u64).into() -> BigInt);
+
+                #[cfg(not(target_family = "wasm"))]
+                gen_conv_functions!(lib => to_bigint(i128, u128).into() -> BigInt);
+            }
+        }
     }
 }
 
@@ -621,3 +641,78 @@ mod decimal_functions {
         })
     }
 }
+
+#[cfg(feature = "bigint")]
+#[export_module]
+mod bigint_functions {
+    use num_bigint::BigInt;
+    use num_traits::Num;
+
+    /// Parse a string into a big integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = parse_bigint("123456789012345678901234567890");
+    ///
+    /// print(x);       // prints 123456789012345678901234567890
+    /// ```
+    #[rhai_fn(name = "parse_bigint", return_raw)]
+    pub fn parse_bigint(string: &str) -> RhaiResultOf<BigInt> {
+        parse_bigint_radix(string, 10)
+    }
+    /// Parse a string into a big integer of the specified `radix`.
+    ///
+    /// `radix` must be between 2 and 36.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = parse_bigint("ffffffffffffffffffff", 16);
+    ///
+    /// print(x);       // prints 1208925819614629174706175
+    /// ```
+    #[rhai_fn(name = "parse_bigint", return_raw)]
+    pub fn parse_bigint_radix(string: &str, radix: INT) -> RhaiResultOf<BigInt> {
+        if !(2..=36).contains(&radix) {
+            return Err(
+                ERR::ErrorArithmetic(format!("Invalid radix: '{radix}'"), Position::NONE).into(),
+            );
+        }
+
+        BigInt::from_str_radix(string.trim(), u32::try_from(radix).unwrap()).map_err(|err| {
+            ERR::ErrorArithmetic(
+                format!("Error parsing big integer '{string}': {err}"),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+    /// Convert the big integer into an integer.
+    #[rhai_fn(return_raw)]
+    pub fn to_int(x: BigInt) -> RhaiResultOf<INT> {
+        INT::try_from(&x).map_err(|_| {
+            ERR::ErrorArithmetic(format!("Integer overflow: to_int({x})"), Position::NONE).into()
+        })
+    }
+    /// Convert the floating-point number into a big integer, discarding the fractional part.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "to_bigint", return_raw)]
+    pub fn float_to_bigint(x: FLOAT) -> RhaiResultOf<BigInt> {
+        #[allow(clippy::useless_conversion)]
+        num_traits::FromPrimitive::from_f64(f64::from(x)).ok_or_else(|| {
+            ERR::ErrorArithmetic(
+                format!("Cannot convert to BigInt: to_bigint({x})"),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+    /// Convert the big integer to floating-point.
+    ///
+    /// Numbers too large to be represented become infinity.
+    #[cfg(not(feature = "no_float"))]
+    pub fn to_float(x: BigInt) -> FLOAT {
+        num_traits::ToPrimitive::to_f64(&x).unwrap_or(f64::NAN) as FLOAT
+    }
+}
@@ -8,6 +8,7 @@ pub(crate) mod bit_field;
 pub(crate) mod blob_basic;
 pub(crate) mod debugging;
 pub(crate) mod fn_basic;
+pub(crate) mod generator_basic;
 pub(crate) mod iter_basic;
 pub(crate) mod lang_core;
 pub(crate) mod logic;
@@ -15,9 +16,11 @@ pub(crate) mod map_basic;
 pub(crate) mod math_basic;
 pub(crate) mod pkg_core;
 pub(crate) mod pkg_std;
+pub(crate) mod regex_basic;
 pub(crate) mod string_basic;
 pub(crate) mod string_more;
 pub(crate) mod time_basic;
+pub(crate) mod tuple_basic;
 
 pub use arithmetic::ArithmeticPackage;
 #[cfg(not(feature = "no_index"))]
@@ -28,6 +31,8 @@ pub use blob_basic::BasicBlobPackage;
 #[cfg(feature = "debugging")]
 pub use debugging::DebuggingPackage;
 pub use fn_basic::BasicFnPackage;
+#[cfg(not(feature = "no_function"))]
+pub use generator_basic::BasicGeneratorPackage;
 pub use iter_basic::BasicIteratorPackage;
 pub use lang_core::LanguageCorePackage;
 pub use logic::LogicPackage;
@@ -36,10 +41,13 @@ pub use map_basic::BasicMapPackage;
 pub use math_basic::BasicMathPackage;
 pub use pkg_core::CorePackage;
 pub use pkg_std::StandardPackage;
+#[cfg(feature = "regex")]
+pub use regex_basic::BasicRegexPackage;
 pub use string_basic::BasicStringPackage;
 pub use string_more::MoreStringPackage;
 #[cfg(not(feature = "no_time"))]
 pub use time_basic::BasicTimePackage;
+pub use tuple_basic::BasicTuplePackage;
 
 /// Trait that all packages must implement.
 pub trait Package {
@@ -14,6 +14,7 @@ def_package! {
     /// * [`BasicStringPackage`][super::BasicStringPackage]
     /// * [`BasicIteratorPackage`][super::BasicIteratorPackage]
     /// * [`BasicFnPackage`][super::BasicFnPackage]
+    /// * [`BasicGeneratorPackage`][super::BasicGeneratorPackage]
     /// * [`DebuggingPackage`][super::DebuggingPackage]
     pub CorePackage(lib) :
             LanguageCorePackage,
@@ -21,6 +22,7 @@ def_package! {
             BasicStringPackage,
             BasicIteratorPackage,
             BasicFnPackage,
+            #[cfg(not(feature = "no_function"))] BasicGeneratorPackage,
             #[cfg(feature = "debugging")] DebuggingPackage
         {
         lib.set_standard_lib(true);
@@ -17,6 +17,8 @@ def_package! {
     /// * [`BasicBlobPackage`][super::BasicBlobPackage]
     /// * [`BasicMapPackage`][super::BasicMapPackage]
     /// * [`BasicTimePackage`][super::BasicTimePackage]
+    /// * [`BasicTuplePackage`][super::BasicTuplePackage]
+    /// * [`BasicRegexPackage`][super::BasicRegexPackage]
     /// * [`MoreStringPackage`][super::MoreStringPackage]
     pub StandardPackage(lib) :
             CorePackage,
@@ -27,6 +29,8 @@ def_package! {
             #[cfg(not(feature = "no_index"))] BasicBlobPackage,
             #[cfg(not(feature = "no_object"))] BasicMapPackage,
             #[cfg(not(feature = "no_time"))] BasicTimePackage,
+            BasicTuplePackage,
+            #[cfg(feature = "regex")] BasicRegexPackage,
             MoreStringPackage
     {
         lib.set_standard_lib(true);
@@ -0,0 +1,228 @@
+#![cfg(feature = "regex")]
+
+use crate::plugin::*;
+use crate::{def_package, ImmutableString, NativeCallContext, Position, Regex, RhaiResultOf, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+def_package! {
+    /// Package of basic regular expression utilities.
+    pub BasicRegexPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "regex", regex_functions);
+    }
+}
+
+/// Check that a string produced from a regular expression is not over the maximum size limit.
+#[inline]
+fn check_string_size(_ctx: &NativeCallContext, _len: usize) -> RhaiResultOf<()> {
+    #[cfg(not(feature = "unchecked"))]
+    if _ctx.engine().max_string_size() > 0 && _len > _ctx.engine().max_string_size() {
+        return Err(ERR::ErrorDataTooLarge("Length of string".to_string(), Position::NONE).into());
+    }
+
+    Ok(())
+}
+
+#[export_module]
+mod regex_functions {
+    /// Compile a regular expression from a `pattern`.
+    ///
+    /// An error is raised if the pattern is not valid, or if it is longer than the maximum
+    /// string size.
+    ///
+    /// Matching with a regular expression is guaranteed to take time linear to the length of the
+    /// text, which is itself limited to the maximum string size.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let re = regex("[0-9]+");
+    ///
+    /// print(re.matches("abc123"));    // prints true
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn regex(ctx: NativeCallContext, pattern: &str) -> RhaiResultOf<Regex> {
+        check_string_size(&ctx, pattern.len())?;
+
+        Regex::new(pattern).map_err(|err| {
+            ERR::ErrorRuntime(
+                format!("Invalid regular expression '{pattern}': {err}").into(),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+    /// Return the pattern of the regular expression.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let re = regex("[0-9]+");
+    ///
+    /// print(re.pattern);      // prints "[0-9]+"
+    /// ```
+    #[rhai_fn(get = "pattern", pure)]
+    pub fn pattern(re: &mut Regex) -> ImmutableString {
+        re.as_str().into()
+    }
+    /// Convert the regular expression into a string, which is its pattern.
+    #[rhai_fn(name = "to_string", pure)]
+    pub fn to_string(re: &mut Regex) -> ImmutableString {
+        re.as_str().into()
+    }
+    /// Convert the regular expression into a string for debugging.
+    #[rhai_fn(name = "to_debug", pure)]
+    pub fn to_debug(re: &mut Regex) -> ImmutableString {
+        format!("{re:?}").into()
+    }
+    /// Return `true` if the regular expression matches anywhere within the `text`.
+    ///
+    /// Use `^` and `$` in the pattern to match the whole text.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let re = regex("^[a-z]+$");
+    ///
+    /// print(re.matches("hello"));     // prints true
+    ///
+    /// print(re.matches("hello!"));    // prints false
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn matches(re: &mut Regex, text: &str) -> bool {
+        re.as_ref().is_match(text)
+    }
+    /// Replace all matches of the regular expression `re` within the string with `replacement`.
+    ///
+    /// Within `replacement`, `$1` (or `${1}`) refers to the first capture group, `${name}` to the
+    /// capture group named `name`, `$0` to the whole match, and `$$` to a literal `$`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "John Smith, Jane Doe";
+    ///
+    /// text.replace(regex("(?<first>\\w+) (?<last>\\w+)"), "${last} $1");
+    ///
+    /// print(text);        // prints "Smith John, Doe Jane"
+    /// ```
+    #[rhai_fn(name = "replace", return_raw)]
+    pub fn replace_regex(
+        ctx: NativeCallContext,
+        string: &mut ImmutableString,
+        re: Regex,
+        replacement: &str,
+    ) -> RhaiResultOf<()> {
+        let result = re.as_ref().replace_all(string, replacement);
+
+        if let std::borrow::Cow::Owned(result) = result {
+            check_string_size(&ctx, result.len())?;
+            *string = result.into();
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    pub mod arrays {
+        use crate::{Array, Dynamic};
+
+        /// Return the text of the first match of the regular expression, followed by the text of
+        /// each of its capture groups, as an array.
+        ///
+        /// A capture group that does not take part in the match is `()`.
+        ///
+        /// If there is no match, `()` is returned.
+        ///
+        /// # Example
+        ///
+        /// ```rhai
+        /// let re = regex("(\\d+)-(\\d+)?");
+        ///
+        /// print(re.captures("call 555-1234"));    // prints ["555-1234", "555", "1234"]
+        ///
+        /// print(re.captures("call 555-"));        // prints ["555-", "555", ()]
+        ///
+        /// print(re.captures("call me"));          // prints ()
+        /// ```
+        #[rhai_fn(pure)]
+        pub fn captures(re: &mut Regex, text: &str) -> Dynamic {
+            re.as_ref().captures(text).map_or(Dynamic::UNIT, |caps| {
+                caps.iter()
+                    .map(|m| m.map_or(Dynamic::UNIT, |m| m.as_str().into()))
+                    .collect::<Array>()
+                    .into()
+            })
+        }
+        /// Return the text of all non-overlapping matches of the regular expression within the
+        /// `text`, as an array.
+        ///
+        /// # Example
+        ///
+        /// ```rhai
+        /// let re = regex("[0-9]+");
+        ///
+        /// print(re.find_all("1, 22 and 333"));    // prints ["1", "22", "333"]
+        /// ```
+        #[rhai_fn(pure)]
+        pub fn find_all(re: &mut Regex, text: &str) -> Array {
+            re.as_ref()
+                .find_iter(text)
+                .map(|m| m.as_str().into())
+                .collect()
+        }
+        /// Split the string into segments separated by matches of the regular expression `re`,
+        /// returning an array of the segments.
+        ///
+        /// # Example
+        ///
+        /// ```rhai
+        /// let text = "a, b;c ,d";
+        ///
+        /// print(text.split(regex("\\s*[,;]\\s*")));   // prints ["a", "b", "c", "d"]
+        /// ```
+        #[rhai_fn(name = "split", pure)]
+        pub fn split_regex(string: &mut ImmutableString, re: Regex) -> Array {
+            re.as_ref().split(string).map(Into::into).collect()
+        }
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    pub mod maps {
+        use crate::{Dynamic, Map};
+
+        /// Return the text of each named capture group of the first match of the regular
+        /// expression, as an object map keyed by group name.
+        ///
+        /// A named capture group that does not take part in the match is `()`.
+        ///
+        /// If there is no match, `()` is returned.
+        ///
+        /// # Example
+        ///
+        /// ```rhai
+        /// let re = regex("(?<key>\\w+)=(?<value>\\w+)?");
+        ///
+        /// print(re.named_captures("x=42"));   // prints #{"key": "x", "value": "42"}
+        ///
+        /// print(re.named_captures("x="));     // prints #{"key": "x", "value": ()}
+        /// ```
+        #[rhai_fn(pure)]
+        pub fn named_captures(re: &mut Regex, text: &str) -> Dynamic {
+            let re = re.as_ref();
+
+            re.captures(text).map_or(Dynamic::UNIT, |caps| {
+                re.capture_names()
+                    .flatten()
+                    .map(|name| {
+                        let value = caps.name(name).map_or(Dynamic::UNIT, |m| m.as_str().into());
+                        (name.into(), value)
+                    })
+                    .collect::<Map>()
+                    .into()
+            })
+        }
+    }
+}
@@ -1,13 +1,18 @@
+use crate::engine::FN_FORMAT;
+use crate::func::{FnCallArgs, RhaiFunc};
 use crate::plugin::*;
 use crate::{
-    def_package, Dynamic, ExclusiveRange, ImmutableString, InclusiveRange, NativeCallContext,
-    RhaiResultOf, SmartString, INT,
+    def_package, Dynamic, ExclusiveRange, FuncRegistration, ImmutableString, InclusiveRange,
+    NativeCallContext, Position, RhaiResult, RhaiResultOf, Shared, SmartString, ERR, INT,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{convert::TryFrom, mem};
+use std::{any::TypeId, convert::TryFrom, iter::Peekable, mem, str::Chars};
 
-use super::string_basic::{print_with_func, FUNC_TO_STRING};
+use super::string_basic::{print_with_func, FUNC_TO_DEBUG, FUNC_TO_STRING};
+
+/// Maximum number of values (after the format string) that can be passed to `format`.
+const MAX_FORMAT_ARGS: usize = 16;
 
 def_package! {
     /// Package of additional string utilities over [`BasicStringPackage`][super::BasicStringPackage]
@@ -15,7 +20,462 @@ def_package! {
         lib.set_standard_lib(true);
 
         combine_with_exported_module!(lib, "string", string_functions);
+
+        // `format` takes any number of values after the format string
+        for num_values in 0..=MAX_FORMAT_ARGS {
+            let arg_types: Vec<_> = std::iter::once(TypeId::of::<ImmutableString>())
+                .chain(std::iter::repeat(TypeId::of::<Dynamic>()).take(num_values))
+                .collect();
+
+            let reg = FuncRegistration::new(FN_FORMAT);
+
+            #[cfg(feature = "metadata")]
+            let reg = reg
+                .with_params_info(
+                    std::iter::once("format: &str".to_string())
+                        .chain((1..=num_values).map(|n| format!("value{n}: Dynamic")))
+                        .chain(std::iter::once("String".to_string())),
+                )
+                .with_comments([FORMAT_DOC_COMMENTS]);
+
+            reg.set_into_module_raw(
+                lib,
+                arg_types,
+                RhaiFunc::Pure {
+                    func: Shared::new(|ctx: Option<NativeCallContext>, args: &mut FnCallArgs| {
+                        format_values(&ctx.unwrap(), args)
+                    }),
+                    has_context: true,
+                    is_pure: true,
+                    is_volatile: false,
+                },
+            );
+        }
+    }
+}
+
+/// Doc-comments of the `format` function.
+#[cfg(feature = "metadata")]
+const FORMAT_DOC_COMMENTS: &str = r#"/// Format values into a string according to the `format` string.
+///
+/// Each `{}` placeholder in `format` is replaced by a value, in order. A placeholder can also
+/// name the value by position (e.g. `{0}`), or by property name (e.g. `{name}`) to take it from
+/// the last value, which must then be an object map. Use `{{` and `}}` for literal braces.
+///
+/// A placeholder can end with a format specification after `:`:
+/// `[[fill]align][sign]['#']['0'][width][grouping]['.' precision][type]`
+///
+/// * `align` is `<` (left), `^` (center) or `>` (right), padding with `fill` (default space)
+/// * `sign` is `+` to always show the sign of a number
+/// * `#` adds the `0x`, `0o` or `0b` prefix for the radix types
+/// * `0` pads numbers with zeros after the sign
+/// * `grouping` is `,` or `_` to separate thousands
+/// * `precision` is the number of decimal places for a number, or the maximum number of characters
+///   for any other value
+/// * `type` is `x` or `X` (hex), `o` (octal), `b` (binary), `e` or `E` (scientific), or `?` (debug)
+///
+/// # Example
+///
+/// ```rhai
+/// let x = 3.14159;
+///
+/// print(format("{:>8.2}|{:04x}|{name}", x, 42, #{ name: "foo" }));    // prints "    3.14|002a|foo"
+///
+/// print(format("{:,}", 1234567));    // prints "1,234,567"
+/// ```"#;
+
+/// A parsed format specification.
+///
+/// `[[fill]align][sign]['#']['0'][width][grouping]['.' precision][type]`
+#[derive(Debug, Clone, Copy)]
+struct FormatSpec {
+    /// Padding character.
+    fill: char,
+    /// Alignment: `<`, `^` or `>`.
+    align: Option<char>,
+    /// Always show the sign of a number?
+    plus: bool,
+    /// Add a radix prefix?
+    alternate: bool,
+    /// Pad numbers with zeros after the sign?
+    zero: bool,
+    /// Minimum width in characters.
+    width: usize,
+    /// Separator character for thousands.
+    grouping: Option<char>,
+    /// Number of decimal places, or maximum number of characters.
+    precision: Option<usize>,
+    /// Type of formatting.
+    kind: Option<char>,
+}
+
+impl FormatSpec {
+    /// Parse a format specification.
+    fn parse(spec: &str) -> Option<Self> {
+        let mut chars = spec.chars().peekable();
+
+        let mut fs = Self {
+            fill: ' ',
+            align: None,
+            plus: false,
+            alternate: false,
+            zero: false,
+            width: 0,
+            grouping: None,
+            precision: None,
+            kind: None,
+        };
+
+        let is_align = |ch: char| matches!(ch, '<' | '^' | '>');
+
+        // [[fill]align]
+        let mut lookahead = spec.chars();
+        match (lookahead.next(), lookahead.next()) {
+            (Some(fill), Some(align)) if is_align(align) => {
+                fs.fill = fill;
+                fs.align = Some(align);
+                chars.next();
+                chars.next();
+            }
+            (Some(align), ..) if is_align(align) => {
+                fs.align = Some(align);
+                chars.next();
+            }
+            _ => (),
+        }
+
+        // [sign]
+        match chars.peek() {
+            Some('+') => {
+                fs.plus = true;
+                chars.next();
+            }
+            Some('-') => {
+                chars.next();
+            }
+            _ => (),
+        }
+        // ['#']
+        if chars.next_if_eq(&'#').is_some() {
+            fs.alternate = true;
+        }
+        // ['0']
+        if chars.next_if_eq(&'0').is_some() {
+            fs.zero = true;
+        }
+        // [width]
+        fs.width = parse_digits(&mut chars)?.unwrap_or(0);
+        // [grouping]
+        if let Some(&ch @ (',' | '_')) = chars.peek() {
+            fs.grouping = Some(ch);
+            chars.next();
+        }
+        // ['.' precision]
+        if chars.next_if_eq(&'.').is_some() {
+            fs.precision = Some(parse_digits(&mut chars)??);
+        }
+        // [type]
+        if let Some(&ch @ ('x' | 'X' | 'o' | 'b' | 'e' | 'E' | '?')) = chars.peek() {
+            fs.kind = Some(ch);
+            chars.next();
+        }
+
+        chars.next().is_none().then_some(fs)
+    }
+}
+
+/// Parse a decimal number from a stream of characters.
+///
+/// Returns `Some(None)` if there are no digits, or `None` if the number overflows.
+fn parse_digits(chars: &mut Peekable<Chars>) -> Option<Option<usize>> {
+    let mut num = None;
+
+    while let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(10)) {
+        num = Some(
+            num.unwrap_or(0_usize)
+                .checked_mul(10)?
+                .checked_add(digit as usize)?,
+        );
+        chars.next();
+    }
+
+    Some(num)
+}
+
+/// Make an error for an invalid call to `format`.
+#[cold]
+#[inline(never)]
+fn format_error(message: String) -> Box<ERR> {
+    ERR::ErrorRuntime(message.into(), Position::NONE).into()
+}
+
+/// Check that a string is not over the maximum size limit.
+#[inline]
+fn check_string_size(_ctx: &NativeCallContext, _len: usize) -> RhaiResultOf<()> {
+    #[cfg(not(feature = "unchecked"))]
+    if _ctx.engine().max_string_size() > 0 && _len > _ctx.engine().max_string_size() {
+        return Err(ERR::ErrorDataTooLarge("Length of string".to_string(), Position::NONE).into());
+    }
+
+    Ok(())
+}
+
+/// Implementation of `format`: format the values in `args` according to the format string, which is
+/// the first argument.
+fn format_values(ctx: &NativeCallContext, args: &mut FnCallArgs) -> RhaiResult {
+    let (fmt, values) = args.split_first_mut().unwrap();
+    let fmt = fmt.read_lock::<ImmutableString>().unwrap().clone();
+
+    let mut result = String::new();
+    let mut chars = fmt.chars().peekable();
+    let mut next_index = 0;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.next_if_eq(&'{').is_some() => result.push('{'),
+            '}' if chars.next_if_eq(&'}').is_some() => result.push('}'),
+            '}' => return Err(format_error("unmatched `}` in format string".into())),
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => placeholder.push(ch),
+                        None => {
+                            return Err(format_error("unterminated `{` in format string".into()))
+                        }
+                    }
+                }
+
+                let (name, spec) = placeholder.split_once(':').unwrap_or((&placeholder, ""));
+                let spec = FormatSpec::parse(spec).ok_or_else(|| {
+                    format_error(format!("invalid format specification: `{spec}`"))
+                })?;
+
+                let mut value = if name.is_empty() {
+                    next_index += 1;
+                    values.get(next_index - 1).map(|v| v.flatten_clone())
+                } else if let Ok(index) = name.parse::<usize>() {
+                    values.get(index).map(|v| v.flatten_clone())
+                } else {
+                    #[cfg(not(feature = "no_object"))]
+                    let value = values
+                        .last()
+                        .and_then(|v| v.read_lock::<crate::Map>())
+                        .and_then(|map| map.get(name).map(Dynamic::flatten_clone));
+                    #[cfg(feature = "no_object")]
+                    let value = None;
+
+                    value
+                }
+                .ok_or_else(|| {
+                    format_error(format!("no value for `{{{name}}}` in format string"))
+                })?;
+
+                format_value(ctx, &mut value, &spec, &mut result)?;
+            }
+            _ => result.push(ch),
+        }
+
+        check_string_size(ctx, result.len())?;
+    }
+
+    Ok(result.into())
+}
+
+/// Format a value according to a format specification, appending the text to `out`.
+fn format_value(
+    ctx: &NativeCallContext,
+    value: &mut Dynamic,
+    spec: &FormatSpec,
+    out: &mut String,
+) -> RhaiResultOf<()> {
+    // Make sure that padding does not go over the maximum string size
+    check_string_size(ctx, spec.width)?;
+
+    if spec.kind != Some('?') {
+        if let Some((negative, digits)) = format_number(ctx, value, spec)? {
+            let sign = if negative {
+                "-"
+            } else if spec.plus {
+                "+"
+            } else {
+                ""
+            };
+            let prefix = match spec.kind {
+                Some('x' | 'X') if spec.alternate => "0x",
+                Some('o') if spec.alternate => "0o",
+                Some('b') if spec.alternate => "0b",
+                _ => "",
+            };
+
+            if spec.zero && spec.align.is_none() {
+                // Zero padding goes between the sign and the digits
+                let len = sign.len() + prefix.len() + digits.chars().count();
+                out.push_str(sign);
+                out.push_str(prefix);
+                out.extend(std::iter::repeat('0').take(spec.width.saturating_sub(len)));
+                out.push_str(&digits);
+            } else {
+                pad_text(out, &format!("{sign}{prefix}{digits}"), spec, '>');
+            }
+
+            return Ok(());
+        }
+    }
+
+    let text = match spec.kind {
+        Some('?') => print_with_func(FUNC_TO_DEBUG, ctx, value),
+        _ if value.is_string() => value.clone().into_immutable_string().unwrap(),
+        _ => print_with_func(FUNC_TO_STRING, ctx, value),
+    };
+
+    match spec.precision {
+        Some(max_len) => {
+            let text: String = text.chars().take(max_len).collect();
+            pad_text(out, &text, spec, '<');
+        }
+        None => pad_text(out, &text, spec, '<'),
+    }
+
+    Ok(())
+}
+
+/// Format a number according to a format specification.
+///
+/// Returns whether the number is negative, and the digits without the sign, or `None` if the
+/// value is not a number.
+fn format_number(
+    ctx: &NativeCallContext,
+    value: &Dynamic,
+    spec: &FormatSpec,
+) -> RhaiResultOf<Option<(bool, String)>> {
+    let (negative, digits, is_radix) = match spec.kind {
+        // Integers in other radixes show the bits of the value, so negative numbers are in
+        // two's complement form
+        Some(kind @ ('x' | 'X' | 'o' | 'b')) if value.is_int() => {
+            let n = value.as_int().unwrap();
+
+            let digits = match kind {
+                'x' => format!("{n:x}"),
+                'X' => format!("{n:X}"),
+                'o' => format!("{n:o}"),
+                _ => format!("{n:b}"),
+            };
+
+            (false, digits, true)
+        }
+        #[cfg(not(feature = "no_float"))]
+        Some('e' | 'E') if value.is_int() => {
+            let value = Dynamic::from_float(value.as_int().unwrap() as crate::FLOAT);
+            return format_number(ctx, &value, spec);
+        }
+        None if value.is_int() => {
+            let n = value.as_int().unwrap();
+            let mut digits = n.unsigned_abs().to_string();
+
+            // Integers have no decimal places, so they are all zeros
+            if let Some(precision) = spec.precision.filter(|&p| p > 0) {
+                check_string_size(ctx, precision)?;
+                digits.push('.');
+                digits.extend(std::iter::repeat('0').take(precision));
+            }
+
+            (n < 0, digits, false)
+        }
+        #[cfg(not(feature = "no_float"))]
+        None | Some('e' | 'E') if value.is_float() => {
+            let f = value.as_float().unwrap();
+            let precision = spec.precision.unwrap_or(0);
+            check_string_size(ctx, precision)?;
+
+            let digits = match spec.kind {
+                Some('e') if spec.precision.is_some() => format!("{:.*e}", precision, f.abs()),
+                Some('e') => format!("{:e}", f.abs()),
+                Some(_) if spec.precision.is_some() => format!("{:.*E}", precision, f.abs()),
+                Some(_) => format!("{:E}", f.abs()),
+                None if spec.precision.is_some() => format!("{:.*}", precision, f.abs()),
+                None => Dynamic::from_float(f.abs()).to_string(),
+            };
+
+            (f.is_sign_negative() && !f.is_nan(), digits, false)
+        }
+        #[cfg(feature = "decimal")]
+        None | Some('e' | 'E') if value.is_decimal() => {
+            let d = value.as_decimal().unwrap();
+            let precision = spec.precision.unwrap_or(0);
+            check_string_size(ctx, precision)?;
+
+            let digits = match spec.kind {
+                Some('e') => format!("{:e}", d.abs()),
+                Some(_) => format!("{:E}", d.abs()),
+                None if spec.precision.is_some() => {
+                    let dp = u32::try_from(precision).unwrap_or(u32::MAX);
+                    let d = d.abs().round_dp_with_strategy(
+                        dp,
+                        rust_decimal::RoundingStrategy::MidpointAwayFromZero,
+                    );
+                    format!("{d:.precision$}")
+                }
+                None => d.abs().to_string(),
+            };
+
+            (d.is_sign_negative() && !d.is_zero(), digits, false)
+        }
+        Some(kind) => {
+            return Err(format_error(format!(
+                "cannot format {} with `{kind}`",
+                ctx.engine().map_type_name(value.type_name())
+            )))
+        }
+        None => return Ok(None),
+    };
+
+    let digits = match spec.grouping {
+        Some(separator) if is_radix => group_digits(&digits, digits.len(), separator, 4),
+        Some(separator) => {
+            let int_len = digits
+                .find(|ch: char| !ch.is_ascii_digit())
+                .unwrap_or(digits.len());
+            group_digits(&digits, int_len, separator, 3)
+        }
+        None => digits,
+    };
+
+    Ok(Some((negative, digits)))
+}
+
+/// Separate the first `int_len` digits in `digits` into groups of `size` with a `separator`
+/// character.
+fn group_digits(digits: &str, int_len: usize, separator: char, size: usize) -> String {
+    let (int_part, rest) = digits.split_at(int_len);
+
+    let mut result = String::with_capacity(digits.len() + int_len / size);
+
+    for (i, ch) in int_part.chars().enumerate() {
+        if i > 0 && (int_len - i) % size == 0 {
+            result.push(separator);
+        }
+        result.push(ch);
     }
+    result.push_str(rest);
+    result
+}
+
+/// Append `text` to `out`, padded to the width in the format specification.
+fn pad_text(out: &mut String, text: &str, spec: &FormatSpec, default_align: char) {
+    let padding = spec.width.saturating_sub(text.chars().count());
+
+    let (left, right) = match spec.align.unwrap_or(default_align) {
+        '<' => (0, padding),
+        '^' => (padding / 2, padding - padding / 2),
+        _ => (padding, 0),
+    };
+
+    out.extend(std::iter::repeat(spec.fill).take(left));
+    out.push_str(text);
+    out.extend(std::iter::repeat(spec.fill).take(right));
 }
 
 #[export_module]
@@ -0,0 +1,287 @@
+use crate::engine::OP_EQUALS;
+use crate::plugin::*;
+use crate::{
+    def_package, Dynamic, EnumValue, ImmutableString, NativeCallContext, RhaiResultOf, Tuple, ERR,
+    INT,
+};
+use std::convert::TryFrom;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::slice;
+
+def_package! {
+    /// Package of basic tuple and `enum` value utilities.
+    pub BasicTuplePackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "tuple", tuple_functions);
+        combine_with_exported_module!(lib, "enum", enum_functions);
+
+        // Register tuple iterator
+        lib.set_iterable::<Tuple>();
+    }
+}
+
+/// Compare two lists of items for equality via the `==` operator.
+fn items_equal(
+    ctx: &NativeCallContext,
+    items1: &mut [Dynamic],
+    items2: &mut [Dynamic],
+) -> RhaiResultOf<bool> {
+    if items1.len() != items2.len() {
+        return Ok(false);
+    }
+
+    for (a1, a2) in items1.iter_mut().zip(items2.iter_mut()) {
+        if !ctx
+            .call_native_fn_raw(OP_EQUALS, true, &mut [a1, a2])
+            .or_else(|err| match *err {
+                ERR::ErrorFunctionNotFound(ref fn_sig, ..) if fn_sig.starts_with(OP_EQUALS) => {
+                    if a1.type_id() == a2.type_id() {
+                        // No default when comparing same type
+                        Err(err)
+                    } else {
+                        Ok(Dynamic::FALSE)
+                    }
+                }
+                _ => Err(err),
+            })?
+            .as_bool()
+            .unwrap_or(false)
+        {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Compare two lists of items lexicographically via the `==` operator and the comparison
+/// operator `op`, which is called on the first pair of items that are not equal.
+///
+/// If one list is a prefix of the other, their lengths are compared via `cmp`.
+fn items_compare(
+    ctx: &NativeCallContext,
+    items1: &mut [Dynamic],
+    items2: &mut [Dynamic],
+    op: &str,
+    cmp: fn(&usize, &usize) -> bool,
+) -> RhaiResultOf<bool> {
+    let (len1, len2) = (items1.len(), items2.len());
+
+    for (a1, a2) in items1.iter_mut().zip(items2.iter_mut()) {
+        // The `==` operator may consume its second operand, which is still needed for `op`
+        let mut a = a2.clone();
+
+        if !items_equal(ctx, slice::from_mut(a1), slice::from_mut(&mut a))? {
+            return Ok(ctx
+                .call_native_fn_raw(op, true, &mut [a1, a2])?
+                .as_bool()
+                .unwrap_or(false));
+        }
+    }
+
+    Ok(cmp(&len1, &len2))
+}
+
+#[export_module]
+mod tuple_functions {
+    /// Return the number of items in the tuple.
+    #[rhai_fn(name = "len", get = "len", pure)]
+    pub fn len(tuple: &mut Tuple) -> INT {
+        INT::try_from(tuple.len()).unwrap_or(INT::MAX)
+    }
+    /// Return true if the tuple is empty.
+    #[rhai_fn(name = "is_empty", get = "is_empty", pure)]
+    pub fn is_empty(tuple: &mut Tuple) -> bool {
+        tuple.len() == 0
+    }
+    /// Convert the tuple into an array.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let t = (1, "hello", true);
+    ///
+    /// print(t.to_array());    // prints [1, "hello", true]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(pure)]
+    pub fn to_array(tuple: &mut Tuple) -> crate::Array {
+        tuple.to_vec()
+    }
+    /// Return `true` if two tuples are equal (i.e. all items are equal and in the same order).
+    ///
+    /// The operator `==` is used to compare items and must be defined,
+    /// otherwise `false` is assumed.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = (1, "a", true);
+    /// let y = (1, "a", true);
+    /// let z = (1, "a");
+    ///
+    /// print(x == y);      // prints true
+    ///
+    /// print(x == z);      // prints false
+    /// ```
+    #[rhai_fn(name = "==", return_raw, pure)]
+    pub fn equals(ctx: NativeCallContext, tuple1: &mut Tuple, tuple2: Tuple) -> RhaiResultOf<bool> {
+        let mut tuple2 = tuple2;
+        items_equal(&ctx, tuple1, &mut tuple2)
+    }
+    /// Return `true` if two tuples are not-equal (i.e. any item not equal or not in the same order).
+    ///
+    /// The operator `==` is used to compare items and must be defined,
+    /// otherwise `false` is assumed.
+    #[rhai_fn(name = "!=", return_raw, pure)]
+    pub fn not_equals(
+        ctx: NativeCallContext,
+        tuple1: &mut Tuple,
+        tuple2: Tuple,
+    ) -> RhaiResultOf<bool> {
+        equals(ctx, tuple1, tuple2).map(|r| !r)
+    }
+    /// Return `true` if the first tuple is less than the second.
+    ///
+    /// Tuples are compared item by item, in order, until a pair of items is not equal.
+    /// A tuple that is a prefix of the other is less.
+    ///
+    /// The operators `==` and `<` are used to compare items.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// print((1, "b") < (2, "a"));     // prints true
+    ///
+    /// print((1, "b") < (1, "a"));     // prints false
+    ///
+    /// print((1, 2) < (1, 2, 3));      // prints true
+    /// ```
+    #[rhai_fn(name = "<", return_raw, pure)]
+    pub fn less_than(
+        ctx: NativeCallContext,
+        tuple1: &mut Tuple,
+        tuple2: Tuple,
+    ) -> RhaiResultOf<bool> {
+        let mut tuple2 = tuple2;
+        items_compare(&ctx, tuple1, &mut tuple2, "<", usize::lt)
+    }
+    /// Return `true` if the first tuple is less than or equals to the second.
+    ///
+    /// Tuples are compared item by item, in order, until a pair of items is not equal.
+    ///
+    /// The operators `==` and `<=` are used to compare items.
+    #[rhai_fn(name = "<=", return_raw, pure)]
+    pub fn less_than_or_equals_to(
+        ctx: NativeCallContext,
+        tuple1: &mut Tuple,
+        tuple2: Tuple,
+    ) -> RhaiResultOf<bool> {
+        let mut tuple2 = tuple2;
+        items_compare(&ctx, tuple1, &mut tuple2, "<=", usize::le)
+    }
+    /// Return `true` if the first tuple is greater than the second.
+    ///
+    /// Tuples are compared item by item, in order, until a pair of items is not equal.
+    /// A tuple that has the other as a prefix is greater.
+    ///
+    /// The operators `==` and `>` are used to compare items.
+    #[rhai_fn(name = ">", return_raw, pure)]
+    pub fn greater_than(
+        ctx: NativeCallContext,
+        tuple1: &mut Tuple,
+        tuple2: Tuple,
+    ) -> RhaiResultOf<bool> {
+        let mut tuple2 = tuple2;
+        items_compare(&ctx, tuple1, &mut tuple2, ">", usize::gt)
+    }
+    /// Return `true` if the first tuple is greater than or equals to the second.
+    ///
+    /// Tuples are compared item by item, in order, until a pair of items is not equal.
+    ///
+    /// The operators `==` and `>=` are used to compare items.
+    #[rhai_fn(name = ">=", return_raw, pure)]
+    pub fn greater_than_or_equals_to(
+        ctx: NativeCallContext,
+        tuple1: &mut Tuple,
+        tuple2: Tuple,
+    ) -> RhaiResultOf<bool> {
+        let mut tuple2 = tuple2;
+        items_compare(&ctx, tuple1, &mut tuple2, ">=", usize::ge)
+    }
+}
+
+#[export_module]
+mod enum_functions {
+    /// Return the name of the `enum` type of the value.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// enum Shape { Circle(r), Empty }
+    ///
+    /// print(Shape::Circle(1).enum_name);      // prints "Shape"
+    /// ```
+    #[rhai_fn(name = "enum_name", get = "enum_name", pure)]
+    pub fn enum_name(value: &mut EnumValue) -> ImmutableString {
+        value.enum_name().into()
+    }
+    /// Return the name of the variant of the value.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// enum Shape { Circle(r), Empty }
+    ///
+    /// print(Shape::Circle(1).variant);        // prints "Circle"
+    /// ```
+    #[rhai_fn(name = "variant", get = "variant", pure)]
+    pub fn variant(value: &mut EnumValue) -> ImmutableString {
+        value.variant().into()
+    }
+    /// Return the fields of the value as a tuple.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// enum Shape { Rect(w, h), Empty }
+    ///
+    /// print(Shape::Rect(1, 2).fields);        // prints (1, 2)
+    /// ```
+    #[rhai_fn(name = "fields", get = "fields", pure)]
+    pub fn fields(value: &mut EnumValue) -> Tuple {
+        value.fields().clone()
+    }
+    /// Return `true` if two `enum` values are equal (i.e. same `enum` type, same variant and all
+    /// fields equal).
+    ///
+    /// The operator `==` is used to compare fields and must be defined,
+    /// otherwise `false` is assumed.
+    #[rhai_fn(name = "==", return_raw, pure)]
+    pub fn equals(
+        ctx: NativeCallContext,
+        value1: &mut EnumValue,
+        value2: EnumValue,
+    ) -> RhaiResultOf<bool> {
+        if !value1.is(value2.enum_name(), value2.variant()) {
+            return Ok(false);
+        }
+
+        let mut fields2 = value2.into_fields();
+        items_equal(&ctx, value1.fields_mut(), &mut fields2)
+    }
+    /// Return `true` if two `enum` values are not equal.
+    ///
+    /// The operator `==` is used to compare fields and must be defined,
+    /// otherwise `false` is assumed.
+    #[rhai_fn(name = "!=", return_raw, pure)]
+    pub fn not_equals(
+        ctx: NativeCallContext,
+        value1: &mut EnumValue,
+        value2: EnumValue,
+    ) -> RhaiResultOf<bool> {
+        equals(ctx, value1, value2).map(|r| !r)
+    }
+}
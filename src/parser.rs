@@ -2,11 +2,11 @@
 
 use crate::api::options::LangOptions;
 use crate::ast::{
-    ASTFlags, BinaryExpr, CaseBlocksList, Expr, FlowControl, FnCallExpr, FnCallHashes, Ident,
-    OpAssignment, RangeCase, ScriptFuncDef, Stmt, StmtBlock, StmtBlockContainer,
-    SwitchCasesCollection,
+    ASTFlags, BinaryExpr, CaseBlocksList, CatchClause, Expr, FlowControl, FnCallExpr, FnCallHashes,
+    Ident, OpAssignment, RangeCase, ScriptFuncDef, Stmt, StmtBlock, StmtBlockContainer,
+    SwitchCasesCollection, SwitchPattern, TryCatchBlock,
 };
-use crate::engine::{Precedence, OP_CONTAINS, OP_NOT};
+use crate::engine::{Precedence, FN_FORMAT, KEYWORD_TYPE_OF, OP_CONTAINS, OP_EQUALS, OP_NOT};
 use crate::eval::{Caches, GlobalRuntimeState};
 use crate::func::{hashing::get_hasher, StraightHashMap};
 #[cfg(not(feature = "no_function"))]
@@ -24,6 +24,7 @@ use bitflags::bitflags;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
+    collections::BTreeMap,
     convert::TryFrom,
     fmt,
     hash::{Hash, Hasher},
@@ -84,6 +85,24 @@ pub struct ParseState<'a, 't, 'f> {
     /// List of globally-imported [module][crate::Module] names.
     #[cfg(not(feature = "no_module"))]
     pub global_imports: ThinVec<ImmutableString>,
+    /// Names of inline [modules][crate::Module] defined at this level.
+    #[cfg(not(feature = "no_module"))]
+    pub modules: ThinVec<ImmutableString>,
+    /// Script-defined `enum` types, mapping each name to its variants and their number of fields.
+    pub enums: BTreeMap<ImmutableString, StaticVec<(ImmutableString, usize)>>,
+    /// Script-defined `struct` types, mapping each name to a template value with all fields set
+    /// to `()`.
+    #[cfg(not(feature = "no_object"))]
+    pub structs: BTreeMap<ImmutableString, crate::StructValue>,
+    /// Script-defined `struct` types defined at this level, to be recorded in the [`AST`].
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_function"))]
+    pub struct_types: ThinVec<crate::types::CustomTypeInfo>,
+    /// Labels of the enclosing loops (if any), innermost last.
+    pub loop_labels: ThinVec<Option<SmartString>>,
+    /// Variables found undefined under Strict Variables mode but not yet reported, because they
+    /// may still be declared by the `for` clause of an array or object map comprehension.
+    pub undefined_vars: Option<ThinVec<Ident>>,
     /// Unused dummy field.
     #[cfg(feature = "no_function")]
     pub _dummy: &'f (),
@@ -107,7 +126,16 @@ impl fmt::Debug for ParseState<'_, '_, '_> {
 
         #[cfg(not(feature = "no_module"))]
         f.field("imports", &self.imports)
-            .field("global_imports", &self.global_imports);
+            .field("global_imports", &self.global_imports)
+            .field("modules", &self.modules);
+
+        f.field("enums", &self.enums);
+
+        #[cfg(not(feature = "no_object"))]
+        f.field("structs", &self.structs);
+
+        f.field("loop_labels", &self.loop_labels)
+            .field("undefined_vars", &self.undefined_vars);
 
         f.finish()
     }
@@ -143,7 +171,139 @@ impl<'a, 't, 'f> ParseState<'a, 't, 'f> {
             imports: ThinVec::new(),
             #[cfg(not(feature = "no_module"))]
             global_imports: ThinVec::new(),
+            #[cfg(not(feature = "no_module"))]
+            modules: ThinVec::new(),
+            enums: BTreeMap::new(),
+            #[cfg(not(feature = "no_object"))]
+            structs: BTreeMap::new(),
+            #[cfg(not(feature = "no_object"))]
+            #[cfg(not(feature = "no_function"))]
+            struct_types: ThinVec::new(),
+            loop_labels: ThinVec::new(),
+            undefined_vars: None,
+        }
+    }
+
+    /// Report a variable that is undefined under Strict Variables mode.
+    ///
+    /// The error is deferred if undefined variables are currently being collected.
+    fn report_undefined_var(&mut self, name: ImmutableString, pos: Position) -> ParseResult<()> {
+        match self.undefined_vars {
+            Some(ref mut vars) => {
+                vars.push(Ident { name, pos });
+                Ok(())
+            }
+            None => Err(PERR::VariableUndefined(name.to_string()).into_err(pos)),
+        }
+    }
+
+    /// Record a parse error if parse errors are being recovered from.
+    ///
+    /// Returns the error if parse errors are not being recovered from.
+    fn report_error(&mut self, err: ParseError) -> ParseResult<()> {
+        match self.tokenizer_control.borrow_mut().errors {
+            Some(ref mut errors) => {
+                errors.push(err);
+                Ok(())
+            }
+            None => Err(err),
+        }
+    }
+
+    /// Get the number of tokens read so far, including the next token.
+    ///
+    /// Used to detect progress, as positions may not be available (e.g. under `no_position`).
+    #[must_use]
+    fn num_tokens(&mut self) -> usize {
+        self.input.peek();
+        self.tokenizer_control.borrow().num_tokens
+    }
+
+    /// Recover from a parse error in a statement starting at the `start`-th token (as returned
+    /// by [`num_tokens`][ParseState::num_tokens]), by skipping tokens up to the start of the next
+    /// statement or the end of the enclosing block (if `in_block`).
+    ///
+    /// The first token is always skipped if the statement has not moved beyond it, so that
+    /// parsing always moves forward.
+    ///
+    /// Returns the error if parse errors are not being recovered from.
+    fn recover(&mut self, err: ParseError, start: usize, in_block: bool) -> ParseResult<()> {
+        self.report_error(err)?;
+
+        let mut level = 0_usize;
+        // Levels of interpolated strings being skipped, with whether each is a heredoc literal
+        let mut interpolations = Vec::<(usize, bool)>::new();
+        let mut first = true;
+
+        loop {
+            let is_start = first && self.num_tokens() == start;
+            let (token, ..) = self.input.peek().unwrap();
+
+            match token {
+                Token::EOF => break,
+                _ if is_start => (),
+                Token::RightBrace if level == 0 && in_block => break,
+                Token::SemiColon if level == 0 => {
+                    eat_token(self.input, &Token::SemiColon);
+                    break;
+                }
+                // Start of the next statement
+                Token::Let
+                | Token::Const
+                | Token::If
+                | Token::Switch
+                | Token::While
+                | Token::Loop
+                | Token::Do
+                | Token::For
+                | Token::Try
+                | Token::Return
+                | Token::Throw
+                | Token::Break
+                | Token::Continue
+                | Token::Enum
+                | Token::Struct
+                    if level == 0 =>
+                {
+                    break
+                }
+                #[cfg(not(feature = "no_function"))]
+                Token::Fn | Token::Private | Token::Yield if level == 0 => break,
+                #[cfg(not(feature = "no_closure"))]
+                Token::Static if level == 0 => break,
+                #[cfg(not(feature = "no_module"))]
+                Token::Import | Token::Export | Token::Module if level == 0 => break,
+                _ => (),
+            }
+
+            first = false;
+
+            match self.input.next().unwrap().0 {
+                Token::LeftBrace | Token::MapStart | Token::LeftParen | Token::LeftBracket => {
+                    level += 1;
+                }
+                Token::RightParen | Token::RightBracket => level = level.saturating_sub(1),
+                Token::RightBrace => {
+                    level = level.saturating_sub(1);
+
+                    // Parse the rest of an interpolated string as text
+                    if interpolations.last().map_or(false, |&(n, ..)| n == level) {
+                        let (.., is_heredoc) = interpolations.pop().unwrap();
+                        let control = &mut *self.tokenizer_control.borrow_mut();
+                        control.is_within_text = true;
+                        control.is_within_heredoc = is_heredoc;
+                    }
+                }
+                Token::InterpolatedString(..) => {
+                    let is_heredoc =
+                        std::mem::take(&mut self.tokenizer_control.borrow_mut().is_within_heredoc);
+                    interpolations.push((level, is_heredoc));
+                }
+                _ => (),
+            }
         }
+
+        Ok(())
     }
 
     /// Find explicitly declared variable by name in the [`ParseState`], searching in reverse order.
@@ -196,6 +356,16 @@ impl<'a, 't, 'f> ParseState<'a, 't, 'f> {
     }
 }
 
+/// Parser state saved when starting on the leading item of an array or object map literal.
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+struct LeadingItem {
+    /// Undefined variables collected by the enclosing context (if any).
+    undefined_vars: Option<ThinVec<Ident>>,
+    /// Number of external variables before the leading item.
+    #[cfg(not(feature = "no_closure"))]
+    num_external_vars: usize,
+}
+
 bitflags! {
     /// Bit-flags containing all status for [`ParseSettings`].
     #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
@@ -213,6 +383,10 @@ bitflags! {
         const DISALLOW_STATEMENTS_IN_BLOCKS = 0b0001_0000;
         /// Disallow unquoted map properties?
         const DISALLOW_UNQUOTED_MAP_PROPERTIES = 0b0010_0000;
+        /// Allow a format specification (e.g. `${x:.2}`) to end a block within an interpolated string?
+        const ALLOW_FORMAT_SPEC = 0b0100_0000;
+        /// Is the block being parsed the body of a function (where `defer` is allowed)?
+        const FN_BODY = 0b1000_0000;
     }
 }
 
@@ -298,6 +472,32 @@ pub fn is_anonymous_fn(fn_name: &str) -> bool {
     fn_name.starts_with(crate::engine::FN_ANONYMOUS)
 }
 
+/// Is this the name of an operator that a script-defined function can overload?
+#[inline]
+#[must_use]
+pub fn is_script_operator(fn_name: &str) -> bool {
+    matches!(
+        fn_name,
+        "+" | "-"
+            | "*"
+            | "/"
+            | "%"
+            | "**"
+            | "=="
+            | "!="
+            | "<"
+            | "<="
+            | ">"
+            | ">="
+            | "&"
+            | "|"
+            | "^"
+            | "<<"
+            | ">>"
+            | "!"
+    )
+}
+
 impl Expr {
     /// Raise an error if the expression can never yield a boolean value.
     fn ensure_bool_expr(self) -> ParseResult<Self> {
@@ -311,7 +511,11 @@ impl Expr {
             Self::StringConstant(..) => "a string",
             Self::InterpolatedString(..) => "a string",
             Self::Array(..) => "an array",
+            Self::Tuple(..) => "a tuple",
+            Self::Enum(..) => "an enum value",
             Self::Map(..) => "an object map",
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(..) => "a struct value",
             _ => return Ok(self),
         };
 
@@ -329,7 +533,10 @@ impl Expr {
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(..) => "a floating-point number",
             Self::CharConstant(..) => "a character",
+            Self::Enum(..) => "an enum value",
             Self::Map(..) => "an object map",
+            #[cfg(not(feature = "no_object"))]
+            Self::Struct(..) => "a struct value",
             _ => return Ok(self),
         };
 
@@ -395,6 +602,106 @@ fn match_token(input: &mut TokenStream, token: &Token) -> (bool, Position) {
     }
 }
 
+/// Make an error for spread arguments in a method-call style function call.
+#[cfg(not(feature = "no_object"))]
+#[cold]
+#[inline(never)]
+fn spread_in_method_call_err(fn_name: &str) -> LexError {
+    LexError::ImproperSymbol(
+        Token::Spread.literal_syntax().into(),
+        format!(
+            "Spread arguments are not supported in method-call style. Try {fn_name}(obj, ...);"
+        ),
+    )
+}
+
+/// Make sure that all `yield` statements in a function body can be suspended and resumed, i.e.
+/// they are nested only within statement blocks, `if`, `while`, `loop`, `do` and `for` statements.
+///
+/// Returns `true` if there is at least one `yield` statement.
+#[cfg(not(feature = "no_function"))]
+fn check_yield_statements(statements: &[Stmt]) -> ParseResult<bool> {
+    use crate::ast::ASTNode;
+
+    let ensure_no_yield = |expr: &Expr| {
+        let mut pos = None;
+
+        expr.walk(&mut Vec::new(), &mut |path| match path.last() {
+            Some(ASTNode::Stmt(Stmt::Yield(.., p))) => {
+                pos = Some(*p);
+                false
+            }
+            _ => true,
+        });
+
+        pos.map_or(Ok(()), |pos| Err(PERR::WrongYield.into_err(pos)))
+    };
+
+    let mut has_yield = false;
+
+    for stmt in statements {
+        has_yield |= match stmt {
+            Stmt::Yield(expr, ..) => {
+                ensure_no_yield(expr)?;
+                true
+            }
+            Stmt::Block(block) => check_yield_statements(block.statements())?,
+            Stmt::If(x, ..) | Stmt::While(x, ..) | Stmt::Do(x, ..) => {
+                ensure_no_yield(&x.expr)?;
+                let body = check_yield_statements(x.body.statements())?;
+                check_yield_statements(x.branch.statements())? || body
+            }
+            Stmt::For(x, ..) => {
+                ensure_no_yield(&x.2.expr)?;
+                check_yield_statements(x.2.body.statements())?
+            }
+            _ => match stmt.yield_position() {
+                Some(pos) => return Err(PERR::WrongYield.into_err(pos)),
+                None => false,
+            },
+        };
+    }
+
+    Ok(has_yield)
+}
+
+/// Make sure that all `static` variables in a function body have distinct names.
+///
+/// Returns the storage for them, or `None` if there are no `static` variables.
+#[cfg(not(feature = "no_function"))]
+fn make_static_variables(statements: &[Stmt]) -> ParseResult<Option<crate::ast::StaticVariables>> {
+    use crate::ast::ASTNode;
+
+    let mut names = StaticVec::<ImmutableString>::new_const();
+    let mut err = None;
+
+    for stmt in statements {
+        stmt.walk(&mut Vec::new(), &mut |path| match path.last() {
+            Some(ASTNode::Stmt(Stmt::Var(x, options, ..)))
+                if options.contains(ASTFlags::STATIC) =>
+            {
+                if names.contains(&x.0.name) {
+                    err = Some(PERR::DuplicatedVariable(x.0.name.to_string()).into_err(x.0.pos));
+                    return false;
+                }
+                names.push(x.0.name.clone());
+                true
+            }
+            _ => true,
+        });
+
+        if let Some(err) = err {
+            return Err(err);
+        }
+    }
+
+    Ok(if names.is_empty() {
+        None
+    } else {
+        Some(crate::Locked::new(BTreeMap::new()).into())
+    })
+}
+
 /// Process a block comment such that it indents properly relative to the start token.
 #[cfg(not(feature = "no_function"))]
 #[cfg(feature = "metadata")]
@@ -443,6 +750,26 @@ fn parse_var_name(input: &mut TokenStream) -> ParseResult<(SmartString, Position
     }
 }
 
+/// Parse the optional label of a `break` or `continue` statement, returning the number of
+/// enclosing loops to leave before the one it names.
+fn parse_loop_label(state: &mut ParseState) -> ParseResult<u8> {
+    let (label, pos) = match state.input.peek().unwrap() {
+        (Token::Label(..), ..) => match state.input.next().unwrap() {
+            (Token::Label(label), pos) => (label, pos),
+            token => unreachable!("Token::Label expected but gets {:?}", token),
+        },
+        _ => return Ok(0),
+    };
+
+    state
+        .loop_labels
+        .iter()
+        .rev()
+        .position(|l| l.as_ref() == Some(&*label))
+        .ok_or_else(|| PERR::LabelUndefined(label.to_string()).into_err(pos))
+        .and_then(|levels| u8::try_from(levels).map_err(|_| PERR::ExprTooDeep.into_err(pos)))
+}
+
 /// Optimize the structure of a chained expression where the root expression is another chained expression.
 ///
 /// # Panics
@@ -683,7 +1010,7 @@ impl Engine {
             match state.input.peek().unwrap() {
                 // id(...args, ) - handle trailing comma
                 (Token::RightParen, ..) => (),
-                _ => args.push(self.parse_expr(state, settings)?),
+                _ => args.push(self.parse_expr_or_spread(state, settings)?),
             }
 
             match state.input.peek().unwrap() {
@@ -949,7 +1276,32 @@ impl Engine {
                     )
                     .into_err(*pos))
                 }
-                _ => array.push(self.parse_expr(state, settings.level_up()?)?),
+                // [ expr for ...
+                _ if array.is_empty() => {
+                    let leading = Self::begin_leading_item(state);
+                    let expr = self.parse_expr_or_spread(state, settings.level_up()?)?;
+
+                    if !matches!(expr, Expr::Spread(..))
+                        && state.input.peek().unwrap().0 == Token::For
+                    {
+                        let settings = settings.level_up()?;
+                        let x = self.parse_comprehension(state, settings, None, expr, leading)?;
+
+                        return match state.input.next().unwrap() {
+                            (Token::RightBracket, ..) => Ok(Expr::Comprehension(x, settings.pos)),
+                            (Token::LexError(err), pos) => Err(err.into_err(pos)),
+                            (.., pos) => Err(PERR::MissingToken(
+                                Token::RightBracket.into(),
+                                MISSING_RBRACKET.into(),
+                            )
+                            .into_err(pos)),
+                        };
+                    }
+
+                    Self::end_leading_item(state, leading, &[])?;
+                    array.push(expr);
+                }
+                _ => array.push(self.parse_expr_or_spread(state, settings.level_up()?)?),
             }
 
             match state.input.peek().unwrap() {
@@ -1010,14 +1362,33 @@ impl Engine {
                 _ => (),
             }
 
+            // #{ [expr]: expr for ... } or #{ `...${expr}...`: expr for ... }
+            if map.is_empty()
+                && matches!(
+                    state.input.peek().unwrap().0,
+                    Token::LeftBracket | Token::InterpolatedString(..)
+                )
+            {
+                let x = self.parse_map_comprehension_with_computed_key(state, settings)?;
+                return Self::end_map_comprehension(state, x, settings.pos);
+            }
+
+            let is_spread = state.input.peek().unwrap().0 == Token::Spread;
+            let is_name = matches!(state.input.peek().unwrap().0, Token::Identifier(..));
+
             let (name, pos) = match state.input.next().unwrap() {
+                // #{ ...map }
+                (Token::Spread, pos) => (crate::SmartString::new_const(), pos),
                 (Token::Identifier(..), pos)
                     if settings.has_flag(ParseSettingFlags::DISALLOW_UNQUOTED_MAP_PROPERTIES) =>
                 {
                     return Err(PERR::PropertyExpected.into_err(pos))
                 }
                 (Token::Identifier(s) | Token::StringConstant(s), pos) => {
-                    if map.iter().any(|(p, ..)| p.as_str() == s.as_str()) {
+                    if map
+                        .iter()
+                        .any(|(p, v)| !matches!(v, Expr::Spread(..)) && p.as_str() == s.as_str())
+                    {
                         return Err(PERR::DuplicatedProperty(s.to_string()).into_err(pos));
                     }
                     (*s, pos)
@@ -1046,15 +1417,17 @@ impl Engine {
                 (.., pos) => return Err(PERR::PropertyExpected.into_err(pos)),
             };
 
-            match state.input.next().unwrap() {
-                (Token::Colon, ..) => (),
-                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
-                (.., pos) => {
-                    return Err(PERR::MissingToken(
-                        Token::Colon.into(),
-                        format!("to follow the property '{name}' in this object map literal"),
-                    )
-                    .into_err(pos))
+            if !is_spread {
+                match state.input.next().unwrap() {
+                    (Token::Colon, ..) => (),
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (.., pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::Colon.into(),
+                            format!("to follow the property '{name}' in this object map literal"),
+                        )
+                        .into_err(pos))
+                    }
                 }
             }
 
@@ -1067,8 +1440,31 @@ impl Engine {
                 .into_err(state.input.peek().unwrap().1));
             }
 
+            // #{ name: expr for ...
+            let leading = (map.is_empty() && !is_spread).then(|| Self::begin_leading_item(state));
             let expr = self.parse_expr(state, settings.level_up()?)?;
-            template.insert(name.clone(), crate::Dynamic::UNIT);
+
+            if let Some(leading) = leading {
+                if state.input.peek().unwrap().0 == Token::For {
+                    let settings = settings.level_up()?;
+                    let key = Ident {
+                        name: self.get_interned_string(name),
+                        pos,
+                    };
+                    let key = Some((key, is_name));
+                    let x = self.parse_comprehension(state, settings, key, expr, leading)?;
+                    return Self::end_map_comprehension(state, x, settings.pos);
+                }
+
+                Self::end_leading_item(state, leading, &[])?;
+            }
+
+            let expr = if is_spread {
+                Expr::Spread(expr.into(), pos)
+            } else {
+                template.insert(name.clone(), crate::Dynamic::UNIT);
+                expr
+            };
 
             let name = self.get_interned_string(name);
             map.push((Ident { name, pos }, expr));
@@ -1100,6 +1496,205 @@ impl Engine {
         Ok(Expr::Map((map, template).into(), settings.pos))
     }
 
+    /// Parse an object map comprehension whose key is computed, either by an expression in
+    /// brackets (e.g. `#{ [expr]: expr for ... }`) or by an interpolated string (e.g.
+    /// `` #{ `...${expr}...`: expr for ... } ``).
+    ///
+    /// A computed key is only allowed in a comprehension.
+    #[cfg(not(feature = "no_object"))]
+    fn parse_map_comprehension_with_computed_key(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+    ) -> ParseResult<Box<crate::ast::ComprehensionExpr>> {
+        let key_pos = state.input.peek().unwrap().1;
+        let leading = Self::begin_leading_item(state);
+
+        let key = if match_token(state.input, &Token::LeftBracket).0 {
+            // [ expr ]
+            let key = self.parse_expr(state, settings.level_up()?)?;
+
+            match state.input.next().unwrap() {
+                (Token::RightBracket, ..) => key,
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightBracket.into(),
+                        "to end this computed property".into(),
+                    )
+                    .into_err(pos))
+                }
+            }
+        } else {
+            // `...${expr}...`
+            self.parse_primary(state, settings.level_up()?, ChainingFlags::empty())?
+        };
+
+        match state.input.next().unwrap() {
+            (Token::Colon, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(PERR::MissingToken(
+                    Token::Colon.into(),
+                    "to follow the computed property in this object map literal".into(),
+                )
+                .into_err(pos))
+            }
+        }
+
+        let value = self.parse_expr(state, settings.level_up()?)?;
+
+        if state.input.peek().unwrap().0 != Token::For {
+            return Err(PERR::PropertyExpected.into_err(key_pos));
+        }
+
+        let mut x = self.parse_comprehension(state, settings.level_up()?, None, value, leading)?;
+        x.key = Some(key);
+        Ok(x)
+    }
+
+    /// Finish parsing an object map comprehension, which must be followed by the closing `}`.
+    #[cfg(not(feature = "no_object"))]
+    fn end_map_comprehension(
+        state: &mut ParseState,
+        x: Box<crate::ast::ComprehensionExpr>,
+        pos: Position,
+    ) -> ParseResult<Expr> {
+        match state.input.next().unwrap() {
+            (Token::RightBrace, ..) => Ok(Expr::Comprehension(x, pos)),
+            (Token::LexError(err), pos) => Err(err.into_err(pos)),
+            (.., pos) => Err(PERR::MissingToken(
+                Token::RightBrace.into(),
+                "to end this object map literal".into(),
+            )
+            .into_err(pos)),
+        }
+    }
+
+    /// Start parsing the leading item of an array or object map literal.
+    ///
+    /// The item may turn out to be that of a comprehension, whose `for` clause declares
+    /// variables only after it, so variables undefined under Strict Variables mode are
+    /// collected rather than reported until [`end_leading_item`][Engine::end_leading_item].
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    fn begin_leading_item(state: &mut ParseState) -> LeadingItem {
+        LeadingItem {
+            undefined_vars: state.undefined_vars.replace(ThinVec::new()),
+            #[cfg(not(feature = "no_closure"))]
+            num_external_vars: state.external_vars.len(),
+        }
+    }
+
+    /// Finish parsing the leading item of an array or object map literal, given the variables
+    /// declared by its comprehension (if any).
+    ///
+    /// Undefined variables collected since [`begin_leading_item`][Engine::begin_leading_item]
+    /// are reported, unless they are declared.
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    fn end_leading_item(
+        state: &mut ParseState,
+        leading: LeadingItem,
+        declared: &[&Ident],
+    ) -> ParseResult<()> {
+        let is_declared = |name: &ImmutableString| declared.iter().any(|v| v.name == *name);
+
+        // The declared variables are not captured by an enclosing closure
+        #[cfg(not(feature = "no_closure"))]
+        {
+            let mut index = 0;
+            state.external_vars.retain(|v| {
+                index += 1;
+                index <= leading.num_external_vars || !is_declared(&v.name)
+            });
+        }
+
+        let undefined_vars = std::mem::replace(&mut state.undefined_vars, leading.undefined_vars);
+
+        undefined_vars
+            .into_iter()
+            .flatten()
+            .filter(|v| !is_declared(&v.name))
+            .try_for_each(|Ident { name, pos }| state.report_undefined_var(name, pos))
+    }
+
+    /// Parse the `for` clause of an array or object map comprehension, following its leading item.
+    ///
+    /// The key of an object map comprehension is given as a property name, together with whether
+    /// it is a variable (i.e. unquoted), which must be one of the loop variables.
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    fn parse_comprehension(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        key: Option<(Ident, bool)>,
+        value: Expr,
+        leading: LeadingItem,
+    ) -> ParseResult<Box<crate::ast::ComprehensionExpr>> {
+        // ... for ...
+        let settings = settings.level_up_with_position(eat_token(state.input, &Token::For))?;
+
+        // ... for name in ...
+        let (var, counter) = self.parse_for_vars(state)?;
+
+        let declared = counter.iter().chain(std::iter::once(&var));
+        Self::end_leading_item(state, leading, &declared.collect::<StaticVec<_>>())?;
+
+        // ... for name in expr ...
+        let iterable = self.parse_expr(state, settings)?.ensure_iterable()?;
+
+        let prev_stack_len = state.stack.len();
+
+        if let Some(ref counter) = counter {
+            state.stack.push(counter.name.clone(), ());
+        }
+        state.stack.push(&var.name, ());
+
+        let key = match key {
+            Some((Ident { name, pos }, true)) => {
+                // A bare key must be a loop variable; other keys are computed (e.g. `[expr]`)
+                if name != var.name && counter.as_ref().map_or(true, |c| name != c.name) {
+                    return Err(PERR::WrongComprehensionKey(name.to_string()).into_err(pos));
+                }
+
+                let (index, ..) = self.access_var(state, &name, pos);
+
+                let short_index = index
+                    .and_then(|x| u8::try_from(x.get()).ok())
+                    .and_then(NonZeroU8::new);
+
+                Some(Expr::Variable(
+                    #[cfg(not(feature = "no_module"))]
+                    (index, name, crate::ast::Namespace::NONE, 0).into(),
+                    #[cfg(feature = "no_module")]
+                    (index, name).into(),
+                    short_index,
+                    pos,
+                ))
+            }
+            Some((Ident { name, pos }, false)) => Some(Expr::StringConstant(name, pos)),
+            None => None,
+        };
+
+        // ... for name in expr if expr
+        let condition = if match_token(state.input, &Token::If).0 {
+            Some(self.parse_expr(state, settings)?.ensure_bool_expr()?)
+        } else {
+            None
+        };
+
+        state.stack.rewind(prev_stack_len);
+
+        Ok(crate::ast::ComprehensionExpr {
+            key,
+            value,
+            var,
+            counter,
+            iterable,
+            condition,
+        }
+        .into())
+    }
+
     /// Parse a switch expression.
     fn parse_switch(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
         // switch ...
@@ -1122,12 +1717,16 @@ impl Engine {
         let mut expressions = FnArgsVec::<BinaryExpr>::new();
         let mut cases = StraightHashMap::<CaseBlocksList>::default();
         let mut ranges = StaticVec::<RangeCase>::new();
+        let mut patterns = StaticVec::<(SwitchPattern, usize)>::new();
         let mut def_case = None;
         let mut def_case_pos = Position::NONE;
 
         loop {
             const MISSING_RBRACE: &str = "to end this switch block";
 
+            let prev_stack_len = state.stack.len();
+            let mut pattern = None;
+
             let (case_expr_list, condition) = match state.input.peek().unwrap() {
                 (Token::RightBrace, ..) => {
                     eat_token(state.input, &Token::RightBrace);
@@ -1162,15 +1761,64 @@ impl Engine {
                     let mut case_expr_list = StaticVec::new_const();
 
                     loop {
-                        let filter = state.expr_filter;
-                        state.expr_filter = |t| t != &Token::Pipe;
-                        let expr = self.parse_expr(state, settings);
-                        state.expr_filter = filter;
-
-                        match expr {
-                            Ok(expr) => case_expr_list.push(expr),
-                            Err(err) => {
-                                return Err(PERR::ExprExpected("literal".into()).into_err(err.1))
+                        let is_pattern = match state.input.peek().unwrap() {
+                            (Token::LeftParen, ..) => true,
+                            (Token::Identifier(s), ..) => state.enums.contains_key(&**s),
+                            _ => false,
+                        };
+
+                        if is_pattern {
+                            let pos = state.input.peek().unwrap().1;
+                            let p = self.parse_switch_pattern(state, settings.level_up()?)?;
+
+                            if let Some(value) = p.to_literal() {
+                                case_expr_list.push(Expr::from_dynamic(value, pos));
+                            } else {
+                                let (has_pipe, pipe_pos) = match_token(state.input, &Token::Pipe);
+
+                                if has_pipe || !case_expr_list.is_empty() {
+                                    return Err(LexError::ImproperSymbol(
+                                        Token::Pipe.literal_syntax().into(),
+                                        "Patterns with bindings or wildcards cannot have alternatives"
+                                            .into(),
+                                    )
+                                    .into_err(if has_pipe { pipe_pos } else { pos }));
+                                }
+
+                                // Make the bound variables visible to the condition and the statement
+                                let mut names = StaticVec::<&ImmutableString>::new_const();
+                                let mut duplicated = None;
+
+                                p.for_each_binding(&mut |x| {
+                                    if names.contains(&&x.name) {
+                                        duplicated.get_or_insert(x);
+                                    }
+                                    names.push(&x.name);
+                                });
+
+                                if let Some(x) = duplicated {
+                                    return Err(PERR::DuplicatedVariable(x.name.to_string())
+                                        .into_err(x.pos));
+                                }
+
+                                names.into_iter().for_each(|name| {
+                                    state.stack.push(name.clone(), ());
+                                });
+
+                                pattern = Some(p);
+                                break;
+                            }
+                        } else {
+                            let filter = state.expr_filter;
+                            state.expr_filter = |t| t != &Token::Pipe;
+                            let expr = self.parse_expr(state, settings);
+                            state.expr_filter = filter;
+
+                            match expr {
+                                Ok(expr) => case_expr_list.push(expr),
+                                Err(err) => {
+                                    return Err(PERR::ExprExpected("literal".into()).into_err(err.1))
+                                }
                             }
                         }
 
@@ -1214,6 +1862,8 @@ impl Engine {
                     (Expr::Stmt(stmt_block.into()), need_comma)
                 };
 
+            state.stack.rewind(prev_stack_len);
+
             expressions.push(BinaryExpr {
                 lhs: condition,
                 rhs: action_expr,
@@ -1221,7 +1871,9 @@ impl Engine {
 
             let index = expressions.len() - 1;
 
-            if case_expr_list.is_empty() {
+            if let Some(pattern) = pattern {
+                patterns.push((pattern, index));
+            } else if case_expr_list.is_empty() {
                 def_case = Some(index);
             } else {
                 for expr in case_expr_list {
@@ -1250,6 +1902,8 @@ impl Engine {
                             Dynamic(Union::Float(..)) => true,
                             #[cfg(feature = "decimal")]
                             Dynamic(Union::Decimal(..)) => true,
+                            #[cfg(feature = "bigint")]
+                            Dynamic(Union::BigInt(..)) => true,
                             _ => false,
                         };
 
@@ -1297,27 +1951,595 @@ impl Engine {
         expressions.shrink_to_fit();
         cases.shrink_to_fit();
         ranges.shrink_to_fit();
+        patterns.shrink_to_fit();
 
         let cases = SwitchCasesCollection {
             expressions,
             cases,
             ranges,
             def_case,
+            patterns,
         };
 
         Ok(Stmt::Switch((item, cases).into(), settings.pos))
     }
 
-    /// Parse a primary expression.
-    fn parse_primary(
+    /// Parse a destructuring pattern in a `switch` case.
+    fn parse_switch_pattern(
         &self,
         state: &mut ParseState,
-        mut settings: ParseSettings,
-        options: ChainingFlags,
-    ) -> ParseResult<Expr> {
-        let (next_token, next_token_pos) = state.input.peek().unwrap();
+        settings: ParseSettings,
+    ) -> ParseResult<SwitchPattern> {
+        match state.input.peek().unwrap() {
+            // _
+            (Token::Underscore, ..) => {
+                eat_token(state.input, &Token::Underscore);
+                Ok(SwitchPattern::Wildcard)
+            }
+            // ( ...
+            (Token::LeftParen, ..) => {
+                eat_token(state.input, &Token::LeftParen);
 
-        settings.pos = *next_token_pos;
+                let first = self.parse_switch_pattern(state, settings.level_up()?)?;
+
+                // ( pattern ) - grouping
+                if match_token(state.input, &Token::RightParen).0 {
+                    return Ok(first);
+                }
+
+                let mut items = ThinVec::new();
+                items.push(first);
+
+                self.parse_switch_pattern_list(state, settings, &mut items, "this tuple pattern")?;
+
+                Ok(SwitchPattern::Tuple(items))
+            }
+            // Enum :: Variant ...
+            (Token::Identifier(s), ..) if state.enums.contains_key(&**s) => {
+                let name = match state.input.next().unwrap() {
+                    (Token::Identifier(s), ..) => self.get_interned_string(*s),
+                    token => unreachable!("Token::Identifier expected but gets {:?}", token),
+                };
+                let (variant, num_fields, pos) = self.parse_enum_variant_name(state, &name)?;
+
+                let mut fields = ThinVec::new();
+
+                match state.input.peek().unwrap() {
+                    (Token::Unit, ..) => {
+                        eat_token(state.input, &Token::Unit);
+                    }
+                    (Token::LeftParen, ..) => {
+                        eat_token(state.input, &Token::LeftParen);
+
+                        if !match_token(state.input, &Token::RightParen).0 {
+                            fields.push(self.parse_switch_pattern(state, settings.level_up()?)?);
+
+                            let msg = "this enum variant pattern";
+                            self.parse_switch_pattern_list(state, settings, &mut fields, msg)?;
+                        }
+                    }
+                    _ => (),
+                }
+
+                if fields.len() != num_fields {
+                    let variant = format!("{name}::{variant}");
+                    return Err(PERR::EnumVariantFields(variant, num_fields).into_err(pos));
+                }
+
+                Ok(SwitchPattern::Enum((name, variant, fields).into()))
+            }
+            // variable binding
+            (Token::Identifier(..), ..) => {
+                let (name, pos) = parse_var_name(state.input)?;
+                let name = self.get_interned_string(name);
+                Ok(SwitchPattern::Bind(Ident { name, pos }))
+            }
+            // literal
+            (.., pos) => {
+                let pos = *pos;
+
+                let filter = state.expr_filter;
+                state.expr_filter = |t| t != &Token::Pipe;
+                let expr = self.parse_expr(state, settings.level_up()?);
+                state.expr_filter = filter;
+
+                let value = expr
+                    .ok()
+                    .and_then(|expr| expr.get_literal_value(None))
+                    .ok_or_else(|| PERR::ExprExpected("a literal".into()).into_err(pos))?;
+
+                Ok(SwitchPattern::Value(value.into()))
+            }
+        }
+    }
+
+    /// Parse the remaining items of a parenthesized list of patterns, up to the closing `)`.
+    ///
+    /// The first item must have already been parsed.
+    fn parse_switch_pattern_list(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        items: &mut ThinVec<SwitchPattern>,
+        target: &str,
+    ) -> ParseResult<()> {
+        loop {
+            match state.input.next().unwrap() {
+                (Token::RightParen, ..) => return Ok(()),
+                (Token::Comma, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightParen.into(),
+                        format!("to end {target}"),
+                    )
+                    .into_err(pos))
+                }
+            }
+
+            // Trailing comma
+            if match_token(state.input, &Token::RightParen).0 {
+                return Ok(());
+            }
+
+            items.push(self.parse_switch_pattern(state, settings.level_up()?)?);
+        }
+    }
+
+    /// Parse an `enum` definition.
+    fn parse_enum(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
+        // enum ...
+        let settings = settings.level_up_with_position(eat_token(state.input, &Token::Enum))?;
+
+        if !settings.has_flag(ParseSettingFlags::GLOBAL_LEVEL) {
+            return Err(PERR::WrongEnumDefinition.into_err(settings.pos));
+        }
+
+        let (name, name_pos) = parse_var_name(state.input)?;
+        let name = self.get_interned_string(name);
+
+        #[cfg(not(feature = "no_object"))]
+        if state.structs.contains_key(&name) {
+            return Err(PERR::EnumDuplicatedDefinition(name.to_string()).into_err(name_pos));
+        }
+        if state.enums.contains_key(&name) {
+            return Err(PERR::EnumDuplicatedDefinition(name.to_string()).into_err(name_pos));
+        }
+
+        match state.input.next().unwrap() {
+            (Token::LeftBrace, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(PERR::MissingToken(
+                    Token::LeftBrace.into(),
+                    format!("to start the definition of enum {name}"),
+                )
+                .into_err(pos))
+            }
+        }
+
+        let mut variants = StaticVec::<(ImmutableString, usize)>::new_const();
+
+        loop {
+            match state.input.peek().unwrap() {
+                (Token::RightBrace, ..) => {
+                    eat_token(state.input, &Token::RightBrace);
+                    break;
+                }
+                (Token::LexError(err), pos) => return Err(err.clone().into_err(*pos)),
+                _ => (),
+            }
+
+            let (variant, pos) = parse_var_name(state.input)?;
+            let variant = self.get_interned_string(variant);
+
+            if variants.iter().any(|(v, ..)| *v == variant) {
+                return Err(
+                    PERR::EnumDuplicatedVariant(name.to_string(), variant.to_string())
+                        .into_err(pos),
+                );
+            }
+
+            // Fields are simply counted; their names serve only as documentation
+            let mut num_fields = 0;
+
+            if match_token(state.input, &Token::Unit).0 {
+                // Variant() - no fields
+            } else if match_token(state.input, &Token::LeftParen).0 {
+                loop {
+                    match state.input.next().unwrap() {
+                        (Token::RightParen, ..) => break,
+                        (Token::Identifier(..), ..) => num_fields += 1,
+                        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                        (.., pos) => return Err(PERR::VariableExpected.into_err(pos)),
+                    }
+
+                    match state.input.next().unwrap() {
+                        (Token::RightParen, ..) => break,
+                        (Token::Comma, ..) => (),
+                        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                        (.., pos) => {
+                            return Err(PERR::MissingToken(
+                                Token::RightParen.into(),
+                                format!("to end the fields of {name}::{variant}"),
+                            )
+                            .into_err(pos))
+                        }
+                    }
+                }
+            }
+
+            variants.push((variant, num_fields));
+
+            match state.input.next().unwrap() {
+                (Token::RightBrace, ..) => break,
+                (Token::Comma, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::Comma.into(),
+                        format!("to separate the variants of enum {name}"),
+                    )
+                    .into_err(pos))
+                }
+            }
+        }
+
+        variants.shrink_to_fit();
+        state.enums.insert(name, variants);
+
+        Ok(Stmt::Noop(settings.pos))
+    }
+
+    /// Parse the `::` and variant name following the name of an `enum`.
+    ///
+    /// Returns the variant name, its number of fields and its position.
+    fn parse_enum_variant_name(
+        &self,
+        state: &mut ParseState,
+        name: &str,
+    ) -> ParseResult<(ImmutableString, usize, Position)> {
+        match state.input.next().unwrap() {
+            (Token::DoubleColon, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(PERR::MissingToken(
+                    Token::DoubleColon.into(),
+                    format!("to qualify a variant of enum {name}"),
+                )
+                .into_err(pos))
+            }
+        }
+
+        let (variant, pos) = parse_var_name(state.input)?;
+
+        let num_fields = state.enums[name]
+            .iter()
+            .find(|(v, ..)| *v == variant)
+            .map(|&(.., n)| n)
+            .ok_or_else(|| {
+                PERR::EnumVariantNotFound(name.to_string(), variant.to_string()).into_err(pos)
+            })?;
+
+        Ok((self.get_interned_string(variant), num_fields, pos))
+    }
+
+    /// Parse the construction of an `enum` variant value.
+    fn parse_enum_value(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        name: ImmutableString,
+    ) -> ParseResult<Expr> {
+        // Enum :: Variant ...
+        let (variant, num_fields, pos) = self.parse_enum_variant_name(state, &name)?;
+
+        let mut fields = ThinVec::new();
+
+        match state.input.peek().unwrap() {
+            (Token::Unit, ..) => {
+                eat_token(state.input, &Token::Unit);
+            }
+            (Token::LeftParen, ..) => {
+                eat_token(state.input, &Token::LeftParen);
+
+                loop {
+                    match state.input.peek().unwrap() {
+                        (Token::RightParen, ..) => {
+                            eat_token(state.input, &Token::RightParen);
+                            break;
+                        }
+                        (Token::EOF, pos) => {
+                            return Err(PERR::MissingToken(
+                                Token::RightParen.into(),
+                                format!("to end the fields of {name}::{variant}"),
+                            )
+                            .into_err(*pos))
+                        }
+                        _ => fields.push(self.parse_expr(state, settings.level_up()?)?),
+                    }
+
+                    match state.input.next().unwrap() {
+                        (Token::RightParen, ..) => break,
+                        (Token::Comma, ..) => (),
+                        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                        (.., pos) => {
+                            return Err(PERR::MissingToken(
+                                Token::Comma.into(),
+                                format!("to separate the fields of {name}::{variant}"),
+                            )
+                            .into_err(pos))
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        if fields.len() != num_fields {
+            let variant = format!("{name}::{variant}");
+            return Err(PERR::EnumVariantFields(variant, num_fields).into_err(pos));
+        }
+
+        fields.shrink_to_fit();
+
+        Ok(Expr::Enum((name, variant, fields).into(), settings.pos))
+    }
+
+    /// Parse a `struct` definition.
+    #[cfg(not(feature = "no_object"))]
+    fn parse_struct(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        #[cfg(not(feature = "no_function"))]
+        #[cfg(feature = "metadata")]
+        comments: impl IntoIterator<Item = crate::Identifier>,
+    ) -> ParseResult<Stmt> {
+        // struct ...
+        let settings = settings.level_up_with_position(eat_token(state.input, &Token::Struct))?;
+
+        if !settings.has_flag(ParseSettingFlags::GLOBAL_LEVEL) {
+            return Err(PERR::WrongStructDefinition.into_err(settings.pos));
+        }
+
+        let (name, name_pos) = parse_var_name(state.input)?;
+        let name = self.get_interned_string(name);
+
+        if state.structs.contains_key(&name) || state.enums.contains_key(&name) {
+            return Err(PERR::StructDuplicatedDefinition(name.to_string()).into_err(name_pos));
+        }
+
+        match state.input.next().unwrap() {
+            (Token::LeftBrace, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(PERR::MissingToken(
+                    Token::LeftBrace.into(),
+                    format!("to start the definition of struct {name}"),
+                )
+                .into_err(pos))
+            }
+        }
+
+        let mut fields = StaticVec::<ImmutableString>::new_const();
+
+        loop {
+            match state.input.peek().unwrap() {
+                (Token::RightBrace, ..) => {
+                    eat_token(state.input, &Token::RightBrace);
+                    break;
+                }
+                (Token::LexError(err), pos) => return Err(err.clone().into_err(*pos)),
+                _ => (),
+            }
+
+            let (field, pos) = parse_var_name(state.input)?;
+            let field = self.get_interned_string(field);
+
+            if fields.contains(&field) {
+                return Err(
+                    PERR::StructDuplicatedField(name.to_string(), field.to_string()).into_err(pos),
+                );
+            }
+
+            fields.push(field);
+
+            match state.input.next().unwrap() {
+                (Token::RightBrace, ..) => break,
+                (Token::Comma, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::Comma.into(),
+                        format!("to separate the fields of struct {name}"),
+                    )
+                    .into_err(pos))
+                }
+            }
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        state.struct_types.push(crate::types::CustomTypeInfo {
+            type_name: name.as_str().into(),
+            display_name: name.as_str().into(),
+            #[cfg(feature = "metadata")]
+            comments: comments.into_iter().collect(),
+            fields: Some(fields.iter().map(|f| f.as_str().into()).collect()),
+        });
+
+        let template = fields.into_iter().map(|f| (f, Dynamic::UNIT));
+        state
+            .structs
+            .insert(name.clone(), crate::StructValue::new(name, template));
+
+        Ok(Stmt::Noop(settings.pos))
+    }
+
+    /// Parse the construction of a `struct` value.
+    #[cfg(not(feature = "no_object"))]
+    fn parse_struct_value(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        name: ImmutableString,
+    ) -> ParseResult<Expr> {
+        // Struct { ...
+        eat_token(state.input, &Token::LeftBrace);
+
+        let template = state.structs[&name].clone();
+        let mut fields = StaticVec::<(Ident, Expr)>::new_const();
+
+        loop {
+            match state.input.peek().unwrap() {
+                (Token::RightBrace, ..) => {
+                    eat_token(state.input, &Token::RightBrace);
+                    break;
+                }
+                (Token::EOF, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightBrace.into(),
+                        format!("to end the fields of struct {name}"),
+                    )
+                    .into_err(*pos))
+                }
+                _ => (),
+            }
+
+            let (field, pos) = match state.input.next().unwrap() {
+                (Token::Identifier(s), pos) => (self.get_interned_string(*s), pos),
+                (Token::Reserved(s), pos) if is_valid_identifier(&s) => {
+                    return Err(PERR::Reserved(s.to_string()).into_err(pos));
+                }
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => return Err(PERR::PropertyExpected.into_err(pos)),
+            };
+
+            if !template.contains_field(&field) {
+                return Err(
+                    PERR::StructFieldNotFound(name.to_string(), field.to_string()).into_err(pos),
+                );
+            }
+            if fields.iter().any(|(f, ..)| f.name == field) {
+                return Err(
+                    PERR::StructDuplicatedField(name.to_string(), field.to_string()).into_err(pos),
+                );
+            }
+
+            match state.input.next().unwrap() {
+                (Token::Colon, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::Colon.into(),
+                        format!("to follow the field '{field}' of struct {name}"),
+                    )
+                    .into_err(pos))
+                }
+            }
+
+            let expr = self.parse_expr(state, settings.level_up()?)?;
+            fields.push((Ident { name: field, pos }, expr));
+
+            match state.input.next().unwrap() {
+                (Token::RightBrace, ..) => break,
+                (Token::Comma, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::Comma.into(),
+                        format!("to separate the fields of struct {name}"),
+                    )
+                    .into_err(pos))
+                }
+            }
+        }
+
+        if let Some((field, ..)) = template
+            .iter()
+            .find(|&(field, ..)| !fields.iter().any(|(f, ..)| f.name == field))
+        {
+            return Err(
+                PERR::StructMissingField(name.to_string(), field.to_string())
+                    .into_err(settings.pos),
+            );
+        }
+
+        fields.shrink_to_fit();
+
+        Ok(Expr::Struct((fields, template).into(), settings.pos))
+    }
+
+    /// Parse the remaining items of a tuple literal.
+    ///
+    /// The first item, and the comma following it, must have already been parsed.
+    fn parse_tuple_literal(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        first: Expr,
+    ) -> ParseResult<Expr> {
+        // ( expr , ...
+        let mut items = ThinVec::new();
+        items.push(first);
+
+        loop {
+            const MISSING_RPAREN: &str = "to end this tuple literal";
+
+            match state.input.peek().unwrap() {
+                (Token::RightParen, ..) => {
+                    eat_token(state.input, &Token::RightParen);
+                    break;
+                }
+                (Token::EOF, pos) => {
+                    return Err(
+                        PERR::MissingToken(Token::RightParen.into(), MISSING_RPAREN.into())
+                            .into_err(*pos),
+                    )
+                }
+                _ => (),
+            }
+
+            #[cfg(not(feature = "unchecked"))]
+            if self.max_array_size() > 0 && items.len() >= self.max_array_size() {
+                return Err(PERR::LiteralTooLarge(
+                    "Size of tuple literal".into(),
+                    self.max_array_size(),
+                )
+                .into_err(state.input.peek().unwrap().1));
+            }
+
+            items.push(self.parse_expr(state, settings.level_up()?)?);
+
+            match state.input.next().unwrap() {
+                (Token::RightParen, ..) => break,
+                (Token::Comma, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::Comma.into(),
+                        "to separate the items of this tuple literal".into(),
+                    )
+                    .into_err(pos))
+                }
+            }
+        }
+
+        items.shrink_to_fit();
+
+        Ok(Expr::Tuple(items, settings.pos))
+    }
+
+    /// Parse a primary expression.
+    fn parse_primary(
+        &self,
+        state: &mut ParseState,
+        mut settings: ParseSettings,
+        options: ChainingFlags,
+    ) -> ParseResult<Expr> {
+        let (next_token, next_token_pos) = state.input.peek().unwrap();
+
+        settings.pos = *next_token_pos;
 
         let root_expr = match next_token {
             _ if !(state.expr_filter)(next_token) => {
@@ -1358,6 +2580,13 @@ impl Engine {
                 state.input.next();
                 Expr::DynamicConstant(Box::new(x.into()), settings.pos)
             }
+            #[cfg(feature = "bigint")]
+            Token::BigIntConstant(..) => match state.input.next().unwrap() {
+                (Token::BigIntConstant(x), ..) => {
+                    Expr::DynamicConstant(Box::new(x.0.into()), settings.pos)
+                }
+                token => unreachable!("Token::BigIntConstant expected but gets {:?}", token),
+            },
 
             // { - block statement as expression
             Token::LeftBrace if settings.has_option(LangOptions::STMT_EXPR) => {
@@ -1376,6 +2605,8 @@ impl Engine {
                 match state.input.next().unwrap() {
                     // ( ... )
                     (Token::RightParen, ..) => expr,
+                    // ( ... , - tuple literal
+                    (Token::Comma, ..) => self.parse_tuple_literal(state, settings, expr)?,
                     // ( <error>
                     (Token::LexError(err), pos) => return Err(err.into_err(pos)),
                     // ( ... ???
@@ -1398,15 +2629,25 @@ impl Engine {
                 if self.allow_looping() && settings.has_option(LangOptions::LOOP_EXPR) =>
             {
                 Expr::Stmt(Box::new(
-                    self.parse_while_loop(state, settings.level_up()?)?.into(),
+                    self.parse_while_loop(state, settings.level_up()?, None)?
+                        .into(),
                 ))
             }
             Token::Do if self.allow_looping() && settings.has_option(LangOptions::LOOP_EXPR) => {
-                Expr::Stmt(Box::new(self.parse_do(state, settings.level_up()?)?.into()))
+                Expr::Stmt(Box::new(
+                    self.parse_do(state, settings.level_up()?, None)?.into(),
+                ))
             }
             Token::For if self.allow_looping() && settings.has_option(LangOptions::LOOP_EXPR) => {
                 Expr::Stmt(Box::new(
-                    self.parse_for(state, settings.level_up()?)?.into(),
+                    self.parse_for(state, settings.level_up()?, None)?.into(),
+                ))
+            }
+            Token::Label(..)
+                if self.allow_looping() && settings.has_option(LangOptions::LOOP_EXPR) =>
+            {
+                Expr::Stmt(Box::new(
+                    self.parse_labeled_loop(state, settings.level_up()?)?.into(),
                 ))
             }
             // Switch statement is allowed to act as expressions
@@ -1430,46 +2671,51 @@ impl Engine {
 
             // Interpolated string
             Token::InterpolatedString(..) => {
-                let mut segments = ThinVec::new();
+                let mut texts = StaticVec::<SmartString>::new();
+                let mut positions = StaticVec::<Position>::new();
+                let mut exprs = StaticVec::<Expr>::new();
                 let settings = settings.level_up()?;
 
                 match state.input.next().unwrap() {
-                    (Token::InterpolatedString(s), ..) if s.is_empty() => (),
                     (Token::InterpolatedString(s), pos) => {
-                        segments.push(Expr::StringConstant(self.get_interned_string(*s), pos))
+                        texts.push(*s);
+                        positions.push(pos);
                     }
                     token => {
                         unreachable!("Token::InterpolatedString expected but gets {:?}", token)
                     }
                 }
 
+                // Is this a multi-line heredoc literal?
+                let is_heredoc =
+                    std::mem::take(&mut state.tokenizer_control.borrow_mut().is_within_heredoc);
+
                 loop {
-                    let expr = match self.parse_block(state, settings, false)? {
-                        block @ Stmt::Block(..) => Expr::Stmt(Box::new(block.into())),
+                    let mut settings = settings;
+                    settings.flags |= ParseSettingFlags::ALLOW_FORMAT_SPEC;
+
+                    match self.parse_block(state, settings, false)? {
+                        block @ Stmt::Block(..) => exprs.push(Expr::Stmt(Box::new(block.into()))),
                         stmt => unreachable!("Stmt::Block expected but gets {:?}", stmt),
-                    };
-                    match expr {
-                        Expr::StringConstant(s, ..) if s.is_empty() => (),
-                        _ => segments.push(expr),
                     }
 
                     // Make sure to parse the following as text
-                    state.tokenizer_control.borrow_mut().is_within_text = true;
+                    {
+                        let control = &mut *state.tokenizer_control.borrow_mut();
+                        control.is_within_text = true;
+                        control.is_within_heredoc = is_heredoc;
+                    }
 
                     match state.input.next().unwrap() {
                         (Token::StringConstant(s), pos) => {
-                            if !s.is_empty() {
-                                segments
-                                    .push(Expr::StringConstant(self.get_interned_string(*s), pos));
-                            }
+                            texts.push(*s);
+                            positions.push(pos);
                             // End the interpolated string if it is terminated by a back-tick.
                             break;
                         }
                         (Token::InterpolatedString(s), pos) => {
-                            if !s.is_empty() {
-                                segments
-                                    .push(Expr::StringConstant(self.get_interned_string(*s), pos));
-                            }
+                            texts.push(*s);
+                            positions.push(pos);
                         }
                         (Token::LexError(err), pos) => match *err {
                             LexError::UnterminatedString | LexError::StringTooLong(_) => {
@@ -1477,10 +2723,43 @@ impl Engine {
                             }
                             _ => unreachable!("improper lex error: {:?}", err),
                         },
-                        (token, ..) => unreachable!(
-                            "string within an interpolated string literal expected but gets {:?}",
-                            token
-                        ),
+                        // `${ ... <EOF> - the block is left unterminated when recovering from errors
+                        (Token::EOF, pos) => {
+                            state.report_error(LexError::UnterminatedString.into_err(pos))?;
+                            // Close the interpolated string, keeping the expression
+                            texts.push(SmartString::new_const());
+                            positions.push(pos);
+                            break;
+                        }
+                        (.., pos) => {
+                            let err = PERR::MissingToken(
+                                Token::RightBrace.into(),
+                                "to terminate this interpolated expression".into(),
+                            )
+                            .into_err(pos);
+                            state.report_error(err)?;
+                            texts.push(SmartString::new_const());
+                            positions.push(pos);
+                            break;
+                        }
+                    }
+                }
+
+                if is_heredoc {
+                    crate::tokenizer::dedent_heredoc(&mut texts);
+                }
+
+                let mut segments = ThinVec::new();
+                let mut exprs = exprs.into_iter();
+
+                for (s, pos) in texts.into_iter().zip(positions) {
+                    if !s.is_empty() {
+                        segments.push(Expr::StringConstant(self.get_interned_string(s), pos));
+                    }
+                    match exprs.next() {
+                        Some(Expr::StringConstant(s, ..)) if s.is_empty() => (),
+                        Some(expr) => segments.push(expr),
+                        None => (),
                     }
                 }
 
@@ -1521,6 +2800,17 @@ impl Engine {
                 };
 
                 match state.input.peek().unwrap() {
+                    // Enum variant
+                    (Token::DoubleColon, ..) if state.enums.contains_key(&**s) => {
+                        let name = self.get_interned_string(*s);
+                        self.parse_enum_value(state, settings, name)?
+                    }
+                    // Struct value
+                    #[cfg(not(feature = "no_object"))]
+                    (Token::LeftBrace, ..) if state.structs.contains_key(&**s) => {
+                        let name = self.get_interned_string(*s);
+                        self.parse_struct_value(state, settings, name)?
+                    }
                     // Function call
                     (Token::LeftParen | Token::Bang | Token::Unit, _) => {
                         // Once the identifier consumed we must enable next variables capturing
@@ -1564,9 +2854,10 @@ impl Engine {
                                 .external_constants
                                 .map_or(false, |scope| scope.contains(&s))
                         {
-                            return Err(
-                                PERR::VariableUndefined(s.to_string()).into_err(settings.pos)
-                            );
+                            state.report_undefined_var(
+                                self.get_interned_string(s.as_str()),
+                                settings.pos,
+                            )?;
                         }
 
                         let short_index = index
@@ -1901,7 +3192,7 @@ impl Engine {
                         #[cfg(not(feature = "no_module"))]
                         namespace: crate::ast::Namespace::NONE,
                         name: self.get_interned_string("-"),
-                        hashes: FnCallHashes::from_native_only(calc_fn_hash(None, "-", 1)),
+                        hashes: FnCallHashes::from_hash(calc_fn_hash(None, "-", 1)),
                         args: IntoIterator::into_iter([expr]).collect(),
                         op_token: Some(token),
                         capture_parent_scope: false,
@@ -1924,7 +3215,7 @@ impl Engine {
                         #[cfg(not(feature = "no_module"))]
                         namespace: crate::ast::Namespace::NONE,
                         name: self.get_interned_string("+"),
-                        hashes: FnCallHashes::from_native_only(calc_fn_hash(None, "+", 1)),
+                        hashes: FnCallHashes::from_hash(calc_fn_hash(None, "+", 1)),
                         args: IntoIterator::into_iter([expr]).collect(),
                         op_token: Some(token),
                         capture_parent_scope: false,
@@ -1941,7 +3232,7 @@ impl Engine {
                     #[cfg(not(feature = "no_module"))]
                     namespace: crate::ast::Namespace::NONE,
                     name: self.get_interned_string("!"),
-                    hashes: FnCallHashes::from_native_only(calc_fn_hash(None, "!", 1)),
+                    hashes: FnCallHashes::from_hash(calc_fn_hash(None, "!", 1)),
                     args: {
                         let expr = self.parse_unary(state, settings.level_up()?)?;
                         IntoIterator::into_iter([expr]).collect()
@@ -2118,6 +3409,10 @@ impl Engine {
                 );
                 Err(LexError::ImproperSymbol(f.name.to_string(), err_msg).into_err(func_pos))
             }
+            // lhs.func(...args)
+            (.., Expr::FnCall(f, func_pos)) if f.has_spread_args() => {
+                Err(spread_in_method_call_err(&f.name).into_err(func_pos))
+            }
             // lhs.func!(...)
             (.., Expr::FnCall(f, func_pos)) if f.capture_parent_scope => {
                 Err(PERR::MalformedCapture(
@@ -2180,6 +3475,10 @@ impl Engine {
                         };
                         Ok(Expr::Dot(BinaryExpr { lhs, rhs }.into(), op_flags, op_pos))
                     }
+                    // lhs.func(...args).dot_rhs or lhs.func(...args)[idx_rhs]
+                    Expr::FnCall(f, func_pos) if f.has_spread_args() => {
+                        Err(spread_in_method_call_err(&f.name).into_err(func_pos))
+                    }
                     // lhs.func().dot_rhs or lhs.func()[idx_rhs]
                     Expr::FnCall(mut f, func_pos) => {
                         // Recalculate hash
@@ -2326,11 +3625,18 @@ impl Engine {
             let hash = calc_fn_hash(None, &op, 2);
             let native_only = !is_valid_identifier(&op);
 
+            // Script-defined operator functions can overload the operator
+            let hashes = if is_script_operator(&op) {
+                FnCallHashes::from_hash(hash)
+            } else {
+                FnCallHashes::from_native_only(hash)
+            };
+
             let mut op_base = FnCallExpr {
                 #[cfg(not(feature = "no_module"))]
                 namespace: crate::ast::Namespace::NONE,
                 name: self.get_interned_string(&op),
-                hashes: FnCallHashes::from_native_only(hash),
+                hashes,
                 args: IntoIterator::into_iter([root, rhs]).collect(),
                 op_token: native_only.then(|| op_token.clone()),
                 capture_parent_scope: false,
@@ -2422,6 +3728,23 @@ impl Engine {
                 }
                 Token::ExclusiveRange | Token::InclusiveRange => op_base.into_fn_call_expr(pos),
 
+                // x |> f(y) -> f(x, y)
+                Token::PipeArrow => {
+                    match op_base.args[1].take() {
+                        Expr::FnCall(mut f, func_pos) if f.op_token.is_none() => {
+                            f.args.insert(0, op_base.args[0].take());
+                            f.recalc_hashes();
+                            Expr::FnCall(f, func_pos)
+                        }
+                        rhs => return Err(LexError::ImproperSymbol(
+                            op_token.literal_syntax().into(),
+                            "The right-hand side of '|>' must be a function call, e.g. x |> f(y)"
+                                .into(),
+                        )
+                        .into_err(rhs.start_position())),
+                    }
+                }
+
                 #[cfg(not(feature = "no_custom_syntax"))]
                 Token::Custom(s) if self.custom_keywords.contains_key(&*s) => {
                     op_base.hashes = if native_only {
@@ -2718,6 +4041,22 @@ impl Engine {
         self.parse_binary_op(state, settings, precedence, lhs)
     }
 
+    /// Parse an expression, optionally prefixed by the spread operator `...`.
+    fn parse_expr_or_spread(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+    ) -> ParseResult<Expr> {
+        let (is_spread, pos) = match_token(state.input, &Token::Spread);
+        let expr = self.parse_expr(state, settings)?;
+
+        Ok(if is_spread {
+            Expr::Spread(expr.into(), pos)
+        } else {
+            expr
+        })
+    }
+
     /// Parse an if statement.
     fn parse_if(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
         // if ...
@@ -2753,6 +4092,7 @@ impl Engine {
         &self,
         state: &mut ParseState,
         settings: ParseSettings,
+        label: Option<SmartString>,
     ) -> ParseResult<Stmt> {
         let mut settings = settings.level_up()?;
 
@@ -2770,7 +4110,11 @@ impl Engine {
         settings.pos = token_pos;
         settings.flags |= ParseSettingFlags::BREAKABLE;
 
-        let body = self.parse_block(state, settings, false)?.into();
+        state.loop_labels.push(label);
+        let body = self.parse_block(state, settings, false);
+        state.loop_labels.pop();
+
+        let body = body?.into();
         let branch = StmtBlock::NONE;
 
         Ok(Stmt::While(
@@ -2779,8 +4123,57 @@ impl Engine {
         ))
     }
 
+    /// Parse a labeled loop.
+    fn parse_labeled_loop(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+    ) -> ParseResult<Stmt> {
+        // 'label ...
+        let label = match state.input.next().unwrap() {
+            (Token::Label(label), pos) => {
+                if state
+                    .loop_labels
+                    .iter()
+                    .any(|l| l.as_ref() == Some(&*label))
+                {
+                    return Err(PERR::LabelDuplicated(label.to_string()).into_err(pos));
+                }
+                *label
+            }
+            token => unreachable!("Token::Label expected but gets {:?}", token),
+        };
+
+        // 'label : ...
+        match state.input.next().unwrap() {
+            (Token::Colon, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(
+                    PERR::MissingToken(Token::Colon.into(), "after the loop label".into())
+                        .into_err(pos),
+                )
+            }
+        }
+
+        // 'label : loop ...
+        match state.input.peek().unwrap() {
+            (Token::While | Token::Loop, ..) => self.parse_while_loop(state, settings, Some(label)),
+            (Token::Do, ..) => self.parse_do(state, settings, Some(label)),
+            (Token::For, ..) => self.parse_for(state, settings, Some(label)),
+            (.., pos) => Err(
+                PERR::MissingSymbol("Expecting a loop after the loop label".into()).into_err(*pos),
+            ),
+        }
+    }
+
     /// Parse a do loop.
-    fn parse_do(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
+    fn parse_do(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        label: Option<SmartString>,
+    ) -> ParseResult<Stmt> {
         // do ...
         let mut settings = settings.level_up_with_position(eat_token(state.input, &Token::Do))?;
         let orig_breakable = settings.has_flag(ParseSettingFlags::BREAKABLE);
@@ -2788,7 +4181,11 @@ impl Engine {
 
         // do { body } [while|until] guard
 
-        let body = self.parse_block(state, settings, false)?.into();
+        state.loop_labels.push(label);
+        let body = self.parse_block(state, settings, false);
+        state.loop_labels.pop();
+
+        let body = body?.into();
 
         let negated = match state.input.next().unwrap() {
             (Token::While, ..) => ASTFlags::empty(),
@@ -2818,12 +4215,9 @@ impl Engine {
         ))
     }
 
-    /// Parse a for loop.
-    fn parse_for(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
-        // for ...
-        let mut settings = settings.level_up_with_position(eat_token(state.input, &Token::For))?;
-
-        // for name ...
+    /// Parse the iteration variables of a `for` loop or a comprehension, up to and including `in`.
+    fn parse_for_vars(&self, state: &mut ParseState) -> ParseResult<(Ident, Option<Ident>)> {
+        // name ...
         let (name, name_pos, counter_name, counter_pos) =
             if match_token(state.input, &Token::LeftParen).0 {
                 // ( name, counter )
@@ -2857,7 +4251,7 @@ impl Engine {
                 (name, name_pos, None, Position::NONE)
             };
 
-        // for name in ...
+        // name in ...
         match state.input.next().unwrap() {
             (Token::In, ..) => (),
             (Token::LexError(err), pos) => return Err(err.into_err(pos)),
@@ -2870,10 +4264,6 @@ impl Engine {
             }
         }
 
-        // for name in expr { body }
-        ensure_not_statement_expr(state.input, "a boolean")?;
-        let expr = self.parse_expr(state, settings)?.ensure_iterable()?;
-
         let counter_var = counter_name.map(|counter_name| Ident {
             name: self.get_interned_string(counter_name),
             pos: counter_pos,
@@ -2884,6 +4274,26 @@ impl Engine {
             pos: name_pos,
         };
 
+        Ok((loop_var, counter_var))
+    }
+
+    /// Parse a for loop.
+    fn parse_for(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        label: Option<SmartString>,
+    ) -> ParseResult<Stmt> {
+        // for ...
+        let mut settings = settings.level_up_with_position(eat_token(state.input, &Token::For))?;
+
+        // for name in ...
+        let (loop_var, counter_var) = self.parse_for_vars(state)?;
+
+        // for name in expr { body }
+        ensure_not_statement_expr(state.input, "a boolean")?;
+        let expr = self.parse_expr(state, settings)?.ensure_iterable()?;
+
         let prev_stack_len = {
             let prev_stack_len = state.stack.len();
 
@@ -2896,7 +4306,12 @@ impl Engine {
         };
 
         settings.flags |= ParseSettingFlags::BREAKABLE;
-        let body = self.parse_block(state, settings, false)?.into();
+
+        state.loop_labels.push(label);
+        let body = self.parse_block(state, settings, false);
+        state.loop_labels.pop();
+
+        let body = body?.into();
 
         state.stack.rewind(prev_stack_len);
 
@@ -3053,44 +4468,192 @@ impl Engine {
                 stmt.set_position(pos);
                 return Ok(stmt);
             }
-            (Token::Const, pos) => {
-                let pos = *pos;
-                let settings = settings.level_up()?;
-                let mut stmt = self.parse_let(state, settings, AccessMode::ReadOnly, true)?;
-                stmt.set_position(pos);
-                return Ok(stmt);
+            (Token::Const, pos) => {
+                let pos = *pos;
+                let settings = settings.level_up()?;
+                let mut stmt = self.parse_let(state, settings, AccessMode::ReadOnly, true)?;
+                stmt.set_position(pos);
+                return Ok(stmt);
+            }
+            _ => (),
+        }
+
+        let (id, id_pos) = parse_var_name(state.input)?;
+
+        let (alias, alias_pos) = if match_token(state.input, &Token::As).0 {
+            parse_var_name(state.input).map(|(name, pos)| (self.get_interned_string(name), pos))?
+        } else {
+            (self.get_interned_string(""), Position::NONE)
+        };
+
+        let (existing, hit_barrier) = state.find_var(&id);
+
+        if !hit_barrier && existing > 0 {
+            state
+                .stack
+                .add_alias_by_index(state.stack.len() - existing, alias.clone());
+        }
+
+        let export = (
+            Ident {
+                name: self.get_interned_string(id),
+                pos: id_pos,
+            },
+            Ident {
+                name: alias,
+                pos: alias_pos,
+            },
+        );
+
+        Ok(Stmt::Export(export.into(), settings.pos))
+    }
+
+    /// Parse an inline module definition.
+    #[cfg(not(feature = "no_module"))]
+    fn parse_module(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
+        // module ...
+        let settings = settings.level_up_with_position(eat_token(state.input, &Token::Module))?;
+
+        // module name ...
+        let (name, name_pos) = parse_var_name(state.input)?;
+        let name = self.get_interned_string(name);
+
+        if state.modules.contains(&name) {
+            return Err(PERR::ModuleDuplicatedDefinition(name.to_string()).into_err(name_pos));
+        }
+
+        // module name { ...
+        match state.input.next().unwrap() {
+            (Token::LeftBrace, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(PERR::MissingToken(
+                    Token::LeftBrace.into(),
+                    format!("to start the body of module '{name}'"),
+                )
+                .into_err(pos))
+            }
+        }
+
+        // The module body is parsed like a separate script, with its own functions
+        #[cfg(not(feature = "no_function"))]
+        let mut lib = FnLib::default();
+
+        let new_state = &mut ParseState::new(
+            None,
+            state.input,
+            state.tokenizer_control.clone(),
+            #[cfg(not(feature = "no_function"))]
+            &mut lib,
+            #[cfg(feature = "no_function")]
+            &(),
+        );
+
+        // Modules imported so far remain visible inside the module body,
+        // but only by name since the body runs with its own imports stack.
+        new_state.global_imports.clone_from(&state.global_imports);
+        new_state.global_imports.extend(state.imports.clone());
+        new_state.enums.clone_from(&state.enums);
+        #[cfg(not(feature = "no_object"))]
+        new_state.structs.clone_from(&state.structs);
+
+        let new_settings = ParseSettings {
+            level: 0,
+            flags: ParseSettingFlags::GLOBAL_LEVEL
+                | (settings.flags & ParseSettingFlags::DISALLOW_UNQUOTED_MAP_PROPERTIES),
+            options: settings.options,
+            pos: settings.pos,
+            #[cfg(not(feature = "unchecked"))]
+            max_expr_depth: self.max_expr_depth(),
+        };
+
+        let mut statements = StmtBlockContainer::new_const();
+
+        loop {
+            match new_state.input.peek().unwrap() {
+                // { ... }
+                (Token::RightBrace, ..) => {
+                    eat_token(new_state.input, &Token::RightBrace);
+                    break;
+                }
+                (Token::EOF, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightBrace.into(),
+                        format!("to end the body of module '{name}'"),
+                    )
+                    .into_err(*pos))
+                }
+                _ => (),
+            }
+
+            let stmt = self.parse_stmt(new_state, new_settings)?;
+
+            if stmt.is_noop() {
+                continue;
+            }
+
+            let need_semicolon = !stmt.is_self_terminated();
+
+            statements.push(stmt);
+
+            match new_state.input.peek().unwrap() {
+                // { ... stmt }
+                (Token::RightBrace, ..) => (),
+                // { ... stmt;
+                (Token::SemiColon, ..) if need_semicolon => {
+                    eat_token(new_state.input, &Token::SemiColon);
+                }
+                // { ... { stmt } ;
+                (Token::SemiColon, ..) if !need_semicolon => {
+                    eat_token(new_state.input, &Token::SemiColon);
+                }
+                // { ... { stmt } ???
+                _ if !need_semicolon => (),
+                // { ... stmt <error>
+                (Token::LexError(err), err_pos) => return Err(err.clone().into_err(*err_pos)),
+                // { ... stmt ???
+                (.., pos) => {
+                    // Semicolons are not optional between statements
+                    return Err(PERR::MissingToken(
+                        Token::SemiColon.into(),
+                        "to terminate this statement".into(),
+                    )
+                    .into_err(*pos));
+                }
             }
-            _ => (),
         }
 
-        let (id, id_pos) = parse_var_name(state.input)?;
+        #[cfg(not(feature = "no_object"))]
+        #[cfg(not(feature = "no_function"))]
+        let struct_types = std::mem::take(&mut new_state.struct_types);
 
-        let (alias, alias_pos) = if match_token(state.input, &Token::As).0 {
-            parse_var_name(state.input).map(|(name, pos)| (self.get_interned_string(name), pos))?
-        } else {
-            (self.get_interned_string(""), Position::NONE)
-        };
+        #[cfg(not(feature = "no_function"))]
+        #[allow(unused_mut)]
+        let mut body = crate::AST::new(statements, {
+            let mut module = crate::Module::new();
+            module.extend(lib.into_values());
+            module
+        });
+        #[cfg(feature = "no_function")]
+        let body = crate::AST::new(statements);
 
-        let (existing, hit_barrier) = state.find_var(&id);
+        #[cfg(not(feature = "no_object"))]
+        #[cfg(not(feature = "no_function"))]
+        body.add_struct_types(struct_types);
 
-        if !hit_barrier && existing > 0 {
-            state
-                .stack
-                .add_alias_by_index(state.stack.len() - existing, alias.clone());
-        }
+        // The module is pushed onto the imports stack just like `import` does
+        state.imports.push(name.clone());
+        state.modules.push(name.clone());
 
-        let export = (
-            Ident {
-                name: self.get_interned_string(id),
-                pos: id_pos,
-            },
-            Ident {
-                name: alias,
-                pos: alias_pos,
-            },
-        );
+        let name = Ident {
+            name,
+            pos: name_pos,
+        };
 
-        Ok(Stmt::Export(export.into(), settings.pos))
+        Ok(Stmt::Module(
+            crate::ast::ModuleBlock { name, body }.into(),
+            settings.pos,
+        ))
     }
 
     /// Parse a statement block.
@@ -3118,11 +4681,33 @@ impl Engine {
         };
         let mut settings = settings.level_up_with_position(brace_start_pos)?;
 
+        // Only the block of an interpolated string can end with a format specification
+        let allow_format_spec = settings.has_flag(ParseSettingFlags::ALLOW_FORMAT_SPEC);
+        settings.flags.remove(ParseSettingFlags::ALLOW_FORMAT_SPEC);
+
+        // Only the body of a function can contain `defer` statements
+        let is_fn_body = settings.has_flag(ParseSettingFlags::FN_BODY);
+        settings.flags.remove(ParseSettingFlags::FN_BODY);
+        let mut defers = Vec::new();
+
         let mut block = StmtBlock::empty(settings.pos);
 
         if settings.has_flag(ParseSettingFlags::DISALLOW_STATEMENTS_IN_BLOCKS) {
             let stmt = self.parse_expr_stmt(state, settings)?;
-            block.statements_mut().push(stmt);
+
+            // `${ expr:spec }
+            if let Stmt::Expr(expr) = stmt {
+                if allow_format_spec && state.input.peek().unwrap().0 == Token::Colon {
+                    let (expr, end_pos) = self.parse_format_spec(state, *expr)?;
+                    block.statements_mut().push(Stmt::Expr(expr.into()));
+                    return Ok(Stmt::Block(
+                        StmtBlock::new(block, settings.pos, end_pos).into(),
+                    ));
+                }
+                block.statements_mut().push(Stmt::Expr(expr));
+            } else {
+                block.statements_mut().push(stmt);
+            }
 
             // Must end with }
             return match state.input.next().unwrap() {
@@ -3149,11 +4734,14 @@ impl Engine {
             match state.input.peek().unwrap() {
                 (Token::RightBrace, ..) => break eat_token(state.input, &Token::RightBrace),
                 (Token::EOF, pos) => {
-                    return Err(PERR::MissingToken(
+                    let pos = *pos;
+                    let err = PERR::MissingToken(
                         Token::RightBrace.into(),
                         "to terminate this block".into(),
                     )
-                    .into_err(*pos));
+                    .into_err(pos);
+                    state.report_error(err)?;
+                    break pos;
                 }
                 _ => (),
             }
@@ -3161,7 +4749,34 @@ impl Engine {
             // Parse statements inside the block
             settings.flags.remove(ParseSettingFlags::GLOBAL_LEVEL);
 
-            let stmt = self.parse_stmt(state, settings)?;
+            // defer { block }
+            if is_fn_body && state.input.peek().unwrap().0 == Token::Defer {
+                let start = state.num_tokens();
+                let pos = eat_token(state.input, &Token::Defer);
+                let finally = match settings
+                    .level_up()
+                    .and_then(|settings| self.parse_block(state, settings, false))
+                {
+                    Ok(finally) => finally,
+                    Err(err) => {
+                        state.recover(err, start, true)?;
+                        continue;
+                    }
+                };
+                defers.push((block.len(), finally.into(), pos));
+                match_token(state.input, &Token::SemiColon);
+                continue;
+            }
+
+            let start = state.num_tokens();
+
+            let stmt = match self.parse_stmt(state, settings) {
+                Ok(stmt) => stmt,
+                Err(err) => {
+                    state.recover(err, start, true)?;
+                    continue;
+                }
+            };
 
             if stmt.is_noop() {
                 continue;
@@ -3185,16 +4800,35 @@ impl Engine {
                 }
                 // { ... { stmt } ???
                 _ if !need_semicolon => (),
+                // `${ ... expr:spec }
+                (Token::Colon, ..)
+                    if allow_format_spec
+                        && matches!(block.statements().last(), Some(Stmt::Expr(..))) =>
+                {
+                    let Some(Stmt::Expr(expr)) = block.statements_mut().pop() else {
+                        unreachable!("Stmt::Expr expected")
+                    };
+                    let (expr, end_pos) = self.parse_format_spec(state, *expr)?;
+                    block.statements_mut().push(Stmt::Expr(expr.into()));
+                    break end_pos;
+                }
+                // { ... stmt <EOF> - reported as an unterminated block when recovering from errors
+                (Token::EOF, ..) if state.tokenizer_control.borrow().errors.is_some() => (),
                 // { ... stmt <error>
-                (Token::LexError(err), err_pos) => return Err(err.clone().into_err(*err_pos)),
+                (Token::LexError(err), err_pos) => {
+                    let err = err.clone().into_err(*err_pos);
+                    state.report_error(err)?;
+                    state.input.next();
+                }
                 // { ... stmt ???
                 (.., pos) => {
                     // Semicolons are not optional between statements
-                    return Err(PERR::MissingToken(
+                    let err = PERR::MissingToken(
                         Token::SemiColon.into(),
                         "to terminate this statement".into(),
                     )
-                    .into_err(*pos));
+                    .into_err(*pos);
+                    state.report_error(err)?;
                 }
             }
         };
@@ -3205,11 +4839,85 @@ impl Engine {
         #[cfg(not(feature = "no_module"))]
         state.imports.truncate(orig_imports_len);
 
+        // defer { block } ... -> try { ... } finally { block }
+        //
+        // The latest `defer` wraps the fewest statements, so it runs first.
+        while let Some((index, finally, pos)) = defers.pop() {
+            let body = block
+                .statements_mut()
+                .drain(index..)
+                .collect::<StmtBlockContainer>();
+            let body = StmtBlock::new(body, pos, end_pos);
+            let catches = StaticVec::new_const();
+            let stmt = Stmt::TryCatch(
+                TryCatchBlock {
+                    body,
+                    catches,
+                    finally,
+                }
+                .into(),
+                pos,
+            );
+            block.statements_mut().push(stmt);
+        }
+
         Ok(Stmt::Block(
             StmtBlock::new(block, settings.pos, end_pos).into(),
         ))
     }
 
+    /// Parse a format specification (e.g. `:>8.2`) that ends a block within an interpolated string,
+    /// up to and including the closing `}`.
+    ///
+    /// The expression is formatted via a call to `format("{:spec}", expr)`.
+    fn parse_format_spec(
+        &self,
+        state: &mut ParseState,
+        expr: Expr,
+    ) -> ParseResult<(Expr, Position)> {
+        let pos = eat_token(state.input, &Token::Colon);
+
+        // The format specification is raw text
+        let mut fmt = SmartString::new_const();
+        fmt.push_str("{:");
+
+        let end_pos = loop {
+            state.tokenizer_control.borrow_mut().in_char_mode = true;
+
+            match state.input.next().unwrap() {
+                (Token::UnprocessedRawChar('}'), pos) => break pos,
+                (Token::UnprocessedRawChar(ch), ..) => fmt.push(ch),
+                (Token::EOF, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightBrace.into(),
+                        "to terminate this block".into(),
+                    )
+                    .into_err(pos))
+                }
+                token => unreachable!("Token::UnprocessedRawChar expected but gets {:?}", token),
+            }
+        };
+
+        fmt.push('}');
+
+        let expr = FnCallExpr {
+            #[cfg(not(feature = "no_module"))]
+            namespace: crate::ast::Namespace::NONE,
+            name: self.get_interned_string(FN_FORMAT),
+            hashes: FnCallHashes::from_native_only(calc_fn_hash(None, FN_FORMAT, 2)),
+            args: IntoIterator::into_iter([
+                Expr::StringConstant(self.get_interned_string(fmt), pos),
+                expr,
+            ])
+            .collect(),
+            op_token: None,
+            capture_parent_scope: false,
+        }
+        .into_fn_call_expr(pos);
+
+        Ok((expr, end_pos))
+    }
+
     /// Parse an expression as a statement.
     fn parse_expr_stmt(
         &self,
@@ -3284,7 +4992,7 @@ impl Engine {
                         }
 
                         match state.input.peek().unwrap() {
-                            (Token::Fn | Token::Private, ..) => break,
+                            (Token::Fn | Token::Private | Token::Struct, ..) => break,
                             (Token::Comment(..), ..) => (),
                             _ => return Err(PERR::WrongDocComment.into_err(comments_pos)),
                         }
@@ -3355,6 +5063,11 @@ impl Engine {
                             new_state.global_imports.extend(state.imports.clone());
                         }
 
+                        // Enums declared so far are visible inside the function
+                        new_state.enums.clone_from(&state.enums);
+                        #[cfg(not(feature = "no_object"))]
+                        new_state.structs.clone_from(&state.structs);
+
                         // Brand new options
                         let options = self.options | (settings.options & LangOptions::STRICT_VAR);
 
@@ -3411,30 +5124,49 @@ impl Engine {
 
             Token::If => self.parse_if(state, settings.level_up()?),
             Token::Switch => self.parse_switch(state, settings.level_up()?),
+            Token::Enum => self.parse_enum(state, settings.level_up()?),
+            #[cfg(not(feature = "no_object"))]
+            Token::Struct => self.parse_struct(
+                state,
+                settings.level_up()?,
+                #[cfg(not(feature = "no_function"))]
+                #[cfg(feature = "metadata")]
+                comments,
+            ),
             Token::While | Token::Loop if self.allow_looping() => {
-                self.parse_while_loop(state, settings.level_up()?)
+                self.parse_while_loop(state, settings.level_up()?, None)
+            }
+            Token::Do if self.allow_looping() => self.parse_do(state, settings.level_up()?, None),
+            Token::For if self.allow_looping() => self.parse_for(state, settings.level_up()?, None),
+            Token::Label(..) if self.allow_looping() => {
+                self.parse_labeled_loop(state, settings.level_up()?)
             }
-            Token::Do if self.allow_looping() => self.parse_do(state, settings.level_up()?),
-            Token::For if self.allow_looping() => self.parse_for(state, settings.level_up()?),
 
             Token::Continue
                 if self.allow_looping() && settings.has_flag(ParseSettingFlags::BREAKABLE) =>
             {
                 let pos = eat_token(state.input, &Token::Continue);
-                Ok(Stmt::BreakLoop(None, ASTFlags::empty(), pos))
+                let levels = parse_loop_label(state)?;
+                Ok(Stmt::BreakLoop(None, ASTFlags::empty(), levels, pos))
             }
             Token::Break
                 if self.allow_looping() && settings.has_flag(ParseSettingFlags::BREAKABLE) =>
             {
                 let pos = eat_token(state.input, &Token::Break);
+                let levels = parse_loop_label(state)?;
 
                 let current_pos = state.input.peek().unwrap().1;
 
                 match self.parse_expr(state, settings.level_up()?) {
-                    Ok(expr) => Ok(Stmt::BreakLoop(Some(expr.into()), ASTFlags::BREAK, pos)),
+                    Ok(expr) => Ok(Stmt::BreakLoop(
+                        Some(expr.into()),
+                        ASTFlags::BREAK,
+                        levels,
+                        pos,
+                    )),
                     Err(err) => {
                         if state.input.peek().unwrap().1 == current_pos {
-                            Ok(Stmt::BreakLoop(None, ASTFlags::BREAK, pos))
+                            Ok(Stmt::BreakLoop(None, ASTFlags::BREAK, levels, pos))
                         } else {
                             Err(err)
                         }
@@ -3476,8 +5208,34 @@ impl Engine {
                 }
             }
 
+            #[cfg(not(feature = "no_function"))]
+            Token::Yield => {
+                let pos = eat_token(state.input, &Token::Yield);
+
+                if !settings.has_flag(ParseSettingFlags::FN_SCOPE) {
+                    return Err(PERR::WrongYield.into_err(pos));
+                }
+
+                let expr = self.parse_expr(state, settings.level_up()?)?;
+                Ok(Stmt::Yield(expr.into(), pos))
+            }
+
             Token::Try => self.parse_try_catch(state, settings.level_up()?),
 
+            // `defer` is handled by `parse_block` at the top level of a function body
+            Token::Defer => Err(PERR::WrongDefer.into_err(token_pos)),
+
+            Token::Static if !settings.has_flag(ParseSettingFlags::FN_SCOPE) => {
+                Err(PERR::WrongStatic.into_err(token_pos))
+            }
+            Token::Static => {
+                let mut stmt = self.parse_let(state, settings.level_up()?, ReadWrite, false)?;
+                if let Stmt::Var(_, ref mut options, ..) = stmt {
+                    *options |= ASTFlags::STATIC;
+                }
+                Ok(stmt)
+            }
+
             Token::Let => self.parse_let(state, settings.level_up()?, ReadWrite, false),
             Token::Const => self.parse_let(state, settings.level_up()?, ReadOnly, false),
 
@@ -3492,6 +5250,14 @@ impl Engine {
             #[cfg(not(feature = "no_module"))]
             Token::Export => self.parse_export(state, settings.level_up()?),
 
+            #[cfg(not(feature = "no_module"))]
+            Token::Module if !settings.has_flag(ParseSettingFlags::GLOBAL_LEVEL) => {
+                Err(PERR::WrongModuleDefinition.into_err(token_pos))
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            Token::Module => self.parse_module(state, settings.level_up()?),
+
             _ => self.parse_expr_stmt(state, settings.level_up()?),
         }
     }
@@ -3508,19 +5274,109 @@ impl Engine {
         // try { try_block }
         let body = self.parse_block(state, settings, false)?.into();
 
-        // try { try_block } catch
-        let (matched, catch_pos) = match_token(state.input, &Token::Catch);
+        // try { try_block } catch ... catch ...
+        let mut catches = StaticVec::new_const();
+
+        while match_token(state.input, &Token::Catch).0 {
+            catches.push(self.parse_catch_clause(state, settings)?);
+        }
+
+        // try { try_block } catch ... finally { finally_block }
+        let (has_finally, finally_pos) = match_token(state.input, &Token::Finally);
 
-        if !matched {
+        let finally = if has_finally {
+            self.parse_block(state, settings, false)?.into()
+        } else if catches.is_empty() {
             return Err(
                 PERR::MissingToken(Token::Catch.into(), "for the 'try' statement".into())
-                    .into_err(catch_pos),
+                    .into_err(finally_pos),
             );
-        }
+        } else {
+            StmtBlock::NONE
+        };
+
+        Ok(Stmt::TryCatch(
+            TryCatchBlock {
+                body,
+                catches,
+                finally,
+            }
+            .into(),
+            settings.pos,
+        ))
+    }
 
-        // try { try_block } catch (
+    /// Parse a `catch` clause of a try/catch statement, after the `catch` keyword.
+    fn parse_catch_clause(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+    ) -> ParseResult<CatchClause> {
+        let mut type_test = None;
+
+        // catch (
         let catch_var = if match_token(state.input, &Token::LeftParen).0 {
             let (name, pos) = parse_var_name(state.input)?;
+            let name = self.get_interned_string(name);
+
+            // catch ( var : type
+            if match_token(state.input, &Token::Colon).0 {
+                let (type_name, type_pos) = match state.input.next().unwrap() {
+                    (Token::Identifier(s) | Token::StringConstant(s), pos) => {
+                        let s = match s.as_str() {
+                            "int" => self.get_interned_string(std::any::type_name::<crate::INT>()),
+                            #[cfg(not(feature = "no_float"))]
+                            "float" => {
+                                self.get_interned_string(std::any::type_name::<crate::FLOAT>())
+                            }
+                            _ => self.get_interned_string(*s),
+                        };
+                        (s, pos)
+                    }
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (.., pos) => {
+                        return Err(PERR::MissingSymbol(
+                            "Expecting a type name for the catch variable".into(),
+                        )
+                        .into_err(pos))
+                    }
+                };
+
+                // type_of(var) == "type"
+                let var = Expr::Variable(
+                    #[cfg(not(feature = "no_module"))]
+                    (None, name.clone(), <_>::default(), 0).into(),
+                    #[cfg(feature = "no_module")]
+                    (None, name.clone()).into(),
+                    None,
+                    pos,
+                );
+                let type_of = FnCallExpr {
+                    #[cfg(not(feature = "no_module"))]
+                    namespace: crate::ast::Namespace::NONE,
+                    name: self.get_interned_string(KEYWORD_TYPE_OF),
+                    hashes: FnCallHashes::from_native_only(calc_fn_hash(None, KEYWORD_TYPE_OF, 1)),
+                    args: IntoIterator::into_iter([var]).collect(),
+                    op_token: None,
+                    capture_parent_scope: false,
+                }
+                .into_fn_call_expr(type_pos);
+                let test = FnCallExpr {
+                    #[cfg(not(feature = "no_module"))]
+                    namespace: crate::ast::Namespace::NONE,
+                    name: self.get_interned_string(OP_EQUALS),
+                    hashes: FnCallHashes::from_native_only(calc_fn_hash(None, OP_EQUALS, 2)),
+                    args: IntoIterator::into_iter([
+                        type_of,
+                        Expr::StringConstant(type_name, type_pos),
+                    ])
+                    .collect(),
+                    op_token: Some(Token::EqualsTo),
+                    capture_parent_scope: false,
+                };
+                type_test = Some(test.into_fn_call_expr(type_pos));
+            }
+
             let (matched, err_pos) = match_token(state.input, &Token::RightParen);
 
             if !matched {
@@ -3531,7 +5387,6 @@ impl Engine {
                 .into_err(err_pos));
             }
 
-            let name = self.get_interned_string(name);
             state.stack.push(name.clone(), ());
             Ident { name, pos }
         } else {
@@ -3541,10 +5396,23 @@ impl Engine {
             }
         };
 
-        // try { try_block } catch ( var ) { catch_block }
-        let branch = self.parse_block(state, settings, false)?.into();
+        // catch ( var ) if guard
+        let guard = if match_token(state.input, &Token::If).0 {
+            let guard = self.parse_expr(state, settings)?.ensure_bool_expr()?;
+            let pos = guard.start_position();
+
+            match type_test {
+                Some(test) => Expr::And(Box::new(vec![test, guard].into()), pos),
+                None => guard,
+            }
+        } else {
+            type_test.unwrap_or(Expr::BoolConstant(true, Position::NONE))
+        };
+
+        // catch ( var ) if guard { catch_block }
+        let body = self.parse_block(state, settings, false)?.into();
 
-        let expr = if catch_var.is_empty() {
+        let var = if catch_var.is_empty() {
             Expr::Unit(catch_var.pos)
         } else {
             // Remove the error variable from the stack
@@ -3560,10 +5428,7 @@ impl Engine {
             )
         };
 
-        Ok(Stmt::TryCatch(
-            FlowControl { expr, body, branch }.into(),
-            settings.pos,
-        ))
+        Ok(CatchClause { var, guard, body })
     }
 
     /// Parse a function definition.
@@ -3616,11 +5481,23 @@ impl Engine {
             }
         };
 
+        #[cfg(not(feature = "no_object"))]
+        let has_this_type = this_type.is_some();
+        #[cfg(feature = "no_object")]
+        let has_this_type = false;
+
         let name = match token {
             #[cfg(not(feature = "no_custom_syntax"))]
             Token::Custom(s) if is_valid_function_name(&s) => *s,
             Token::Identifier(s) if is_valid_function_name(&s) => *s,
             Token::Reserved(s) => return Err(PERR::Reserved(s.to_string()).into_err(pos)),
+            // Operator overload, e.g. `fn +(a, b)`
+            op if op.is_standard_symbol() && is_script_operator(op.literal_syntax()) => {
+                if has_this_type {
+                    return Err(PERR::FnMissingName.into_err(pos));
+                }
+                op.literal_syntax().into()
+            }
             _ => return Err(PERR::FnMissingName.into_err(pos)),
         };
 
@@ -3682,13 +5559,36 @@ impl Engine {
             }
         }
 
+        // Unary operators take one parameter, `+` and `-` can be either
+        if is_script_operator(&name) {
+            let num_params_ok = match (name.as_str(), params.len()) {
+                ("!", n) => n == 1,
+                ("+" | "-", n) => n == 1 || n == 2,
+                (_, n) => n == 2,
+            };
+
+            if !num_params_ok {
+                return Err(PERR::FnWrongOperatorParams(name.into()).into_err(pos));
+            }
+        }
+
         // Parse function body
-        let body = match state.input.peek().unwrap() {
-            (Token::LeftBrace, ..) => self.parse_block(state, settings, false)?,
+        let mut body: StmtBlock = match state.input.peek().unwrap() {
+            (Token::LeftBrace, ..) => {
+                let mut settings = settings;
+                settings.flags |= ParseSettingFlags::FN_BODY;
+                self.parse_block(state, settings, false)?
+            }
             (.., pos) => return Err(PERR::FnMissingBody(name.into()).into_err(*pos)),
         }
         .into();
 
+        let is_generator = check_yield_statements(body.statements())?;
+        if is_generator {
+            body.cache_yield();
+        }
+        let statics = make_static_variables(body.statements())?;
+
         let mut params: FnArgsVec<_> = params.into_iter().map(|(p, ..)| p).collect();
         params.shrink_to_fit();
 
@@ -3698,6 +5598,8 @@ impl Engine {
             #[cfg(not(feature = "no_object"))]
             this_type,
             params,
+            is_generator,
+            statics,
             body,
             #[cfg(feature = "metadata")]
             comments: comments.into_iter().collect(),
@@ -3802,6 +5704,10 @@ impl Engine {
             new_state.global_imports.extend(state.imports.clone());
         }
 
+        new_state.enums.clone_from(&state.enums);
+        #[cfg(not(feature = "no_object"))]
+        new_state.structs.clone_from(&state.structs);
+
         let mut params_list = StaticVec::<ImmutableString>::new_const();
 
         // Parse parameters
@@ -3861,12 +5767,17 @@ impl Engine {
         let options = self.options | (settings.options & LangOptions::STRICT_VAR);
 
         // Brand new flags, turn on function scope and closure scope
-        let flags = ParseSettingFlags::FN_SCOPE
+        let mut flags = ParseSettingFlags::FN_SCOPE
             | ParseSettingFlags::CLOSURE_SCOPE
             | (settings.flags
                 & (ParseSettingFlags::DISALLOW_UNQUOTED_MAP_PROPERTIES
                     | ParseSettingFlags::DISALLOW_STATEMENTS_IN_BLOCKS));
 
+        // A function body block can contain `defer` statements
+        if new_state.input.peek().unwrap().0 == Token::LeftBrace {
+            flags |= ParseSettingFlags::FN_BODY;
+        }
+
         let new_settings = ParseSettings {
             flags,
             options,
@@ -3906,6 +5817,13 @@ impl Engine {
         let hash = hasher.finish();
         let fn_name = self.get_interned_string(make_anonymous_fn(hash));
 
+        let mut body: StmtBlock = body.into();
+        let is_generator = check_yield_statements(body.statements())?;
+        if is_generator {
+            body.cache_yield();
+        }
+        let statics = make_static_variables(body.statements())?;
+
         // Define the function
         let fn_def = Shared::new(ScriptFuncDef {
             name: fn_name.clone(),
@@ -3913,7 +5831,9 @@ impl Engine {
             #[cfg(not(feature = "no_object"))]
             this_type: None,
             params,
-            body: body.into(),
+            is_generator,
+            statics,
+            body,
             #[cfg(not(feature = "no_function"))]
             #[cfg(feature = "metadata")]
             comments: <_>::default(),
@@ -3950,7 +5870,7 @@ impl Engine {
                 // If the parent scope is not inside another capturing closure
                 // then we can conclude that the captured variable doesn't exist.
                 // Under Strict Variables mode, this is not allowed.
-                return Err(PERR::VariableUndefined(name.to_string()).into_err(*pos));
+                state.report_undefined_var(name.clone(), *pos)?;
             }
         }
 
@@ -4030,7 +5950,15 @@ impl Engine {
         process_settings(&mut settings);
 
         while state.input.peek().unwrap().0 != Token::EOF {
-            let stmt = self.parse_stmt(state, settings)?;
+            let start = state.num_tokens();
+
+            let stmt = match self.parse_stmt(state, settings) {
+                Ok(stmt) => stmt,
+                Err(err) => {
+                    state.recover(err, start, false)?;
+                    continue;
+                }
+            };
 
             if stmt.is_noop() {
                 continue;
@@ -4052,15 +5980,20 @@ impl Engine {
                 // { stmt } ???
                 _ if !need_semicolon => (),
                 // stmt <error>
-                (Token::LexError(err), pos) => return Err(err.clone().into_err(*pos)),
+                (Token::LexError(err), pos) => {
+                    let err = err.clone().into_err(*pos);
+                    state.report_error(err)?;
+                    state.input.next();
+                }
                 // stmt ???
                 (.., pos) => {
                     // Semicolons are not optional between statements
-                    return Err(PERR::MissingToken(
+                    let err = PERR::MissingToken(
                         Token::SemiColon.into(),
                         "to terminate this statement".into(),
                     )
-                    .into_err(*pos));
+                    .into_err(*pos);
+                    state.report_error(err)?;
                 }
             }
         }
@@ -4083,16 +6016,18 @@ impl Engine {
         let (statements, _lib) = self.parse_global_level(&mut state, |_| {})?;
 
         #[cfg(not(feature = "no_optimize"))]
-        return Ok(self.optimize_into_ast(
+        #[allow(unused_mut)]
+        let mut ast = self.optimize_into_ast(
             state.external_constants,
             statements,
             #[cfg(not(feature = "no_function"))]
             _lib,
             optimization_level,
-        ));
+        );
 
         #[cfg(feature = "no_optimize")]
-        return Ok(AST::new(
+        #[allow(unused_mut)]
+        let mut ast = AST::new(
             statements,
             #[cfg(not(feature = "no_function"))]
             {
@@ -4100,6 +6035,12 @@ impl Engine {
                 new_lib.extend(_lib);
                 new_lib
             },
-        ));
+        );
+
+        #[cfg(not(feature = "no_object"))]
+        #[cfg(not(feature = "no_function"))]
+        ast.add_struct_types(std::mem::take(&mut state.struct_types));
+
+        Ok(ast)
     }
 }
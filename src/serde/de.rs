@@ -2,8 +2,10 @@
 
 use crate::api::formatting::map_std_type_name;
 use crate::types::dynamic::Union;
-use crate::{Dynamic, ImmutableString, LexError, Position, RhaiError, RhaiResultOf, ERR};
-use serde::de::{Error, IntoDeserializer, Visitor};
+use crate::{
+    Dynamic, EnumValue, ImmutableString, LexError, Position, RhaiError, RhaiResultOf, Tuple, ERR,
+};
+use serde::de::{value::SeqDeserializer, Error, IntoDeserializer, Visitor};
 use serde::{Deserialize, Deserializer};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -51,6 +53,27 @@ impl<'de> DynamicDeserializer<'de> {
         #[cfg(feature = "only_i32")]
         return visitor.visit_i32(v);
     }
+    /// Visit a big integer as the smallest primitive integer type holding it, or as a string of
+    /// digits if none does.
+    #[cfg(feature = "bigint")]
+    fn deserialize_bigint<V: Visitor<'de>>(
+        v: &num_bigint::BigInt,
+        visitor: V,
+    ) -> RhaiResultOf<V::Value> {
+        use num_traits::ToPrimitive;
+
+        if let Some(n) = v.to_i64() {
+            visitor.visit_i64(n)
+        } else if let Some(n) = v.to_u64() {
+            visitor.visit_u64(n)
+        } else if let Some(n) = v.to_i128() {
+            visitor.visit_i128(n)
+        } else if let Some(n) = v.to_u128() {
+            visitor.visit_u128(n)
+        } else {
+            visitor.visit_string(v.to_string())
+        }
+    }
 }
 
 /// Deserialize a [`Dynamic`][crate::Dynamic] value into a Rust type that implements [`serde::Deserialize`].
@@ -151,13 +174,23 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
             #[cfg(feature = "f32_float")]
             Union::Decimal(..) => self.deserialize_f32(visitor),
 
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref n, ..) => Self::deserialize_bigint(n, visitor),
+
             #[cfg(not(feature = "no_index"))]
             Union::Array(..) => self.deserialize_seq(visitor),
             #[cfg(not(feature = "no_index"))]
             Union::Blob(..) => self.deserialize_bytes(visitor),
             #[cfg(not(feature = "no_object"))]
             Union::Map(..) => self.deserialize_map(visitor),
+            // Structs are presented as plain maps
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(..) => self.deserialize_map(visitor),
             Union::FnPtr(..) => self.type_error(),
+            Union::Tuple(..) => self.deserialize_seq(visitor),
+            // Enums are presented in externally-tagged form
+            Union::Enum(ref e, ..) if e.fields().is_empty() => visitor.visit_str(e.variant()),
+            Union::Enum(ref e, ..) => visitor.visit_map(EnumValueAccess::new(e)),
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(..) => self.type_error(),
 
@@ -217,6 +250,8 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> RhaiResultOf<V::Value> {
         match self.0.as_int() {
             Ok(v) => Self::deserialize_int(v, visitor),
+            #[cfg(feature = "bigint")]
+            Err(_) if self.0.is_bigint() => self.deserialize_any(visitor),
             _ if cfg!(not(feature = "only_i32")) => self.type_error(),
             _ => self
                 .0
@@ -228,6 +263,8 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> RhaiResultOf<V::Value> {
         match self.0.as_int() {
             Ok(v) => Self::deserialize_int(v, visitor),
+            #[cfg(feature = "bigint")]
+            Err(_) if self.0.is_bigint() => self.deserialize_any(visitor),
             _ if cfg!(not(feature = "only_i32")) => self.type_error(),
             _ => self
                 .0
@@ -269,6 +306,8 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> RhaiResultOf<V::Value> {
         match self.0.as_int() {
             Ok(v) => Self::deserialize_int(v, visitor),
+            #[cfg(feature = "bigint")]
+            Err(_) if self.0.is_bigint() => self.deserialize_any(visitor),
             Err(_) => self
                 .0
                 .downcast_ref::<u64>()
@@ -279,6 +318,8 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> RhaiResultOf<V::Value> {
         match self.0.as_int() {
             Ok(v) => Self::deserialize_int(v, visitor),
+            #[cfg(feature = "bigint")]
+            Err(_) if self.0.is_bigint() => self.deserialize_any(visitor),
             Err(_) => self
                 .0
                 .downcast_ref::<u128>()
@@ -396,6 +437,10 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     }
 
     fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> RhaiResultOf<V::Value> {
+        if let Some(tuple) = self.0.downcast_ref::<Tuple>() {
+            return _visitor.visit_seq(IterateDynamicArray::new(tuple.iter()));
+        }
+
         #[cfg(not(feature = "no_index"))]
         return self.0.downcast_ref::<crate::Array>().map_or_else(
             || self.type_error(),
@@ -420,6 +465,14 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     }
 
     fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> RhaiResultOf<V::Value> {
+        #[cfg(not(feature = "no_object"))]
+        if let Some(value) = self.0.downcast_ref::<crate::StructValue>() {
+            return _visitor.visit_map(IterateMap::new(
+                value.iter().map(|(k, ..)| k),
+                value.iter().map(|(.., v)| v),
+            ));
+        }
+
         #[cfg(not(feature = "no_object"))]
         return self.0.downcast_ref::<crate::Map>().map_or_else(
             || self.type_error(),
@@ -441,6 +494,14 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
         _fields: &'static [&'static str],
         _visitor: V,
     ) -> RhaiResultOf<V::Value> {
+        #[cfg(not(feature = "no_object"))]
+        if let Some(value) = self.0.downcast_ref::<crate::StructValue>() {
+            return _visitor.visit_map(IterateMap::new(
+                value.iter().map(|(k, ..)| k),
+                value.iter().map(|(.., v)| v),
+            ));
+        }
+
         #[cfg(not(feature = "no_object"))]
         return self.0.downcast_ref::<crate::Map>().map_or_else(
             || {
@@ -469,6 +530,10 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
         _variants: &'static [&'static str],
         visitor: V,
     ) -> RhaiResultOf<V::Value> {
+        if let Some(value) = self.0.downcast_ref::<EnumValue>() {
+            return visitor.visit_enum(EnumValueAccess::new(value));
+        }
+
         match self.0.as_immutable_string_ref() {
             Ok(s) => visitor.visit_enum(s.into_deserializer()),
             Err(_) => {
@@ -505,14 +570,12 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     }
 }
 
-/// `SeqAccess` implementation for arrays.
-#[cfg(not(feature = "no_index"))]
+/// `SeqAccess` implementation for arrays and tuples.
 struct IterateDynamicArray<'de, ITER: Iterator<Item = &'de Dynamic>> {
     /// Iterator for a stream of [`Dynamic`][crate::Dynamic] values.
     iter: ITER,
 }
 
-#[cfg(not(feature = "no_index"))]
 impl<'de, ITER: Iterator<Item = &'de Dynamic>> IterateDynamicArray<'de, ITER> {
     #[inline(always)]
     #[must_use]
@@ -521,7 +584,6 @@ impl<'de, ITER: Iterator<Item = &'de Dynamic>> IterateDynamicArray<'de, ITER> {
     }
 }
 
-#[cfg(not(feature = "no_index"))]
 impl<'de, ITER: Iterator<Item = &'de Dynamic>> serde::de::SeqAccess<'de>
     for IterateDynamicArray<'de, ITER>
 {
@@ -633,3 +695,108 @@ impl<'de> serde::de::VariantAccess<'de> for EnumDeserializer<'de> {
         self.content.deserialize_struct("", fields, visitor)
     }
 }
+
+/// `MapAccess`, `EnumAccess` and `VariantAccess` implementation for script-defined enum values.
+///
+/// As a map, the value is presented in externally-tagged form, i.e. `{ variant: fields }`.
+struct EnumValueAccess<'de> {
+    /// The enum value.
+    value: &'de EnumValue,
+    /// Has the variant name been taken as a map key?
+    key_taken: bool,
+}
+
+impl<'de> EnumValueAccess<'de> {
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(value: &'de EnumValue) -> Self {
+        Self {
+            value,
+            key_taken: false,
+        }
+    }
+    /// Deserialize the fields of the variant, unwrapping a single field.
+    fn deserialize_fields<S: serde::de::DeserializeSeed<'de>>(
+        &self,
+        seed: S,
+    ) -> RhaiResultOf<S::Value> {
+        match self.value.fields().as_ref() {
+            [field] => seed.deserialize(field.into_deserializer()),
+            fields => seed.deserialize(SeqDeserializer::new(fields.iter())),
+        }
+    }
+}
+
+impl<'de> serde::de::MapAccess<'de> for EnumValueAccess<'de> {
+    type Error = RhaiError;
+
+    fn next_key_seed<S: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> RhaiResultOf<Option<S::Value>> {
+        if self.key_taken {
+            return Ok(None);
+        }
+        self.key_taken = true;
+        seed.deserialize(self.value.variant().into_deserializer())
+            .map(Some)
+    }
+
+    #[inline(always)]
+    fn next_value_seed<S: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> RhaiResultOf<S::Value> {
+        self.deserialize_fields(seed)
+    }
+}
+
+impl<'de> serde::de::EnumAccess<'de> for EnumValueAccess<'de> {
+    type Error = RhaiError;
+    type Variant = Self;
+
+    fn variant_seed<V: serde::de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> RhaiResultOf<(V::Value, Self::Variant)> {
+        seed.deserialize(self.value.variant().into_deserializer())
+            .map(|v| (v, self))
+    }
+}
+
+impl<'de> serde::de::VariantAccess<'de> for EnumValueAccess<'de> {
+    type Error = RhaiError;
+
+    fn unit_variant(self) -> RhaiResultOf<()> {
+        if self.value.fields().is_empty() {
+            Ok(())
+        } else {
+            Err(RhaiError::invalid_length(
+                self.value.fields().len(),
+                &"a unit variant",
+            ))
+        }
+    }
+
+    #[inline(always)]
+    fn newtype_variant_seed<T: serde::de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> RhaiResultOf<T::Value> {
+        self.deserialize_fields(seed)
+    }
+
+    #[inline(always)]
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> RhaiResultOf<V::Value> {
+        visitor.visit_seq(IterateDynamicArray::new(self.value.fields().iter()))
+    }
+
+    #[inline(always)]
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> RhaiResultOf<V::Value> {
+        visitor.visit_seq(IterateDynamicArray::new(self.value.fields().iter()))
+    }
+}
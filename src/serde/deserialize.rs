@@ -55,6 +55,10 @@ impl<'de> Visitor<'de> for DynamicVisitor {
                 return Ok(Dynamic::from_decimal(n));
             }
 
+            // Fall back to `BigInt` only when `Decimal` cannot represent the number exactly
+            #[cfg(feature = "bigint")]
+            return Ok(Dynamic::from_bigint(v.into()));
+
             #[cfg(not(feature = "no_float"))]
             return Ok(Dynamic::from_float(v as crate::FLOAT));
 
@@ -63,8 +67,8 @@ impl<'de> Visitor<'de> for DynamicVisitor {
     }
     #[inline]
     fn visit_i128<E: Error>(self, v: i128) -> Result<Self::Value, E> {
-        if v <= i128::from(INT::MAX) {
-            return Ok(Dynamic::from(v as INT));
+        if let Ok(v) = INT::try_from(v) {
+            return Ok(Dynamic::from_int(v));
         }
 
         #[allow(unreachable_code)]
@@ -74,6 +78,10 @@ impl<'de> Visitor<'de> for DynamicVisitor {
                 return Ok(Dynamic::from_decimal(n));
             }
 
+            // Fall back to `BigInt` only when `Decimal` cannot represent the number exactly
+            #[cfg(feature = "bigint")]
+            return Ok(Dynamic::from_bigint(v.into()));
+
             #[cfg(not(feature = "no_float"))]
             return Ok(Dynamic::from_float(v as crate::FLOAT));
 
@@ -105,6 +113,10 @@ impl<'de> Visitor<'de> for DynamicVisitor {
                 return Ok(Dynamic::from_decimal(n));
             }
 
+            // Fall back to `BigInt` only when `Decimal` cannot represent the number exactly
+            #[cfg(feature = "bigint")]
+            return Ok(Dynamic::from_bigint(v.into()));
+
             #[cfg(not(feature = "no_float"))]
             return Ok(Dynamic::from_float(v as crate::FLOAT));
 
@@ -117,16 +129,22 @@ impl<'de> Visitor<'de> for DynamicVisitor {
             return Ok(Dynamic::from_int(v));
         }
 
-        #[cfg(feature = "decimal")]
-        if let Some(n) = rust_decimal::Decimal::from_u64(v) {
-            return Ok(Dynamic::from_decimal(n));
-        }
+        #[allow(unreachable_code)]
+        {
+            #[cfg(feature = "decimal")]
+            if let Some(n) = rust_decimal::Decimal::from_u64(v) {
+                return Ok(Dynamic::from_decimal(n));
+            }
 
-        #[cfg(not(feature = "no_float"))]
-        return Ok(Dynamic::from_float(v as crate::FLOAT));
+            // Fall back to `BigInt` only when `Decimal` cannot represent the number exactly
+            #[cfg(feature = "bigint")]
+            return Ok(Dynamic::from_bigint(v.into()));
 
-        #[allow(unreachable_code)]
-        Err(Error::custom(format!("integer number too large: {v}")))
+            #[cfg(not(feature = "no_float"))]
+            return Ok(Dynamic::from_float(v as crate::FLOAT));
+
+            Err(Error::custom(format!("integer number too large: {v}")))
+        }
     }
     #[inline]
     fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> {
@@ -134,16 +152,22 @@ impl<'de> Visitor<'de> for DynamicVisitor {
             return Ok(Dynamic::from(v as INT));
         }
 
-        #[cfg(feature = "decimal")]
-        if let Some(n) = rust_decimal::Decimal::from_u128(v) {
-            return Ok(Dynamic::from_decimal(n));
-        }
+        #[allow(unreachable_code)]
+        {
+            #[cfg(feature = "decimal")]
+            if let Some(n) = rust_decimal::Decimal::from_u128(v) {
+                return Ok(Dynamic::from_decimal(n));
+            }
 
-        #[cfg(not(feature = "no_float"))]
-        return Ok(Dynamic::from_float(v as crate::FLOAT));
+            // Fall back to `BigInt` only when `Decimal` cannot represent the number exactly
+            #[cfg(feature = "bigint")]
+            return Ok(Dynamic::from_bigint(v.into()));
 
-        #[allow(unreachable_code)]
-        Err(Error::custom(format!("integer number too large: {v}")))
+            #[cfg(not(feature = "no_float"))]
+            return Ok(Dynamic::from_float(v as crate::FLOAT));
+
+            Err(Error::custom(format!("integer number too large: {v}")))
+        }
     }
 
     #[cfg(not(feature = "no_float"))]
@@ -37,6 +37,8 @@ struct FnParam<'a> {
 struct CustomTypeMetadata<'a> {
     pub type_name: &'a str,
     pub display_name: &'a str,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<&'a str>>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub doc_comments: Vec<&'a str>,
 }
@@ -61,6 +63,14 @@ impl<'a> From<(&'a str, &'a CustomTypeInfo)> for CustomTypeMetadata<'a> {
         Self {
             type_name: value.0,
             display_name: &value.1.display_name,
+            #[cfg(not(feature = "no_object"))]
+            fields: value
+                .1
+                .fields
+                .as_ref()
+                .map(|f| f.iter().map(<_>::as_ref).collect()),
+            #[cfg(feature = "no_object")]
+            fields: None,
             doc_comments: value.1.comments.iter().map(<_>::as_ref).collect(),
         }
     }
@@ -2,14 +2,14 @@
 
 use crate::types::dynamic::Union;
 use crate::{Dynamic, ImmutableString, Scope};
-use serde::{ser::SerializeSeq, Serialize, Serializer};
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Serialize, Serializer,
+};
 use std::iter::once;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
-#[cfg(not(feature = "no_object"))]
-use serde::ser::SerializeMap;
-
 #[cfg(not(feature = "no_time"))]
 use crate::types::dynamic::Variant;
 
@@ -54,6 +54,23 @@ impl Serialize for Dynamic {
                 }
             }
 
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref x, ..) => {
+                use num_traits::ToPrimitive;
+
+                if let Some(v) = x.to_i64() {
+                    ser.serialize_i64(v)
+                } else if let Some(v) = x.to_u64() {
+                    ser.serialize_u64(v)
+                } else if let Some(v) = x.to_i128() {
+                    ser.serialize_i128(v)
+                } else if let Some(v) = x.to_u128() {
+                    ser.serialize_u128(v)
+                } else {
+                    ser.serialize_str(&x.to_string())
+                }
+            }
+
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref a, ..) => (**a).serialize(ser),
             #[cfg(not(feature = "no_index"))]
@@ -65,10 +82,32 @@ impl Serialize for Dynamic {
                     .try_for_each(|(k, v)| map.serialize_entry(k.as_str(), v))?;
                 map.end()
             }
+            // Structs are serialized as plain maps
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref st, ..) => {
+                let mut map = ser.serialize_map(Some(st.len()))?;
+                st.iter().try_for_each(|(k, v)| map.serialize_entry(k, v))?;
+                map.end()
+            }
             Union::FnPtr(ref f, ..) if f.is_curried() => {
                 ser.collect_seq(once(f.fn_name().into()).chain(f.iter_curry().cloned()))
             }
             Union::FnPtr(ref f, ..) => ser.serialize_str(f.fn_name()),
+            Union::Tuple(ref t, ..) => ser.collect_seq(t.iter()),
+            // Enums are serialized in externally-tagged form
+            Union::Enum(ref e, ..) => match e.fields().as_ref() {
+                [] => ser.serialize_str(e.variant()),
+                [value] => {
+                    let mut map = ser.serialize_map(Some(1))?;
+                    map.serialize_entry(e.variant(), value)?;
+                    map.end()
+                }
+                fields => {
+                    let mut map = ser.serialize_map(Some(1))?;
+                    map.serialize_entry(e.variant(), fields)?;
+                    map.end()
+                }
+            },
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(ref x, ..) => ser.serialize_str(x.as_ref().type_name()),
 
@@ -4,7 +4,10 @@ use rhai_codegen::expose_under_internals;
 
 use crate::engine::Precedence;
 use crate::func::native::OnParseTokenCallback;
-use crate::{Engine, Identifier, LexError, Position, SmartString, StaticVec, INT, UNSIGNED_INT};
+use crate::types::Span;
+use crate::{
+    Engine, Identifier, LexError, ParseError, Position, SmartString, StaticVec, INT, UNSIGNED_INT,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
@@ -23,8 +26,12 @@ pub struct TokenizerControlBlock {
     ///
     /// This flag allows switching the tokenizer back to _text_ parsing after an interpolation stream.
     pub is_within_text: bool,
+    /// Is the current interpolated text string a multi-line heredoc literal wrapped by `"""`?
+    ///
+    /// Set by the tokenizer when a heredoc literal is first interrupted by an interpolation, and
+    /// set again together with `is_within_text` to switch back to the heredoc text.
+    pub is_within_heredoc: bool,
     /// Return the next character in the input stream instead of the next token?
-    #[cfg(not(feature = "no_custom_syntax"))]
     pub in_char_mode: bool,
     /// Global comments.
     #[cfg(feature = "metadata")]
@@ -33,6 +40,20 @@ pub struct TokenizerControlBlock {
     ///
     /// Set to `Some` in order to collect a compressed script.
     pub compressed: Option<String>,
+    /// Spans of all the tokens in the script (if any).
+    ///
+    /// Set to `Some` in order to collect the spans of the tokens.
+    pub spans: Option<Vec<Span>>,
+    /// Parse errors recovered from (if any).
+    ///
+    /// Set to `Some` in order to recover from parse errors, resuming at the next statement.
+    pub errors: Option<Vec<ParseError>>,
+    /// Deprecated symbols used in the script (if any), together with their positions.
+    ///
+    /// Set to `Some` in order to collect the deprecated symbols.
+    pub deprecated: Option<Vec<(Identifier, Position)>>,
+    /// Number of tokens read from the tokenizer so far.
+    pub num_tokens: usize,
 }
 
 impl TokenizerControlBlock {
@@ -42,11 +63,15 @@ impl TokenizerControlBlock {
     pub const fn new() -> Self {
         Self {
             is_within_text: false,
-            #[cfg(not(feature = "no_custom_syntax"))]
+            is_within_heredoc: false,
             in_char_mode: false,
             #[cfg(feature = "metadata")]
             global_comments: String::new(),
             compressed: None,
+            spans: None,
+            errors: None,
+            deprecated: None,
+            num_tokens: 0,
         }
     }
 }
@@ -59,6 +84,9 @@ type LERR = LexError;
 /// Separator character for numbers.
 const NUMBER_SEPARATOR: char = '_';
 
+/// Wrapper of a multi-line heredoc string literal.
+const HEREDOC_WRAPPER: &str = "\"\"\"";
+
 /// A stream of tokens.
 pub type TokenStream<'a> = Peekable<TokenIterator<'a>>;
 
@@ -79,10 +107,17 @@ pub enum Token {
     /// Requires the `decimal` feature, including its text representation.
     #[cfg(feature = "decimal")]
     DecimalConstant(Box<(rust_decimal::Decimal, Identifier)>),
+    /// A [`BigInt`][num_bigint::BigInt] constant (e.g. `123n`).
+    ///
+    /// Requires the `bigint` feature, including its text representation.
+    #[cfg(feature = "bigint")]
+    BigIntConstant(Box<(num_bigint::BigInt, Identifier)>),
     /// An identifier.
     Identifier(Box<Identifier>),
     /// A character constant.
     CharConstant(char),
+    /// A loop label, e.g. `'outer`.
+    Label(Box<Identifier>),
     /// A string constant.
     StringConstant(Box<SmartString>),
     /// An interpolated string.
@@ -151,6 +186,8 @@ pub enum Token {
     ExclusiveRange,
     /// `..=`
     InclusiveRange,
+    /// `...`
+    Spread,
     /// `#{`
     MapStart,
     /// `=`
@@ -169,6 +206,10 @@ pub enum Token {
     Else,
     /// `switch`
     Switch,
+    /// `enum`
+    Enum,
+    /// `struct`
+    Struct,
     /// `do`
     Do,
     /// `while`
@@ -201,6 +242,10 @@ pub enum Token {
     Pipe,
     /// `||`
     Or,
+    /// `|>`
+    ///
+    /// `x |> f(y)` is exactly `f(x, y)`.
+    PipeArrow,
     /// `^`
     XOr,
     /// `&`
@@ -212,6 +257,11 @@ pub enum Token {
     /// Reserved under the `no_function` feature.
     #[cfg(not(feature = "no_function"))]
     Fn,
+    /// `yield`
+    ///
+    /// Reserved under the `no_function` feature.
+    #[cfg(not(feature = "no_function"))]
+    Yield,
     /// `continue`
     Continue,
     /// `break`
@@ -224,6 +274,12 @@ pub enum Token {
     Try,
     /// `catch`
     Catch,
+    /// `finally`
+    Finally,
+    /// `defer`
+    Defer,
+    /// `static`
+    Static,
     /// `+=`
     PlusAssign,
     /// `-=`
@@ -266,6 +322,11 @@ pub enum Token {
     /// Reserved under the `no_module` feature.
     #[cfg(not(feature = "no_module"))]
     As,
+    /// `module`
+    ///
+    /// Reserved under the `no_module` feature.
+    #[cfg(not(feature = "no_module"))]
+    Module,
     /// A lexer error.
     LexError(Box<LexError>),
     /// A comment block.
@@ -278,9 +339,6 @@ pub enum Token {
     #[cfg(not(feature = "no_custom_syntax"))]
     Custom(Box<Identifier>),
     /// A single character from the input stream, unprocessed.
-    ///
-    /// Not available under `no_custom_syntax`.
-    #[cfg(not(feature = "no_custom_syntax"))]
     UnprocessedRawChar(char),
     /// End of the input stream.
     /// Used as a placeholder for the end of input.
@@ -299,14 +357,16 @@ impl fmt::Display for Token {
             FloatConstant(v) => write!(f, "{}", v.0),
             #[cfg(feature = "decimal")]
             DecimalConstant(d) => write!(f, "{}", d.0),
+            #[cfg(feature = "bigint")]
+            BigIntConstant(n) => write!(f, "{}n", n.0),
             StringConstant(s) => write!(f, r#""{s}""#),
             InterpolatedString(..) => f.write_str("string"),
             CharConstant(c) => write!(f, "{c}"),
+            Label(s) => write!(f, "'{s}"),
             Identifier(s) => f.write_str(s),
             Reserved(s) => f.write_str(s),
             #[cfg(not(feature = "no_custom_syntax"))]
             Custom(s) => f.write_str(s),
-            #[cfg(not(feature = "no_custom_syntax"))]
             UnprocessedRawChar(c) => f.write_char(*c),
             LexError(err) => write!(f, "{err}"),
             Comment(s) => f.write_str(s),
@@ -325,199 +385,172 @@ impl fmt::Display for Token {
 const MIN_KEYWORD_LEN: usize = 1;
 const MAX_KEYWORD_LEN: usize = 8;
 const MIN_KEYWORD_HASH_VALUE: usize = 1;
-const MAX_KEYWORD_HASH_VALUE: usize = 152;
+const MAX_KEYWORD_HASH_VALUE: usize = 120;
 
 static KEYWORD_ASSOC_VALUES: [u8; 257] = [
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 115, 153, 100, 153, 110,
-    105, 40, 80, 2, 20, 25, 125, 95, 15, 40, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 55,
-    35, 10, 5, 0, 30, 110, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 120, 105, 100, 85, 90, 153, 125, 5,
-    0, 125, 35, 10, 100, 153, 20, 0, 153, 10, 0, 45, 55, 0, 153, 50, 55, 5, 0, 153, 0, 0, 35, 153,
-    45, 50, 30, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
-    153,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 46, 121, 25, 121, 35, 39,
+    5, 27, 57, 16, 7, 53, 59, 0, 33, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 31, 52, 3,
+    1, 10, 4, 68, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 5, 65, 30, 32, 45, 121, 86, 1, 16, 12, 19, 0,
+    23, 75, 89, 34, 58, 26, 0, 3, 1, 7, 121, 101, 18, 2, 28, 121, 0, 5, 39, 121, 34, 40, 24, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
+    121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121, 121,
 ];
-static KEYWORDS_LIST: [(&str, Token); 153] = [
+static KEYWORDS_LIST: [(&str, Token); 121] = [
     ("", Token::EOF),
-    (">", Token::GreaterThan),
-    (">=", Token::GreaterThanEqualsTo),
-    (")", Token::RightParen),
+    (".", Token::Period),
     ("", Token::EOF),
-    ("const", Token::Const),
     ("=", Token::Equals),
-    ("==", Token::EqualsTo),
-    ("continue", Token::Continue),
-    ("", Token::EOF),
-    ("catch", Token::Catch),
-    ("<", Token::LessThan),
-    ("<=", Token::LessThanEqualsTo),
-    ("for", Token::For),
-    ("loop", Token::Loop),
     ("", Token::EOF),
-    (".", Token::Period),
-    ("<<", Token::LeftShift),
-    ("<<=", Token::LeftShiftAssign),
-    ("", Token::EOF),
-    ("false", Token::False),
-    ("*", Token::Multiply),
-    ("*=", Token::MultiplyAssign),
-    ("let", Token::Let),
     ("", Token::EOF),
-    ("while", Token::While),
-    ("+", Token::Plus),
-    ("+=", Token::PlusAssign),
+    #[cfg(not(feature = "no_function"))]
+    ("fn", Token::Fn),
+    #[cfg(feature = "no_function")]
     ("", Token::EOF),
+    ("<", Token::LessThan),
+    ("<<=", Token::LeftShiftAssign),
+    ("<<", Token::LeftShift),
     ("", Token::EOF),
-    ("throw", Token::Throw),
-    ("}", Token::RightBrace),
-    (">>", Token::RightShift),
-    (">>=", Token::RightShiftAssign),
+    ("[", Token::LeftBracket),
     ("", Token::EOF),
     ("", Token::EOF),
-    (";", Token::SemiColon),
+    ("==", Token::EqualsTo),
+    ("+", Token::Plus),
+    ("<=", Token::LessThanEqualsTo),
     ("=>", Token::DoubleArrow),
+    (">>=", Token::RightShiftAssign),
     ("", Token::EOF),
-    ("else", Token::Else),
-    ("", Token::EOF),
-    ("/", Token::Divide),
-    ("/=", Token::DivideAssign),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("{", Token::LeftBrace),
-    ("**", Token::PowerOf),
+    ("+=", Token::PlusAssign),
+    (">", Token::GreaterThan),
+    ("do", Token::Do),
+    (">=", Token::GreaterThanEqualsTo),
+    ("enum", Token::Enum),
+    ("false", Token::False),
+    (">>", Token::RightShift),
     ("**=", Token::PowerOfAssign),
     ("", Token::EOF),
+    ("*=", Token::MultiplyAssign),
+    ("const", Token::Const),
+    ("let", Token::Let),
+    #[cfg(not(feature = "no_module"))]
+    ("module", Token::Module),
+    #[cfg(feature = "no_module")]
     ("", Token::EOF),
-    ("|", Token::Pipe),
-    ("|=", Token::OrAssign),
-    ("", Token::EOF),
-    ("", Token::EOF),
+    ("*", Token::Multiply),
     ("", Token::EOF),
-    (":", Token::Colon),
     ("..", Token::ExclusiveRange),
+    ("...", Token::Spread),
     ("..=", Token::InclusiveRange),
     ("", Token::EOF),
-    ("until", Token::Until),
-    ("switch", Token::Switch),
-    #[cfg(not(feature = "no_function"))]
-    ("private", Token::Private),
-    #[cfg(feature = "no_function")]
-    ("", Token::EOF),
-    ("try", Token::Try),
-    ("true", Token::True),
-    ("break", Token::Break),
-    ("return", Token::Return),
-    #[cfg(not(feature = "no_function"))]
-    ("fn", Token::Fn),
-    #[cfg(feature = "no_function")]
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    #[cfg(not(feature = "no_module"))]
-    ("import", Token::Import),
-    #[cfg(feature = "no_module")]
-    ("", Token::EOF),
     #[cfg(not(feature = "no_object"))]
     ("?.", Token::Elvis),
     #[cfg(feature = "no_object")]
     ("", Token::EOF),
     ("", Token::EOF),
+    ("**", Token::PowerOf),
+    ("else", Token::Else),
+    ("true", Token::True),
+    ("loop", Token::Loop),
+    ("^=", Token::XOrAssign),
+    ("/=", Token::DivideAssign),
     ("", Token::EOF),
+    ("%=", Token::ModuloAssign),
+    ("}", Token::RightBrace),
+    ("continue", Token::Continue),
+    #[cfg(not(feature = "no_function"))]
+    ("private", Token::Private),
+    #[cfg(feature = "no_function")]
     ("", Token::EOF),
-    #[cfg(not(feature = "no_module"))]
-    ("export", Token::Export),
-    #[cfg(feature = "no_module")]
-    ("", Token::EOF),
-    ("in", Token::In),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
+    ("&=", Token::AndAssign),
+    ("|=", Token::OrAssign),
+    ("struct", Token::Struct),
     ("(", Token::LeftParen),
-    ("||", Token::Or),
+    ("|>", Token::PipeArrow),
     ("", Token::EOF),
     ("", Token::EOF),
+    ("!=", Token::NotEqualsTo),
+    ("until", Token::Until),
+    ("]", Token::RightBracket),
+    ("try", Token::Try),
+    (":", Token::Colon),
     ("", Token::EOF),
     ("^", Token::XOr),
-    ("^=", Token::XOrAssign),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("_", Token::Underscore),
-    ("::", Token::DoubleColon),
-    ("", Token::EOF),
+    #[cfg(not(feature = "no_module"))]
+    ("export", Token::Export),
+    #[cfg(feature = "no_module")]
     ("", Token::EOF),
+    ("/", Token::Divide),
+    ("static", Token::Static),
+    ("{", Token::LeftBrace),
     ("", Token::EOF),
-    ("-", Token::Minus),
+    ("%", Token::Modulo),
     ("-=", Token::MinusAssign),
     ("", Token::EOF),
     ("", Token::EOF),
     ("", Token::EOF),
-    ("]", Token::RightBracket),
-    ("()", Token::Unit),
-    ("", Token::EOF),
+    #[cfg(not(feature = "no_index"))]
+    ("?[", Token::QuestionBracket),
+    #[cfg(feature = "no_index")]
     ("", Token::EOF),
     ("", Token::EOF),
+    ("??", Token::DoubleQuestion),
     ("&", Token::Ampersand),
-    ("&=", Token::AndAssign),
+    ("finally", Token::Finally),
+    ("|", Token::Pipe),
+    ("break", Token::Break),
     ("", Token::EOF),
     ("", Token::EOF),
+    ("&&", Token::And),
+    ("!in", Token::NotIn),
+    ("", Token::EOF),
     ("", Token::EOF),
-    ("%", Token::Modulo),
-    ("%=", Token::ModuloAssign),
     ("", Token::EOF),
+    #[cfg(not(feature = "no_function"))]
+    ("yield", Token::Yield),
+    #[cfg(feature = "no_function")]
     ("", Token::EOF),
+    ("_", Token::Underscore),
     ("", Token::EOF),
     ("!", Token::Bang),
-    ("!=", Token::NotEqualsTo),
-    ("!in", Token::NotIn),
     ("", Token::EOF),
+    ("in", Token::In),
+    ("throw", Token::Throw),
+    ("catch", Token::Catch),
     ("", Token::EOF),
-    ("[", Token::LeftBracket),
-    ("if", Token::If),
     ("", Token::EOF),
+    #[cfg(not(feature = "no_module"))]
+    ("import", Token::Import),
+    #[cfg(feature = "no_module")]
     ("", Token::EOF),
+    ("#{", Token::MapStart),
+    ("()", Token::Unit),
     ("", Token::EOF),
+    ("switch", Token::Switch),
+    (";", Token::SemiColon),
+    ("||", Token::Or),
     (",", Token::Comma),
-    ("do", Token::Do),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
     #[cfg(not(feature = "no_module"))]
     ("as", Token::As),
     #[cfg(feature = "no_module")]
     ("", Token::EOF),
     ("", Token::EOF),
+    ("return", Token::Return),
+    ("for", Token::For),
     ("", Token::EOF),
+    ("while", Token::While),
+    ("if", Token::If),
+    (")", Token::RightParen),
+    ("::", Token::DoubleColon),
     ("", Token::EOF),
+    ("defer", Token::Defer),
+    ("-", Token::Minus),
     ("", Token::EOF),
-    #[cfg(not(feature = "no_index"))]
-    ("?[", Token::QuestionBracket),
-    #[cfg(feature = "no_index")]
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("??", Token::DoubleQuestion),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("&&", Token::And),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("", Token::EOF),
-    ("#{", Token::MapStart),
 ];
 
 // Table-driven reserved symbol recognizer generated by GNU `gperf` on the file `tools/reserved.txt`.
@@ -566,7 +599,7 @@ static RESERVED_LIST: [(&str, bool, bool, bool); 150] = [
     ("===", true, false, false),
     ("sync", true, false, false),
     ("curry", true, true, true),
-    ("static", true, false, false),
+    ("static", false, false, false),
     ("default", true, false, false),
     ("!==", true, false, false),
     ("is_shared", cfg!(not(feature = "no_closure")), true, true),
@@ -580,7 +613,7 @@ static RESERVED_LIST: [(&str, bool, bool, bool); 150] = [
     ("?.", cfg!(feature = "no_object"), false, false),
     ("", false, false, false),
     ("is_def_fn", cfg!(not(feature = "no_function")), true, false),
-    ("yield", true, false, false),
+    ("yield", cfg!(feature = "no_function"), false, false),
     ("", false, false, false),
     ("fn", cfg!(feature = "no_function"), false, false),
     ("new", true, false, false),
@@ -593,7 +626,7 @@ static RESERVED_LIST: [(&str, bool, bool, bool); 150] = [
     ("await", true, false, false),
     ("", false, false, false),
     (":=", true, false, false),
-    ("...", true, false, false),
+    ("", false, false, false),
     ("null", true, false, false),
     ("debug", true, true, false),
     ("@", true, false, false),
@@ -622,11 +655,11 @@ static RESERVED_LIST: [(&str, bool, bool, bool); 150] = [
     ("", false, false, false),
     ("", false, false, false),
     ("", false, false, false),
-    ("|>", true, false, false),
     ("", false, false, false),
     ("", false, false, false),
     ("", false, false, false),
-    ("module", true, false, false),
+    ("", false, false, false),
+    ("module", cfg!(feature = "no_module"), false, false),
     ("?[", cfg!(feature = "no_index"), false, false),
     ("", false, false, false),
     ("", false, false, false),
@@ -710,9 +743,12 @@ impl Token {
             FloatConstant(..) => false,
             #[cfg(feature = "decimal")]
             DecimalConstant(..) => false,
+            #[cfg(feature = "bigint")]
+            BigIntConstant(..) => false,
             StringConstant(..)
             | InterpolatedString(..)
             | CharConstant(..)
+            | Label(..)
             | Identifier(..)
             | Reserved(..) => false,
             #[cfg(not(feature = "no_custom_syntax"))]
@@ -762,6 +798,7 @@ impl Token {
             QuestionBracket => "?[",
             ExclusiveRange => "..",
             InclusiveRange => "..=",
+            Spread => "...",
             MapStart => "#{",
             Equals => "=",
             True => "true",
@@ -771,6 +808,8 @@ impl Token {
             If => "if",
             Else => "else",
             Switch => "switch",
+            Enum => "enum",
+            Struct => "struct",
             Do => "do",
             While => "while",
             Until => "until",
@@ -787,6 +826,7 @@ impl Token {
             NotEqualsTo => "!=",
             Pipe => "|",
             Or => "||",
+            PipeArrow => "|>",
             Ampersand => "&",
             And => "&&",
             Continue => "continue",
@@ -795,6 +835,8 @@ impl Token {
             Throw => "throw",
             Try => "try",
             Catch => "catch",
+            Finally => "finally",
+            Defer => "defer",
             PlusAssign => "+=",
             MinusAssign => "-=",
             MultiplyAssign => "*=",
@@ -815,7 +857,10 @@ impl Token {
             #[cfg(not(feature = "no_function"))]
             Fn => "fn",
             #[cfg(not(feature = "no_function"))]
+            Yield => "yield",
+            #[cfg(not(feature = "no_function"))]
             Private => "private",
+            Static => "static",
 
             #[cfg(not(feature = "no_module"))]
             Import => "import",
@@ -823,6 +868,8 @@ impl Token {
             Export => "export",
             #[cfg(not(feature = "no_module"))]
             As => "as",
+            #[cfg(not(feature = "no_module"))]
+            Module => "module",
 
             _ => panic!("token is not a literal symbol"),
         }
@@ -937,6 +984,7 @@ impl Token {
             _ => hash_val += KEYWORD_ASSOC_VALUES[(utf8[1] as usize) + 1] as usize,
         }
         hash_val += KEYWORD_ASSOC_VALUES[utf8[0] as usize] as usize;
+        hash_val += KEYWORD_ASSOC_VALUES[utf8[len - 1] as usize] as usize;
 
         if !(MIN_KEYWORD_HASH_VALUE..=MAX_KEYWORD_HASH_VALUE).contains(&hash_val) {
             return None;
@@ -969,6 +1017,7 @@ impl Token {
             DoubleQuestion   | // ?? - is unary
             ExclusiveRange   | // .. - is unary
             InclusiveRange   | // ..= - is unary
+            Spread           | // ... - is unary
             LeftBrace        | // { -expr } - is unary
             // RightBrace    | // { expr } - expr not unary & is closing
             LeftParen        | // ( -expr ) - is unary
@@ -1002,6 +1051,7 @@ impl Token {
             LessThanEqualsTo |
             GreaterThanEqualsTo |
             Pipe             |
+            PipeArrow        |
             Ampersand        |
             If               |
             //Do             |
@@ -1034,6 +1084,8 @@ impl Token {
         use Token::*;
 
         Precedence::new(match self {
+            PipeArrow => 20,
+
             Or | XOr | Pipe => 30,
 
             And | Ampersand => 60,
@@ -1084,11 +1136,11 @@ impl Token {
             LeftBrace | RightBrace | LeftParen | RightParen | LeftBracket | RightBracket | Plus
             | UnaryPlus | Minus | UnaryMinus | Multiply | Divide | Modulo | PowerOf | LeftShift
             | RightShift | SemiColon | Colon | DoubleColon | Comma | Period | DoubleQuestion
-            | ExclusiveRange | InclusiveRange | MapStart | Equals | LessThan | GreaterThan
-            | LessThanEqualsTo | GreaterThanEqualsTo | EqualsTo | NotEqualsTo | Bang | Pipe
-            | Or | XOr | Ampersand | And | PlusAssign | MinusAssign | MultiplyAssign
-            | DivideAssign | LeftShiftAssign | RightShiftAssign | AndAssign | OrAssign
-            | XOrAssign | ModuloAssign | PowerOfAssign => true,
+            | ExclusiveRange | InclusiveRange | Spread | MapStart | Equals | LessThan
+            | GreaterThan | LessThanEqualsTo | GreaterThanEqualsTo | EqualsTo | NotEqualsTo
+            | Bang | Pipe | Or | PipeArrow | XOr | Ampersand | And | PlusAssign | MinusAssign
+            | MultiplyAssign | DivideAssign | LeftShiftAssign | RightShiftAssign | AndAssign
+            | OrAssign | XOrAssign | ModuloAssign | PowerOfAssign => true,
 
             #[cfg(not(feature = "no_object"))]
             Elvis => true,
@@ -1109,13 +1161,17 @@ impl Token {
 
         match self {
             #[cfg(not(feature = "no_function"))]
-            Fn | Private => true,
+            Fn | Private | Yield => true,
+
+            Static => true,
 
             #[cfg(not(feature = "no_module"))]
-            Import | Export | As => true,
+            Import | Export | As | Module => true,
 
             True | False | Let | Const | If | Else | Do | While | Until | Loop | For | In
-            | Continue | Break | Return | Throw | Try | Catch => true,
+            | Continue | Break | Return | Throw | Try | Catch | Finally | Defer | Enum | Struct => {
+                true
+            }
 
             _ => false,
         }
@@ -1611,6 +1667,250 @@ pub fn parse_string_literal(
     Ok((result, interpolated, first_char))
 }
 
+/// Check if the input stream, right after a `"`, starts a multi-line heredoc string literal,
+/// i.e. `""` followed by a line break.
+///
+/// The input stream is left untouched.
+fn is_heredoc_start(stream: &mut (impl InputStream + ?Sized)) -> bool {
+    if stream.peek_next() != Some('"') {
+        return false;
+    }
+
+    stream.get_next();
+
+    let result = if stream.peek_next() == Some('"') {
+        stream.get_next();
+        let result = matches!(stream.peek_next(), Some('\r' | '\n'));
+        stream.unget('"');
+        result
+    } else {
+        false
+    };
+
+    stream.unget('"');
+    result
+}
+
+/// _(internals)_ Parse a multi-line heredoc string literal wrapped by `"""`.
+/// Exported under the `internals` feature only.
+///
+/// The opening `"""` must be immediately followed by a line break, which is not part of the text.
+///
+/// Like a back-tick string, the text is verbatim (i.e. there are no escape sequences) and can be
+/// interpolated via `${` ... `}`.
+///
+/// If `opening` is `true`, the first `"` is already consumed and the rest of the opening `"""`,
+/// together with its line break, is next in the input stream.
+/// Otherwise, the text continues after an interpolation.
+///
+/// Returns the parsed text and a boolean indicating whether the string is terminated by an
+/// interpolation `${`.
+///
+/// The text is returned as-is; [`dedent_heredoc`] must be called on the entire literal
+/// afterwards.
+///
+/// Like [`parse_string_literal`], this function does not throw a [`LexError`] for an
+/// unterminated literal at _{EOF}_, in order to facilitate parsing a script line-by-line.
+/// `state.is_within_text_terminated_by` is set to `Some("\"\"\"")` in this case.
+pub fn parse_heredoc_literal(
+    stream: &mut (impl InputStream + ?Sized),
+    state: &mut TokenizeState,
+    pos: &mut Position,
+    opening: bool,
+) -> Result<(SmartString, bool), (LexError, Position)> {
+    #[cfg(not(feature = "unchecked"))]
+    let start = *pos;
+    let mut result = SmartString::new_const();
+    let mut interpolated = false;
+    let mut quotes = 0;
+
+    state.is_within_text_terminated_by = Some(HEREDOC_WRAPPER.into());
+    if let Some(ref mut last) = state.last_token {
+        last.clear();
+        last.push('"');
+    }
+
+    if opening {
+        // Skip the rest of the opening `"""` and its line break
+        for _ in 0..2 {
+            stream.eat_next_and_advance(pos);
+        }
+        if stream.peek_next() == Some('\r') {
+            stream.get_next();
+        }
+        if stream.peek_next() == Some('\n') {
+            stream.get_next();
+        }
+        pos.new_line();
+
+        if let Some(ref mut last) = state.last_token {
+            last.push_str("\"\"\n");
+        }
+    }
+
+    loop {
+        let next_char = match stream.get_next() {
+            Some(ch) => {
+                pos.advance();
+                ch
+            }
+            None => {
+                pos.advance();
+                result.extend(repeat('"').take(quotes));
+                break;
+            }
+        };
+
+        if let Some(ref mut last) = state.last_token {
+            last.push(next_char);
+        }
+
+        // Close wrapper
+        if next_char == '"' {
+            quotes += 1;
+
+            if quotes == HEREDOC_WRAPPER.len() {
+                state.is_within_text_terminated_by = None;
+                break;
+            }
+            continue;
+        }
+
+        result.extend(repeat('"').take(quotes));
+        quotes = 0;
+
+        match (next_char, stream.peek_next()) {
+            // `${` starts interpolation
+            ('$', Some('{')) => {
+                interpolated = true;
+                state.is_within_text_terminated_by = None;
+                break;
+            }
+            // `\${` escapes the `$`
+            ('\\', Some('$')) => {
+                stream.get_next();
+
+                if stream.peek_next() == Some('{') {
+                    pos.advance();
+                    if let Some(ref mut last) = state.last_token {
+                        last.push('$');
+                    }
+                    result.push('$');
+                } else {
+                    stream.unget('$');
+                    result.push('\\');
+                }
+            }
+            // \r - ignore if followed by \n
+            ('\r', Some('\n')) => (),
+            // LF
+            ('\n', ..) => {
+                pos.new_line();
+                result.push('\n');
+            }
+            (ch, ..) => result.push(ch),
+        }
+
+        // Check string length
+        #[cfg(not(feature = "unchecked"))]
+        if let Some(max) = state.max_string_len {
+            if result.len() > max.get() {
+                return Err((LexError::StringTooLong(max.get()), start));
+            }
+        }
+    }
+
+    Ok((result, interpolated))
+}
+
+/// _(internals)_ Strip the common leading whitespace from all the lines of a multi-line heredoc
+/// string literal, as well as the line break before the closing `"""` if it is on a line by
+/// itself.
+/// Exported under the `internals` feature only.
+///
+/// The literal is passed in as its text segments, which are separated by interpolations.
+/// An interpolation counts as non-whitespace text.
+///
+/// The common leading whitespace is the longest whitespace prefix shared by all the lines, so tabs
+/// and spaces are never mixed up.  Lines that contain only whitespace do not count towards it.
+pub fn dedent_heredoc(segments: &mut [SmartString]) {
+    let num_segments = segments.len();
+
+    // Remove the last line if it contains only whitespace
+    if let Some(last) = segments.last_mut() {
+        match last.rfind('\n') {
+            Some(n) if last[n + 1..].chars().all(char::is_whitespace) => last.truncate(n),
+            None if num_segments == 1 && last.chars().all(char::is_whitespace) => last.clear(),
+            _ => (),
+        }
+    }
+
+    // Find the longest leading whitespace common to all lines
+    let mut indent: Option<SmartString> = None;
+
+    for (i, segment) in segments.iter().enumerate() {
+        let num_lines = segment.split('\n').count();
+
+        for (j, line) in segment.split('\n').enumerate() {
+            // The text after an interpolation is not the start of a line
+            if i > 0 && j == 0 {
+                continue;
+            }
+
+            let text = line.trim_start_matches(char::is_whitespace);
+            let followed_by_interpolation = i < num_segments - 1 && j == num_lines - 1;
+
+            // Skip lines that contain only whitespace
+            if text.is_empty() && !followed_by_interpolation {
+                continue;
+            }
+
+            let prefix = &line[..line.len() - text.len()];
+
+            indent = Some(match indent {
+                Some(mut indent) => {
+                    indent.truncate(common_prefix_len(&indent, prefix));
+                    indent
+                }
+                None => prefix.into(),
+            });
+        }
+    }
+
+    let indent = match indent {
+        Some(indent) if !indent.is_empty() => indent,
+        _ => return,
+    };
+
+    // Strip the common leading whitespace from each line
+    for (i, segment) in segments.iter_mut().enumerate() {
+        let mut text = SmartString::new_const();
+
+        for (j, line) in segment.split('\n').enumerate() {
+            if j > 0 {
+                text.push('\n');
+            }
+            if i > 0 && j == 0 {
+                text.push_str(line);
+            } else {
+                text.push_str(&line[common_prefix_len(line, &indent)..]);
+            }
+        }
+
+        *segment = text;
+    }
+}
+
+/// Length (in bytes) of the longest common prefix of two strings.
+#[must_use]
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, ..)| x.len_utf8())
+        .sum()
+}
+
 /// Scan for a block comment until the end.
 fn scan_block_comment(
     stream: &mut (impl InputStream + ?Sized),
@@ -1745,6 +2045,21 @@ fn get_next_token_inner(
 
     // Within text?
     match state.is_within_text_terminated_by.take() {
+        Some(ch) if ch == HEREDOC_WRAPPER => {
+            let mut start_pos = *pos;
+            start_pos.advance();
+
+            return parse_heredoc_literal(stream, state, pos, false).map_or_else(
+                |(err, err_pos)| (Token::LexError(err.into()), err_pos),
+                |(result, interpolated)| {
+                    if interpolated {
+                        (Token::InterpolatedString(result.into()), start_pos)
+                    } else {
+                        (Token::StringConstant(result.into()), start_pos)
+                    }
+                },
+            );
+        }
         Some(ch) if ch.starts_with('#') => {
             return parse_raw_string_literal(stream, state, pos, ch.len()).map_or_else(
                 |(err, err_pos)| (Token::LexError(err.into()), err_pos),
@@ -1888,6 +2203,36 @@ fn get_next_token_inner(
                     }
                 }
 
+                // 123n - big integer
+                #[cfg(feature = "bigint")]
+                if !_has_period && stream.peek_next() == Some('n') {
+                    stream.eat_next_and_advance(pos);
+
+                    let digits = radix_base.map_or(&*result, |_| &result[2..]);
+                    let value = num_bigint::BigInt::parse_bytes(
+                        digits.as_bytes(),
+                        radix_base.unwrap_or(10),
+                    );
+
+                    let num_pos = negated.map_or(start_pos, |negated_pos| {
+                        result.insert(0, '-');
+                        negated_pos
+                    });
+                    result.push('n');
+
+                    if let Some(ref mut last) = state.last_token {
+                        *last = result.clone();
+                    }
+
+                    let token = match value {
+                        Some(v) if negated.is_some() => Token::BigIntConstant((-v, result).into()),
+                        Some(v) => Token::BigIntConstant((v, result).into()),
+                        None => Token::LexError(LERR::MalformedNumber(result.to_string()).into()),
+                    };
+
+                    return (token, num_pos);
+                }
+
                 let num_pos = negated.map_or(start_pos, |negated_pos| {
                     result.insert(0, '-');
                     negated_pos
@@ -1946,6 +2291,22 @@ fn get_next_token_inner(
                 return (token, num_pos);
             }
 
+            // """ - heredoc string literal
+            ('"', '"') if is_heredoc_start(stream) => {
+                return parse_heredoc_literal(stream, state, pos, true).map_or_else(
+                    |(err, err_pos)| (Token::LexError(err.into()), err_pos),
+                    |(mut result, interpolated)| {
+                        if interpolated {
+                            // Let the parser know to strip the indentation of the entire literal
+                            state.tokenizer_control.borrow_mut().is_within_heredoc = true;
+                            (Token::InterpolatedString(result.into()), start_pos)
+                        } else {
+                            dedent_heredoc(std::slice::from_mut(&mut result));
+                            (Token::StringConstant(result.into()), start_pos)
+                        }
+                    },
+                );
+            }
             // " - string literal
             ('"', ..) => {
                 return parse_string_literal(stream, state, pos, c, false, true, false)
@@ -2002,6 +2363,45 @@ fn get_next_token_inner(
                     start_pos,
                 )
             }
+            // ' - loop label
+            ('\'', cc) if cc == '_' || is_id_first_alphabetic(cc) => {
+                let mut label = SmartString::new_const();
+
+                while let Some(next_char) = stream.peek_next() {
+                    match next_char {
+                        x if is_id_continue(x) => {
+                            stream.eat_next_and_advance(pos);
+                            label.push(x);
+                        }
+                        _ => break,
+                    }
+                }
+
+                // A single character followed by ' is a character literal
+                if stream.peek_next() == Some('\'') {
+                    stream.eat_next_and_advance(pos);
+
+                    if let Some(ref mut last) = state.last_token {
+                        last.push('\'');
+                        last.push_str(&label);
+                        last.push('\'');
+                    }
+
+                    let mut chars = label.chars();
+                    let first = chars.next().unwrap();
+
+                    return if chars.next().is_some() {
+                        (
+                            Token::LexError(LERR::MalformedChar(label.to_string()).into()),
+                            start_pos,
+                        )
+                    } else {
+                        (Token::CharConstant(first), start_pos)
+                    };
+                }
+
+                return (Token::Label(label.into()), start_pos);
+            }
             ('\'', ..) => {
                 return parse_string_literal(stream, state, pos, c, false, false, false)
                     .map_or_else(
@@ -2220,7 +2620,7 @@ fn get_next_token_inner(
                     match stream.peek_next() {
                         Some('.') => {
                             stream.eat_next_and_advance(pos);
-                            Token::Reserved(Box::new("...".into()))
+                            Token::Spread
                         }
                         Some('=') => {
                             stream.eat_next_and_advance(pos);
@@ -2249,7 +2649,6 @@ fn get_next_token_inner(
             }
             ('=', ..) => return (Token::Equals, start_pos),
 
-            #[cfg(not(feature = "no_module"))]
             (':', ':') => {
                 stream.eat_next_and_advance(pos);
 
@@ -2363,7 +2762,7 @@ fn get_next_token_inner(
             }
             ('|', '>') => {
                 stream.eat_next_and_advance(pos);
-                return (Token::Reserved(Box::new("|>".into())), start_pos);
+                return (Token::PipeArrow, start_pos);
             }
             ('|', ..) => return (Token::Pipe, start_pos),
 
@@ -2680,39 +3079,43 @@ impl Iterator for TokenIterator<'_> {
     type Item = (Token, Position);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (within_interpolated, _char_mode, compress_script) = {
+        let (within_interpolated, char_mode, compress_script) = {
             let control = &mut *self.state.tokenizer_control.borrow_mut();
 
+            control.num_tokens += 1;
+
             if control.is_within_text {
-                // Switch to text mode terminated by back-tick
-                self.state.is_within_text_terminated_by = Some("`".to_string().into());
+                // Switch to text mode terminated by back-tick, or by `"""` for a heredoc
+                let wrapper = if control.is_within_heredoc {
+                    HEREDOC_WRAPPER
+                } else {
+                    "`"
+                };
+                self.state.is_within_text_terminated_by = Some(wrapper.into());
                 // Reset it
                 control.is_within_text = false;
+                control.is_within_heredoc = false;
             }
 
             // Check if in single-character mode
-            #[cfg(not(feature = "no_custom_syntax"))]
             let in_char_mode = std::mem::take(&mut control.in_char_mode);
 
             (
                 self.state.is_within_text_terminated_by.is_some(),
-                #[cfg(not(feature = "no_custom_syntax"))]
                 in_char_mode,
-                #[cfg(feature = "no_custom_syntax")]
-                false,
                 control.compressed.is_some(),
             )
         };
 
-        #[cfg(not(feature = "no_custom_syntax"))]
-        if _char_mode {
+        if char_mode {
             if let Some(ch) = self.stream.get_next() {
                 let pos = self.pos;
                 match ch {
                     '\n' => self.pos.new_line(),
                     _ => self.pos.advance(),
                 }
-                // Compacting support for `$raw$` custom syntax: append the
+                // Compacting support for `$raw$` custom syntax and format
+                // specifications in interpolated strings: append the
                 // consumed raw character verbatim to the compressed buffer.
                 // Raw characters are not produced by `get_next_token` and
                 // carry no `last_token`, so the normal token-compression
@@ -2812,12 +3215,40 @@ impl Iterator for TokenIterator<'_> {
             r => r,
         };
 
+        // Collect deprecated symbols, if needed
+        if !self.engine.deprecated_symbols.is_empty() {
+            let syntax = match token {
+                Token::Identifier(ref s) | Token::Reserved(ref s) => Some(s.as_str()),
+                #[cfg(not(feature = "no_custom_syntax"))]
+                Token::Custom(ref s) => Some(s.as_str()),
+                ref token if token.is_literal() => Some(token.literal_syntax()),
+                _ => None,
+            };
+
+            if let Some(syntax) = syntax.filter(|s| self.engine.is_symbol_deprecated(s)) {
+                let control = &mut *self.state.tokenizer_control.borrow_mut();
+
+                if let Some(ref mut deprecated) = control.deprecated {
+                    deprecated.push((syntax.into(), pos));
+                }
+            }
+        }
+
         // Run the mapper, if any
         let token = match self.token_mapper {
             Some(func) => func(token, pos, &self.state),
             None => token,
         };
 
+        // Collect the spans of tokens, if needed
+        if token != Token::EOF {
+            let control = &mut *self.state.tokenizer_control.borrow_mut();
+
+            if let Some(ref mut spans) = control.spans {
+                spans.push(Span::new(pos, self.pos));
+            }
+        }
+
         // Collect the compressed script, if needed
         if compress_script {
             let control = &mut *self.state.tokenizer_control.borrow_mut();
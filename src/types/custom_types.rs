@@ -27,6 +27,11 @@ pub struct CustomTypeInfo {
     /// Each line in non-block doc-comments starts with `///`.
     #[cfg(feature = "metadata")]
     pub comments: crate::StaticVec<crate::SmartString>,
+    /// Names of the fields, in order of declaration, if this is a script-defined `struct` type.
+    ///
+    /// Not available under `no_object`.
+    #[cfg(not(feature = "no_object"))]
+    pub fields: Option<crate::StaticVec<Identifier>>,
 }
 
 /// _(internals)_ A collection of custom types.
@@ -52,6 +57,12 @@ impl CustomTypesCollection {
     pub fn clear(&mut self) {
         self.0.clear();
     }
+    /// Is the [`CustomTypesCollection`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
     /// Register a custom type.
     #[inline(always)]
     pub fn add(&mut self, type_name: impl Into<Identifier>, name: impl Into<Identifier>) {
@@ -61,6 +72,8 @@ impl CustomTypesCollection {
             display_name: name.into(),
             #[cfg(feature = "metadata")]
             comments: <_>::default(),
+            #[cfg(not(feature = "no_object"))]
+            fields: None,
         };
         self.add_raw(type_name, custom_type);
     }
@@ -79,6 +92,8 @@ impl CustomTypesCollection {
             type_name: type_name.clone(),
             display_name: name.into(),
             comments: comments.into_iter().map(Into::into).collect(),
+            #[cfg(not(feature = "no_object"))]
+            fields: None,
         };
         self.add_raw(type_name, custom_type);
     }
@@ -92,6 +107,8 @@ impl CustomTypesCollection {
                 display_name: name.into(),
                 #[cfg(feature = "metadata")]
                 comments: <_>::default(),
+                #[cfg(not(feature = "no_object"))]
+                fields: None,
             },
         );
     }
@@ -107,6 +124,8 @@ impl CustomTypesCollection {
                 display_name: name.into(),
                 #[cfg(feature = "metadata")]
                 comments: comments.iter().map(|&s| s.into()).collect(),
+                #[cfg(not(feature = "no_object"))]
+                fields: None,
             },
         );
     }
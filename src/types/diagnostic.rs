@@ -0,0 +1,156 @@
+//! Module containing definitions of diagnostics (warnings) reported when compiling a script.
+
+use crate::Position;
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum Severity {
+    /// A hint that the script may be improved.
+    Hint,
+    /// A warning that the script likely contains a mistake.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hint => f.write_str("hint"),
+            Self::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// Type of a [`Diagnostic`] reported when compiling a script.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[non_exhaustive]
+#[must_use]
+pub enum DiagnosticType {
+    /// A variable is defined but never used. Wrapped value is the variable name.
+    UnusedVariable(String),
+    /// A function parameter is never used. Wrapped value is the parameter name.
+    UnusedParameter(String),
+    /// A variable shadows another variable of the same name.
+    /// Wrapped value is the variable name.
+    ///
+    /// This is reported even when shadowing is allowed.
+    ShadowedVariable(String),
+    /// A statement can never be reached because it follows a `return`, `throw`, `break` or
+    /// `continue`.
+    UnreachableCode,
+    /// The condition of an `if`, `while` or `do` statement is a constant.
+    /// Wrapped value is the constant.
+    ConstantCondition(bool),
+    /// A function is called that is neither defined in the script nor registered with the
+    /// [`Engine`][crate::Engine]. Wrapped values are the function name and number of arguments.
+    UnknownFunction(String, usize),
+    /// A function name is passed as a string where a function pointer is expected (e.g.
+    /// `call("foo")` or the deprecated `map("foo")` on arrays). Wrapped value is the function name.
+    FnNameString(String),
+    /// A keyword or operator marked deprecated via
+    /// [`Engine::deprecate_symbol`][crate::Engine::deprecate_symbol] is used.
+    /// Wrapped values are the symbol and the note on its replacement (if any).
+    Deprecated(String, String),
+}
+
+impl fmt::Display for DiagnosticType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnusedVariable(s) => write!(f, "Variable '{s}' is never used"),
+            Self::UnusedParameter(s) => write!(f, "Parameter '{s}' is never used"),
+            Self::ShadowedVariable(s) => write!(f, "Variable '{s}' shadows a previous variable"),
+            Self::UnreachableCode => f.write_str("Unreachable code"),
+            Self::ConstantCondition(b) => write!(f, "Condition is always {b}"),
+            Self::UnknownFunction(s, 1) => {
+                write!(f, "Function '{s}' with 1 parameter is not defined")
+            }
+            Self::UnknownFunction(s, n) => {
+                write!(f, "Function '{s}' with {n} parameters is not defined")
+            }
+            Self::FnNameString(s) => write!(
+                f,
+                "Function name '{s}' is passed as a string; use Fn(\"{s}\") instead"
+            ),
+            Self::Deprecated(s, note) if note.is_empty() => write!(f, "'{s}' is deprecated"),
+            Self::Deprecated(s, note) => write!(f, "'{s}' is deprecated: {note}"),
+        }
+    }
+}
+
+impl DiagnosticType {
+    /// Get the stable code of this diagnostic type (e.g. `"unused_variable"`), which can be used
+    /// to filter diagnostics.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::UnusedVariable(..) => "unused_variable",
+            Self::UnusedParameter(..) => "unused_parameter",
+            Self::ShadowedVariable(..) => "shadowed_variable",
+            Self::UnreachableCode => "unreachable_code",
+            Self::ConstantCondition(..) => "constant_condition",
+            Self::UnknownFunction(..) => "unknown_function",
+            Self::FnNameString(..) => "fn_name_string",
+            Self::Deprecated(..) => "deprecated",
+        }
+    }
+    /// Get the [severity][Severity] of this diagnostic type.
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        match self {
+            Self::ShadowedVariable(..) => Severity::Hint,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// A diagnostic (warning) reported when compiling a script.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[must_use]
+pub struct Diagnostic(
+    /// Diagnostic type.
+    pub DiagnosticType,
+    /// [Position] of the diagnostic.
+    pub Position,
+);
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.severity(), self.0, self.code())?;
+
+        // Do not write any position if None
+        if !self.1.is_none() {
+            write!(f, " ({})", self.1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Diagnostic {
+    /// Get the [type][DiagnosticType] of this diagnostic.
+    #[inline(always)]
+    pub const fn diagnostic_type(&self) -> &DiagnosticType {
+        &self.0
+    }
+    /// Get the stable code of this diagnostic (e.g. `"unused_variable"`).
+    #[inline(always)]
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        self.0.code()
+    }
+    /// Get the [severity][Severity] of this diagnostic.
+    #[inline(always)]
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        self.0.severity()
+    }
+    /// Get the [position][Position] of this diagnostic.
+    #[inline(always)]
+    #[must_use]
+    pub const fn position(&self) -> Position {
+        self.1
+    }
+}
@@ -1,6 +1,6 @@
 //! Helper module which defines the [`Dynamic`] data type.
 
-use crate::{ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, INT};
+use crate::{EnumValue, ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, Tuple, INT};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
@@ -76,6 +76,10 @@ pub enum Union {
     /// Exported under the `decimal` feature only.
     #[cfg(feature = "decimal")]
     Decimal(Box<rust_decimal::Decimal>, Tag, AccessMode),
+    /// _(bigint)_ An arbitrary-precision integer value.
+    /// Exported under the `bigint` feature only.
+    #[cfg(feature = "bigint")]
+    BigInt(Box<num_bigint::BigInt>, Tag, AccessMode),
     /// An array value.
     #[cfg(not(feature = "no_index"))]
     Array(Box<Array>, Tag, AccessMode),
@@ -87,6 +91,13 @@ pub enum Union {
     Map(Box<Map>, Tag, AccessMode),
     /// A function pointer.
     FnPtr(Box<FnPtr>, Tag, AccessMode),
+    /// A tuple value.
+    Tuple(Box<Tuple>, Tag, AccessMode),
+    /// A value of a script-defined `enum` type.
+    Enum(Box<EnumValue>, Tag, AccessMode),
+    /// A value of a script-defined `struct` type.
+    #[cfg(not(feature = "no_object"))]
+    Struct(Box<crate::StructValue>, Tag, AccessMode),
     /// A timestamp value.
     #[cfg(not(feature = "no_time"))]
     TimeStamp(Box<Instant>, Tag, AccessMode),
@@ -192,16 +203,20 @@ impl Dynamic {
             | Union::Char(_, tag, _)
             | Union::Int(_, tag, _)
             | Union::FnPtr(_, tag, _)
+            | Union::Tuple(_, tag, _)
+            | Union::Enum(_, tag, _)
             | Union::Variant(_, tag, _) => tag,
 
             #[cfg(not(feature = "no_float"))]
             Union::Float(_, tag, _) => tag,
             #[cfg(feature = "decimal")]
             Union::Decimal(_, tag, _) => tag,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, tag, _) => tag,
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, tag, _) | Union::Blob(_, tag, _) => tag,
             #[cfg(not(feature = "no_object"))]
-            Union::Map(_, tag, _) => tag,
+            Union::Map(_, tag, _) | Union::Struct(_, tag, _) => tag,
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(_, tag, _) => tag,
             #[cfg(not(feature = "no_closure"))]
@@ -217,16 +232,20 @@ impl Dynamic {
             | Union::Char(_, ref mut tag, _)
             | Union::Int(_, ref mut tag, _)
             | Union::FnPtr(_, ref mut tag, _)
+            | Union::Tuple(_, ref mut tag, _)
+            | Union::Enum(_, ref mut tag, _)
             | Union::Variant(_, ref mut tag, _) => *tag = value,
 
             #[cfg(not(feature = "no_float"))]
             Union::Float(_, ref mut tag, _) => *tag = value,
             #[cfg(feature = "decimal")]
             Union::Decimal(_, ref mut tag, _) => *tag = value,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_index"))]
             Union::Array(_, ref mut tag, _) | Union::Blob(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_object"))]
-            Union::Map(_, ref mut tag, _) => *tag = value,
+            Union::Map(_, ref mut tag, _) | Union::Struct(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_closure"))]
@@ -241,6 +260,18 @@ impl Dynamic {
     pub const fn is_variant(&self) -> bool {
         matches!(self.0, Union::Variant(..))
     }
+    /// Does this [`Dynamic`] hold a value whose operators can be overloaded by script-defined
+    /// operator functions, i.e. a custom type or a struct?
+    #[inline(always)]
+    #[must_use]
+    pub(crate) const fn is_overloadable(&self) -> bool {
+        match self.0 {
+            Union::Variant(..) => true,
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(..) => true,
+            _ => false,
+        }
+    }
     /// Is the value held by this [`Dynamic`] shared?
     ///
     /// Not available under `no_closure`.
@@ -301,9 +332,23 @@ impl Dynamic {
         if TypeId::of::<T>() == TypeId::of::<rust_decimal::Decimal>() {
             return matches!(self.0, Union::Decimal(..));
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<num_bigint::BigInt>() {
+            return matches!(self.0, Union::BigInt(..));
+        }
         if TypeId::of::<T>() == TypeId::of::<FnPtr>() {
             return matches!(self.0, Union::FnPtr(..));
         }
+        if TypeId::of::<T>() == TypeId::of::<Tuple>() {
+            return matches!(self.0, Union::Tuple(..));
+        }
+        if TypeId::of::<T>() == TypeId::of::<EnumValue>() {
+            return matches!(self.0, Union::Enum(..));
+        }
+        #[cfg(not(feature = "no_object"))]
+        if TypeId::of::<T>() == TypeId::of::<crate::StructValue>() {
+            return matches!(self.0, Union::Struct(..));
+        }
         #[cfg(not(feature = "no_time"))]
         if TypeId::of::<T>() == TypeId::of::<crate::Instant>() {
             return matches!(self.0, Union::TimeStamp(..));
@@ -329,6 +374,8 @@ impl Dynamic {
             Union::Float(..) => TypeId::of::<crate::FLOAT>(),
             #[cfg(feature = "decimal")]
             Union::Decimal(..) => TypeId::of::<rust_decimal::Decimal>(),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(..) => TypeId::of::<num_bigint::BigInt>(),
             #[cfg(not(feature = "no_index"))]
             Union::Array(..) => TypeId::of::<Array>(),
             #[cfg(not(feature = "no_index"))]
@@ -336,6 +383,10 @@ impl Dynamic {
             #[cfg(not(feature = "no_object"))]
             Union::Map(..) => TypeId::of::<Map>(),
             Union::FnPtr(..) => TypeId::of::<FnPtr>(),
+            Union::Tuple(..) => TypeId::of::<Tuple>(),
+            Union::Enum(..) => TypeId::of::<EnumValue>(),
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(..) => TypeId::of::<crate::StructValue>(),
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(..) => TypeId::of::<Instant>(),
 
@@ -363,6 +414,8 @@ impl Dynamic {
             Union::Float(..) => type_name::<crate::FLOAT>(),
             #[cfg(feature = "decimal")]
             Union::Decimal(..) => "decimal",
+            #[cfg(feature = "bigint")]
+            Union::BigInt(..) => "bigint",
             #[cfg(not(feature = "no_index"))]
             Union::Array(..) => "array",
             #[cfg(not(feature = "no_index"))]
@@ -370,6 +423,10 @@ impl Dynamic {
             #[cfg(not(feature = "no_object"))]
             Union::Map(..) => "map",
             Union::FnPtr(..) => "Fn",
+            Union::Tuple(..) => "tuple",
+            Union::Enum(..) => "enum",
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(..) => "struct",
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(..) => "timestamp",
 
@@ -408,6 +465,8 @@ impl Hash for Dynamic {
             Union::Float(ref f, ..) => f.hash(state),
             #[cfg(feature = "decimal")]
             Union::Decimal(ref d, ..) => d.hash(state),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref n, ..) => n.hash(state),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref a, ..) => a.hash(state),
             #[cfg(not(feature = "no_index"))]
@@ -422,6 +481,10 @@ impl Hash for Dynamic {
                 f.fn_name().hash(state);
                 f.curry().hash(state);
             }
+            Union::Tuple(ref t, ..) => t.hash(state),
+            Union::Enum(ref e, ..) => e.hash(state),
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref v, ..) => v.hash(state),
 
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(..) => unreachable!(),
@@ -496,6 +559,8 @@ impl fmt::Display for Dynamic {
             Union::Float(ref v, ..) => fmt::Display::fmt(v, f),
             #[cfg(feature = "decimal")]
             Union::Decimal(ref v, ..) => fmt::Display::fmt(v, f),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref v, ..) => fmt::Display::fmt(v, f),
             #[cfg(not(feature = "no_index"))]
             Union::Array(..) => fmt::Debug::fmt(self, f),
             #[cfg(not(feature = "no_index"))]
@@ -503,6 +568,9 @@ impl fmt::Display for Dynamic {
             #[cfg(not(feature = "no_object"))]
             Union::Map(..) => fmt::Debug::fmt(self, f),
             Union::FnPtr(ref v, ..) => fmt::Display::fmt(v, f),
+            Union::Tuple(..) | Union::Enum(..) => fmt::Debug::fmt(self, f),
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(..) => fmt::Debug::fmt(self, f),
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(..) => f.write_str("<timestamp>"),
 
@@ -628,6 +696,19 @@ impl fmt::Display for Dynamic {
                             }
                             f.write_str("}")
                         }
+                        Union::Tuple(ref t, ..) => {
+                            dict.insert(value);
+                            t.fmt_with(f, |v, f| display_fmt_print(f, v, dict))
+                        }
+                        Union::Enum(ref e, ..) => {
+                            dict.insert(value);
+                            e.fmt_with(f, |v, f| display_fmt_print(f, v, dict))
+                        }
+                        #[cfg(not(feature = "no_object"))]
+                        Union::Struct(ref v, ..) => {
+                            dict.insert(value);
+                            v.fmt_with(f, |v, f| display_fmt_print(f, v, dict))
+                        }
                         _ => fmt::Display::fmt(value, f),
                     }
                 }
@@ -652,6 +733,8 @@ impl fmt::Debug for Dynamic {
             Union::Float(ref v, ..) => fmt::Debug::fmt(v, f),
             #[cfg(feature = "decimal")]
             Union::Decimal(ref v, ..) => fmt::Debug::fmt(v, f),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref v, ..) => write!(f, "{v}n"),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref v, ..) => fmt::Debug::fmt(v, f),
             #[cfg(not(feature = "no_index"))]
@@ -671,6 +754,10 @@ impl fmt::Debug for Dynamic {
                 fmt::Debug::fmt(v, f)
             }
             Union::FnPtr(ref v, ..) => fmt::Debug::fmt(v, f),
+            Union::Tuple(ref v, ..) => fmt::Debug::fmt(v, f),
+            Union::Enum(ref v, ..) => fmt::Debug::fmt(v, f),
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref v, ..) => fmt::Debug::fmt(v, f),
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(..) => write!(f, "<timestamp>"),
 
@@ -807,6 +894,19 @@ impl fmt::Debug for Dynamic {
                             }
                             f.write_str(")")
                         }
+                        Union::Tuple(ref t, ..) => {
+                            dict.insert(value);
+                            t.fmt_with(f, |v, f| checked_debug_fmt(f, v, dict))
+                        }
+                        Union::Enum(ref e, ..) => {
+                            dict.insert(value);
+                            e.fmt_with(f, |v, f| checked_debug_fmt(f, v, dict))
+                        }
+                        #[cfg(not(feature = "no_object"))]
+                        Union::Struct(ref v, ..) => {
+                            dict.insert(value);
+                            v.fmt_with(f, |v, f| checked_debug_fmt(f, v, dict))
+                        }
                         _ => fmt::Debug::fmt(value, f),
                     }
                 }
@@ -837,6 +937,8 @@ impl Clone for Dynamic {
             Union::Float(v, tag, ..) => Self(Union::Float(v, tag, ReadWrite)),
             #[cfg(feature = "decimal")]
             Union::Decimal(ref v, tag, ..) => Self(Union::Decimal(v.clone(), tag, ReadWrite)),
+            #[cfg(feature = "bigint")]
+            Union::BigInt(ref v, tag, ..) => Self(Union::BigInt(v.clone(), tag, ReadWrite)),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref v, tag, ..) => Self(Union::Array(v.clone(), tag, ReadWrite)),
             #[cfg(not(feature = "no_index"))]
@@ -844,6 +946,10 @@ impl Clone for Dynamic {
             #[cfg(not(feature = "no_object"))]
             Union::Map(ref v, tag, ..) => Self(Union::Map(v.clone(), tag, ReadWrite)),
             Union::FnPtr(ref v, tag, ..) => Self(Union::FnPtr(v.clone(), tag, ReadWrite)),
+            Union::Tuple(ref v, tag, ..) => Self(Union::Tuple(v.clone(), tag, ReadWrite)),
+            Union::Enum(ref v, tag, ..) => Self(Union::Enum(v.clone(), tag, ReadWrite)),
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref v, tag, ..) => Self(Union::Struct(v.clone(), tag, ReadWrite)),
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(ref v, tag, ..) => Self(Union::TimeStamp(v.clone(), tag, ReadWrite)),
 
@@ -1056,6 +1162,14 @@ impl Dynamic {
     pub fn from_decimal(value: rust_decimal::Decimal) -> Self {
         Self(Union::Decimal(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
     }
+    /// Create a new [`Dynamic`] from a [`BigInt`](https://docs.rs/num-bigint).
+    ///
+    /// Exported under the `bigint` feature only.
+    #[cfg(feature = "bigint")]
+    #[inline(always)]
+    pub fn from_bigint(value: num_bigint::BigInt) -> Self {
+        Self(Union::BigInt(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
     /// Create a [`Dynamic`] from an [`Array`].
     #[cfg(not(feature = "no_index"))]
     #[inline(always)]
@@ -1074,6 +1188,28 @@ impl Dynamic {
     pub fn from_map(map: Map) -> Self {
         Self(Union::Map(map.into(), DEFAULT_TAG_VALUE, ReadWrite))
     }
+    /// Create a [`Dynamic`] from a [`Tuple`].
+    #[inline(always)]
+    pub fn from_tuple(tuple: impl Into<Tuple>) -> Self {
+        Self(Union::Tuple(
+            Box::new(tuple.into()),
+            DEFAULT_TAG_VALUE,
+            ReadWrite,
+        ))
+    }
+    /// Create a [`Dynamic`] from an [`EnumValue`].
+    #[inline(always)]
+    pub fn from_enum(value: EnumValue) -> Self {
+        Self(Union::Enum(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+    /// Create a [`Dynamic`] from a [`StructValue`][crate::StructValue].
+    ///
+    /// Not available under `no_object`.
+    #[cfg(not(feature = "no_object"))]
+    #[inline(always)]
+    pub fn from_struct(value: crate::StructValue) -> Self {
+        Self(Union::Struct(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
     /// Create a new [`Dynamic`] from an [`Instant`].
     ///
     /// Not available under `no-std` or `no_time`.
@@ -1093,16 +1229,20 @@ impl Dynamic {
             | Union::Char(.., access)
             | Union::Int(.., access)
             | Union::FnPtr(.., access)
+            | Union::Tuple(.., access)
+            | Union::Enum(.., access)
             | Union::Variant(.., access) => access,
 
             #[cfg(not(feature = "no_float"))]
             Union::Float(.., access) => access,
             #[cfg(feature = "decimal")]
             Union::Decimal(.., access) => access,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(.., access) => access,
             #[cfg(not(feature = "no_index"))]
             Union::Array(.., access) | Union::Blob(.., access) => access,
             #[cfg(not(feature = "no_object"))]
-            Union::Map(.., access) => access,
+            Union::Map(.., access) | Union::Struct(.., access) => access,
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(.., access) => access,
             #[cfg(not(feature = "no_closure"))]
@@ -1124,6 +1264,8 @@ impl Dynamic {
             Union::Float(.., ref mut access) => *access = typ,
             #[cfg(feature = "decimal")]
             Union::Decimal(.., ref mut access) => *access = typ,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(.., ref mut access) => *access = typ,
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref mut a, _, ref mut access) => {
                 *access = typ;
@@ -1140,6 +1282,25 @@ impl Dynamic {
                     v.set_access_mode(typ);
                 }
             }
+            Union::Tuple(ref mut t, _, ref mut access) => {
+                *access = typ;
+                for v in t.iter_mut() {
+                    v.set_access_mode(typ);
+                }
+            }
+            Union::Enum(ref mut e, _, ref mut access) => {
+                *access = typ;
+                for v in e.fields_mut().iter_mut() {
+                    v.set_access_mode(typ);
+                }
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref mut v, _, ref mut access) => {
+                *access = typ;
+                for v in v.values_mut() {
+                    v.set_access_mode(typ);
+                }
+            }
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(.., ref mut access) => *access = typ,
             #[cfg(not(feature = "no_closure"))]
@@ -1219,6 +1380,8 @@ impl Dynamic {
             Union::Float(..) => true,
             #[cfg(feature = "decimal")]
             Union::Decimal(..) => true,
+            #[cfg(feature = "bigint")]
+            Union::BigInt(..) => true,
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref a, ..) => a.iter().all(Self::is_hashable),
             #[cfg(not(feature = "no_index"))]
@@ -1228,6 +1391,10 @@ impl Dynamic {
             #[cfg(not(feature = "no_function"))]
             Union::FnPtr(ref f, ..) if f.env.is_some() => false,
             Union::FnPtr(ref f, ..) => f.curry().iter().all(Self::is_hashable),
+            Union::Tuple(ref t, ..) => t.iter().all(Self::is_hashable),
+            Union::Enum(ref e, ..) => e.fields().iter().all(Self::is_hashable),
+            #[cfg(not(feature = "no_object"))]
+            Union::Struct(ref v, ..) => v.iter().all(|(.., v)| v.is_hashable()),
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(..) => false,
 
@@ -1305,6 +1472,14 @@ impl Dynamic {
                             f.env.is_none()
                                 && f.curry().iter().all(|v| checked_is_hashable(v, dict))
                         }
+                        Union::Tuple(ref t, ..) => t.iter().all(|v| checked_is_hashable(v, dict)),
+                        Union::Enum(ref e, ..) => {
+                            e.fields().iter().all(|v| checked_is_hashable(v, dict))
+                        }
+                        #[cfg(not(feature = "no_object"))]
+                        Union::Struct(ref v, ..) => {
+                            v.iter().all(|(.., v)| checked_is_hashable(v, dict))
+                        }
                         _ => value.is_hashable(),
                     }
                 }
@@ -1390,6 +1565,9 @@ impl Dynamic {
         #[cfg(feature = "decimal")]
         reify! { value => |v: rust_decimal::Decimal| return v.into() }
 
+        #[cfg(feature = "bigint")]
+        reify! { value => |v: num_bigint::BigInt| return v.into() }
+
         reify! { value => |v: bool| return v.into() }
         reify! { value => |v: char| return v.into() }
         reify! { value => |v: ImmutableString| return v.into() }
@@ -1405,6 +1583,10 @@ impl Dynamic {
         #[cfg(not(feature = "no_object"))]
         reify! { value => |v: Map| return v.into() }
         reify! { value => |v: FnPtr| return v.into() }
+        reify! { value => |v: Tuple| return v.into() }
+        reify! { value => |v: EnumValue| return v.into() }
+        #[cfg(not(feature = "no_object"))]
+        reify! { value => |v: crate::StructValue| return v.into() }
 
         #[cfg(not(feature = "no_time"))]
         reify! { value => |v: Instant| return v.into() }
@@ -1541,6 +1723,13 @@ impl Dynamic {
                 _ => Err(self),
             };
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<num_bigint::BigInt>() {
+            return match self.0 {
+                Union::BigInt(v, ..) => Ok(reify! { *v => !!! T }),
+                _ => Err(self),
+            };
+        }
         if TypeId::of::<T>() == TypeId::of::<bool>() {
             return match self.0 {
                 Union::Bool(b, ..) => Ok(reify! { b => !!! T }),
@@ -1592,6 +1781,25 @@ impl Dynamic {
                 _ => Err(self),
             };
         }
+        if TypeId::of::<T>() == TypeId::of::<Tuple>() {
+            return match self.0 {
+                Union::Tuple(t, ..) => Ok(reify! { *t => !!! T }),
+                _ => Err(self),
+            };
+        }
+        if TypeId::of::<T>() == TypeId::of::<EnumValue>() {
+            return match self.0 {
+                Union::Enum(e, ..) => Ok(reify! { *e => !!! T }),
+                _ => Err(self),
+            };
+        }
+        #[cfg(not(feature = "no_object"))]
+        if TypeId::of::<T>() == TypeId::of::<crate::StructValue>() {
+            return match self.0 {
+                Union::Struct(v, ..) => Ok(reify! { *v => !!! T }),
+                _ => Err(self),
+            };
+        }
         #[cfg(not(feature = "no_time"))]
         if TypeId::of::<T>() == TypeId::of::<Instant>() {
             return match self.0 {
@@ -1888,6 +2096,13 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<num_bigint::BigInt>() {
+            return match self.0 {
+                Union::BigInt(ref v, ..) => v.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
         if TypeId::of::<T>() == TypeId::of::<bool>() {
             return match self.0 {
                 Union::Bool(ref v, ..) => v.as_any().downcast_ref::<T>(),
@@ -1933,6 +2148,25 @@ impl Dynamic {
                 _ => None,
             };
         }
+        if TypeId::of::<T>() == TypeId::of::<Tuple>() {
+            return match self.0 {
+                Union::Tuple(ref v, ..) => v.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
+        if TypeId::of::<T>() == TypeId::of::<EnumValue>() {
+            return match self.0 {
+                Union::Enum(ref v, ..) => v.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
+        #[cfg(not(feature = "no_object"))]
+        if TypeId::of::<T>() == TypeId::of::<crate::StructValue>() {
+            return match self.0 {
+                Union::Struct(ref v, ..) => v.as_ref().as_any().downcast_ref::<T>(),
+                _ => None,
+            };
+        }
         #[cfg(not(feature = "no_time"))]
         if TypeId::of::<T>() == TypeId::of::<Instant>() {
             return match self.0 {
@@ -1991,6 +2225,13 @@ impl Dynamic {
                 _ => None,
             };
         }
+        #[cfg(feature = "bigint")]
+        if TypeId::of::<T>() == TypeId::of::<num_bigint::BigInt>() {
+            return match self.0 {
+                Union::BigInt(ref mut v, ..) => v.as_mut().as_any_mut().downcast_mut::<T>(),
+                _ => None,
+            };
+        }
         if TypeId::of::<T>() == TypeId::of::<bool>() {
             return match self.0 {
                 Union::Bool(ref mut v, ..) => v.as_any_mut().downcast_mut::<T>(),
@@ -2036,6 +2277,25 @@ impl Dynamic {
                 _ => None,
             };
         }
+        if TypeId::of::<T>() == TypeId::of::<Tuple>() {
+            return match self.0 {
+                Union::Tuple(ref mut v, ..) => v.as_mut().as_any_mut().downcast_mut::<T>(),
+                _ => None,
+            };
+        }
+        if TypeId::of::<T>() == TypeId::of::<EnumValue>() {
+            return match self.0 {
+                Union::Enum(ref mut v, ..) => v.as_mut().as_any_mut().downcast_mut::<T>(),
+                _ => None,
+            };
+        }
+        #[cfg(not(feature = "no_object"))]
+        if TypeId::of::<T>() == TypeId::of::<crate::StructValue>() {
+            return match self.0 {
+                Union::Struct(ref mut v, ..) => v.as_mut().as_any_mut().downcast_mut::<T>(),
+                _ => None,
+            };
+        }
         #[cfg(not(feature = "no_time"))]
         if TypeId::of::<T>() == TypeId::of::<Instant>() {
             return match self.0 {
@@ -2154,6 +2414,30 @@ impl Dynamic {
             _ => false,
         }
     }
+    /// _(bigint)_ Return `true` if the [`Dynamic`] holds a [`BigInt`][num_bigint::BigInt].
+    /// Exported under the `bigint` feature only.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, `false` is returned.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[cfg(feature = "bigint")]
+    #[inline]
+    #[must_use]
+    pub fn is_bigint(&self) -> bool {
+        match self.0 {
+            Union::BigInt(..) => true,
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => {
+                crate::func::locked_read(cell).map_or(false, |v| matches!(v.0, Union::BigInt(..)))
+            }
+            _ => false,
+        }
+    }
     /// Return `true` if the [`Dynamic`] holds a [`bool`].
     ///
     /// # Shared Value
@@ -2319,6 +2603,75 @@ impl Dynamic {
             _ => false,
         }
     }
+    /// Return `true` if the [`Dynamic`] holds a [`Tuple`].
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, `false` is returned.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline]
+    #[must_use]
+    pub fn is_tuple(&self) -> bool {
+        match self.0 {
+            Union::Tuple(..) => true,
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => {
+                crate::func::locked_read(cell).map_or(false, |v| matches!(v.0, Union::Tuple(..)))
+            }
+            _ => false,
+        }
+    }
+    /// Return `true` if the [`Dynamic`] holds an [`EnumValue`].
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, `false` is returned.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline]
+    #[must_use]
+    pub fn is_enum(&self) -> bool {
+        match self.0 {
+            Union::Enum(..) => true,
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => {
+                crate::func::locked_read(cell).map_or(false, |v| matches!(v.0, Union::Enum(..)))
+            }
+            _ => false,
+        }
+    }
+    /// Return `true` if the [`Dynamic`] holds a [`StructValue`][crate::StructValue].
+    ///
+    /// Not available under `no_object`.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, `false` is returned.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[cfg(not(feature = "no_object"))]
+    #[inline]
+    #[must_use]
+    pub fn is_struct(&self) -> bool {
+        match self.0 {
+            Union::Struct(..) => true,
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => {
+                crate::func::locked_read(cell).map_or(false, |v| matches!(v.0, Union::Struct(..)))
+            }
+            _ => false,
+        }
+    }
     /// Return `true` if the [`Dynamic`] holds a [timestamp][Instant].
     ///
     /// Not available under `no_time`.
@@ -2461,6 +2814,36 @@ impl Dynamic {
             _ => Err(self.type_name()),
         }
     }
+    /// _(bigint)_ Cast the [`Dynamic`] as a [`BigInt`][num_bigint::BigInt], cloning it.
+    /// Exported under the `bigint` feature only.
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[cfg(feature = "bigint")]
+    #[inline]
+    pub fn as_bigint(&self) -> Result<num_bigint::BigInt, &'static str> {
+        match self.0 {
+            Union::BigInt(ref n, ..) => Ok((**n).clone()),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => crate::func::locked_read(cell)
+                .and_then(|guard| match guard.0 {
+                    Union::BigInt(ref n, ..) => Some((**n).clone()),
+                    _ => None,
+                })
+                .ok_or_else(|| cell.type_name()),
+            _ => Err(self.type_name()),
+        }
+    }
     /// Cast the [`Dynamic`] as a [`bool`].
     ///
     /// # Errors
@@ -2688,6 +3071,129 @@ impl Dynamic {
         let type_name = self.type_name();
         self.write_lock::<Map>().ok_or(type_name)
     }
+    /// Cast the [`Dynamic`] as a reference to a [`Tuple`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline(always)]
+    pub fn as_tuple_ref(&self) -> Result<impl Deref<Target = Tuple> + '_, &'static str> {
+        self.read_lock::<Tuple>().ok_or_else(|| self.type_name())
+    }
+    /// Cast the [`Dynamic`] as a mutable reference to a [`Tuple`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline(always)]
+    pub fn as_tuple_mut(&mut self) -> Result<impl DerefMut<Target = Tuple> + '_, &'static str> {
+        let type_name = self.type_name();
+        self.write_lock::<Tuple>().ok_or(type_name)
+    }
+    /// Cast the [`Dynamic`] as a reference to an [`EnumValue`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline(always)]
+    pub fn as_enum_ref(&self) -> Result<impl Deref<Target = EnumValue> + '_, &'static str> {
+        self.read_lock::<EnumValue>()
+            .ok_or_else(|| self.type_name())
+    }
+    /// Cast the [`Dynamic`] as a mutable reference to an [`EnumValue`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline(always)]
+    pub fn as_enum_mut(&mut self) -> Result<impl DerefMut<Target = EnumValue> + '_, &'static str> {
+        let type_name = self.type_name();
+        self.write_lock::<EnumValue>().ok_or(type_name)
+    }
+    /// Cast the [`Dynamic`] as a reference to a [`StructValue`][crate::StructValue].
+    ///
+    /// Not available under `no_object`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[cfg(not(feature = "no_object"))]
+    #[inline(always)]
+    pub fn as_struct_ref(
+        &self,
+    ) -> Result<impl Deref<Target = crate::StructValue> + '_, &'static str> {
+        self.read_lock::<crate::StructValue>()
+            .ok_or_else(|| self.type_name())
+    }
+    /// Cast the [`Dynamic`] as a mutable reference to a [`StructValue`][crate::StructValue].
+    ///
+    /// Not available under `no_object`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[cfg(not(feature = "no_object"))]
+    #[inline(always)]
+    pub fn as_struct_mut(
+        &mut self,
+    ) -> Result<impl DerefMut<Target = crate::StructValue> + '_, &'static str> {
+        let type_name = self.type_name();
+        self.write_lock::<crate::StructValue>().ok_or(type_name)
+    }
     /// Convert the [`Dynamic`] into a [`String`].
     ///
     /// If there are other references to the same string, a cloned copy is returned.
@@ -2768,6 +3274,62 @@ impl Dynamic {
             _ => Err(self.type_name()),
         }
     }
+    /// Convert the [`Dynamic`] into a [`Tuple`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline(always)]
+    pub fn into_tuple(self) -> Result<Tuple, &'static str> {
+        match self.0 {
+            Union::Tuple(t, ..) => Ok(*t),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => crate::func::locked_read(cell)
+                .and_then(|guard| match guard.0 {
+                    Union::Tuple(ref t, ..) => Some(t.as_ref().clone()),
+                    _ => None,
+                })
+                .ok_or_else(|| cell.type_name()),
+            _ => Err(self.type_name()),
+        }
+    }
+    /// Convert the [`Dynamic`] into an [`EnumValue`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the actual type as an error if the cast fails.
+    ///
+    /// # Shared Value
+    ///
+    /// Under the `sync` feature, a _shared_ value may deadlock.
+    /// Otherwise, the data may currently be borrowed for write (so its type cannot be determined).
+    ///
+    /// Under these circumstances, the cast also fails.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai are single-threaded.
+    #[inline(always)]
+    pub fn into_enum(self) -> Result<EnumValue, &'static str> {
+        match self.0 {
+            Union::Enum(e, ..) => Ok(*e),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => crate::func::locked_read(cell)
+                .and_then(|guard| match guard.0 {
+                    Union::Enum(ref e, ..) => Some(e.as_ref().clone()),
+                    _ => None,
+                })
+                .ok_or_else(|| cell.type_name()),
+            _ => Err(self.type_name()),
+        }
+    }
     /// Convert the [`Dynamic`] into a [`Vec`].
     ///
     /// Not available under `no_index`.
@@ -2873,6 +3435,13 @@ impl Dynamic {
                 #[cfg(not(feature = "no_object"))]
                 Union::Map(m, ..) => m.values_mut().for_each(|v| scan_inner(v, filter)),
                 Union::FnPtr(f, ..) => f.iter_curry_mut().for_each(|v| scan_inner(v, filter)),
+                Union::Tuple(t, ..) => t.iter_mut().for_each(|v| scan_inner(v, filter)),
+                Union::Enum(e, ..) => e
+                    .fields_mut()
+                    .iter_mut()
+                    .for_each(|v| scan_inner(v, filter)),
+                #[cfg(not(feature = "no_object"))]
+                Union::Struct(v, ..) => v.values_mut().for_each(|v| scan_inner(v, filter)),
                 _ => (),
             }
         }
@@ -2920,6 +3489,13 @@ impl From<rust_decimal::Decimal> for Dynamic {
         Self(Union::Decimal(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
     }
 }
+#[cfg(feature = "bigint")]
+impl From<num_bigint::BigInt> for Dynamic {
+    #[inline(always)]
+    fn from(value: num_bigint::BigInt) -> Self {
+        Self(Union::BigInt(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+}
 impl From<char> for Dynamic {
     #[inline(always)]
     fn from(value: char) -> Self {
@@ -3038,6 +3614,25 @@ impl From<FnPtr> for Dynamic {
         Self(Union::FnPtr(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
     }
 }
+impl From<Tuple> for Dynamic {
+    #[inline(always)]
+    fn from(value: Tuple) -> Self {
+        Self(Union::Tuple(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+}
+impl From<EnumValue> for Dynamic {
+    #[inline(always)]
+    fn from(value: EnumValue) -> Self {
+        Self(Union::Enum(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+}
+#[cfg(not(feature = "no_object"))]
+impl From<crate::StructValue> for Dynamic {
+    #[inline(always)]
+    fn from(value: crate::StructValue) -> Self {
+        Self(Union::Struct(value.into(), DEFAULT_TAG_VALUE, ReadWrite))
+    }
+}
 #[cfg(not(feature = "no_time"))]
 impl From<Instant> for Dynamic {
     #[inline(always)]
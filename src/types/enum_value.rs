@@ -0,0 +1,113 @@
+//! The `EnumValue` type.
+
+use super::Tuple;
+use crate::{Dynamic, ImmutableString};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{fmt, hash::Hash};
+
+/// A value of a script-defined `enum` type (i.e. a tagged union).
+///
+/// Enum types are declared in scripts via `enum Shape { Circle(r), Rect(w, h), Empty }`.
+/// Each value carries the name of its enum type, the name of the variant and the variant's fields.
+#[derive(Clone, Hash)]
+pub struct EnumValue {
+    /// Name of the `enum` type.
+    name: ImmutableString,
+    /// Name of the variant.
+    variant: ImmutableString,
+    /// Fields of the variant.
+    fields: Tuple,
+}
+
+impl EnumValue {
+    /// Create a new [`EnumValue`].
+    #[inline(always)]
+    #[must_use]
+    pub fn new(
+        name: impl Into<ImmutableString>,
+        variant: impl Into<ImmutableString>,
+        fields: impl Into<Tuple>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            variant: variant.into(),
+            fields: fields.into(),
+        }
+    }
+    /// Get the name of the `enum` type.
+    #[inline(always)]
+    #[must_use]
+    pub fn enum_name(&self) -> &str {
+        &self.name
+    }
+    /// Get the name of the variant.
+    #[inline(always)]
+    #[must_use]
+    pub fn variant(&self) -> &str {
+        &self.variant
+    }
+    /// Get the fields of the variant.
+    #[inline(always)]
+    #[must_use]
+    pub const fn fields(&self) -> &Tuple {
+        &self.fields
+    }
+    /// Get a mutable reference to the fields of the variant.
+    #[inline(always)]
+    #[must_use]
+    pub fn fields_mut(&mut self) -> &mut Tuple {
+        &mut self.fields
+    }
+    /// Consume this [`EnumValue`], returning the fields of the variant.
+    #[inline(always)]
+    #[must_use]
+    pub fn into_fields(self) -> Tuple {
+        self.fields
+    }
+    /// Is this value of the specified `enum` type and variant?
+    #[inline(always)]
+    #[must_use]
+    pub fn is(&self, name: &str, variant: &str) -> bool {
+        self.name == name && self.variant == variant
+    }
+
+    /// Write this [`EnumValue`] using a formatting closure for the fields.
+    pub(crate) fn fmt_with(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        mut fmt_item: impl FnMut(&Dynamic, &mut fmt::Formatter<'_>) -> fmt::Result,
+    ) -> fmt::Result {
+        write!(f, "{}::{}", self.name, self.variant)?;
+
+        if self.fields.is_empty() {
+            return Ok(());
+        }
+
+        // Enum fields never carry a trailing comma, even with only one field.
+        f.write_str("(")?;
+        for (i, item) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            fmt_item(item, f)?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for EnumValue {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for EnumValue {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, fmt::Debug::fmt)
+    }
+}
@@ -119,7 +119,14 @@ pub enum EvalAltResult {
     /// Breaking out of loops - not an error if within a loop.
     /// The wrapped value, if true, means breaking clean out of the loop (i.e. a `break` statement).
     /// The wrapped value, if false, means breaking the current context (i.e. a `continue` statement).
+    /// The second wrapped value is the value of the `break` statement.
     LoopBreak(bool, Dynamic, Position),
+    /// Breaking out of an outer loop via a labeled `break` or `continue` - not an error if within
+    /// enough loops.
+    /// The first two wrapped values are the same as for [`LoopBreak`][EvalAltResult::LoopBreak].
+    /// The third wrapped value is the number of enclosing loops to leave before the one targeted
+    /// (always non-zero).
+    LoopBreakOuter(bool, Dynamic, u8, Position),
     /// Not an error: Value returned from a script via the `return` keyword.
     /// Wrapped value is the result value.
     Return(Dynamic, Position),
@@ -230,6 +237,12 @@ impl EvalAltResult {
 
             Self::LoopBreak(true, ..) => f.write_str("'break' must be within a loop")?,
             Self::LoopBreak(false, ..) => f.write_str("'continue' must be within a loop")?,
+            Self::LoopBreakOuter(true, ..) => {
+                f.write_str("'break' must be within a labeled loop")?
+            }
+            Self::LoopBreakOuter(false, ..) => {
+                f.write_str("'continue' must be within a labeled loop")?
+            }
 
             Self::Return(..) => f.write_str("NOT AN ERROR - function returns value")?,
             Self::Exit(..) => f.write_str("NOT AN ERROR - exit value")?,
@@ -301,16 +314,32 @@ impl<T: AsRef<str>> From<T> for Box<EvalAltResult> {
 }
 
 impl EvalAltResult {
+    /// Create a pseudo error breaking out of the loop that is `levels` loops outside the
+    /// innermost one.
+    #[inline]
+    pub(crate) const fn loop_break(
+        is_break: bool,
+        value: Dynamic,
+        levels: u8,
+        pos: Position,
+    ) -> Self {
+        if levels == 0 {
+            Self::LoopBreak(is_break, value, pos)
+        } else {
+            Self::LoopBreakOuter(is_break, value, levels, pos)
+        }
+    }
     /// Is this a pseudo error?  A pseudo error is one that does not occur naturally.
     ///
-    /// [`LoopBreak`][EvalAltResult::LoopBreak], [`Return`][EvalAltResult::Return] and [`Exit`][EvalAltResult::Exit] are pseudo errors.
+    /// [`LoopBreak`][EvalAltResult::LoopBreak], [`LoopBreakOuter`][EvalAltResult::LoopBreakOuter],
+    /// [`Return`][EvalAltResult::Return] and [`Exit`][EvalAltResult::Exit] are pseudo errors.
     #[cold]
     #[inline(never)]
     #[must_use]
     pub const fn is_pseudo_error(&self) -> bool {
         matches!(
             self,
-            Self::LoopBreak(..) | Self::Return(..) | Self::Exit(..)
+            Self::LoopBreak(..) | Self::LoopBreakOuter(..) | Self::Return(..) | Self::Exit(..)
         )
     }
     /// Can this error be caught?
@@ -359,7 +388,9 @@ impl EvalAltResult {
             | Self::ErrorDataTooLarge(..)
             | Self::ErrorTerminated(..) => false,
 
-            Self::LoopBreak(..) | Self::Return(..) | Self::Exit(..) => false,
+            Self::LoopBreak(..) | Self::LoopBreakOuter(..) | Self::Return(..) | Self::Exit(..) => {
+                false
+            }
         }
     }
     /// Is this error a system exception?
@@ -395,7 +426,7 @@ impl EvalAltResult {
         );
 
         match self {
-            Self::LoopBreak(..) | Self::Return(..) | Self::Exit(..) => (),
+            Self::LoopBreak(..) | Self::LoopBreakOuter(..) | Self::Return(..) | Self::Exit(..) => {}
 
             Self::ErrorSystem(..)
             | Self::ErrorParsing(..)
@@ -516,6 +547,7 @@ impl EvalAltResult {
             | Self::ErrorCustomSyntax(.., pos)
             | Self::ErrorRuntime(.., pos)
             | Self::LoopBreak(.., pos)
+            | Self::LoopBreakOuter(.., pos)
             | Self::Return(.., pos)
             | Self::Exit(.., pos) => *pos,
         }
@@ -578,6 +610,7 @@ impl EvalAltResult {
             | Self::ErrorCustomSyntax(.., pos)
             | Self::ErrorRuntime(.., pos)
             | Self::LoopBreak(.., pos)
+            | Self::LoopBreakOuter(.., pos)
             | Self::Return(.., pos)
             | Self::Exit(.., pos) => *pos = new_position,
         }
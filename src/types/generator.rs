@@ -0,0 +1,148 @@
+//! The `Generator` type.
+#![cfg(not(feature = "no_function"))]
+
+use crate::ast::ScriptFuncDef;
+use crate::func::locked_read;
+use crate::{Dynamic, ImmutableString, Locked, RhaiResultOf, Scope, Shared, SharedModule, INT};
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Iterator held by a suspended `for` loop inside a generator.
+///
+/// Under the `sync` feature, iterators returned by type iterator functions are not [`Send`], so
+/// the iterable is collected when the loop starts.
+#[cfg(not(feature = "sync"))]
+pub(crate) type GeneratorForIter = Box<dyn Iterator<Item = RhaiResultOf<Dynamic>>>;
+/// Iterator held by a suspended `for` loop inside a generator.
+///
+/// Under the `sync` feature, iterators returned by type iterator functions are not [`Send`], so
+/// the iterable is collected when the loop starts.
+#[cfg(feature = "sync")]
+pub(crate) type GeneratorForIter = std::vec::IntoIter<RhaiResultOf<Dynamic>>;
+
+/// Source of items for a suspended `for` loop inside a generator.
+pub(crate) enum GeneratorForSource {
+    /// Iterator obtained from a type iterator function.
+    Iter(GeneratorForIter),
+    /// Another [`Generator`], resumed for each item.
+    Generator(Generator),
+}
+
+/// A saved frame of a suspended generator, one for each statement that encloses the current
+/// `yield` statement.
+pub(crate) enum GeneratorFrame {
+    /// Statements block: index of the next statement to run, and length of the [`Scope`] when the
+    /// block started.
+    Block(usize, usize),
+    /// `if` statement: the branch taken.
+    If(bool),
+    /// `while`, `loop` or `do` statement.
+    Loop,
+    /// `for` statement: source of items, loop counter, and length of the [`Scope`] before the loop
+    /// variables.
+    For(GeneratorForSource, INT, usize),
+}
+
+/// Run state of a [`Generator`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) enum GeneratorStatus {
+    /// Not started, or suspended at a `yield` statement.
+    Suspended,
+    /// Currently running.
+    Running,
+    /// Completed, either by running to the end of the function, by `return`, or by an error.
+    Done,
+}
+
+/// State of a [`Generator`].
+pub(crate) struct GeneratorState {
+    /// The generator function.
+    pub fn_def: Shared<ScriptFuncDef>,
+    /// Stack of loaded [modules][crate::Module] containing script-defined functions, when the
+    /// generator was created.
+    pub lib: crate::StaticVec<SharedModule>,
+    /// Imported [modules][crate::Module], including those imported by the generator itself.
+    #[cfg(not(feature = "no_module"))]
+    pub imports: crate::ThinVec<(ImmutableString, SharedModule)>,
+    /// Globally-defined constants.
+    #[cfg(not(feature = "no_module"))]
+    pub constants: Option<crate::eval::SharedGlobalConstants>,
+    /// Source of the generator function, if any.
+    pub source: Option<ImmutableString>,
+    /// Local variables of the generator function, starting with its parameters.
+    pub scope: Scope<'static>,
+    /// Copy of the `this` pointer, if the generator function was called in method-call style.
+    pub this_ptr: Option<Dynamic>,
+    /// Frames of the suspended function body, outermost first.
+    pub frames: Vec<GeneratorFrame>,
+    /// `static` variables held in the scope as copies, under `no_closure`.
+    #[cfg(feature = "no_closure")]
+    pub static_slots: Vec<crate::eval::StaticSlot>,
+    /// Run state.
+    pub status: GeneratorStatus,
+}
+
+/// A lazy iterator created by calling a script-defined function that contains `yield` statements.
+///
+/// Each time the next item is requested, the function body runs until the next `yield`
+/// statement, then it is suspended with all its local variables intact.
+///
+/// A [`Generator`] is a handle; clones share the same running state.
+///
+/// Not available under `no_function`.
+///
+/// # Example
+///
+/// ```rhai
+/// fn evens(limit) {
+///     for x in 0..limit {
+///         if x % 2 == 0 { yield x; }
+///     }
+/// }
+///
+/// for x in evens(10) {
+///     print(x);           // prints 0, 2, 4, 6, 8
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Generator(pub(crate) Shared<Locked<GeneratorState>>);
+
+impl Generator {
+    /// Create a new [`Generator`] from its initial state.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn new(state: GeneratorState) -> Self {
+        Self(Shared::new(Locked::new(state)))
+    }
+    /// Get the name of the generator function.
+    #[inline]
+    #[must_use]
+    pub fn fn_name(&self) -> ImmutableString {
+        locked_read(&self.0).map_or_else(ImmutableString::new, |state| state.fn_def.name.clone())
+    }
+    /// Has this [`Generator`] completed?
+    ///
+    /// A completed generator produces no more items.
+    #[inline]
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        locked_read(&self.0).map_or(false, |state| state.status == GeneratorStatus::Done)
+    }
+}
+
+impl fmt::Debug for Generator {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Generator({})", self.fn_name())
+    }
+}
+
+impl fmt::Display for Generator {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
@@ -2,28 +2,40 @@
 
 pub mod bloom_filter;
 pub mod custom_types;
+pub mod diagnostic;
 pub mod dynamic;
+pub mod enum_value;
 pub mod error;
 pub mod float;
 pub mod fn_ptr;
+pub mod generator;
 pub mod immutable_string;
 pub mod interner;
 pub mod parse_error;
 pub mod position;
 pub mod position_none;
+pub mod regex;
 pub mod scope;
+pub mod struct_value;
+pub mod tuple;
 pub mod var_def;
 pub mod variant;
 
+#[cfg(feature = "regex")]
+pub use self::regex::Regex;
 pub use bloom_filter::BloomFilterU64;
 pub use custom_types::{CustomTypeInfo, CustomTypesCollection};
+pub use diagnostic::{Diagnostic, DiagnosticType, Severity};
 pub use dynamic::Dynamic;
 #[cfg(not(feature = "no_time"))]
 pub use dynamic::Instant;
+pub use enum_value::EnumValue;
 pub use error::EvalAltResult;
 #[cfg(not(feature = "no_float"))]
 pub use float::FloatWrapper;
 pub use fn_ptr::FnPtr;
+#[cfg(not(feature = "no_function"))]
+pub use generator::Generator;
 pub use immutable_string::ImmutableString;
 pub use interner::StringsInterner;
 pub use parse_error::{LexError, ParseError, ParseErrorType};
@@ -35,4 +47,7 @@ pub use position::{Position, Span};
 pub use position_none::{Position, Span};
 
 pub use scope::Scope;
+#[cfg(not(feature = "no_object"))]
+pub use struct_value::StructValue;
+pub use tuple::Tuple;
 pub use variant::Variant;
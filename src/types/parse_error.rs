@@ -158,8 +158,42 @@ pub enum ParseErrorType {
     FnDuplicatedParam(String, String),
     /// A function definition is missing the body. Wrapped value is the function name.
     FnMissingBody(String),
+    /// An operator function definition has the wrong number of parameters.
+    /// Wrapped value is the operator.
+    FnWrongOperatorParams(String),
     /// Export statement not at global level.
     WrongExport,
+    /// Defining a `module` in an inappropriate place (e.g. inside a block or function).
+    WrongModuleDefinition,
+    /// Defining a `module` with a name that conflicts with another `module` defined in the same
+    /// scope. Wrapped value is the `module` name.
+    ModuleDuplicatedDefinition(String),
+    /// Defining an `enum` in an appropriate place (e.g. inside a block or function).
+    WrongEnumDefinition,
+    /// Defining an `enum` with a name that conflicts with an existing `enum`.
+    /// Wrapped value is the `enum` name.
+    EnumDuplicatedDefinition(String),
+    /// An `enum` definition has duplicated variants. Wrapped values are the `enum` name and
+    /// variant name.
+    EnumDuplicatedVariant(String, String),
+    /// A variant is not defined in an `enum`. Wrapped values are the `enum` name and variant name.
+    EnumVariantNotFound(String, String),
+    /// An `enum` variant is given the wrong number of fields.
+    /// Wrapped values are the qualified variant name and the number of fields it requires.
+    EnumVariantFields(String, usize),
+    /// Defining a `struct` in an appropriate place (e.g. inside a block or function).
+    WrongStructDefinition,
+    /// Defining a `struct` with a name that conflicts with an existing `struct` or `enum`.
+    /// Wrapped value is the `struct` name.
+    StructDuplicatedDefinition(String),
+    /// A `struct` definition, or a `struct` construction, has duplicated fields.
+    /// Wrapped values are the `struct` name and field name.
+    StructDuplicatedField(String, String),
+    /// A field is not defined in a `struct`. Wrapped values are the `struct` name and field name.
+    StructFieldNotFound(String, String),
+    /// A `struct` construction does not provide a value for a field.
+    /// Wrapped values are the `struct` name and field name.
+    StructMissingField(String, String),
     /// Assignment to an a constant variable. Wrapped value is the constant variable name.
     AssignmentToConstant(String),
     /// Assignment to an inappropriate LHS (left-hand-side) expression.
@@ -185,6 +219,24 @@ pub enum ParseErrorType {
     LiteralTooLarge(String, usize),
     /// Break statement not inside a loop.
     LoopBreak,
+    /// A `break` or `continue` names a label that is not on any enclosing loop.
+    /// Wrapped value is the label.
+    LabelUndefined(String),
+    /// A loop label is already used by an enclosing loop. Wrapped value is the label.
+    LabelDuplicated(String),
+    /// Yield statement not inside a function body, or nested within a construct (such as an
+    /// expression, a `switch` or a `try` block) that cannot be suspended.
+    WrongYield,
+    /// Defer statement not at the top level of a function body.
+    ///
+    /// A `defer` statement nested inside a block (e.g. an `if` statement or a loop) in a function
+    /// body is also an error.
+    WrongDefer,
+    /// Static variable definition not inside a function body.
+    WrongStatic,
+    /// The unquoted key of an object map comprehension is not one of its loop variables.
+    /// Wrapped value is the key.
+    WrongComprehensionKey(String),
 }
 
 impl fmt::Display for ParseErrorType {
@@ -213,6 +265,24 @@ impl fmt::Display for ParseErrorType {
             Self::FnMissingBody(s) =>  write!(f, "Expecting body statement block for function {s}"),
 
             Self::FnMissingParams(s) => write!(f, "Expecting parameters for function {s}"),
+            Self::FnWrongOperatorParams(s) => write!(f, "Wrong number of parameters for operator function {s}"),
+
+            Self::ModuleDuplicatedDefinition(s) => write!(f, "Module {s} already exists"),
+            Self::EnumDuplicatedDefinition(s) => write!(f, "Enum {s} already exists"),
+            Self::EnumDuplicatedVariant(s, v) => write!(f, "Duplicated variant {v} for enum {s}"),
+            Self::EnumVariantNotFound(s, v) => write!(f, "Enum {s} has no variant {v}"),
+            Self::EnumVariantFields(s, n) => {
+                write!(f, "Enum variant {s} ")?;
+                match n {
+                    0 => f.write_str("has no fields"),
+                    1 => f.write_str("requires 1 field"),
+                    _ => write!(f, "requires {n} fields"),
+                }
+            }
+            Self::StructDuplicatedDefinition(s) => write!(f, "Type {s} already exists"),
+            Self::StructDuplicatedField(s, n) => write!(f, "Duplicated field {n} for struct {s}"),
+            Self::StructFieldNotFound(s, n) => write!(f, "Struct {s} has no field {n}"),
+            Self::StructMissingField(s, n) => write!(f, "Missing field {n} for struct {s}"),
             Self::FnDuplicatedParam(s, arg) => write!(f, "Duplicated parameter {arg} for function {s}"),
 
             Self::DuplicatedProperty(s) => write!(f, "Duplicated property for object map literal: {s}"),
@@ -249,9 +319,18 @@ impl fmt::Display for ParseErrorType {
             Self::FnMissingName => f.write_str("Expecting function name in function declaration"),
             Self::WrongDocComment => f.write_str("Doc-comment must be followed immediately by a function definition"),
             Self::WrongExport => f.write_str("Export statement can only appear at global level"),
+            Self::WrongModuleDefinition => f.write_str("Module definitions must be at global level and cannot be inside a block or a function"),
+            Self::WrongEnumDefinition => f.write_str("Enum definitions must be at global level and cannot be inside a block or a function"),
+            Self::WrongStructDefinition => f.write_str("Struct definitions must be at global level and cannot be inside a block or a function"),
             Self::ExprTooDeep => f.write_str("Expression exceeds maximum complexity"),
             Self::TooManyFunctions => f.write_str("Number of functions defined exceeds maximum limit"),
             Self::LoopBreak => f.write_str("Break statement should only be used inside a loop"),
+            Self::LabelUndefined(s) => write!(f, "Undefined loop label: '{s}"),
+            Self::LabelDuplicated(s) => write!(f, "Duplicated loop label: '{s}"),
+            Self::WrongYield => f.write_str("Yield statement can only be used inside a function body, and not within an expression, switch or try block"),
+            Self::WrongDefer => f.write_str("Defer statement can only be used at the top level of a function body, not within a nested block"),
+            Self::WrongStatic => f.write_str("Static variables can only be defined inside a function body"),
+            Self::WrongComprehensionKey(s) => write!(f, "Unquoted key of object map comprehension must be a loop variable (use [{s}] for a computed key): {s}"),
 
             #[allow(deprecated)]
             Self::DuplicatedSwitchCase => f.write_str("Duplicated switch case"),
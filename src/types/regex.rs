@@ -0,0 +1,77 @@
+//! The `Regex` type.
+#![cfg(feature = "regex")]
+
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// A compiled regular expression.
+///
+/// Not available under `no_std`.
+///
+/// Created in scripts by calling the `regex` function with a pattern.
+///
+/// # Example
+///
+/// ```rhai
+/// let re = regex("(?<key>\\w+)=(?<value>\\d+)");
+///
+/// print(re.matches("x=42"));          // prints true
+/// print(re.find_all("x=1, y=2"));     // prints ["x=1", "y=2"]
+/// ```
+///
+/// Matching is guaranteed to run in time linear to the length of the input, so a script cannot
+/// stall the [`Engine`][crate::Engine] with a pathological pattern.
+#[derive(Clone)]
+pub struct Regex(regex::Regex);
+
+impl Regex {
+    /// Compile a new [`Regex`] from a pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid regular expression.
+    #[inline(always)]
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(Self)
+    }
+    /// Get the pattern of this [`Regex`].
+    #[inline(always)]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl From<regex::Regex> for Regex {
+    #[inline(always)]
+    fn from(value: regex::Regex) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Regex> for regex::Regex {
+    #[inline(always)]
+    fn from(value: Regex) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<regex::Regex> for Regex {
+    #[inline(always)]
+    fn as_ref(&self) -> &regex::Regex {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "regex({:?})", self.0.as_str())
+    }
+}
+
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
@@ -0,0 +1,146 @@
+//! The `StructValue` type.
+#![cfg(not(feature = "no_object"))]
+
+use crate::{Dynamic, ImmutableString, Map};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{fmt, hash::Hash};
+
+/// A value of a script-defined `struct` type (i.e. a record with a fixed set of fields).
+///
+/// Struct types are declared in scripts via `struct Point { x, y }`.
+/// Each value carries the name of its `struct` type and its fields, in the order they are declared.
+///
+/// Not available under `no_object`.
+#[derive(Clone, Hash)]
+pub struct StructValue {
+    /// Name of the `struct` type.
+    name: ImmutableString,
+    /// Fields, in order of declaration.
+    fields: Box<[(ImmutableString, Dynamic)]>,
+}
+
+impl StructValue {
+    /// Create a new [`StructValue`] from its fields, in order of declaration.
+    #[inline]
+    #[must_use]
+    pub fn new<N: Into<ImmutableString>>(
+        name: impl Into<ImmutableString>,
+        fields: impl IntoIterator<Item = (N, Dynamic)>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            fields: fields.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        }
+    }
+    /// Get the name of the `struct` type.
+    #[inline(always)]
+    #[must_use]
+    pub fn struct_name(&self) -> &str {
+        &self.name
+    }
+    /// Get the number of fields.
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+    /// Is this value without any field?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+    /// Does the `struct` type have a field of this name?
+    #[inline]
+    #[must_use]
+    pub fn contains_field(&self, field: &str) -> bool {
+        self.fields.iter().any(|(k, ..)| k.as_str() == field)
+    }
+    /// Get a reference to the value of a field.
+    ///
+    /// Returns [`None`] if the `struct` type has no such field.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, field: &str) -> Option<&Dynamic> {
+        self.fields
+            .iter()
+            .find(|(k, ..)| k.as_str() == field)
+            .map(|(.., v)| v)
+    }
+    /// Get a mutable reference to the value of a field.
+    ///
+    /// Returns [`None`] if the `struct` type has no such field.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, field: &str) -> Option<&mut Dynamic> {
+        self.fields
+            .iter_mut()
+            .find(|(k, ..)| k.as_str() == field)
+            .map(|(.., v)| v)
+    }
+    /// Iterate the fields, in order of declaration.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Dynamic)> {
+        self.fields.iter().map(|(k, v)| (k.as_str(), v))
+    }
+    /// Iterate the values of the fields mutably, in order of declaration.
+    #[inline]
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Dynamic> {
+        self.fields.iter_mut().map(|(.., v)| v)
+    }
+    /// Is this value of the specified `struct` type?
+    #[inline(always)]
+    #[must_use]
+    pub fn is(&self, name: &str) -> bool {
+        self.name == name
+    }
+    /// Convert the fields into an object [map][Map].
+    #[inline]
+    #[must_use]
+    pub fn to_map(&self) -> Map {
+        self.fields
+            .iter()
+            .map(|(k, v)| (k.into(), v.clone()))
+            .collect()
+    }
+
+    /// Write this [`StructValue`] using a formatting closure for the fields.
+    pub(crate) fn fmt_with(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        mut fmt_item: impl FnMut(&Dynamic, &mut fmt::Formatter<'_>) -> fmt::Result,
+    ) -> fmt::Result {
+        write!(f, "{} {{", self.name)?;
+
+        for (i, (name, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, " {name}: ")?;
+            fmt_item(value, f)?;
+        }
+
+        if self.fields.is_empty() {
+            f.write_str("}")
+        } else {
+            f.write_str(" }")
+        }
+    }
+}
+
+impl fmt::Display for StructValue {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for StructValue {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, fmt::Debug::fmt)
+    }
+}
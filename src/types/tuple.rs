@@ -0,0 +1,134 @@
+//! The `Tuple` type.
+
+use crate::Dynamic;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    fmt,
+    hash::Hash,
+    iter::FromIterator,
+    ops::{Deref, DerefMut},
+};
+
+/// A fixed-size, heterogeneous collection of [`Dynamic`] values.
+///
+/// Created in scripts via tuple literals such as `(1, "hello", true)`.
+/// A one-element tuple requires a trailing comma: `(42,)`.
+#[derive(Clone, Default, Hash)]
+pub struct Tuple(Box<[Dynamic]>);
+
+impl Tuple {
+    /// Create a new [`Tuple`] from a list of values.
+    #[inline(always)]
+    #[must_use]
+    pub fn new(items: impl Into<Vec<Dynamic>>) -> Self {
+        Self(items.into().into_boxed_slice())
+    }
+    /// Convert this [`Tuple`] into a [`Vec`] of values.
+    #[inline(always)]
+    #[must_use]
+    pub fn into_vec(self) -> Vec<Dynamic> {
+        self.0.into_vec()
+    }
+}
+
+impl Deref for Tuple {
+    type Target = [Dynamic];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Tuple {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl AsRef<[Dynamic]> for Tuple {
+    #[inline(always)]
+    fn as_ref(&self) -> &[Dynamic] {
+        &self.0
+    }
+}
+
+impl From<Vec<Dynamic>> for Tuple {
+    #[inline(always)]
+    fn from(value: Vec<Dynamic>) -> Self {
+        Self(value.into_boxed_slice())
+    }
+}
+
+impl From<Tuple> for Vec<Dynamic> {
+    #[inline(always)]
+    fn from(value: Tuple) -> Self {
+        value.into_vec()
+    }
+}
+
+impl FromIterator<Dynamic> for Tuple {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = Dynamic>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Tuple {
+    type Item = Dynamic;
+    type IntoIter = std::vec::IntoIter<Dynamic>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_vec().into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Tuple {
+    type Item = &'a Dynamic;
+    type IntoIter = std::slice::Iter<'a, Dynamic>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Tuple {
+    /// Write the items of this [`Tuple`], surrounded by parentheses, using a formatting closure.
+    pub(crate) fn fmt_with(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        mut fmt_item: impl FnMut(&Dynamic, &mut fmt::Formatter<'_>) -> fmt::Result,
+    ) -> fmt::Result {
+        f.write_str("(")?;
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            fmt_item(item, f)?;
+        }
+        if self.0.len() == 1 {
+            f.write_str(",")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for Tuple {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for Tuple {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, fmt::Debug::fmt)
+    }
+}
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_index"))]
-use rhai::{Array, Dynamic, Engine, ParseErrorType, INT};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ParseErrorType, INT};
 use std::iter::FromIterator;
 
 #[test]
@@ -572,3 +572,30 @@ fn test_array_invalid_index_callback() {
         143
     );
 }
+
+#[test]
+fn test_arrays_comprehension() {
+    let engine = Engine::new();
+
+    let to_ints = |a: Array| a.into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>();
+
+    assert_eq!(to_ints(engine.eval("let xs = [1, -2, 3, -4, 5]; [x * 2 for x in xs if x > 0]").unwrap()), [2, 6, 10]);
+    assert_eq!(to_ints(engine.eval("[x * i for (x, i) in [10, 20, 30]]").unwrap()), [0, 20, 60]);
+    assert_eq!(to_ints(engine.eval("[x for x in 0..10 if x % 3 == 0]").unwrap()), [0, 3, 6, 9]);
+    assert_eq!(to_ints(engine.eval("[x for x in 0..10 if x > 100]").unwrap()), Vec::<INT>::new());
+    assert_eq!(to_ints(engine.eval("let k = 3; let j = 4; [x + k * j for x in 0..3]").unwrap()), [12, 13, 14]);
+    assert_eq!(engine.eval::<INT>("let x = 42; let a = [x for x in 0..3]; x + len(a)").unwrap(), 45);
+    assert_eq!(engine.eval::<INT>("let m = [[x * y for x in 1..=2] for y in 1..=3]; m[2][1]").unwrap(), 6);
+    assert_eq!(engine.eval::<INT>("let a = [x for x in 0..5]; a[3]").unwrap(), 3);
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(to_ints(engine.eval("fn sq(x) { x * x } [sq(x) for x in 1..4]").unwrap()), [1, 4, 9]);
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(to_ints(engine.eval("let k = 10; let f = |x| x * k; [call(f, x) for x in 0..3]").unwrap()), [0, 10, 20]);
+
+    assert!(matches!(*engine.eval::<Array>("[x for x in 0..3 if x]").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+    assert!(matches!(*engine.eval::<Array>("let n = 42; [x for x in n]").unwrap_err(), EvalAltResult::ErrorFor(..)));
+    assert!(matches!(engine.compile("[...[1, 2] for x in 0..3]").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+}
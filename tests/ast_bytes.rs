@@ -0,0 +1,238 @@
+use rhai::{ASTFormatError, Dynamic, Engine, EvalAltResult, Position, AST, INT};
+
+fn roundtrip(engine: &Engine, script: &str) -> (AST, AST) {
+    let ast = engine.compile(script).unwrap();
+    let bytes = ast.to_bytes(engine).unwrap();
+    let loaded = AST::from_bytes(engine, &bytes).unwrap();
+    (ast, loaded)
+}
+
+fn assert_roundtrip(engine: &Engine, script: &str) {
+    let (ast, loaded) = roundtrip(engine, script);
+
+    let expected = engine.eval_ast::<Dynamic>(&ast).unwrap().to_string();
+    let actual = engine.eval_ast::<Dynamic>(&loaded).unwrap().to_string();
+    assert_eq!(actual, expected, "{script}");
+
+    // Nothing is lost, and serialization is deterministic
+    assert_eq!(loaded.to_bytes(engine).unwrap(), ast.to_bytes(engine).unwrap(), "{script}");
+}
+
+#[test]
+fn test_ast_bytes() {
+    let engine = Engine::new();
+
+    assert_roundtrip(&engine, "");
+    assert_roundtrip(&engine, "let x = 40; x += 2; x");
+    assert_roundtrip(&engine, "const X = 42; let y = -X; y = +y; !(y < 0)");
+    assert_roundtrip(&engine, r#"let s = "hello"; `${s}, ${'w'}orld ${1 + 2}`"#);
+    assert_roundtrip(&engine, "let x = 0; while x < 10 { x += 1; if x == 5 { break; } } x");
+    assert_roundtrip(&engine, "let x = 0; do { x += 1 } until x >= 3; loop { x *= 2; if x > 100 { break x; } }");
+    assert_roundtrip(&engine, "let n = 0; for (x, i) in 0..10 { if i % 2 == 0 { continue; } n += x } n");
+    assert_roundtrip(&engine, "let x = (); x ?? 42");
+    assert_roundtrip(&engine, "let x = 1; x > 0 && x < 10 || false");
+    assert_roundtrip(&engine, "try { throw 42 } catch (err) { err + 1 }");
+    assert_roundtrip(&engine, "let t = (1, 'x', true); t");
+    assert_roundtrip(&engine, "let x = 3; switch x { 1 => 'a', 2 | 3 if x > 2 => 'b', 4..10 => 'c', _ => 'd' }");
+    assert_roundtrip(&engine, "enum Op { Add(x, y), Neg(x) } switch Op::Add(40, 2) { Op::Add(x, y) => x + y, Op::Neg(x) => -x }");
+    #[cfg(not(feature = "no_float"))]
+    assert_roundtrip(&engine, "let x = 1.5; x * 2.0");
+    #[cfg(not(feature = "no_index"))]
+    assert_roundtrip(&engine, "let a = [1, 2, [3, 4]]; a[2][1] = 42; a + [x * 2 for x in 0..3 if x > 0]");
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    assert_roundtrip(&engine, "let m = #{ a: 1, b: #{ c: [true] } }; m.b.c.len() + m.a");
+    #[cfg(not(feature = "no_object"))]
+    assert_roundtrip(&engine, "struct Point { x, y } let p = Point { x: 1, y: 2 }; p.x = 40; p.x + p.y");
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_roundtrip(&engine, "fn add(x, y) { x + y } private fn neg(x) { -x } add(40, neg(-2))");
+        #[cfg(not(feature = "no_closure"))]
+        assert_roundtrip(&engine, "fn hit() { static n = 0; n += 1; n } hit(); hit()");
+        #[cfg(not(feature = "no_object"))]
+        assert_roundtrip(&engine, "fn gen() { yield 1; yield 2; } let g = gen(); g.next() + g.next()");
+        assert_roundtrip(&engine, "fn gen() { for x in 0..5 { if x % 2 == 1 { yield x; } } } let s = 0; for x in gen() { s += x; } s");
+        assert_roundtrip(&engine, "let f = Fn(\"abs\"); call(f, -42)");
+        #[cfg(not(feature = "no_object"))]
+        assert_roundtrip(&engine, "fn inc(n) { this += n } let x = 40; x.inc(2); x");
+        #[cfg(not(feature = "no_closure"))]
+        assert_roundtrip(&engine, "let x = 40; let f = |y| x + y; x = 0; call(f, 2)");
+    }
+
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(not(feature = "no_function"))]
+    assert_roundtrip(&engine, "module m { export const X = 40; fn f() { 2 } } m::X + m::f()");
+}
+
+#[test]
+fn test_ast_bytes_positions() {
+    let engine = Engine::new();
+
+    let (.., ast) = roundtrip(&engine, "let x = 42;\n\n  foo(x)");
+    let err = engine.run_ast(&ast).unwrap_err();
+
+    assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(..)));
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.position(), Position::new(3, 3));
+    #[cfg(feature = "no_position")]
+    assert_eq!(err.position(), Position::NONE);
+}
+
+#[cfg(feature = "metadata")]
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_ast_bytes_doc_comments() {
+    let engine = Engine::new();
+
+    let (.., ast) = roundtrip(
+        &engine,
+        "
+            //! Module doc
+            //! comments
+
+            /// Adds two numbers.
+            fn add(x, y) { x + y }
+        ",
+    );
+
+    assert_eq!(ast.doc(), "//! Module doc\n//! comments");
+    assert_eq!(ast.iter_functions().next().unwrap().comments, ["/// Adds two numbers."]);
+}
+
+#[test]
+fn test_ast_bytes_rejected() {
+    let engine = Engine::new();
+    let ast = engine.compile("let x = 40; x + 2").unwrap();
+    let bytes = ast.to_bytes(&engine).unwrap();
+
+    assert_eq!(AST::from_bytes(&engine, b"not an AST").unwrap_err(), ASTFormatError::BadMagic);
+
+    let mut other = bytes.clone();
+    other[4] = 0xff;
+    assert!(matches!(AST::from_bytes(&engine, &other).unwrap_err(), ASTFormatError::UnsupportedVersion(..)));
+
+    // Truncated input is an error, not a panic
+    for len in 0..bytes.len() {
+        assert!(AST::from_bytes(&engine, &bytes[..len]).is_err());
+    }
+
+    // Corrupted input is an error, not a panic
+    for i in 0..bytes.len() {
+        let mut other = bytes.clone();
+        other[i] ^= 0xff;
+        let _ = AST::from_bytes(&engine, &other);
+    }
+
+    let mut other = bytes.clone();
+    other.push(0);
+    assert!(matches!(AST::from_bytes(&engine, &other).unwrap_err(), ASTFormatError::Corrupted(..)));
+
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        let mut engine2 = Engine::new();
+        engine2.set_optimization_level(rhai::OptimizationLevel::None);
+        let err = AST::from_bytes(&engine2, &bytes).unwrap_err();
+        assert!(matches!(err, ASTFormatError::ConfigMismatch(..)));
+        assert_eq!(err.to_string(), "Serialized AST was written with optimization level Simple");
+    }
+}
+
+#[test]
+fn test_ast_bytes_unsupported() {
+    #[derive(Debug, Clone)]
+    struct Foo;
+
+    let mut engine = Engine::new();
+    engine.register_type::<Foo>();
+
+    let mut scope = rhai::Scope::new();
+    scope.push_constant("FOO", Foo);
+    scope.push_constant("X", 42 as INT);
+
+    let ast = engine.compile_with_scope(&scope, "X").unwrap();
+    assert!(ast.to_bytes(&engine).is_ok());
+
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        let ast = engine.compile_with_scope(&scope, "let x = FOO; x").unwrap();
+        assert!(matches!(ast.to_bytes(&engine).unwrap_err(), ASTFormatError::UnsupportedValue(..)));
+    }
+
+    #[cfg(not(feature = "no_custom_syntax"))]
+    {
+        engine
+            .register_custom_syntax(["twice", "$expr$"], false, |context, inputs| Ok((context.eval_expression_tree(&inputs[0])?.as_int().unwrap() * 2).into()))
+            .unwrap();
+
+        let ast = engine.compile("twice 21").unwrap();
+        let bytes = ast.to_bytes(&engine).unwrap();
+        assert_eq!(engine.eval_ast::<INT>(&AST::from_bytes(&engine, &bytes).unwrap()).unwrap(), 42);
+
+        let engine2 = Engine::new();
+        assert_eq!(AST::from_bytes(&engine2, &bytes).unwrap_err(), ASTFormatError::UnknownCustomSyntax("twice".into()));
+    }
+}
+
+/// Set in the child process spawned by `test_ast_bytes_other_process`.
+const CHILD_PROCESS: &str = "RHAI_TEST_AST_BYTES_CHILD";
+
+/// Each script evaluates to 42.
+fn other_process_scripts() -> Vec<&'static str> {
+    let mut scripts = vec!["let x = -37; abs(x) + 5"];
+    #[cfg(not(feature = "no_object"))]
+    scripts.push("let s = \"hello\"; let m = #{ a: 35 }; m.a += 2; s.len() + m.a");
+    #[cfg(not(feature = "no_function"))]
+    scripts.push("fn add(x, y) { x + y } let f = |x| add(x, 2); call(f, 40)");
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(not(feature = "no_function"))]
+    scripts.push("module m { export const X = 40; } m::X + 2");
+    scripts
+}
+
+#[test]
+fn test_ast_bytes_other_process_child() {
+    if std::env::var_os(CHILD_PROCESS).is_none() {
+        return;
+    }
+
+    // This process does not hash anything else, so the seed can still be set
+    rhai::config::hashing::set_hashing_seed(Some([1, 2, 3, 4])).unwrap();
+
+    let engine = Engine::new();
+
+    for script in other_process_scripts().into_iter().chain(["let x = 1; switch x { 1 => 42, _ => 0 }"]) {
+        let bytes = engine.compile(script).unwrap().to_bytes(&engine).unwrap();
+        let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        println!("AST {hex}");
+    }
+}
+
+#[test]
+fn test_ast_bytes_other_process() {
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["test_ast_bytes_other_process_child", "--exact", "--nocapture"])
+        .env(CHILD_PROCESS, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // The test harness may print its own output in front of ours on the same line
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut loaded = stdout.lines().filter_map(|line| line.split_once("AST ")).map(|(.., hex)| {
+        let bytes: Vec<u8> = (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect();
+        AST::from_bytes(&Engine::new(), &bytes)
+    });
+
+    let engine = Engine::new();
+
+    for script in other_process_scripts() {
+        let ast = loaded.next().unwrap().expect(script);
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42, "{script}");
+    }
+
+    // Only the hashes of switch cases are kept, so these need the same seed
+    let err = loaded.next().unwrap().unwrap_err();
+    assert!(matches!(err, ASTFormatError::ConfigMismatch(..)));
+    assert!(loaded.next().is_none());
+}
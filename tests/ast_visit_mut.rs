@@ -0,0 +1,115 @@
+#![cfg(feature = "internals")]
+use rhai::{Engine, Expr, FnCallExpr, ImmutableString, VisitorMut, INT};
+
+/// Wrap every call to `http_get` into a call to `audit`.
+#[derive(Default)]
+struct Audit {
+    count: usize,
+    limit: Option<usize>,
+}
+
+impl VisitorMut for Audit {
+    fn visit_expr(&mut self, expr: &mut Expr) -> bool {
+        if matches!(expr, Expr::FnCall(x, ..) if x.name == "http_get") {
+            let pos = expr.position();
+            *expr = FnCallExpr::new("audit", [expr.take()]).into_fn_call_expr(pos);
+            self.count += 1;
+        }
+        self.limit.map_or(true, |limit| self.count < limit)
+    }
+}
+
+fn make_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.register_fn("http_get", |url: &str| format!("GET {url}")).register_fn("audit", |s: &str| format!("[{s}]"));
+    engine
+}
+
+#[test]
+fn test_ast_walk_mut() {
+    let engine = make_engine();
+
+    let mut ast = engine.compile(r#"http_get("a"); let x = http_get("b"); x + " " + http_get(http_get("c"))"#).unwrap();
+
+    let mut audit = Audit::default();
+    assert!(ast.walk_mut(&mut audit));
+    assert_eq!(audit.count, 4);
+    assert_eq!(engine.eval_ast::<String>(&ast).unwrap(), "[GET b] [GET [GET c]]");
+
+    // Rewritten calls are not visited again
+    let mut audit = Audit::default();
+    assert!(ast.walk_mut(&mut audit));
+    assert_eq!(audit.count, 4);
+
+    let mut ast = engine.compile(r#"http_get("a"); http_get("b"); http_get("c")"#).unwrap();
+    let mut audit = Audit { limit: Some(2), ..Audit::default() };
+    assert!(!ast.walk_mut(&mut audit));
+    assert_eq!(audit.count, 2);
+    assert_eq!(engine.eval_ast::<String>(&ast).unwrap(), "GET c");
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let mut ast = engine.compile(r#"fn get(url) { if url != "" { http_get(url) } else { throw "empty" } } get("x")"#).unwrap();
+
+        let mut audit = Audit::default();
+        assert!(ast.walk_mut(&mut audit));
+        assert_eq!(audit.count, 1);
+        assert_eq!(engine.eval_ast::<String>(&ast).unwrap(), "[GET x]");
+    }
+}
+
+#[cfg(not(feature = "no_optimize"))]
+#[test]
+fn test_ast_walk_mut_optimize() {
+    use rhai::{OptimizationLevel, Scope};
+
+    let mut engine = make_engine();
+    let script = r#"let url = "a"; switch url { "a" => http_get(url), _ => () }"#;
+
+    // Before optimization
+    engine.set_optimization_level(OptimizationLevel::None);
+    let mut ast = engine.compile(script).unwrap();
+    ast.walk_mut(&mut Audit::default());
+    let ast = engine.optimize_ast(&Scope::new(), ast, OptimizationLevel::Full);
+    assert_eq!(engine.eval_ast::<String>(&ast).unwrap(), "[GET a]");
+
+    // After optimization
+    engine.set_optimization_level(OptimizationLevel::Full);
+    let mut ast = engine.compile(script).unwrap();
+    ast.walk_mut(&mut Audit::default());
+    assert_eq!(engine.eval_ast::<String>(&ast).unwrap(), "[GET a]");
+}
+
+#[test]
+fn test_ast_walk_mut_recalc_hashes() {
+    /// Rename `old` calls to `renamed`, adding an extra argument.
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_expr(&mut self, expr: &mut Expr) -> bool {
+            match expr {
+                Expr::FnCall(x, pos) if x.name == "old" => {
+                    x.name = ImmutableString::from("renamed");
+                    x.args.push(Expr::IntegerConstant(1, *pos));
+                    x.recalc_hashes();
+                }
+                _ => (),
+            }
+            true
+        }
+    }
+
+    let mut engine = Engine::new();
+    engine.register_fn("old", |x: INT| x).register_fn("renamed", |x: INT, y: INT| x + y);
+
+    let mut ast = engine.compile("old(41)").unwrap();
+    ast.walk_mut(&mut Rename);
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let mut ast = engine.compile("fn renamed(x, y) { x * 10 + y } let x = old(4); x").unwrap();
+        ast.walk_mut(&mut Rename);
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 41);
+    }
+}
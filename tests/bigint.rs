@@ -0,0 +1,141 @@
+#![cfg(feature = "bigint")]
+
+use num_bigint::BigInt;
+use rhai::{Engine, EvalAltResult, INT};
+
+fn big(s: &str) -> BigInt {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_bigint_literals() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<BigInt>("123n").unwrap(), BigInt::from(123));
+    assert_eq!(engine.eval::<BigInt>("123_456_789_012_345_678_901_234_567_890n").unwrap(), big("123456789012345678901234567890"));
+    assert_eq!(engine.eval::<BigInt>("0xffff_ffff_ffff_ffff_ffffn").unwrap(), big("1208925819614629174706175"));
+    assert_eq!(engine.eval::<BigInt>("0b1000n").unwrap(), BigInt::from(8));
+    assert_eq!(engine.eval::<BigInt>("-42n").unwrap(), BigInt::from(-42));
+    assert_eq!(engine.eval::<String>("type_of(1n)").unwrap(), "bigint");
+    assert_eq!(engine.eval::<String>("let x = 99999999999999999999999n; `${x}`").unwrap(), "99999999999999999999999");
+
+    assert!(engine.compile("1.5n").is_err());
+}
+
+#[test]
+fn test_bigint_arithmetic() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<BigInt>("2n ** 100").unwrap(), big("1267650600228229401496703205376"));
+    assert_eq!(engine.eval::<BigInt>("let x = 1n; for n in 1..=30 { x *= n; } x").unwrap(), big("265252859812191058636308480000000"));
+    assert_eq!(engine.eval::<BigInt>("10 - 3n").unwrap(), BigInt::from(7));
+    assert_eq!(engine.eval::<BigInt>("-7n / 2").unwrap(), BigInt::from(-3));
+    assert_eq!(engine.eval::<BigInt>("-7n % 2").unwrap(), BigInt::from(-1));
+    assert_eq!(engine.eval::<BigInt>("1n << 70 >> 68").unwrap(), BigInt::from(4));
+    assert_eq!(engine.eval::<BigInt>("8n << -2").unwrap(), BigInt::from(2));
+    assert_eq!(engine.eval::<BigInt>("-1n >> 1000").unwrap(), BigInt::from(-1));
+    assert_eq!(engine.eval::<BigInt>("(0xf0n | 0x0f) ^ 0xffn & 0x3c").unwrap(), BigInt::from(0xc3));
+    assert_eq!(engine.eval::<BigInt>("let x = 5n; x -= 8; -x").unwrap(), BigInt::from(3));
+
+    assert!(engine.eval::<bool>("2n ** 64 > 18446744073709551615n").unwrap());
+    assert!(engine.eval::<bool>("5n == 5").unwrap());
+    assert!(engine.eval::<bool>("4 < 5n").unwrap());
+    assert!(engine.eval::<bool>("sign(-3n) == -1 && 0n.is_zero && 3n.is_odd").unwrap());
+    assert_eq!(engine.eval::<INT>("(2n ** 64).bits").unwrap(), 65);
+    assert_eq!(engine.eval::<BigInt>("max(3, 10n) + min(2n, -1)").unwrap(), BigInt::from(9));
+
+    assert!(matches!(*engine.eval::<BigInt>("1n / 0").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<BigInt>("1n % 0n").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<BigInt>("2n ** -1").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+}
+
+#[test]
+fn test_bigint_conversions() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<BigInt>(r#"parse_bigint("-98765432109876543210")"#).unwrap(), big("-98765432109876543210"));
+    assert_eq!(engine.eval::<BigInt>(r#"parse_bigint("ff", 16)"#).unwrap(), BigInt::from(255));
+    assert!(engine.eval::<BigInt>(r#"parse_bigint("12x")"#).is_err());
+    assert_eq!(engine.eval::<BigInt>("to_bigint(42)").unwrap(), BigInt::from(42));
+    assert_eq!(engine.eval::<INT>("to_int(42n)").unwrap(), 42);
+    assert!(engine.eval::<INT>("to_int(2n ** 100)").is_err());
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<BigInt>("to_bigint(-2.9)").unwrap(), BigInt::from(-2));
+        assert_eq!(engine.eval::<rhai::FLOAT>("to_float(1n << 10)").unwrap(), 1024.0);
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<String>("let a = [3n, -1n, 20n]; a.sort(); `${a}`").unwrap(), "[-1n, 3n, 20n]");
+}
+
+#[test]
+fn test_bigint_hashing() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("switch 100000000000000000000n { 100000000000000000000n => 1, _ => 2 }").unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("switch 5n { 0..10 => 1, _ => 2 }").unwrap(), 1);
+
+    #[cfg(not(feature = "no_index"))]
+    assert!(engine.eval::<bool>("[1n, 2n ** 80].contains(2n ** 80)").unwrap());
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_bigint_max_size() {
+    let mut engine = Engine::new();
+    engine.set_max_string_size(100);
+
+    assert_eq!(engine.eval::<INT>("(10n ** 99).to_string().len()").unwrap(), 100);
+
+    for script in ["10n ** 100", "(10n ** 60) * (10n ** 60)", "1n << 400", "let x = 10n ** 99; x *= 10; x"] {
+        assert!(matches!(*engine.eval::<BigInt>(script).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)), "{script}");
+    }
+
+    // One to any power is still one
+    assert_eq!(engine.eval::<BigInt>("1n ** 1000000").unwrap(), BigInt::from(1));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_bigint_serde() {
+    use rhai::serde::{from_dynamic, to_dynamic};
+    use rhai::Dynamic;
+
+    let value = Dynamic::from(big("340282366920938463463374607431768211456"));
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#""340282366920938463463374607431768211456""#);
+
+    let value = Dynamic::from(BigInt::from(u64::MAX));
+    assert_eq!(from_dynamic::<u64>(&value).unwrap(), u64::MAX);
+    assert_eq!(from_dynamic::<u128>(&value).unwrap(), u128::from(u64::MAX));
+
+    let value = to_dynamic(u128::MAX).unwrap();
+    assert_eq!(value.type_name(), "bigint");
+    assert_eq!(from_dynamic::<u128>(&value).unwrap(), u128::MAX);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+#[cfg(feature = "decimal")]
+fn test_bigint_serde_decimal() {
+    use rhai::Dynamic;
+    use rust_decimal::Decimal;
+    use serde::de::{value::Error, IntoDeserializer};
+    use serde::Deserialize;
+
+    // `Decimal` takes precedence when it represents the number exactly
+    let value: Dynamic = serde_json::from_str("10000000000000000000").unwrap();
+    assert!(value.is::<Decimal>());
+
+    let value = Dynamic::deserialize(IntoDeserializer::<Error>::into_deserializer(u128::from(u64::MAX) + 1)).unwrap();
+    assert!(value.is::<Decimal>());
+
+    // Otherwise fall back to `BigInt`
+    let value = Dynamic::deserialize(IntoDeserializer::<Error>::into_deserializer(u128::MAX)).unwrap();
+    assert_eq!(value.cast::<BigInt>(), BigInt::from(u128::MAX));
+
+    let value = Dynamic::deserialize(IntoDeserializer::<Error>::into_deserializer(i128::MIN)).unwrap();
+    assert_eq!(value.cast::<BigInt>(), BigInt::from(i128::MIN));
+}
@@ -0,0 +1,210 @@
+#![cfg(feature = "coverage")]
+#![cfg(not(feature = "no_position"))]
+use rhai::coverage::{BranchKind, Coverage};
+use rhai::{Engine, Position, INT};
+
+#[test]
+fn test_coverage_statements() {
+    let mut engine = Engine::new();
+
+    assert!(engine.coverage().is_none());
+
+    engine.set_coverage_enabled(true);
+
+    assert!(engine.is_coverage_enabled());
+
+    engine
+        .run(
+            "
+                let x = 42;
+                if x > 100 {
+                    x = 0;
+                }
+                while x > 40 {
+                    x -= 1;
+                }
+            ",
+        )
+        .unwrap();
+
+    let coverage = engine.take_coverage().unwrap();
+    let script = coverage.get("").unwrap();
+
+    assert_eq!(script.statements[&Position::new(2, 17)], 1);
+    assert_eq!(script.statements[&Position::new(4, 23)], 0);
+    assert_eq!(script.statements[&Position::new(7, 23)], 2);
+
+    let lines = script.lines();
+    assert_eq!(lines[&4], 0);
+    assert_eq!(lines[&7], 2);
+
+    let branch = &script.branches[&Position::new(3, 17)];
+    assert_eq!(branch.kind, BranchKind::If);
+    assert_eq!(branch.hits, vec![0, 1]);
+
+    assert!(engine.take_coverage().unwrap().is_empty());
+
+    engine.set_coverage_enabled(false);
+    engine.run("let x = 42;").unwrap();
+    assert!(engine.coverage().is_none());
+}
+
+#[test]
+fn test_coverage_branches() {
+    let mut engine = Engine::new();
+
+    engine.set_coverage_enabled(true);
+
+    for x in 0..4 as INT {
+        engine
+            .run(&format!(
+                "
+                    let x = {x};
+                    let y = switch x {{
+                        0 => 1,
+                        1 | 2 if x > 1 => 2,
+                        _ => 3
+                    }};
+                    let z = switch x {{ 0 => 1 }};
+                    let w = z ?? y ?? 0;
+                "
+            ))
+            .unwrap();
+    }
+
+    let coverage = engine.coverage().unwrap();
+    let script = coverage.get("").unwrap();
+
+    let branch = &script.branches[&Position::new(3, 29)];
+    assert_eq!(branch.kind, BranchKind::Switch);
+    assert_eq!(branch.hits.iter().sum::<usize>(), 4);
+    assert_eq!(branch.hits.iter().filter(|&&n| n > 0).count(), 3);
+
+    let branch = &script.branches[&Position::new(8, 29)];
+    assert_eq!(branch.kind, BranchKind::Switch);
+    assert_eq!(branch.hits, vec![1, 3]);
+
+    let branch = &script.branches[&Position::new(9, 31)];
+    assert_eq!(branch.kind, BranchKind::Coalesce);
+    assert_eq!(branch.hits, vec![1, 3, 0]);
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_coverage_elvis() {
+    let mut engine = Engine::new();
+
+    engine.set_coverage_enabled(true);
+
+    engine
+        .run(
+            "
+                let a = #{x: 1};
+                let b = ();
+                a?.x;
+                b?.x;
+                b?.x;
+            ",
+        )
+        .unwrap();
+
+    let coverage = engine.take_coverage().unwrap();
+    let script = coverage.get("").unwrap();
+
+    let (elvis, other): (Vec<_>, Vec<_>) = script.branches.values().partition(|b| b.kind == BranchKind::Elvis);
+
+    assert!(other.is_empty());
+    assert_eq!(elvis.len(), 3);
+    assert_eq!(elvis.iter().map(|b| b.hits[0]).sum::<usize>(), 1);
+    assert_eq!(elvis.iter().map(|b| b.hits[1]).sum::<usize>(), 2);
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_coverage_functions_and_modules() {
+    use rhai::{Module, ModuleResolver, Shared};
+
+    struct TestResolver;
+
+    impl ModuleResolver for TestResolver {
+        fn resolve(&self, engine: &Engine, _: Option<&str>, path: &str, _: Position) -> Result<Shared<Module>, Box<rhai::EvalAltResult>> {
+            let mut ast = engine.compile(
+                "
+                    fn double(x) {
+                        if x < 0 {
+                            throw \"negative\";
+                        }
+                        x * 2
+                    }
+                ",
+            )?;
+            ast.set_source(path);
+            Module::eval_ast_as_new(rhai::Scope::new(), &ast, engine).map(Into::into)
+        }
+    }
+
+    let mut engine = Engine::new();
+
+    engine.set_module_resolver(TestResolver);
+    engine.set_coverage_enabled(true);
+
+    let mut ast = engine
+        .compile(
+            "
+                import \"math\" as m;
+
+                fn unused() {
+                    42
+                }
+                fn triple(x) {
+                    x * 3
+                }
+
+                triple(m::double(21))
+            ",
+        )
+        .unwrap();
+    ast.set_source("main");
+
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 126);
+    assert_eq!(engine.call_fn::<INT>(&mut rhai::Scope::new(), &ast, "triple", (1 as INT,)).unwrap(), 3);
+
+    let coverage = engine.take_coverage().unwrap();
+    assert_eq!(coverage.iter().map(|(source, ..)| source).collect::<Vec<_>>(), ["main", "math"]);
+
+    let main = coverage.get("main").unwrap();
+    assert_eq!(main.statements[&Position::new(5, 21)], 0);
+    // `call_fn` evaluates the script before calling `triple`
+    assert_eq!(main.statements[&Position::new(8, 23)], 3);
+    assert_eq!(main.statements[&Position::new(11, 17)], 2);
+
+    let math = coverage.get("math").unwrap();
+    assert_eq!(math.statements[&Position::new(3, 25)], 2);
+    assert_eq!(math.statements[&Position::new(4, 29)], 0);
+    assert_eq!(math.branches[&Position::new(3, 25)].hits, vec![0, 2]);
+}
+
+#[test]
+fn test_coverage_reports() {
+    let mut engine = Engine::new();
+
+    engine.set_coverage_enabled(true);
+
+    engine.run("let x = 1;\nif x > 0 { x = 2; } else { x = 3; }").unwrap();
+
+    let mut coverage = engine.take_coverage().unwrap();
+
+    assert_eq!(coverage.to_lcov(), "TN:\nSF:\nBRDA:2,0,0,1\nBRDA:2,0,1,0\nBRF:2\nBRH:1\nDA:1,1\nDA:2,1\nLF:2\nLH:2\nend_of_record\n");
+
+    let json: serde_json::Value = serde_json::from_str(&coverage.to_json()).unwrap();
+    assert_eq!(json["files"][0]["statements"]["total"], 4);
+    assert_eq!(json["files"][0]["statements"]["covered"], 3);
+    assert_eq!(json["total"]["branches"]["percent"], 50.0);
+    assert_eq!(json["files"][0]["uncovered_lines"], serde_json::json!([]));
+
+    coverage.merge(&coverage.clone());
+    assert_eq!(coverage.get("").unwrap().statements[&Position::new(1, 1)], 2);
+
+    assert_eq!(Coverage::new().to_lcov(), "");
+}
@@ -0,0 +1,403 @@
+#![cfg(feature = "dap")]
+#![cfg(not(feature = "no_position"))]
+use rhai::dap::{read_message, write_message, DebugAdapter};
+use rhai::Engine;
+use serde_json::{json, Value};
+use std::io::{self, Cursor, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// An output stream that can be read back after the adapter is done with it.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Write a script into a temporary file, returning its canonical path.
+fn script(name: &str, text: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("rhai-dap-tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, text).unwrap();
+    path.canonicalize().unwrap()
+}
+
+/// Run a debugging session with requests queued up front, returning all messages from the adapter.
+fn debug(engine: &mut Engine, requests: &[(&str, Value)]) -> Vec<Value> {
+    let mut input = Vec::new();
+    for (i, (command, arguments)) in requests.iter().enumerate() {
+        let request = json!({ "seq": i + 1, "type": "request", "command": command, "arguments": arguments });
+        write_message(&mut input, &request.to_string()).unwrap();
+    }
+
+    let output = Buffer::default();
+    DebugAdapter::new(Cursor::new(input), output.clone()).run(engine).unwrap();
+
+    let mut output = Cursor::new(output.0.lock().unwrap().clone());
+    let mut messages = Vec::new();
+    while let Some(message) = read_message(&mut output).unwrap() {
+        messages.push(serde_json::from_str::<Value>(&message).unwrap());
+    }
+    messages
+}
+
+/// Requests to start a session on a script, with line break-points.
+fn launch(path: &PathBuf, stop_on_entry: bool, lines: &[usize]) -> Vec<(&'static str, Value)> {
+    vec![
+        ("initialize", json!({ "adapterID": "rhai" })),
+        ("launch", json!({ "program": path, "stopOnEntry": stop_on_entry })),
+        ("setBreakpoints", json!({ "source": { "path": path }, "breakpoints": lines.iter().map(|line| json!({ "line": line })).collect::<Vec<_>>() })),
+        ("configurationDone", json!({})),
+    ]
+}
+
+fn responses<'a>(messages: &'a [Value], command: &str) -> Vec<&'a Value> {
+    messages.iter().filter(|m| m["type"] == "response" && m["command"] == command).collect()
+}
+
+fn events<'a>(messages: &'a [Value], event: &str) -> Vec<&'a Value> {
+    messages.iter().filter(|m| m["type"] == "event" && m["event"] == event).map(|m| &m["body"]).collect()
+}
+
+fn output(messages: &[Value]) -> String {
+    events(messages, "output").iter().map(|body| body["output"].as_str().unwrap()).collect()
+}
+
+/// The `name = value` pairs in a `variables` response.
+fn variables(response: &Value) -> Vec<(String, String)> {
+    response["body"]["variables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| (v["name"].as_str().unwrap().to_string(), v["value"].as_str().unwrap().to_string()))
+        .collect()
+}
+
+/// The `(name, line)` of each frame in a `stackTrace` response.
+fn frames(response: &Value) -> Vec<(String, i64)> {
+    response["body"]["stackFrames"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| (f["name"].as_str().unwrap().to_string(), f["line"].as_i64().unwrap()))
+        .collect()
+}
+
+fn stopped_lines(messages: &[Value]) -> Vec<i64> {
+    responses(messages, "stackTrace").iter().map(|r| frames(r)[0].1).collect()
+}
+
+#[test]
+fn test_dap_protocol() {
+    let path = script("protocol.rhai", "print(40 + 2);\ndebug(\"done\");\n");
+
+    let mut requests = launch(&path, false, &[]);
+    requests.push(("threads", json!({})));
+    requests.push(("disconnect", json!({})));
+
+    let messages = debug(&mut Engine::new(), &requests);
+
+    let seqs = messages.iter().map(|m| m["seq"].as_i64().unwrap()).collect::<Vec<_>>();
+    assert_eq!(seqs, (1..=seqs.len() as i64).collect::<Vec<_>>());
+
+    let initialize = responses(&messages, "initialize");
+    assert_eq!(initialize[0]["success"], true);
+    assert_eq!(initialize[0]["request_seq"], 1);
+    assert_eq!(initialize[0]["body"]["supportsConfigurationDoneRequest"], true);
+    assert_eq!(initialize[0]["body"]["supportsFunctionBreakpoints"], true);
+    assert_eq!(events(&messages, "initialized").len(), 1);
+    assert_eq!(responses(&messages, "launch")[0]["success"], true);
+
+    assert!(events(&messages, "stopped").is_empty());
+    assert_eq!(output(&messages), "42\n\"done\"\n");
+    assert_eq!(events(&messages, "exited")[0]["exitCode"], 0);
+    assert_eq!(events(&messages, "terminated").len(), 1);
+
+    let threads = responses(&messages, "threads");
+    assert_eq!(threads[0]["body"]["threads"][0]["id"], 1);
+    assert_eq!(responses(&messages, "disconnect")[0]["success"], true);
+
+    // Errors
+    let missing = script("missing.rhai", "");
+    std::fs::remove_file(&missing).unwrap();
+
+    let messages = debug(&mut Engine::new(), &[("launch", json!({ "program": missing })), ("disconnect", json!({}))]);
+    assert_eq!(responses(&messages, "launch")[0]["success"], false);
+
+    let path = script("error.rhai", "let x = 1;\nthrow \"oops\";\n");
+    let messages = debug(&mut Engine::new(), &launch(&path, false, &[]));
+    assert!(output(&messages).contains("oops"));
+    assert_eq!(events(&messages, "exited")[0]["exitCode"], 1);
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_dap_break_points() {
+    let path = script(
+        "break_points.rhai",
+        "
+fn add(a, b) {
+    let sum = a + b;
+    sum
+}
+fn apply(n) {
+    add(40, n)
+}
+let x = 1;
+let y = apply(x + 1);
+print(y);
+",
+    );
+
+    let mut requests = launch(&path, false, &[3, 11]);
+    requests.extend([
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("scopes", json!({ "frameId": 0 })),
+        ("variables", json!({ "variablesReference": 1 })),
+        ("scopes", json!({ "frameId": 1 })),
+        ("variables", json!({ "variablesReference": 2 })),
+        ("evaluate", json!({ "expression": "a * b", "frameId": 0 })),
+        ("evaluate", json!({ "expression": "c", "frameId": 0 })),
+        ("continue", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("continue", json!({ "threadId": 1 })),
+        ("disconnect", json!({})),
+    ]);
+
+    let messages = debug(&mut Engine::new(), &requests);
+
+    let set = responses(&messages, "setBreakpoints");
+    let ids = set[0]["body"]["breakpoints"].as_array().unwrap().iter().map(|bp| bp["id"].clone()).collect::<Vec<_>>();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(set[0]["body"]["breakpoints"][0]["verified"], true);
+
+    let stopped = events(&messages, "stopped");
+    assert_eq!(stopped.len(), 2);
+    assert_eq!(stopped[0]["reason"], "breakpoint");
+    assert_eq!(stopped[0]["hitBreakpointIds"], json!([ids[0]]));
+    assert_eq!(stopped[1]["hitBreakpointIds"], json!([ids[1]]));
+
+    let traces = responses(&messages, "stackTrace");
+    assert_eq!(frames(traces[0]), vec![("add".to_string(), 3), ("apply".to_string(), 7), ("main".to_string(), 10)]);
+    assert_eq!(traces[0]["body"]["stackFrames"][0]["source"]["path"], path.to_string_lossy().as_ref());
+    assert_eq!(frames(traces[1]), vec![("main".to_string(), 11)]);
+
+    let scopes = responses(&messages, "scopes");
+    assert_eq!(scopes[0]["body"]["scopes"][0]["name"], "Locals");
+    assert_eq!(scopes[1]["body"]["scopes"][0]["name"], "Arguments");
+
+    let vars = responses(&messages, "variables");
+    assert_eq!(variables(vars[0]), vec![("a".to_string(), "40".to_string()), ("b".to_string(), "2".to_string())]);
+    assert_eq!(variables(vars[1]), vec![("[0]".to_string(), "2".to_string())]);
+
+    let evaluate = responses(&messages, "evaluate");
+    assert_eq!(evaluate[0]["body"]["result"], "80");
+    assert_eq!(evaluate[1]["success"], false);
+
+    assert_eq!(output(&messages), "42\n");
+    assert_eq!(events(&messages, "exited")[0]["exitCode"], 0);
+}
+
+#[test]
+fn test_dap_loops() {
+    let path = script("loops.rhai", "let sum = 0;\nfor i in 0..3 {\n    sum += i * 2;\n}\nprint(sum);\n");
+
+    let mut requests = launch(&path, false, &[3]);
+    requests.extend([
+        ("evaluate", json!({ "expression": "i" })),
+        ("continue", json!({ "threadId": 1 })),
+        ("evaluate", json!({ "expression": "i" })),
+        ("continue", json!({ "threadId": 1 })),
+        ("evaluate", json!({ "expression": "i" })),
+        ("continue", json!({ "threadId": 1 })),
+    ]);
+
+    let messages = debug(&mut Engine::new(), &requests);
+
+    // A line break-point stops once every time the line is reached
+    assert_eq!(events(&messages, "stopped").len(), 3);
+
+    let values = responses(&messages, "evaluate").iter().map(|r| r["body"]["result"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(values, ["0", "1", "2"]);
+    assert_eq!(output(&messages), "6\n");
+}
+
+#[test]
+fn test_dap_conditions() {
+    let path = script("conditions.rhai", "let sum = 0;\nfor i in 0..6 {\n    sum += i * 2;\n}\nprint(sum);\n");
+
+    let requests = vec![
+        ("initialize", json!({ "adapterID": "rhai" })),
+        ("launch", json!({ "program": path })),
+        (
+            "setBreakpoints",
+            json!({ "source": { "path": path }, "breakpoints": [
+                { "line": 3, "condition": "i > 2", "hitCondition": "% 2" },
+                { "line": 5, "logMessage": "sum is {sum}, `{sum / 2}`" },
+                { "line": 1, "hitCondition": "twice" },
+            ] }),
+        ),
+        ("configurationDone", json!({})),
+        ("evaluate", json!({ "expression": "i" })),
+        ("continue", json!({ "threadId": 1 })),
+    ];
+
+    let messages = debug(&mut Engine::new(), &requests);
+
+    let initialize = responses(&messages, "initialize");
+    assert_eq!(initialize[0]["body"]["supportsConditionalBreakpoints"], true);
+    assert_eq!(initialize[0]["body"]["supportsHitConditionalBreakpoints"], true);
+    assert_eq!(initialize[0]["body"]["supportsLogPoints"], true);
+
+    let break_points = &responses(&messages, "setBreakpoints")[0]["body"]["breakpoints"];
+    assert_eq!(break_points[0]["verified"], true);
+    assert_eq!(break_points[1]["verified"], true);
+    assert_eq!(break_points[2]["verified"], false);
+
+    // The condition is met on 3, 4 and 5, and every second hit stops
+    assert_eq!(events(&messages, "stopped").len(), 1);
+    assert_eq!(responses(&messages, "evaluate")[0]["body"]["result"], "4");
+
+    // A log-point prints instead of stopping
+    assert_eq!(output(&messages), "sum is 30, `15`\n30\n");
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_dap_stepping() {
+    let path = script(
+        "stepping.rhai",
+        "
+fn double(x) {
+    let y = x * 2;
+    y
+}
+let a = double(1);
+let b = double(a);
+print(b);
+",
+    );
+
+    let mut requests = launch(&path, true, &[]);
+    requests.extend([
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("next", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("stepIn", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("stepIn", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("stepOut", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("setFunctionBreakpoints", json!({ "breakpoints": [{ "name": "print" }] })),
+        ("continue", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        ("continue", json!({ "threadId": 1 })),
+    ]);
+
+    let messages = debug(&mut Engine::new(), &requests);
+
+    let reasons = events(&messages, "stopped").iter().map(|e| e["reason"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(reasons, ["entry", "step", "step", "step", "step", "breakpoint"]);
+
+    let traces = responses(&messages, "stackTrace");
+    assert_eq!(frames(traces[0]), vec![("main".to_string(), 6)]);
+    assert_eq!(frames(traces[1]), vec![("main".to_string(), 7)]);
+    assert_eq!(frames(traces[2]), vec![("double".to_string(), 2), ("main".to_string(), 7)]);
+    assert_eq!(frames(traces[3]), vec![("double".to_string(), 3), ("main".to_string(), 7)]);
+    assert_eq!(frames(traces[4]), vec![("double".to_string(), 5), ("main".to_string(), 7)]);
+    assert_eq!(frames(traces[5]), vec![("main".to_string(), 8)]);
+
+    assert_eq!(output(&messages), "4\n");
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_dap_variables() {
+    let path = script("variables.rhai", "let x = [1, 2];\nlet m = #{ a: [true] };\nlet x = \"hello\";\nx.len();\n");
+
+    let mut requests = launch(&path, false, &[4]);
+    requests.extend([
+        ("scopes", json!({ "frameId": 0 })),
+        ("variables", json!({ "variablesReference": 1 })),
+        ("variables", json!({ "variablesReference": 2 })),
+        ("variables", json!({ "variablesReference": 3 })),
+        ("evaluate", json!({ "expression": "x.len() + m.a.len()", "context": "watch" })),
+        ("evaluate", json!({ "expression": "m", "context": "hover" })),
+        ("continue", json!({ "threadId": 1 })),
+        ("variables", json!({ "variablesReference": 1 })),
+    ]);
+
+    let messages = debug(&mut Engine::new(), &requests);
+
+    let vars = responses(&messages, "variables");
+    assert_eq!(variables(vars[0]), vec![("x".to_string(), "\"hello\"".to_string()), ("m".to_string(), "#{\"a\": [true]}".to_string())]);
+    assert_eq!(vars[0]["body"]["variables"][0]["type"], "string");
+    assert_eq!(vars[0]["body"]["variables"][1]["variablesReference"], 2);
+    assert_eq!(variables(vars[1]), vec![("a".to_string(), "[true]".to_string())]);
+    assert_eq!(variables(vars[2]), vec![("[0]".to_string(), "true".to_string())]);
+
+    let evaluate = responses(&messages, "evaluate");
+    assert_eq!(evaluate[0]["body"]["result"], "6");
+    assert!(evaluate[1]["body"]["variablesReference"].as_i64().unwrap() > 0);
+
+    // Variable references are cleared once the script resumes
+    assert_eq!(vars[3]["success"], false);
+}
+
+#[test]
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_function"))]
+fn test_dap_modules() {
+    let lib = script("dap_lib.rhai", "fn triple(x) {\n    x * 3\n}\nexport const SCALE = 3;\n");
+    let main = script("modules.rhai", "import \"dap_lib\" as lib;\nprint(lib::triple(lib::SCALE));\n");
+
+    let mut requests = launch(&main, false, &[]);
+    requests.insert(3, ("setBreakpoints", json!({ "source": { "path": lib }, "breakpoints": [{ "line": 2 }, { "line": 4 }] })));
+    requests.extend([("stackTrace", json!({ "threadId": 1 })), ("continue", json!({ "threadId": 1 })), ("stackTrace", json!({ "threadId": 1 })), ("continue", json!({ "threadId": 1 }))]);
+
+    let mut engine = Engine::new();
+    let mut resolver = rhai::module_resolvers::FileModuleResolver::new();
+    resolver.enable_cache(false);
+    engine.set_module_resolver(resolver);
+
+    let messages = debug(&mut engine, &requests);
+
+    assert_eq!(stopped_lines(&messages), [4, 2]);
+
+    let traces = responses(&messages, "stackTrace");
+    assert_eq!(traces[0]["body"]["stackFrames"][0]["source"]["path"], lib.to_string_lossy().as_ref());
+    assert_eq!(frames(traces[1])[0].0, "triple");
+    assert_eq!(traces[1]["body"]["stackFrames"][0]["source"]["path"], lib.to_string_lossy().as_ref());
+    assert_eq!(traces[1]["body"]["stackFrames"][1]["source"]["path"], main.to_string_lossy().as_ref());
+
+    assert_eq!(output(&messages), "9\n");
+}
+
+#[test]
+fn test_dap_disconnect() {
+    let path = script("disconnect.rhai", "let x = 1;\nprint(x);\n");
+
+    let mut requests = launch(&path, true, &[]);
+    requests.push(("disconnect", json!({})));
+
+    let messages = debug(&mut Engine::new(), &requests);
+
+    assert_eq!(events(&messages, "stopped").len(), 1);
+    assert_eq!(responses(&messages, "disconnect")[0]["success"], true);
+    assert!(events(&messages, "exited").is_empty());
+    assert_eq!(output(&messages), "");
+
+    // End of input while stopped
+    let messages = debug(&mut Engine::new(), &launch(&path, true, &[]));
+    assert_eq!(events(&messages, "stopped").len(), 1);
+    assert!(events(&messages, "exited").is_empty());
+}
@@ -213,6 +213,10 @@ fn test_max_array_size() {
         EvalAltResult::ErrorDataTooLarge(..)
     ));
 
+    assert!(matches!(*engine.run("[x for x in 0..20]").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert!(matches!(*engine.run("let x = [1,2,3]; [x for i in 0..4]").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert_eq!(engine.eval::<Array>("[x for x in 0..20 if x < 5]").unwrap().len(), 5);
+
     engine.set_max_array_size(0);
 
     assert_eq!(
@@ -307,6 +311,24 @@ fn test_max_map_size() {
         EvalAltResult::ErrorDataTooLarge(..)
     ));
 
+    // A replaced value no longer counts towards the size
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<Map>(r#"#{ "k": [x] for x in 0..100 }"#).unwrap().len(), 1);
+
+    #[cfg(not(feature = "no_index"))]
+    assert!(matches!(
+        *engine
+            .run(
+                "
+                    let keys = [];
+                    for x in 0..20 { keys.push(`k${x}`); }
+                    #{ k: 0 for k in keys }
+                "
+            )
+            .unwrap_err(),
+        EvalAltResult::ErrorDataTooLarge(..)
+    ));
+
     engine.set_max_map_size(0);
 
     assert_eq!(
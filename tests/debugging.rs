@@ -71,3 +71,161 @@ fn test_debugger_state() {
 
     engine.run("let x = 42;").unwrap();
 }
+
+#[cfg(not(feature = "no_position"))]
+#[test]
+fn test_debugger_conditional_break_points() {
+    use rhai::debugger::{BreakPoint, BreakPointExpr, DebuggerCommand, DebuggerEvent, HitCondition};
+    use rhai::Position;
+    use std::sync::{Arc, Mutex};
+
+    fn run(condition: Option<&str>, hit_condition: Option<HitCondition>, log_message: Option<&str>) -> (Vec<INT>, Vec<String>) {
+        let mut engine = Engine::new();
+        let stops = Arc::new(Mutex::new(Vec::new()));
+        let logs = Arc::new(Mutex::new(Vec::new()));
+
+        let bp = BreakPoint::Conditional {
+            break_point: BreakPoint::AtPosition {
+                source: None,
+                pos: Position::new(4, 0),
+                enabled: true,
+            }
+            .into(),
+            condition: condition.map(|c| BreakPointExpr::new(&engine, c).unwrap()),
+            hit_condition,
+            log_message: log_message.map(|m| BreakPointExpr::new_log_message(&engine, m).unwrap()),
+            hits: 0,
+        };
+
+        let log = logs.clone();
+        engine.on_print(move |s| log.lock().unwrap().push(s.to_string()));
+        let log = logs.clone();
+        engine.on_debug(move |s, _, _| log.lock().unwrap().push(s.to_string()));
+
+        let stop = stops.clone();
+        engine.register_debugger(
+            move |_, mut dbg| {
+                dbg.break_points_mut().push(bp.clone());
+                dbg
+            },
+            move |context, event, _, _, _| {
+                if let DebuggerEvent::BreakPoint(..) = event {
+                    stop.lock().unwrap().push(context.scope().get_value::<INT>("i").unwrap());
+                }
+                Ok(DebuggerCommand::Continue)
+            },
+        );
+
+        engine
+            .run(
+                "
+                    let total = 0;
+                    for i in 0..10 {
+                        total += i * 2;
+                    }
+                ",
+            )
+            .unwrap();
+
+        let stops = stops.lock().unwrap().clone();
+        let logs = logs.lock().unwrap().clone();
+        (stops, logs)
+    }
+
+    // Once per line and loop iteration
+    assert_eq!(run(None, None, None).0, (0..10).collect::<Vec<_>>());
+    assert_eq!(run(Some("i > 5"), None, None).0, vec![6, 7, 8, 9]);
+    assert_eq!(run(Some("i > 5"), Some(HitCondition::Multiple(2)), None).0, vec![7, 9]);
+    assert_eq!(run(None, Some(HitCondition::Equals(3)), None).0, vec![2]);
+    assert_eq!(run(None, Some(HitCondition::AtLeast(9)), None).0, vec![8, 9]);
+
+    // Conditions that fail to evaluate are not met
+    let (stops, logs) = run(Some("i"), None, None);
+    assert!(stops.is_empty());
+    assert_eq!(logs.len(), 10);
+    assert_eq!(logs[0], "Break-point @ 4 if i: Output type incorrect: i64 (expecting bool) (line 4, position 31)");
+    let (stops, logs) = run(Some("i > 8 || j"), None, None);
+    assert_eq!(stops, vec![9]);
+    assert_eq!(logs.len(), 9);
+
+    let (stops, logs) = run(Some("i < 3"), None, Some("i = ${i}, total = ${total}"));
+    assert!(stops.is_empty());
+    assert_eq!(logs, vec!["i = 0, total = 0", "i = 1, total = 0", "i = 2, total = 2"]);
+
+    assert_eq!("3".parse(), Ok(HitCondition::Equals(3)));
+    assert_eq!(">= 2".parse(), Ok(HitCondition::AtLeast(2)));
+    assert_eq!("%4".parse(), Ok(HitCondition::Multiple(4)));
+    assert!("> 4".parse::<HitCondition>().is_err());
+
+    // Conditions are compiled when the break-point is set
+    let mut engine = Engine::new();
+    assert!(BreakPointExpr::new(&engine, "i >").is_err());
+    assert!(BreakPointExpr::new_log_message(&engine, "${i").is_err());
+    engine.set_strict_variables(true);
+    assert_eq!(BreakPointExpr::new(&engine, "i > 5").unwrap().text(), "i > 5");
+}
+
+#[test]
+fn test_debugger_watch_points() {
+    use rhai::debugger::{BreakPoint, DebuggerCommand, DebuggerEvent};
+    use std::sync::{Arc, Mutex};
+
+    let mut engine = Engine::new();
+    let stops = Arc::new(Mutex::new(Vec::new()));
+
+    let stop = stops.clone();
+    engine.register_debugger(
+        |_, mut dbg| {
+            dbg.break_points_mut().push(BreakPoint::AtVariableChange { name: "x".into(), value: None, enabled: true });
+            dbg
+        },
+        move |context, event, _, _, _| {
+            if let DebuggerEvent::BreakPoint(..) = event {
+                stop.lock().unwrap().push(context.scope().get_value::<INT>("x").unwrap());
+            }
+            Ok(DebuggerCommand::Continue)
+        },
+    );
+
+    engine
+        .run(
+            "
+                let x = 1;
+                let y = x + 1;
+                x += y;
+                x = 3;
+                y = 0;
+                x *= 2;
+                y = 1;
+            ",
+        )
+        .unwrap();
+
+    assert_eq!(*stops.lock().unwrap(), vec![3, 6]);
+
+    // Values are compared structurally
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "no_object"))]
+    {
+        let mut engine = Engine::new();
+        let stops = Arc::new(Mutex::new(0));
+
+        let stop = stops.clone();
+        engine.register_debugger(
+            |_, mut dbg| {
+                dbg.break_points_mut().push(BreakPoint::AtVariableChange { name: "x".into(), value: None, enabled: true });
+                dbg
+            },
+            move |_, event, _, _, _| {
+                if let DebuggerEvent::BreakPoint(..) = event {
+                    *stop.lock().unwrap() += 1;
+                }
+                Ok(DebuggerCommand::Continue)
+            },
+        );
+
+        engine.run("let x = [1, #{ a: 2 }]; x[1].a = 2; x = [1, #{ a: 2 }]; x[1].a = 3; x.push(4); let y = x;").unwrap();
+
+        assert_eq!(*stops.lock().unwrap(), 2);
+    }
+}
@@ -0,0 +1,143 @@
+use rhai::{DiagnosticType, Engine, Severity};
+
+/// Compile a script, returning the types of all diagnostics.
+fn diagnostics(engine: &Engine, script: &str) -> Vec<DiagnosticType> {
+    let (_, diagnostics) = engine.compile_with_diagnostics(script).unwrap();
+    diagnostics.into_iter().map(|d| d.0).collect()
+}
+
+#[test]
+fn test_diagnostics_unused() {
+    let engine = Engine::new();
+
+    assert_eq!(diagnostics(&engine, "let x = 42; x"), vec![]);
+    assert_eq!(diagnostics(&engine, "let x = 42; 0"), vec![DiagnosticType::UnusedVariable("x".into())]);
+    assert_eq!(diagnostics(&engine, "let x = 42; { let y = x; }"), vec![DiagnosticType::UnusedVariable("y".into())]);
+    assert_eq!(diagnostics(&engine, "let x = 1; x = 2;"), vec![DiagnosticType::UnusedVariable("x".into())]);
+    assert_eq!(diagnostics(&engine, "let x = 1; x += 2;"), vec![]);
+    assert_eq!(diagnostics(&engine, "for (x, i) in 0..10 { print(x); }"), vec![DiagnosticType::UnusedVariable("i".into())]);
+    assert_eq!(diagnostics(&engine, r#"let x = 1; eval("x")"#), vec![]);
+
+    #[cfg(not(feature = "no_position"))]
+    {
+        let (_, d) = engine.compile_with_diagnostics("let x = 1;\n{\n    let y = 2;\n}\nx").unwrap();
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].code(), "unused_variable");
+        assert_eq!(d[0].severity(), Severity::Warning);
+        assert_eq!(d[0].position(), rhai::Position::new(3, 9));
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(diagnostics(&engine, "fn foo(x, y) { x } foo(1, 2)"), vec![DiagnosticType::UnusedParameter("y".into())]);
+        #[cfg(not(feature = "no_module"))]
+        assert_eq!(diagnostics(&engine, "fn foo() { global::X } const X = 42; foo()"), vec![]);
+        assert_eq!(diagnostics(&engine, "let f = |x| 42; call(f, 1)"), vec![DiagnosticType::UnusedParameter("x".into())]);
+
+        #[cfg(not(feature = "no_closure"))]
+        assert_eq!(diagnostics(&engine, "let x = 1; let f = |y| x + y; call(f, 1)"), vec![]);
+    }
+}
+
+#[test]
+fn test_diagnostics_shadowing() {
+    let engine = Engine::new();
+
+    let (_, d) = engine.compile_with_diagnostics("let x = 1; let x = x + 1; x").unwrap();
+    assert_eq!(d.len(), 1);
+    assert_eq!(d[0].0, DiagnosticType::ShadowedVariable("x".into()));
+    assert_eq!(d[0].severity(), Severity::Hint);
+
+    assert_eq!(diagnostics(&engine, "let x = 1; { let y = x; y }"), vec![]);
+    assert_eq!(diagnostics(&engine, "let x = 1; for x in 0..x { print(x) }"), vec![DiagnosticType::ShadowedVariable("x".into())]);
+}
+
+#[test]
+fn test_diagnostics_unreachable() {
+    let engine = Engine::new();
+
+    assert_eq!(diagnostics(&engine, "let x = 1; if x > 0 { return; print(x); x += 1; }"), vec![DiagnosticType::UnreachableCode]);
+    assert_eq!(diagnostics(&engine, "loop { break; print(1); }"), vec![DiagnosticType::UnreachableCode]);
+    assert_eq!(diagnostics(&engine, r#"throw "error"; 42"#), vec![DiagnosticType::UnreachableCode]);
+}
+
+#[test]
+fn test_diagnostics_constant_condition() {
+    let engine = Engine::new();
+
+    assert_eq!(diagnostics(&engine, "if true { print(1) }"), vec![DiagnosticType::ConstantCondition(true)]);
+    assert_eq!(diagnostics(&engine, "while false { print(1) }"), vec![DiagnosticType::ConstantCondition(false)]);
+    assert_eq!(diagnostics(&engine, "loop { break; }"), vec![]);
+}
+
+#[test]
+fn test_diagnostics_unknown_function() {
+    let mut engine = Engine::new();
+
+    engine.register_fn("double", |x: i64| x * 2);
+
+    assert_eq!(diagnostics(&engine, "print(double(21)); debug(type_of(42))"), vec![]);
+    assert_eq!(diagnostics(&engine, "double(1, 2)"), vec![DiagnosticType::UnknownFunction("double".into(), 2)]);
+    assert_eq!(diagnostics(&engine, "triple(1)"), vec![DiagnosticType::UnknownFunction("triple".into(), 1)]);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(diagnostics(&engine, "fn triple(x) { x * 3 } triple(1)"), vec![]);
+        assert_eq!(diagnostics(&engine, "let f = Fn(\"double\"); f(1)"), vec![]);
+    }
+
+    #[cfg(not(feature = "no_position"))]
+    {
+        let (_, d) = engine.compile_with_diagnostics("let x = 1;\nfoo(x)").unwrap();
+        assert_eq!(d[0].position(), rhai::Position::new(2, 1));
+        assert_eq!(d[0].to_string(), "warning: Function 'foo' with 1 parameter is not defined [unknown_function] (line 2, position 1)");
+    }
+}
+
+#[test]
+fn test_diagnostics_fn_name_string() {
+    let engine = Engine::new();
+
+    assert_eq!(diagnostics(&engine, r#"call("abs", -1)"#), vec![DiagnosticType::FnNameString("abs".into())]);
+    assert_eq!(diagnostics(&engine, r#"curry("abs", -1)"#), vec![DiagnosticType::FnNameString("abs".into())]);
+    assert_eq!(diagnostics(&engine, r#"call(Fn("abs"), -1)"#), vec![]);
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_index"))]
+    {
+        let (ast, d) = engine.compile_with_diagnostics(r#"fn square(x) { x * x } map([1, 2, 3], "square")"#).unwrap();
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].code(), "fn_name_string");
+        assert_eq!(d[0].to_string(), r#"warning: Function name 'square' is passed as a string; use Fn("square") instead [fn_name_string] (line 1, position 39)"#);
+
+        assert_eq!(engine.eval_ast::<rhai::Array>(&ast).unwrap().len(), 3);
+        assert_eq!(diagnostics(&engine, r#"fn square(x) { x * x } [1, 2, 3].map("square")"#), vec![DiagnosticType::FnNameString("square".into())]);
+        assert_eq!(diagnostics(&engine, r#"fn square(x) { x * x } map([1, 2, 3], Fn("square"))"#), vec![]);
+    }
+}
+
+#[test]
+fn test_diagnostics_deprecated() {
+    let mut engine = Engine::new();
+
+    engine.deprecate_symbol("while", "use 'loop' instead").deprecate_symbol("+=", "");
+    assert!(engine.is_symbol_deprecated("while"));
+    assert!(!engine.is_symbol_deprecated("loop"));
+
+    let (ast, d) = engine
+        .compile_with_diagnostics(
+            "let x = 0;
+while x < 10 { x += 1; }
+x",
+        )
+        .unwrap();
+    assert_eq!(d.len(), 2);
+    assert_eq!(d[0].code(), "deprecated");
+    assert_eq!(d[0].to_string(), "warning: 'while' is deprecated: use 'loop' instead [deprecated] (line 2, position 1)");
+    assert_eq!(d[1].to_string(), "warning: '+=' is deprecated [deprecated] (line 2, position 18)");
+    assert_eq!(engine.eval_ast::<rhai::INT>(&ast).unwrap(), 10);
+
+    // Strings and comments are not checked
+    assert_eq!(diagnostics(&engine, "// while\n\"while\""), vec![]);
+    assert!(engine.compile("while false {}").is_ok());
+}
@@ -0,0 +1,90 @@
+#![cfg(not(feature = "no_position"))]
+use rhai::{Engine, FormatOptions};
+
+/// Format a script, checking that formatting is idempotent and that the script is unchanged.
+fn format(engine: &Engine, script: &str, options: FormatOptions) -> String {
+    let output = engine.format_script(script, options).unwrap();
+
+    assert_eq!(engine.format_script(&output, options).unwrap(), output);
+    assert_eq!(engine.compact_script(&output).unwrap(), engine.compact_script(script).unwrap());
+
+    output
+}
+
+#[test]
+fn test_format() {
+    let engine = Engine::new();
+
+    assert_eq!(format(&engine, "", FormatOptions::new()), "");
+    assert_eq!(format(&engine, "let x=40+2;x+=-1;x", FormatOptions::new()), "let x = 40 + 2;\nx += -1;\nx\n");
+    assert_eq!(
+        format(&engine, "let x = if x>0 {1} else {-1}; while x<10 { x+=1; if x==5 {break;} }", FormatOptions::new()),
+        "let x = if x > 0 { 1 } else { -1 };\nwhile x < 10 {\n    x += 1;\n    if x == 5 {\n        break;\n    }\n}\n"
+    );
+    assert_eq!(format(&engine, "let s = `x = ${ x+1 }!`; print ( s )", FormatOptions::new()), "let s = `x = ${ x+1 }!`;\nprint(s)\n");
+    assert_eq!(format(&engine, "do { x -= 1 } until x<0; loop { x = !( x in y ) }", FormatOptions::new()), "do {\n    x -= 1\n} until x < 0;\nloop {\n    x = !(x in y)\n}\n");
+
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(format(&engine, "let x = #{a:[1,2,3], b:()};x.a[0]", FormatOptions::new()), "let x = #{ a: [1, 2, 3], b: () };\nx.a[0]\n");
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        format(&engine, "fn add(x,y){x+y} let f=|x|x*2; switch add(1,2) { 3 => call(f, 3), _ => { 0 } }", FormatOptions::new()),
+        "fn add(x, y) {\n    x + y\n}\nlet f = |x| x * 2;\nswitch add(1, 2) {\n    3 => call(f, 3),\n    _ => { 0 }\n}\n"
+    );
+
+    assert!(engine.format_script("let x = ;", FormatOptions::new()).is_err());
+}
+
+#[test]
+fn test_format_comments() {
+    let engine = Engine::new();
+
+    let script = "
+        // header
+
+
+        let x = 42;    // the answer
+        /* block */ let y = x;
+
+        // leading
+        if x > y { // inside
+            x /* inline */ + y
+            // at end
+        }
+        // trailing
+    ";
+
+    assert_eq!(
+        format(&engine, script, FormatOptions::new()),
+        "// header\n\nlet x = 42; // the answer\n/* block */\nlet y = x;\n\n// leading\nif x > y { // inside\n    x /* inline */ + y\n    // at end\n}\n// trailing\n"
+    );
+}
+
+#[test]
+fn test_format_options() {
+    let engine = Engine::new();
+
+    let script = "if true { foo(the_first_argument, the_second_argument) }";
+
+    assert_eq!(format(&engine, script, FormatOptions::new().indent_width(2)), "if true {\n  foo(the_first_argument, the_second_argument)\n}\n");
+    assert_eq!(format(&engine, script, FormatOptions::new().indent_width(2).line_width(40)), "if true {\n  foo(\n    the_first_argument,\n    the_second_argument\n  )\n}\n");
+}
+
+#[cfg(not(feature = "no_custom_syntax"))]
+#[test]
+fn test_format_custom_syntax() {
+    let mut engine = Engine::new();
+
+    engine.register_custom_operator("foo", 160).unwrap();
+    engine.register_custom_operator("#", 160).unwrap();
+    engine.register_custom_syntax(["iff", "$expr$", "?", "$expr$", ":", "$expr$"], false, |_, _| Ok(().into())).unwrap();
+    engine
+        .register_custom_syntax(["exec", "[", "$ident$", "]", "->", "$block$", "while", "$expr$"], true, |_, _| Ok(().into()))
+        .unwrap();
+
+    assert_eq!(format(&engine, "let x = 1 foo 2#3;", FormatOptions::new()), "let x = 1 foo 2 # 3;\n");
+    assert_eq!(format(&engine, "let x = iff x>1?2:3;", FormatOptions::new()), "let x = iff x > 1 ? 2 : 3;\n");
+    assert_eq!(format(&engine, "exec [x] -> { x += 1; } while x < 42; x", FormatOptions::new()), "exec [x] -> {\n    x += 1;\n} while x < 42;\nx\n");
+}
@@ -0,0 +1,205 @@
+#![cfg(not(feature = "no_function"))]
+use rhai::{Engine, EvalAltResult, Generator, ParseErrorType, INT};
+
+#[test]
+fn test_generators() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>("fn count(n) { let x = 0; while x < n { x += 1; yield x; } } let sum = 0; for x in count(5) { sum += x; } sum")
+            .unwrap(),
+        15
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>("fn evens(n) { for x in 0..n { if x % 2 == 0 { yield x; } } } let sum = 0; for x in evens(10) { sum += x; } sum")
+            .unwrap(),
+        20
+    );
+    assert_eq!(engine.eval::<String>("fn one() { yield 1; } type_of(one())").unwrap(), "generator");
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn three() { yield 1; yield 2; yield 3; } let g = three(); g.next() * 10 + g.next()").unwrap(), 12);
+        assert_eq!(engine.eval::<()>("fn one() { yield 1; } let g = one(); g.next(); g.next()").unwrap(), ());
+        assert_eq!(
+            engine
+                .eval::<bool>("fn one() { yield 1; } let g = one(); g.next(); let before = g.is_done; g.next(); !before && g.is_done")
+                .unwrap(),
+            true
+        );
+
+        // Local variables survive each `yield`
+        assert_eq!(
+            engine
+                .eval::<INT>("fn fib() { let a = 0; let b = 1; loop { yield a; let t = a + b; a = b; b = t; } } let g = fib(); for i in 0..9 { g.next(); } g.next()")
+                .unwrap(),
+            34
+        );
+    }
+
+    // Nested generators
+    assert_eq!(
+        engine
+            .eval::<INT>("fn inner(n) { for x in 0..n { yield x; } } fn outer() { for n in 1..=3 { for x in inner(n) { yield x; } } } let sum = 0; for x in outer() { sum += x; } sum")
+            .unwrap(),
+        4
+    );
+
+    // `break`, `continue` and `return`
+    assert_eq!(
+        engine
+            .eval::<INT>("fn g() { let x = 0; loop { x += 1; if x == 2 { continue; } if x > 4 { break; } yield x; } yield 100; } let sum = 0; for x in g() { sum += x; } sum")
+            .unwrap(),
+        108
+    );
+    assert_eq!(engine.eval::<INT>("fn g() { yield 1; return; yield 2; } let sum = 0; for x in g() { sum += x; } sum").unwrap(), 1);
+    assert_eq!(
+        engine
+            .eval::<INT>("fn g() { yield 1; yield 2; yield 3; } let sum = 0; for x in g() { if x == 2 { break; } sum += x; } sum")
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>("fn g() { let x = 0; do { x += 1; if x == 1 { continue; } yield x; } while x < 3; } let sum = 0; for x in g() { sum += x; } sum")
+            .unwrap(),
+        5
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>("fn g() { 'outer: for x in 0..5 { let y = 0; while true { y += 1; if y > x { continue 'outer; } if x == 4 { break 'outer; } yield x * 10 + y; } } yield 1000; } let sum = 0; for x in g() { sum += x; } sum")
+            .unwrap(),
+        1150
+    );
+
+    // Method-call style
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine
+            .eval::<INT>("fn up_to(n) { let x = this; while x <= n { yield x; x += 1; } } let sum = 0; for x in 3.up_to(5) { sum += x; } sum")
+            .unwrap(),
+        12
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<String>("fn squares(n) { for x in 1..=n { yield x * x; } } to_string(squares(4).to_array())").unwrap(), "[1, 4, 9, 16]");
+        assert_eq!(
+            engine
+                .eval::<String>("fn naturals() { let x = 0; loop { x += 1; yield x; } } let g = naturals(); g.take(3); to_string(g.take(2))")
+                .unwrap(),
+            "[4, 5]"
+        );
+        assert_eq!(engine.eval::<String>("fn one() { yield 1; } to_string(one().take(5))").unwrap(), "[1]");
+        assert_eq!(engine.eval::<String>("fn g() { yield 1; yield 2; } to_string([0, ...g(), 3])").unwrap(), "[0, 1, 2, 3]");
+        assert_eq!(engine.eval::<String>("fn g() { for x in [1, 2] { yield x; } } to_string(g().to_array())").unwrap(), "[1, 2]");
+    }
+
+    // Clones share the same running state
+    let g = engine.eval::<Generator>("fn g() { yield 1; } g()").unwrap();
+    assert_eq!(g.fn_name(), "g");
+    assert!(!g.is_done());
+}
+
+#[cfg(not(feature = "no_optimize"))]
+#[test]
+fn test_generators_optimized() {
+    let mut engine = Engine::new();
+
+    let script = "
+        fn g(n) {
+            let x = 0;
+            yield -1;
+            if true {
+                let y = 2;
+                while x < n {
+                    x += 1;
+                    { if x % y == 0 { yield x; } }
+                }
+            }
+            do { yield 100; } while false;
+        }
+        let sum = 0;
+        for x in g(5) { sum += x; }
+        sum
+    ";
+
+    for level in [rhai::OptimizationLevel::None, rhai::OptimizationLevel::Simple, rhai::OptimizationLevel::Full] {
+        engine.set_optimization_level(level);
+        assert_eq!(engine.eval::<INT>(script).unwrap(), 105, "{level:?}");
+
+        let ast = engine.compile(script).unwrap();
+        let ast = engine.optimize_ast(&rhai::Scope::new(), ast, level);
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 105, "{level:?}");
+    }
+}
+
+#[cfg(feature = "internals")]
+#[test]
+fn test_generators_cached_yield() {
+    let engine = Engine::new();
+
+    let ast = engine.compile("fn g(n) { let x = n; for i in 0..n { if i > 1 { yield i; } else { x += i; } } x }").unwrap();
+    let fn_def = ast.iter_fn_def().next().unwrap();
+    let body = &fn_def.body;
+
+    assert!(fn_def.is_generator);
+    assert!(body.has_yield());
+    assert_eq!(body.statements().iter().map(|stmt| stmt.has_yield()).collect::<Vec<_>>(), [false, true, false]);
+
+    let ast = engine.compile("fn f(n) { for i in 0..n { if i > 1 { i } } }").unwrap();
+    assert!(!ast.iter_fn_def().next().unwrap().body.has_yield());
+}
+
+#[test]
+fn test_generators_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(engine.compile("yield 1;").unwrap_err().err_type(), ParseErrorType::WrongYield));
+    assert!(matches!(engine.compile("fn g(x) { switch x { 1 => yield 1 } }").unwrap_err().err_type(), ParseErrorType::WrongYield));
+    assert!(matches!(engine.compile("fn g() { try { yield 1; } catch { } }").unwrap_err().err_type(), ParseErrorType::WrongYield));
+    assert!(matches!(engine.compile("fn g() { let x = { yield 1; 2 }; }").unwrap_err().err_type(), ParseErrorType::WrongYield));
+    assert!(matches!(engine.compile("fn g() { if 0 < { yield 1; 2 } { } }").unwrap_err().err_type(), ParseErrorType::WrongYield));
+
+    assert!(matches!(
+        *engine.eval::<INT>("fn g() { yield 1; throw 42; } let sum = 0; for x in g() { sum += x; } sum").unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(fn_name, ..) if fn_name == "g"
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.eval::<bool>("fn g() { throw 42; yield 1; } let g = g(); try { g.next(); } catch { } g.is_done").unwrap());
+
+    #[cfg(not(feature = "no_closure"))]
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine.eval::<INT>("let h; let f = || h.next(); fn rec(f) { yield f.call(); } let g = rec(f); h = g; g.next()").unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(fn_name, .., err, _) if fn_name == "rec" && matches!(
+            &*err,
+            EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(&**err, EvalAltResult::ErrorDataRace(name, ..) if name == "rec")
+        )
+    ));
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_generators_limits() {
+    let mut engine = Engine::new();
+    engine.set_max_operations(500);
+
+    assert!(matches!(*engine.run("fn forever() { loop { yield 1; } } for x in forever() { }").unwrap_err(), EvalAltResult::ErrorTooManyOperations(..)));
+
+    engine.set_max_operations(0);
+    engine.set_max_call_levels(10);
+
+    assert!(matches!(*engine.run("fn deep(n) { for x in deep(n + 1) { yield x; } yield n; } for x in deep(0) { }").unwrap_err(), EvalAltResult::ErrorStackOverflow(..)));
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        engine.set_max_array_size(10);
+
+        assert!(matches!(*engine.run("fn forever() { loop { yield 1; } } to_array(forever())").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    }
+}
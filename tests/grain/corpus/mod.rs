@@ -178,6 +178,11 @@ pub fn applies_to_this_build(name: &str) -> bool {
     if matches!(name, "decimal_numbers" | "decimal_arithmetic") {
         return false;
     }
+    // Without `bigint` there is no `BigInt` literal to parse.
+    #[cfg(not(feature = "bigint"))]
+    if matches!(name, "bigint_numbers" | "bigint_arithmetic") {
+        return false;
+    }
     // `no_function` removes `fn` and the anonymous form with it, so a case that
     // declares one, points at one, or has a `this` to be a method of does not
     // parse. The prefixes carry the families; the rest reach for a function
@@ -204,6 +209,9 @@ pub fn applies_to_this_build(name: &str) -> bool {
                 | "throw_from_a_function_leaves_the_caller_top_level_alone"
                 | "throw_in_fn"
                 | "try_around_a_compiled_call"
+                | "defer_error_replaces_the_thrown_one"
+                | "defer_runs_in_reverse"
+                | "finally_on_return"
                 | "try_catch_does_not_swallow_return"
                 | "try_does_not_catch_return"
                 | "type_of_a_pointer"
@@ -220,6 +228,7 @@ pub fn applies_to_this_build(name: &str) -> bool {
     if matches!(
         name,
         "array_literal"
+            | "array_comprehension_filtered"
             | "array_methods"
             | "bitfield_assign"
             | "call_style_argument_replaces_the_receiver"
@@ -243,6 +252,7 @@ pub fn applies_to_this_build(name: &str) -> bool {
             | "empty_literals_nested_in_computed_ones"
             | "empty_map_nested_in_a_computed_map"
             | "error_array_bounds"
+            | "error_comprehension_condition_not_bool"
             | "error_const_root_method_step"
             | "error_host_index_bounds"
             | "error_index_into_an_unindexable_step"
@@ -270,6 +280,7 @@ pub fn applies_to_this_build(name: &str) -> bool {
             | "index_expression_reads_the_root"
             | "interpolation_of_containers"
             | "is_shared_after_capture"
+            | "map_comprehension_with_counter"
             | "map_computed_in_array"
             | "map_read_absent_through_a_chain"
             | "map_read_of_absent_key_does_not_create_it"
@@ -341,6 +352,7 @@ pub fn applies_to_this_build(name: &str) -> bool {
                 | "error_property_on_a_variable"
                 | "error_this_is_not_inherited"
                 | "fn_mutating_method"
+                | "fn_operator_on_a_host_type"
                 | "fn_ptr_call"
                 | "fn_ptr_curried"
                 | "fn_ptr_from_dynamic_name"
@@ -373,6 +385,8 @@ pub const CASES: &[Case] = &[
     case("mixed_numeric", "1 + 2.5"),
     case("decimal_numbers", "let a = parse_decimal(\"42\")"),
     case("decimal_arithmetic", "let a = parse_decimal(\"42\"); let b = 1; a + b"),
+    case("bigint_numbers", "let a = 123456789012345678901234567890n; a"),
+    case("bigint_arithmetic", "let a = 2n; let b = 100; let c = a ** b; c -= 1; c % 1000000007 < b"),
     case("comparison_chain", "let a = 5; a > 1 && a < 10 || a == 5"),
     case("bitwise", "let a = 0b1010; (a & 0b0110) | (a ^ 0b1111) << 2"),
     case("string_ops", r#"let s = "hello"; s + " " + "world" + s.len"#),
@@ -406,6 +420,10 @@ pub const CASES: &[Case] = &[
     // literal is observable.
     case("map_computed_order", r#"let log = ""; fn note(s, c) { s + c } let m = #{ a: note("", "x"), b: note("", "y") }; m.a + m.b"#),
     case("nested_containers", r#"let m = #{ xs: [1, 2, #{ y: 3 }] }; m.xs[2].y"#),
+    // A `for` loop whose body feeds a container rather than a block.
+    case("array_comprehension_filtered", "let k = 10; [x * k for x in 0..6 if x % 2 == 0]"),
+    case("map_comprehension_with_counter", r#"let k = 10; #{ s: i * k for (s, i) in ["a", "b", "a"] }"#),
+    case("error_comprehension_condition_not_bool", "[x for x in 0..3 if x]"),
     // --- control flow -----------------------------------------------------
     case("if_else", "let a = 5; if a > 3 { \"big\" } else { \"small\" }"),
     case("while_loop", "let i = 0; let s = 0; while i < 5 { s += i; i += 1; } s"),
@@ -430,6 +448,13 @@ pub const CASES: &[Case] = &[
     // A `break` out of a `while` nested in a `for` must drop nothing, and out
     // of the `for` must drop one — the two are easy to get the wrong way round.
     case("for_around_while_break", "let s = 0; for i in 0..3 { let j = 0; while true { j += 1; if j > 2 { break; } s += 1; } } s"),
+    // A labeled jump leaves every loop inside the one it names: each `for`
+    // on the way out drops its iterator, and a `continue` keeps only the
+    // target's own.
+    case("labeled_break_out_of_nested_fors", "let s = 0; 'outer: for i in 0..5 { for j in 0..5 { if i * j == 6 { break 'outer; } s += 1; } } s"),
+    case("labeled_continue_from_a_while", "let s = 0; 'rows: for i in 0..4 { let j = 0; while true { j += 1; if j > i { continue 'rows; } s += j; } } s"),
+    case("labeled_break_value", "'outer: loop { for i in 0..10 { for j in 0..10 { if i + j == 7 { break 'outer i * 10 + j; } } } }"),
+    case("labeled_break_through_a_finally", "let s = 0; 'outer: for i in 0..3 { for j in 0..3 { try { if j == 1 { break 'outer; } } finally { s += 1; } } } s"),
     // Iterating a shared cell walks a snapshot, because Rhai flattens the
     // iterable before asking for an iterator (`eval/stmt.rs:677`).
     case("for_over_captured_array", "let a = [1, 2, 3]; { let f = || a; } let s = 0; for x in a { s += x; } s"),
@@ -501,6 +526,11 @@ pub const CASES: &[Case] = &[
     // A script method mutating its receiver: `this` is bound by reference, so
     // the write has to land back in the caller's variable.
     case("fn_mutating_method", "fn double() { this *= 2; } let v = 21; v.double(); v"),
+    // A script-defined operator runs for a custom type, including through
+    // `op=`, but never for a standard type — `-a.level` inside it is the
+    // built-in, not a recursive call.
+    case("fn_operator_on_a_host_type", "fn +(a, b) { widget(a.level + b.level) } fn -(a) { widget(-a.level) } let w = widget(1) + widget(2); w += widget(3); (-w).level"),
+    case("fn_operator_does_not_overload_standard_types", "fn +(a, b) { a * b } fn -(a) { 0 } let x = 3; x += 4; -(x + 4)"),
     case("top_level_return", "let x = 5; if x > 0 { return x * 2; } 0"),
     // A script function this compiler could not lower still runs — Rhai finds
     // it in `global.lib` — and it must run in a scope of its own. Handing it
@@ -757,6 +787,21 @@ pub const CASES: &[Case] = &[
     case("try_around_a_compiled_call", "fn boom() { throw 7; } try { boom(); } catch (e) { e }"),
     // `return` is a pseudo error and must pass straight through a handler.
     case("try_does_not_catch_return", "fn f() { try { return 1; } catch { 2 } } f()"),
+    // Several clauses share one handler and test in turn; the error is bound
+    // once, and a clause that does not match leaves no variable behind.
+    case("catch_clauses_by_type", r#"let r = ""; try { throw "x"; } catch (e: int) { r = "int"; } catch (e: string) { r = e; } r"#),
+    case("catch_clause_guard", "let r = 0; try { throw 42; } catch (e) if e > 100 { r = 1; } catch (e) if e > 10 { r = e; } r"),
+    case("catch_clause_no_match_rethrows", "let r = 0; try { try { throw 3; } catch (e: string) { r = 1; } } catch (e) { r += e; } r"),
+    // `finally` runs inline on every way out of the block: falling through,
+    // an error, and a jump, which runs it before leaving any outer handler.
+    case("finally_after_body", "let s = 0; try { s += 1; } finally { s *= 10; } s"),
+    case("finally_after_catch", "let s = 0; try { throw 2; } catch (e) { s += e; } finally { s *= 10; } s"),
+    case("finally_on_error", "let s = 0; try { try { throw 2; } finally { s += 1; } } catch (e) { s += e * 10; } s"),
+    case("finally_on_break_and_continue", "let n = 0; for i in 0..10 { try { if i % 2 == 0 { continue; } if i > 6 { break; } } finally { n += 1; } } n"),
+    case("finally_nested_in_order", "let s = 0; try { try { s = 1; } finally { s = s * 10 + 2; } } finally { s = s * 10 + 3; } s"),
+    case("finally_on_return", "fn f(x) { try { return x; } finally { x = 0; } } f(7)"),
+    case("defer_runs_in_reverse", "fn f(x) { defer { throw x; } defer { x *= 10; } x += 1; } let r = 0; try { f(1); } catch (e) { r = e; } r"),
+    case("defer_error_replaces_the_thrown_one", "fn f(x) { defer { throw x * 10; } throw x; } let r = 0; try { f(2); } catch (e) { r = e; } r"),
     // --- host types -------------------------------------------------------
     //
     // The one part of the chain walker that approximates rather than
@@ -85,6 +85,19 @@ fn vm_agrees_with_rhai() {
     assert!(failures.is_empty(), "{} of {applicable} corpus scripts diverged:{}", failures.len(), failures.join(""),);
 }
 
+/// Without fast operators, a script-defined operator function overrides an
+/// operator for every type, including through an op-assignment.
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn vm_agrees_with_rhai_on_script_operators_without_fast_operators() {
+    let mut engine = corpus::engine();
+    engine.set_fast_operators(false);
+
+    for source in ["fn +(a, b) { a * b } let x = 3; x + 4", "fn +(a, b) { a * b } let x = 3; x += 4; x", "fn -(a) { 0 } let x = 3; -x", "fn *(a, b) { a + b } let x = 3; x -= 1; x * 4"] {
+        assert_eq!(run_stock(&engine, source), run_vm(&engine, source), "{source}");
+    }
+}
+
 /// The corpus is only worth anything if the comparison can actually fail.
 ///
 /// Guards against the harness silently degrading into a tautology — comparing
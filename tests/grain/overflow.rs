@@ -0,0 +1,72 @@
+//! Integer overflow modes, through the VM's operator fast paths.
+//!
+//! The corpus runs every script on a stock engine, whose mode is
+//! `Overflow::Checked`, so the other two never reach the VM from there. The
+//! mode is read from the engine at the moment an operator runs — by the
+//! built-in the VM calls as much as by the walker — and these hold the VM to
+//! giving the walker's answer under each of them, errors included.
+
+use rhai::grain::{Compiler, Vm};
+use rhai::{Engine, Overflow, Scope, INT};
+
+fn scripts() -> Vec<String> {
+    let max = INT::MAX;
+    let min = INT::MIN;
+
+    vec![
+        format!("let x = {max}; x + 1"),
+        format!("let x = {min}; x - 1"),
+        format!("let x = {max}; x * 3"),
+        format!("let x = {min}; x / -1"),
+        format!("let x = {min}; x % -1"),
+        "let x = 7; x ** 99".to_string(),
+        format!("let x = {min}; -x"),
+        format!("let x = {max}; x += 1; x"),
+        format!("let x = {min}; x *= 2; x"),
+        format!("let x = {max}; let n = 0; while n < 3 {{ x += {max}; n += 1; }} x"),
+        "let x = 1; x / 0".to_string(),
+        "let x = 1; x %= 0; x".to_string(),
+        "let x = 2; x ** -1".to_string(),
+    ]
+}
+
+fn outcome(result: Result<rhai::Dynamic, Box<rhai::EvalAltResult>>) -> Result<String, String> {
+    result.map(|value| format!("{value:?}")).map_err(|err| format!("{err:?}"))
+}
+
+#[test]
+fn vm_agrees_with_rhai_in_every_overflow_mode() {
+    for mode in [Overflow::Checked, Overflow::Wrapping, Overflow::Saturating] {
+        let mut engine = Engine::new();
+        engine.set_integer_overflow(mode);
+
+        for source in scripts() {
+            let ast = engine.compile(&source).expect("must compile");
+            let program = Compiler::new().compile(&ast);
+
+            assert_eq!(program.residual_count(), 0, "{source:?} must be fully lowered, or this tests Rhai rather than the VM");
+
+            let stock = outcome(engine.eval_ast_with_scope(&mut Scope::new(), &ast));
+            let vm = outcome(Vm::new(&engine).eval_with_scope(&mut Scope::new(), &program));
+
+            assert_eq!(vm, stock, "{mode:?}: {source:?}");
+        }
+    }
+}
+
+#[test]
+fn the_mode_is_read_when_the_operator_runs() {
+    let mut engine = Engine::new();
+    let ast = engine.compile(format!("let x = {}; x + 1", INT::MAX)).unwrap();
+    let program = Compiler::new().compile(&ast);
+
+    assert!(Vm::new(&engine).eval_with_scope(&mut Scope::new(), &program).is_err());
+
+    engine.set_integer_overflow(Overflow::Wrapping);
+    let value = Vm::new(&engine).eval_with_scope(&mut Scope::new(), &program).unwrap();
+    assert_eq!(value.as_int().unwrap(), INT::MIN);
+
+    engine.set_integer_overflow(Overflow::Saturating);
+    let value = Vm::new(&engine).eval_with_scope(&mut Scope::new(), &program).unwrap();
+    assert_eq!(value.as_int().unwrap(), INT::MAX);
+}
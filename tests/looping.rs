@@ -89,3 +89,111 @@ fn test_loop_expression() {
         )
         .unwrap_err();
 }
+
+#[test]
+fn test_loop_labels() {
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let found = 0;
+
+                    'outer: for x in 0..10 {
+                        for y in 0..10 {
+                            if x * y == 42 {
+                                found = x * 100 + y;
+                                break 'outer;
+                            }
+                        }
+                    }
+
+                    found
+                "
+            )
+            .unwrap(),
+        607
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let n = 0;
+
+                    'rows: for x in 0..5 {
+                        let y = 0;
+                        while true {
+                            y += 1;
+                            if y > x { continue 'rows; }
+                            n += 1;
+                        }
+                        n = -1000;
+                    }
+
+                    n
+                "
+            )
+            .unwrap(),
+        10
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let i = 0;
+
+                    'a: loop {
+                        'b: loop {
+                            'c: do {
+                                i += 1;
+                                if i > 2 { break 'c; }
+                            } while true;
+                            i *= 10;
+                            break 'a;
+                        }
+                    }
+
+                    i
+                "
+            )
+            .unwrap(),
+        30
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let value = 'outer: loop {
+                        for i in 0..10 {
+                            if i == 5 { break 'outer i * 10; }
+                        }
+                    };
+
+                    value
+                "
+            )
+            .unwrap(),
+        50
+    );
+
+    assert_eq!(engine.eval::<char>("let c = 'x'; c").unwrap(), 'x');
+
+    assert_eq!(*engine.compile("'outer: loop { loop { break 'inner; } }").unwrap_err().err_type(), ParseErrorType::LabelUndefined("inner".into()));
+    assert_eq!(*engine.compile("'outer: loop { } loop { break 'outer; }").unwrap_err().err_type(), ParseErrorType::LabelUndefined("outer".into()));
+    assert_eq!(*engine.compile("let xs = [1, 2]; 'a: for x in xs { 'a: for y in xs { break 'a; } }").unwrap_err().err_type(), ParseErrorType::LabelDuplicated("a".into()));
+    assert_eq!(engine.compile("let xs = [1, 2]; 'a: for x in xs { loop { 'a: while true { } } }").unwrap_err().position(), rhai::Position::new(1, 43));
+    engine.compile("let xs = [1, 2]; 'a: for x in xs { } 'a: for y in xs { break 'a; }").unwrap();
+    assert!(engine.compile("'outer: let x = 42;").is_err());
+    assert!(engine.compile("'outer loop { }").is_err());
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(*engine.compile("'outer: loop { let f = || { loop { break 'outer; } }; }").unwrap_err().err_type(), ParseErrorType::LabelUndefined("outer".into()));
+
+    engine.set_allow_loop_expressions(false);
+
+    assert!(engine.compile("let x = 'outer: loop { break 'outer 42; };").is_err());
+}
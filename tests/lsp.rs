@@ -0,0 +1,306 @@
+#![cfg(feature = "lsp")]
+#![cfg(not(feature = "no_position"))]
+use rhai::lsp::{read_message, write_message, Server};
+use rhai::{Engine, FuncRegistration, INT};
+use serde_json::{json, Value};
+use std::io::Cursor;
+
+#[derive(Debug, Clone)]
+struct TestStruct;
+
+/// Run messages through a language server over in-memory streams, returning all replies.
+fn exchange(server: &mut Server, messages: &[Value]) -> Vec<Value> {
+    let mut input = Vec::new();
+    for message in messages {
+        write_message(&mut input, &message.to_string()).unwrap();
+    }
+
+    let mut output = Vec::new();
+    server.run(Cursor::new(input), &mut output).unwrap();
+
+    let mut output = Cursor::new(output);
+    let mut replies = Vec::new();
+    while let Some(reply) = read_message(&mut output).unwrap() {
+        replies.push(serde_json::from_str(&reply).unwrap());
+    }
+    replies
+}
+
+/// Send a request, returning its result.
+fn call(server: &mut Server, method: &str, params: Value) -> Value {
+    let replies = exchange(server, &[json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })]);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["id"], 1);
+    replies[0]["result"].clone()
+}
+
+/// Open a document, returning the diagnostics published for it.
+fn open(server: &mut Server, uri: &str, text: &str) -> Vec<Value> {
+    let replies = exchange(server, &[json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": { "uri": uri, "languageId": "rhai", "version": 1, "text": text } } })]);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(replies[0]["params"]["uri"], uri);
+    replies[0]["params"]["diagnostics"].as_array().unwrap().clone()
+}
+
+fn at(uri: &str, line: usize, character: usize) -> Value {
+    json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } })
+}
+
+fn labels(items: &Value) -> Vec<&str> {
+    items.as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap()).collect()
+}
+
+fn server() -> Server {
+    let mut engine = Engine::new();
+
+    FuncRegistration::new("double")
+        .with_params_info(["x: int", "int"])
+        .with_comments(["/// Double a number."])
+        .register_into_engine(&mut engine, |x: INT| x * 2);
+
+    engine.register_type_with_name::<TestStruct>("TestStruct");
+
+    #[cfg(not(feature = "no_module"))]
+    {
+        let mut module = rhai::Module::new();
+        FuncRegistration::new("cube").set_into_module(&mut module, |x: INT| x * x * x);
+        engine.register_static_module("math", module.into());
+    }
+
+    Server::new(engine)
+}
+
+#[test]
+fn test_lsp_protocol() {
+    let mut server = server();
+
+    let result = call(&mut server, "initialize", json!({ "capabilities": {} }));
+    assert_eq!(result["capabilities"]["textDocumentSync"], 1);
+    assert_eq!(result["capabilities"]["hoverProvider"], true);
+    assert_eq!(result["serverInfo"]["name"], "rhai-lsp");
+
+    let replies = exchange(&mut server, &[json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }), json!({ "jsonrpc": "2.0", "id": "x", "method": "foo/bar" })]);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["id"], "x");
+    assert_eq!(replies[0]["error"]["code"], -32601);
+
+    let replies = server.handle("{ not json");
+    assert_eq!(replies.len(), 1);
+    assert!(replies[0].contains("-32700"));
+
+    // Nothing is processed after `exit`
+    let replies = exchange(
+        &mut server,
+        &[
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "textDocument/hover", "params": at("file:///x.rhai", 0, 0) }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+        ],
+    );
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["id"], 2);
+    assert_eq!(replies[0]["result"], Value::Null);
+    assert_eq!(replies[1]["error"]["code"], -32600);
+    assert!(server.is_shut_down());
+    assert!(server.is_exited());
+}
+
+#[test]
+fn test_lsp_diagnostics() {
+    let mut server = server();
+    let uri = "file:///test.rhai";
+
+    let diagnostics = open(&mut server, uri, "let x = 40 +;\nlet y = 2;\ny +;");
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 0, "character": 12 }));
+    assert_eq!(diagnostics[1]["range"]["start"]["line"], 2);
+
+    let replies = exchange(
+        &mut server,
+        &[json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": { "textDocument": { "uri": uri, "version": 2 }, "contentChanges": [{ "text": "let s = \"😀\"; let x = 42;\ns" }] } })],
+    );
+    let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 2);
+    assert_eq!(diagnostics[0]["code"], "unused_variable");
+    assert_eq!(diagnostics[0]["tags"], json!([1]));
+    assert_eq!(diagnostics[0]["range"], json!({ "start": { "line": 0, "character": 18 }, "end": { "line": 0, "character": 19 } }));
+
+    let replies = exchange(&mut server, &[json!({ "jsonrpc": "2.0", "method": "textDocument/didClose", "params": { "textDocument": { "uri": uri } } })]);
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+
+    // Incomplete interpolated strings, as they are typed
+    for text in ["`${", "let x = 1;\n`${x", "`a${ {"] {
+        let diagnostics = open(&mut server, uri, text);
+        assert!(!diagnostics.is_empty(), "{text}");
+        assert!(diagnostics.iter().all(|d| d["severity"] == 1), "{text}");
+        assert!(call(&mut server, "textDocument/completion", at(uri, 0, 3)).is_array(), "{text}");
+    }
+}
+
+#[cfg(not(feature = "no_function"))]
+const SCRIPT: &str = "/// Add two numbers.
+fn add(a, b) {
+    a + b
+}
+
+let x = add(1, 2);
+let y = double(x);
+print(y);
+";
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_lsp_completion() {
+    let mut server = server();
+    let uri = "file:///test.rhai";
+
+    assert_eq!(open(&mut server, uri, SCRIPT), Vec::<Value>::new());
+
+    // Inside a function body
+    let items = call(&mut server, "textDocument/completion", at(uri, 2, 4));
+    let names = labels(&items);
+    assert!(names.contains(&"a"));
+    assert!(names.contains(&"b"));
+    assert!(names.contains(&"add"));
+    assert!(names.contains(&"double"));
+    assert!(names.contains(&"print"));
+    assert!(names.contains(&"TestStruct"));
+    assert!(!names.contains(&"x"));
+    #[cfg(not(feature = "no_module"))]
+    assert!(names.contains(&"math"));
+
+    let add = items.as_array().unwrap().iter().find(|item| item["label"] == "add").unwrap();
+    assert_eq!(add["kind"], 3);
+    assert_eq!(add["detail"], "fn add(a, b)");
+    assert_eq!(add["documentation"]["value"], "Add two numbers.");
+
+    let double = items.as_array().unwrap().iter().find(|item| item["label"] == "double").unwrap();
+    assert!(double["detail"].as_str().unwrap().starts_with("double(x: "));
+    assert_eq!(double["documentation"]["value"], "Double a number.");
+
+    // At the top level
+    let items = call(&mut server, "textDocument/completion", at(uri, 6, 0));
+    let names = labels(&items);
+    assert!(names.contains(&"x"));
+    assert!(!names.contains(&"y"));
+    assert!(!names.contains(&"a"));
+
+    // Functions in a registered module
+    #[cfg(not(feature = "no_module"))]
+    {
+        exchange(&mut server, &[json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": { "textDocument": { "uri": uri }, "contentChanges": [{ "text": "math::c" }] } })]);
+        let items = call(&mut server, "textDocument/completion", at(uri, 0, 7));
+        assert_eq!(labels(&items), vec!["cube"]);
+    }
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_lsp_hover() {
+    let mut server = server();
+    let uri = "file:///test.rhai";
+
+    open(&mut server, uri, SCRIPT);
+
+    let hover = call(&mut server, "textDocument/hover", at(uri, 5, 9));
+    assert_eq!(hover["contents"]["value"], "```rhai\nfn add(a, b)\n```\n\nAdd two numbers.");
+    assert_eq!(hover["range"], json!({ "start": { "line": 5, "character": 8 }, "end": { "line": 5, "character": 11 } }));
+
+    let hover = call(&mut server, "textDocument/hover", at(uri, 6, 10));
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.starts_with("```rhai\nfn double(x: "));
+    assert!(text.ends_with("\n```\n\nDouble a number."));
+
+    let hover = call(&mut server, "textDocument/hover", at(uri, 6, 15));
+    assert_eq!(hover["contents"]["value"], "```rhai\nlet x\n```");
+
+    assert_eq!(call(&mut server, "textDocument/hover", at(uri, 4, 0)), Value::Null);
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_lsp_definition() {
+    let mut server = server();
+    let uri = "file:///test.rhai";
+
+    open(&mut server, uri, SCRIPT);
+
+    let location = call(&mut server, "textDocument/definition", at(uri, 5, 9));
+    assert_eq!(location, json!({ "uri": uri, "range": { "start": { "line": 1, "character": 3 }, "end": { "line": 1, "character": 6 } } }));
+
+    let location = call(&mut server, "textDocument/definition", at(uri, 6, 15));
+    assert_eq!(location["range"]["start"], json!({ "line": 5, "character": 4 }));
+
+    let location = call(&mut server, "textDocument/definition", at(uri, 2, 8));
+    assert_eq!(location["range"]["start"], json!({ "line": 1, "character": 10 }));
+
+    assert_eq!(call(&mut server, "textDocument/definition", at(uri, 6, 10)), Value::Null);
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_lsp_imports() {
+    let mut server = server();
+    let lib = "file:///lsp-test/lib.rhai";
+    let uri = "file:///lsp-test/main.rhai";
+
+    open(&mut server, lib, "/// Triple a number.\nfn triple(x) { x * 3 }\nexport const ANSWER = 42;");
+    open(&mut server, uri, "import \"lib\" as lib;\nlet z = lib::triple(2);\nprint(lib::ANSWER);");
+
+    let location = call(&mut server, "textDocument/definition", at(uri, 1, 14));
+    assert_eq!(location, json!({ "uri": lib, "range": { "start": { "line": 1, "character": 3 }, "end": { "line": 1, "character": 9 } } }));
+
+    let location = call(&mut server, "textDocument/definition", at(uri, 2, 12));
+    assert_eq!(location["range"]["start"], json!({ "line": 2, "character": 13 }));
+
+    // The path or the alias of the module
+    let location = call(&mut server, "textDocument/definition", at(uri, 0, 9));
+    assert_eq!(location, json!({ "uri": lib, "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } } }));
+    assert_eq!(call(&mut server, "textDocument/definition", at(uri, 1, 9))["uri"], lib);
+
+    let hover = call(&mut server, "textDocument/hover", at(uri, 1, 14));
+    assert_eq!(hover["contents"]["value"], "```rhai\nfn triple(x)\n```\n\nTriple a number.");
+
+    let items = call(&mut server, "textDocument/completion", at(uri, 1, 13));
+    assert_eq!(labels(&items), vec!["triple", "ANSWER"]);
+
+    // Modules not opened are read from files
+    let uri = format!("file://{}/scripts/test.rhai", env!("CARGO_MANIFEST_DIR"));
+    open(&mut server, &uri, "import \"loop\" as m;\nprint(m::foo);");
+
+    let location = call(&mut server, "textDocument/definition", at(&uri, 1, 9));
+    assert!(location["uri"].as_str().unwrap().ends_with("/scripts/loop.rhai"));
+    assert_eq!(location["range"]["start"], json!({ "line": 13, "character": 12 }));
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_lsp_symbols() {
+    let mut server = server();
+    let uri = "file:///test.rhai";
+
+    open(&mut server, uri, SCRIPT);
+
+    let symbols = call(&mut server, "textDocument/documentSymbol", json!({ "textDocument": { "uri": uri } }));
+    let symbols = symbols.as_array().unwrap();
+    assert_eq!(symbols.len(), 3);
+
+    assert_eq!(symbols[0]["name"], "add");
+    assert_eq!(symbols[0]["kind"], 12);
+    assert_eq!(symbols[0]["range"], json!({ "start": { "line": 1, "character": 0 }, "end": { "line": 3, "character": 1 } }));
+    assert_eq!(symbols[0]["selectionRange"], json!({ "start": { "line": 1, "character": 3 }, "end": { "line": 1, "character": 6 } }));
+    assert_eq!(labels_of(&symbols[0]["children"]), vec!["a", "b"]);
+
+    assert_eq!(symbols[1]["name"], "x");
+    assert_eq!(symbols[1]["kind"], 13);
+    assert_eq!(symbols[2]["name"], "y");
+}
+
+fn labels_of(symbols: &Value) -> Vec<&str> {
+    symbols.as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect()
+}
@@ -285,3 +285,63 @@ fn test_map_missing_property_callback() {
         143
     );
 }
+
+#[test]
+fn test_map_comprehension() {
+    let engine = Engine::new();
+
+    let map = engine.eval::<Map>(r#"#{ "k": x for x in 0..5 if x < 3 }"#).unwrap();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map["k"].as_int().unwrap(), 2);
+
+    assert!(engine.eval::<Map>("#{ k: 1 for k in 0..5 if k > 10 }").unwrap().is_empty());
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let map = engine
+            .eval::<Map>(
+                r#"
+                    let names = ["a", "b", "c", "a"];
+                    #{ name: i for (name, i) in names if name != "b" }
+                "#,
+            )
+            .unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"].as_int().unwrap(), 3);
+        assert_eq!(map["c"].as_int().unwrap(), 2);
+
+        assert_eq!(
+            engine
+                .eval::<INT>(
+                    "
+                        let m = #{ a: 1, b: 2 };
+                        let d = #{ k: m[k] * 10 for k in m.keys() };
+                        d.a + d.b
+                    "
+                )
+                .unwrap(),
+            30
+        );
+    }
+
+    assert!(matches!(*engine.eval::<Map>("#{ x: 1 for x in 0..3 }").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+
+    // Computed keys
+    let map = engine.eval::<Map>("#{ `k${x}`: x * 10 for x in 0..3 }").unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["k2"].as_int().unwrap(), 20);
+
+    let map = engine.eval::<Map>(r#"let prefix = "p"; #{ [prefix + x]: x for x in 0..3 if x > 0 }"#).unwrap();
+    assert_eq!(map.keys().map(|k| k.as_str()).collect::<Vec<_>>(), ["p1", "p2"]);
+
+    assert!(matches!(engine.compile("#{ [1]: 2 }").unwrap_err().err_type(), ParseErrorType::PropertyExpected));
+    assert!(matches!(engine.compile("#{ `k${1}`: 2 }").unwrap_err().err_type(), ParseErrorType::PropertyExpected));
+    assert!(matches!(engine.compile("#{ [x: x for x in 0..3 }").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+
+    // An unquoted key must be a loop variable
+    assert!(matches!(
+        engine.compile(r#"let k = "a"; #{ k: x for x in 0..3 }"#).unwrap_err().err_type(),
+        ParseErrorType::WrongComprehensionKey(k) if k == "k"
+    ));
+    assert!(engine.compile("#{ i: x for (x, i) in 0..3 }").is_ok());
+}
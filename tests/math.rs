@@ -1,5 +1,8 @@
 use rhai::{Engine, EvalAltResult, INT};
 
+#[cfg(not(feature = "unchecked"))]
+use rhai::Overflow;
+
 #[cfg(not(feature = "no_float"))]
 use rhai::FLOAT;
 
@@ -57,3 +60,94 @@ fn test_math_parse() {
     assert_eq!(engine.eval::<INT>(r#"parse_int("42", 16)"#).unwrap(), 0x42);
     assert_eq!(engine.eval::<INT>(r#"parse_int("abcdef", 16)"#).unwrap(), 0xabcdef);
 }
+
+#[test]
+fn test_math_overflow_functions() {
+    let engine = Engine::new();
+
+    let max = INT::MAX;
+    let min = INT::MIN;
+
+    assert_eq!(engine.eval::<INT>(&format!("wrapping_add({max}, 1)")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("wrapping_sub({min}, 2)")).unwrap(), max - 1);
+    assert_eq!(engine.eval::<INT>(&format!("wrapping_mul({max}, 2)")).unwrap(), -2);
+    assert_eq!(engine.eval::<INT>(&format!("wrapping_div({min}, -1)")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("wrapping_rem({min}, -1)")).unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("wrapping_pow(3, 100)").unwrap(), (3 as INT).wrapping_pow(100));
+    assert_eq!(engine.eval::<INT>("wrapping_shl(1, 65)").unwrap(), (1 as INT).wrapping_shl(65));
+    assert_eq!(engine.eval::<INT>(&format!("wrapping_neg({min})")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("wrapping_abs({min})")).unwrap(), min);
+
+    assert_eq!(engine.eval::<INT>(&format!("saturating_add({max}, 1)")).unwrap(), max);
+    assert_eq!(engine.eval::<INT>(&format!("saturating_sub({min}, 1)")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("saturating_mul({min}, 2)")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("saturating_div({min}, -1)")).unwrap(), max);
+    assert_eq!(engine.eval::<INT>("saturating_pow(-3, 101)").unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("saturating_neg({min})")).unwrap(), max);
+    assert_eq!(engine.eval::<INT>(&format!("saturating_abs({min})")).unwrap(), max);
+
+    assert_eq!(engine.eval::<String>(&format!("to_string(overflowing_add({max}, 1))")).unwrap(), format!("({min}, true)"));
+    assert_eq!(engine.eval::<String>("to_string(overflowing_sub(5, 3))").unwrap(), "(2, false)");
+    assert_eq!(engine.eval::<String>("to_string(overflowing_shl(1, 1))").unwrap(), "(2, false)");
+    assert_eq!(engine.eval::<String>("to_string(overflowing_shl(1, 129))").unwrap(), "(2, true)");
+    assert_eq!(engine.eval::<String>(&format!("to_string(overflowing_neg({min}))")).unwrap(), format!("({min}, true)"));
+
+    assert!(matches!(*engine.eval::<INT>("wrapping_div(1, 0)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("saturating_pow(2, -1)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("wrapping_shl(1, -1)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_math_overflow_modes() {
+    let mut engine = Engine::new();
+
+    let max = INT::MAX;
+    let min = INT::MIN;
+
+    assert_eq!(engine.integer_overflow(), Overflow::Checked);
+
+    engine.set_integer_overflow(Overflow::Wrapping);
+
+    // Folded by the optimizer
+    assert_eq!(engine.eval::<INT>(&format!("{max} + 1")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("{max} * 2")).unwrap(), -2);
+
+    // Evaluated at run-time
+    assert_eq!(engine.eval::<INT>(&format!("let x = {max}; x + 1")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; x -= 1; x")).unwrap(), max);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; x / -1")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; x % -1")).unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("let x = 3; x ** 100").unwrap(), (3 as INT).wrapping_pow(100));
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; -x")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; abs(x)")).unwrap(), min);
+    assert!(matches!(*engine.eval::<INT>("let x = 1; x / 0").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("let x = 2; x ** -1").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    engine.set_integer_overflow(Overflow::Saturating);
+
+    assert_eq!(engine.eval::<INT>(&format!("{max} + 1")).unwrap(), max);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {max}; x + 1")).unwrap(), max);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; x *= 2; x")).unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; x / -1")).unwrap(), max);
+    assert_eq!(engine.eval::<INT>("let x = -3; x ** 101").unwrap(), min);
+    assert_eq!(engine.eval::<INT>(&format!("let x = {min}; -x")).unwrap(), max);
+    assert!(matches!(*engine.eval::<INT>("let x = 1; x % 0").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    // The mode in effect at run-time applies to an AST compiled under another mode
+    engine.set_integer_overflow(Overflow::Checked);
+    let ast = engine.compile(format!("let x = {max}; x + 1")).unwrap();
+    assert!(matches!(*engine.eval_ast::<INT>(&ast).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    engine.set_integer_overflow(Overflow::Wrapping);
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), min);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        engine.register_fn("byte", |x: INT| x as u8);
+        assert_eq!(engine.eval::<u8>("byte(250) + byte(10)").unwrap(), 4);
+        engine.set_integer_overflow(Overflow::Saturating);
+        assert_eq!(engine.eval::<u8>("byte(250) + byte(10)").unwrap(), 255);
+        assert_eq!(engine.eval::<u8>("saturating_sub(byte(3), byte(10))").unwrap(), 0);
+    }
+}
@@ -30,6 +30,9 @@ mod grain {
     mod fuzz;
     #[cfg(not(feature = "unchecked"))]
     mod limits;
+    // The overflow mode is an engine option only when arithmetic is checked.
+    #[cfg(not(feature = "unchecked"))]
+    mod overflow;
     // Prices Rhai's own AST nodes, which are exported under `internals` only,
     // against `follow.rhai` — a checked-in fixture, so a build without the
     // syntax it is written in has nothing to price.
@@ -590,3 +590,76 @@ fn test_module_dynamic() {
 
     assert_eq!(engine.eval::<INT>(r#"import "test" as test; test::test("test", 38);"#).unwrap(), 42);
 }
+
+#[test]
+fn test_module_inline() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    module geometry {
+                        export const SIDES = 4;
+                        let hidden = 99;
+                    }
+                    geometry::SIDES
+                "
+            )
+            .unwrap(),
+        4
+    );
+
+    assert!(engine.eval::<INT>("module m { let hidden = 1; } m::hidden").is_err());
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(
+            engine
+                .eval::<INT>(
+                    "
+                        module geometry {
+                            export const SCALE = 3;
+                            fn area(w, h) { scale(w * h) }
+                            private fn scale(x) { x * global::SCALE }
+
+                            module units {
+                                fn cm(x) { x * 10 }
+                            }
+                        }
+                        geometry::area(2, 5) + geometry::units::cm(1)
+                    "
+                )
+                .unwrap(),
+            40
+        );
+
+        assert!(engine.eval::<INT>("module m { private fn secret() { 42 } } m::secret()").is_err());
+
+        let ast = engine.compile("module m { fn answer() { 42 } }").unwrap();
+        let module = Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap();
+        assert!(module.get_sub_module("m").is_some());
+
+        let ast = ast.merge(&engine.compile("m::answer()").unwrap());
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+    }
+
+    assert!(matches!(
+        engine.compile("{ module m { } }").unwrap_err(),
+        ParseError(x, ..) if *x == ParseErrorType::WrongModuleDefinition
+    ));
+
+    #[cfg(not(feature = "no_function"))]
+    assert!(matches!(
+        engine.compile("fn abc() { module m { } }").unwrap_err(),
+        ParseError(x, ..) if *x == ParseErrorType::WrongModuleDefinition
+    ));
+
+    let err = engine.compile("module m { }\nmodule m { }").unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::ModuleDuplicatedDefinition("m".into()));
+    assert_eq!(err.position(), rhai::Position::new(2, 8));
+    assert_eq!(err.err_type().to_string(), "Module m already exists");
+
+    // Modules in different scopes may share a name
+    engine.compile("module m { module m { } } module n { module m { } }").unwrap();
+}
@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, Scope, INT};
+use rhai::{Engine, EvalAltResult, ParseErrorType, Scope, INT};
 
 #[test]
 fn test_ops() {
@@ -80,3 +80,46 @@ fn test_ops_custom_types() {
     assert!(!engine.eval::<bool>("let y = new_ts2(); 0 > y").unwrap());
     assert!(!engine.eval::<bool>("let y = new_ts2(); y > 0").unwrap());
 }
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_ops_script_defined() {
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        let script = "
+            struct Money { cents }
+
+            fn +(a, b) { Money { cents: a.cents + b.cents } }
+            fn -(a) { Money { cents: -a.cents } }
+            fn ==(a, b) { a.cents == b.cents }
+            fn <(a, b) { a.cents < b.cents }
+        ";
+
+        assert_eq!(engine.eval::<INT>(&format!("{script} let m = Money {{ cents: 40 }} + Money {{ cents: 2 }}; m.cents")).unwrap(), 42);
+        assert_eq!(engine.eval::<INT>(&format!("{script} let m = Money {{ cents: 40 }}; m += Money {{ cents: 2 }}; m.cents")).unwrap(), 42);
+        assert_eq!(engine.eval::<INT>(&format!("{script} let m = -Money {{ cents: 42 }}; m.cents")).unwrap(), -42);
+        assert!(engine.eval::<bool>(&format!("{script} Money {{ cents: 1 }} < Money {{ cents: 2 }}")).unwrap());
+        assert!(engine.eval::<bool>(&format!("{script} let a = 1; Money {{ cents: a }} == Money {{ cents: 1 }}")).unwrap());
+    }
+
+    // Operators on standard types are not overloaded
+    assert_eq!(engine.eval::<INT>("fn +(a, b) { a * b } let x = 3; x + 4").unwrap(), 7);
+    assert_eq!(engine.eval::<INT>("fn -(a) { 0 } let x = 3; -x").unwrap(), -3);
+
+    assert_eq!(engine.eval::<INT>("fn +(a, b) { a * b } let x = 3; x += 4; x").unwrap(), 7);
+
+    // Without Fast Operators mode, they are
+    engine.set_fast_operators(false);
+
+    assert_eq!(engine.eval::<INT>("fn +(a, b) { a * b } let x = 3; x + 4").unwrap(), 12);
+    assert_eq!(engine.eval::<INT>("fn +(a, b) { a * b } let x = 3; x += 4; x").unwrap(), 12);
+    assert_eq!(engine.eval::<INT>("fn -(a) { 0 } let x = 3; -x").unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("fn -(a, b) { 0 } let x = 3; x - 1 + x * 2").unwrap(), 6);
+    assert_eq!(engine.eval::<String>(r#"fn +(a, b) { "!" } let s = "x"; s += "y"; s"#).unwrap(), "!");
+
+    assert!(matches!(engine.compile("fn *(a) { a }").unwrap_err().err_type(), ParseErrorType::FnWrongOperatorParams(op) if op == "*"));
+    assert!(matches!(engine.compile("fn !(a, b) { a }").unwrap_err().err_type(), ParseErrorType::FnWrongOperatorParams(op) if op == "!"));
+    assert!(matches!(engine.compile("fn &&(a, b) { a }").unwrap_err().err_type(), ParseErrorType::FnMissingName));
+}
@@ -87,6 +87,14 @@ fn test_optimizer_parse() {
 
     assert_eq!(format!("{ast:?}"), r#"AST { source: None, doc: "", resolver: None, body: [Expr(42 @ 1:1)] }"#);
 
+    let ast = engine.compile("abs(...[-42])").unwrap();
+
+    assert_eq!(format!("{ast:?}"), r#"AST { source: None, doc: "", resolver: None, body: [Expr(42 @ 1:1)] }"#);
+
+    let ast = engine.compile("#{...#{x: 42}}.x").unwrap();
+
+    assert_eq!(format!("{ast:?}"), r#"AST { source: None, doc: "", resolver: None, body: [Expr(42 @ 1:1)] }"#);
+
     let ast = engine.compile("NUMBER").unwrap();
 
     assert_eq!(format!("{ast:?}"), r#"AST { source: None, doc: "", resolver: None, body: [Expr(Variable(NUMBER) @ 1:1)] }"#);
@@ -101,6 +109,31 @@ fn test_optimizer_parse() {
     assert_eq!(format!("{ast:?}"), r#"AST { source: None, doc: "", resolver: None, body: [Expr(42 @ 1:1)] }"#);
 }
 
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_position"))]
+#[test]
+fn test_optimizer_script_operators() {
+    let mut engine = Engine::new();
+
+    engine.set_optimization_level(OptimizationLevel::Simple);
+
+    // Under Fast Operators mode, a script-defined operator cannot be reached by standard types
+    let ast = engine.compile("fn +(a, b) { a * b } 3 + 4").unwrap();
+    assert!(format!("{ast:?}").contains("body: [Expr(7 @ 1:"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 7);
+
+    engine.set_fast_operators(false);
+
+    let ast = engine.compile("fn +(a, b) { a * b } 3 + 4").unwrap();
+    assert!(!format!("{ast:?}").contains("body: [Expr(7 @ 1:"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 12);
+
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    let ast = engine.compile("fn +(a, b) { a * b } 3 + 4").unwrap();
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 12);
+}
+
 #[cfg(not(feature = "no_function"))]
 #[test]
 fn test_optimizer_scope() {
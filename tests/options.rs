@@ -85,6 +85,28 @@ fn test_options_strict_var() {
 
     assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "{ let y = 42; x * y }").unwrap(), 42 * 42);
 
+    #[cfg(not(feature = "no_index"))]
+    {
+        engine.compile("let a = [x * 2 for x in 0..3 if x > 0];").unwrap();
+        engine.compile("let a = [(x, i) for (x, i) in 0..3];").unwrap();
+        engine.compile("let a = [[x * y for x in 0..3] for y in 0..3];").unwrap();
+        let _ = engine.compile("let a = [x * z for x in 0..3];").unwrap_err();
+        let _ = engine.compile("let a = [[x * y for x in 0..3] for x in 0..3];").unwrap_err();
+        let _ = engine.compile("let a = [x, 1];").unwrap_err();
+        let _ = engine.compile("let a = [x for x in x];").unwrap_err();
+        #[cfg(not(feature = "no_closure"))]
+        engine.compile("let f = || [x for x in 0..3];").unwrap();
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        engine.compile("let m = #{ k: 1 for k in 0..3 };").unwrap();
+        let _ = engine.compile("let m = #{ k: 1 for x in 0..3 };").unwrap_err();
+        let _ = engine.compile("let m = #{ k: x for k in 0..3 };").unwrap_err();
+        engine.compile("let m = #{ [`k${x}`]: x for x in 0..3 };").unwrap();
+        let _ = engine.compile("let m = #{ [`k${z}`]: x for x in 0..3 };").unwrap_err();
+    }
+
     #[cfg(not(feature = "no_function"))]
     let _ = engine.compile("fn foo(x) { x + y }").unwrap_err();
 
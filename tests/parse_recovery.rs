@@ -0,0 +1,96 @@
+use rhai::{Engine, LexError, ParseErrorType, INT};
+
+#[test]
+fn test_parse_recovery() {
+    let engine = Engine::new();
+
+    let (ast, errors) = engine.compile_with_recovery("let x = 40 + 2; x");
+    assert!(errors.is_empty());
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    let (ast, errors) = engine.compile_with_recovery("let x = 40 +* 2; let y = (1, ; let z = 42; z");
+    assert_eq!(errors.len(), 2);
+    assert_eq!(*errors[0].err_type(), ParseErrorType::BadInput(LexError::UnexpectedInput("*".into())));
+    assert_eq!(*errors[1].err_type(), ParseErrorType::BadInput(LexError::UnexpectedInput(";".into())));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    // Missing semicolons and unterminated blocks
+    let (ast, errors) = engine.compile_with_recovery("let x = 1 let y = 2; { x + y");
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0].err_type(), ParseErrorType::MissingToken(t, ..) if t == ";"));
+    assert!(matches!(errors[1].err_type(), ParseErrorType::MissingToken(t, ..) if t == "}"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 3);
+
+    // Stray tokens
+    let (ast, errors) = engine.compile_with_recovery("} ); 42");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    // Interpolated strings
+    let (ast, errors) = engine.compile_with_recovery("let x = `a${ 1 + }b${ 2 }c`; let y = `d${ 3 }e`; y");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(engine.eval_ast::<String>(&ast).unwrap(), "d3e");
+
+    // Truncated interpolated strings
+    for script in ["`${", "`${x", "`a${ {", "let x = 1; `${x}${"] {
+        let (.., errors) = engine.compile_with_recovery(script);
+        assert!(!errors.is_empty(), "{script}");
+        assert!(errors.iter().any(|e| *e.err_type() == ParseErrorType::BadInput(LexError::UnterminatedString)), "{script}");
+    }
+
+    #[cfg(not(feature = "no_position"))]
+    {
+        let (_, errors) = engine.compile_with_recovery("let x = 1 +;\n{\n    let y = ;\n}\nlet z = *;");
+        let positions: Vec<_> = errors.iter().map(|e| e.position()).collect();
+        assert_eq!(positions, [rhai::Position::new(1, 12), rhai::Position::new(3, 13), rhai::Position::new(5, 9)]);
+    }
+
+    // Without recovery, only the first error is returned
+    assert_eq!(*engine.compile("let x = 40 +* 2; let y = (1, ;").unwrap_err().err_type(), ParseErrorType::BadInput(LexError::UnexpectedInput("*".into())));
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_parse_recovery_functions() {
+    let engine = Engine::new();
+
+    let (ast, errors) = engine.compile_with_recovery(
+        "
+            fn foo(x) {
+                let y = x +;
+                x * 2
+            }
+            fn bar( { 0 }
+            fn baz(x) { x + 1 }
+            foo(20) + baz(1)
+        ",
+    );
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[1].err_type(), ParseErrorType::MissingToken(t, ..) if t == ")"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        let (ast, errors) = engine.compile_with_recovery("let f = |x| { let y = ; x }; f.call(42)");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+    }
+}
+
+#[cfg(not(feature = "no_custom_syntax"))]
+#[test]
+fn test_parse_recovery_custom_syntax() {
+    let mut engine = Engine::new();
+
+    engine.register_custom_operator("foo", 160).unwrap().register_fn("foo", |x: INT, y: INT| x * y);
+    engine
+        .register_custom_syntax(["twice", "$block$"], false, |context, inputs| {
+            let value = context.eval_expression_tree(&inputs[0])?;
+            Ok(rhai::Dynamic::from(value.as_int().unwrap_or(0) * 2))
+        })
+        .unwrap();
+
+    let (ast, errors) = engine.compile_with_recovery("let x = twice { 1 + } ; let y = twice { 3 foo 7 }; let z = 2 foo; y");
+    assert_eq!(errors.len(), 2);
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+}
@@ -0,0 +1,102 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_pipeline() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#""hello" |> type_of()"#).unwrap(), "string");
+    assert_eq!(engine.eval::<INT>("-42 |> abs()").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("(-40 |> abs()) + 2").unwrap(), 42);
+    assert_eq!(engine.eval::<String>("let x = 1; x + 41 |> to_string()").unwrap(), "42");
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn add(x, y) { x + y } 40 |> add(2)").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("fn add(x, y) { x + y } fn double(x) { x * 2 } 20 |> add(1) |> double()").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("fn sub(x, y) { x - y } 50 - 6 |> sub(2)").unwrap(), 42);
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("let x = [1, 2]; x |> push(3); x |> len()").unwrap(), 3);
+
+    assert!(matches!(engine.compile("40 |> 2").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+    assert!(matches!(engine.compile("40 |> foo").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+    assert!(matches!(engine.compile("40 |> !foo()").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+}
+
+#[test]
+fn test_spread() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("max(...(40, 42))").unwrap(), 42);
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(engine.eval::<String>("let a = [1, 2]; let b = [3]; to_string([0, ...a, ...b, 4])").unwrap(), "[0, 1, 2, 3, 4]");
+        assert_eq!(engine.eval::<String>("let a = [1, 2]; to_string([...a, ...(3, 4), ...[]])").unwrap(), "[1, 2, 3, 4]");
+        assert_eq!(engine.eval::<INT>("let args = [2, 40]; max(...args)").unwrap(), 40);
+        assert_eq!(engine.eval::<INT>("let x = [1]; insert(x, ...[0, 42]); x[0]").unwrap(), 42);
+
+        assert!(matches!(*engine.eval::<INT>("let x = 42; [...x]").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("let d = #{x: 1, y: 2}; let m = #{...d, x: 40}; m.x + m.y").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("let d = #{x: 1, y: 2}; let m = #{x: 40, ...d}; m.x + m.y").unwrap(), 3);
+        assert_eq!(engine.eval::<INT>("let m = #{...#{x: 1}, ...#{x: 42}}; m.x").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("#{x: 1, ...#{x: 42}}.x").unwrap(), 42);
+
+        assert!(matches!(*engine.eval::<INT>("let x = 42; #{...x}").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+        assert!(matches!(engine.compile("let x = [1]; x.push(...[2])").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn add(x, y, z) { x + y + z } let args = (2, 3); add(1, ...args)").unwrap(), 6);
+        assert_eq!(engine.eval::<INT>("fn add(x, y, z) { x + y + z } add(...(1, 2), 3)").unwrap(), 6);
+        assert_eq!(engine.eval::<INT>("fn add(x, y) { x + y } 1 |> add(...(41,))").unwrap(), 42);
+    }
+
+    assert!(matches!(engine.compile("let x = ...y;").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_spread_evaluation_order() {
+    use std::sync::{Arc, Mutex};
+
+    let mut engine = Engine::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let log2 = log.clone();
+
+    engine.on_print(move |s| log2.lock().unwrap().push(s.to_string()));
+
+    let script = "
+        fn t(x) { print(x); x }
+        fn f(a, b, c, d) { a + b + c + d }
+        f(t(1), ...[t(2), t(3)], t(4))
+    ";
+    assert_eq!(engine.eval::<INT>(script).unwrap(), 10);
+    assert_eq!(*log.lock().unwrap(), ["1", "2", "3", "4"]);
+
+    log.lock().unwrap().clear();
+
+    let script = "
+        fn t(x) { print(x); x }
+        let f = |a, b, c| a + b + c;
+        call(f, t(1), ...[t(2), t(3)])
+    ";
+    assert_eq!(engine.eval::<INT>(script).unwrap(), 6);
+    assert_eq!(*log.lock().unwrap(), ["1", "2", "3"]);
+
+    #[cfg(not(feature = "no_module"))]
+    {
+        let mut module = rhai::Module::new();
+        module.set_native_fn("add", |a: INT, b: INT, c: INT| Ok(a + b + c));
+        engine.register_static_module("m", module.into());
+
+        assert_eq!(engine.eval::<INT>("let x = [2, 3]; m::add(1, ...x)").unwrap(), 6);
+    }
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "profiling")]
+use rhai::{Engine, INT};
+
+#[test]
+fn test_profiler() {
+    let mut engine = Engine::new();
+
+    assert!(engine.profile().is_none());
+
+    engine.register_fn("add", |x: INT, y: INT| x + y);
+    engine.set_profiling_enabled(true);
+
+    assert!(engine.is_profiling_enabled());
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        engine
+            .run(
+                "
+                    fn fib(n) {
+                        if n < 2 { n } else { add(fib(n - 1), fib(n - 2)) }
+                    }
+                    fib(3);
+                ",
+            )
+            .unwrap();
+
+        let profile = engine.take_profile().unwrap();
+
+        assert_eq!(profile.get(&["<script>"]).unwrap().calls, 1);
+        assert_eq!(profile.get(&["<script>", "fib"]).unwrap().calls, 1);
+        assert_eq!(profile.get(&["<script>", "fib", "fib"]).unwrap().calls, 2);
+        assert_eq!(profile.get(&["<script>", "fib", "fib", "fib"]).unwrap().calls, 2);
+        assert_eq!(profile.get(&["<script>", "fib", "add"]).unwrap().calls, 1);
+        assert_eq!(profile.get(&["<script>", "fib", "fib", "add"]).unwrap().calls, 1);
+        assert!(profile.get(&["fib"]).is_none());
+
+        let script = profile.get(&["<script>"]).unwrap();
+        let fib = profile.get(&["<script>", "fib"]).unwrap();
+        assert!(fib.time <= script.time);
+        #[cfg(not(feature = "unchecked"))]
+        assert!(fib.operations > 0 && fib.operations < script.operations);
+
+        let functions = profile.functions();
+        let fib = functions.iter().find(|f| f.name == "fib").unwrap();
+        assert_eq!(fib.calls, 5);
+        assert_eq!(fib.total_time, profile.get(&["<script>", "fib"]).unwrap().time);
+        let add = functions.iter().find(|f| f.name == "add").unwrap();
+        assert_eq!(add.calls, 2);
+        assert_eq!(add.total_time, add.self_time);
+
+        let folded = profile.to_folded();
+        let stacks: Vec<_> = folded.lines().map(|line| line.rsplit_once(' ').unwrap().0).collect();
+        assert_eq!(stacks, ["<script>", "<script>;fib", "<script>;fib;add", "<script>;fib;fib", "<script>;fib;fib;add", "<script>;fib;fib;fib"]);
+        assert!(folded.lines().all(|line| line.rsplit_once(' ').unwrap().1.parse::<u128>().is_ok()));
+
+        let table = profile.to_table();
+        assert!(table.starts_with("function"));
+        assert_eq!(table.lines().count(), 4);
+    }
+
+    assert!(engine.take_profile().unwrap().is_empty());
+
+    engine.set_profiling_enabled(false);
+    engine.run("let x = 42;").unwrap();
+    assert!(engine.profile().is_none());
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_profiler_call_fn() {
+    let mut engine = Engine::new();
+
+    engine.set_profiling_enabled(true);
+
+    let mut ast = engine
+        .compile(
+            "
+                fn foo(x) { bar(x) + 1 }
+                fn bar(x) { x * 2 }
+            ",
+        )
+        .unwrap();
+    ast.set_source("test");
+
+    let r = engine.call_fn::<INT>(&mut rhai::Scope::new(), &ast, "foo", (20 as INT,)).unwrap();
+    assert_eq!(r, 41);
+
+    let profile = engine.take_profile().unwrap();
+    let stacks: Vec<_> = profile.iter().map(|(stack, ..)| stack.join(";")).collect();
+    assert_eq!(stacks, ["foo", "foo;bar"]);
+
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let ast = engine.compile("let y = 1; |x| x + y").unwrap();
+        let f = engine.eval_ast::<rhai::FnPtr>(&ast).unwrap();
+        assert_eq!(f.call::<INT>(&engine, &ast, (41 as INT,)).unwrap(), 42);
+
+        let profile = engine.take_profile().unwrap();
+        let stacks: Vec<_> = profile.iter().map(|(stack, ..)| stack.join(";")).collect();
+        assert_eq!(stacks.len(), 2);
+        assert_eq!(stacks[0], "<script>");
+        assert!(stacks[1].starts_with("anon$"));
+    }
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_profiler_modules() {
+    use rhai::module_resolvers::StaticModuleResolver;
+    use rhai::{Module, Scope};
+
+    let mut engine = Engine::new();
+
+    let mut ast = engine.compile("fn double(x) { x * 2 }").unwrap();
+    ast.set_source("math");
+    let math = Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap();
+
+    let mut native = Module::new();
+    native.set_native_fn("triple", |x: INT| Ok(x * 3));
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("math", math);
+    resolver.insert("native", native);
+    engine.set_module_resolver(resolver);
+
+    engine.set_profiling_enabled(true);
+
+    let r = engine
+        .eval::<INT>(
+            r#"
+                import "math" as m;
+                import "native" as n;
+
+                let f = |x| n::triple(m::double(x));
+                call(f, 7)
+            "#,
+        )
+        .unwrap();
+    assert_eq!(r, 42);
+
+    let profile = engine.take_profile().unwrap();
+    let stacks: Vec<_> = profile.iter().map(|(stack, ..)| stack.join(";")).collect();
+
+    assert_eq!(stacks.len(), 4);
+    assert_eq!(stacks[0], "<script>");
+    assert!(stacks[1].starts_with("<script>;anon$"));
+    assert!(stacks[2].ends_with(";math::double"));
+    assert!(stacks[3].ends_with(";native::triple"));
+}
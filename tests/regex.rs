@@ -0,0 +1,67 @@
+#![cfg(feature = "regex")]
+use rhai::{Engine, EvalAltResult, Regex, Scope, INT};
+
+#[test]
+fn test_regex() {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>(r#"matches(regex("^[a-z]+$"), "hello")"#).unwrap());
+    assert!(!engine.eval::<bool>(r#"matches(regex("^[a-z]+$"), "hello!")"#).unwrap());
+    assert_eq!(engine.eval::<String>(r#"type_of(regex("x"))"#).unwrap(), "regex");
+    assert_eq!(engine.eval::<String>(r#"`${regex("[0-9]+")}`"#).unwrap(), "[0-9]+");
+
+    assert_eq!(
+        engine
+            .eval::<String>(r#"let s = "John Smith, Jane Doe"; replace(s, regex("(?<first>\\w+) (?<last>\\w+)"), "${last} $1"); s"#)
+            .unwrap(),
+        "Smith John, Doe Jane"
+    );
+    assert_eq!(engine.eval::<String>(r#"let s = "a.b.c"; replace(s, regex("\\."), "$$"); s"#).unwrap(), "a$b$c");
+    assert_eq!(engine.eval::<String>(r#"let s = "abc"; replace(s, regex("x"), "y"); s"#).unwrap(), "abc");
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert!(engine.eval::<bool>(r#"regex("\\d").matches("a1")"#).unwrap());
+        assert_eq!(engine.eval::<String>(r#"regex("[0-9]+").pattern"#).unwrap(), "[0-9]+");
+        assert_eq!(engine.eval::<String>(r#"let s = "a-b"; s.replace(regex("-"), "+"); s"#).unwrap(), "a+b");
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let m = regex("(?<key>\\w+)=(?<value>\\w+)?").named_captures("x=42"); m.key + m.value"#)
+                .unwrap(),
+            "x42"
+        );
+        assert_eq!(engine.eval::<()>(r#"regex("(?<key>\\w+)=(?<value>\\w+)?").named_captures("x=").value"#).unwrap(), ());
+        assert_eq!(engine.eval::<()>(r#"regex("(?<key>\\w+)=").named_captures("none")"#).unwrap(), ());
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(engine.eval::<String>(r#"let re = regex("(\\d+)-(\\d+)?"); `${captures(re, "call 555-1234")}`"#).unwrap(), r#"["555-1234", "555", "1234"]"#);
+        assert_eq!(engine.eval::<String>(r#"let re = regex("(\\d+)-(\\d+)?"); `${captures(re, "call 555-")}`"#).unwrap(), r#"["555-", "555", ()]"#);
+        assert_eq!(engine.eval::<()>(r#"captures(regex("(\\d+)"), "none")"#).unwrap(), ());
+        assert_eq!(engine.eval::<String>(r#"`${find_all(regex("[0-9]+"), "1, 22 and 333")}`"#).unwrap(), r#"["1", "22", "333"]"#);
+        assert_eq!(engine.eval::<INT>(r#"len(find_all(regex("[0-9]+"), "none"))"#).unwrap(), 0);
+        assert_eq!(engine.eval::<String>(r#"`${split("a, b;c ,d", regex("\\s*[,;]\\s*"))}`"#).unwrap(), r#"["a", "b", "c", "d"]"#);
+    }
+
+    assert!(matches!(*engine.eval::<Regex>(r#"regex("(")"#).unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+
+    let mut scope = Scope::new();
+    scope.push("re", Regex::new("b+").unwrap());
+    assert!(engine.eval_with_scope::<bool>(&mut scope, r#"matches(re, "abbc")"#).unwrap());
+    assert_eq!(engine.eval_with_scope::<Regex>(&mut scope, "re").unwrap().as_str(), "b+");
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_regex_limits() {
+    let mut engine = Engine::new();
+    engine.set_max_string_size(10);
+
+    let mut scope = Scope::new();
+    scope.push("pattern", "[a-z][a-z][a-z]");
+
+    assert!(matches!(*engine.eval_with_scope::<Regex>(&mut scope, "regex(pattern)").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert!(matches!(*engine.run(r#"let s = "aaaaa"; replace(s, regex("a"), "xyz");"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert_eq!(engine.eval::<String>(r#"let s = "aaaaa"; replace(s, regex("a"), "xy"); s"#).unwrap(), "xyxyxyxyxy");
+}
@@ -497,6 +497,74 @@ fn test_serde_de_externally_tagged_enum() {
     assert_eq!(MyEnum::VariantStruct { a: 123 }, from_dynamic(&map_outer.into()).unwrap());
 }
 
+#[test]
+fn test_serde_de_script_enum() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(i32),
+        Rect(i32, i32),
+        Named { w: i32, h: i32 },
+    }
+
+    let engine = Engine::new();
+    let script = "enum Shape { Empty, Circle(r), Rect(w, h), Named(w, h) }";
+
+    let d = engine.eval::<Dynamic>(&format!("{script} Shape::Empty")).unwrap();
+    assert_eq!(Shape::Empty, from_dynamic(&d).unwrap());
+    let d = engine.eval::<Dynamic>(&format!("{script} Shape::Circle(42)")).unwrap();
+    assert_eq!(Shape::Circle(42), from_dynamic(&d).unwrap());
+    let d = engine.eval::<Dynamic>(&format!("{script} Shape::Rect(1, 2)")).unwrap();
+    assert_eq!(Shape::Rect(1, 2), from_dynamic(&d).unwrap());
+    let d = engine.eval::<Dynamic>(&format!("{script} Shape::Named(1, 2)")).unwrap();
+    assert_eq!(Shape::Named { w: 1, h: 2 }, from_dynamic(&d).unwrap());
+
+    let d = engine.eval::<Dynamic>("(1, true)").unwrap();
+    assert_eq!((1, true), from_dynamic::<(i32, bool)>(&d).unwrap());
+}
+
+#[test]
+fn test_serde_ser_script_enum() {
+    let engine = Engine::new();
+    let script = "enum Shape { Empty, Circle(r), Rect(w, h) }";
+
+    let d = engine.eval::<Dynamic>(&format!("{script} Shape::Empty")).unwrap();
+    assert_eq!(serde_json::to_value(&d).unwrap(), json!("Empty"));
+    let d = engine.eval::<Dynamic>(&format!("{script} Shape::Circle(42)")).unwrap();
+    assert_eq!(serde_json::to_value(&d).unwrap(), json!({ "Circle": 42 }));
+    let d = engine.eval::<Dynamic>(&format!("{script} Shape::Rect(1, (2, 3))")).unwrap();
+    assert_eq!(serde_json::to_value(&d).unwrap(), json!({ "Rect": [1, [2, 3]] }));
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_serde_script_struct() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Point {
+        x: i32,
+        y: String,
+    }
+
+    let engine = Engine::new();
+
+    let d = engine.eval::<Dynamic>(r#"struct Point { x, y } Point { x: 42, y: "hello" }"#).unwrap();
+    assert_eq!(serde_json::to_value(&d).unwrap(), json!({ "x": 42, "y": "hello" }));
+    assert_eq!(Point { x: 42, y: "hello".into() }, from_dynamic(&d).unwrap());
+    assert_eq!(engine.eval::<String>(r#"struct Point { x, y } to_json(#{ p: Point { x: 42, y: true } })"#).unwrap(), r#"{"p":{"x":42,"y":true}}"#);
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_function"))]
+#[cfg(feature = "metadata")]
+fn test_serde_script_struct_metadata() {
+    let engine = Engine::new();
+    let ast = engine.compile("/// A point.\nstruct Point { x, y }").unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&engine.gen_fn_metadata_with_ast_to_json(&ast, false).unwrap()).unwrap();
+    assert_eq!(json["customTypes"], json!([{ "typeName": "Point", "displayName": "Point", "fields": ["x", "y"], "docComments": ["/// A point."] }]));
+}
+
 #[test]
 #[cfg(not(feature = "no_object"))]
 fn test_serde_de_internally_tagged_enum() {
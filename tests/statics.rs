@@ -0,0 +1,111 @@
+#![cfg(not(feature = "no_function"))]
+use rhai::{Engine, ParseErrorType, Scope, INT};
+
+#[test]
+fn test_statics() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("fn hit() { static n = 0; n += 1; n } hit(); hit(); hit()").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("fn first(x) { static v = x; v } first(1); first(2)").unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("fn hit() { static n = 0; n += 1; let m = n; m = 42; n } hit(); hit()").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("fn hit() { static n; if n == () { n = 0 } n += 1; n } hit(); hit()").unwrap(), 2);
+    assert_eq!(
+        engine
+            .eval::<INT>("fn tick() { for i in 0..3 { static n = 0; n += 1; } } fn read() { static n = 100; n } tick(); tick(); read()")
+            .unwrap(),
+        100
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>("fn deepest(n) { static max = 0; if n > max { max = n; } if n > 0 { deepest(n - 1); } max } deepest(3); deepest(1)")
+            .unwrap(),
+        3
+    );
+    assert_eq!(engine.eval::<INT>("fn trail(n) { static t = []; t.push(n); if n > 0 { trail(n - 1); } t.len() } trail(3)").unwrap(), 4);
+    assert_eq!(
+        engine
+            .eval::<INT>("fn other() { static n = 100; n += 1; n } fn hit() { static n = 0; n += 1; other(); n } hit(); hit()")
+            .unwrap(),
+        2
+    );
+    assert_eq!(engine.eval::<INT>("let f = || { static n = 0; n += 1; n }; call(f); call(f); call(f)").unwrap(), 3);
+    assert_eq!(
+        engine
+            .eval::<INT>("fn ids() { static next = 0; loop { next += 1; yield next; } } let s = 0; for x in ids() { s += x; if x >= 3 { break; } } s")
+            .unwrap(),
+        6
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "only_i32"))]
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn fib(n) {
+                        static cache = #{};
+                        let key = n.to_string();
+                        if key in cache { return cache[key]; }
+                        let v = if n < 2 { n } else { fib(n - 1) + fib(n - 2) };
+                        cache[key] = v;
+                        v
+                    }
+                    let v = 0;
+                    for n in 0..=60 { v = fib(n); }
+                    v
+                "
+            )
+            .unwrap(),
+        1_548_008_755_920
+    );
+
+    assert!(matches!(engine.compile("static x = 1;").unwrap_err().err_type(), ParseErrorType::WrongStatic));
+    assert!(matches!(
+        engine.compile("fn f() { static x = 1; if true { static x = 2; } x }").unwrap_err().err_type(),
+        ParseErrorType::DuplicatedVariable(v) if v == "x"
+    ));
+}
+
+#[test]
+fn test_statics_ast() {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let script = "
+        fn hit(n) {
+            static total = 0;
+            total += n;
+            total
+        }
+        fn untouched() {
+            static never = 42;
+            never
+        }
+    ";
+
+    let mut ast = engine.compile(script).unwrap();
+
+    assert_eq!(ast.iter_static_variables().count(), 0);
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast, "hit", (1 as INT,)).unwrap(), 1);
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast, "hit", (2 as INT,)).unwrap(), 3);
+
+    let statics: Vec<_> = ast.iter_static_variables().map(|(f, n, v)| (f.to_string(), n.to_string(), v.as_int().unwrap())).collect();
+    assert_eq!(statics, vec![("hit".to_string(), "total".to_string(), 3)]);
+
+    // A clone starts from the current values but has its own storage
+    let ast2 = ast.clone();
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast2, "hit", (3 as INT,)).unwrap(), 6);
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast, "hit", (0 as INT,)).unwrap(), 3);
+
+    // So does a separately-compiled script
+    let ast3 = engine.compile(script).unwrap();
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast3, "hit", (3 as INT,)).unwrap(), 3);
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast, "hit", (0 as INT,)).unwrap(), 3);
+
+    ast.reset_static_variables();
+    assert_eq!(ast.iter_static_variables().count(), 0);
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast, "hit", (5 as INT,)).unwrap(), 5);
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast2, "hit", (1 as INT,)).unwrap(), 7);
+    assert_eq!(engine.call_fn::<INT>(&mut scope, &ast3, "hit", (1 as INT,)).unwrap(), 4);
+}
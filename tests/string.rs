@@ -257,6 +257,53 @@ fn test_string_format() {
     assert_eq!(engine.eval::<String>(r#"let x = new_ts(); x + "foo""#).unwrap(), "TS=42foo");
     #[cfg(not(feature = "no_index"))]
     assert_eq!(engine.eval::<String>(r#"let x = [new_ts()]; "foo" + x"#).unwrap(), "foo[!!!TS=42!!!]");
+    assert_eq!(engine.eval::<String>(r#"format("{:>8}|{:?}", new_ts(), new_ts())"#).unwrap(), "   TS=42|!!!TS=42!!!");
+}
+
+#[test]
+fn test_string_format_fn() {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"format("hello")"#).unwrap(), "hello");
+    assert_eq!(engine.eval::<String>(r#"format("{} + {} = {}", 1, 2, 1 + 2)"#).unwrap(), "1 + 2 = 3");
+    assert_eq!(engine.eval::<String>(r#"format("{1} {0} {} {}", "a", "b")"#).unwrap(), "b a a b");
+    assert_eq!(engine.eval::<String>(r#"format("{{{}}}", 42)"#).unwrap(), "{42}");
+    assert_eq!(engine.eval::<String>(r#"format("[{:5}] [{:<5}] [{:^5}] [{:*>5}]", 42, 42, 42, 42)"#).unwrap(), "[   42] [42   ] [ 42  ] [***42]");
+    assert_eq!(engine.eval::<String>(r#"format("[{:5}] [{:>5}] [{:.2}] [{:-^7.3}]", "ab", "ab", "abcd", "abcd")"#).unwrap(), "[ab   ] [   ab] [ab] [--abc--]");
+    assert_eq!(engine.eval::<String>(r#"format("{:+} {:+} {:05} {:+05}", 42, -42, -42, 42)"#).unwrap(), "+42 -42 -0042 +0042");
+    assert_eq!(engine.eval::<String>(r#"format("{:x} {:X} {:o} {:b}", 255, 255, 8, 5)"#).unwrap(), "ff FF 10 101");
+    assert_eq!(engine.eval::<String>(r#"format("{:#x} {:#06x} {:#b} {:_x}", 255, 255, 5, 0x1234abcd)"#).unwrap(), "0xff 0x00ff 0b101 1234_abcd");
+    assert_eq!(engine.eval::<String>(r#"format("{:,} {:_} {:,}", 1234567, -1234567, 123)"#).unwrap(), "1,234,567 -1_234_567 123");
+    assert_eq!(engine.eval::<String>(r#"format("{:.2} {:>8,.1}", 5, 12345)"#).unwrap(), "5.00 12,345.0");
+    assert_eq!(engine.eval::<String>(r#"format("{:?} {}", "hi", 'x')"#).unwrap(), r#""hi" x"#);
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<String>(r#"format("{name} is {age:>3} {}", "!", #{ name: "Bob", age: 42 })"#).unwrap(), "Bob is  42 !");
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<String>(r#"format("{:>8.2}|{:04x}", 3.14159, 42)"#).unwrap(), "    3.14|002a");
+        assert_eq!(engine.eval::<String>(r#"format("{} {:.0} {:+08.1} {:,.2}", 1.0, 2.5, 3.14159, -1234567.25)"#).unwrap(), "1.0 2 +00003.1 -1,234,567.25");
+        assert_eq!(engine.eval::<String>(r#"format("{:e} {:.2E} {:e}", 1234.5, 1234.5, 1000)"#).unwrap(), "1.2345e3 1.23E3 1e3");
+    }
+
+    #[cfg(feature = "decimal")]
+    #[cfg(feature = "no_float")]
+    assert_eq!(engine.eval::<String>(r#"format("{:.2} {:,.1} {}", 3.14159, -1234.56, 1.5)"#).unwrap(), "3.14 -1,234.6 1.5");
+
+    for script in [r#"format("{}")"#, r#"format("{2}", 1)"#, r#"format("{:z}", 1)"#, r#"format("{:x}", "s")"#, r#"format("{")"#, r#"format("}")"#] {
+        assert!(matches!(*engine.eval::<String>(script).unwrap_err(), EvalAltResult::ErrorRuntime(..)), "{script}");
+    }
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        engine.set_max_string_size(10);
+
+        assert_eq!(engine.eval::<String>(r#"format("{:>10}", 1)"#).unwrap(), "         1");
+        assert!(matches!(*engine.eval::<String>(r#"format("{:>11}", 1)"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+        assert!(matches!(*engine.eval::<String>(r#"format("{:.99}", 1)"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+        assert!(matches!(*engine.eval::<String>(r#"format("{}{}{}", "abcd", "efgh", "ijkl")"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    }
 }
 
 #[test]
@@ -426,6 +473,113 @@ Undeniable logic:
     );
 }
 
+#[test]
+fn test_string_interpolated_format_spec() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("let x = 42; `[${x:>6}] [${x + 1:#x}] [${x:*<4}]`").unwrap(), "[    42] [0x2b] [42**]");
+    assert_eq!(engine.eval::<String>("let x = 42; `[${ let y = x * 2; y : >5}]`").unwrap(), "[   84]");
+    assert_eq!(engine.eval::<String>(r#"let s = "hello"; `${s:.3}|${`${s:^7}`:?}`"#).unwrap(), r#"hel|" hello ""#);
+    assert_eq!(engine.eval::<String>("`${1234567:,}`").unwrap(), "1,234,567");
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(engine.eval::<String>("let x = 3.14159; `pi = ${x:.2}!`").unwrap(), "pi = 3.14!");
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<String>("let m = #{a: 42}; `${ #{b: m.a}.b :03}`").unwrap(), "042");
+
+    assert_eq!(engine.eval_expression::<String>("`a${40 + 2:>5}b`").unwrap(), "a   42b");
+    assert_eq!(engine.compact_script("let x = 42;\n`${ x : >8}`").unwrap(), "let x=42;`${x: >8}`");
+
+    assert!(matches!(engine.compile("let x = 42; `${x:>8`").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+    assert!(matches!(engine.compile("let x = 42; `${let y = x:>8}`").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+    assert!(matches!(*engine.eval::<String>("let x = 42; `${x:z}`").unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+}
+
+#[test]
+fn test_string_heredoc() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<String>(
+                r#"
+                    let s = """
+                        Hello,
+                          "World"!
+
+                        ""Bye""
+                    """;
+                    s
+                "#
+            )
+            .unwrap(),
+        "Hello,\n  \"World\"!\n\n\"\"Bye\"\""
+    );
+
+    assert_eq!(engine.eval::<String>("\"\"\"\n  a\\nb\n    c  \n  \"\"\"").unwrap(), "a\\nb\n  c  ");
+    assert_eq!(engine.eval::<String>("\"\"\"\r\n    a\r\n      b\r\n    \"\"\"").unwrap(), "a\n  b");
+    assert_eq!(engine.eval::<String>("\"\"\"\n    a\n      b\"\"\"").unwrap(), "a\n  b");
+    assert_eq!(engine.eval::<String>("\"\"\"\n    \"\"\"").unwrap(), "");
+    assert_eq!(engine.eval::<String>("\"\"\"\n\"\"\"").unwrap(), "");
+
+    // Only the exact common whitespace prefix is stripped, so tabs and spaces are never mixed up
+    assert_eq!(engine.eval::<String>("\"\"\"\n  a\n\tb\n  \"\"\"").unwrap(), "  a\n\tb");
+    assert_eq!(engine.eval::<String>("\"\"\"\n\t  a\n\t b\n\t\"\"\"").unwrap(), " a\nb");
+    assert_eq!(engine.eval::<String>("\"\"\"\n \ta\n\t b\n\"\"\"").unwrap(), " \ta\n\t b");
+
+    // Not a heredoc without a line break
+    assert_eq!(engine.eval::<String>(r#""""x""#).unwrap(), r#""x"#);
+
+    assert_eq!(
+        engine
+            .eval::<String>(
+                r#"
+                    let x = 42;
+                    let s = """
+                        ${x}
+                          x = ${x + 1}, ${`${x}`} \${x}
+                        ${x}""";
+                    s
+                "#
+            )
+            .unwrap(),
+        "42\n  x = 43, 42 ${x}\n42"
+    );
+
+    assert_eq!(
+        engine
+            .eval::<String>(
+                r#"
+                    let name = "Rhai";
+                    let inner = """
+                            - ${name}
+                        """;
+                    """
+                        Outer:
+                        ${inner} ${"""
+                            !
+                        """}
+                    """
+                "#
+            )
+            .unwrap(),
+        "Outer:\n- Rhai !"
+    );
+
+    // Positions within and after a heredoc are correct
+    let err = engine.compile("let s = \"\"\"\n    a\n    ${ 1 + }\n    \"\"\";").unwrap_err();
+    assert_eq!(err.position(), Position::new(3, 12));
+    let err = engine.compile("let s = \"\"\"\n    a\n    \"\"\";\nlet = 1;").unwrap_err();
+    assert_eq!(err.position(), Position::new(4, 5));
+
+    assert!(matches!(engine.compile("let s = \"\"\"\n    a\n").unwrap_err().err_type(), ParseErrorType::BadInput(LexError::UnterminatedString)));
+
+    let script = "let x = 42; \"\"\"\n    a ${x}\n      b\n    \"\"\"";
+    let compacted = engine.compact_script(script).unwrap();
+    assert_eq!(engine.eval::<String>(&compacted).unwrap(), "a 42\n  b");
+}
+
 #[test]
 fn test_immutable_string() {
     let x: ImmutableString = "hello".into();
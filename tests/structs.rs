@@ -0,0 +1,94 @@
+#![cfg(not(feature = "no_object"))]
+use rhai::{Dynamic, Engine, EvalAltResult, ParseErrorType, StructValue, INT};
+
+#[test]
+fn test_structs() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("struct Point { x, y } let p = Point { x: 1, y: 2 }; p.x + p.y").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("struct Point { x, y } let p = Point { y: 2, x: 1 }; p.x = 40; p.x += p.y; p.x").unwrap(), 42);
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>(r#"struct Point { x, y } let p = Point { x: 1, y: 2 }; p["y"] = 41; p.x + p["y"]"#).unwrap(), 42);
+    assert_eq!(engine.eval::<INT>(r#"struct Point { x, y } let p = Point { x: "ab", y: () }; p.x += "c"; p.x.len()"#).unwrap(), 3);
+    assert_eq!(engine.eval::<String>("struct Point { x, y } type_of(Point { x: 1, y: 2 })").unwrap(), "Point");
+    assert_eq!(engine.eval::<String>("struct Point { x, y } Point { x: 1, y: 2 }.struct_name()").unwrap(), "Point");
+    assert_eq!(engine.eval::<String>(r#"struct Point { x, y } to_string(Point { x: 1, y: "a" })"#).unwrap(), r#"Point { x: 1, y: "a" }"#);
+    assert_eq!(engine.eval::<String>(r#"struct Point { x, y } to_debug(Point { x: 1, y: "a" })"#).unwrap(), r#"Point { x: 1, y: "a" }"#);
+    assert_eq!(engine.eval::<String>("struct Empty {} `${Empty {}}`").unwrap(), "Empty {}");
+    assert_eq!(engine.eval::<INT>("struct Point { x, y } Point { x: 1, y: 2 }.to_map().len()").unwrap(), 2);
+
+    assert!(engine.eval::<bool>("struct P { x, y } P { x: 1, y: 2 } == P { x: 1, y: 2 }").unwrap());
+    assert!(engine.eval::<bool>("struct P { x, y } P { x: 1, y: 2 } != P { x: 1, y: 3 }").unwrap());
+    assert!(engine.eval::<bool>("struct P { x } struct Q { x } P { x: 1 } != Q { x: 1 }").unwrap());
+
+    // Struct values are only constructed through their declarations
+    let value = engine.eval::<StructValue>("struct Point { x, y } let x = 42; Point { x: x, y: true }").unwrap();
+    assert_eq!(value.struct_name(), "Point");
+    assert_eq!(value.iter().map(|(k, ..)| k).collect::<Vec<_>>(), ["x", "y"]);
+    assert_eq!(value.get("x").unwrap().as_int().unwrap(), 42);
+
+    let d = Dynamic::from(StructValue::new("Point", [("x", Dynamic::from(1 as INT))]));
+    assert!(d.is_struct());
+    assert_eq!(d.as_struct_ref().unwrap().len(), 1);
+    assert_eq!(d.to_string(), "Point { x: 1 }");
+
+    assert!(matches!(
+        *engine.run("struct Point { x, y } let p = Point { x: 1, y: 2 }; p.z").unwrap_err(),
+        EvalAltResult::ErrorPropertyNotFound(ref f, ..) if f == "z"
+    ));
+    assert!(matches!(
+        *engine.run("struct Point { x, y } let p = Point { x: 1, y: 2 }; p.z = 42;").unwrap_err(),
+        EvalAltResult::ErrorPropertyNotFound(ref f, ..) if f == "z"
+    ));
+    #[cfg(not(feature = "no_index"))]
+    assert!(matches!(
+        *engine.run(r#"struct Point { x, y } let p = Point { x: 1, y: 2 }; p["z"]"#).unwrap_err(),
+        EvalAltResult::ErrorPropertyNotFound(ref f, ..) if f == "z"
+    ));
+
+    assert_eq!(engine.compile("struct Point { x, y } Point { x: 1, z: 2 }").unwrap_err().err_type(), &ParseErrorType::StructFieldNotFound("Point".into(), "z".into()));
+    assert_eq!(engine.compile("struct Point { x, y } Point { x: 1 }").unwrap_err().err_type(), &ParseErrorType::StructMissingField("Point".into(), "y".into()));
+    assert_eq!(engine.compile("struct Point { x, y } Point { x: 1, x: 2 }").unwrap_err().err_type(), &ParseErrorType::StructDuplicatedField("Point".into(), "x".into()));
+    assert_eq!(engine.compile("struct Point { x, x }").unwrap_err().err_type(), &ParseErrorType::StructDuplicatedField("Point".into(), "x".into()));
+    assert_eq!(engine.compile("struct Point { x } struct Point { y }").unwrap_err().err_type(), &ParseErrorType::StructDuplicatedDefinition("Point".into()));
+    assert_eq!(engine.compile("if true { struct Point { x } }").unwrap_err().err_type(), &ParseErrorType::WrongStructDefinition);
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_structs_methods() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    struct Point { x, y }
+
+                    fn Point.len() { this.x + this.y }
+                    fn Point.shift(d) { this.x += d; }
+                    fn len_of(p) { p.len() }
+
+                    let p = Point { x: 1, y: 2 };
+                    p.shift(39);
+                    len_of(p)
+                "
+            )
+            .unwrap(),
+        42
+    );
+
+    assert_eq!(engine.eval::<String>("struct Point { x, y } fn make(x) { Point { x: x, y: 0 } } type_of(make(1))").unwrap(), "Point");
+
+    assert!(matches!(engine.compile("fn f() { struct Point { x } }").unwrap_err().err_type(), ParseErrorType::WrongStructDefinition));
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_structs_data_size() {
+    let mut engine = Engine::new();
+    engine.set_max_map_size(2);
+
+    assert_eq!(engine.eval::<INT>("struct Point { x, y } let p = Point { x: 1, y: 2 }; p.y").unwrap(), 2);
+    assert!(matches!(*engine.run("struct Point { x, y, z } let a = 1; Point { x: a, y: a, z: a }").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+}
@@ -1,4 +1,5 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+use std::sync::{Arc, RwLock};
 
 #[test]
 fn test_throw() {
@@ -95,3 +96,164 @@ fn test_try_catch() {
     #[cfg(not(feature = "unchecked"))]
     assert!(matches!(*engine.run("try { 42/0; } catch { throw; }").expect_err("expects error"), EvalAltResult::ErrorArithmetic(..)));
 }
+
+#[test]
+fn test_try_catch_clauses() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<String>(r#"let r = ""; try { throw "x"; } catch (e: int) { r = "int"; } catch (e: string) { r = "string:" + e; } r"#)
+            .unwrap(),
+        "string:x"
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>("let r = 0; try { throw 42; } catch (e) if e > 100 { r = 1; } catch (e) if e > 10 { r = e; } catch { r = -1; } r")
+            .unwrap(),
+        42
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>("let r = 0; try { throw 42; } catch (e: int) if e < 10 { r = 1; } catch (e: int) { r = 2; } r")
+            .unwrap(),
+        2
+    );
+
+    // Not caught by any clause
+    assert!(matches!(
+        *engine.run(r#"try { throw 42; } catch (e: string) { }"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(v, ..) if v.as_int().unwrap() == 42
+    ));
+
+    // The error variable of a clause is not visible in the next
+    assert!(matches!(*engine.run("try { throw 1; } catch (e) if false { } catch { throw e; }").expect_err("should error"), EvalAltResult::ErrorVariableNotFound(..)));
+
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "unchecked"))]
+    assert_eq!(
+        engine
+            .eval::<INT>(r#"let r = 0; try { r = 1 / 0; } catch (e: map) if e.error == "ErrorArithmetic" { r = -1; } r"#)
+            .unwrap(),
+        -1
+    );
+
+    assert_eq!(*engine.compile("try { } catch (e: ) { }").expect_err("should error").err_type(), ParseErrorType::MissingSymbol("Expecting a type name for the catch variable".into()));
+    assert!(matches!(*engine.run("try { throw 1; } catch (e) if e { }").expect_err("should error"), EvalAltResult::ErrorMismatchDataType(..)));
+}
+
+#[test]
+fn test_try_finally() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"let s = ""; try { s += "a"; } finally { s += "b"; } s"#).unwrap(), "ab");
+    assert_eq!(engine.eval::<String>(r#"let s = ""; try { s += "a"; throw 1; } catch { s += "c"; } finally { s += "b"; } s"#).unwrap(), "acb");
+    assert_eq!(engine.eval::<INT>("try { 42 } finally { 0 }").unwrap(), 42);
+
+    // Runs on break and continue
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let n = 0;
+                    for i in 0..10 {
+                        try {
+                            if i % 2 == 0 { continue; }
+                            if i > 6 { break; }
+                        } finally {
+                            n += 1;
+                        }
+                    }
+                    n
+                "
+            )
+            .unwrap(),
+        8
+    );
+
+    // Runs when an error passes through, which still propagates
+    assert!(matches!(
+        *engine.run(r#"let s = ""; try { try { throw 42; } finally { s = "done"; } } catch (e) { if s != "done" { throw "finally not run"; } throw e; }"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(v, ..) if v.as_int().unwrap() == 42
+    ));
+
+    // An error out of the finally block replaces the original
+    assert!(matches!(
+        *engine.run("try { throw 1; } finally { throw 2; }").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(v, ..) if v.as_int().unwrap() == 2
+    ));
+
+    assert!(engine.compile("try { }").is_err());
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_try_finally_return() {
+    let logbook = Arc::new(RwLock::new(Vec::<String>::new()));
+    let log = logbook.clone();
+
+    let mut engine = Engine::new();
+    engine.on_print(move |s| log.write().unwrap().push(s.to_string()));
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                r#"
+                    fn foo(x) {
+                        try {
+                            if x > 0 { return x; }
+                            throw x;
+                        } catch (e) {
+                            return -1;
+                        } finally {
+                            print("finally " + x);
+                        }
+                    }
+                    foo(42) + foo(0)
+                "#
+            )
+            .unwrap(),
+        41
+    );
+    assert_eq!(*logbook.read().unwrap(), ["finally 42", "finally 0"]);
+    logbook.write().unwrap().clear();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                r#"
+                    fn foo(x) {
+                        let y = x * 2;
+                        defer { print("first " + y); }
+                        let z = y + 1;
+                        defer { print("second " + x); }
+                        if x > 0 { return z; }
+                        throw x;
+                    }
+                    let r = foo(1);
+                    try { foo(0); } catch { r += 100; }
+                    r
+                "#
+            )
+            .unwrap(),
+        103
+    );
+    assert_eq!(*logbook.read().unwrap(), ["second 1", "first 2", "second 0", "first 0"]);
+    logbook.write().unwrap().clear();
+
+    assert_eq!(engine.eval::<INT>("fn foo() { defer { print(\"bye\"); } 42 } foo()").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("let f = |x| { defer { print(\"bye\"); } x + 1 }; call(f, 41)").unwrap(), 42);
+    assert_eq!(*logbook.read().unwrap(), ["bye", "bye"]);
+
+    assert_eq!(*engine.compile("defer { }").expect_err("should error").err_type(), ParseErrorType::WrongDefer);
+    // `defer` must be at the top level of a function body, not within a nested block
+    assert_eq!(*engine.compile("fn foo() { if true { defer { } } }").expect_err("should error").err_type(), ParseErrorType::WrongDefer);
+    assert_eq!(*engine.compile("fn foo(c) { if c { defer { } } 1 }").expect_err("should error").err_type(), ParseErrorType::WrongDefer);
+    assert_eq!(*engine.compile("fn foo() { for x in 0..3 { defer { } } }").expect_err("should error").err_type(), ParseErrorType::WrongDefer);
+    assert_eq!(*engine.compile("fn foo() { { defer { } } }").expect_err("should error").err_type(), ParseErrorType::WrongDefer);
+    assert_eq!(*engine.compile("fn foo() { try { defer { } } catch { } }").expect_err("should error").err_type(), ParseErrorType::WrongDefer);
+
+    // ... but a closure within a function body has its own top level
+    assert_eq!(engine.eval::<INT>("fn foo(x) { let f = |y| { defer { print(\"inner\"); } y + 1 }; call(f, x) } foo(41)").unwrap(), 42);
+    assert_eq!(*logbook.read().unwrap(), ["bye", "bye", "inner"]);
+}
@@ -125,3 +125,12 @@ fn test_compact_script_operator_adjacency() {
         engine.compile(&compacted).unwrap_or_else(|e| panic!("compacted {:?} failed to recompile: {}", compacted, e));
     }
 }
+
+#[test]
+fn test_compact_script_loop_labels() {
+    let engine = Engine::new();
+
+    let compacted = engine.compact_script("'outer: loop { let c = 'x'; loop { break 'outer c; } }").unwrap();
+    assert_eq!(compacted, "'outer:loop{let c='x';loop{break'outer c;}}");
+    engine.compile(&compacted).unwrap();
+}
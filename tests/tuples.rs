@@ -0,0 +1,144 @@
+use rhai::{Dynamic, Engine, EnumValue, ParseErrorType, Tuple, INT};
+
+#[test]
+fn test_tuples() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let t = (1, 2, 3); len(t)").unwrap(), 3);
+    assert_eq!(engine.eval::<String>("type_of((1, 'x'))").unwrap(), "tuple");
+    assert_eq!(engine.eval::<String>("to_string((1, \"x\", true))").unwrap(), "(1, \"x\", true)");
+    assert_eq!(engine.eval::<String>("to_string((42,))").unwrap(), "(42,)");
+    assert_eq!(engine.eval::<INT>("(42)").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("let x = 40; let t = (x, x + 2,); len(t)").unwrap(), 2);
+
+    assert!(engine.eval::<bool>("(1, 'x') == (1, 'x')").unwrap());
+    assert!(engine.eval::<bool>("(1, 'x') != (1, 'y')").unwrap());
+    assert!(!engine.eval::<bool>("(1, 2) == (1, 2, 3)").unwrap());
+    assert!(engine.eval::<bool>("(1, \"b\") < (2, \"a\")").unwrap());
+    assert!(!engine.eval::<bool>("(1, \"b\") < (1, \"a\")").unwrap());
+    assert!(engine.eval::<bool>("(1, \"b\") > (1, \"a\")").unwrap());
+    assert!(engine.eval::<bool>("(1, 2) < (1, 2, 3)").unwrap());
+    assert!(engine.eval::<bool>("(1, 2, 3) >= (1, 2)").unwrap());
+    assert!(engine.eval::<bool>("(1, 2) <= (1, 2)").unwrap());
+    assert!(!engine.eval::<bool>("(1, 2) > (1, 2)").unwrap());
+    assert!(engine.eval::<bool>("((1, 2), 'x') < ((1, 3), 'a')").unwrap());
+    assert!(engine.eval::<bool>("let t = (1, 2); t >= (0, 9)").unwrap());
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("let t = (1, 2, 3); t[1] = 42; t[1] + t[-1]").unwrap(), 45);
+    assert_eq!(engine.eval::<INT>("let s = 0; for x in (1, 2, 3) { s += x } s").unwrap(), 6);
+
+    let t = engine.eval::<Tuple>("(1, \"hello\")").unwrap();
+    assert_eq!(t.len(), 2);
+    assert_eq!(t[0].as_int().unwrap(), 1);
+    assert_eq!(t[1].clone().into_string().unwrap(), "hello");
+
+    let d = engine.eval::<Dynamic>("(1, 2)").unwrap();
+    assert!(d.is_tuple());
+    assert_eq!(d.as_tuple_ref().unwrap().len(), 2);
+    assert_eq!(d.into_tuple().unwrap().into_vec().len(), 2);
+
+    assert!(matches!(engine.compile("let t = (1, 2").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+}
+
+#[test]
+fn test_enums() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<String>(
+                "
+                    enum Shape { Circle(r), Rect(w, h), Empty }
+
+                    let s = Shape::Rect(2, 3);
+                    `${type_of(s)} ${enum_name(s)}::${variant(s)} ${fields(s)} ${Shape::Empty}`
+                "
+            )
+            .unwrap(),
+        "Shape Shape::Rect (2, 3) Shape::Empty"
+    );
+    assert_eq!(engine.eval::<String>("enum Shape { Circle(r), Empty, } to_string(Shape::Circle(to_string(1)))").unwrap(), "Shape::Circle(\"1\")");
+
+    assert!(engine.eval::<bool>("enum E { A(x), B } E::A(1) == E::A(1)").unwrap());
+    assert!(engine.eval::<bool>("enum E { A(x), B } E::A(1) != E::A(2)").unwrap());
+    assert!(engine.eval::<bool>("enum E { A(x), B } E::B != E::A(2)").unwrap());
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    enum Op { Add(x, y), Neg(x) }
+
+                    let total = 0;
+
+                    for op in (Op::Add(40, 5), Op::Neg(3)) {
+                        total += switch op {
+                            Op::Add(x, y) => x + y,
+                            Op::Neg(x) => -x,
+                        };
+                    }
+
+                    total
+                "
+            )
+            .unwrap(),
+        42
+    );
+
+    let value = engine.eval::<EnumValue>("enum E { A(x, y) } E::A(1, true)").unwrap();
+    assert!(value.is("E", "A"));
+    assert_eq!(value.fields().len(), 2);
+
+    let d = Dynamic::from_enum(EnumValue::new("E", "B", vec![Dynamic::from(42 as INT)]));
+    assert!(d.is_enum());
+    assert_eq!(d.to_string(), "E::B(42)");
+    assert_eq!(d.as_enum_ref().unwrap().variant(), "B");
+
+    assert!(matches!(
+        engine.compile("enum E { A, B } E::C").unwrap_err().err_type(),
+        ParseErrorType::EnumVariantNotFound(e, v) if e == "E" && v == "C"
+    ));
+    assert!(matches!(
+        engine.compile("enum E { A(x), B } E::A(1, 2)").unwrap_err().err_type(),
+        ParseErrorType::EnumVariantFields(v, 1) if v == "E::A"
+    ));
+    assert!(matches!(
+        engine.compile("enum E { A, A }").unwrap_err().err_type(),
+        ParseErrorType::EnumDuplicatedVariant(e, v) if e == "E" && v == "A"
+    ));
+    assert!(matches!(
+        engine.compile("enum E { A } enum E { B }").unwrap_err().err_type(),
+        ParseErrorType::EnumDuplicatedDefinition(e) if e == "E"
+    ));
+    assert!(matches!(engine.compile("if true { enum E { A } }").unwrap_err().err_type(), ParseErrorType::WrongEnumDefinition));
+}
+
+#[test]
+fn test_switch_patterns() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("switch (1, 2) { (1, 3) => 1, (1, 2) => 2, _ => 3 }").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("enum E { A(x), B } switch E::A(1) { E::B => 1, E::A(1) | E::A(2) => 2, _ => 3 }").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("switch (1, 2) { (a, 2) => a * 10, _ => 0 }").unwrap(), 10);
+    assert_eq!(engine.eval::<INT>("switch (1, (2, 3)) { (_, (b, c)) => b + c }").unwrap(), 5);
+    assert_eq!(engine.eval::<INT>("switch (5, 1) { (0..3, _) => 1, (3..10, x) => x + 1, _ => 3 }").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("switch (5, 1) { (a, b) if a < b => 1, (a, b) if a > b => 2, _ => 3 }").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("const a = 42; switch (1, 2) { (a, _) => a }").unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("let a = 42; switch (1, 2) { (a, _) => a } a").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("switch (1, 2, 3) { (a, b) => 1, _ => 2 }").unwrap(), 2);
+
+    assert!(matches!(
+        engine.compile("switch x { (a, a) => 1 }").unwrap_err().err_type(),
+        ParseErrorType::DuplicatedVariable(v) if v == "a"
+    ));
+    assert!(matches!(engine.compile("switch x { (a, 1) | (1, a) => 1 }").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let mut engine = Engine::new();
+        engine.set_max_variables(2);
+
+        assert!(matches!(*engine.run("let x = 1; switch (1, 2, 3) { (a, b, c) => () }").unwrap_err(), rhai::EvalAltResult::ErrorTooManyVariables(..)));
+    }
+}